@@ -0,0 +1,149 @@
+use std::{hint::black_box, sync::Arc};
+
+use chrono::{Duration, Utc};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mongodb::{bson::oid::ObjectId, options::ClientOptions, Client};
+use tokio::{runtime::Runtime, sync::mpsc};
+use tv_trading_bot::{
+    api::{calc_final_funding_fees, calc_liquidation_price, calc_order_quantity, calc_percentage_exits, calc_pnl, calc_roe, calc_trailing_stop, is_exit_trigger_hit, is_liquidation_hit, to_coinbase_product_id, websocket::process_price_tick},
+    models::{ActiveTrade, AppState, ContractType, FundingSchedule, MarginMode, MarketHours, MongoDBState, PriceFeedTick, RoundingPolicy, TradeDirection, TradeKind, TradeLeverage}
+};
+
+/// The pairs that the benchmarked trades are spread across, with a base price.
+const PAIRS: &[(&str, f64)] = &[("BTCUSDT", 60_000.0), ("ETHUSDT", 3_000.0), ("SOLUSDT", 150.0), ("BNBUSDT", 600.0)];
+
+/// The numbers of open trades that the trigger evaluation and the tick routing are measured over.
+const OPEN_TRADES: &[usize] = &[10, 100, 1_000];
+
+/// Builds `count` open trades spread across `PAIRS`, alternating directions, whose exits aren't hit around the base prices.
+fn open_trades(count: usize) -> Vec<ActiveTrade> {
+    (0..count)
+        .map(|i| {
+            let (pair, price) = PAIRS[i % PAIRS.len()];
+            let direction = if i % 2 == 0 { TradeDirection::Long } else { TradeDirection::Short };
+            let (take_profit, stop_loss) = calc_percentage_exits(price, &direction, Some(5.0), Some(5.0));
+
+            ActiveTrade {
+                id: ObjectId::new(),
+                alert_name: format!("bench-{}", i),
+                pair: pair.to_string(),
+                kind: TradeKind::Paper,
+                open_timestamp: Utc::now(),
+                quantity: 1_000.0 / price,
+                entry_price: price,
+                leverage: TradeLeverage::Ten,
+                contract_type: ContractType::Linear,
+                liquidation_price: calc_liquidation_price(price, 10.0, &direction, &ContractType::Linear, 0.4),
+                direction,
+                take_profit,
+                stop_loss,
+                near_maintenance: false,
+                experiment: None,
+                originating_request_id: None,
+                trailing_stop_percentage: None,
+                exchange: None,
+                margin_mode: MarginMode::Isolated,
+                partial_liquidations: Vec::new(),
+                regime: None,
+                entry_fees: None,
+                entry_order_id: None,
+            }
+        })
+        .collect()
+}
+
+fn trade_math(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trade_math");
+    let rounding = RoundingPolicy::default();
+    let schedule = FundingSchedule { interval_hours: 8 };
+    let market_hours = MarketHours::default();
+    let opened = Utc::now() - Duration::days(3);
+    let closed = Utc::now();
+    let trailing = ActiveTrade { trailing_stop_percentage: Some(1.0), ..open_trades(1).remove(0) };
+
+    group.bench_function("calc_pnl", |b| b.iter(|| {
+        calc_pnl(black_box(60_000.0), black_box(61_250.0), 0.5, 12.0, 3.0, &TradeDirection::Long, &ContractType::Linear)
+    }));
+    group.bench_function("calc_roe", |b| b.iter(|| {
+        calc_roe(black_box(625.0), black_box(60_000.0), 0.5, 10.0, &ContractType::Linear)
+    }));
+    group.bench_function("calc_liquidation_price", |b| b.iter(|| {
+        calc_liquidation_price(black_box(60_000.0), black_box(10.0), &TradeDirection::Short, &ContractType::Inverse, 0.4)
+    }));
+    group.bench_function("calc_order_quantity", |b| b.iter(|| {
+        calc_order_quantity(black_box(1_000.0), black_box(60_000.0), 1.0, &ContractType::Linear, &rounding)
+    }));
+    group.bench_function("calc_percentage_exits", |b| b.iter(|| {
+        calc_percentage_exits(black_box(60_000.0), &TradeDirection::Long, Some(2.0), Some(1.0))
+    }));
+    group.bench_function("calc_trailing_stop", |b| b.iter(|| {
+        calc_trailing_stop(black_box(&trailing), black_box(61_000.0))
+    }));
+    group.bench_function("calc_final_funding_fees", |b| b.iter(|| {
+        calc_final_funding_fees(black_box(opened), black_box(closed), 1_000.0, &schedule, &market_hours)
+    }));
+
+    group.finish();
+}
+
+/// Evaluates the exit and liquidation triggers of every open trade against a price, like each tick does.
+fn trigger_evaluation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trigger_evaluation");
+
+    for &count in OPEN_TRADES {
+        let trades = open_trades(count);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &trades, |b, trades| b.iter(|| {
+            trades
+                .iter()
+                .filter(|trade| is_liquidation_hit(trade, black_box(trade.entry_price)) || is_exit_trigger_hit(trade, black_box(trade.entry_price)))
+                .count()
+        }));
+    }
+
+    group.finish();
+}
+
+/// Routes a price tick through `process_price_tick` (feed quality, consensus, price alerts and the triggers of the trades on its
+/// product) with `count` open trades, none of which are exited, so that the database is never reached.
+fn tick_routing(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let backfill_client = reqwest::Client::new();
+    let mut group = c.benchmark_group("tick_routing");
+
+    for &count in OPEN_TRADES {
+        let app_state = runtime.block_on(async {
+            // the client connects lazily, so no database is required to build the state
+            let client = Client::with_options(ClientOptions::parse("mongodb://localhost:27017").await.unwrap()).unwrap();
+            let (ws_commands, _) = mpsc::unbounded_channel();
+
+            Arc::new(AppState::new(Arc::new(MongoDBState::new(Arc::new(client))), ws_commands))
+        });
+
+        app_state.active_trades.lock().unwrap().extend(open_trades(count).into_iter().map(|trade| (trade.id, trade)));
+
+        let (pair, price) = PAIRS[0];
+        let product_id = to_coinbase_product_id(pair).unwrap();
+        let mut i = 0u64;
+
+        group.bench_function(BenchmarkId::from_parameter(count), |b| b.iter(|| {
+            // the price wanders slightly, so that the ticks are neither duplicates nor spikes
+            i += 1;
+
+            let tick = PriceFeedTick {
+                product_id: product_id.clone(),
+                price: price * (1.0 + (i % 20) as f64 / 10_000.0),
+                sequence: None,
+                trade_id: None,
+            };
+
+            runtime.block_on(process_price_tick(&app_state, tick, &backfill_client));
+        }));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, trade_math, trigger_evaluation, tick_routing);
+criterion_main!(benches);
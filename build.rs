@@ -0,0 +1,34 @@
+use std::{process::Command, time::{SystemTime, UNIX_EPOCH}};
+
+/// Embeds the git commit and the build time into the binary, reported by `GET /version`,
+/// and generates the gRPC control-plane service from `proto/control.proto`.
+fn main() {
+    // the proto is parsed with protox, so that building doesn't require a protoc installation
+    let file_descriptors = protox::compile(["proto/control.proto"], ["proto"]).expect("Failed to parse proto/control.proto");
+
+    tonic_build::configure()
+        .build_client(false)
+        .compile_fds(file_descriptors)
+        .expect("Failed to generate the gRPC service");
+
+    let git_commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+
+    // builds without a git checkout (e.g. from a source archive) report no commit
+    if let Some(git_commit) = git_commit {
+        println!("cargo:rustc-env=GIT_COMMIT={}", git_commit.trim());
+    }
+
+    if let Ok(build_time) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_time.as_secs());
+    }
+
+    // only rebuild the info when the checked out commit changes
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=proto/control.proto");
+}
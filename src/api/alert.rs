@@ -0,0 +1,330 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{self, doc, oid::ObjectId, to_bson, Document}, results::{DeleteResult, InsertOneResult, UpdateResult}, Cursor};
+use ring::digest::{digest, SHA256};
+use serde_json::Value;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+use crate::{api::trade::{calc_page_skip, clamp_per_page}, configs::is_duplicate_key_error, constants::{ALERT_CLAIM_STALE_SECS, DEFAULT_ALERT_MAX_AGE_SECS, MAX_ALERT_CLOCK_SKEW_SECS, MAX_PER_PAGE}, models::{tradingview::TradingViewAlert, AlertClaim, AlertOutcome, ApiResponse, AppState, MongoDBState, RejectedAlert, RejectedAlertQuery, RejectionReason, ResponseCode}};
+
+/// A thread-safe map of the locks serializing the execution of alerts with the same alert name and pair.
+pub type AlertLocksMap = Arc<Mutex<HashMap<(String, String), Arc<AsyncMutex<()>>>>>;
+
+impl AppState {
+    /// Waits for the lock of alerts named `alert_name` on `pair`, so that concurrent alerts of a strategy on the same pair
+    /// are executed one after another (rather than both seeing no active trade and opening one each).
+    /// 
+    /// The lock is held until the returned guard is dropped.
+    pub async fn lock_alert(&self, alert_name: &str, pair: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.alert_locks.lock().unwrap();
+
+            // drop the locks that are neither held nor waited for anymore
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+
+            locks.entry((alert_name.to_string(), pair.to_string())).or_default().clone()
+        };
+
+        lock.lock_owned().await
+    }
+}
+
+/// CRUD operations for rejected alerts in the database.
+impl MongoDBState {
+    /// Adds a rejected alert into the database.
+    pub async fn add_rejected_alert(&self, alert: RejectedAlert) -> Result<InsertOneResult, mongodb::error::Error> {
+        self.rejected_alert_collection.insert_one(alert).await
+    }
+
+    /// Fetches rejected alerts with pagination and optional filtering, newest first.
+    pub async fn fetch_rejected_alerts(&self, filter: Document, page: u32, per_page: u32) -> Result<Vec<RejectedAlert>, mongodb::error::Error> {
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) is empty
+        let Some(skip) = calc_page_skip(page.max(1), per_page) else {
+            return Ok(Vec::new())
+        };
+
+        let mut cursor: Cursor<RejectedAlert> = self
+            .rejected_alert_collection
+            .find(filter)
+            .sort(doc! { "timestamp": -1 })
+            .skip(skip)
+            .limit(per_page as i64)
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Claims the alert with the idempotency key `key` for `instance_id` at `now`.
+    ///
+    /// Returns the existing claim if the alert was already claimed (i.e. it's a duplicate delivery), or `None` if the claim succeeded.
+    /// A stale claim (see `is_stale_alert_claim`) is taken over instead.
+    pub async fn try_claim_alert(&self, key: &str, instance_id: &str, now: DateTime<Utc>) -> Result<Option<AlertClaim>, mongodb::error::Error> {
+
+        let claim = AlertClaim {
+            id: key.to_string(),
+            holder: instance_id.to_string(),
+            claimed_timestamp: now,
+            claimed_at: Some(bson::DateTime::from_millis(now.timestamp_millis())),
+            outcome: None,
+        };
+
+        // the unique `_id` index guarantees that only one replica can insert the claim
+        match self.alert_claim_collection.insert_one(claim).await {
+            Ok(_) => Ok(None),
+            Err(err) if is_duplicate_key_error(&err) => {
+                match self.alert_claim_collection.find_one(doc! { "_id": key }).await? {
+                    Some(existing) if is_stale_alert_claim(&existing, now) => {
+                        // only take the claim over if it wasn't completed or taken over by another replica in the meantime
+                        let taken_over = self.alert_claim_collection
+                            .update_one(
+                                doc! {
+                                    "_id": key,
+                                    "holder": &existing.holder,
+                                    "claimedTimestamp": existing.claimed_timestamp.timestamp(),
+                                    "outcome": null
+                                },
+                                doc! { "$set": { "holder": instance_id, "claimedTimestamp": now.timestamp(), "claimedAt": bson::DateTime::from_millis(now.timestamp_millis()) } }
+                            )
+                            .await?;
+
+                        if taken_over.modified_count == 1 {
+                            println!("(try_claim_alert) Took over stale claim of alert {} from {}.", key, existing.holder);
+                            Ok(None)
+                        } else {
+                            Box::pin(self.try_claim_alert(key, instance_id, now)).await
+                        }
+                    }
+                    Some(existing) => Ok(Some(existing)),
+                    // the claim was released in the meantime, so try again
+                    None => Box::pin(self.try_claim_alert(key, instance_id, now)).await,
+                }
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Records the outcome of the alert with the idempotency key `key`.
+    pub async fn set_alert_outcome(&self, key: &str, outcome: &AlertOutcome) -> Result<UpdateResult, mongodb::error::Error> {
+        self.alert_claim_collection
+            .update_one(
+                doc! { "_id": key },
+                doc! { "$set": { "outcome": { "statusCode": outcome.status_code as i32, "message": &outcome.message } } }
+            )
+            .await
+    }
+
+    /// Releases the claim on the alert with the idempotency key `key`, allowing it to be executed again.
+    pub async fn release_alert_claim(&self, key: &str) -> Result<DeleteResult, mongodb::error::Error> {
+        self.alert_claim_collection.delete_one(doc! { "_id": key }).await
+    }
+}
+
+/// Reads the maximum age (in seconds) of an alert's timestamp from the `ALERT_MAX_AGE_SECS` env variable.
+///
+/// Falls back to `DEFAULT_ALERT_MAX_AGE_SECS` if the variable is unset or invalid.
+pub fn alert_max_age_secs() -> i64 {
+    std::env::var("ALERT_MAX_AGE_SECS")
+        .ok()
+        .and_then(|max_age| max_age.parse().ok())
+        .unwrap_or(DEFAULT_ALERT_MAX_AGE_SECS)
+}
+
+/// Checks that an alert sent at `timestamp` and received at `now` is neither older than `max_age_secs` (e.g. a replayed capture
+/// of a valid webhook) nor further in the future than `MAX_ALERT_CLOCK_SKEW_SECS`.
+///
+/// Returns the reason if the timestamp is outside of the window.
+pub fn check_alert_timestamp(timestamp: DateTime<Utc>, now: DateTime<Utc>, max_age_secs: i64) -> Result<(), String> {
+    let age_secs = (now - timestamp).num_seconds();
+
+    if age_secs > max_age_secs {
+        Err(format!("Alert is {}s old (max {}s).", age_secs, max_age_secs))
+    } else if -age_secs > MAX_ALERT_CLOCK_SKEW_SECS {
+        Err(format!("Alert is {}s in the future.", -age_secs))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the idempotency key of an alert.
+///
+/// The alert's `idempotency_key` is used if set. Otherwise, the key is derived from the alert name and a SHA-256 hash of the payload
+/// (without the secret). The payload includes the alert's `timestamp`, so redeliveries of an alert share its key no matter when they're
+/// received, while later alerts with the same contents don't.
+pub fn alert_idempotency_key(alert: &TradingViewAlert, payload: &Value) -> String {
+    if let Some(key) = &alert.idempotency_key {
+        return format!("{}:{}", alert.name, key);
+    }
+
+    let mut payload = payload.clone();
+
+    if let Some(fields) = payload.as_object_mut() {
+        fields.remove("secret");
+    }
+
+    // the fields of a JSON object are always serialized in the same (sorted) order
+    let hash = digest(&SHA256, payload.to_string().as_bytes());
+
+    format!("{}:{}", alert.name, hash.as_ref().iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+}
+
+/// Returns the static status text of the status codes an alert can be answered with.
+fn to_status_text(status_code: StatusCode) -> &'static str {
+    match status_code {
+        StatusCode::OK => "200 OK",
+        StatusCode::BAD_REQUEST => "400 Bad Request",
+        StatusCode::FORBIDDEN => "403 Forbidden",
+        StatusCode::CONFLICT => "409 Conflict",
+        StatusCode::UNPROCESSABLE_ENTITY => "422 Unprocessable Entity",
+        StatusCode::SERVICE_UNAVAILABLE => "503 Service Unavailable",
+        _ => "500 Internal Server Error",
+    }
+}
+
+/// Checks whether `claim` is still without an outcome `ALERT_CLAIM_STALE_SECS` after it was claimed at `now`,
+/// meaning that its holder most likely crashed while executing the alert, and another replica may take it over.
+pub fn is_stale_alert_claim(claim: &AlertClaim, now: DateTime<Utc>) -> bool {
+    claim.outcome.is_none() && now - claim.claimed_timestamp >= Duration::seconds(ALERT_CLAIM_STALE_SECS)
+}
+
+/// Builds the response to a duplicate delivery of an alert, returning the outcome of the replica that executed it.
+pub fn replay_alert_claim(claim: AlertClaim) -> (StatusCode, Json<ApiResponse<()>>) {
+    match claim.outcome {
+        Some(outcome) => {
+            let status_code = StatusCode::from_u16(outcome.status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            (
+                status_code,
+                Json(ApiResponse {
+                    status: to_status_text(status_code),
+                    code: outcome.code,
+                    message: outcome.message,
+                    data: None
+                })
+            )
+        }
+        None => (
+            StatusCode::CONFLICT,
+            Json(ApiResponse {
+                status: "409 Conflict",
+                code: Some(ResponseCode::AlertInProgress),
+                message: format!("(replay_alert_claim) Alert is already being executed by {}.", claim.holder),
+                data: None
+            })
+        ),
+    }
+}
+
+/// Records the outcome of an executed alert, so that duplicate deliveries of the alert return it.
+///
+/// Server errors release the claim instead, so that a retry of the alert can execute it again. Failing to record the outcome
+/// doesn't change the response, so errors are only logged.
+pub async fn complete_alert_claim(mongo_state: &MongoDBState, key: &str, (status_code, response): &(StatusCode, Json<ApiResponse<()>>)) {
+    let result = if status_code.is_server_error() {
+        mongo_state.release_alert_claim(key).await.map(|_| ())
+    } else {
+        mongo_state.set_alert_outcome(key, &AlertOutcome { status_code: status_code.as_u16(), code: response.code, message: response.message.clone() }).await.map(|_| ())
+    };
+
+    if let Err(err) = result {
+        eprintln!("(complete_alert_claim) Failed to complete claim of alert {}: {}", key, err);
+    }
+}
+
+/// Records a rejected alert and builds the response returned to the sender.
+///
+/// Failing to record the rejection doesn't change the response, so errors are only logged.
+pub async fn reject_alert(
+    app_state: &AppState,
+    payload: &Value,
+    request_id: &str,
+    reason: RejectionReason,
+    (status_code, status): (StatusCode, &'static str),
+    message: String,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    eprintln!("[{}] {}", request_id, message);
+
+    let mut payload = payload.clone();
+
+    // never store the secret, even if it's wrong
+    if let Some(fields) = payload.as_object_mut() {
+        fields.remove("secret");
+    }
+
+    let rejected_alert = RejectedAlert {
+        id: ObjectId::new(),
+        timestamp: app_state.clock.now(),
+        reason,
+        message: message.clone(),
+        alert_name: payload.get("name").and_then(Value::as_str).map(str::to_string),
+        pair: payload.get("pair").and_then(Value::as_str).map(str::to_string),
+        payload,
+        request_id: Some(request_id.to_string()),
+    };
+
+    if let Err(err) = app_state.mongo_state.add_rejected_alert(rejected_alert).await {
+        eprintln!("(reject_alert) Failed to record rejected alert: {}", err);
+    }
+
+    (
+        status_code,
+        Json(ApiResponse {
+            status,
+            code: Some(reason.into()),
+            message,
+            data: None
+        })
+    )
+}
+
+/// Returns rejected alerts, newest first. Optionally filtered by `reason` and `alert_name` query parameters.
+pub async fn get_rejected_alerts(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<RejectedAlertQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<RejectedAlert>>>) {
+    let mut filter = Document::new();
+
+    if let Some(reason) = query.reason {
+        if let Ok(reason) = to_bson(&reason) {
+            filter.insert("reason", reason);
+        }
+    }
+
+    if let Some(alert_name) = &query.alert_name {
+        filter.insert("alertName", alert_name);
+    }
+
+    match mongo_state.fetch_rejected_alerts(filter, query.page.unwrap_or(1), query.per_page.unwrap_or(MAX_PER_PAGE as u32)).await {
+        Ok(alerts) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_rejected_alerts) Fetched rejected alerts successfully.".to_string(),
+                data: Some(alerts)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_rejected_alerts) Failed to fetch rejected alerts: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_rejected_alerts) Failed to fetch rejected alerts: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
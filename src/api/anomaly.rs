@@ -0,0 +1,93 @@
+use std::{collections::VecDeque, sync::{Arc, Mutex}};
+
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::doc;
+
+use crate::{constants::{ALERT_FREQUENCY_WINDOW_SECS, MAX_ALERTS_PER_WINDOW, MAX_ALERT_PRICE_DEVIATION_PERCENTAGE, STRATEGY_SILENCE_DAYS}, models::{tradingview::TradingViewAlert, AlertAnomaly, AppState, LruCache, MongoDBState}};
+
+/// A thread-safe map of the recent alert timestamps of each strategy (alert name), used to detect frequency spikes.
+pub type AlertHistoryMap = Arc<Mutex<LruCache<String, VecDeque<DateTime<Utc>>>>>;
+
+impl AlertAnomaly {
+    /// Whether the anomaly blocks the alert from being executed, rather than only flagging it.
+    pub fn is_blocking(&self) -> bool {
+        !matches!(self, AlertAnomaly::SilentStrategy { .. })
+    }
+}
+
+/// Queries used to detect anomalies on incoming alerts.
+impl MongoDBState {
+    /// Fetches the timestamp of the latest trade activity (opening an active trade or closing a trade) of a strategy.
+    pub async fn fetch_last_trade_activity(&self, alert_name: &str) -> Result<Option<DateTime<Utc>>, mongodb::error::Error> {
+        let last_opened = self.active_trade_collection
+            .find_one(doc! { "alertName": alert_name })
+            .sort(doc! { "openTimestamp": -1 })
+            .await?
+            .map(|trade| trade.open_timestamp);
+
+        let last_closed = self.closed_trade_collection
+            .find_one(doc! { "alertName": alert_name })
+            .sort(doc! { "closeTimestamp": -1 })
+            .await?
+            .map(|trade| trade.close_timestamp);
+
+        Ok(last_opened.max(last_closed))
+    }
+}
+
+/// Calculates how far (in percentage) the price of an alert deviates from the market price.
+pub fn calc_price_deviation_percentage(alert_price: f64, market_price: f64) -> f64 {
+    ((alert_price - market_price) / market_price * 100.0).abs()
+}
+
+/// Records an alert at `now` into `history`, dropping entries older than `window`, and returns the amount of alerts within the window.
+pub fn record_alert_timestamp(history: &mut VecDeque<DateTime<Utc>>, now: DateTime<Utc>, window: Duration) -> usize {
+    history.push_back(now);
+
+    while history.front().is_some_and(|timestamp| *timestamp <= now - window) {
+        history.pop_front();
+    }
+
+    history.len()
+}
+
+/// Checks an incoming alert for anomalies:
+/// 1) its price deviates more than `MAX_ALERT_PRICE_DEVIATION_PERCENTAGE` from the latest market price.
+/// 2) its strategy sent more than `MAX_ALERTS_PER_WINDOW` alerts within `ALERT_FREQUENCY_WINDOW_SECS`.
+/// 3) its strategy has had no trade activity for `STRATEGY_SILENCE_DAYS`.
+/// 
+/// Every alert passed in counts towards the frequency of its strategy.
+pub async fn detect_alert_anomalies(app_state: &AppState, mongo_state: &MongoDBState, alert: &TradingViewAlert) -> Vec<AlertAnomaly> {
+    let mut anomalies = Vec::new();
+    let now = app_state.clock.now();
+
+    let market_price = app_state.current_price(&alert.pair).await.ok();
+
+    // alerts can't be checked against the market until a price is available (from the price feed or the price exchange)
+    if let Some(market_price) = market_price.filter(|price| *price > 0.0) {
+        let deviation_percentage = calc_price_deviation_percentage(alert.price, market_price);
+
+        if deviation_percentage > MAX_ALERT_PRICE_DEVIATION_PERCENTAGE {
+            anomalies.push(AlertAnomaly::PriceDeviation { alert_price: alert.price, market_price, deviation_percentage });
+        }
+    }
+
+    let alerts = {
+        let mut history = app_state.alert_history.lock().unwrap();
+        record_alert_timestamp(history.get_or_insert_with(alert.name.clone(), VecDeque::new), now, Duration::seconds(ALERT_FREQUENCY_WINDOW_SECS))
+    };
+
+    if alerts > MAX_ALERTS_PER_WINDOW {
+        anomalies.push(AlertAnomaly::FrequencySpike { alerts, window_secs: ALERT_FREQUENCY_WINDOW_SECS });
+    }
+
+    match mongo_state.fetch_last_trade_activity(&alert.name).await {
+        Ok(Some(last_activity)) if now - last_activity > Duration::days(STRATEGY_SILENCE_DAYS) => {
+            anomalies.push(AlertAnomaly::SilentStrategy { last_activity });
+        }
+        Ok(_) => {}
+        Err(err) => eprintln!("(detect_alert_anomalies) Failed to fetch last trade activity of {}: {}", alert.name, err),
+    }
+
+    anomalies
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId}, results::InsertOneResult, Cursor};
+
+use crate::{api::trade::{calc_page_skip, clamp_per_page}, constants::MAX_PER_PAGE, models::{ApiResponse, AuditAction, AuditActor, AuditLogEntry, AuditLogQuery, MongoDBState}};
+
+/// CRUD operations for the audit log in the database.
+impl MongoDBState {
+    /// Adds an entry into the audit log.
+    pub async fn add_audit_log_entry(&self, entry: AuditLogEntry) -> Result<InsertOneResult, mongodb::error::Error> {
+        self.audit_log_collection.insert_one(entry).await
+    }
+
+    /// Fetches the audit log entries (optionally of a single target) with pagination, newest first.
+    pub async fn fetch_audit_log(&self, target: Option<&str>, page: u32, per_page: u32) -> Result<Vec<AuditLogEntry>, mongodb::error::Error> {
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) is empty
+        let Some(skip) = calc_page_skip(page.max(1), per_page) else {
+            return Ok(Vec::new())
+        };
+
+        let filter = match target {
+            Some(target) => doc! { "target": target },
+            None => doc! {},
+        };
+
+        let mut cursor: Cursor<AuditLogEntry> = self
+            .audit_log_collection
+            .find(filter)
+            .sort(doc! { "timestamp": -1 })
+            .skip(skip)
+            .limit(per_page as i64)
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Records a change made at `now` in the audit log.
+    ///
+    /// Failing to record a change doesn't fail the change itself, so errors are only logged.
+    pub async fn record_audit(&self, actor: AuditActor, action: AuditAction, target: &str, details: Option<String>, request_id: Option<&str>, now: DateTime<Utc>) {
+        let entry = AuditLogEntry {
+            id: ObjectId::new(),
+            timestamp: now,
+            actor,
+            action,
+            target: target.to_string(),
+            details,
+            request_id: request_id.map(str::to_string),
+        };
+
+        if let Err(err) = self.add_audit_log_entry(entry).await {
+            eprintln!("(record_audit) Failed to record {:?} of {}: {}", action, target, err);
+        }
+    }
+}
+
+/// Returns the audit log, newest first. Optionally filtered by the `target` query parameter.
+pub async fn get_audit_log(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<AuditLogQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<AuditLogEntry>>>) {
+    match mongo_state.fetch_audit_log(query.target.as_deref(), query.page.unwrap_or(1), query.per_page.unwrap_or(MAX_PER_PAGE as u32)).await {
+        Ok(entries) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_audit_log) Fetched audit log successfully.".to_string(),
+                data: Some(entries)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_audit_log) Failed to fetch audit log: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_audit_log) Failed to fetch audit log: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
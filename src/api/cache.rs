@@ -0,0 +1,126 @@
+use std::{borrow::Borrow, collections::{BTreeMap, HashMap}, hash::Hash, sync::Arc};
+
+use axum::{Extension, Json};
+use hyper::StatusCode;
+
+use crate::models::{ApiResponse, AppState, CacheReport, LruCache};
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// Creates an empty cache holding at most `capacity` entries (at least 1).
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), entries: HashMap::new(), order: BTreeMap::new(), writes: 0, evictions: 0 }
+    }
+
+    /// Returns the value of `key`, without counting it as a use.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    /// Marks `key` as the most recently written entry.
+    fn touch(&mut self, key: &K) {
+        self.writes += 1;
+
+        if let Some((_, written)) = self.entries.get_mut(key) {
+            self.order.remove(written);
+            *written = self.writes;
+            self.order.insert(self.writes, key.clone());
+        }
+    }
+
+    /// Evicts the least recently written entries until the cache is within its capacity.
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let Some((_, key)) = self.order.pop_first() else { break };
+
+            self.entries.remove(&key);
+            self.evictions += 1;
+        }
+    }
+
+    /// Inserts or replaces the value of `key`, evicting the least recently written entry if the cache is full.
+    /// Returns the previous value of `key`, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let previous = self.entries.insert(key.clone(), (value, 0)).map(|(previous, written)| {
+            self.order.remove(&written);
+            previous
+        });
+
+        self.touch(&key);
+        self.evict();
+
+        previous
+    }
+
+    /// Returns the value of `key` to modify it, inserting `default()` first if there is none. Counts as a write.
+    pub fn get_or_insert_with(&mut self, key: K, default: impl FnOnce() -> V) -> &mut V {
+        if !self.entries.contains_key(&key) {
+            self.insert(key.clone(), default());
+        } else {
+            self.touch(&key);
+        }
+
+        &mut self.entries.get_mut(&key).expect("the entry was just inserted").0
+    }
+
+    /// Iterates over all entries in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, (value, _))| (key, value))
+    }
+
+    /// Removes and returns all entries, least recently written first.
+    pub fn take(&mut self) -> Vec<(K, V)> {
+        let mut entries = std::mem::take(&mut self.entries);
+
+        std::mem::take(&mut self.order)
+            .into_values()
+            .filter_map(|key| entries.remove(&key).map(|(value, _)| (key, value)))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Reports the size and evictions of the cache as `name`.
+    pub fn report(&self, name: &'static str) -> CacheReport {
+        CacheReport { name, len: self.len(), capacity: self.capacity, evictions: self.evictions }
+    }
+}
+
+/// Creates an unbounded cache.
+impl<K: Hash + Eq + Clone, V> Default for LruCache<K, V> {
+    fn default() -> Self {
+        Self::new(usize::MAX)
+    }
+}
+
+impl AppState {
+    /// Reports the size and evictions of each bounded in-memory cache.
+    pub fn cache_reports(&self) -> Vec<CacheReport> {
+        vec![
+            self.latest_prices.lock().unwrap().report("latestPrices"),
+            self.alert_history.lock().unwrap().report("alertHistory"),
+            self.trade_ticks.buffer.lock().unwrap().report("tradeTicks"),
+        ]
+    }
+}
+
+/// Returns the size and evictions of each bounded in-memory cache.
+pub async fn get_caches(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<Vec<CacheReport>>>) {
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_caches) Fetched the in-memory caches successfully.".to_string(),
+            data: Some(app_state.cache_reports())
+        })
+    )
+}
@@ -0,0 +1,116 @@
+use std::{collections::{HashMap, HashSet}, str::FromStr};
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::America::New_York;
+
+use crate::{constants::{FOREX_WEEKLY_OPEN_HOUR, US_EQUITIES_CLOSE_TIME, US_EQUITIES_OPEN_TIME}, models::{MarketCalendar, MarketHours, TradingCalendar}};
+
+impl FromStr for MarketCalendar {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "continuous" | "24/7" => Ok(MarketCalendar::Continuous),
+            "forex" | "fx" => Ok(MarketCalendar::Forex),
+            "us_equities" | "us" => Ok(MarketCalendar::UsEquities),
+            _ => Err(format!("Unknown market calendar: {}", s)),
+        }
+    }
+}
+
+/// Parses the calendars of the TradFi symbols, e.g. `EURUSD:forex,SPX500USD:us_equities`.
+pub fn parse_market_calendars(value: &str) -> Result<HashMap<String, MarketCalendar>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (symbol, calendar) = entry.split_once(':').ok_or_else(|| format!("Expected <symbol>:<calendar>, got {:?}", entry))?;
+
+            Ok((symbol.trim().to_uppercase(), calendar.parse::<MarketCalendar>()?))
+        })
+        .collect()
+}
+
+/// Parses the days that the TradFi markets are closed on, e.g. `2025-12-25,2026-01-01`.
+pub fn parse_market_holidays(value: &str) -> Result<HashSet<NaiveDate>, String> {
+    value
+        .split(',')
+        .filter(|date| !date.trim().is_empty())
+        .map(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| format!("Invalid date {:?}, expected YYYY-MM-DD", date)))
+        .collect()
+}
+
+impl MarketHours {
+    /// Whether the market is open at `at`. Holidays only close non-continuous markets.
+    ///
+    /// The forex hours are fixed in UTC, while the US equities hours follow New York time (including daylight saving time).
+    pub fn is_open(&self, at: DateTime<Utc>) -> bool {
+        match self.calendar {
+            MarketCalendar::Continuous => true,
+            MarketCalendar::Forex => {
+                let open = match at.weekday() {
+                    Weekday::Sat => false,
+                    Weekday::Sun => at.hour() >= FOREX_WEEKLY_OPEN_HOUR,
+                    Weekday::Fri => at.hour() < FOREX_WEEKLY_OPEN_HOUR,
+                    _ => true,
+                };
+
+                open && !self.holidays.contains(&at.date_naive())
+            }
+            MarketCalendar::UsEquities => {
+                let local = at.with_timezone(&New_York);
+
+                let open_time = NaiveTime::from_hms_opt(US_EQUITIES_OPEN_TIME.0, US_EQUITIES_OPEN_TIME.1, 0).expect("valid open time");
+                let close_time = NaiveTime::from_hms_opt(US_EQUITIES_CLOSE_TIME.0, US_EQUITIES_CLOSE_TIME.1, 0).expect("valid close time");
+
+                !matches!(local.weekday(), Weekday::Sat | Weekday::Sun)
+                    && (open_time..close_time).contains(&local.time())
+                    && !self.holidays.contains(&local.date_naive())
+            }
+        }
+    }
+}
+
+impl TradingCalendar {
+    /// Reads the calendars of the TradFi symbols from the `TRADFI_SYMBOLS` env variable (e.g. `EURUSD:forex,SPX500USD:us_equities`)
+    /// and the days their markets are closed on from `MARKET_HOLIDAYS` (e.g. `2025-12-25`). Without either, all markets trade 24/7.
+    pub fn from_env() -> Self {
+        let symbols = match std::env::var("TRADFI_SYMBOLS") {
+            Ok(value) => parse_market_calendars(&value).unwrap_or_else(|err| {
+                eprintln!("(TradingCalendar::from_env) {}. Treating all symbols as 24/7 markets.", err);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        let holidays = match std::env::var("MARKET_HOLIDAYS") {
+            Ok(value) => parse_market_holidays(&value).unwrap_or_else(|err| {
+                eprintln!("(TradingCalendar::from_env) {}. Ignoring the market holidays.", err);
+                HashSet::new()
+            }),
+            Err(_) => HashSet::new(),
+        };
+
+        Self { symbols, holidays }
+    }
+
+    /// Whether `pair` is a TradFi symbol, i.e. it doesn't trade 24/7.
+    pub fn is_tradfi(&self, pair: &str) -> bool {
+        self.symbols.get(&pair.to_uppercase()).is_some_and(|calendar| *calendar != MarketCalendar::Continuous)
+    }
+
+    /// Returns the trading hours of `pair`.
+    pub fn market_hours(&self, pair: &str) -> MarketHours {
+        let calendar = self.symbols.get(&pair.to_uppercase()).copied().unwrap_or_default();
+
+        MarketHours {
+            calendar,
+            holidays: if calendar == MarketCalendar::Continuous { HashSet::new() } else { self.holidays.clone() },
+        }
+    }
+
+    /// Whether the market of `pair` is open at `at`.
+    pub fn is_open(&self, pair: &str, at: DateTime<Utc>) -> bool {
+        self.market_hours(pair).is_open(at)
+    }
+}
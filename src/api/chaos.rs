@@ -0,0 +1,112 @@
+use std::future::Future;
+
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::{constants::DEFAULT_CHAOS_FEED_GAP_SECS, models::ChaosMode};
+
+impl ChaosMode {
+    /// Reads the chaos mode from the variables returned by `lookup`. Failures are only injected if `CHAOS_MODE` is `true`.
+    /// Invalid rates are logged and treated as 0, and valid ones are clamped between 0 and 1.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let parse_rate = |name: &str| -> f64 {
+            let Some(value) = lookup(name) else {
+                return 0.0;
+            };
+
+            match value.trim().parse::<f64>() {
+                Ok(rate) if rate.is_finite() => rate.clamp(0.0, 1.0),
+                _ => {
+                    eprintln!("(ChaosMode::from_lookup) Ignoring invalid {}: {}", name, value);
+                    0.0
+                }
+            }
+        };
+
+        Self {
+            enabled: lookup("CHAOS_MODE").is_some_and(|enabled| enabled.trim().eq_ignore_ascii_case("true")),
+            db_write_failure_rate: parse_rate("CHAOS_DB_WRITE_FAILURE_RATE"),
+            order_rejection_rate: parse_rate("CHAOS_ORDER_REJECTION_RATE"),
+            feed_gap_rate: parse_rate("CHAOS_FEED_GAP_RATE"),
+            feed_gap_secs: lookup("CHAOS_FEED_GAP_SECS")
+                .and_then(|secs| secs.trim().parse().ok())
+                .unwrap_or(DEFAULT_CHAOS_FEED_GAP_SECS),
+            feed_gap_until: Default::default(),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    /// Reads the chaos mode from the `CHAOS_*` env variables.
+    pub fn from_env() -> Self {
+        let chaos = Self::from_lookup(|name| std::env::var(name).ok());
+
+        if chaos.enabled {
+            eprintln!(
+                "(ChaosMode::from_env) Chaos mode enabled. DB write failure rate: {}, order rejection rate: {}, feed gap rate: {} ({}s gaps).",
+                chaos.db_write_failure_rate, chaos.order_rejection_rate, chaos.feed_gap_rate, chaos.feed_gap_secs
+            );
+        }
+
+        chaos
+    }
+
+    /// Returns `true` with a probability of `rate`. Always `false` if the chaos mode is disabled.
+    pub fn roll(&self, rate: f64) -> bool {
+        if !self.enabled || rate <= 0.0 {
+            return false;
+        }
+
+        let mut bytes = [0u8; 8];
+
+        if self.rng.fill(&mut bytes).is_err() {
+            return false;
+        }
+
+        // the upper 53 bits give a uniformly distributed float in [0, 1)
+        let sample = (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64;
+
+        sample < rate
+    }
+
+    /// Runs `write`, unless a failure is injected, in which case it fails with a transient (connection reset) error instead,
+    /// so that the retries of `retry_transient_write` and the handling of failed writes are exercised.
+    pub async fn disrupt_write<T>(
+        &self,
+        operation: &str,
+        write: impl Future<Output = Result<T, mongodb::error::Error>>
+    ) -> Result<T, mongodb::error::Error> {
+        if self.roll(self.db_write_failure_rate) {
+            eprintln!("(disrupt_write) [chaos] Injecting a write failure into {}.", operation);
+
+            return Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "write failure injected by the chaos mode").into());
+        }
+
+        write.await
+    }
+
+    /// Whether the (simulated) exchange rejects the order of an alert.
+    pub fn rejects_order(&self) -> bool {
+        self.roll(self.order_rejection_rate)
+    }
+
+    /// Whether the price feed is in a gap at `now`, i.e. whether a tick received at `now` should be dropped.
+    /// Each tick outside of a gap may start a new gap lasting `feed_gap_secs`.
+    pub fn is_feed_gap(&self, now: DateTime<Utc>) -> bool {
+        let mut feed_gap_until = self.feed_gap_until.lock().unwrap();
+
+        if feed_gap_until.is_some_and(|until| now < until) {
+            return true;
+        }
+
+        if self.roll(self.feed_gap_rate) {
+            eprintln!("(is_feed_gap) [chaos] Dropping the price feed for {}s.", self.feed_gap_secs);
+            *feed_gap_until = Some(now + Duration::seconds(self.feed_gap_secs as i64));
+
+            return true;
+        }
+
+        *feed_gap_until = None;
+
+        false
+    }
+}
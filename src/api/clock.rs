@@ -0,0 +1,38 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::{Clock, SimulatedClock, SystemClock};
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[allow(dead_code)]
+impl SimulatedClock {
+    /// Initializes a simulated clock starting at `start`.
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+
+    /// Sets the current time of the clock to `now`.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// Moves the clock forward by `duration` and returns the new current time.
+    pub fn advance(&self, duration: Duration) -> DateTime<Utc> {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+
+        *now
+    }
+}
@@ -0,0 +1,494 @@
+use std::{collections::HashSet, sync::{atomic::Ordering, Arc}, time::Duration as StdDuration};
+
+use axum::{extract::{Path, Request}, http::HeaderMap, middleware::Next, response::{IntoResponse, Response}, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId, to_bson}, options::ReturnDocument, results::{InsertOneResult, UpdateResult}};
+use serde_json::json;
+
+use crate::{api::{live::close_active_trade, risk::calc_day_start, stats::resolve_timezone}, constants::{COMMAND_POLL_INTERVAL_SECS, COMMAND_SECRET_HEADER, TELEGRAM_API_URL, TELEGRAM_POLL_TIMEOUT_SECS}, models::{ApiResponse, AppState, AuditAction, AuditActor, BotCommand, CommandSource, CommandStatus, CurrencyConversion, MongoDBState, NewCommand, PnlPeriod, QueuedCommand, ReportingCurrency, TelegramResponse, TelegramUpdate}};
+
+/// Operations on the command queue in the database.
+impl MongoDBState {
+    /// Adds a command to the queue. The sender (e.g. a Telegram chat ID) is encrypted if field encryption is enabled.
+    pub async fn add_command(&self, mut command: QueuedCommand) -> Result<InsertOneResult, mongodb::error::Error> {
+        command.sender = self.encrypt_field("sender", &command.sender)?;
+
+        self.command_collection.insert_one(command).await
+    }
+
+    /// Decrypts the sender of a stored command.
+    fn decrypt_command(&self, command: Option<QueuedCommand>) -> Result<Option<QueuedCommand>, mongodb::error::Error> {
+        command
+            .map(|mut command| {
+                command.sender = self.decrypt_field("sender", &command.sender)?;
+                Ok(command)
+            })
+            .transpose()
+    }
+
+    /// Fetches a command based on the provided ID.
+    pub async fn fetch_command(&self, id: ObjectId) -> Result<Option<QueuedCommand>, mongodb::error::Error> {
+        let command = self.command_collection.find_one(doc! { "_id": id }).await?;
+
+        self.decrypt_command(command)
+    }
+
+    /// Claims the oldest pending command for processing, so that it's only processed once.
+    pub async fn claim_next_command(&self) -> Result<Option<QueuedCommand>, mongodb::error::Error> {
+        let pending = to_bson(&CommandStatus::Pending).map_err(mongodb::error::Error::from)?;
+        let processing = to_bson(&CommandStatus::Processing).map_err(mongodb::error::Error::from)?;
+
+        let command = self.command_collection
+            .find_one_and_update(doc! { "status": pending }, doc! { "$set": { "status": processing } })
+            .sort(doc! { "createdTimestamp": 1 })
+            .return_document(ReturnDocument::After)
+            .await?;
+
+        self.decrypt_command(command)
+    }
+
+    /// Marks a command as processed at `now` with its reply.
+    pub async fn complete_command(&self, id: ObjectId, status: CommandStatus, result: &str, now: DateTime<Utc>) -> Result<UpdateResult, mongodb::error::Error> {
+        let status = to_bson(&status).map_err(mongodb::error::Error::from)?;
+
+        self.command_collection
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "status": status, "result": result, "processedTimestamp": now.timestamp() } }
+            )
+            .await
+    }
+}
+
+/// Parses the text of a command (e.g. `/pnl today`).
+///
+/// Returns the usage of the command (or the available commands) if it can't be parsed.
+pub fn parse_bot_command(text: &str) -> Result<BotCommand, String> {
+    let text = text.trim();
+    let (name, argument) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+
+    // Telegram appends the bot's username to commands sent in group chats (e.g. `/status@my_bot`)
+    let name = name.split('@').next().unwrap_or(name).to_lowercase();
+    let argument = Some(argument.trim()).filter(|argument| !argument.is_empty());
+
+    match name.as_str() {
+        "/close" => argument
+            .and_then(|trade_id| ObjectId::parse_str(trade_id).ok())
+            .map(|trade_id| BotCommand::Close { trade_id })
+            .ok_or_else(|| "Usage: /close <trade id>".to_string()),
+        "/pause" => Ok(BotCommand::Pause { strategy: argument.map(str::to_string) }),
+        "/resume" => Ok(BotCommand::Resume { strategy: argument.map(str::to_string) }),
+        "/status" => Ok(BotCommand::Status),
+        "/pnl" => {
+            let period = match argument.map(str::to_lowercase).as_deref() {
+                None | Some("today") => PnlPeriod::Today,
+                Some("week") => PnlPeriod::Week,
+                Some("month") => PnlPeriod::Month,
+                Some("all") => PnlPeriod::All,
+                Some(_) => return Err("Usage: /pnl [today|week|month|all]".to_string()),
+            };
+
+            Ok(BotCommand::Pnl { period })
+        }
+        _ => Err(format!("Unknown command {}. Available commands: /close, /pause, /resume, /status, /pnl", name)),
+    }
+}
+
+/// Pauses or resumes the execution of all alerts (if `strategy` is `None`), or disables/enables a single strategy.
+async fn set_execution_enabled(app_state: &AppState, strategy: Option<String>, enabled: bool, sender: &str) -> Result<String, String> {
+    let action = if enabled { AuditAction::Enabled } else { AuditAction::Disabled };
+    let details = Some(format!("Sent by {}", sender));
+
+    let Some(strategy) = strategy else {
+        app_state.paused.store(!enabled, Ordering::SeqCst);
+        app_state.mongo_state.record_audit(AuditActor::Command, action, "alerts", details, None, app_state.clock.now()).await;
+
+        return Ok(if enabled { "Resumed the execution of alerts." } else { "Paused the execution of alerts." }.to_string())
+    };
+
+    match app_state.mongo_state.set_strategy_enabled(&strategy, enabled, app_state.clock.now()).await {
+        Ok(result) if result.matched_count == 0 => Err(format!("Strategy {} not found.", strategy)),
+        Ok(_) => {
+            app_state.mongo_state.record_audit(AuditActor::Command, action, &strategy, details, None, app_state.clock.now()).await;
+
+            Ok(format!("{} strategy {}.", if enabled { "Enabled" } else { "Disabled" }, strategy))
+        }
+        Err(err) => Err(format!("Failed to update strategy {}: {}", strategy, err)),
+    }
+}
+
+/// Executes a command and returns its reply.
+async fn execute_command(app_state: &AppState, command: BotCommand, sender: &str) -> Result<String, String> {
+    match command {
+        BotCommand::Close { trade_id } => {
+            let trade = app_state.active_trades.lock().unwrap().get(&trade_id).cloned();
+            let Some(trade) = trade else {
+                return Err(format!("Trade {} is not active.", trade_id))
+            };
+
+            let exit_price = app_state.current_price(&trade.pair).await?;
+
+            match close_active_trade(app_state, &trade_id, exit_price).await {
+                Ok(Some(closed_trade)) => Ok(format!(
+                    "Closed trade {} on {} at {}. PnL: {:.2} {} ({:.2}% ROE).",
+                    trade_id, closed_trade.pair, closed_trade.exit_price, closed_trade.pnl, closed_trade.settlement_currency, closed_trade.roe
+                )),
+                Ok(None) => Err(format!("Trade {} is not active.", trade_id)),
+                Err(err) => Err(format!("Failed to close trade {}: {}", trade_id, err)),
+            }
+        }
+        BotCommand::Pause { strategy } => set_execution_enabled(app_state, strategy, false, sender).await,
+        BotCommand::Resume { strategy } => set_execution_enabled(app_state, strategy, true, sender).await,
+        BotCommand::Status => {
+            let mut trades: Vec<_> = app_state.active_trades.lock().unwrap().values().cloned().collect();
+            trades.sort_by_key(|trade| trade.open_timestamp);
+
+            let mut reply = format!(
+                "Alerts: {}\nOpen trades: {}",
+                if app_state.paused.load(Ordering::SeqCst) { "paused" } else { "running" },
+                trades.len()
+            );
+
+            for trade in trades {
+                reply.push_str(&format!("\n- {} {} {:?} @ {} ({})", trade.id, trade.pair, trade.direction, trade.entry_price, trade.alert_name));
+            }
+
+            Ok(reply)
+        }
+        BotCommand::Pnl { period } => {
+            let now = app_state.clock.now();
+            let since = match period {
+                PnlPeriod::Today => calc_day_start(now, resolve_timezone(None)),
+                PnlPeriod::Week => Some(now - Duration::days(7)),
+                PnlPeriod::Month => Some(now - Duration::days(30)),
+                PnlPeriod::All => None,
+            };
+
+            let filter = since
+                .map(|since| doc! { "closeTimestamp": { "$gte": since.timestamp() } })
+                .unwrap_or_default();
+
+            let currency = ReportingCurrency::from_env();
+            let conversion = app_state.current_fx_rates()
+                .rate(currency)
+                .map(|fallback_rate| CurrencyConversion { currency, fallback_rate })
+                .ok_or_else(|| format!("No conversion rate available for {:?} yet.", currency))?;
+
+            match app_state.mongo_state.aggregate_stats_overview(filter, &conversion, resolve_timezone(None), app_state.clock.now()).await {
+                Ok(overview) => Ok(format!(
+                    "PnL ({:?}): {:.2} {:?} over {} trades ({:.1}% win rate).",
+                    period, overview.lifetime.total_pnl, currency, overview.lifetime.total_trades, overview.lifetime.win_rate
+                )),
+                Err(err) => Err(format!("Failed to aggregate PnL: {}", err)),
+            }
+        }
+    }
+}
+
+/// Sends a message to a Telegram chat.
+async fn send_telegram_message(client: &reqwest::Client, token: &str, chat_id: &str, text: &str) -> Result<(), reqwest::Error> {
+    client
+        .post(format!("{}/bot{}/sendMessage", TELEGRAM_API_URL, token))
+        .json(&json!({ "chat_id": chat_id, "text": text }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+/// Processes a claimed command and stores its reply. Commands sent via Telegram are also replied to in the chat.
+async fn process_command(app_state: &AppState, client: &reqwest::Client, command: QueuedCommand) {
+    let result = match parse_bot_command(&command.text) {
+        Ok(parsed) => execute_command(app_state, parsed, &command.sender).await,
+        Err(usage) => Err(usage),
+    };
+
+    let (status, reply) = match result {
+        Ok(reply) => (CommandStatus::Completed, reply),
+        Err(reply) => (CommandStatus::Failed, reply),
+    };
+
+    println!("(process_command) {} from {:?} {}: {:?}", command.text, command.source, command.sender, status);
+
+    if let Err(err) = app_state.mongo_state.complete_command(command.id, status, &reply, app_state.clock.now()).await {
+        eprintln!("(process_command) Failed to complete command {}: {}", command.id, err);
+    }
+
+    if command.source == CommandSource::Telegram {
+        if let Ok(token) = std::env::var("TELEGRAM_BOT_TOKEN") {
+            if let Err(err) = send_telegram_message(client, &token, &command.sender, &reply).await {
+                eprintln!("(process_command) Failed to reply to Telegram chat {}: {}", command.sender, err);
+            }
+        }
+    }
+}
+
+/// Periodically processes the pending commands in the queue, oldest first.
+///
+/// When running multiple instances, only the leader processes commands.
+pub async fn start_command_processor(app_state: Arc<AppState>) {
+    let client = reqwest::Client::new();
+    let mut interval = tokio::time::interval(StdDuration::from_secs(COMMAND_POLL_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        if !app_state.leadership.is_leader() {
+            continue;
+        }
+
+        loop {
+            match app_state.mongo_state.claim_next_command().await {
+                Ok(Some(command)) => process_command(&app_state, &client, command).await,
+                Ok(None) => break,
+                Err(err) => {
+                    eprintln!("(start_command_processor) Failed to claim command: {}", err);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Fetches the messages sent to the Telegram bot since `offset` (waiting up to `TELEGRAM_POLL_TIMEOUT_SECS` for new ones)
+/// and queues the commands sent from `allowed_chat_ids`.
+///
+/// Returns the offset of the next poll.
+async fn poll_telegram_updates(
+    client: &reqwest::Client,
+    app_state: &AppState,
+    token: &str,
+    allowed_chat_ids: &HashSet<i64>,
+    offset: i64
+) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+    let response = client
+        .get(format!("{}/bot{}/getUpdates", TELEGRAM_API_URL, token))
+        .query(&[("offset", offset.to_string()), ("timeout", TELEGRAM_POLL_TIMEOUT_SECS.to_string())])
+        .send()
+        .await?
+        .json::<TelegramResponse<Vec<TelegramUpdate>>>()
+        .await?;
+
+    if !response.ok {
+        return Err(response.description.unwrap_or_else(|| "getUpdates failed".to_string()).into())
+    }
+
+    let mut next_offset = offset;
+
+    for update in response.result.unwrap_or_default() {
+        next_offset = next_offset.max(update.update_id + 1);
+
+        let Some(message) = update.message else { continue };
+        let Some(text) = message.text.filter(|text| text.starts_with('/')) else { continue };
+
+        // only operators' chats are allowed to send commands
+        if !allowed_chat_ids.contains(&message.chat.id) {
+            println!("(poll_telegram_updates) Ignoring command from unauthorized chat {}", message.chat.id);
+            continue;
+        }
+
+        app_state.mongo_state.add_command(QueuedCommand {
+            id: ObjectId::new(),
+            source: CommandSource::Telegram,
+            sender: message.chat.id.to_string(),
+            text,
+            status: CommandStatus::Pending,
+            result: None,
+            created_timestamp: app_state.clock.now(),
+            processed_timestamp: None,
+        }).await?;
+    }
+
+    Ok(next_offset)
+}
+
+/// Long-polls the Telegram bot for commands and queues them.
+///
+/// Requires the `TELEGRAM_BOT_TOKEN` and `TELEGRAM_ALLOWED_CHAT_IDS` (a comma-separated list of chat IDs) env variables.
+/// When running multiple instances, only the leader polls the bot.
+pub async fn start_telegram_listener(app_state: Arc<AppState>) {
+    let Ok(token) = std::env::var("TELEGRAM_BOT_TOKEN") else {
+        return
+    };
+
+    let allowed_chat_ids: HashSet<i64> = std::env::var("TELEGRAM_ALLOWED_CHAT_IDS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|chat_id| chat_id.trim().parse().ok())
+        .collect();
+
+    if allowed_chat_ids.is_empty() {
+        eprintln!("(start_telegram_listener) TELEGRAM_ALLOWED_CHAT_IDS is not set. Telegram commands are disabled.");
+        return;
+    }
+
+    let client = reqwest::Client::new();
+    let mut offset = 0;
+
+    loop {
+        if !app_state.leadership.is_leader() {
+            tokio::time::sleep(StdDuration::from_secs(COMMAND_POLL_INTERVAL_SECS)).await;
+            continue;
+        }
+
+        match poll_telegram_updates(&client, &app_state, &token, &allowed_chat_ids, offset).await {
+            Ok(next_offset) => offset = next_offset,
+            Err(err) => {
+                eprintln!("(start_telegram_listener) Failed to poll Telegram updates: {}", err);
+                tokio::time::sleep(StdDuration::from_secs(TELEGRAM_POLL_TIMEOUT_SECS)).await;
+            }
+        }
+    }
+}
+
+/// Whether `secret` matches the configured command secret `expected`. Nothing is authorized unless a secret is configured.
+pub fn is_valid_command_secret(expected: Option<&str>, secret: &str) -> bool {
+    expected.is_some_and(|expected| !expected.is_empty() && expected == secret)
+}
+
+/// Whether the `X-Command-Secret` header of a request matches the configured command secret `expected`.
+pub fn has_command_secret(headers: &HeaderMap, expected: Option<&str>) -> bool {
+    headers
+        .get(COMMAND_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|secret| is_valid_command_secret(expected, secret))
+}
+
+/// Rejects requests without a valid `X-Command-Secret` header with 401. Added as a route layer to routes that change state.
+pub async fn require_command_secret(Extension(app_state): Extension<Arc<AppState>>, request: Request, next: Next) -> Response {
+    if !has_command_secret(request.headers(), app_state.command_secret.as_deref()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse::<()> {
+                status: "401 Unauthorized",
+                code: None,
+                message: "(require_command_secret) Invalid secret provided.".to_string(),
+                data: None
+            })
+        ).into_response()
+    }
+
+    next.run(request).await
+}
+
+/// Queues a command (e.g. relayed by a Discord bot). Its reply can be fetched via `GET /commands/:id` once it's processed.
+pub async fn post_command(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Json(payload): Json<NewCommand>,
+) -> (StatusCode, Json<ApiResponse<QueuedCommand>>) {
+    // commands can't be sent via the API unless a secret is configured
+    if !is_valid_command_secret(app_state.command_secret.as_deref(), &payload.secret) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse {
+                status: "401 Unauthorized",
+                code: None,
+                message: "(post_command) Invalid secret provided.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    if let Err(usage) = parse_bot_command(&payload.text) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(post_command) {}", usage),
+                data: None
+            })
+        )
+    }
+
+    let command = QueuedCommand {
+        id: ObjectId::new(),
+        source: CommandSource::Api,
+        sender: payload.sender,
+        text: payload.text,
+        status: CommandStatus::Pending,
+        result: None,
+        created_timestamp: app_state.clock.now(),
+        processed_timestamp: None,
+    };
+
+    match mongo_state.add_command(command.clone()).await {
+        Ok(_) => (
+            StatusCode::ACCEPTED,
+            Json(ApiResponse {
+                status: "202 Accepted",
+                code: None,
+                message: "(post_command) Queued command successfully.".to_string(),
+                data: Some(command)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(post_command) Failed to queue command: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(post_command) Failed to queue command: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns a queued command based on the provided ID, including its reply once it's processed.
+pub async fn get_command(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<QueuedCommand>>) {
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(get_command) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.fetch_command(id).await {
+        Ok(Some(command)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_command) Fetched command successfully.".to_string(),
+                data: Some(command)
+            })
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(get_command) Command {} not found.", id),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_command) Failed to fetch command: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_command) Failed to fetch command: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use crate::{constants::PAPER_TRADING_EXCHANGE, models::{ActiveTrade, ConflictPolicy, ContractType, PositionConflictGuard, TradeDirection}};
+
+impl FromStr for ConflictPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "allow" => Ok(ConflictPolicy::Allow),
+            "block" => Ok(ConflictPolicy::Block),
+            "net" => Ok(ConflictPolicy::Net),
+            "require_hedge_mode" | "hedge" => Ok(ConflictPolicy::RequireHedgeMode),
+            _ => Err(format!("Unknown conflict policy: {}", s)),
+        }
+    }
+}
+
+/// Returns the active trades of other strategies that a new trade of `alert_name` would oppose: those in the other direction
+/// on the same pair and contract type, on the same exchange account.
+///
+/// The strategy's own trade in the other direction is left out, since it's closed before the new trade is opened.
+pub fn find_conflicting_trades<'a>(
+    trades: impl IntoIterator<Item = &'a ActiveTrade>,
+    alert_name: &str,
+    pair: &str,
+    exchange: &str,
+    contract_type: &ContractType,
+    direction: &TradeDirection
+) -> Vec<&'a ActiveTrade> {
+    trades
+        .into_iter()
+        .filter(|trade| {
+            trade.alert_name != alert_name
+                && trade.pair.eq_ignore_ascii_case(pair)
+                && trade.contract_type == *contract_type
+                && trade.direction != *direction
+                && trade.exchange.as_deref().unwrap_or(PAPER_TRADING_EXCHANGE).eq_ignore_ascii_case(exchange)
+        })
+        .collect()
+}
+
+impl PositionConflictGuard {
+    /// Reads the policy from the `POSITION_CONFLICT_POLICY` env variable (`allow`, `block`, `net` or `require_hedge_mode`; defaults to `allow`)
+    /// and the exchanges in hedge mode from `HEDGE_MODE_EXCHANGES` (comma-separated, e.g. `binance,bybit`).
+    pub fn from_env() -> Self {
+        let policy = match std::env::var("POSITION_CONFLICT_POLICY") {
+            Ok(policy) => policy.parse().unwrap_or_else(|err| {
+                eprintln!("(PositionConflictGuard::from_env) {}. Allowing conflicting trades.", err);
+                ConflictPolicy::Allow
+            }),
+            Err(_) => ConflictPolicy::Allow,
+        };
+
+        let hedge_mode_exchanges = std::env::var("HEDGE_MODE_EXCHANGES")
+            .unwrap_or_default()
+            .split(',')
+            .map(|exchange| exchange.trim().to_lowercase())
+            .filter(|exchange| !exchange.is_empty())
+            .collect();
+
+        Self { policy, hedge_mode_exchanges }
+    }
+
+    /// Applies the policy to a new trade on `exchange` opposing `conflicts` (see `find_conflicting_trades`).
+    ///
+    /// Returns why the trade has to be rejected, if it has to.
+    pub fn check(&self, exchange: &str, conflicts: &[&ActiveTrade]) -> Result<(), String> {
+        let Some(conflict) = conflicts.first() else {
+            return Ok(());
+        };
+
+        match self.policy {
+            ConflictPolicy::Allow | ConflictPolicy::Net => Ok(()),
+            ConflictPolicy::Block => Err(format!(
+                "The trade would oppose the {:?} trade of {} on {} on the same {} account.",
+                conflict.direction, conflict.alert_name, conflict.pair, exchange
+            )),
+            ConflictPolicy::RequireHedgeMode if self.hedge_mode_exchanges.contains(&exchange.to_lowercase()) => Ok(()),
+            ConflictPolicy::RequireHedgeMode => Err(format!(
+                "The trade would oppose the {:?} trade of {} on {}, which requires the {} account to run in hedge mode.",
+                conflict.direction, conflict.alert_name, conflict.pair, exchange
+            )),
+        }
+    }
+}
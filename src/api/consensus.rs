@@ -0,0 +1,197 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use chrono::{DateTime, Duration, Utc};
+use futures_util::StreamExt;
+use serde_json::from_str;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use crate::{api::{network::connect_websocket, to_coinbase_product_id}, constants::{ACCEPTED_SYMBOLS, BINANCE_SPOT_WS_URL, CONSENSUS_MAX_PRICE_AGE_SECS, DEFAULT_CONSENSUS_DEVIATION_PERCENTAGE}, models::{AppState, BinanceTradeStreamMessage, PriceConsensus, PriceVenue, VenuePrice}};
+
+impl FromStr for PriceVenue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "coinbase" => Ok(PriceVenue::Coinbase),
+            "binance" => Ok(PriceVenue::Binance),
+            _ => Err(format!("Unknown price venue: {}", s)),
+        }
+    }
+}
+
+/// Calculates the median of `prices`. Returns `None` if there are none.
+pub fn calc_median(prices: &mut [f64]) -> Option<f64> {
+    if prices.is_empty() {
+        return None;
+    }
+
+    prices.sort_by(f64::total_cmp);
+
+    let middle = prices.len() / 2;
+
+    if prices.len().is_multiple_of(2) {
+        Some((prices[middle - 1] + prices[middle]) / 2.0)
+    } else {
+        Some(prices[middle])
+    }
+}
+
+/// Parses the weights of the venues in the index price, e.g. `coinbase:0.6,binance:0.4`. The weights don't have to add up to 1.
+pub fn parse_index_weights(value: &str) -> Result<HashMap<PriceVenue, f64>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (venue, weight) = entry.split_once(':').ok_or_else(|| format!("Expected <venue>:<weight>, got {:?}", entry))?;
+            let weight = weight.trim().parse::<f64>().ok().filter(|weight| *weight > 0.0).ok_or_else(|| format!("Invalid weight of {}: {:?}", venue.trim(), weight))?;
+
+            Ok((venue.parse::<PriceVenue>()?, weight))
+        })
+        .collect()
+}
+
+/// Calculates the index price from the prices of the venues, weighted by `weights` (equally if empty).
+/// The weights are normalized over the venues with a price, so that a venue without one doesn't skew the index.
+///
+/// Returns `None` if no venue with a weight has a price.
+pub fn calc_index_price(prices: &[(PriceVenue, f64)], weights: &HashMap<PriceVenue, f64>) -> Option<f64> {
+    let weighted: Vec<(f64, f64)> = prices
+        .iter()
+        .filter_map(|(venue, price)| {
+            let weight = if weights.is_empty() { Some(1.0) } else { weights.get(venue).copied() };
+            weight.map(|weight| (*price, weight))
+        })
+        .collect();
+
+    let total_weight: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    Some(weighted.iter().map(|(price, weight)| price * weight).sum::<f64>() / total_weight)
+}
+
+impl PriceConsensus {
+    /// Reads the secondary venues from the `SECONDARY_PRICE_FEEDS` env variable (comma-separated, e.g. `binance`; none by default)
+    /// and the deviation threshold from `CONSENSUS_DEVIATION_PERCENTAGE` (defaults to `DEFAULT_CONSENSUS_DEVIATION_PERCENTAGE`).
+    pub fn from_env() -> Self {
+        let mut secondary_venues = Vec::new();
+
+        for venue in std::env::var("SECONDARY_PRICE_FEEDS").unwrap_or_default().split(',').filter(|venue| !venue.trim().is_empty()) {
+            match venue.parse::<PriceVenue>() {
+                // Coinbase is always the primary price feed
+                Ok(PriceVenue::Coinbase) => {}
+                Ok(venue) if !secondary_venues.contains(&venue) => secondary_venues.push(venue),
+                Ok(_) => {}
+                Err(err) => eprintln!("(PriceConsensus::from_env) {}. Ignoring it.", err),
+            }
+        }
+
+        let deviation_percentage = std::env::var("CONSENSUS_DEVIATION_PERCENTAGE")
+            .ok()
+            .and_then(|percentage| percentage.trim().parse::<f64>().ok())
+            .filter(|percentage| *percentage > 0.0)
+            .unwrap_or(DEFAULT_CONSENSUS_DEVIATION_PERCENTAGE);
+
+        let index_weights = match std::env::var("INDEX_PRICE_WEIGHTS") {
+            Ok(value) => parse_index_weights(&value).unwrap_or_else(|err| {
+                eprintln!("(PriceConsensus::from_env) {}. Weighting venues equally.", err);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Self { secondary_venues, deviation_percentage, index_weights, ..Default::default() }
+    }
+
+    /// The index price of `product_id` at `now`, like exchanges compute their mark price: the weighted average of the prices
+    /// the venues reported within `CONSENSUS_MAX_PRICE_AGE_SECS`.
+    ///
+    /// Returns `None` without secondary venues, in which case the last price of the price feed should be used.
+    pub fn index_price(&self, product_id: &str, now: DateTime<Utc>) -> Option<f64> {
+        let products = self.prices.lock().unwrap();
+
+        let prices: Vec<(PriceVenue, f64)> = products
+            .get(product_id)?
+            .iter()
+            .filter(|(_, venue_price)| now - venue_price.timestamp <= Duration::seconds(CONSENSUS_MAX_PRICE_AGE_SECS))
+            .map(|(venue, venue_price)| (*venue, venue_price.price))
+            .collect();
+
+        calc_index_price(&prices, &self.index_weights)
+    }
+
+    /// Submits a tick of `product_id` (e.g. `BTC-USDT`) received from `venue` at `now`.
+    ///
+    /// The tick is rejected (returning the median price of the other venues) if it deviates more than the threshold
+    /// from the median of the prices the other venues reported within `CONSENSUS_MAX_PRICE_AGE_SECS`. Otherwise, it's accepted
+    /// and counts towards the consensus. Ticks are always accepted while no other venue has a recent price, or no secondary venue is connected.
+    pub fn submit(&self, product_id: &str, venue: PriceVenue, price: f64, now: DateTime<Utc>) -> Result<(), f64> {
+        if self.secondary_venues.is_empty() {
+            return Ok(());
+        }
+
+        let mut products = self.prices.lock().unwrap();
+        let venue_prices = products.entry(product_id.to_string()).or_default();
+
+        let mut other_prices: Vec<f64> = venue_prices
+            .iter()
+            .filter(|(other_venue, venue_price)| **other_venue != venue && now - venue_price.timestamp <= Duration::seconds(CONSENSUS_MAX_PRICE_AGE_SECS))
+            .map(|(_, venue_price)| venue_price.price)
+            .collect();
+
+        if let Some(median) = calc_median(&mut other_prices) {
+            if ((price - median) / median).abs() * 100.0 > self.deviation_percentage {
+                return Err(median);
+            }
+        }
+
+        venue_prices.insert(venue, VenuePrice { price, timestamp: now });
+
+        Ok(())
+    }
+}
+
+/// Connects to Binance's trade streams of all accepted symbols and submits each trade to the price consensus.
+/// Does nothing unless Binance is a secondary price feed.
+pub async fn start_binance_price_feed(app_state: Arc<AppState>) {
+    if !app_state.price_consensus.secondary_venues.contains(&PriceVenue::Binance) {
+        return;
+    }
+
+    let streams: Vec<String> = ACCEPTED_SYMBOLS.iter().map(|symbol| format!("{}@trade", symbol.to_lowercase())).collect();
+    let url = format!("{}/stream?streams={}", BINANCE_SPOT_WS_URL, streams.join("/"));
+
+    let mut ws_stream = match connect_websocket(&url, &app_state.network).await {
+        Ok(connection) => connection,
+        Err(err) => {
+            eprintln!("(start_binance_price_feed) Failed to connect to Binance: {}", err);
+            return;
+        }
+    };
+
+    println!("(start_binance_price_feed) Connected to Binance: {}", url);
+
+    // pings are answered while reading
+    while let Some(msg_result) = ws_stream.next().await {
+        match msg_result {
+            Ok(Message::Text(text)) => {
+                let Ok(message) = from_str::<BinanceTradeStreamMessage>(&text) else { continue };
+                let Some(product_id) = to_coinbase_product_id(&message.data.symbol) else { continue };
+                let Some(price) = message.data.price.parse::<f64>().ok().filter(|price| *price > 0.0) else { continue };
+
+                if let Err(median) = app_state.price_consensus.submit(&product_id, PriceVenue::Binance, price, app_state.clock.now()) {
+                    eprintln!("(start_binance_price_feed) Rejected {} tick of {} at {} (consensus: {})", message.stream, product_id, price, median);
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("(start_binance_price_feed) WebSocket error: {}", err);
+                break;
+            }
+        }
+    }
+
+    println!("(start_binance_price_feed) Exiting read loop.");
+}
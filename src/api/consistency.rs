@@ -0,0 +1,172 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{Extension, Json};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId}, Cursor};
+use serde_json::Value;
+
+use crate::models::{ActiveTrade, ApiResponse, AppState, AuditAction, AuditActor, ConsistencyReport, DiscrepancyKind, MongoDBState, RequestId, TradeDiscrepancy};
+
+impl MongoDBState {
+    /// Fetches all active trades, without pagination.
+    pub async fn fetch_all_active_trades(&self) -> Result<Vec<ActiveTrade>, mongodb::error::Error> {
+        let cursor: Cursor<ActiveTrade> = self.active_trade_collection.find(doc! {}).await?;
+
+        self.collect_documents(cursor).await?.into_iter().map(|trade| self.decrypt_active_trade(trade)).collect()
+    }
+}
+
+/// Diffs the active trades in memory against the ones in the database, ordered by trade ID.
+///
+/// Trades are compared in their serialized form, so that differences the database can't represent (e.g. sub-second timestamps)
+/// aren't reported.
+pub fn diff_active_trades(in_memory: &HashMap<ObjectId, ActiveTrade>, in_database: &[ActiveTrade]) -> Vec<TradeDiscrepancy> {
+    let in_database: HashMap<ObjectId, &ActiveTrade> = in_database.iter().map(|trade| (trade.id, trade)).collect();
+
+    let mut discrepancies = Vec::new();
+
+    for (id, stored_trade) in &in_database {
+        let Some(trade) = in_memory.get(id) else {
+            discrepancies.push(TradeDiscrepancy { trade_id: *id, kind: DiscrepancyKind::MissingInMemory, fields: Vec::new() });
+            continue
+        };
+
+        let (Ok(Value::Object(fields)), Ok(Value::Object(stored_fields))) = (serde_json::to_value(trade), serde_json::to_value(stored_trade)) else {
+            continue
+        };
+
+        let mut differing_fields: Vec<String> = fields
+            .keys()
+            .chain(stored_fields.keys())
+            .filter(|field| fields.get(*field) != stored_fields.get(*field))
+            .cloned()
+            .collect();
+
+        differing_fields.sort();
+        differing_fields.dedup();
+
+        if !differing_fields.is_empty() {
+            discrepancies.push(TradeDiscrepancy { trade_id: *id, kind: DiscrepancyKind::Mismatched, fields: differing_fields });
+        }
+    }
+
+    for id in in_memory.keys().filter(|id| !in_database.contains_key(id)) {
+        discrepancies.push(TradeDiscrepancy { trade_id: *id, kind: DiscrepancyKind::MissingInDatabase, fields: Vec::new() });
+    }
+
+    discrepancies.sort_by_key(|discrepancy| discrepancy.trade_id);
+
+    discrepancies
+}
+
+/// Checks the in-memory active trades against the database, optionally repairing the in-memory state to match the database.
+async fn check_consistency(app_state: &AppState, repair: bool) -> Result<ConsistencyReport, mongodb::error::Error> {
+    // snapshot the memory before the database, so that trades opened or closed in between only cause discrepancies
+    // that are harmless to repair (trades are stored before they're tracked, and deleted before they're untracked)
+    let in_memory = app_state.active_trades.lock().unwrap().clone();
+    let in_database = app_state.mongo_state.fetch_all_active_trades().await?;
+
+    let discrepancies = diff_active_trades(&in_memory, &in_database);
+
+    if repair && !discrepancies.is_empty() {
+        let stored_trades: HashMap<ObjectId, &ActiveTrade> = in_database.iter().map(|trade| (trade.id, trade)).collect();
+
+        for discrepancy in &discrepancies {
+            match stored_trades.get(&discrepancy.trade_id) {
+                Some(trade) => {
+                    app_state.subscribe_pair(&trade.pair);
+                    app_state.active_trades.lock().unwrap().insert(trade.id, (*trade).clone());
+                }
+                None => {
+                    app_state.active_trades.lock().unwrap().remove(&discrepancy.trade_id);
+                }
+            }
+        }
+    }
+
+    Ok(ConsistencyReport {
+        checked_timestamp: app_state.clock.now(),
+        in_memory: in_memory.len(),
+        in_database: in_database.len(),
+        repaired: repair && !discrepancies.is_empty(),
+        discrepancies,
+    })
+}
+
+/// Returns the differences between the in-memory active trades (which TP/SL/liquidation checks run against) and the database.
+pub async fn get_consistency(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<ConsistencyReport>>) {
+    match check_consistency(&app_state, false).await {
+        Ok(report) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: format!("(get_consistency) Found {} discrepancies.", report.discrepancies.len()),
+                data: Some(report)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_consistency) Failed to fetch active trades: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_consistency) Failed to fetch active trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Repairs the in-memory active trades to match the database (the source of truth): stored trades are (re)loaded into memory,
+/// and trades that aren't stored are dropped from memory.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn repair_consistency(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
+) -> (StatusCode, Json<ApiResponse<ConsistencyReport>>) {
+    match check_consistency(&app_state, true).await {
+        Ok(report) => {
+            if report.repaired {
+                mongo_state.record_audit(
+                    AuditActor::Api,
+                    AuditAction::Updated,
+                    "activeTrades",
+                    Some(format!("Repaired {} in-memory trades to match the database", report.discrepancies.len())),
+                    Some(&request_id.0),
+                    app_state.clock.now()
+                ).await;
+            }
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: None,
+                    message: format!("(repair_consistency) Repaired {} discrepancies.", report.discrepancies.len()),
+                    data: Some(report)
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(repair_consistency) Failed to fetch active trades: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(repair_consistency) Failed to fetch active trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
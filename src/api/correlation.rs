@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{doc, from_document, Document};
+
+use crate::{api::{fx::currency_conversion_stage, risk::calc_daily_returns, stats::timestamp_to_date}, constants::{BTC_USD_PRODUCT_ID, CORRELATION_LOOKBACK_DAYS, CORRELATION_MIN_DAYS, DEFAULT_CORRELATION_WINDOW_DAYS, MAX_CORRELATION_WINDOW_DAYS}, models::{ApiResponse, AppState, CorrelationPoint, CorrelationQuery, CorrelationReport, CurrencyConversion, MongoDBState, ReportingCurrency, StrategyCorrelation, StrategyDailyPnl}};
+
+impl MongoDBState {
+    /// Aggregates the realized PnL (in USDT) of each strategy per UTC day from the trades closed since `since`.
+    pub async fn aggregate_strategy_daily_pnl(&self, alert_name: Option<&str>, since: DateTime<Utc>) -> Result<Vec<StrategyDailyPnl>, mongodb::error::Error> {
+        let mut filter = doc! { "closeTimestamp": { "$gte": since.timestamp() } };
+
+        if let Some(alert_name) = alert_name {
+            filter.insert("alertName", alert_name);
+        }
+
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+        let pipeline: Vec<Document> = vec![
+            doc! { "$match": filter },
+            currency_conversion_stage(&conversion),
+            doc! {
+                "$group": {
+                    "_id": { "alertName": "$alertName", "day": { "$dateToString": { "format": "%Y-%m-%d", "date": timestamp_to_date("$closeTimestamp") } } },
+                    "pnl": { "$sum": "$pnl" },
+                }
+            },
+            doc! { "$sort": { "_id.day": 1 } },
+            doc! { "$group": { "_id": "$_id.alertName", "days": { "$push": { "day": "$_id.day", "pnl": "$pnl" } } } },
+            doc! { "$sort": { "_id": 1 } },
+        ];
+
+        let mut cursor = self.closed_trade_collection
+            .aggregate(pipeline)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        let mut strategies = Vec::new();
+
+        while cursor.advance().await? {
+            strategies.push(from_document::<StrategyDailyPnl>(cursor.deserialize_current()?)?);
+        }
+
+        Ok(strategies)
+    }
+}
+
+/// Calculates the Pearson correlation of two equally long series.
+///
+/// Returns `None` if there are fewer than `CORRELATION_MIN_DAYS` values, or either series doesn't vary.
+pub fn calc_correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    if xs.len() != ys.len() || xs.len() < CORRELATION_MIN_DAYS {
+        return None;
+    }
+
+    let n = xs.len() as f64;
+    let (mean_x, mean_y) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+
+    let covariance = xs.iter().zip(ys).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+    let variance_x = xs.iter().map(|x| (x - mean_x).powi(2)).sum::<f64>();
+    let variance_y = ys.iter().map(|y| (y - mean_y).powi(2)).sum::<f64>();
+
+    (variance_x > 0.0 && variance_y > 0.0).then(|| covariance / (variance_x * variance_y).sqrt())
+}
+
+/// Calculates the correlation of the daily PnL of a strategy to the daily returns of the benchmark over the trailing `window` days
+/// ending on each day of the benchmark (oldest first).
+///
+/// Days without closed trades count as a PnL of zero, but only from the first day with closed trades on,
+/// so that a strategy isn't correlated over the days before it started trading.
+pub fn calc_rolling_correlation(daily_pnl: &BTreeMap<String, f64>, benchmark_returns: &BTreeMap<String, f64>, window: usize) -> Vec<CorrelationPoint> {
+    let first_day = daily_pnl.keys().next();
+
+    // the PnL (if the strategy was trading yet) and the benchmark return of each day
+    let days: Vec<(&String, Option<f64>, f64)> = benchmark_returns
+        .iter()
+        .map(|(day, benchmark_return)| {
+            let pnl = first_day.filter(|first_day| day >= *first_day).map(|_| daily_pnl.get(day).copied().unwrap_or(0.0));
+            (day, pnl, *benchmark_return)
+        })
+        .collect();
+
+    days.windows(window.max(1))
+        .map(|window_days| {
+            let (pnls, returns): (Vec<f64>, Vec<f64>) = window_days
+                .iter()
+                .filter_map(|(_, pnl, benchmark_return)| Some(((*pnl)?, *benchmark_return)))
+                .unzip();
+
+            CorrelationPoint { day: window_days[window_days.len() - 1].0.clone(), correlation: calc_correlation(&pnls, &returns) }
+        })
+        .collect()
+}
+
+impl AppState {
+    /// Calculates the rolling correlation of the daily PnL of each strategy (or only `alert_name`) to the daily returns of BTC
+    /// over the last `CORRELATION_LOOKBACK_DAYS` days.
+    pub async fn calc_strategy_correlations(&self, alert_name: Option<&str>, window: usize) -> Result<Vec<StrategyCorrelation>, mongodb::error::Error> {
+        // one more day of candles, since the first return requires the previous close
+        let since = self.clock.now() - Duration::days((CORRELATION_LOOKBACK_DAYS + window) as i64 + 1);
+
+        let candles = self.mongo_state.fetch_daily_candles(&[BTC_USD_PRODUCT_ID.to_string()], since).await?;
+        let benchmark_returns = calc_daily_returns(&candles).remove(BTC_USD_PRODUCT_ID).unwrap_or_default();
+        let strategies = self.mongo_state.aggregate_strategy_daily_pnl(alert_name, since).await?;
+
+        Ok(strategies
+            .into_iter()
+            .map(|strategy| {
+                let daily_pnl: BTreeMap<String, f64> = strategy.days.into_iter().map(|day| (day.day, day.pnl)).collect();
+                let mut rolling = calc_rolling_correlation(&daily_pnl, &benchmark_returns, window);
+                rolling = rolling.split_off(rolling.len().saturating_sub(CORRELATION_LOOKBACK_DAYS));
+
+                StrategyCorrelation {
+                    alert_name: strategy.alert_name,
+                    correlation: rolling.last().and_then(|point| point.correlation),
+                    rolling,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Returns the rolling correlation of the daily PnL of each strategy to the daily returns of BTC,
+/// to show whether a strategy's returns are just (levered) exposure to the market.
+///
+/// Optionally filtered by the `alertName` query parameter, with a trailing `window` in days (`DEFAULT_CORRELATION_WINDOW_DAYS` by default).
+pub async fn get_stats_correlation(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<CorrelationQuery>,
+) -> (StatusCode, Json<ApiResponse<CorrelationReport>>) {
+    let window = query.window.unwrap_or(DEFAULT_CORRELATION_WINDOW_DAYS);
+
+    if !(CORRELATION_MIN_DAYS..=MAX_CORRELATION_WINDOW_DAYS).contains(&window) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(get_stats_correlation) The window must be between {} and {} days.", CORRELATION_MIN_DAYS, MAX_CORRELATION_WINDOW_DAYS),
+                data: None
+            })
+        )
+    }
+
+    match app_state.calc_strategy_correlations(query.alert_name.as_deref(), window).await {
+        Ok(strategies) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_stats_correlation) Calculated correlations successfully.".to_string(),
+                data: Some(CorrelationReport { benchmark: BTC_USD_PRODUCT_ID.to_string(), window_days: window, strategies })
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_stats_correlation) Failed to fetch daily PnL or candles: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_stats_correlation) Failed to fetch daily PnL or candles: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::{aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN}, rand::{SecureRandom, SystemRandom}};
+
+use crate::{constants::ENCRYPTED_FIELD_PREFIX, models::{FieldCipher, MongoDBState}};
+
+impl FieldCipher {
+    /// Initializes a cipher with a 32-byte key.
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        let key = UnboundKey::new(&AES_256_GCM, key).map_err(|_| format!("The key must be {} bytes long", AES_256_GCM.key_len()))?;
+
+        Ok(Self { key: LessSafeKey::new(key), rng: SystemRandom::new() })
+    }
+
+    /// Initializes a cipher with the base64-encoded 32-byte key in the `FIELD_ENCRYPTION_KEY` env variable. Like any other variable,
+    /// the key can be read from the file at `FIELD_ENCRYPTION_KEY_FILE` instead (e.g. a secret mounted from a KMS or secret manager),
+    /// which `load_env` resolves at startup.
+    ///
+    /// Returns `None` if the key isn't set, in which case fields are stored in plaintext.
+    pub fn from_env() -> Option<Self> {
+        let encoded_key = std::env::var("FIELD_ENCRYPTION_KEY").ok()?;
+
+        // never fall back to plaintext if a key is configured but invalid
+        let key = STANDARD.decode(encoded_key.trim()).expect("(FieldCipher::from_env) The field encryption key must be base64-encoded");
+
+        Some(Self::new(&key).expect("(FieldCipher::from_env) Invalid field encryption key"))
+    }
+
+    /// Encrypts the value of `field`. The field name is authenticated alongside the value, so that an encrypted value can't be
+    /// moved into another field.
+    pub fn encrypt(&self, field: &str, plaintext: &str) -> Result<String, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng.fill(&mut nonce).map_err(|_| "Failed to generate a nonce".to_string())?;
+
+        let mut in_out = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(field), &mut in_out)
+            .map_err(|_| format!("Failed to encrypt {}", field))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(in_out);
+
+        Ok(format!("{}{}", ENCRYPTED_FIELD_PREFIX, STANDARD.encode(sealed)))
+    }
+
+    /// Decrypts the value of `field`. Values that aren't encrypted are returned as is.
+    pub fn decrypt(&self, field: &str, value: &str) -> Result<String, String> {
+        let Some(encoded) = value.strip_prefix(ENCRYPTED_FIELD_PREFIX) else {
+            return Ok(value.to_string())
+        };
+
+        let sealed = STANDARD.decode(encoded).map_err(|_| format!("Malformed encrypted value of {}", field))?;
+
+        if sealed.len() < NONCE_LEN {
+            return Err(format!("Malformed encrypted value of {}", field))
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| format!("Malformed encrypted value of {}", field))?;
+
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self.key
+            .open_in_place(nonce, Aad::from(field), &mut in_out)
+            .map_err(|_| format!("Failed to decrypt {} (wrong key or tampered value)", field))?;
+
+        String::from_utf8(plaintext.to_vec()).map_err(|_| format!("Decrypted value of {} isn't valid UTF-8", field))
+    }
+}
+
+impl MongoDBState {
+    /// Encrypts the value of `field` before storing it, if field encryption is enabled (see `FieldCipher::from_env`).
+    pub fn encrypt_field(&self, field: &str, value: &str) -> Result<String, mongodb::error::Error> {
+        match &self.field_cipher {
+            Some(cipher) => cipher.encrypt(field, value).map_err(to_mongodb_error),
+            None => Ok(value.to_string()),
+        }
+    }
+
+    /// Decrypts the stored value of `field`. Values that aren't encrypted are returned as is.
+    pub fn decrypt_field(&self, field: &str, value: &str) -> Result<String, mongodb::error::Error> {
+        match &self.field_cipher {
+            Some(cipher) => cipher.decrypt(field, value).map_err(to_mongodb_error),
+            None if value.starts_with(ENCRYPTED_FIELD_PREFIX) => Err(to_mongodb_error(format!("{} is encrypted, but no field encryption key is configured", field))),
+            None => Ok(value.to_string()),
+        }
+    }
+}
+
+/// Wraps an encryption error, so that it's handled like any other database error.
+fn to_mongodb_error(message: String) -> mongodb::error::Error {
+    mongodb::error::Error::from(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
@@ -0,0 +1,161 @@
+use axum::Json;
+use hyper::StatusCode;
+
+use crate::{constants::{EXCHANGE_LIQUIDATION_FEES, EXCHANGE_MAINTENANCE_MARGIN_TIERS, EXCHANGE_TRADING_FEES, MAINTENANCE_MARGIN, PAPER_TRADING_EXCHANGE}, models::{ApiResponse, ExchangeProfile, ExchangeProfileInfo, FundingSchedule, MaintenanceMarginTier}};
+
+impl ExchangeProfile {
+    /// Builds the profile of a known exchange (see `EXCHANGE_TRADING_FEES`), with the fees overridden by the `MAKER_FEE_PERCENTAGE_<EXCHANGE>`,
+    /// `TAKER_FEE_PERCENTAGE_<EXCHANGE>` and `LIQUIDATION_FEE_PERCENTAGE_<EXCHANGE>` variables returned by `lookup`,
+    /// the funding interval by `FUNDING_INTERVAL_HOURS_<EXCHANGE>` and the maintenance margin tiers by `MAINTENANCE_MARGIN_TIERS_<EXCHANGE>`
+    /// (see `parse_maintenance_margin_tiers`). Invalid values are logged and ignored.
+    /// 
+    /// Returns `None` if the exchange isn't known.
+    pub fn from_lookup(exchange: &str, lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let name = exchange.trim().to_lowercase();
+        let (_, maker_fee_percentage, taker_fee_percentage) = EXCHANGE_TRADING_FEES.iter().find(|(known, _, _)| *known == name)?;
+
+        let liquidation_fee_percentage = EXCHANGE_LIQUIDATION_FEES
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, fee)| *fee)
+            .unwrap_or_default();
+
+        let maintenance_margin_tiers = EXCHANGE_MAINTENANCE_MARGIN_TIERS
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|(_, tiers)| {
+                tiers
+                    .iter()
+                    .map(|(max_notional_value, maintenance_margin_percentage)| MaintenanceMarginTier {
+                        max_notional_value: (*max_notional_value < f64::MAX).then_some(*max_notional_value),
+                        maintenance_margin_percentage: *maintenance_margin_percentage,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let tiers_variable = format!("MAINTENANCE_MARGIN_TIERS_{}", name.to_uppercase());
+        let maintenance_margin_tiers = match lookup(&tiers_variable).map(|value| parse_maintenance_margin_tiers(&value)) {
+            Some(Ok(tiers)) => tiers,
+            Some(Err(err)) => {
+                eprintln!("(ExchangeProfile::from_lookup) Ignoring invalid {}: {}", tiers_variable, err);
+                maintenance_margin_tiers
+            }
+            None => maintenance_margin_tiers,
+        };
+
+        let fee_percentage = |variable: &str, default: f64| -> f64 {
+            let variable = format!("{}_{}", variable, name.to_uppercase());
+
+            match lookup(&variable).map(|value| (value.trim().parse::<f64>(), value)) {
+                Some((Ok(fee), _)) if fee.is_finite() && fee >= 0.0 => fee,
+                Some((_, value)) => {
+                    eprintln!("(ExchangeProfile::from_lookup) Ignoring invalid {}: {}", variable, value);
+                    default
+                }
+                None => default,
+            }
+        };
+
+        Some(Self {
+            funding_schedule: FundingSchedule::from_lookup(&name, &lookup),
+            maker_fee_percentage: fee_percentage("MAKER_FEE_PERCENTAGE", *maker_fee_percentage),
+            taker_fee_percentage: fee_percentage("TAKER_FEE_PERCENTAGE", *taker_fee_percentage),
+            liquidation_fee_percentage: fee_percentage("LIQUIDATION_FEE_PERCENTAGE", liquidation_fee_percentage),
+            maintenance_margin_tiers,
+            name,
+        })
+    }
+
+    /// Builds the profile of a known exchange, with the fees and funding interval overridden by the env variables (see `from_lookup`).
+    pub fn for_exchange(exchange: &str) -> Option<Self> {
+        Self::from_lookup(exchange, |name| std::env::var(name).ok())
+    }
+
+    /// Builds the profile of `exchange`, falling back to the profile of `PAPER_TRADING_EXCHANGE` if it's unset or unknown
+    /// (e.g. for trades opened before exchange profiles existed).
+    pub fn resolve(exchange: Option<&str>) -> Self {
+        exchange
+            .and_then(Self::for_exchange)
+            .or_else(|| Self::for_exchange(PAPER_TRADING_EXCHANGE))
+            .expect("(ExchangeProfile::resolve) PAPER_TRADING_EXCHANGE must be a known exchange")
+    }
+
+    /// Returns the maintenance margin (in percentage) required for a position with `notional_value` (in USDT), based on the tier
+    /// that the notional value falls into. Falls back to `MAINTENANCE_MARGIN` if the profile has no tiers.
+    pub fn maintenance_margin_percentage(&self, notional_value: f64) -> f64 {
+        self.maintenance_margin_tiers
+            .iter()
+            .find(|tier| tier.max_notional_value.is_none_or(|max_notional_value| notional_value <= max_notional_value))
+            .or(self.maintenance_margin_tiers.last())
+            .map(|tier| tier.maintenance_margin_percentage)
+            .unwrap_or(MAINTENANCE_MARGIN)
+    }
+
+    /// Returns the names of all known exchanges.
+    pub fn known_exchanges() -> impl Iterator<Item = &'static str> {
+        EXCHANGE_TRADING_FEES.iter().map(|(name, _, _)| *name)
+    }
+}
+
+/// Parses maintenance margin tiers from comma-separated `<max notional value>:<maintenance margin percentage>` pairs in ascending order,
+/// where the last tier has `*` as its maximum notional value (e.g. `50000:0.4,600000:0.5,*:2.5`).
+pub fn parse_maintenance_margin_tiers(value: &str) -> Result<Vec<MaintenanceMarginTier>, String> {
+    let mut tiers: Vec<MaintenanceMarginTier> = Vec::new();
+
+    for tier in value.split(',') {
+        let (max_notional_value, maintenance_margin_percentage) = tier
+            .split_once(':')
+            .ok_or_else(|| format!("Tier {} isn't formatted as <max notional value>:<maintenance margin percentage>", tier.trim()))?;
+
+        let max_notional_value = match max_notional_value.trim() {
+            "*" => None,
+            max_notional_value => Some(max_notional_value.parse::<f64>().map_err(|_| format!("Invalid max notional value: {}", max_notional_value))?),
+        };
+        let maintenance_margin_percentage = maintenance_margin_percentage
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|percentage| (0.0..100.0).contains(percentage))
+            .ok_or_else(|| format!("Invalid maintenance margin percentage: {}", maintenance_margin_percentage.trim()))?;
+
+        if let Some(previous) = tiers.last() {
+            let ascending = match (previous.max_notional_value, max_notional_value) {
+                (Some(previous_max), Some(max)) => max > previous_max,
+                (Some(_), None) => true,
+                // nothing may follow the last tier
+                (None, _) => false,
+            };
+
+            if !ascending {
+                return Err("Tiers must be in ascending order of their max notional value".to_string());
+            }
+        }
+
+        tiers.push(MaintenanceMarginTier { max_notional_value, maintenance_margin_percentage });
+    }
+
+    if tiers.last().is_some_and(|tier| tier.max_notional_value.is_some()) {
+        return Err("The last tier must have * as its max notional value".to_string());
+    }
+
+    Ok(tiers)
+}
+
+/// Returns the profiles (fees, funding schedule and maintenance margin tiers) of all known exchanges.
+pub async fn get_exchange_profiles() -> (StatusCode, Json<ApiResponse<Vec<ExchangeProfileInfo>>>) {
+    let profiles = ExchangeProfile::known_exchanges()
+        .filter_map(ExchangeProfile::for_exchange)
+        .map(|profile| ExchangeProfileInfo { funding_hours: profile.funding_schedule.funding_hours(), profile })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_exchange_profiles) Fetched exchange profiles successfully.".to_string(),
+            data: Some(profiles)
+        })
+    )
+}
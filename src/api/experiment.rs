@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, to_bson, Document}, results::DeleteResult};
+
+use crate::models::{ApiResponse, AppState, ExperimentReset, MongoDBState, TradeKind};
+
+/// Builds the filter matching the paper trades opened under `experiment`.
+///
+/// Live trades are never matched, since discarding them would leave their positions on the exchange unmonitored.
+pub fn experiment_paper_trade_filter(experiment: &str) -> Document {
+    doc! { "experiment": experiment, "kind": to_bson(&TradeKind::Paper).unwrap_or_default() }
+}
+
+/// Bulk operations on the trades of an experiment in the database.
+impl MongoDBState {
+    /// Deletes all active paper trades opened under `experiment` from the database.
+    pub async fn delete_experiment_active_trades(&self, experiment: &str) -> Result<DeleteResult, mongodb::error::Error> {
+        self.active_trade_collection.delete_many(experiment_paper_trade_filter(experiment)).await
+    }
+
+    /// Deletes all closed paper trades opened under `experiment` from the database.
+    pub async fn delete_experiment_closed_trades(&self, experiment: &str) -> Result<DeleteResult, mongodb::error::Error> {
+        self.closed_trade_collection.delete_many(experiment_paper_trade_filter(experiment)).await
+    }
+}
+
+/// Resets an experiment by discarding all of its active paper trades and deleting all of its closed paper trades,
+/// so that the experiment can be rerun from a clean slate. Live trades of the experiment are kept.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn reset_experiment(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(experiment): Path<String>,
+) -> (StatusCode, Json<ApiResponse<ExperimentReset>>) {
+    let deleted_active_trades = match mongo_state.delete_experiment_active_trades(&experiment).await {
+        Ok(result) => result.deleted_count,
+        Err(err) => {
+            eprintln!("(reset_experiment) Failed to delete active trades: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(reset_experiment) Failed to delete active trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    // removes the discarded trades from the ActiveTradesMap so that they're no longer checked against the price feed
+    {
+        let mut map = app_state.active_trades.lock().unwrap();
+        map.retain(|_, trade| matches!(trade.kind, TradeKind::Live) || trade.experiment.as_deref() != Some(experiment.as_str()));
+    }
+
+    let deleted_closed_trades = match mongo_state.delete_experiment_closed_trades(&experiment).await {
+        Ok(result) => result.deleted_count,
+        Err(err) => {
+            eprintln!("(reset_experiment) Failed to delete closed trades: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(reset_experiment) Failed to delete closed trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    println!(
+        "(reset_experiment) Reset experiment {}: deleted {} active and {} closed trades.",
+        experiment, deleted_active_trades, deleted_closed_trades
+    );
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(reset_experiment) Reset experiment successfully.".to_string(),
+            data: Some(ExperimentReset { experiment, deleted_active_trades, deleted_closed_trades })
+        })
+    )
+}
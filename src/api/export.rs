@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use axum::{body::Body, extract::Query, http::header, response::{IntoResponse, Response}, Extension, Json};
+use chrono::{DateTime, Utc};
+use futures_util::{stream::{self, BoxStream}, StreamExt};
+use hyper::StatusCode;
+use mongodb::bson::{doc, Document, RawDocumentBuf};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{ApiResponse, ClosedTrade, ClosedTradeExportQuery, DeserializationMode, ExportFormat, MongoDBState, ReportLocale};
+
+/// The columns of a closed trade CSV export.
+const CSV_COLUMNS: &[&str] = &[
+    "id", "alertName", "pair", "direction", "kind", "quantity", "entryPrice", "exitPrice", "leverage", "contractType",
+    "openTimestamp", "closeTimestamp", "pnl", "roe", "executionFees", "fundingFees", "settlementCurrency", "settlementUsdtRate", "experiment",
+];
+
+impl MongoDBState {
+    /// Streams the closed trades matching `filter`, oldest first, without buffering them in memory.
+    ///
+    /// Malformed documents end the stream with an error in strict mode, or are logged and skipped in lenient mode.
+    pub async fn stream_closed_trades(&self, filter: Document) -> Result<BoxStream<'static, Result<ClosedTrade, mongodb::error::Error>>, mongodb::error::Error> {
+        let cursor = self
+            .closed_trade_collection
+            .clone_with_type::<RawDocumentBuf>()
+            .find(filter)
+            .sort(doc! { "closeTimestamp": 1 })
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        let mode = self.deserialization_mode;
+
+        Ok(cursor
+            .filter_map(move |document| async move {
+                let document = match document {
+                    Ok(document) => document,
+                    Err(err) => return Some(Err(err)),
+                };
+
+                match mongodb::bson::from_slice::<ClosedTrade>(document.as_bytes()) {
+                    Ok(trade) => Some(Ok(trade)),
+                    Err(err) if mode == DeserializationMode::Lenient => {
+                        eprintln!("(stream_closed_trades) Skipping malformed ClosedTrade document {:?}: {}", document.get("_id").ok().flatten(), err);
+                        None
+                    }
+                    Err(err) => Some(Err(err.into())),
+                }
+            })
+            .boxed())
+    }
+}
+
+/// Builds the filter of closed trades to export.
+pub fn closed_trade_export_filter(query: &ClosedTradeExportQuery) -> Document {
+    let mut filter = Document::new();
+
+    if let Some(alert_name) = &query.alert_name {
+        filter.insert("alertName", alert_name);
+    }
+
+    let mut close_timestamp = Document::new();
+
+    if let Some(from) = query.from {
+        close_timestamp.insert("$gte", from.timestamp());
+    }
+
+    if let Some(to) = query.to {
+        close_timestamp.insert("$lt", to.timestamp());
+    }
+
+    if !close_timestamp.is_empty() {
+        filter.insert("closeTimestamp", close_timestamp);
+    }
+
+    filter
+}
+
+/// Writes a single CSV row with fields separated by `delimiter`, quoting fields where required.
+pub fn to_csv_row<I: IntoIterator<Item = String>>(fields: I, delimiter: u8) -> String {
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(Vec::new());
+
+    // writing into memory can't fail
+    let _ = writer.write_record(fields);
+    let bytes = writer.into_inner().unwrap_or_default();
+
+    String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// Returns the name an enum variant is stored as (e.g. `long`, `1x`).
+fn stored_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+/// Renders a closed trade as a row of `CSV_COLUMNS`, with numbers and timestamps formatted according to `locale`.
+pub fn closed_trade_csv_row(trade: &ClosedTrade, locale: &ReportLocale) -> String {
+    to_csv_row([
+        trade.id.to_hex(),
+        trade.alert_name.clone(),
+        trade.pair.clone(),
+        stored_name(&trade.direction),
+        stored_name(&trade.kind),
+        locale.format_number(trade.quantity),
+        locale.format_number(trade.entry_price),
+        locale.format_number(trade.exit_price),
+        stored_name(&trade.leverage),
+        stored_name(&trade.contract_type),
+        locale.format_timestamp(trade.open_timestamp),
+        locale.format_timestamp(trade.close_timestamp),
+        locale.format_number(trade.pnl),
+        locale.format_number(trade.roe),
+        locale.format_number(trade.execution_fees),
+        locale.format_number(trade.funding_fees),
+        trade.settlement_currency.clone(),
+        trade.settlement_usdt_rate.map(|rate| locale.format_number(rate)).unwrap_or_default(),
+        trade.experiment.clone().unwrap_or_default(),
+    ], locale.csv_delimiter())
+}
+
+/// Renders a point of an equity curve as a CSV row, formatted according to `locale`.
+pub fn equity_csv_row(timestamp: DateTime<Utc>, equity: f64, locale: &ReportLocale) -> String {
+    to_csv_row([locale.format_timestamp(timestamp), locale.format_fixed(equity, 8)], locale.csv_delimiter())
+}
+
+/// Builds a streamed (chunked) response of `chunks`. An error while streaming aborts the response, since the status was already sent.
+fn stream_response(content_type: &'static str, chunks: BoxStream<'static, Result<String, mongodb::error::Error>>) -> Response {
+    let chunks = chunks.inspect(|chunk| {
+        if let Err(err) = chunk {
+            eprintln!("(stream_response) Failed to stream closed trades: {}", err);
+        }
+    });
+
+    ([(header::CONTENT_TYPE, content_type)], Body::from_stream(chunks)).into_response()
+}
+
+/// Builds the response of a failure to start streaming.
+fn stream_error_response(function: &str, err: mongodb::error::Error) -> Response {
+    eprintln!("({}) Failed to fetch closed trades: {}", function, err);
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiResponse::<()> {
+            status: "500 Internal Server Error",
+            code: None,
+            message: format!("({}) Failed to fetch closed trades: {}", function, err),
+            data: None
+        })
+    ).into_response()
+}
+
+/// Exports closed trades (optionally filtered by alert name and close time) as CSV or NDJSON, oldest first.
+///
+/// Numbers and timestamps of CSV exports are formatted according to the report locale, which the query may override.
+///
+/// The trades are streamed as they're read from the database, so that exporting a large history keeps memory usage flat.
+pub async fn export_closed_trades(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<ClosedTradeExportQuery>,
+) -> Response {
+    let trades = match mongo_state.stream_closed_trades(closed_trade_export_filter(&query)).await {
+        Ok(trades) => trades,
+        Err(err) => return stream_error_response("export_closed_trades", err),
+    };
+
+    match query.format.unwrap_or_default() {
+        ExportFormat::Csv => {
+            let locale = ReportLocale::from_env().with_overrides(&query);
+
+            let header = stream::once(async move { Ok(to_csv_row(CSV_COLUMNS.iter().map(|column| column.to_string()), locale.csv_delimiter())) });
+            let rows = trades.map(move |trade| trade.map(|trade| closed_trade_csv_row(&trade, &locale)));
+
+            stream_response("text/csv", header.chain(rows).boxed())
+        }
+        ExportFormat::Ndjson => {
+            let lines = trades.map(|trade| trade.map(|trade| format!("{}\n", serde_json::to_string(&trade).unwrap_or_default())));
+
+            stream_response("application/x-ndjson", lines.boxed())
+        }
+    }
+}
+
+/// Returns the equity curve (the cumulative PnL in USDT after each closed trade) as CSV, computed while streaming the trades.
+///
+/// Numbers and timestamps are formatted according to the report locale, which the query may override.
+pub async fn get_equity_report(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<ClosedTradeExportQuery>,
+) -> Response {
+    let trades = match mongo_state.stream_closed_trades(closed_trade_export_filter(&query)).await {
+        Ok(trades) => trades,
+        Err(err) => return stream_error_response("get_equity_report", err),
+    };
+
+    let locale = ReportLocale::from_env().with_overrides(&query);
+
+    let header = stream::once(async move { Ok(to_csv_row(["timestamp".to_string(), "equity".to_string()], locale.csv_delimiter())) });
+    let rows = trades.scan(0.0, move |equity, trade| {
+        let row = trade.map(|trade| {
+            *equity += trade.pnl * trade.settlement_usdt_rate.unwrap_or(1.0);
+            equity_csv_row(trade.close_timestamp, *equity, &locale)
+        });
+
+        async move { Some(row) }
+    });
+
+    stream_response("text/csv", header.chain(rows).boxed())
+}
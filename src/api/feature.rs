@@ -0,0 +1,18 @@
+use crate::models::FeatureFlags;
+
+impl FeatureFlags {
+    /// Reads the feature flags from the variables returned by `lookup`. A feature is only enabled if its variable is `true`.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let is_enabled = |name: &str| lookup(name).is_some_and(|enabled| enabled.trim().eq_ignore_ascii_case("true"));
+
+        Self {
+            live_trading: is_enabled("FEATURE_LIVE_TRADING"),
+            auto_liquidation: is_enabled("FEATURE_AUTO_LIQUIDATION"),
+        }
+    }
+
+    /// Reads the feature flags from the `FEATURE_*` env variables.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+}
@@ -0,0 +1,287 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::{bson, bson::oid::ObjectId};
+
+use crate::{api::to_coinbase_product_id, constants::{COINBASE_EXCHANGE_API_URL, DEFAULT_FEED_SPIKE_PERCENTAGE, FEED_BACKFILL_MAX_PAGES, FEED_BACKFILL_MIN_MISSING_TRADES, FEED_BACKFILL_PAGE_SIZE, FEED_GAP_SECS, FEED_SPIKE_CONFIRMATION_TICKS}, models::{ApiResponse, AppState, CoinbaseTrade, FeedQualityMonitor, FeedQualityReport, PriceTick, ProductFeedQuality, TickIssue, TradeGap}};
+
+/// Records a tick of a product in its data quality and decides whether it should be processed.
+///
+/// Gaps are measured between any two ticks. Ticks with an invalid price, an out-of-order or duplicate sequence number,
+/// or a price more than `spike_percentage` away from the last accepted price are discarded. After `FEED_SPIKE_CONFIRMATION_TICKS`
+/// spikes in a row, the new price level is accepted.
+pub fn assess_tick(
+    quality: &mut ProductFeedQuality,
+    sequence: Option<u64>,
+    price: f64,
+    timestamp: DateTime<Utc>,
+    spike_percentage: f64
+) -> Result<(), TickIssue> {
+    quality.ticks += 1;
+
+    if let Some(last_tick_timestamp) = quality.last_tick_timestamp {
+        let gap_secs = (timestamp - last_tick_timestamp).num_seconds();
+
+        if gap_secs > FEED_GAP_SECS {
+            quality.gaps += 1;
+        }
+
+        quality.max_gap_secs = quality.max_gap_secs.max(gap_secs);
+    }
+
+    quality.last_tick_timestamp = Some(timestamp);
+
+    if !price.is_finite() || price <= 0.0 {
+        quality.invalid_prices += 1;
+        quality.discarded += 1;
+        return Err(TickIssue::InvalidPrice);
+    }
+
+    if let Some(sequence) = sequence {
+        if quality.last_sequence.is_some_and(|last_sequence| sequence <= last_sequence) {
+            quality.out_of_order += 1;
+            quality.discarded += 1;
+            return Err(TickIssue::OutOfOrder);
+        }
+
+        quality.last_sequence = Some(sequence);
+    }
+
+    if let Some(last_price) = quality.last_price {
+        let change_percentage = ((price - last_price) / last_price).abs() * 100.0;
+
+        if change_percentage > spike_percentage {
+            quality.consecutive_spikes += 1;
+
+            if quality.consecutive_spikes < FEED_SPIKE_CONFIRMATION_TICKS {
+                quality.spikes += 1;
+                quality.discarded += 1;
+                return Err(TickIssue::Spike);
+            }
+        }
+    }
+
+    quality.consecutive_spikes = 0;
+    quality.last_price = Some(price);
+
+    Ok(())
+}
+
+/// Records the trade ID of an accepted tick of `product_id`, returning the trades in between it and the last one if
+/// at least `FEED_BACKFILL_MIN_MISSING_TRADES` were skipped.
+pub fn detect_trade_gap(quality: &mut ProductFeedQuality, product_id: &str, trade_id: Option<u64>) -> Option<TradeGap> {
+    let trade_id = trade_id?;
+
+    let Some(last_trade_id) = quality.last_trade_id.filter(|last_trade_id| *last_trade_id < trade_id) else {
+        quality.last_trade_id = quality.last_trade_id.max(Some(trade_id));
+        return None;
+    };
+
+    quality.last_trade_id = Some(trade_id);
+
+    let missing_trades = trade_id - last_trade_id - 1;
+    quality.missed_trades += missing_trades;
+
+    (missing_trades >= FEED_BACKFILL_MIN_MISSING_TRADES).then(|| TradeGap {
+        product_id: product_id.to_string(),
+        after_trade_id: last_trade_id,
+        before_trade_id: trade_id,
+    })
+}
+
+/// Downsamples backfilled trades (oldest first) into price ticks of `product_id`, keeping the last price within each `interval_secs`
+/// like the price tick recorder does. Trades with an invalid price are skipped.
+pub fn downsample_trades(trades: &[CoinbaseTrade], product_id: &str, interval_secs: i64) -> Vec<PriceTick> {
+    let mut buckets: BTreeMap<i64, (DateTime<Utc>, f64)> = BTreeMap::new();
+
+    for trade in trades {
+        let Some(price) = trade.price.parse::<f64>().ok().filter(|price| *price > 0.0) else { continue };
+
+        buckets.insert(trade.time.timestamp().div_euclid(interval_secs.max(1)), (trade.time, price));
+    }
+
+    buckets
+        .into_values()
+        .map(|(timestamp, price)| PriceTick {
+            timestamp: bson::DateTime::from_millis(timestamp.timestamp_millis()),
+            product: product_id.to_string(),
+            price,
+        })
+        .collect()
+}
+
+/// Fetches the trades of a gap from Coinbase's REST API, oldest first.
+///
+/// Coinbase returns the trades before the `after` cursor newest first, so the pages walk back from the end of the gap,
+/// for at most `FEED_BACKFILL_MAX_PAGES` pages.
+async fn fetch_missing_trades(client: &reqwest::Client, gap: &TradeGap) -> Result<Vec<CoinbaseTrade>, reqwest::Error> {
+    let mut trades = Vec::new();
+    let mut cursor = gap.before_trade_id;
+
+    for _ in 0..FEED_BACKFILL_MAX_PAGES {
+        let page = client
+            .get(format!("{}/products/{}/trades", COINBASE_EXCHANGE_API_URL, gap.product_id))
+            .query(&[("after", cursor.to_string()), ("limit", FEED_BACKFILL_PAGE_SIZE.to_string())])
+            // Coinbase rejects requests without a user agent
+            .header(reqwest::header::USER_AGENT, env!("CARGO_PKG_NAME"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<CoinbaseTrade>>()
+            .await?;
+
+        let Some(oldest_trade_id) = page.iter().map(|trade| trade.trade_id).min() else { break };
+
+        trades.extend(page.into_iter().filter(|trade| trade.trade_id > gap.after_trade_id));
+
+        if oldest_trade_id <= gap.after_trade_id + 1 {
+            break;
+        }
+
+        cursor = oldest_trade_id;
+    }
+
+    trades.sort_by_key(|trade| trade.trade_id);
+
+    Ok(trades)
+}
+
+impl AppState {
+    /// Backfills the trades missed by the price feed: they're stored as price ticks (so that candles derived from them are complete)
+    /// unless no ticks are persisted, and as ticks of the trades that were open on the product at the time (so that their price paths are complete).
+    pub async fn backfill_trade_gap(&self, client: &reqwest::Client, gap: &TradeGap) {
+        let trades = match fetch_missing_trades(client, gap).await {
+            Ok(trades) => trades,
+            Err(err) => {
+                eprintln!("(backfill_trade_gap) Failed to fetch trades {} to {} of {}: {}", gap.after_trade_id, gap.before_trade_id, gap.product_id, err);
+                return;
+            }
+        };
+
+        let price_ticks = match self.price_ticks.backfill_interval_secs(self.clock.now()) {
+            Some(interval_secs) => downsample_trades(&trades, &gap.product_id, interval_secs as i64),
+            None => Vec::new(),
+        };
+
+        if let Err(err) = self.mongo_state.insert_price_ticks(&price_ticks).await {
+            eprintln!("(backfill_trade_gap) Failed to store the backfilled price ticks of {}: {}", gap.product_id, err);
+        }
+
+        let open_trades: Vec<(ObjectId, DateTime<Utc>)> = {
+            let map = self.active_trades.lock().unwrap();
+            map.values()
+                .filter(|trade| to_coinbase_product_id(&trade.pair).is_some_and(|product_id| product_id == gap.product_id))
+                .map(|trade| (trade.id, trade.open_timestamp))
+                .collect()
+        };
+
+        for trade in &trades {
+            let Ok(price) = trade.price.parse::<f64>() else { continue };
+
+            let trade_ids: Vec<ObjectId> = open_trades
+                .iter()
+                .filter(|(_, open_timestamp)| *open_timestamp <= trade.time)
+                .map(|(id, _)| *id)
+                .collect();
+
+            self.trade_ticks.record(&trade_ids, price, trade.time);
+        }
+
+        if let Some(quality) = self.feed_quality.products.lock().unwrap().get_mut(&gap.product_id) {
+            quality.backfilled_trades += trades.len() as u64;
+        }
+
+        println!("(backfill_trade_gap) Backfilled {} trades of {} ({} price ticks).", trades.len(), gap.product_id, price_ticks.len());
+    }
+}
+
+/// Calculates the data quality score (0 to 100) of a product: the share of its ticks that were neither discarded nor preceded by a gap.
+///
+/// A product without ticks scores 100.
+pub fn calc_feed_quality_score(quality: &ProductFeedQuality) -> f64 {
+    if quality.ticks == 0 {
+        return 100.0;
+    }
+
+    let bad_ticks = (quality.discarded + quality.gaps).min(quality.ticks);
+
+    (quality.ticks - bad_ticks) as f64 / quality.ticks as f64 * 100.0
+}
+
+impl FeedQualityMonitor {
+    /// Reads the spike threshold from the `FEED_SPIKE_PERCENTAGE` env variable. Defaults to `DEFAULT_FEED_SPIKE_PERCENTAGE`.
+    pub fn from_env() -> Self {
+        let spike_percentage = match std::env::var("FEED_SPIKE_PERCENTAGE") {
+            Ok(value) => match value.trim().parse::<f64>() {
+                Ok(percentage) if percentage > 0.0 => percentage,
+                _ => {
+                    eprintln!("(FeedQualityMonitor::from_env) Invalid FEED_SPIKE_PERCENTAGE {:?}; using {}.", value, DEFAULT_FEED_SPIKE_PERCENTAGE);
+                    DEFAULT_FEED_SPIKE_PERCENTAGE
+                }
+            },
+            Err(_) => DEFAULT_FEED_SPIKE_PERCENTAGE,
+        };
+
+        Self { spike_percentage, ..Default::default() }
+    }
+
+    /// Records a tick of `product_id` (e.g. `BTC-USD`) received at `timestamp`, returning why it should be discarded, if it should.
+    pub fn assess(&self, product_id: &str, sequence: Option<u64>, price: f64, timestamp: DateTime<Utc>) -> Result<(), TickIssue> {
+        let mut products = self.products.lock().unwrap();
+        let quality = products.entry(product_id.to_string()).or_default();
+
+        assess_tick(quality, sequence, price, timestamp, self.spike_percentage)
+    }
+
+    /// Records that an otherwise accepted tick of `product_id` was discarded for deviating from the other venues.
+    pub fn record_consensus_outlier(&self, product_id: &str) {
+        let mut products = self.products.lock().unwrap();
+        let quality = products.entry(product_id.to_string()).or_default();
+
+        quality.consensus_outliers += 1;
+        quality.discarded += 1;
+    }
+
+    /// Records the trade ID of an accepted tick of `product_id`, returning the trades that should be backfilled, if any.
+    pub fn record_trade_id(&self, product_id: &str, trade_id: Option<u64>) -> Option<TradeGap> {
+        let mut products = self.products.lock().unwrap();
+        let quality = products.entry(product_id.to_string()).or_default();
+
+        detect_trade_gap(quality, product_id, trade_id)
+    }
+
+    /// Returns the data quality of each product that received ticks, sorted by product ID.
+    pub fn reports(&self) -> Vec<FeedQualityReport> {
+        let products = self.products.lock().unwrap();
+
+        let mut reports: Vec<FeedQualityReport> = products
+            .iter()
+            .map(|(product_id, quality)| FeedQualityReport {
+                product_id: product_id.clone(),
+                score: calc_feed_quality_score(quality),
+                quality: quality.clone(),
+            })
+            .collect();
+
+        reports.sort_by(|a, b| a.product_id.cmp(&b.product_id));
+
+        reports
+    }
+}
+
+/// Returns the data quality of the price feed of each product since the bot started.
+pub async fn get_feed_quality(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<Vec<FeedQualityReport>>>) {
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_feed_quality) Fetched the data quality of the price feed successfully.".to_string(),
+            data: Some(app_state.feed_quality.reports())
+        })
+    )
+}
@@ -0,0 +1,390 @@
+use std::{future::IntoFuture, sync::Arc, time::Duration as StdDuration};
+
+use axum::{extract::{Path, Query}, Extension, Json};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId, to_document, Document}, results::UpdateResult, Cursor};
+
+use crate::{api::{trade::{calc_page_skip, clamp_per_page}, calc_accrued_funding, split_pair}, configs::retry_transient_write, constants::{ACCEPTED_SYMBOLS, DEFAULT_FUNDING_INTERVAL_HOURS, EXCHANGE_FUNDING_INTERVAL_HOURS, FUNDING_RATE_POLL_INTERVAL_SECS, MAX_PER_PAGE}, models::{ActiveTrade, ApiResponse, AppState, Exchange, FundingHistory, FundingLedger, FundingLedgerQuery, FundingPayment, FundingQuery, FundingRate, FundingSchedule, MarketHours, MongoDBState, OpenTradeFunding}};
+
+/// CRUD operations for funding rates in the database.
+impl MongoDBState {
+    /// Inserts a funding rate into the database, or updates it if a rate for the same pair and settlement time already exists.
+    pub async fn upsert_funding_rate(&self, funding_rate: &FundingRate) -> Result<UpdateResult, mongodb::error::Error> {
+        let document = to_document(funding_rate).map_err(mongodb::error::Error::from)?;
+
+        self.funding_rate_collection
+            .update_one(
+                doc! { "pair": &funding_rate.pair, "fundingTime": funding_rate.funding_time.timestamp() },
+                doc! { "$set": document }
+            )
+            .upsert(true)
+            .await
+    }
+
+    /// Fetches the most recently settled funding rate of a pair.
+    pub async fn fetch_latest_funding_rate(&self, pair: &str) -> Result<Option<FundingRate>, mongodb::error::Error> {
+        self.funding_rate_collection
+            .find_one(doc! { "pair": pair })
+            .sort(doc! { "fundingTime": -1 })
+            .await
+    }
+
+    /// Fetches the funding rates of a pair with pagination, newest first.
+    pub async fn fetch_funding_rates(&self, pair: &str, page: u32, per_page: u32) -> Result<Vec<FundingRate>, mongodb::error::Error> {
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) is empty
+        let Some(skip) = calc_page_skip(page.max(1), per_page) else {
+            return Ok(Vec::new())
+        };
+
+        let mut cursor: Cursor<FundingRate> = self
+            .funding_rate_collection
+            .find(doc! { "pair": pair })
+            .sort(doc! { "fundingTime": -1 })
+            .skip(skip)
+            .limit(per_page as i64)
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Adds the funding payments of a closed trade to the funding ledger.
+    pub async fn add_funding_payments(&self, payments: &[FundingPayment]) -> Result<(), mongodb::error::Error> {
+        // inserting no documents is an error
+        if payments.is_empty() {
+            return Ok(());
+        }
+
+        retry_transient_write("add_funding_payments", || self.funding_payment_collection.insert_many(payments).into_future()).await.map(|_| ())
+    }
+
+    /// Fetches the funding payments in the funding ledger matching `filter` with pagination, newest first.
+    pub async fn fetch_funding_payments(&self, filter: Document, page: u32, per_page: u32) -> Result<Vec<FundingPayment>, mongodb::error::Error> {
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) is empty
+        let Some(skip) = calc_page_skip(page.max(1), per_page) else {
+            return Ok(Vec::new())
+        };
+
+        let cursor: Cursor<FundingPayment> = self
+            .funding_payment_collection
+            .find(filter)
+            .sort(doc! { "fundingTime": -1 })
+            .skip(skip)
+            .limit(per_page as i64)
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+
+    /// Fetches all funding rates of a pair settled after `since`, oldest first.
+    pub async fn fetch_funding_rates_since(&self, pair: &str, since: DateTime<Utc>) -> Result<Vec<FundingRate>, mongodb::error::Error> {
+        let mut cursor: Cursor<FundingRate> = self
+            .funding_rate_collection
+            .find(doc! { "pair": pair, "fundingTime": { "$gt": since.timestamp() } })
+            .sort(doc! { "fundingTime": 1 })
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+}
+
+impl FundingSchedule {
+    /// Initializes a funding schedule settling every `interval_hours` hours. The interval must divide a day evenly (e.g. 1, 4 or 8).
+    pub fn new(interval_hours: u32) -> Result<Self, String> {
+        if interval_hours == 0 || 24 % interval_hours != 0 {
+            return Err(format!("Funding interval of {} hours doesn't divide a day evenly", interval_hours));
+        }
+
+        Ok(Self { interval_hours })
+    }
+
+    /// Reads the funding schedule of `exchange` from the `FUNDING_INTERVAL_HOURS_<EXCHANGE>` variable returned by `lookup`,
+    /// falling back to the known interval of the exchange (see `EXCHANGE_FUNDING_INTERVAL_HOURS`), or `DEFAULT_FUNDING_INTERVAL_HOURS`.
+    pub fn from_lookup(exchange: &str, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let known_interval_hours = EXCHANGE_FUNDING_INTERVAL_HOURS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(exchange))
+            .map(|(_, interval_hours)| *interval_hours)
+            .unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS);
+        let known = Self { interval_hours: known_interval_hours };
+
+        let name = format!("FUNDING_INTERVAL_HOURS_{}", exchange.to_uppercase());
+
+        match lookup(&name) {
+            Some(value) => match value.trim().parse::<u32>().map_err(|err| err.to_string()).and_then(Self::new) {
+                Ok(schedule) => schedule,
+                Err(err) => {
+                    eprintln!("(FundingSchedule::from_lookup) Ignoring invalid {}: {}", name, err);
+                    known
+                }
+            },
+            None => known,
+        }
+    }
+
+    /// The time between two funding settlements.
+    pub fn interval(&self) -> Duration {
+        Duration::hours(self.interval_hours as i64)
+    }
+
+    /// The hours (in UTC) at which funding settles (e.g. 0, 8 and 16 for an 8 hour interval).
+    pub fn funding_hours(&self) -> Vec<u32> {
+        (0..24).step_by(self.interval_hours as usize).collect()
+    }
+
+    /// Returns the first funding time strictly after `timestamp` (e.g. 08:00 UTC for both 07:59 and 00:00 UTC with an 8 hour interval).
+    pub fn next_funding_time(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let midnight = timestamp.date_naive().and_time(NaiveTime::MIN).and_utc();
+        let elapsed_intervals = (timestamp - midnight).num_seconds() / self.interval().num_seconds();
+
+        // since the interval divides a day evenly, the last funding time of a day is followed by midnight of the next day
+        midnight + self.interval() * (elapsed_intervals as i32 + 1)
+    }
+
+    /// Returns the amount of funding times after `from` up until (and including) `to`.
+    #[allow(dead_code)]
+    pub fn count_funding_times(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> u32 {
+        self.count_open_funding_times(from, to, &MarketHours::default())
+    }
+
+    /// Returns the amount of funding times after `from` up until (and including) `to` at which the market of `market_hours` is open,
+    /// since funding isn't settled while a market is closed (e.g. over the weekend).
+    pub fn count_open_funding_times(&self, from: DateTime<Utc>, to: DateTime<Utc>, market_hours: &MarketHours) -> u32 {
+        self.open_funding_times(from, to, market_hours).len() as u32
+    }
+
+    /// Returns the funding times after `from` up until (and including) `to` at which the market of `market_hours` is open, oldest first.
+    pub fn open_funding_times(&self, from: DateTime<Utc>, to: DateTime<Utc>, market_hours: &MarketHours) -> Vec<DateTime<Utc>> {
+        let mut funding_times = Vec::new();
+        let mut funding_time = self.next_funding_time(from);
+
+        while funding_time <= to {
+            if market_hours.is_open(funding_time) {
+                funding_times.push(funding_time);
+            }
+
+            funding_time += self.interval();
+        }
+
+        funding_times
+    }
+}
+
+/// Fetches the funding rates of `pair` settled since the latest stored one from `exchange` and stores them.
+///
+/// Returns the amount of funding rates stored.
+async fn poll_funding_rates(
+    exchange: &dyn Exchange,
+    mongo_state: &MongoDBState,
+    pair: &str
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    // only fetch rates that haven't been stored yet
+    let since = mongo_state.fetch_latest_funding_rate(pair).await?.map(|latest| latest.funding_time);
+    let funding_rates = exchange.get_funding_rates(pair, since).await?;
+
+    for funding_rate in &funding_rates {
+        mongo_state.upsert_funding_rate(funding_rate).await?;
+    }
+
+    Ok(funding_rates.len())
+}
+
+/// Periodically polls the settled funding rates of all accepted USDT-quoted symbols from `exchange` (Binance) and stores them in the database.
+pub async fn start_funding_rate_poller(mongo_state: Arc<MongoDBState>, exchange: Arc<dyn Exchange>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(FUNDING_RATE_POLL_INTERVAL_SECS));
+
+    // only USDⓈ-M perpetuals are listed on Binance's futures API
+    let pairs: Vec<&str> = ACCEPTED_SYMBOLS
+        .iter()
+        .copied()
+        .filter(|pair| split_pair(pair).is_some_and(|(_, quote)| quote == "USDT"))
+        .collect();
+
+    loop {
+        interval.tick().await;
+
+        for pair in &pairs {
+            match poll_funding_rates(exchange.as_ref(), &mongo_state, pair).await {
+                Ok(0) => {}
+                Ok(stored) => println!("(start_funding_rate_poller) Stored {} new funding rates for {}", stored, pair),
+                Err(err) => eprintln!("(start_funding_rate_poller) Failed to poll funding rates for {}: {}", pair, err),
+            }
+        }
+    }
+}
+
+/// Returns the stored funding rate history of a pair, alongside the cumulative funding paid/received by each open trade on the pair.
+pub async fn get_funding_history(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(pair): Path<String>,
+    Query(query): Query<FundingQuery>,
+) -> (StatusCode, Json<ApiResponse<FundingHistory>>) {
+    let pair = pair.to_uppercase();
+
+    let rates = match mongo_state.fetch_funding_rates(&pair, query.page.unwrap_or(1), query.per_page.unwrap_or(MAX_PER_PAGE as u32)).await {
+        Ok(rates) => rates,
+        Err(err) => {
+            eprintln!("(get_funding_history) Failed to fetch funding rates: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_funding_history) Failed to fetch funding rates: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let open_trades: Vec<ActiveTrade> = {
+        let map = app_state.active_trades.lock().unwrap();
+        map.values()
+            .filter(|trade| trade.pair.eq_ignore_ascii_case(&pair))
+            .cloned()
+            .collect()
+    };
+
+    let mut open_trade_funding = Vec::new();
+
+    // only the rates settled since the oldest open trade was opened are required
+    if let Some(oldest_open_timestamp) = open_trades.iter().map(|trade| trade.open_timestamp).min() {
+        let funding_rates = match mongo_state.fetch_funding_rates_since(&pair, oldest_open_timestamp).await {
+            Ok(funding_rates) => funding_rates,
+            Err(err) => {
+                eprintln!("(get_funding_history) Failed to fetch funding rates of open trades: {}", err);
+
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse {
+                        status: "500 Internal Server Error",
+                        code: None,
+                        message: format!("(get_funding_history) Failed to fetch funding rates of open trades: {}", err),
+                        data: None
+                    })
+                )
+            }
+        };
+
+        for trade in open_trades {
+            open_trade_funding.push(OpenTradeFunding {
+                trade_id: trade.id,
+                settlements: funding_rates.iter().filter(|rate| rate.funding_time > trade.open_timestamp).count(),
+                cumulative_funding: calc_accrued_funding(&trade, &funding_rates),
+                alert_name: trade.alert_name,
+                direction: trade.direction,
+            });
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_funding_history) Fetched funding history successfully.".to_string(),
+            data: Some(FundingHistory { pair, rates, open_trades: open_trade_funding })
+        })
+    )
+}
+
+
+/// Builds the MongoDB filter of the funding payments matching `query`.
+///
+/// Returns an error if the trade ID isn't a valid `ObjectId`.
+pub fn funding_ledger_filter(query: &FundingLedgerQuery) -> Result<Document, String> {
+    let mut filter = Document::new();
+
+    if let Some(trade_id) = &query.trade_id {
+        let trade_id = ObjectId::parse_str(trade_id).map_err(|_| format!("Invalid trade ID: {}", trade_id))?;
+        filter.insert("tradeId", trade_id);
+    }
+
+    if let Some(exchange) = &query.exchange {
+        filter.insert("exchange", exchange.to_lowercase());
+    }
+
+    if let Some(pair) = &query.pair {
+        filter.insert("pair", pair.to_uppercase());
+    }
+
+    let mut funding_time = Document::new();
+
+    if let Some(from) = query.from {
+        funding_time.insert("$gte", from.timestamp());
+    }
+
+    if let Some(to) = query.to {
+        funding_time.insert("$lt", to.timestamp());
+    }
+
+    if !funding_time.is_empty() {
+        filter.insert("fundingTime", funding_time);
+    }
+
+    Ok(filter)
+}
+
+/// Returns the funding payments in the funding ledger, optionally filtered by trade, exchange account, pair and settlement time,
+/// so that the funding fees of closed trades can be audited line by line.
+pub async fn get_funding_ledger(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<FundingLedgerQuery>,
+) -> (StatusCode, Json<ApiResponse<FundingLedger>>) {
+    let filter = match funding_ledger_filter(&query) {
+        Ok(filter) => filter,
+        Err(err) => return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(get_funding_ledger) {}", err),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.fetch_funding_payments(filter, query.page.unwrap_or(1), query.per_page.unwrap_or(MAX_PER_PAGE as u32)).await {
+        Ok(payments) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_funding_ledger) Fetched funding ledger successfully.".to_string(),
+                data: Some(FundingLedger {
+                    total_amount: payments.iter().map(|payment| payment.amount).sum(),
+                    payments,
+                })
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_funding_ledger) Failed to fetch funding payments: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_funding_ledger) Failed to fetch funding payments: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,122 @@
+use std::str::FromStr;
+
+use mongodb::bson::{doc, Bson, Document};
+
+use crate::{constants::{BTC_USD_PRODUCT_ID, DEFAULT_REPORTING_CURRENCY, USDT_EUR_PRODUCT_ID, USDT_USD_PRODUCT_ID}, models::{AppState, CurrencyConversion, FxRates, ReportingCurrency}};
+
+impl FromStr for ReportingCurrency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "USDT" => Ok(ReportingCurrency::Usdt),
+            "USD" => Ok(ReportingCurrency::Usd),
+            "EUR" => Ok(ReportingCurrency::Eur),
+            "BTC" => Ok(ReportingCurrency::Btc),
+            other => Err(format!("Unsupported reporting currency: {}", other)),
+        }
+    }
+}
+
+impl ReportingCurrency {
+    /// Reads the configured reporting currency from the `REPORTING_CURRENCY` env variable.
+    ///
+    /// Falls back to `DEFAULT_REPORTING_CURRENCY` if the variable is unset or invalid.
+    pub fn from_env() -> Self {
+        match std::env::var("REPORTING_CURRENCY") {
+            Ok(value) => value.parse().unwrap_or_else(|err| {
+                eprintln!("(ReportingCurrency::from_env) {}. Using {:?} instead.", err, DEFAULT_REPORTING_CURRENCY);
+                DEFAULT_REPORTING_CURRENCY
+            }),
+            Err(_) => DEFAULT_REPORTING_CURRENCY,
+        }
+    }
+
+    /// The path of the stored `FxRates` field of a closed trade holding the rate of this currency.
+    ///
+    /// `None` for USDT, since trade values are already denominated in USDT.
+    pub fn rate_field(&self) -> Option<&'static str> {
+        match self {
+            ReportingCurrency::Usdt => None,
+            ReportingCurrency::Usd => Some("$fxRates.usd"),
+            ReportingCurrency::Eur => Some("$fxRates.eur"),
+            ReportingCurrency::Btc => Some("$fxRates.btc"),
+        }
+    }
+}
+
+impl FxRates {
+    /// Returns the value of 1 USDT in `currency`, if known.
+    pub fn rate(&self, currency: ReportingCurrency) -> Option<f64> {
+        match currency {
+            ReportingCurrency::Usdt => Some(1.0),
+            ReportingCurrency::Usd => self.usd,
+            ReportingCurrency::Eur => self.eur,
+            ReportingCurrency::Btc => self.btc,
+        }
+    }
+}
+
+impl AppState {
+    /// Snapshots the current conversion rates of 1 USDT into each reporting currency from the latest prices received by the price feed.
+    pub fn current_fx_rates(&self) -> FxRates {
+        let prices = self.latest_prices.lock().unwrap();
+
+        let usd = prices.get(USDT_USD_PRODUCT_ID).copied();
+        let eur = prices.get(USDT_EUR_PRODUCT_ID).copied();
+        // USDT -> BTC = (USDT -> USD) / (BTC -> USD)
+        let btc = match (usd, prices.get(BTC_USD_PRODUCT_ID)) {
+            (Some(usd), Some(btc_usd)) if *btc_usd > 0.0 => Some(usd / btc_usd),
+            _ => None,
+        };
+
+        FxRates { usd, eur, btc }
+    }
+
+    /// Returns the current value of 1 unit of `currency` (e.g. `BTC`) in USDT, derived from the latest prices received by the price feed.
+    ///
+    /// Non-fiat currencies are priced via their `<CURRENCY>-USD` product. Returns `None` if the required prices haven't been received yet.
+    pub fn usdt_value_of(&self, currency: &str) -> Option<f64> {
+        let currency = currency.to_uppercase();
+
+        if currency == "USDT" {
+            return Some(1.0);
+        }
+
+        let fx_rates = self.current_fx_rates();
+        // the value of 1 USDT in USD
+        let usdt_usd = fx_rates.usd.filter(|rate| *rate > 0.0)?;
+
+        match currency.as_str() {
+            "USD" | "USDC" => Some(1.0 / usdt_usd),
+            "EUR" => fx_rates.eur.filter(|rate| *rate > 0.0).map(|rate| 1.0 / rate),
+            _ => {
+                let prices = self.latest_prices.lock().unwrap();
+
+                prices.get(&format!("{}-USD", currency)).map(|usd_price| usd_price / usdt_usd)
+            }
+        }
+    }
+}
+
+/// Builds an `$addFields` stage converting the monetary fields of closed trades (PnL and fees) into the reporting currency.
+///
+/// Values are first converted from the trade's settlement currency into USDT, then into the reporting currency.
+/// Trades closed without a stored reporting currency rate are converted with the current fallback rate.
+pub fn currency_conversion_stage(conversion: &CurrencyConversion) -> Document {
+    let settlement_rate = doc! { "$ifNull": ["$settlementUsdtRate", 1.0] };
+    let reporting_rate = match conversion.currency.rate_field() {
+        Some(rate_field) => Bson::Document(doc! { "$ifNull": [rate_field, conversion.fallback_rate] }),
+        // trade values are already denominated in USDT
+        None => Bson::Double(1.0),
+    };
+    let rate = doc! { "$multiply": [settlement_rate, reporting_rate] };
+
+    doc! {
+        "$addFields": {
+            "pnl": { "$multiply": ["$pnl", rate.clone()] },
+            "executionFees": { "$multiply": ["$executionFees", rate.clone()] },
+            "fundingFees": { "$multiply": ["$fundingFees", rate] },
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::{net::SocketAddr, sync::{atomic::Ordering, Arc}};
+
+use mongodb::bson::{doc, oid::ObjectId};
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::{api::{live::close_active_trade, stats::resolve_timezone}, models::{AppState, AuditAction, AuditActor, ControlPlaneService, CurrencyConversion, ReportingCurrency, TradeDirection}};
+
+use self::proto::{control_plane_server::{ControlPlane, ControlPlaneServer}, ActiveTrade, CloseTradeRequest, CloseTradeResponse, GetStatsRequest, GetStatsResponse, ListActiveTradesRequest, ListActiveTradesResponse, SetPausedRequest, SetPausedResponse};
+
+/// The types and service trait generated from `proto/control.proto`.
+pub mod proto {
+    tonic::include_proto!("tvbot.control.v1");
+}
+
+impl From<&crate::models::ActiveTrade> for ActiveTrade {
+    fn from(trade: &crate::models::ActiveTrade) -> Self {
+        Self {
+            id: trade.id.to_hex(),
+            alert_name: trade.alert_name.clone(),
+            pair: trade.pair.clone(),
+            direction: match trade.direction {
+                TradeDirection::Long => "long".to_string(),
+                TradeDirection::Short => "short".to_string(),
+            },
+            open_timestamp: trade.open_timestamp.timestamp(),
+            quantity: trade.quantity,
+            entry_price: trade.entry_price,
+            leverage: trade.leverage.into(),
+            liquidation_price: trade.liquidation_price,
+            take_profit: trade.take_profit,
+            stop_loss: trade.stop_loss,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ControlPlane for ControlPlaneService {
+    async fn list_active_trades(&self, request: Request<ListActiveTradesRequest>) -> Result<Response<ListActiveTradesResponse>, Status> {
+        let alert_name = request.into_inner().alert_name;
+
+        let mut trades: Vec<ActiveTrade> = self.app_state.active_trades
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|trade| alert_name.as_ref().is_none_or(|alert_name| &trade.alert_name == alert_name))
+            .map(ActiveTrade::from)
+            .collect();
+
+        trades.sort_by_key(|trade| trade.open_timestamp);
+
+        Ok(Response::new(ListActiveTradesResponse { trades }))
+    }
+
+    async fn close_trade(&self, request: Request<CloseTradeRequest>) -> Result<Response<CloseTradeResponse>, Status> {
+        let id = request.into_inner().id;
+        let trade_id = ObjectId::parse_str(&id).map_err(|_| Status::invalid_argument(format!("Invalid trade ID {}.", id)))?;
+
+        let trade = self.app_state.active_trades.lock().unwrap().get(&trade_id).cloned();
+        let Some(trade) = trade else {
+            return Err(Status::not_found(format!("Trade {} is not active.", id)))
+        };
+
+        let exit_price = self.app_state.current_price(&trade.pair).await.map_err(Status::unavailable)?;
+
+        match close_active_trade(&self.app_state, &trade_id, exit_price).await {
+            Ok(Some(closed_trade)) => Ok(Response::new(CloseTradeResponse {
+                id,
+                pair: closed_trade.pair,
+                exit_price: closed_trade.exit_price,
+                pnl: closed_trade.pnl,
+                settlement_currency: closed_trade.settlement_currency,
+                roe: closed_trade.roe,
+            })),
+            Ok(None) => Err(Status::not_found(format!("Trade {} is not active.", id))),
+            Err(err) => Err(Status::internal(format!("Failed to close trade {}: {}", id, err))),
+        }
+    }
+
+    async fn set_paused(&self, request: Request<SetPausedRequest>) -> Result<Response<SetPausedResponse>, Status> {
+        let paused = request.into_inner().paused;
+        let was_paused = self.app_state.paused.swap(paused, Ordering::SeqCst);
+
+        if was_paused != paused {
+            let action = if paused { AuditAction::Disabled } else { AuditAction::Enabled };
+            self.app_state.mongo_state.record_audit(AuditActor::Api, action, "alerts", Some("Sent via gRPC".to_string()), None, self.app_state.clock.now()).await;
+        }
+
+        Ok(Response::new(SetPausedResponse { was_paused, paused }))
+    }
+
+    async fn get_stats(&self, request: Request<GetStatsRequest>) -> Result<Response<GetStatsResponse>, Status> {
+        let request = request.into_inner();
+        let mut filter = doc! {};
+
+        if let Some(alert_name) = request.alert_name {
+            filter.insert("alertName", alert_name);
+        }
+
+        if let Some(pair) = request.pair {
+            filter.insert("pair", pair.to_uppercase());
+        }
+
+        // all values are reported in USDT, so that no conversion rate is required
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+        let stats = self.app_state.mongo_state
+            .aggregate_stats_overview(filter, &conversion, resolve_timezone(None), self.app_state.clock.now())
+            .await
+            .map_err(|err| Status::internal(format!("Failed to aggregate stats: {}", err)))?
+            .lifetime;
+
+        Ok(Response::new(GetStatsResponse {
+            total_trades: stats.total_trades,
+            wins: stats.wins,
+            losses: stats.losses,
+            win_rate: stats.win_rate,
+            total_pnl: stats.total_pnl,
+            average_roe: stats.average_roe,
+            profit_factor: stats.profit_factor,
+        }))
+    }
+}
+
+/// Authenticates a gRPC request via its `authorization: Bearer <secret>` metadata.
+///
+/// All requests are rejected if `secret` is empty.
+// the signature is dictated by tonic's interceptors
+#[allow(clippy::result_large_err)]
+pub fn check_grpc_secret(request: Request<()>, secret: &str) -> Result<Request<()>, Status> {
+    let token = request.metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match token {
+        Some(token) if !secret.is_empty() && token == secret => Ok(request),
+        _ => Err(Status::unauthenticated("Missing or invalid secret.")),
+    }
+}
+
+/// Serves the gRPC control-plane API on the `GRPC_PORT` env variable, alongside the REST API.
+/// Requests are authenticated with the command secret (`COMMAND_SECRET` env variable).
+///
+/// The API is disabled if `GRPC_PORT` isn't set.
+#[allow(clippy::result_large_err)]
+pub async fn start_grpc_server(app_state: Arc<AppState>) {
+    let Some(port) = std::env::var("GRPC_PORT").ok().and_then(|port| port.parse::<u16>().ok()) else {
+        return
+    };
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let secret = app_state.command_secret.clone().unwrap_or_default();
+    let service = ControlPlaneServer::with_interceptor(ControlPlaneService { app_state }, move |request| check_grpc_secret(request, &secret));
+
+    println!("gRPC control plane running on: {}", addr);
+
+    if let Err(err) = Server::builder().add_service(service).serve(addr).await {
+        eprintln!("(start_grpc_server) gRPC server stopped: {}", err);
+    }
+}
@@ -0,0 +1,56 @@
+use std::{sync::Arc, time::Instant};
+
+use axum::{Extension, Json};
+use hyper::StatusCode;
+use mongodb::bson::doc;
+
+use crate::models::{ApiResponse, MongoDBState, MongoHealth, MongoPoolMetrics};
+
+impl MongoDBState {
+    /// Pings the database, returning the round trip time in milliseconds.
+    pub async fn ping(&self) -> Result<u64, mongodb::error::Error> {
+        let started = Instant::now();
+
+        self.active_trade_collection
+            .client()
+            .database("admin")
+            .run_command(doc! { "ping": 1 })
+            .await?;
+
+        Ok(started.elapsed().as_millis() as u64)
+    }
+}
+
+/// Returns whether the database is reachable, alongside the counters of the connection pool,
+/// to diagnose stalls (e.g. exhausted connections during webhook bursts).
+pub async fn get_mongo_health(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(pool_metrics): Extension<Arc<MongoPoolMetrics>>,
+) -> (StatusCode, Json<ApiResponse<MongoHealth>>) {
+    let ping = mongo_state.ping().await;
+
+    if let Err(err) = &ping {
+        eprintln!("(get_mongo_health) Failed to ping the database: {}", err);
+    }
+
+    let health = MongoHealth {
+        reachable: ping.is_ok(),
+        ping_ms: ping.as_ref().ok().copied(),
+        pool: pool_metrics.stats(),
+    };
+
+    let (status_code, status, message) = match &ping {
+        Ok(_) => (StatusCode::OK, "200 OK", "(get_mongo_health) The database is reachable.".to_string()),
+        Err(err) => (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable", format!("(get_mongo_health) Failed to ping the database: {}", err)),
+    };
+
+    (
+        status_code,
+        Json(ApiResponse {
+            status,
+            code: None,
+            message,
+            data: Some(health)
+        })
+    )
+}
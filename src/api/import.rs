@@ -0,0 +1,359 @@
+use std::{collections::{HashMap, HashSet}, sync::Arc};
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{doc, oid::ObjectId, Bson};
+use ring::digest::{digest, SHA256};
+
+use crate::{api::{calc_roe, get_settlement_currency}, constants::IMPORTED_ALERT_NAME, models::{ApiResponse, ClosedTrade, ContractType, FxRates, ImportQuery, ImportSummary, MongoDBState, TradeDirection, TradeHistoryFormat, TradeKind, TradeLeverage}};
+
+/// The date formats used by the supported exchange exports.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%y-%m-%d %H:%M:%S", "%Y/%m/%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"];
+
+impl MongoDBState {
+    /// Returns which of the provided import keys were already imported.
+    pub async fn fetch_existing_import_keys(&self, keys: &[String]) -> Result<HashSet<String>, mongodb::error::Error> {
+        let existing = self.closed_trade_collection.distinct("importKey", doc! { "importKey": { "$in": keys } }).await?;
+
+        Ok(existing.into_iter().filter_map(|key| match key {
+            Bson::String(key) => Some(key),
+            _ => None,
+        }).collect())
+    }
+}
+
+/// A single row of a CSV export, with its cells keyed by the (case-insensitive) column name.
+struct CsvRow {
+    cells: HashMap<String, String>,
+    /// the line of the row in the file, used in error messages.
+    line: u64,
+    /// a hash of the format and the row's contents, used to skip rows that were already imported.
+    import_key: String,
+}
+
+impl CsvRow {
+    fn get(&self, column: &str) -> Result<&str, String> {
+        self.cells
+            .get(&column.to_lowercase())
+            .map(String::as_str)
+            .ok_or_else(|| format!("line {}: missing column `{}`", self.line, column))
+    }
+
+    /// Parses a numeric cell, ignoring thousands separators and a trailing unit (e.g. `0.0123 USDT`).
+    fn number(&self, column: &str) -> Result<f64, String> {
+        let value = self.get(column)?;
+
+        value
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .replace(',', "")
+            .parse()
+            .map_err(|_| format!("line {}: invalid number `{}` in column `{}`", self.line, value, column))
+    }
+
+    fn timestamp(&self, column: &str) -> Result<DateTime<Utc>, String> {
+        let value = self.get(column)?.trim();
+
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+            return Ok(timestamp.with_timezone(&Utc))
+        }
+
+        DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .map(|timestamp| timestamp.and_utc())
+            .ok_or_else(|| format!("line {}: invalid date `{}` in column `{}`", self.line, value, column))
+    }
+}
+
+/// Reads the rows of a CSV export.
+fn read_csv_rows(format: TradeHistoryFormat, csv: &str) -> Result<Vec<CsvRow>, String> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).flexible(true).from_reader(csv.as_bytes());
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|err| format!("invalid header row: {}", err))?
+        .iter()
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut rows = Vec::new();
+
+    for record in reader.records() {
+        let record = record.map_err(|err| format!("invalid row: {}", err))?;
+
+        // skip blank lines and footers
+        if record.iter().all(str::is_empty) {
+            continue
+        }
+
+        let hash = digest(&SHA256, format!("{:?}:{}", format, record.iter().collect::<Vec<_>>().join(",")).as_bytes());
+
+        rows.push(CsvRow {
+            cells: headers.iter().cloned().zip(record.iter().map(str::to_string)).collect(),
+            line: record.position().map(|position| position.line()).unwrap_or_default(),
+            import_key: hash.as_ref().iter().map(|byte| format!("{:02x}", byte)).collect(),
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Builds an imported closed trade. Imported trades are filed as live 1x linear trades, since the exports don't include the leverage.
+fn build_imported_trade(
+    row: &CsvRow,
+    alert_name: &str,
+    (pair, direction): (String, TradeDirection),
+    quantity: f64,
+    (entry_price, exit_price): (f64, f64),
+    (open_timestamp, close_timestamp): (DateTime<Utc>, DateTime<Utc>),
+    (pnl, execution_fees): (f64, f64),
+) -> Result<ClosedTrade, String> {
+    let settlement_currency = get_settlement_currency(&pair, &ContractType::Linear)
+        .ok_or_else(|| format!("line {}: unsupported pair `{}`", row.line, pair))?;
+
+    Ok(ClosedTrade {
+        id: ObjectId::new(),
+        alert_name: alert_name.to_string(),
+        direction,
+        kind: TradeKind::Live,
+        quantity,
+        entry_price,
+        exit_price,
+        leverage: TradeLeverage::One,
+        contract_type: ContractType::Linear,
+        liquidation_price: 0.0,
+        take_profit: None,
+        stop_loss: None,
+        open_timestamp,
+        close_timestamp,
+        pnl,
+        roe: calc_roe(pnl, entry_price, quantity, 1.0, &ContractType::Linear),
+        execution_fees,
+        funding_fees: 0.0,
+        // the rates at the time of closing aren't known, so the current rates are used when converting
+        fx_rates: FxRates::default(),
+        settlement_usdt_rate: if settlement_currency == "USDT" { Some(1.0) } else { None },
+        settlement_currency,
+        near_maintenance: false,
+        experiment: None,
+        originating_request_id: None,
+        import_key: Some(row.import_key.clone()),
+        liquidated: false,
+        liquidation_fee: 0.0,
+        partial_liquidations: Vec::new(),
+        regime: None,
+        entry_order_id: None,
+        exit_order_id: None,
+        pair,
+    })
+}
+
+/// Maps a Binance trade history export into closed trades.
+///
+/// Each fill realizing a profit or loss closes (part of) a position and is imported as a trade. Its entry price is derived from
+/// the realized PnL, while its open timestamp and fees include the opening fills since the previous close on the same side.
+fn parse_binance_rows(rows: &[CsvRow], alert_name: &str, summary: &mut ImportSummary) -> Vec<ClosedTrade> {
+    let mut fills = Vec::new();
+
+    for row in rows {
+        let fill = (|| Ok::<_, String>((
+            row.timestamp("Date(UTC)")?,
+            row.get("Symbol")?.to_uppercase(),
+            row.get("Side")?.to_uppercase(),
+            row.number("Price")?,
+            row.number("Quantity")?,
+            row.number("Fee")?,
+            row.number("Realized Profit")?,
+        )))();
+
+        match fill {
+            Ok(fill) => fills.push((row, fill)),
+            Err(err) => summary.errors.push(err),
+        }
+    }
+
+    // exports are ordered newest first
+    fills.sort_by_key(|(row, (timestamp, ..))| (*timestamp, row.line));
+
+    // the first open timestamp and the accumulated fees of the opening fills of each open position
+    let mut open_positions: HashMap<(String, bool), (DateTime<Utc>, f64)> = HashMap::new();
+    let mut trades = Vec::new();
+
+    for (row, (timestamp, symbol, side, price, quantity, fee, realized_pnl)) in fills {
+        let is_buy = side == "BUY";
+
+        if realized_pnl == 0.0 {
+            let (_, fees) = open_positions.entry((symbol, is_buy)).or_insert((timestamp, 0.0));
+            *fees += fee.abs();
+            summary.skipped += 1;
+            continue
+        }
+
+        if quantity <= 0.0 {
+            summary.errors.push(format!("line {}: invalid quantity {}", row.line, quantity));
+            continue
+        }
+
+        // selling closes a long, buying closes a short
+        let (direction, entry_price) = if is_buy {
+            (TradeDirection::Short, price + realized_pnl / quantity)
+        } else {
+            (TradeDirection::Long, price - realized_pnl / quantity)
+        };
+
+        let (open_timestamp, opening_fees) = open_positions.remove(&(symbol.clone(), !is_buy)).unwrap_or((timestamp, 0.0));
+        let execution_fees = opening_fees + fee.abs();
+
+        match build_imported_trade(
+            row,
+            alert_name,
+            (symbol, direction),
+            quantity,
+            (entry_price, price),
+            (open_timestamp, timestamp),
+            (realized_pnl - execution_fees, execution_fees)
+        ) {
+            Ok(trade) => trades.push(trade),
+            Err(err) => summary.errors.push(err),
+        }
+    }
+
+    trades
+}
+
+/// Maps a Bybit closed P&L export into closed trades. The export doesn't include the open timestamp or fees separately,
+/// so trades are opened at their close timestamp and their PnL (already net of fees) is used as is.
+fn parse_bybit_rows(rows: &[CsvRow], alert_name: &str, summary: &mut ImportSummary) -> Vec<ClosedTrade> {
+    let mut trades = Vec::new();
+
+    for row in rows {
+        let trade = (|| {
+            let closing_direction = row.get("Closing Direction")?.to_lowercase();
+
+            let direction = if closing_direction.contains("long") || closing_direction == "sell" {
+                TradeDirection::Long
+            } else if closing_direction.contains("short") || closing_direction == "buy" {
+                TradeDirection::Short
+            } else {
+                return Err(format!("line {}: invalid closing direction `{}`", row.line, closing_direction))
+            };
+
+            let close_timestamp = row.timestamp("Trade Time(UTC+0)")?;
+
+            build_imported_trade(
+                row,
+                alert_name,
+                (row.get("Contracts")?.to_uppercase(), direction),
+                row.number("Qty")?,
+                (row.number("Entry Price")?, row.number("Exit Price")?),
+                (close_timestamp, close_timestamp),
+                (row.number("Closed P&L")?, 0.0)
+            )
+        })();
+
+        match trade {
+            Ok(trade) => trades.push(trade),
+            Err(err) => summary.errors.push(err),
+        }
+    }
+
+    trades
+}
+
+/// Maps a CSV export of an exchange's trade history into closed trades, alongside a summary of skipped and invalid rows.
+pub fn parse_trade_history(format: TradeHistoryFormat, csv: &str, alert_name: &str) -> Result<(Vec<ClosedTrade>, ImportSummary), String> {
+    let rows = read_csv_rows(format, csv)?;
+    let mut summary = ImportSummary::default();
+
+    let trades = match format {
+        TradeHistoryFormat::Binance => parse_binance_rows(&rows, alert_name, &mut summary),
+        TradeHistoryFormat::Bybit => parse_bybit_rows(&rows, alert_name, &mut summary),
+    };
+
+    Ok((trades, summary))
+}
+
+/// Imports the closed trades of a trade history CSV export (sent as the request body), so that they're included in the stats
+/// and reports. Rows that were already imported are skipped.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn import_trades(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> (StatusCode, Json<ApiResponse<ImportSummary>>) {
+    let alert_name = query.alert_name.unwrap_or_else(|| IMPORTED_ALERT_NAME.to_string());
+
+    let (trades, mut summary) = match parse_trade_history(query.exchange, &body, &alert_name) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    status: "400 Bad Request",
+                    code: None,
+                    message: format!("(import_trades) Failed to read CSV: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let keys: Vec<String> = trades.iter().filter_map(|trade| trade.import_key.clone()).collect();
+
+    let mut seen = match mongo_state.fetch_existing_import_keys(&keys).await {
+        Ok(existing) => existing,
+        Err(err) => {
+            eprintln!("(import_trades) Failed to fetch imported trades: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(import_trades) Failed to fetch imported trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    // skip rows that were imported before, or that are repeated within the file
+    let new_trades: Vec<ClosedTrade> = trades
+        .into_iter()
+        .filter(|trade| trade.import_key.as_ref().is_some_and(|key| seen.insert(key.clone())))
+        .collect();
+
+    summary.duplicates = keys.len() - new_trades.len();
+
+    if !new_trades.is_empty() {
+        if let Err(err) = mongo_state.closed_trade_collection.insert_many(&new_trades).await {
+            eprintln!("(import_trades) Failed to import trades: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(import_trades) Failed to import trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+
+    summary.imported = new_trades.len();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: format!("(import_trades) Imported {} trades.", summary.imported),
+            data: Some(summary)
+        })
+    )
+}
@@ -0,0 +1,189 @@
+use std::{sync::{atomic::Ordering, Arc}, time::Duration as StdDuration};
+
+use axum::{Extension, Json};
+use chrono::{Duration, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::doc, options::ReturnDocument};
+
+use crate::{configs::is_duplicate_key_error, constants::{LEADER_LEASE_ID, LEADER_LEASE_RENEW_INTERVAL_SECS, LEADER_LEASE_TTL_SECS}, models::{ApiResponse, AppState, LeaderStatus, Leadership, MongoDBState, Notification, NotificationSeverity}};
+
+/// Operations on the leader lease in the database.
+impl MongoDBState {
+    /// Renews the leader lease if held by `instance_id`, or takes it over if it expired (or doesn't exist yet).
+    ///
+    /// Returns the fencing token of the lease if `instance_id` holds it afterwards.
+    pub async fn try_acquire_leader_lease(&self, instance_id: &str) -> Result<Option<i64>, mongodb::error::Error> {
+        // leases are shared between replicas, so they're timed with the system clock rather than `AppState::clock`
+        let now = Utc::now();
+        let expires_at = (now + Duration::seconds(LEADER_LEASE_TTL_SECS)).timestamp();
+
+        let renewed = self.leader_lease_collection
+            .find_one_and_update(
+                doc! { "_id": LEADER_LEASE_ID, "holder": instance_id },
+                doc! { "$set": { "expiresAt": expires_at } }
+            )
+            .return_document(ReturnDocument::After)
+            .await?;
+
+        if let Some(lease) = renewed {
+            return Ok(Some(lease.fencing_token));
+        }
+
+        // the lease is held by another instance (or doesn't exist), so try to take it over once it expired.
+        // if it's still valid, the upsert collides with the existing `_id` instead.
+        let taken_over = self.leader_lease_collection
+            .find_one_and_update(
+                doc! { "_id": LEADER_LEASE_ID, "expiresAt": { "$lt": now.timestamp() } },
+                doc! {
+                    "$set": { "holder": instance_id, "expiresAt": expires_at },
+                    "$inc": { "fencingToken": 1_i64 },
+                }
+            )
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .await;
+
+        match taken_over {
+            Ok(lease) => Ok(lease.map(|lease| lease.fencing_token)),
+            Err(err) if is_duplicate_key_error(&err) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Checks whether `instance_id` still holds an unexpired leader lease with `fencing_token`.
+    pub async fn verify_leader_lease(&self, instance_id: &str, fencing_token: i64) -> Result<bool, mongodb::error::Error> {
+        let lease = self.leader_lease_collection
+            .find_one(doc! {
+                "_id": LEADER_LEASE_ID,
+                "holder": instance_id,
+                "fencingToken": fencing_token,
+                "expiresAt": { "$gte": Utc::now().timestamp() },
+            })
+            .await?;
+
+        Ok(lease.is_some())
+    }
+}
+
+impl Leadership {
+    /// Reads the leadership configuration from the `HA_MODE` env variable.
+    ///
+    /// Leader election is disabled unless `HA_MODE` is `true`.
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("HA_MODE").is_ok_and(|value| value.eq_ignore_ascii_case("true"));
+
+        Self {
+            enabled,
+            // without leader election, this instance always leads
+            is_leader: (!enabled).into(),
+            fencing_token: 0.into(),
+        }
+    }
+
+    /// Whether this instance currently believes it's the leader.
+    pub fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::SeqCst)
+    }
+}
+
+impl AppState {
+    /// Checks whether this instance may execute (i.e. whether it's the leader), verifying the fencing token
+    /// against the database so that a stale leader never executes alongside the new one.
+    pub async fn can_execute(&self) -> bool {
+        if !self.leadership.enabled {
+            return true;
+        }
+
+        if !self.leadership.is_leader() {
+            return false;
+        }
+
+        let fencing_token = self.leadership.fencing_token.load(Ordering::SeqCst);
+
+        match self.mongo_state.verify_leader_lease(&self.instance_id, fencing_token).await {
+            Ok(valid) => valid,
+            Err(err) => {
+                eprintln!("(can_execute) Failed to verify leader lease: {}", err);
+                false
+            }
+        }
+    }
+}
+
+/// Periodically renews (or tries to acquire) the leader lease, updating the leadership state of this instance.
+///
+/// Does nothing if leader election is disabled.
+pub async fn start_leader_election(app_state: Arc<AppState>) {
+    if !app_state.leadership.enabled {
+        return;
+    }
+
+    let leadership = &app_state.leadership;
+    let mut interval = tokio::time::interval(StdDuration::from_secs(LEADER_LEASE_RENEW_INTERVAL_SECS));
+
+    println!("(start_leader_election) Running as instance {}", app_state.instance_id);
+
+    loop {
+        interval.tick().await;
+
+        let fencing_token = match app_state.mongo_state.try_acquire_leader_lease(&app_state.instance_id).await {
+            Ok(fencing_token) => fencing_token,
+            Err(err) => {
+                // step down if the lease can't be renewed, since another instance may take over once it expires
+                eprintln!("(start_leader_election) Failed to renew leader lease: {}", err);
+                None
+            }
+        };
+
+        if let Some(fencing_token) = fencing_token {
+            leadership.fencing_token.store(fencing_token, Ordering::SeqCst);
+        }
+
+        let is_leader = fencing_token.is_some();
+        let was_leader = leadership.is_leader.swap(is_leader, Ordering::SeqCst);
+
+        if is_leader != was_leader {
+            let (title, message) = if is_leader {
+                ("Became leader", format!("Instance {} acquired the leader lease and now processes alerts and price triggers.", app_state.instance_id))
+            } else {
+                ("Lost leadership", format!("Instance {} lost the leader lease and stopped processing alerts and price triggers.", app_state.instance_id))
+            };
+
+            app_state.notifier.notify(Notification::new(NotificationSeverity::Warning, title, message));
+        }
+    }
+}
+
+/// Returns the leadership state of this instance. Responds with `503 Service Unavailable` unless this instance is the leader,
+/// so that it can be used as a load balancer health check to route alerts to the leader only.
+pub async fn get_leader_status(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<LeaderStatus>>) {
+    let status = LeaderStatus {
+        instance_id: app_state.instance_id.clone(),
+        enabled: app_state.leadership.enabled,
+        is_leader: app_state.leadership.is_leader(),
+    };
+
+    if status.is_leader {
+        (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_leader_status) This instance is the leader.".to_string(),
+                data: Some(status)
+            })
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                status: "503 Service Unavailable",
+                code: None,
+                message: "(get_leader_status) This instance is not the leader.".to_string(),
+                data: Some(status)
+            })
+        )
+    }
+}
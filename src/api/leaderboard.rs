@@ -0,0 +1,226 @@
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc, time::Duration as StdDuration};
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use hyper::StatusCode;
+use mongodb::bson::{doc, from_document, Document};
+
+use crate::{api::{fx::currency_conversion_stage, stats::resolve_timezone, stats_helpers::calc_max_drawdown}, constants::{DEFAULT_LEADERBOARD_LIMIT, LEADERBOARD_MIN_TRADES, LEADERBOARD_REFRESH_INTERVAL_SECS, MAX_PER_PAGE}, models::{ApiResponse, AppState, CurrencyConversion, Leaderboard, LeaderboardEntry, LeaderboardMetric, LeaderboardQuery, LeaderboardWindow, MongoDBState, RankedLeaderboard, ReportingCurrency, StrategyTradeSequence}};
+
+impl LeaderboardWindow {
+    /// All windows, each of which has its own precomputed leaderboard.
+    pub const ALL: [LeaderboardWindow; 4] = [LeaderboardWindow::Week, LeaderboardWindow::Month, LeaderboardWindow::Quarter, LeaderboardWindow::AllTime];
+
+    /// The length of the window in days. `None` for all time.
+    pub fn days(&self) -> Option<i64> {
+        match self {
+            LeaderboardWindow::Week => Some(7),
+            LeaderboardWindow::Month => Some(30),
+            LeaderboardWindow::Quarter => Some(90),
+            LeaderboardWindow::AllTime => None,
+        }
+    }
+}
+
+/// CRUD operations for leaderboards in the database.
+impl MongoDBState {
+    /// Groups the trades closed since `since` (or all of them) by strategy, each oldest first, with their PnL in USDT.
+    pub async fn aggregate_strategy_trade_sequences(&self, since: Option<DateTime<Utc>>) -> Result<Vec<StrategyTradeSequence>, mongodb::error::Error> {
+        let filter = since.map(|since| doc! { "closeTimestamp": { "$gte": since.timestamp() } }).unwrap_or_default();
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+        let pipeline: Vec<Document> = vec![
+            doc! { "$match": filter },
+            currency_conversion_stage(&conversion),
+            doc! { "$sort": { "closeTimestamp": 1 } },
+            doc! {
+                "$group": {
+                    "_id": "$alertName",
+                    "totalRoe": { "$sum": "$roe" },
+                    "trades": { "$push": { "closeTimestamp": "$closeTimestamp", "pnl": "$pnl" } },
+                }
+            },
+        ];
+
+        let mut cursor = self.closed_trade_collection
+            .aggregate(pipeline)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        let mut sequences = Vec::new();
+
+        while cursor.advance().await? {
+            sequences.push(from_document::<StrategyTradeSequence>(cursor.deserialize_current()?)?);
+        }
+
+        Ok(sequences)
+    }
+
+    /// Replaces the stored leaderboard of its window.
+    pub async fn save_leaderboard(&self, leaderboard: &Leaderboard) -> Result<(), mongodb::error::Error> {
+        self.leaderboard_collection
+            .replace_one(doc! { "_id": mongodb::bson::to_bson(&leaderboard.window)? }, leaderboard)
+            .upsert(true)
+            .await
+            .map(|_| ())
+    }
+
+    /// Fetches the stored leaderboard of `window`, if it was computed yet.
+    pub async fn fetch_leaderboard(&self, window: LeaderboardWindow) -> Result<Option<Leaderboard>, mongodb::error::Error> {
+        self.leaderboard_collection.find_one(doc! { "_id": mongodb::bson::to_bson(&window)? }).await
+    }
+}
+
+/// Calculates the leaderboard metrics of a strategy from its closed trades (oldest first).
+/// Trading days are delimited in `timezone`.
+pub fn calc_leaderboard_entry(sequence: &StrategyTradeSequence, timezone: Tz) -> LeaderboardEntry {
+    let pnls: Vec<f64> = sequence.trades.iter().map(|trade| trade.pnl).collect();
+    let total_pnl = pnls.iter().sum::<f64>();
+    let max_drawdown = calc_max_drawdown(&pnls);
+
+    let mut daily_pnl: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+
+    for trade in &sequence.trades {
+        *daily_pnl.entry(trade.close_timestamp.with_timezone(&timezone).date_naive()).or_default() += trade.pnl;
+    }
+
+    let profitable_days = daily_pnl.values().filter(|pnl| **pnl > 0.0).count();
+
+    LeaderboardEntry {
+        rank: 0,
+        alert_name: sequence.alert_name.clone(),
+        trades: sequence.trades.len(),
+        total_pnl,
+        total_roe: sequence.total_roe,
+        max_drawdown,
+        return_over_drawdown: (max_drawdown > 0.0).then(|| total_pnl / max_drawdown),
+        profitable_days_percentage: if daily_pnl.is_empty() { 0.0 } else { profitable_days as f64 / daily_pnl.len() as f64 * 100.0 },
+    }
+}
+
+/// The value of an entry that strategies are ranked by (higher is better).
+fn metric_value(entry: &LeaderboardEntry, metric: LeaderboardMetric) -> f64 {
+    match metric {
+        LeaderboardMetric::Roe => entry.total_roe,
+        // strategies that are in profit without ever drawing down rank above all others
+        LeaderboardMetric::RiskAdjustedReturn => entry.return_over_drawdown.unwrap_or(if entry.total_pnl > 0.0 { f64::MAX } else { entry.total_pnl }),
+        LeaderboardMetric::Consistency => entry.profitable_days_percentage,
+    }
+}
+
+/// Ranks the entries by `metric` (ties are broken by ROE) and keeps the best `limit` of them.
+pub fn rank_leaderboard(mut entries: Vec<LeaderboardEntry>, metric: LeaderboardMetric, limit: usize) -> Vec<LeaderboardEntry> {
+    entries.sort_by(|a, b| {
+        metric_value(b, metric)
+            .partial_cmp(&metric_value(a, metric))
+            .unwrap_or(Ordering::Equal)
+            .then(b.total_roe.partial_cmp(&a.total_roe).unwrap_or(Ordering::Equal))
+    });
+
+    entries.truncate(limit);
+
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.rank = index + 1;
+    }
+
+    entries
+}
+
+impl AppState {
+    /// Recomputes and stores the leaderboards of all windows. Strategies with fewer than `LEADERBOARD_MIN_TRADES` trades within a window aren't ranked.
+    pub async fn refresh_leaderboards(&self) {
+        let now = self.clock.now();
+        let timezone = resolve_timezone(None);
+
+        for window in LeaderboardWindow::ALL {
+            let since = window.days().map(|days| now - Duration::days(days));
+
+            let sequences = match self.mongo_state.aggregate_strategy_trade_sequences(since).await {
+                Ok(sequences) => sequences,
+                Err(err) => {
+                    eprintln!("(refresh_leaderboards) Failed to aggregate the trades of the {:?} leaderboard: {}", window, err);
+                    continue;
+                }
+            };
+
+            let entries: Vec<LeaderboardEntry> = sequences
+                .iter()
+                .filter(|sequence| sequence.trades.len() >= LEADERBOARD_MIN_TRADES)
+                .map(|sequence| calc_leaderboard_entry(sequence, timezone))
+                .collect();
+            let entries = rank_leaderboard(entries, LeaderboardMetric::default(), usize::MAX);
+
+            if let Err(err) = self.mongo_state.save_leaderboard(&Leaderboard { window, computed_timestamp: now, entries }).await {
+                eprintln!("(refresh_leaderboards) Failed to save the {:?} leaderboard: {}", window, err);
+            }
+        }
+    }
+}
+
+/// Periodically recomputes the leaderboards, so that `GET /leaderboard` doesn't aggregate all closed trades on every request.
+///
+/// When running multiple instances, only the leader computes them.
+pub async fn start_leaderboard_aggregator(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(LEADERBOARD_REFRESH_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        if app_state.leadership.is_leader() {
+            app_state.refresh_leaderboards().await;
+        }
+    }
+}
+
+/// Returns the strategies ranked by ROE, risk-adjusted return (`risk_adjusted_return`) or consistency (`sort_by` query parameter)
+/// over a `window` of `7d`, `30d`, `90d` or `all`.
+///
+/// The leaderboards are computed every `LEADERBOARD_REFRESH_INTERVAL_SECS`, so recently closed trades may not be reflected yet.
+pub async fn get_leaderboard(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<LeaderboardQuery>,
+) -> (StatusCode, Json<ApiResponse<RankedLeaderboard>>) {
+    let window = query.window.unwrap_or_default();
+    let sort_by = query.sort_by.unwrap_or_default();
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT).clamp(1, MAX_PER_PAGE as usize);
+
+    match mongo_state.fetch_leaderboard(window).await {
+        Ok(Some(leaderboard)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_leaderboard) Fetched leaderboard successfully.".to_string(),
+                data: Some(RankedLeaderboard {
+                    window,
+                    sort_by,
+                    computed_timestamp: leaderboard.computed_timestamp,
+                    entries: rank_leaderboard(leaderboard.entries, sort_by, limit),
+                })
+            })
+        ),
+        Ok(None) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                status: "503 Service Unavailable",
+                code: None,
+                message: "(get_leaderboard) The leaderboard hasn't been computed yet.".to_string(),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_leaderboard) Failed to fetch leaderboard: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_leaderboard) Failed to fetch leaderboard: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use std::sync::atomic::Ordering;
+
+use axum::Json;
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{doc, oid::ObjectId};
+use serde_json::Value;
+
+use crate::{api::{alert::reject_alert, risk::enforce_daily_loss_limit, trade::{build_closed_paper_trade, build_paper_trade, close_paper_trade, is_accepted_symbol, run_pre_trade_guards}, calc_liquidation_price, calc_notional_value, calc_pnl, calc_roe, split_pair}, constants::{LIVE_CLOSE_MAX_RETRY_DELAY_SECS, LIVE_CLOSE_RETRY_DELAY_SECS}, models::{tradingview::TradingViewAlert, ActiveTrade, ApiResponse, AppState, ClosedTrade, ContractType, Exchange, ExchangeProfile, LiveCloseAttempt, LiveCloseTracker, MongoDBState, Notification, NotificationSeverity, OpenLiveTradeError, OrderFill, OrderSide, RejectionReason, ResponseCode, StrategyParameters, TradeEventKind, TradeKind}};
+
+/// Whether `pair` can be traded live: only USDT-margined pairs are listed on Binance's USDⓈ-M futures.
+pub fn is_live_tradable(pair: &str) -> bool {
+    split_pair(pair).is_some_and(|(_, quote)| quote == "USDT")
+}
+
+/// How long to wait before retrying to close a live trade after `failures` closes in a row failed.
+pub fn calc_live_close_retry_delay(failures: u32) -> Duration {
+    let delay_secs = LIVE_CLOSE_RETRY_DELAY_SECS.saturating_mul(2_i64.saturating_pow(failures.saturating_sub(1)));
+
+    Duration::seconds(delay_secs.min(LIVE_CLOSE_MAX_RETRY_DELAY_SECS))
+}
+
+impl LiveCloseTracker {
+    /// Marks the close of a live trade as in flight, unless it already is or it's still backing off from a failed close.
+    /// Returns whether the caller should close the trade.
+    pub fn try_start(&self, trade_id: ObjectId, now: DateTime<Utc>) -> bool {
+        let mut attempts = self.attempts.lock().unwrap();
+        let attempt = attempts.entry(trade_id).or_insert(LiveCloseAttempt { in_flight: false, failures: 0, retry_at: now });
+
+        if attempt.in_flight || now < attempt.retry_at {
+            return false
+        }
+
+        attempt.in_flight = true;
+        true
+    }
+
+    /// Records the outcome of closing a live trade. A failed close is retried once its backoff passed.
+    pub fn finish(&self, trade_id: ObjectId, succeeded: bool, now: DateTime<Utc>) {
+        let mut attempts = self.attempts.lock().unwrap();
+
+        if succeeded {
+            attempts.remove(&trade_id);
+            return;
+        }
+
+        if let Some(attempt) = attempts.get_mut(&trade_id) {
+            attempt.in_flight = false;
+            attempt.failures += 1;
+            attempt.retry_at = now + calc_live_close_retry_delay(attempt.failures);
+        }
+    }
+}
+
+/// Builds the closed trade of a live trade exited by the order `fill`.
+///
+/// Unlike paper trades, the exit price and the execution fees are the ones actually reported by the exchange, and the trade
+/// is never liquidated locally (the exchange does so). Funding fees are still estimated.
+pub fn build_closed_live_trade(app_state: &AppState, trade: &ActiveTrade, fill: &OrderFill) -> ClosedTrade {
+    // the liquidation price is ignored, since the order was filled at the given price regardless
+    let mut closed_trade = build_closed_paper_trade(app_state, &ActiveTrade { liquidation_price: 0.0, ..trade.clone() }, fill.price);
+
+    let execution_fees = trade.entry_fees.unwrap_or(0.0) + fill.fees;
+    let pnl = calc_pnl(
+        trade.entry_price,
+        fill.price,
+        trade.quantity,
+        execution_fees,
+        closed_trade.funding_fees,
+        &trade.direction,
+        &trade.contract_type
+    );
+
+    closed_trade.liquidation_price = trade.liquidation_price;
+    closed_trade.execution_fees = execution_fees;
+    closed_trade.pnl = pnl;
+    closed_trade.roe = calc_roe(pnl, trade.entry_price, trade.quantity, trade.leverage.into(), &trade.contract_type);
+    closed_trade.exit_order_id = Some(fill.order_id.to_string());
+
+    closed_trade
+}
+
+/// Splits a live trade partially closed by the order `fill` into the closed part, recorded as a closed trade of its own, and the part
+/// that remains open. The entry fees are split in proportion to the quantities.
+pub fn split_partially_closed_live_trade(app_state: &AppState, trade: &ActiveTrade, fill: &OrderFill) -> (ClosedTrade, ActiveTrade) {
+    let closed_fraction = fill.quantity / trade.quantity;
+    let entry_fees = trade.entry_fees.unwrap_or(0.0);
+
+    let closed_part = ActiveTrade {
+        id: ObjectId::new(),
+        quantity: fill.quantity,
+        entry_fees: Some(entry_fees * closed_fraction),
+        ..trade.clone()
+    };
+
+    let remaining_part = ActiveTrade {
+        quantity: trade.quantity - fill.quantity,
+        entry_fees: Some(entry_fees * (1.0 - closed_fraction)),
+        ..trade.clone()
+    };
+
+    (build_closed_live_trade(app_state, &closed_part, fill), remaining_part)
+}
+
+/// Closes an active live trade with a reduce-only market order on the live exchange, then moves it to the closed trades.
+///
+/// Returns the closed trade, or `None` if the trade isn't active (e.g. it was already closed). If the exchange rejects the order,
+/// the trade stays active so that closing it can be retried, unless the account is already flat on the pair (e.g. the exchange
+/// liquidated the position), in which case the trade is closed at the last price. If the order is only partially filled, the filled
+/// part is closed and the rest stays active.
+pub async fn close_live_trade(app_state: &AppState, trade_id: &ObjectId) -> Result<Option<ClosedTrade>, String> {
+    let Some(exchange) = &app_state.live_exchange else {
+        return Err("Live trading is not configured (BINANCE_API_KEY and BINANCE_API_SECRET).".to_string())
+    };
+
+    // remove from in-memory so we don't close it twice
+    let trade = {
+        let mut map = app_state.active_trades.lock().unwrap();
+        map.remove(trade_id)
+    };
+
+    let Some(trade) = trade else {
+        return Ok(None)
+    };
+
+    let fill = match exchange.close_position(&trade).await {
+        Ok(fill) => fill,
+        Err(err) => {
+            // a reduce-only order is rejected if there's no position left to reduce, in which case retrying would never succeed
+            if exchange.get_position(&trade.pair).await.is_ok_and(|position| position == 0.0) {
+                return close_flat_live_trade(app_state, exchange.as_ref(), trade, &err).await.map(Some)
+            }
+
+            // put the trade back, so that closing it can be retried
+            app_state.active_trades.lock().unwrap().insert(trade.id, trade);
+
+            return Err(err)
+        }
+    };
+
+    if fill.quantity < trade.quantity * (1.0 - 1e-9) {
+        return close_partially_filled_live_trade(app_state, trade, &fill).await
+    }
+
+    let closed_trade = build_closed_live_trade(app_state, &trade, &fill);
+
+    let result = async {
+        app_state.mongo_state.add_closed_trade(closed_trade.clone()).await?;
+        app_state.mongo_state.delete_active_trade(trade.id).await
+    }.await;
+
+    // the position is already closed on the exchange, so the trade isn't put back
+    if let Err(err) = result {
+        app_state.notifier.notify(Notification::new(
+            NotificationSeverity::Critical,
+            "Live trade closed but not recorded",
+            format!("Trade {} of {} on {} was closed on {} (order {}) but couldn't be recorded: {}", trade.id, trade.alert_name, trade.pair, exchange.name(), fill.order_id, err)
+        ));
+
+        return Err(format!("Failed to record closed trade {}: {}", trade.id, err))
+    }
+
+    println!("Live trade {} closed at price {}", trade_id, fill.price);
+
+    app_state.mqtt.publish_trade_event(TradeEventKind::Closed, &closed_trade.alert_name, &closed_trade);
+
+    enforce_daily_loss_limit(app_state).await;
+
+    Ok(Some(closed_trade))
+}
+
+/// Records the part of a live trade closed by a partially filled order, and keeps the rest active so that closing it can be retried.
+async fn close_partially_filled_live_trade(app_state: &AppState, trade: ActiveTrade, fill: &OrderFill) -> Result<Option<ClosedTrade>, String> {
+    let (closed_trade, remaining_trade) = split_partially_closed_live_trade(app_state, &trade, fill);
+
+    let result = async {
+        app_state.mongo_state.add_closed_trade(closed_trade.clone()).await?;
+        app_state.mongo_state.update_active_trade(trade.id, doc! {
+            "$set": { "quantity": remaining_trade.quantity, "entryFees": remaining_trade.entry_fees }
+        }).await
+    }.await;
+
+    if let Err(err) = result {
+        app_state.notifier.notify(Notification::new(
+            NotificationSeverity::Critical,
+            "Live trade partially closed but not recorded",
+            format!("Order {} closed {} of {} of trade {} on {}, but it couldn't be recorded: {}", fill.order_id, fill.quantity, trade.quantity, trade.id, trade.pair, err)
+        ));
+    } else {
+        app_state.mqtt.publish_trade_event(TradeEventKind::Closed, &closed_trade.alert_name, &closed_trade);
+    }
+
+    let remaining_quantity = remaining_trade.quantity;
+    app_state.active_trades.lock().unwrap().insert(remaining_trade.id, remaining_trade);
+
+    Err(format!("Order {} only closed {} of trade {}; {} remains open.", fill.order_id, fill.quantity, trade.id, remaining_quantity))
+}
+
+/// Closes a live trade whose position is no longer open on `exchange` (e.g. it was liquidated, or closed manually on the exchange),
+/// at the last price of the pair, since its actual exit isn't known.
+async fn close_flat_live_trade(app_state: &AppState, exchange: &dyn Exchange, trade: ActiveTrade, close_error: &str) -> Result<ClosedTrade, String> {
+    let exit_price = match exchange.get_ticker(&trade.pair).await {
+        Ok(price) => price,
+        Err(err) => {
+            app_state.active_trades.lock().unwrap().insert(trade.id, trade);
+            return Err(format!("{}; the position is already flat, but the last price couldn't be fetched: {}", close_error, err))
+        }
+    };
+
+    app_state.notifier.notify(Notification::new(
+        NotificationSeverity::Warning,
+        "Live trade already flat",
+        format!("Trade {} of {} on {} has no open position on {} anymore. Recording it as closed at the last price ({}).", trade.id, trade.alert_name, trade.pair, exchange.name(), exit_price)
+    ));
+
+    let closed_trade = build_closed_live_trade(app_state, &trade, &OrderFill { order_id: 0, price: exit_price, quantity: trade.quantity, fees: 0.0 });
+    let closed_trade = ClosedTrade { exit_order_id: None, ..closed_trade };
+
+    let result = async {
+        app_state.mongo_state.add_closed_trade(closed_trade.clone()).await?;
+        app_state.mongo_state.delete_active_trade(trade.id).await
+    }.await;
+
+    if let Err(err) = result {
+        return Err(format!("Failed to record closed trade {}: {}", trade.id, err))
+    }
+
+    app_state.mqtt.publish_trade_event(TradeEventKind::Closed, &closed_trade.alert_name, &closed_trade);
+
+    enforce_daily_loss_limit(app_state).await;
+
+    Ok(closed_trade)
+}
+
+/// Closes an active trade of either kind: paper trades are exited at `exit_price`, live trades at the price that their closing order fills at.
+///
+/// Returns the closed trade, or `None` if the trade isn't active (e.g. it was already closed).
+pub async fn close_active_trade(app_state: &AppState, trade_id: &ObjectId, exit_price: f64) -> Result<Option<ClosedTrade>, String> {
+    let is_live = app_state.active_trades.lock().unwrap().get(trade_id).is_some_and(|trade| matches!(trade.kind, TradeKind::Live));
+
+    if is_live {
+        close_live_trade(app_state, trade_id).await
+    } else {
+        close_paper_trade(app_state, trade_id, exit_price).await.map_err(|err| err.to_string())
+    }
+}
+
+/// Records the fill of the opening order of a live trade as its entry: the trade is recorded at the fill price and quantity,
+/// with the fees charged for it.
+///
+/// Returns the requested quantity if the order was only partially filled.
+pub fn apply_entry_fill(trade: &mut ActiveTrade, fill: &OrderFill) -> Option<f64> {
+    let requested_quantity = trade.quantity;
+
+    let exchange_profile = ExchangeProfile::resolve(trade.exchange.as_deref());
+    let maintenance_margin_percentage = exchange_profile.maintenance_margin_percentage(calc_notional_value(fill.quantity, fill.price, &trade.contract_type));
+
+    trade.entry_price = fill.price;
+    trade.quantity = fill.quantity;
+    trade.liquidation_price = calc_liquidation_price(fill.price, trade.leverage.into(), &trade.direction, &trade.contract_type, maintenance_margin_percentage);
+    trade.entry_fees = Some(fill.fees);
+    trade.entry_order_id = Some(fill.order_id.to_string());
+
+    (fill.quantity < requested_quantity * (1.0 - 1e-9)).then_some(requested_quantity)
+}
+
+/// Opens a live trade with a market order on `exchange`, sized and leveraged like a paper trade (see `build_paper_trade`), but recorded
+/// at the fill price and quantity, with the fees charged for it.
+///
+/// If the order is rejected, nothing is recorded. If the order was filled but recording the trade fails, the position is flattened again.
+async fn open_live_trade(
+    app_state: &AppState,
+    exchange: &dyn Exchange,
+    alert: TradingViewAlert,
+    parameters: &StrategyParameters,
+    near_maintenance: bool,
+    request_id: &str
+) -> Result<ActiveTrade, OpenLiveTradeError> {
+    let mut trade = build_paper_trade(alert, parameters, 1.0, &app_state.rounding, near_maintenance, request_id, app_state.clock.now());
+    trade.kind = TradeKind::Live;
+    trade.exchange = Some(exchange.name().to_string());
+
+    exchange.set_leverage(&trade.pair, trade.leverage.into()).await.map_err(OpenLiveTradeError::Rejected)?;
+
+    let fill = exchange
+        .place_order(&trade.pair, OrderSide::opening(&trade.direction), trade.quantity, false)
+        .await
+        .map_err(OpenLiveTradeError::Rejected)?;
+
+    if let Some(requested_quantity) = apply_entry_fill(&mut trade, &fill) {
+        println!("(execute_live_trade) [{}] Order {} was partially filled: {} of {}.", request_id, fill.order_id, fill.quantity, requested_quantity);
+
+        app_state.notifier.notify(Notification::new(
+            NotificationSeverity::Warning,
+            "Live order partially filled",
+            format!("Order {} of {} on {} was filled for {} of {}. The trade is recorded with the filled quantity.", fill.order_id, trade.alert_name, trade.pair, fill.quantity, requested_quantity)
+        ));
+    }
+
+    if let Err(err) = app_state.mongo_state.add_active_trade(trade.clone()).await {
+        eprintln!("(execute_live_trade) [{}] Failed to record live trade after order {} was filled: {}", request_id, fill.order_id, err);
+
+        // roll back, so that no untracked position is left on the exchange
+        if let Err(rollback_err) = exchange.place_order(&trade.pair, OrderSide::closing(&trade.direction), fill.quantity, true).await {
+            app_state.notifier.notify(Notification::new(
+                NotificationSeverity::Critical,
+                "Untracked live position",
+                format!("Order {} of {} on {} was filled but couldn't be recorded ({}), and flattening it failed: {}", fill.order_id, trade.alert_name, trade.pair, err, rollback_err)
+            ));
+        }
+
+        return Err(OpenLiveTradeError::NotRecorded(err.to_string()))
+    }
+
+    // insert the trade into the in-memory store, and make sure the price feed tracks its pair
+    app_state.subscribe_pair(&trade.pair);
+    app_state.mqtt.publish_trade_event(TradeEventKind::Opened, &trade.alert_name, &trade);
+    app_state.active_trades.lock().unwrap().insert(trade.id, trade.clone());
+
+    Ok(trade)
+}
+
+/// Executes an authenticated alert as a live trade on the live exchange (Binance): opens the live trade of its strategy on the pair, or flips it
+/// if it's in the opposite direction.
+///
+/// The alert passes the same pre-trade guards as paper alerts (see `run_pre_trade_guards`) before any order is placed, but skips the
+/// simulation-only checks of paper trades (e.g. sizing modes and filter scripts). The chaos mode can't be enabled together with live
+/// trading (see `check_chaos_mode`), so no failures are injected around real orders.
+pub async fn process_live_trade_alert(
+    mongo_state: &MongoDBState,
+    app_state: &AppState,
+    payload: &Value,
+    request_id: &str,
+    alert: TradingViewAlert
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let Some(exchange) = app_state.live_exchange.as_deref().filter(|_| app_state.features.live_trading) else {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::LiveTradingDisabled,
+            (StatusCode::FORBIDDEN, "403 Forbidden"),
+            "(execute_live_trade) Live trading is disabled.".to_string()
+        ).await
+    };
+
+    if app_state.paused.load(Ordering::SeqCst) {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::Paused,
+            (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable"),
+            "(execute_live_trade) The execution of alerts is paused.".to_string()
+        ).await
+    }
+
+    if !is_accepted_symbol(app_state, &alert.pair) || !is_live_tradable(&alert.pair) || alert.contract_type != ContractType::Linear {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::SymbolNotAllowed,
+            (StatusCode::BAD_REQUEST, "400 Bad Request"),
+            format!("(execute_live_trade) Symbol {} can't be traded live", alert.pair)
+        ).await
+    }
+
+    let mut parameters = match mongo_state.fetch_strategy(&alert.name).await {
+        Ok(Some(strategy)) if !strategy.enabled => {
+            return reject_alert(
+                app_state,
+                payload,
+                request_id,
+                RejectionReason::StrategyDisabled,
+                (StatusCode::FORBIDDEN, "403 Forbidden"),
+                format!("(execute_live_trade) Strategy {} is disabled.", strategy.name)
+            ).await
+        }
+        Ok(Some(strategy)) => strategy.parameters,
+        Ok(None) => StrategyParameters::default(),
+        Err(err) => {
+            eprintln!("(execute_live_trade) [{}] Failed to fetch strategy: {}", request_id, err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: Some(ResponseCode::InternalError),
+                    message: format!("(execute_live_trade) Failed to fetch strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let near_maintenance = match run_pre_trade_guards(app_state, &alert, &mut parameters, exchange.name(), &TradeKind::Live).await {
+        Ok(near_maintenance) => near_maintenance,
+        Err((reason, status, message)) => return reject_alert(app_state, payload, request_id, reason, status, message).await,
+    };
+
+    // concurrent alerts of the strategy on the pair wait for each other, so that they don't both place an order
+    let _alert_lock = app_state.lock_alert(&alert.name, &alert.pair).await;
+
+    let existing_trade = match mongo_state.fetch_active_trade_by_apk(&alert.name, &alert.pair, &TradeKind::Live).await {
+        Ok(existing_trade) => existing_trade,
+        Err(err) => {
+            eprintln!("(execute_live_trade) [{}] Failed to fetch existing trade: {}", request_id, err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: Some(ResponseCode::InternalError),
+                    message: format!("(execute_live_trade) Failed to fetch existing trade: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let flipped = match existing_trade {
+        Some(existing_trade) if existing_trade.direction == alert.signal.into() => {
+            return (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: Some(ResponseCode::AlertIgnoredSameDirection),
+                    message: "(execute_live_trade) Alert signal matches existing trade direction. Ignoring alert.".to_string(),
+                    data: None
+                })
+            )
+        }
+        Some(existing_trade) => {
+            // the existing trade has to be fully closed on the exchange before opening the new one, otherwise the flip would open a
+            // second position next to the old one
+            let result = match close_live_trade(app_state, &existing_trade.id).await {
+                Ok(Some(_)) if app_state.active_trades.lock().unwrap().contains_key(&existing_trade.id) => Err("the trade was only partially closed".to_string()),
+                Ok(Some(_)) => Ok(()),
+                Ok(None) => Err("the trade isn't active in memory".to_string()),
+                Err(err) => Err(err),
+            };
+
+            if let Err(err) = result {
+                return reject_alert(
+                    app_state,
+                    payload,
+                    request_id,
+                    RejectionReason::ExchangeRejected,
+                    (StatusCode::BAD_GATEWAY, "502 Bad Gateway"),
+                    format!("(execute_live_trade) Failed to close existing trade {}: {}", existing_trade.id, err)
+                ).await
+            }
+
+            true
+        }
+        None => false,
+    };
+
+    let (alert_name, pair) = (alert.name.clone(), alert.pair.clone());
+
+    match open_live_trade(app_state, exchange, alert, &parameters, near_maintenance, request_id).await {
+        Ok(trade) => {
+            println!("(execute_live_trade) [{}] Opened live trade {} at {}.", request_id, trade.id, trade.entry_price);
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: Some(if flipped { ResponseCode::TradeFlipped } else { ResponseCode::TradeOpened }),
+                    message: if flipped {
+                        "(execute_live_trade) Closed existing trade and added to closed trades collection. Also opened new trade successfully.".to_string()
+                    } else {
+                        "(execute_live_trade) Opened new trade successfully.".to_string()
+                    },
+                    data: None
+                })
+            )
+        }
+        Err(OpenLiveTradeError::Rejected(err)) => {
+            reject_alert(
+                app_state,
+                payload,
+                request_id,
+                RejectionReason::ExchangeRejected,
+                (StatusCode::BAD_GATEWAY, "502 Bad Gateway"),
+                format!("(execute_live_trade) {} rejected the order of {} on {}: {}", exchange.name(), alert_name, pair, err)
+            ).await
+        }
+        Err(OpenLiveTradeError::NotRecorded(err)) => {
+            // an order was placed, so the alert must not be retried: a client error keeps its claim, so that a redelivery returns this outcome
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse {
+                    status: "422 Unprocessable Entity",
+                    code: Some(ResponseCode::OrderNotRecorded),
+                    message: format!("(execute_live_trade) Failed to open new trade: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::models::{ClosedTradeExportQuery, DateFormat, DecimalSeparator, ReportLocale};
+
+impl FromStr for DecimalSeparator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "point" => Ok(DecimalSeparator::Point),
+            "comma" => Ok(DecimalSeparator::Comma),
+            other => Err(format!("Unsupported decimal separator: {}", other)),
+        }
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "rfc3339" => Ok(DateFormat::Rfc3339),
+            "iso" => Ok(DateFormat::Iso),
+            "european" => Ok(DateFormat::European),
+            "us" => Ok(DateFormat::Us),
+            other => Err(format!("Unsupported date format: {}", other)),
+        }
+    }
+}
+
+impl ReportLocale {
+    /// Reads the locale from the variables returned by `lookup`. Invalid values are logged and replaced by the default.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let default = Self::default();
+
+        let decimal_separator = lookup("REPORT_DECIMAL_SEPARATOR").map_or(default.decimal_separator, |value| {
+            value.parse().unwrap_or_else(|err| {
+                eprintln!("(ReportLocale::from_lookup) Invalid REPORT_DECIMAL_SEPARATOR {}: {}", value, err);
+                default.decimal_separator
+            })
+        });
+
+        let date_format = lookup("REPORT_DATE_FORMAT").map_or(default.date_format, |value| {
+            value.parse().unwrap_or_else(|err| {
+                eprintln!("(ReportLocale::from_lookup) Invalid REPORT_DATE_FORMAT {}: {}", value, err);
+                default.date_format
+            })
+        });
+
+        let timezone = lookup("REPORT_TIMEZONE").map_or(default.timezone, |value| {
+            value.trim().parse::<Tz>().unwrap_or_else(|err| {
+                eprintln!("(ReportLocale::from_lookup) Invalid REPORT_TIMEZONE {}: {}", value, err);
+                default.timezone
+            })
+        });
+
+        Self { decimal_separator, date_format, timezone }
+    }
+
+    /// Reads the locale from the `REPORT_*` env variables.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Applies the locale options of an export query on top of this locale.
+    pub fn with_overrides(self, query: &ClosedTradeExportQuery) -> Self {
+        Self {
+            decimal_separator: query.decimal_separator.unwrap_or(self.decimal_separator),
+            date_format: query.date_format.unwrap_or(self.date_format),
+            timezone: query.timezone.unwrap_or(self.timezone),
+        }
+    }
+
+    /// The delimiter of CSV fields, which mustn't clash with the decimal separator.
+    pub fn csv_delimiter(&self) -> u8 {
+        match self.decimal_separator {
+            DecimalSeparator::Point => b',',
+            DecimalSeparator::Comma => b';',
+        }
+    }
+
+    /// Replaces the decimal point of a formatted number with the decimal separator.
+    fn localize_number(&self, formatted: String) -> String {
+        match self.decimal_separator {
+            DecimalSeparator::Point => formatted,
+            DecimalSeparator::Comma => formatted.replace('.', ","),
+        }
+    }
+
+    /// Formats a number with as many decimals as needed to represent it.
+    pub fn format_number(&self, value: f64) -> String {
+        self.localize_number(value.to_string())
+    }
+
+    /// Formats a number with exactly `decimals` decimals.
+    pub fn format_fixed(&self, value: f64, decimals: usize) -> String {
+        self.localize_number(format!("{:.*}", decimals, value))
+    }
+
+    /// Formats a timestamp in the timezone of the locale, to the second.
+    pub fn format_timestamp(&self, timestamp: DateTime<Utc>) -> String {
+        let timestamp = timestamp.with_timezone(&self.timezone);
+
+        match self.date_format {
+            DateFormat::Rfc3339 => timestamp.to_rfc3339(),
+            DateFormat::Iso => timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            DateFormat::European => timestamp.format("%d.%m.%Y %H:%M:%S").to_string(),
+            DateFormat::Us => timestamp.format("%m/%d/%Y %I:%M:%S %p").to_string(),
+        }
+    }
+
+    /// Formats a timestamp in the timezone of the locale, to the minute (e.g. in tables meant to be read by humans).
+    pub fn format_timestamp_short(&self, timestamp: DateTime<Utc>) -> String {
+        let timestamp = timestamp.with_timezone(&self.timezone);
+
+        let format = match self.date_format {
+            DateFormat::Rfc3339 | DateFormat::Iso => "%Y-%m-%d %H:%M",
+            DateFormat::European => "%d.%m.%Y %H:%M",
+            DateFormat::Us => "%m/%d/%Y %I:%M %p",
+        };
+
+        timestamp.format(format).to_string()
+    }
+}
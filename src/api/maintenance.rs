@@ -0,0 +1,262 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use axum::{extract::Path, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId}, results::{DeleteResult, InsertOneResult, UpdateResult}, Cursor};
+
+use crate::{constants::{BINANCE_SPOT_API_URL, MAINTENANCE_PROXIMITY_MINUTES, MAINTENANCE_STATUS_POLL_INTERVAL_SECS, PAPER_TRADING_EXCHANGE}, models::{ApiResponse, AppState, BinanceSystemStatus, MaintenanceSource, MaintenanceWindow, MongoDBState, NewMaintenanceWindow, SharedClock}};
+
+/// CRUD operations for exchange maintenance windows in the database.
+impl MongoDBState {
+    /// Adds a maintenance window into the database.
+    pub async fn add_maintenance_window(&self, window: MaintenanceWindow) -> Result<InsertOneResult, mongodb::error::Error> {
+        self.maintenance_window_collection.insert_one(window).await
+    }
+
+    /// Fetches all maintenance windows that haven't ended before `since`, ordered by start time.
+    pub async fn fetch_maintenance_windows(&self, since: DateTime<Utc>) -> Result<Vec<MaintenanceWindow>, mongodb::error::Error> {
+        let mut cursor: Cursor<MaintenanceWindow> = self
+            .maintenance_window_collection
+            .find(doc! { "end": { "$gte": since.timestamp() } })
+            .sort(doc! { "start": 1 })
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Fetches a maintenance window of `exchange` overlapping the period between `from` and `to`, if any.
+    pub async fn fetch_overlapping_maintenance_window(
+        &self,
+        exchange: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    ) -> Result<Option<MaintenanceWindow>, mongodb::error::Error> {
+        self.maintenance_window_collection
+            .find_one(doc! {
+                "exchange": exchange,
+                "start": { "$lte": to.timestamp() },
+                "end": { "$gte": from.timestamp() },
+            })
+            .await
+    }
+
+    /// Updates the end of a maintenance window.
+    pub async fn extend_maintenance_window(&self, id: ObjectId, end: DateTime<Utc>) -> Result<UpdateResult, mongodb::error::Error> {
+        self.maintenance_window_collection
+            .update_one(doc! { "_id": id }, doc! { "$set": { "end": end.timestamp() } })
+            .await
+    }
+
+    /// Deletes a maintenance window from the database based on the provided ID.
+    pub async fn delete_maintenance_window(&self, id: ObjectId) -> Result<DeleteResult, mongodb::error::Error> {
+        self.maintenance_window_collection.delete_one(doc! { "_id": id }).await
+    }
+
+    /// Checks whether a trade opened on `exchange` at `at` is within `MAINTENANCE_PROXIMITY_MINUTES` of a maintenance window.
+    pub async fn is_near_maintenance(&self, exchange: &str, at: DateTime<Utc>) -> Result<bool, mongodb::error::Error> {
+        let proximity = Duration::minutes(MAINTENANCE_PROXIMITY_MINUTES);
+
+        Ok(self.fetch_overlapping_maintenance_window(exchange, at - proximity, at + proximity).await?.is_some())
+    }
+}
+
+/// Polls Binance's system status once at `now`. If the exchange reports maintenance, the ongoing status-detected
+/// maintenance window is extended (or a new one is registered) until the next poll.
+async fn poll_maintenance_status(client: &reqwest::Client, mongo_state: &MongoDBState, now: DateTime<Utc>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let status = client
+        .get(format!("{}/sapi/v1/system/status", BINANCE_SPOT_API_URL))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<BinanceSystemStatus>()
+        .await?;
+
+    // 0 = normal
+    if status.status == 0 {
+        return Ok(());
+    }
+
+    let poll_interval = Duration::seconds(MAINTENANCE_STATUS_POLL_INTERVAL_SECS as i64);
+    let end = now + poll_interval;
+
+    match mongo_state.fetch_overlapping_maintenance_window(PAPER_TRADING_EXCHANGE, now - poll_interval, now).await? {
+        Some(window) if window.source == MaintenanceSource::StatusEndpoint => {
+            mongo_state.extend_maintenance_window(window.id, end.max(window.end)).await?;
+        }
+        // manually registered windows are left untouched
+        Some(_) => {}
+        None => {
+            println!("(poll_maintenance_status) {} reported maintenance: {}", PAPER_TRADING_EXCHANGE, status.msg);
+
+            mongo_state.add_maintenance_window(MaintenanceWindow {
+                id: ObjectId::new(),
+                exchange: PAPER_TRADING_EXCHANGE.to_string(),
+                start: now,
+                end,
+                reason: Some(status.msg),
+                source: MaintenanceSource::StatusEndpoint,
+            }).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically polls the exchange's system status endpoint (with `client`) and registers maintenance windows for unannounced maintenance.
+pub async fn start_maintenance_status_poller(mongo_state: Arc<MongoDBState>, client: reqwest::Client, clock: SharedClock) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(MAINTENANCE_STATUS_POLL_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(err) = poll_maintenance_status(&client, &mongo_state, clock.now()).await {
+            eprintln!("(start_maintenance_status_poller) Failed to poll system status: {}", err);
+        }
+    }
+}
+
+/// Returns all ongoing and upcoming maintenance windows.
+pub async fn get_maintenance_windows(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<Vec<MaintenanceWindow>>>) {
+    match mongo_state.fetch_maintenance_windows(app_state.clock.now()).await {
+        Ok(windows) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_maintenance_windows) Fetched maintenance windows successfully.".to_string(),
+                data: Some(windows)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_maintenance_windows) Failed to fetch maintenance windows: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_maintenance_windows) Failed to fetch maintenance windows: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Manually registers a maintenance window (e.g. one announced by the exchange in advance).
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn add_maintenance_window(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Json(payload): Json<NewMaintenanceWindow>,
+) -> (StatusCode, Json<ApiResponse<MaintenanceWindow>>) {
+    if payload.end <= payload.start {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: "(add_maintenance_window) The end of a maintenance window must be after its start.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    let window = MaintenanceWindow {
+        id: ObjectId::new(),
+        exchange: payload.exchange.to_lowercase(),
+        start: payload.start,
+        end: payload.end,
+        reason: payload.reason,
+        source: MaintenanceSource::Manual,
+    };
+
+    match mongo_state.add_maintenance_window(window.clone()).await {
+        Ok(_) => (
+            StatusCode::CREATED,
+            Json(ApiResponse {
+                status: "201 Created",
+                code: None,
+                message: "(add_maintenance_window) Added maintenance window successfully.".to_string(),
+                data: Some(window)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(add_maintenance_window) Failed to add maintenance window: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(add_maintenance_window) Failed to add maintenance window: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Deletes a maintenance window based on the provided ID.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn delete_maintenance_window(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(delete_maintenance_window) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.delete_maintenance_window(id).await {
+        Ok(result) if result.deleted_count == 0 => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(delete_maintenance_window) Maintenance window {} not found.", id),
+                data: None
+            })
+        ),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(delete_maintenance_window) Deleted maintenance window successfully.".to_string(),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(delete_maintenance_window) Failed to delete maintenance window: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(delete_maintenance_window) Failed to delete maintenance window: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::{collections::HashSet, time::Duration};
+
+use chrono::Utc;
+use futures_util::FutureExt;
+use mongodb::{bson::{doc, to_bson, Document}, options::{IndexOptions, TimeseriesGranularity, TimeseriesOptions}, results::UpdateResult, IndexModel};
+
+use crate::{configs::is_namespace_exists_error, constants::{ALERT_CLAIM_TTL_SECS, DUPLICATE_ACTIVE_TRADES_COLLECTION, EQUITY_SNAPSHOT_TTL_SECS, PRICE_TICK_TTL_SECS, TRADE_TICKS_CAPPED_SIZE_BYTES}, models::{AppliedMigration, Migration, MongoDBState, TradeKind}};
+
+/// All migrations, in ascending order of version. New migrations are appended to the end.
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            name: "create unique index on the import key of closed trades",
+            run: |mongo_state| create_import_key_index(mongo_state).boxed(),
+        },
+        Migration {
+            version: 2,
+            name: "backfill the contract type of trades stored before inverse contracts were supported",
+            run: |mongo_state| backfill_contract_type(mongo_state).boxed(),
+        },
+        Migration {
+            version: 3,
+            name: "create the time-series collections of price ticks and equity snapshots",
+            run: |mongo_state| create_timeseries_collections(mongo_state).boxed(),
+        },
+        Migration {
+            version: 4,
+            name: "create the capped collection of trade ticks",
+            run: |mongo_state| create_trade_tick_collection(mongo_state).boxed(),
+        },
+        Migration {
+            version: 5,
+            name: "index the PnL snapshots of trades by trade",
+            run: |mongo_state| create_trade_pnl_snapshot_index(mongo_state).boxed(),
+        },
+        Migration {
+            version: 6,
+            name: "create unique index on the alert name, pair and kind of active trades",
+            run: |mongo_state| create_active_trade_apk_index(mongo_state).boxed(),
+        },
+        Migration {
+            version: 7,
+            name: "index the funding ledger by trade and by exchange account",
+            run: |mongo_state| create_funding_payment_indexes(mongo_state).boxed(),
+        },
+        Migration {
+            version: 8,
+            name: "expire alert claims with a TTL index on their claim time",
+            run: |mongo_state| create_alert_claim_ttl_index(mongo_state).boxed(),
+        },
+    ]
+}
+
+/// Ensures that a row of an exchange export can only be imported once, even if the same file is imported concurrently.
+async fn create_import_key_index(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let index = IndexModel::builder()
+        .keys(doc! { "importKey": 1 })
+        .options(
+            IndexOptions::builder()
+                .unique(true)
+                .partial_filter_expression(doc! { "importKey": { "$type": "string" } })
+                .build()
+        )
+        .build();
+
+    mongo_state.closed_trade_collection.create_index(index).await.map(|_| ())
+}
+
+/// Stores the default contract type (linear) on trades without one, so that aggregations reading the raw field (rather than
+/// the deserialized default) see the same value.
+async fn backfill_contract_type(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let filter = doc! { "contractType": { "$exists": false } };
+    let update = doc! { "$set": { "contractType": "linear" } };
+
+    mongo_state.active_trade_collection.clone_with_type::<Document>().update_many(filter.clone(), update.clone()).await?;
+    mongo_state.closed_trade_collection.clone_with_type::<Document>().update_many(filter, update).await?;
+
+    Ok(())
+}
+
+/// Creates the price tick and equity snapshot collections as time-series collections, which MongoDB buckets by time (and product)
+/// and expires automatically. Time-series collections can't be converted from regular ones, so they must exist before the first insert.
+async fn create_timeseries_collections(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let collections = [
+        (mongo_state.price_tick_collection.namespace(), Some("product"), TimeseriesGranularity::Seconds, PRICE_TICK_TTL_SECS),
+        (mongo_state.equity_snapshot_collection.namespace(), None, TimeseriesGranularity::Minutes, EQUITY_SNAPSHOT_TTL_SECS),
+    ];
+
+    let client = mongo_state.price_tick_collection.client();
+
+    for (namespace, meta_field, granularity, ttl_secs) in collections {
+        let timeseries = TimeseriesOptions::builder()
+            .time_field("timestamp".to_string())
+            .meta_field(meta_field.map(str::to_string))
+            .granularity(Some(granularity))
+            .build();
+
+        let result = client
+            .database(&namespace.db)
+            .create_collection(&namespace.coll)
+            .timeseries(timeseries)
+            .expire_after_seconds(Duration::from_secs(ttl_secs))
+            .await;
+
+        // the collection was already created (e.g. by a replica applying this migration at the same time)
+        match result {
+            Err(err) if !is_namespace_exists_error(&err) => return Err(err),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the trade tick collection as a capped collection, so that capturing ticks can't grow the database unbounded,
+/// and indexes it by trade.
+async fn create_trade_tick_collection(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let namespace = mongo_state.trade_tick_collection.namespace();
+
+    let result = mongo_state.trade_tick_collection
+        .client()
+        .database(&namespace.db)
+        .create_collection(&namespace.coll)
+        .capped(true)
+        .size(TRADE_TICKS_CAPPED_SIZE_BYTES)
+        .await;
+
+    match result {
+        Err(err) if !is_namespace_exists_error(&err) => return Err(err),
+        _ => {}
+    }
+
+    let index = IndexModel::builder().keys(doc! { "tradeId": 1, "timestamp": 1 }).build();
+
+    mongo_state.trade_tick_collection.create_index(index).await.map(|_| ())
+}
+
+/// Indexes the PnL snapshots of trades by trade, since they're always fetched per trade.
+async fn create_trade_pnl_snapshot_index(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let index = IndexModel::builder().keys(doc! { "tradeId": 1, "timestamp": 1 }).build();
+
+    mongo_state.trade_pnl_snapshot_collection.create_index(index).await.map(|_| ())
+}
+
+/// Ensures that a strategy can only have one active trade of a kind on a pair, even if its alerts are executed concurrently
+/// (e.g. by multiple replicas).
+///
+/// Duplicates opened before the index existed would make creating it fail, so only the oldest trade of each strategy, pair and
+/// kind is kept. The others are moved to the `DUPLICATE_ACTIVE_TRADES_COLLECTION` to be reviewed, rather than closed at a made-up price.
+///
+/// Duplicate live trades fail the migration instead, since each of them may hold a real position on the exchange that would no
+/// longer be managed once archived. They have to be reconciled with the exchange and removed manually before the server can start.
+async fn create_active_trade_apk_index(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let active_trades = mongo_state.active_trade_collection.clone_with_type::<Document>();
+    let namespace = active_trades.namespace();
+    let archive = active_trades.client().database(&namespace.db).collection::<Document>(DUPLICATE_ACTIVE_TRADES_COLLECTION);
+
+    let mut duplicates = active_trades
+        .aggregate([
+            doc! { "$sort": { "openTimestamp": 1, "_id": 1 } },
+            doc! { "$group": { "_id": { "alertName": "$alertName", "pair": "$pair", "kind": "$kind" }, "ids": { "$push": "$_id" } } },
+            doc! { "$match": { "ids.1": { "$exists": true } } },
+        ])
+        .await?;
+
+    while duplicates.advance().await? {
+        let group = duplicates.deserialize_current()?;
+        let ids = group.get_array("ids").map_err(|err| mongodb::error::Error::custom(err.to_string()))?;
+        let Some((kept, archived)) = ids.split_first() else { continue };
+
+        if is_live_trade_group(&group) {
+            return Err(mongodb::error::Error::custom(format!(
+                "(create_active_trade_apk_index) Found duplicate live trades {:?} of {:?}. Reconcile them with the exchange and remove all but one before restarting.",
+                ids, group.get("_id")
+            )))
+        }
+
+        let mut trades = active_trades.find(doc! { "_id": { "$in": archived } }).await?;
+        let archived_at = Utc::now().timestamp();
+
+        while trades.advance().await? {
+            let mut trade = trades.deserialize_current()?;
+            trade.insert("archivedAt", archived_at);
+
+            // the trade may already have been archived by a previous, interrupted run of this migration
+            archive.replace_one(doc! { "_id": trade.get("_id") }, trade).upsert(true).await?;
+        }
+
+        active_trades.delete_many(doc! { "_id": { "$in": archived } }).await?;
+
+        println!(
+            "(create_active_trade_apk_index) Kept active trade {} of {:?} and moved {} duplicates to {}.",
+            kept, group.get("_id"), archived.len(), DUPLICATE_ACTIVE_TRADES_COLLECTION
+        );
+    }
+
+    let index = IndexModel::builder()
+        .keys(doc! { "alertName": 1, "pair": 1, "kind": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    mongo_state.active_trade_collection.create_index(index).await.map(|_| ())
+}
+
+/// Checks whether a group of duplicate active trades (grouped by alert name, pair and kind) consists of live trades.
+pub fn is_live_trade_group(group: &Document) -> bool {
+    let live = to_bson(&TradeKind::Live).ok();
+
+    group.get_document("_id").ok().and_then(|key| key.get("kind")) == live.as_ref()
+}
+
+/// Indexes the funding ledger by trade (to audit the funding fees of a trade) and by exchange account (to audit an account over time).
+async fn create_funding_payment_indexes(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let indexes = [
+        IndexModel::builder().keys(doc! { "tradeId": 1, "fundingTime": 1 }).build(),
+        IndexModel::builder().keys(doc! { "exchange": 1, "fundingTime": -1 }).build(),
+    ];
+
+    mongo_state.funding_payment_collection.create_indexes(indexes).await.map(|_| ())
+}
+
+/// Expires alert claims `ALERT_CLAIM_TTL_SECS` after they were claimed, so that the collection doesn't grow unbounded.
+///
+/// Claims stored before `claimedAt` existed get it from their timestamp in seconds, since TTL indexes ignore documents without a date.
+async fn create_alert_claim_ttl_index(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    mongo_state.alert_claim_collection
+        .clone_with_type::<Document>()
+        .update_many(
+            doc! { "claimedAt": { "$exists": false } },
+            vec![doc! { "$set": { "claimedAt": { "$toDate": { "$multiply": ["$claimedTimestamp", 1000] } } } }]
+        )
+        .await?;
+
+    let index = IndexModel::builder()
+        .keys(doc! { "claimedAt": 1 })
+        .options(IndexOptions::builder().expire_after(Duration::from_secs(ALERT_CLAIM_TTL_SECS)).build())
+        .build();
+
+    mongo_state.alert_claim_collection.create_index(index).await.map(|_| ())
+}
+
+/// CRUD operations for applied migrations in the database.
+impl MongoDBState {
+    /// Fetches the versions of all applied migrations.
+    pub async fn fetch_applied_migration_versions(&self) -> Result<HashSet<u32>, mongodb::error::Error> {
+        let mut cursor = self.migration_collection.find(doc! {}).await?;
+
+        let mut versions = HashSet::new();
+
+        while cursor.advance().await? {
+            versions.insert(cursor.deserialize_current()?.version);
+        }
+
+        Ok(versions)
+    }
+
+    /// Records a migration as applied.
+    pub async fn record_applied_migration(&self, migration: &AppliedMigration) -> Result<UpdateResult, mongodb::error::Error> {
+        self.migration_collection
+            .replace_one(doc! { "_id": migration.version }, migration)
+            .upsert(true)
+            .await
+    }
+}
+
+/// Returns the migrations that haven't been applied yet, in the order they should be applied in.
+pub fn pending_migrations(mut migrations: Vec<Migration>, applied: &HashSet<u32>) -> Vec<Migration> {
+    migrations.retain(|migration| !applied.contains(&migration.version));
+    migrations.sort_by_key(|migration| migration.version);
+
+    migrations
+}
+
+/// Applies all pending migrations in order. Called at startup, before any documents are loaded.
+///
+/// Stops at the first failing migration, so that later migrations never run against a partially migrated database.
+pub async fn run_migrations(mongo_state: &MongoDBState) -> Result<(), mongodb::error::Error> {
+    let applied = mongo_state.fetch_applied_migration_versions().await?;
+
+    for migration in pending_migrations(migrations(), &applied) {
+        println!("(run_migrations) Applying migration {}: {}", migration.version, migration.name);
+
+        (migration.run)(mongo_state).await?;
+
+        mongo_state.record_applied_migration(&AppliedMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_timestamp: Utc::now(),
+        }).await?;
+    }
+
+    Ok(())
+}
@@ -1,9 +1,67 @@
+pub mod alert;
+pub mod anomaly;
+pub mod audit;
+pub mod cache;
+pub mod calendar;
+pub mod chaos;
+pub mod clock;
+pub mod command;
+pub mod conflict;
+pub mod consensus;
+pub mod consistency;
+pub mod correlation;
+pub mod encryption;
+pub mod exchange;
+pub mod experiment;
+pub mod export;
+pub mod feature;
+pub mod feed_quality;
+pub mod funding;
+pub mod grpc;
+pub mod health;
+pub mod import;
+pub mod fx;
+pub mod leader;
+pub mod leaderboard;
+pub mod live;
+pub mod locale;
+pub mod maintenance;
+pub mod migration;
+pub mod mqtt;
+pub mod network;
+pub mod notifier;
+pub mod outcome;
+pub mod plugin;
+pub mod pnl_snapshot;
+pub mod position;
+pub mod price_alert;
+pub mod readiness;
+pub mod regime;
+pub mod report;
+pub mod request;
+pub mod response;
+pub mod risk;
+pub mod rounding;
+pub mod scheduler;
+pub mod script;
+pub mod seed;
+pub mod shard;
+pub mod sizing;
+pub mod snapshot;
+pub mod strategy;
+pub mod timeseries;
 pub mod trade;
 pub mod trade_helpers;
+pub mod trade_replay;
+pub mod trade_tick;
+pub mod version;
+pub mod watchlist;
 pub mod websocket;
 pub mod state;
+pub mod statement;
+pub mod stats;
+pub mod stats_helpers;
 
 pub use trade::*;
 pub use trade_helpers::*;
 pub use websocket::*;
-pub use state::*;
\ No newline at end of file
@@ -0,0 +1,133 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use rumqttc::{AsyncClient, EventLoop, MqttOptions, QoS};
+use serde::Serialize;
+
+use crate::{constants::{DEFAULT_MQTT_PORT, DEFAULT_MQTT_TOPIC_PREFIX, MQTT_CHANNEL_CAPACITY, MQTT_KEEP_ALIVE_SECS, MQTT_RECONNECT_DELAY_SECS}, models::{MqttConfig, MqttPublisher, TickerEvent, TradeEvent, TradeEventKind}};
+
+impl TradeEventKind {
+    /// The name of the event in topics (e.g. `partially_liquidated`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeEventKind::Opened => "opened",
+            TradeEventKind::Closed => "closed",
+            TradeEventKind::PartiallyLiquidated => "partially_liquidated",
+        }
+    }
+}
+
+impl MqttConfig {
+    /// Reads the config from the variables returned by `lookup`:
+    /// - `MQTT_HOST` and `MQTT_PORT`: the broker to publish to. the sink is disabled (`None`) if `MQTT_HOST` isn't set.
+    /// - `MQTT_USERNAME` and `MQTT_PASSWORD`: the credentials to authenticate with, if any.
+    /// - `MQTT_TOPIC_PREFIX`: the prefix of all topics. defaults to `DEFAULT_MQTT_TOPIC_PREFIX`.
+    /// - `MQTT_TICKERS`: a comma-separated list of products whose tickers are published (e.g. `SOL-USD,BTC-USD`).
+    ///   only products the price feed is subscribed to (i.e. with active trades or on the watchlist) produce tickers.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let non_empty = |name: &str| lookup(name).map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+
+        let host = non_empty("MQTT_HOST")?;
+
+        let port = non_empty("MQTT_PORT").map_or(DEFAULT_MQTT_PORT, |port| port.parse::<u16>().unwrap_or_else(|_| {
+            eprintln!("Invalid MQTT_PORT `{}`. Falling back to {}.", port, DEFAULT_MQTT_PORT);
+            DEFAULT_MQTT_PORT
+        }));
+
+        let tickers = non_empty("MQTT_TICKERS")
+            .map(|tickers| {
+                tickers
+                    .split(',')
+                    .map(|ticker| ticker.trim().to_uppercase())
+                    .filter(|ticker| !ticker.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            host,
+            port,
+            credentials: non_empty("MQTT_USERNAME").zip(non_empty("MQTT_PASSWORD")),
+            topic_prefix: non_empty("MQTT_TOPIC_PREFIX").unwrap_or_else(|| DEFAULT_MQTT_TOPIC_PREFIX.to_string()),
+            tickers,
+        })
+    }
+}
+
+impl MqttPublisher {
+    /// Connects to the broker configured via the `MQTT_*` env variables (see `MqttConfig::from_lookup`).
+    ///
+    /// Returns a disabled publisher if no broker is configured.
+    pub fn from_env() -> Self {
+        let Some(config) = MqttConfig::from_lookup(|name| std::env::var(name).ok()) else {
+            return Self::default()
+        };
+
+        let mut options = MqttOptions::new(format!("tv-trading-bot-{}", ObjectId::new().to_hex()), config.host, config.port);
+        options.set_keep_alive(StdDuration::from_secs(MQTT_KEEP_ALIVE_SECS));
+
+        if let Some((username, password)) = config.credentials {
+            options.set_credentials(username, password);
+        }
+
+        let (client, event_loop) = AsyncClient::new(options, MQTT_CHANNEL_CAPACITY);
+        tokio::spawn(drive_mqtt_event_loop(event_loop));
+
+        Self { client: Some(client), topic_prefix: config.topic_prefix, tickers: config.tickers }
+    }
+
+    /// The topic of the `kind` events of the trades of `alert_name`.
+    pub fn trade_event_topic(&self, kind: TradeEventKind, alert_name: &str) -> String {
+        format!("{}/trades/{}/{}", self.topic_prefix, alert_name, kind.as_str())
+    }
+
+    /// The topic of the ticker of `product_id`.
+    pub fn ticker_topic(&self, product_id: &str) -> String {
+        format!("{}/tickers/{}", self.topic_prefix, product_id)
+    }
+
+    /// Publishes a lifecycle event of a trade of `alert_name`. Does nothing if the MQTT sink is disabled.
+    pub fn publish_trade_event<T: Serialize>(&self, kind: TradeEventKind, alert_name: &str, trade: &T) {
+        self.publish(self.trade_event_topic(kind, alert_name), QoS::AtLeastOnce, false, &TradeEvent { event: kind, trade });
+    }
+
+    /// Publishes the latest price of `product_id`, if its ticker is configured to be published.
+    ///
+    /// Tickers are retained, so that new subscribers immediately receive the latest price.
+    pub fn publish_ticker(&self, product_id: &str, price: f64, timestamp: DateTime<Utc>) {
+        if self.tickers.contains(product_id) {
+            self.publish(self.ticker_topic(product_id), QoS::AtMostOnce, true, &TickerEvent { product_id, price, timestamp });
+        }
+    }
+
+    /// Queues a message without waiting for the broker, so that trading is never held up by it.
+    /// Messages are dropped if the queue is full (e.g. while the broker is unreachable).
+    fn publish(&self, topic: String, qos: QoS, retain: bool, payload: &impl Serialize) {
+        let Some(client) = &self.client else {
+            return
+        };
+
+        let payload = match serde_json::to_vec(payload) {
+            Ok(payload) => payload,
+            Err(err) => {
+                eprintln!("(publish) Failed to serialize the MQTT payload of {}: {}", topic, err);
+                return;
+            }
+        };
+
+        if let Err(err) = client.try_publish(&topic, qos, retain, payload) {
+            eprintln!("(publish) Failed to publish to {}: {}", topic, err);
+        }
+    }
+}
+
+/// Drives the connection to the MQTT broker, reconnecting after `MQTT_RECONNECT_DELAY_SECS` whenever it fails.
+async fn drive_mqtt_event_loop(mut event_loop: EventLoop) {
+    loop {
+        if let Err(err) = event_loop.poll().await {
+            eprintln!("(drive_mqtt_event_loop) MQTT connection failed: {}. Reconnecting in {}s.", err, MQTT_RECONNECT_DELAY_SECS);
+            tokio::time::sleep(StdDuration::from_secs(MQTT_RECONNECT_DELAY_SECS)).await;
+        }
+    }
+}
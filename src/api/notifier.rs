@@ -0,0 +1,182 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+use serde_json::json;
+
+use crate::{constants::{DEFAULT_PUSH_MIN_SEVERITY, DEFAULT_WEBHOOK_MIN_SEVERITY, PUSHOVER_MESSAGES_API_URL}, models::{Notification, NotificationSeverity, NotificationSink, NotificationTarget, Notifier}};
+
+impl Notification {
+    /// Creates a new notification timestamped now.
+    pub fn new(severity: NotificationSeverity, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            title: title.into(),
+            message: message.into(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+impl FromStr for NotificationSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "info" => Ok(NotificationSeverity::Info),
+            "warning" => Ok(NotificationSeverity::Warning),
+            "critical" => Ok(NotificationSeverity::Critical),
+            other => Err(format!("Unsupported notification severity: {}", other)),
+        }
+    }
+}
+
+impl NotificationTarget {
+    /// Builds a target, reading its minimum severity from the env variable `min_severity_var`.
+    ///
+    /// Falls back to `default_min_severity` if the variable is unset or invalid.
+    fn from_env(sink: NotificationSink, min_severity_var: &str, default_min_severity: NotificationSeverity) -> Self {
+        let min_severity = match std::env::var(min_severity_var) {
+            Ok(value) => value.parse().unwrap_or_else(|err| {
+                eprintln!("(NotificationTarget::from_env) {} in {}. Using {:?} instead.", err, min_severity_var, default_min_severity);
+                default_min_severity
+            }),
+            Err(_) => default_min_severity,
+        };
+
+        Self { sink, min_severity }
+    }
+
+    /// Whether a notification of `severity` is delivered to this target.
+    pub fn accepts(&self, severity: NotificationSeverity) -> bool {
+        severity >= self.min_severity
+    }
+}
+
+/// Reads a non-empty env variable.
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+impl Notifier {
+    /// Builds a notifier with the sinks configured via env variables:
+    /// - `NOTIFICATION_WEBHOOK_URL`: a webhook URL (e.g. a Discord or Slack incoming webhook).
+    /// - `PUSHOVER_APP_TOKEN` and `PUSHOVER_USER_KEY`: a Pushover application and the user (or group) to notify.
+    /// - `NTFY_TOPIC_URL` (e.g. `https://ntfy.sh/my-topic`) and optionally `NTFY_ACCESS_TOKEN`: an ntfy topic to publish to.
+    ///
+    /// The minimum severity of each sink is configured via `NOTIFICATION_WEBHOOK_MIN_SEVERITY`, `PUSHOVER_MIN_SEVERITY` and `NTFY_MIN_SEVERITY`
+    /// (`info`, `warning` or `critical`). Push sinks only receive critical notifications by default.
+    pub fn from_env() -> Self {
+        let mut targets = Vec::new();
+
+        if let Some(url) = non_empty_env("NOTIFICATION_WEBHOOK_URL") {
+            targets.push(NotificationTarget::from_env(
+                NotificationSink::Webhook { url },
+                "NOTIFICATION_WEBHOOK_MIN_SEVERITY",
+                DEFAULT_WEBHOOK_MIN_SEVERITY
+            ));
+        }
+
+        if let (Some(app_token), Some(user_key)) = (non_empty_env("PUSHOVER_APP_TOKEN"), non_empty_env("PUSHOVER_USER_KEY")) {
+            targets.push(NotificationTarget::from_env(
+                NotificationSink::Pushover { app_token, user_key },
+                "PUSHOVER_MIN_SEVERITY",
+                DEFAULT_PUSH_MIN_SEVERITY
+            ));
+        }
+
+        if let Some(topic_url) = non_empty_env("NTFY_TOPIC_URL") {
+            targets.push(NotificationTarget::from_env(
+                NotificationSink::Ntfy { topic_url, access_token: non_empty_env("NTFY_ACCESS_TOKEN") },
+                "NTFY_MIN_SEVERITY",
+                DEFAULT_PUSH_MIN_SEVERITY
+            ));
+        }
+
+        Self { client: reqwest::Client::new(), targets }
+    }
+
+    /// Delivers a notification to all sinks accepting its severity in the background, so that callers aren't held up by slow sinks.
+    ///
+    /// Notifications are always logged, even if no sinks are configured.
+    pub fn notify(&self, notification: Notification) {
+        println!("(notify) [{:?}] {}: {}", notification.severity, notification.title, notification.message);
+
+        for target in self.targets.iter().filter(|target| target.accepts(notification.severity)) {
+            let client = self.client.clone();
+            let sink = target.sink.clone();
+            let notification = notification.clone();
+
+            tokio::spawn(async move {
+                if let Err(err) = send_to_sink(&client, &sink, &notification).await {
+                    eprintln!("(notify) Failed to deliver notification to {:?}: {}", sink, err);
+                }
+            });
+        }
+    }
+}
+
+/// Delivers a notification to a single sink.
+async fn send_to_sink(
+    client: &reqwest::Client,
+    sink: &NotificationSink,
+    notification: &Notification
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match sink {
+        NotificationSink::Webhook { url } => {
+            let text = format!("**{}**\n{}", notification.title, notification.message);
+
+            client
+                .post(url)
+                .json(&json!({
+                    "content": text,
+                    "text": text,
+                    "notification": notification,
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+        NotificationSink::Pushover { app_token, user_key } => {
+            // high priority bypasses the user's quiet hours
+            let priority = match notification.severity {
+                NotificationSeverity::Critical => 1,
+                NotificationSeverity::Warning | NotificationSeverity::Info => 0,
+            };
+
+            client
+                .post(PUSHOVER_MESSAGES_API_URL)
+                .json(&json!({
+                    "token": app_token,
+                    "user": user_key,
+                    "title": notification.title,
+                    "message": notification.message,
+                    "priority": priority,
+                    "timestamp": notification.timestamp.timestamp(),
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+        NotificationSink::Ntfy { topic_url, access_token } => {
+            let priority = match notification.severity {
+                NotificationSeverity::Critical => "urgent",
+                NotificationSeverity::Warning => "high",
+                NotificationSeverity::Info => "default",
+            };
+
+            let mut request = client
+                .post(topic_url)
+                .header("Title", notification.title.as_str())
+                .header("Priority", priority)
+                .body(notification.message.clone());
+
+            if let Some(access_token) = access_token {
+                request = request.bearer_auth(access_token);
+            }
+
+            request.send().await?.error_for_status()?;
+        }
+    }
+
+    Ok(())
+}
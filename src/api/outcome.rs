@@ -0,0 +1,168 @@
+use std::{net::IpAddr, time::Duration};
+
+use axum::Json;
+use hyper::StatusCode;
+use reqwest::Url;
+
+use crate::{constants::OUTCOME_CALLBACK_TIMEOUT_SECS, models::{AlertOutcomeEvent, AlertOutcomeKind, ApiResponse, AppState, ResponseCode, TradeKind}};
+
+impl AlertOutcomeKind {
+    /// Summarizes the response code of a processed alert.
+    pub fn from_code(code: Option<ResponseCode>) -> Self {
+        match code {
+            Some(ResponseCode::TradeOpened) => AlertOutcomeKind::Opened,
+            Some(ResponseCode::TradeFlipped) => AlertOutcomeKind::Flipped,
+            Some(ResponseCode::AlertIgnoredSameDirection) => AlertOutcomeKind::Ignored,
+            Some(ResponseCode::InternalError) | None => AlertOutcomeKind::Failed,
+            Some(_) => AlertOutcomeKind::Rejected,
+        }
+    }
+}
+
+/// Checks that `callback_url` is an HTTP(S) URL whose host isn't an internal address (e.g. loopback, private or link-local), so
+/// that strategies can't make the bot send requests into its own network.
+pub fn validate_callback_url(callback_url: &str) -> Result<Url, String> {
+    let url = Url::parse(callback_url).map_err(|err| format!("It must be an HTTP(S) URL: {}", err))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("It must be an HTTP(S) URL.".to_string())
+    }
+
+    let is_internal = match url.host_str() {
+        Some(host) => match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) => is_internal_ip(ip),
+            Err(_) => {
+                let domain = host.trim_end_matches('.').to_ascii_lowercase();
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+        },
+        None => true,
+    };
+
+    if is_internal {
+        return Err("Its host must not be a loopback, private or link-local address.".to_string())
+    }
+
+    Ok(url)
+}
+
+/// Checks whether `ip` isn't publicly routable, i.e. it's loopback, private, link-local, shared (CGNAT), unspecified, broadcast,
+/// multicast or reserved for documentation.
+pub fn is_internal_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast() || ip.is_multicast()
+                || ip.is_documentation() || first == 0 || (first == 100 && (second & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_internal_ip(IpAddr::V4(ip))
+            }
+
+            let first_segment = ip.segments()[0];
+
+            // unique local (fc00::/7) and link-local (fe80::/10) addresses
+            ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || (first_segment & 0xfe00) == 0xfc00 || (first_segment & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// Resolves the host of a callback URL and checks that none of its addresses are internal (see `is_internal_ip`).
+///
+/// Domains are checked when the outcome is sent rather than when the strategy is saved, since they may resolve differently later.
+async fn check_callback_host(url: &Url) -> Result<(), String> {
+    let (Some(domain), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Ok(())
+    };
+
+    // addresses were already checked by `validate_callback_url`
+    if domain.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().is_ok() {
+        return Ok(())
+    }
+
+    let mut addresses = tokio::net::lookup_host((domain, port)).await.map_err(|err| format!("Failed to resolve {}: {}", domain, err))?;
+
+    match addresses.find(|address| is_internal_ip(address.ip())) {
+        Some(address) => Err(format!("{} resolves to the internal address {}", domain, address.ip())),
+        None => Ok(()),
+    }
+}
+
+/// Sends the outcome of a processed alert of the given trade `kind` to the callback URL of its strategy in the background, if the strategy has one.
+///
+/// Failing to deliver the outcome doesn't change the response, so errors are only logged.
+pub async fn send_alert_outcome(
+    app_state: &AppState,
+    request_id: &str,
+    alert_name: &str,
+    pair: &str,
+    kind: &TradeKind,
+    (status_code, Json(response)): &(StatusCode, Json<ApiResponse<()>>)
+) {
+    let callback_url = match app_state.mongo_state.fetch_strategy(alert_name).await {
+        Ok(Some(strategy)) => match strategy.callback_url {
+            Some(callback_url) => callback_url,
+            None => return,
+        },
+        Ok(None) => return,
+        Err(err) => {
+            eprintln!("(send_alert_outcome) [{}] Failed to fetch strategy {}: {}", request_id, alert_name, err);
+            return
+        }
+    };
+
+    let outcome = AlertOutcomeKind::from_code(response.code);
+
+    let trade = match outcome {
+        AlertOutcomeKind::Opened | AlertOutcomeKind::Flipped | AlertOutcomeKind::Ignored => {
+            let map = app_state.active_trades.lock().unwrap();
+            map.values()
+                .find(|trade| trade.alert_name == alert_name && trade.pair.eq_ignore_ascii_case(pair) && matches!((&trade.kind, kind), (TradeKind::Paper, TradeKind::Paper) | (TradeKind::Live, TradeKind::Live)))
+                .cloned()
+        }
+        AlertOutcomeKind::Rejected | AlertOutcomeKind::Failed => None,
+    };
+
+    let event = AlertOutcomeEvent {
+        request_id: request_id.to_string(),
+        alert_name: alert_name.to_string(),
+        pair: pair.to_string(),
+        outcome,
+        code: response.code,
+        status_code: status_code.as_u16(),
+        message: response.message.clone(),
+        trade,
+        timestamp: app_state.clock.now(),
+    };
+
+    let client = app_state.notifier.client.clone();
+
+    tokio::spawn(async move {
+        let url = match validate_callback_url(&callback_url) {
+            Ok(url) => url,
+            Err(err) => {
+                eprintln!("(send_alert_outcome) [{}] Refusing to send outcome to {}: {}", event.request_id, callback_url, err);
+                return
+            }
+        };
+
+        if let Err(err) = check_callback_host(&url).await {
+            eprintln!("(send_alert_outcome) [{}] Refusing to send outcome to {}: {}", event.request_id, callback_url, err);
+            return
+        }
+
+        let result = client
+            .post(url)
+            .timeout(Duration::from_secs(OUTCOME_CALLBACK_TIMEOUT_SECS))
+            .json(&event)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        if let Err(err) = result {
+            eprintln!("(send_alert_outcome) [{}] Failed to send outcome to {}: {}", event.request_id, callback_url, err);
+        }
+    });
+}
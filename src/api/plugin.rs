@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{tradingview::TradingViewAlert, ActiveTrade, AlertFilter, ExitRule, FilterDecision, PluginRegistry, StrategyParameters};
+
+impl PluginRegistry {
+    /// Registers an alert filter, running after the ones registered before it.
+    #[allow(dead_code)]
+    pub fn with_alert_filter(mut self, filter: Arc<dyn AlertFilter>) -> Self {
+        self.alert_filters.push(filter);
+        self
+    }
+
+    /// Registers an exit rule.
+    #[allow(dead_code)]
+    pub fn with_exit_rule(mut self, rule: Arc<dyn ExitRule>) -> Self {
+        self.exit_rules.push(rule);
+        self
+    }
+
+    /// Runs the alert filters in order, until one of them rejects the alert.
+    ///
+    /// Returns the name of the rejecting filter and its reason. Later filters see the exits and sizing overridden by earlier ones.
+    pub fn filter_alert(&self, alert: &mut TradingViewAlert, parameters: &mut StrategyParameters) -> Result<(), (&'static str, String)> {
+        for filter in &self.alert_filters {
+            match filter.filter(alert, parameters) {
+                FilterDecision::Allow(overrides) => overrides.apply(alert, parameters),
+                FilterDecision::Deny(reason) => return Err((filter.name(), reason)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the name of the first exit rule deciding that `trade` should be closed at `price`, if any.
+    pub fn exit_rule_hit(&self, trade: &ActiveTrade, price: f64, now: DateTime<Utc>) -> Option<&'static str> {
+        self.exit_rules
+            .iter()
+            .find(|rule| rule.should_exit(trade, price, now))
+            .map(|rule| rule.name())
+    }
+}
+
+/// Builds the registry of the plugins compiled into the bot.
+///
+/// Plugins are Rust modules under `plugins/`, each compiled in via its own Cargo feature (e.g. `plugin-max-holding-time`).
+/// To add one, implement `AlertFilter` or `ExitRule` in a new module, gate it behind a new feature and register it here.
+pub fn registered_plugins() -> PluginRegistry {
+    #[allow(unused_mut)]
+    let mut registry = PluginRegistry::default();
+
+    #[cfg(feature = "plugin-max-holding-time")]
+    {
+        registry = registry.with_exit_rule(Arc::new(crate::plugins::max_holding_time::MaxHoldingTime::from_env()));
+    }
+
+    registry
+}
@@ -0,0 +1,124 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use axum::{extract::Path, Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{api::{calc_pnl, calc_roe, to_coinbase_product_id}, constants::TRADE_PNL_SNAPSHOT_INTERVAL_SECS, models::{ActiveTrade, ApiResponse, AppState, MongoDBState, TradePnlSnapshot}};
+
+/// CRUD operations for trade PnL snapshots in the database.
+impl MongoDBState {
+    /// Inserts a batch of trade PnL snapshots into the database.
+    pub async fn insert_trade_pnl_snapshots(&self, snapshots: &[TradePnlSnapshot]) -> Result<(), mongodb::error::Error> {
+        if snapshots.is_empty() {
+            return Ok(());
+        }
+
+        self.trade_pnl_snapshot_collection.insert_many(snapshots).await.map(|_| ())
+    }
+
+    /// Fetches the PnL snapshots of a trade, oldest first.
+    pub async fn fetch_trade_pnl_snapshots(&self, trade_id: ObjectId) -> Result<Vec<TradePnlSnapshot>, mongodb::error::Error> {
+        let cursor = self.trade_pnl_snapshot_collection
+            .find(doc! { "tradeId": trade_id })
+            .sort(doc! { "timestamp": 1 })
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+}
+
+/// Builds the PnL snapshot of a trade at `price`. Fees are left out, since they're only known once the trade is closed.
+pub fn build_trade_pnl_snapshot(trade: &ActiveTrade, price: f64, timestamp: DateTime<Utc>) -> TradePnlSnapshot {
+    let unrealized_pnl = calc_pnl(trade.entry_price, price, trade.quantity, 0.0, 0.0, &trade.direction, &trade.contract_type);
+
+    TradePnlSnapshot {
+        trade_id: trade.id,
+        timestamp,
+        price,
+        unrealized_pnl,
+        roe: calc_roe(unrealized_pnl, trade.entry_price, trade.quantity, trade.leverage.into(), &trade.contract_type),
+    }
+}
+
+impl AppState {
+    /// Snapshots the unrealized PnL of each active trade at the latest price of its pair. Trades without a price are skipped.
+    pub async fn save_trade_pnl_snapshots(&self) {
+        let trades: Vec<ActiveTrade> = self.active_trades.lock().unwrap().values().cloned().collect();
+        let prices = self.latest_prices.lock().unwrap().clone();
+        let now = self.clock.now();
+
+        let snapshots: Vec<TradePnlSnapshot> = trades
+            .iter()
+            .filter_map(|trade| {
+                let price = to_coinbase_product_id(&trade.pair).and_then(|product_id| prices.get(&product_id).copied())?;
+
+                Some(build_trade_pnl_snapshot(trade, price, now))
+            })
+            .collect();
+
+        if let Err(err) = self.mongo_state.insert_trade_pnl_snapshots(&snapshots).await {
+            eprintln!("(save_trade_pnl_snapshots) Failed to save {} PnL snapshots: {}", snapshots.len(), err);
+        }
+    }
+}
+
+/// Periodically snapshots the unrealized PnL of each active trade.
+///
+/// When running multiple instances, only the leader takes snapshots, so that they aren't duplicated.
+pub async fn start_trade_pnl_snapshotter(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(TRADE_PNL_SNAPSHOT_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        if app_state.leadership.is_leader() {
+            app_state.save_trade_pnl_snapshots().await;
+        }
+    }
+}
+
+/// Returns how the unrealized PnL of a trade evolved while it was open, oldest first,
+/// e.g. to analyze whether its stop loss was too tight or its take profit too far.
+pub async fn get_trade_pnl_history(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Vec<TradePnlSnapshot>>>) {
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(get_trade_pnl_history) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.fetch_trade_pnl_snapshots(id).await {
+        Ok(snapshots) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: format!("(get_trade_pnl_history) Fetched {} PnL snapshots successfully.", snapshots.len()),
+                data: Some(snapshots)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_trade_pnl_history) Failed to fetch PnL snapshots of trade {}: {}", id, err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_trade_pnl_history) Failed to fetch PnL snapshots of trade {}: {}", id, err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{Extension, Json};
+use hyper::StatusCode;
+
+use crate::{api::{calc_liquidation_price, calc_notional_value, calc_pnl, split_pair, to_coinbase_product_id}, models::{ActiveTrade, ApiResponse, AppState, ContractType, ExchangeProfile, NetPosition, TradeDirection}};
+
+/// Averages the entry prices of two positions in the same direction. Inverse contracts are averaged harmonically, since their quantity
+/// is their notional value in the quote currency.
+fn average_entry_price(quantity: f64, entry_price: f64, added_quantity: f64, added_entry_price: f64, contract_type: &ContractType) -> f64 {
+    if quantity <= 0.0 {
+        return added_entry_price;
+    }
+
+    match contract_type {
+        ContractType::Linear => (quantity * entry_price + added_quantity * added_entry_price) / (quantity + added_quantity),
+        ContractType::Inverse => (quantity + added_quantity) / (quantity / entry_price + added_quantity / added_entry_price),
+    }
+}
+
+/// Nets `trades` (all on the same pair, contract type and exchange) into a single position, without a mark price.
+///
+/// The trades are applied in the order they were opened: trades in the direction of the position add to it at their entry price,
+/// opposite trades reduce it (keeping its entry price) or flip it (at their entry price). `quote_usdt_value` is the value of 1 unit
+/// of the quote currency in USDT, since the maintenance margin tiers are in USDT.
+pub fn calc_net_position(trades: &[ActiveTrade], exchange_profile: &ExchangeProfile, quote_usdt_value: f64) -> Option<NetPosition> {
+    let first = trades.first()?;
+    let contract_type = first.contract_type;
+
+    let mut trades: Vec<&ActiveTrade> = trades.iter().collect();
+    trades.sort_by_key(|trade| trade.open_timestamp);
+
+    let mut quantity = 0.0;
+    let mut entry_price = 0.0;
+    let mut long_quantity = 0.0;
+    let mut short_quantity = 0.0;
+    let mut margin = 0.0;
+    let mut strategies: Vec<String> = Vec::new();
+
+    for trade in &trades {
+        let signed_quantity = match trade.direction {
+            TradeDirection::Long => {
+                long_quantity += trade.quantity;
+                trade.quantity
+            }
+            TradeDirection::Short => {
+                short_quantity += trade.quantity;
+                -trade.quantity
+            }
+        };
+
+        if quantity == 0.0 || (quantity > 0.0) == (signed_quantity > 0.0) {
+            entry_price = average_entry_price(f64::abs(quantity), entry_price, trade.quantity, trade.entry_price, &contract_type);
+        } else if trade.quantity > f64::abs(quantity) {
+            entry_price = trade.entry_price;
+        }
+
+        quantity += signed_quantity;
+        margin += calc_notional_value(trade.quantity, trade.entry_price, &contract_type) / f64::from(trade.leverage);
+
+        if !strategies.contains(&trade.alert_name) {
+            strategies.push(trade.alert_name.clone());
+        }
+    }
+
+    strategies.sort();
+
+    // quantities are rounded to 2 dp, so anything below is left over from floating point arithmetic
+    let flat = f64::abs(quantity) < 1e-9;
+    let direction = if flat {
+        None
+    } else if quantity > 0.0 {
+        Some(TradeDirection::Long)
+    } else {
+        Some(TradeDirection::Short)
+    };
+
+    let average_entry_price = (!flat).then_some(entry_price);
+    let quantity = if flat { 0.0 } else { f64::abs(quantity) };
+
+    let effective_leverage = average_entry_price
+        .map(|entry_price| calc_notional_value(quantity, entry_price, &contract_type) / margin)
+        .filter(|leverage| leverage.is_finite() && *leverage > 0.0);
+
+    let liquidation_price = match (average_entry_price, effective_leverage, &direction) {
+        (Some(entry_price), Some(leverage), Some(direction)) => {
+            let notional_usdt_value = match contract_type {
+                ContractType::Linear => quantity * entry_price,
+                ContractType::Inverse => quantity,
+            } * quote_usdt_value;
+
+            Some(calc_liquidation_price(entry_price, leverage, direction, &contract_type, exchange_profile.maintenance_margin_percentage(notional_usdt_value)))
+                .filter(|price| price.is_finite() && *price > 0.0)
+        }
+        _ => None,
+    };
+
+    Some(NetPosition {
+        exchange: exchange_profile.name.clone(),
+        pair: first.pair.to_uppercase(),
+        contract_type,
+        direction,
+        quantity,
+        long_quantity,
+        short_quantity,
+        average_entry_price,
+        margin,
+        effective_leverage,
+        liquidation_price,
+        mark_price: None,
+        unrealized_pnl: None,
+        liquidation_distance_percentage: None,
+        trades: trades.len(),
+        strategies,
+    })
+}
+
+/// Marks a net position to `mark_price`, i.e. sets its unrealized PnL and distance to liquidation.
+pub fn mark_net_position(position: &mut NetPosition, mark_price: f64) {
+    position.mark_price = Some(mark_price);
+
+    if let (Some(direction), Some(entry_price)) = (&position.direction, position.average_entry_price) {
+        position.unrealized_pnl = Some(calc_pnl(entry_price, mark_price, position.quantity, 0.0, 0.0, direction, &position.contract_type));
+    }
+
+    position.liquidation_distance_percentage = position
+        .liquidation_price
+        .map(|liquidation_price| f64::abs(mark_price - liquidation_price) / mark_price * 100.0);
+}
+
+impl AppState {
+    /// Nets the active trades into a position per exchange, pair and contract type, marked to the latest prices.
+    pub fn calc_net_positions(&self) -> Vec<NetPosition> {
+        let trades: Vec<ActiveTrade> = self.active_trades.lock().unwrap().values().cloned().collect();
+
+        let mut groups: BTreeMap<(String, String, bool), Vec<ActiveTrade>> = BTreeMap::new();
+
+        for trade in trades {
+            let exchange = ExchangeProfile::resolve(trade.exchange.as_deref()).name;
+            let key = (exchange, trade.pair.to_uppercase(), trade.contract_type == ContractType::Inverse);
+
+            groups.entry(key).or_default().push(trade);
+        }
+
+        groups
+            .into_values()
+            .filter_map(|trades| {
+                let first = trades.first()?;
+                let exchange_profile = ExchangeProfile::resolve(first.exchange.as_deref());
+                let quote_usdt_value = split_pair(&first.pair).and_then(|(_, quote)| self.usdt_value_of(&quote)).unwrap_or(1.0);
+                let mark_price = to_coinbase_product_id(&first.pair).and_then(|product_id| self.latest_prices.lock().unwrap().get(&product_id).copied());
+
+                let mut position = calc_net_position(&trades, &exchange_profile, quote_usdt_value)?;
+
+                if let Some(mark_price) = mark_price {
+                    mark_net_position(&mut position, mark_price);
+                }
+
+                Some(position)
+            })
+            .collect()
+    }
+}
+
+/// Returns the active trades of all strategies netted into a single position per exchange, pair and contract type
+/// (size, average entry price and combined liquidation risk), i.e. what the exchange account holds when the strategies trade live.
+pub async fn get_net_positions(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<Vec<NetPosition>>>) {
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_net_positions) Netted the active trades successfully.".to_string(),
+            data: Some(app_state.calc_net_positions())
+        })
+    )
+}
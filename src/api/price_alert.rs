@@ -0,0 +1,293 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use axum::{extract::{Path, Query}, Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId}, results::{DeleteResult, InsertOneResult, UpdateResult}, Cursor};
+
+use crate::{api::to_coinbase_product_id, constants::ACCEPTED_SYMBOLS, models::{ApiResponse, AppState, MongoDBState, NewPriceAlert, Notification, NotificationSeverity, PriceAlert, PriceAlertCondition, PriceAlertQuery}};
+
+/// A thread-safe map of the price alerts that haven't triggered yet, in memory.
+pub type PriceAlertsMap = Arc<Mutex<HashMap<ObjectId, PriceAlert>>>;
+
+/// CRUD operations for price alerts in the database.
+impl MongoDBState {
+    /// Adds a price alert into the database.
+    pub async fn add_price_alert(&self, alert: PriceAlert) -> Result<InsertOneResult, mongodb::error::Error> {
+        self.price_alert_collection.insert_one(alert).await
+    }
+
+    /// Fetches all price alerts (or only the ones that haven't triggered yet if `active_only`), newest first.
+    pub async fn fetch_price_alerts(&self, active_only: bool) -> Result<Vec<PriceAlert>, mongodb::error::Error> {
+        let filter = if active_only { doc! { "triggeredTimestamp": null } } else { doc! {} };
+
+        let mut cursor: Cursor<PriceAlert> = self
+            .price_alert_collection
+            .find(filter)
+            .sort(doc! { "createdTimestamp": -1 })
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Marks a price alert as triggered at `price` and `now`.
+    pub async fn mark_price_alert_triggered(&self, id: ObjectId, price: f64, now: DateTime<Utc>) -> Result<UpdateResult, mongodb::error::Error> {
+        self.price_alert_collection
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "triggeredTimestamp": now.timestamp(), "triggeredPrice": price } }
+            )
+            .await
+    }
+
+    /// Deletes a price alert from the database based on the provided ID.
+    pub async fn delete_price_alert(&self, id: ObjectId) -> Result<DeleteResult, mongodb::error::Error> {
+        self.price_alert_collection.delete_one(doc! { "_id": id }).await
+    }
+}
+
+/// Checks whether the condition of a price alert is met at `price`.
+pub fn is_price_alert_hit(condition: &PriceAlertCondition, price: f64) -> bool {
+    match condition {
+        PriceAlertCondition::Above { price: level } => price >= *level,
+        PriceAlertCondition::Below { price: level } => price <= *level,
+        PriceAlertCondition::PercentMove { percent, reference_price: Some(reference_price) } if *reference_price > 0.0 => {
+            ((price - reference_price) / reference_price * 100.0).abs() >= percent.abs()
+        }
+        // alerts without a valid reference price cannot be evaluated
+        PriceAlertCondition::PercentMove { .. } => false,
+    }
+}
+
+impl AppState {
+    /// Checks the active price alerts of the pair matching `product_id` against `price`, and notifies (and deactivates)
+    /// the ones that triggered. Called by the price listener for every price update.
+    pub async fn check_price_alerts(&self, product_id: &str, price: f64) {
+        let triggered: Vec<PriceAlert> = {
+            let mut map = self.price_alerts.lock().unwrap();
+
+            let ids: Vec<ObjectId> = map
+                .values()
+                .filter(|alert| to_coinbase_product_id(&alert.pair).is_some_and(|alert_product_id| alert_product_id == product_id))
+                .filter(|alert| is_price_alert_hit(&alert.condition, price))
+                .map(|alert| alert.id)
+                .collect();
+
+            // remove from in-memory so that each alert only triggers once
+            ids.iter().filter_map(|id| map.remove(id)).collect()
+        };
+
+        for alert in triggered {
+            let description = match &alert.condition {
+                PriceAlertCondition::Above { price: level } => format!("rose to {} (at or above {})", price, level),
+                PriceAlertCondition::Below { price: level } => format!("fell to {} (at or below {})", price, level),
+                PriceAlertCondition::PercentMove { percent, reference_price } => format!(
+                    "moved to {} ({}% or more from {})", price, percent, reference_price.unwrap_or_default()
+                ),
+            };
+
+            let mut message = format!("{} {}.", alert.pair, description);
+
+            if let Some(note) = &alert.note {
+                message.push_str(&format!(" Note: {}", note));
+            }
+
+            self.notifier.notify(Notification::new(NotificationSeverity::Info, "Price alert triggered", message));
+
+            if let Err(err) = self.mongo_state.mark_price_alert_triggered(alert.id, price, self.clock.now()).await {
+                eprintln!("(check_price_alerts) Failed to mark price alert {} as triggered: {}", alert.id, err);
+            }
+        }
+    }
+}
+
+/// Returns all price alerts. Only the ones that haven't triggered yet if the `active` query parameter is true.
+pub async fn get_price_alerts(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<PriceAlertQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<PriceAlert>>>) {
+    match mongo_state.fetch_price_alerts(query.active.unwrap_or(false)).await {
+        Ok(alerts) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_price_alerts) Fetched price alerts successfully.".to_string(),
+                data: Some(alerts)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_price_alerts) Failed to fetch price alerts: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_price_alerts) Failed to fetch price alerts: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Registers a price alert, which is evaluated against the price feed and delivered through the notifier once triggered.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn add_price_alert(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Json(payload): Json<NewPriceAlert>,
+) -> (StatusCode, Json<ApiResponse<PriceAlert>>) {
+    let pair = payload.pair.trim().to_uppercase();
+
+    // check if the symbol is accepted
+    if !ACCEPTED_SYMBOLS.contains(&pair.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(add_price_alert) Symbol {} not accepted", pair),
+                data: None
+            })
+        )
+    }
+
+    let condition = match payload.condition {
+        // percent moves without an explicit reference are measured from the latest price
+        PriceAlertCondition::PercentMove { percent, reference_price: None } => {
+            let latest_price = to_coinbase_product_id(&pair)
+                .and_then(|product_id| app_state.latest_prices.lock().unwrap().get(&product_id).copied());
+
+            match latest_price {
+                Some(latest_price) => PriceAlertCondition::PercentMove { percent, reference_price: Some(latest_price) },
+                None => {
+                    return (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        Json(ApiResponse {
+                            status: "503 Service Unavailable",
+                            code: None,
+                            message: format!("(add_price_alert) No price available for {} yet. Provide a reference price instead.", pair),
+                            data: None
+                        })
+                    )
+                }
+            }
+        }
+        condition => condition,
+    };
+
+    let alert = PriceAlert {
+        id: ObjectId::new(),
+        pair,
+        condition,
+        note: payload.note,
+        created_timestamp: app_state.clock.now(),
+        triggered_timestamp: None,
+        triggered_price: None,
+    };
+
+    match mongo_state.add_price_alert(alert.clone()).await {
+        Ok(_) => {
+            // make sure the price feed tracks the pair, and evaluate the alert from now on
+            app_state.subscribe_pair(&alert.pair);
+            {
+                let mut map = app_state.price_alerts.lock().unwrap();
+                map.insert(alert.id, alert.clone());
+            }
+
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse {
+                    status: "201 Created",
+                    code: None,
+                    message: "(add_price_alert) Added price alert successfully.".to_string(),
+                    data: Some(alert)
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(add_price_alert) Failed to add price alert: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(add_price_alert) Failed to add price alert: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Deletes a price alert based on the provided ID.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn delete_price_alert(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(delete_price_alert) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.delete_price_alert(id).await {
+        Ok(result) if result.deleted_count == 0 => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(delete_price_alert) Price alert {} not found.", id),
+                data: None
+            })
+        ),
+        Ok(_) => {
+            {
+                let mut map = app_state.price_alerts.lock().unwrap();
+                map.remove(&id);
+            }
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: None,
+                    message: "(delete_price_alert) Deleted price alert successfully.".to_string(),
+                    data: None
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(delete_price_alert) Failed to delete price alert: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(delete_price_alert) Failed to delete price alert: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
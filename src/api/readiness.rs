@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use chrono::Utc;
+use hyper::StatusCode;
+
+use crate::{api::to_coinbase_product_id, constants::ACCEPTED_SYMBOLS, models::{ApiResponse, ChaosMode, CheckStatus, FeatureFlags, MongoDBState, ReadinessCheck, ReadinessReport}};
+
+/// The secrets that are checked at startup, and whether the server refuses to start without them.
+const REQUIRED_SECRETS: [(&str, bool); 2] = [
+    // every alert is rejected without it
+    ("TRADINGVIEW_SECRET", true),
+    // commands can't be sent via the API without it
+    ("COMMAND_SECRET", false),
+];
+
+impl ReadinessCheck {
+    fn new(name: impl Into<String>, status: CheckStatus, critical: bool, message: impl Into<String>) -> Self {
+        Self { name: name.into(), status, critical, message: message.into() }
+    }
+}
+
+impl ReadinessReport {
+    /// Builds a report from `checks`. The report is ready unless a critical check failed.
+    pub fn new(checks: Vec<ReadinessCheck>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            ready: !checks.iter().any(|check| check.critical && check.status == CheckStatus::Failed),
+            checks,
+        }
+    }
+
+    /// Prints the report, one line per check.
+    pub fn print(&self) {
+        println!("(startup self-check) {}", if self.ready { "Ready" } else { "NOT ready" });
+
+        for check in &self.checks {
+            println!(
+                "  [{:?}]{} {}: {}",
+                check.status,
+                if check.critical { " (critical)" } else { "" },
+                check.name,
+                check.message
+            );
+        }
+    }
+}
+
+/// Checks that the secrets in `REQUIRED_SECRETS` are set (and not empty), looking up env variables with `lookup`.
+pub fn check_secrets(lookup: impl Fn(&str) -> Option<String>) -> Vec<ReadinessCheck> {
+    REQUIRED_SECRETS
+        .iter()
+        .map(|(name, critical)| {
+            let status = match lookup(name) {
+                Some(value) if !value.is_empty() => CheckStatus::Passed,
+                _ if *critical => CheckStatus::Failed,
+                _ => CheckStatus::Warning,
+            };
+
+            let message = if status == CheckStatus::Passed { "Set." } else { "Not set." };
+
+            ReadinessCheck::new(format!("secret {}", name), status, *critical, message)
+        })
+        .collect()
+}
+
+/// Checks that each of `symbols` can be resolved into a product of the price feed, since trades on unresolvable symbols
+/// are never checked against their triggers.
+pub fn check_symbols(symbols: &[&str]) -> ReadinessCheck {
+    let unresolvable: Vec<&str> = symbols
+        .iter()
+        .filter(|symbol| to_coinbase_product_id(symbol).is_none())
+        .copied()
+        .collect();
+
+    if unresolvable.is_empty() {
+        ReadinessCheck::new("accepted symbols", CheckStatus::Passed, false, format!("All {} symbols resolve to a price feed product.", symbols.len()))
+    } else {
+        ReadinessCheck::new("accepted symbols", CheckStatus::Warning, false, format!("No price feed product for {}.", unresolvable.join(", ")))
+    }
+}
+
+/// Checks that the chaos mode isn't enabled together with live trading, looking up env variables with `lookup`.
+///
+/// Injected write failures and feed gaps would hit real positions: a failed write after a fill flattens the position again,
+/// and dropped ticks skip the stops of live trades. The server therefore refuses to start with both enabled.
+pub fn check_chaos_mode(lookup: impl Fn(&str) -> Option<String>) -> ReadinessCheck {
+    let chaos_enabled = ChaosMode::from_lookup(&lookup).enabled;
+    let live_trading = FeatureFlags::from_lookup(&lookup).live_trading;
+
+    match (chaos_enabled, live_trading) {
+        (true, true) => ReadinessCheck::new("chaos mode", CheckStatus::Failed, true, "CHAOS_MODE can't be enabled together with FEATURE_LIVE_TRADING."),
+        (true, false) => ReadinessCheck::new("chaos mode", CheckStatus::Warning, false, "Enabled, failures are injected into paper trading."),
+        (false, _) => ReadinessCheck::new("chaos mode", CheckStatus::Passed, false, "Disabled."),
+    }
+}
+
+/// Checks that the Binance API credentials (`BINANCE_API_KEY` and `BINANCE_API_SECRET`) are set if live trading is enabled
+/// (`FEATURE_LIVE_TRADING`), looking up env variables with `lookup`. Paper trades don't use any exchange credentials.
+pub fn check_exchange_credentials(lookup: impl Fn(&str) -> Option<String>) -> ReadinessCheck {
+    if !FeatureFlags::from_lookup(&lookup).live_trading {
+        return ReadinessCheck::new("exchange credentials", CheckStatus::Skipped, false, "Live trading is disabled, so no exchange credentials are used.")
+    }
+
+    let missing: Vec<&str> = ["BINANCE_API_KEY", "BINANCE_API_SECRET"]
+        .into_iter()
+        .filter(|name| lookup(name).is_none_or(|value| value.trim().is_empty()))
+        .collect();
+
+    if missing.is_empty() {
+        ReadinessCheck::new("exchange credentials", CheckStatus::Passed, true, "Live trades are placed on Binance.")
+    } else {
+        ReadinessCheck::new("exchange credentials", CheckStatus::Failed, true, format!("Live trading is enabled but {} is not set.", missing.join(" and ")))
+    }
+}
+
+/// Checks that `expected` indexes are among the `existing` index names of `collection`.
+pub fn check_indexes(collection: &str, existing: Result<Vec<String>, mongodb::error::Error>, expected: &[&str]) -> ReadinessCheck {
+    let name = format!("indexes of {}", collection);
+
+    let existing = match existing {
+        Ok(existing) => existing,
+        Err(err) => return ReadinessCheck::new(name, CheckStatus::Failed, true, format!("Failed to list the indexes: {}", err)),
+    };
+
+    let missing: Vec<&str> = expected
+        .iter()
+        .filter(|index| !existing.iter().any(|existing| existing == *index))
+        .copied()
+        .collect();
+
+    if missing.is_empty() {
+        ReadinessCheck::new(name, CheckStatus::Passed, true, "Present.")
+    } else {
+        ReadinessCheck::new(name, CheckStatus::Failed, true, format!("Missing {}.", missing.join(", ")))
+    }
+}
+
+/// Validates the configuration and the database: the secrets, the chaos mode, the database connection and the indexes created by the migrations,
+/// the exchange credentials, and whether the accepted symbols resolve on the price feed.
+pub async fn run_startup_checks(mongo_state: &MongoDBState) -> ReadinessReport {
+    let mut checks = check_secrets(|name| std::env::var(name).ok());
+    checks.push(check_chaos_mode(|name| std::env::var(name).ok()));
+
+    match mongo_state.ping().await {
+        Ok(ping_ms) => checks.push(ReadinessCheck::new("database", CheckStatus::Passed, true, format!("Reachable ({}ms).", ping_ms))),
+        Err(err) => checks.push(ReadinessCheck::new("database", CheckStatus::Failed, true, format!("Unreachable: {}", err))),
+    }
+
+    checks.push(check_indexes(
+        "active trades",
+        mongo_state.active_trade_collection.list_index_names().await,
+        &["alertName_1_pair_1_kind_1"]
+    ));
+    checks.push(check_indexes(
+        "closed trades",
+        mongo_state.closed_trade_collection.list_index_names().await,
+        &["importKey_1"]
+    ));
+    checks.push(check_indexes(
+        "trade ticks",
+        mongo_state.trade_tick_collection.list_index_names().await,
+        &["tradeId_1_timestamp_1"]
+    ));
+    checks.push(check_indexes(
+        "trade PnL snapshots",
+        mongo_state.trade_pnl_snapshot_collection.list_index_names().await,
+        &["tradeId_1_timestamp_1"]
+    ));
+
+    checks.push(check_exchange_credentials(|name| std::env::var(name).ok()));
+
+    checks.push(check_symbols(ACCEPTED_SYMBOLS));
+
+    ReadinessReport::new(checks)
+}
+
+/// Runs the startup self-check again and returns its report. Responds with 503 if a critical check fails.
+pub async fn get_readiness(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+) -> (StatusCode, Json<ApiResponse<ReadinessReport>>) {
+    let report = run_startup_checks(&mongo_state).await;
+
+    let (status_code, status, message) = if report.ready {
+        (StatusCode::OK, "200 OK", "(get_readiness) All critical checks passed.".to_string())
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable", "(get_readiness) A critical check failed.".to_string())
+    };
+
+    (
+        status_code,
+        Json(ApiResponse {
+            status,
+            code: None,
+            message,
+            data: Some(report)
+        })
+    )
+}
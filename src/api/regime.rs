@@ -0,0 +1,91 @@
+use chrono::Duration;
+
+use crate::{api::{sizing::calc_atr_percentage, to_coinbase_product_id}, constants::{ATR_PERIOD_DAYS, REGIME_EMA_PERIOD_DAYS, REGIME_FLAT_SLOPE_PERCENTAGE, REGIME_LOOKBACK_DAYS, REGIME_SLOPE_DAYS, REGIME_VOLATILITY_PERCENTILES}, models::{AppState, DailyCandle, MarketRegime, TrendRegime, VolatilityRegime}};
+
+/// Calculates the exponential moving average of `values` (oldest first), seeded with the simple average of the first `period` values.
+///
+/// Returns one value per value from the `period`th on, or nothing if there are fewer values than `period`.
+pub fn calc_ema(values: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || values.len() < period {
+        return Vec::new();
+    }
+
+    let smoothing = 2.0 / (period as f64 + 1.0);
+    let mut ema = vec![values[..period].iter().sum::<f64>() / period as f64];
+
+    for value in &values[period..] {
+        let previous = ema[ema.len() - 1];
+        ema.push((value - previous) * smoothing + previous);
+    }
+
+    ema
+}
+
+/// Calculates the change (in percent) of the EMA of the daily closes over the last `REGIME_SLOPE_DAYS` days.
+pub fn calc_ema_slope_percentage(candles: &[DailyCandle]) -> Option<f64> {
+    let closes: Vec<f64> = candles.iter().map(|candle| candle.close).collect();
+    let ema = calc_ema(&closes, REGIME_EMA_PERIOD_DAYS);
+
+    let latest = *ema.last()?;
+    let previous = *ema.get(ema.len().checked_sub(REGIME_SLOPE_DAYS + 1)?)?;
+
+    (previous > 0.0).then(|| (latest - previous) / previous * 100.0)
+}
+
+/// Calculates the percentile (0 to 100) of the latest ATR among the ATRs of every day of the candles (oldest first),
+/// i.e. the percentage of days that were less volatile.
+pub fn calc_atr_percentile(candles: &[DailyCandle]) -> Option<f64> {
+    let atrs: Vec<f64> = (ATR_PERIOD_DAYS + 1..=candles.len())
+        .filter_map(|end| calc_atr_percentage(&candles[..end], ATR_PERIOD_DAYS))
+        .collect();
+
+    let latest = *atrs.last()?;
+    let lower = atrs.iter().filter(|atr| **atr < latest).count();
+
+    Some(lower as f64 / atrs.len() as f64 * 100.0)
+}
+
+/// Derives the market regime of a pair from its daily candles (oldest first).
+///
+/// Returns `None` if there isn't enough history for both the EMA slope and the ATR percentile.
+pub fn classify_market_regime(candles: &[DailyCandle]) -> Option<MarketRegime> {
+    let ema_slope_percentage = calc_ema_slope_percentage(candles)?;
+    let atr_percentile = calc_atr_percentile(candles)?;
+    let (quiet, volatile) = REGIME_VOLATILITY_PERCENTILES;
+
+    let trend = if ema_slope_percentage > REGIME_FLAT_SLOPE_PERCENTAGE {
+        TrendRegime::Up
+    } else if ema_slope_percentage < -REGIME_FLAT_SLOPE_PERCENTAGE {
+        TrendRegime::Down
+    } else {
+        TrendRegime::Flat
+    };
+
+    let volatility = if atr_percentile < quiet {
+        VolatilityRegime::Quiet
+    } else if atr_percentile > volatile {
+        VolatilityRegime::Volatile
+    } else {
+        VolatilityRegime::Normal
+    };
+
+    Some(MarketRegime { trend, ema_slope_percentage, volatility, atr_percentile })
+}
+
+impl AppState {
+    /// Derives the current market regime of a pair from the daily candles of the last `REGIME_LOOKBACK_DAYS` days.
+    ///
+    /// `None` if there isn't enough price history of the pair yet, or it can't be fetched.
+    pub async fn detect_market_regime(&self, pair: &str) -> Option<MarketRegime> {
+        let product = to_coinbase_product_id(pair)?;
+        let since = self.clock.now() - Duration::days(REGIME_LOOKBACK_DAYS);
+
+        match self.mongo_state.fetch_daily_candles(&[product], since).await {
+            Ok(candles) => classify_market_regime(&candles),
+            Err(err) => {
+                eprintln!("(detect_market_regime) Failed to fetch the daily candles of {}: {}", pair, err);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use lettre::{message::{header::ContentType, Attachment, MultiPart, SinglePart}, transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use mongodb::{bson::doc, Cursor};
+
+use crate::{api::{export::{equity_csv_row, to_csv_row}, stats::resolve_timezone}, constants::{DEFAULT_SMTP_PORT, IMPLICIT_TLS_SMTP_PORT, REPORT_MAILER_INTERVAL_SECS}, models::{AppState, ClosedTrade, CurrencyConversion, EmailReporter, EquityPoint, MongoDBState, PerformanceReport, ReportLocale, ReportPeriod, ReportingCurrency, RoundingPolicy}};
+
+impl MongoDBState {
+    /// Fetches the trades closed between `from` (inclusive) and `to` (exclusive), oldest first.
+    pub async fn fetch_closed_trades_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<ClosedTrade>, mongodb::error::Error> {
+        let cursor: Cursor<ClosedTrade> = self
+            .closed_trade_collection
+            .find(doc! { "closeTimestamp": { "$gte": from.timestamp(), "$lt": to.timestamp() } })
+            .sort(doc! { "closeTimestamp": 1 })
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+}
+
+impl ReportPeriod {
+    /// Parses a report period from its name (`daily` or `weekly`).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            _ => None,
+        }
+    }
+
+    /// The length of the period.
+    pub fn length(&self) -> Duration {
+        match self {
+            Self::Daily => Duration::days(1),
+            Self::Weekly => Duration::days(7),
+        }
+    }
+}
+
+/// Returns the period covered by the report due between `from` (exclusive) and `to` (inclusive), if any.
+///
+/// Daily reports are due at midnight (UTC) and weekly reports on Mondays at midnight (UTC), each covering the period that just ended.
+pub fn calc_due_report_window(period: ReportPeriod, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let midnight = to.date_naive().and_hms_opt(0, 0, 0)?.and_utc();
+
+    let boundary = match period {
+        ReportPeriod::Daily => midnight,
+        ReportPeriod::Weekly => midnight - Duration::days(to.weekday().num_days_from_monday() as i64),
+    };
+
+    (boundary > from && boundary <= to).then(|| (boundary - period.length(), boundary))
+}
+
+/// Calculates the cumulative PnL (in USDT) after each trade, in the order of `trades`.
+pub fn calc_equity_curve(trades: &[ClosedTrade]) -> Vec<EquityPoint> {
+    let mut equity = 0.0;
+
+    trades
+        .iter()
+        .map(|trade| {
+            equity += trade.pnl * trade.settlement_usdt_rate.unwrap_or(1.0);
+            EquityPoint { timestamp: trade.close_timestamp, equity }
+        })
+        .collect()
+}
+
+/// Escapes the characters of `text` that have a special meaning in HTML.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders a report as an HTML email body: a summary of the period, followed by a table of the closed trades.
+/// 
+/// Numbers and timestamps are formatted according to `locale`.
+pub fn build_report_html(report: &PerformanceReport, locale: &ReportLocale, rounding: &RoundingPolicy) -> String {
+    let stats = &report.stats;
+
+    let mut html = format!(
+        "<h2>{:?} report ({} - {} {})</h2>\
+        <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+        <tr><th>Trades</th><th>Win rate</th><th>Total PnL (USDT)</th><th>Profit factor</th><th>Best trade</th><th>Worst trade</th></tr>\
+        <tr><td>{}</td><td>{}%</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\
+        </table>",
+        report.period,
+        locale.format_timestamp_short(report.from),
+        locale.format_timestamp_short(report.to),
+        locale.timezone.name(),
+        stats.total_trades,
+        locale.format_fixed(stats.win_rate, 1),
+        rounding.format_amount(locale, stats.total_pnl),
+        stats.profit_factor.map_or("-".to_string(), |profit_factor| locale.format_fixed(profit_factor, 2)),
+        rounding.format_amount(locale, stats.best_trade_pnl),
+        rounding.format_amount(locale, stats.worst_trade_pnl),
+    );
+
+    if report.trades.is_empty() {
+        html.push_str("<p>No trades were closed within this period.</p>");
+        return html;
+    }
+
+    html.push_str(&format!(
+        "<h3>Closed trades</h3>\
+        <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+        <tr><th>Closed ({})</th><th>Strategy</th><th>Pair</th><th>Direction</th><th>Entry</th><th>Exit</th><th>PnL</th><th>ROE</th><th>Equity (USDT)</th></tr>",
+        locale.timezone.name()
+    ));
+
+    for (trade, point) in report.trades.iter().zip(&report.equity) {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td><td>{} {}</td><td>{}%</td><td>{}</td></tr>",
+            locale.format_timestamp_short(trade.close_timestamp),
+            escape_html(&trade.alert_name),
+            escape_html(&trade.pair),
+            trade.direction,
+            rounding.format_price(locale, &trade.pair, trade.entry_price),
+            rounding.format_price(locale, &trade.pair, trade.exit_price),
+            rounding.format_amount(locale, trade.pnl),
+            escape_html(&trade.settlement_currency),
+            locale.format_fixed(trade.roe, 2),
+            rounding.format_amount(locale, point.equity),
+        ));
+    }
+
+    html.push_str("</table><p>The equity curve is attached as <code>equity.csv</code>.</p>");
+
+    html
+}
+
+/// Renders the equity curve of a report as CSV, e.g. to chart it in a spreadsheet. Formatted according to `locale`.
+pub fn build_equity_csv(equity: &[EquityPoint], locale: &ReportLocale) -> String {
+    let mut csv = to_csv_row(["timestamp".to_string(), "equity".to_string()], locale.csv_delimiter());
+
+    for point in equity {
+        csv.push_str(&equity_csv_row(point.timestamp, point.equity, locale));
+    }
+
+    csv
+}
+
+impl EmailReporter {
+    /// Builds an email reporter from env variables:
+    /// - `SMTP_HOST`, `SMTP_PORT` (defaults to 587 with STARTTLS, 465 uses implicit TLS), `SMTP_USERNAME` and `SMTP_PASSWORD`.
+    /// - `REPORT_EMAIL_FROM`: the sender address.
+    /// - `REPORT_EMAIL_RECIPIENTS`: a comma-separated list of recipient addresses.
+    /// - `REPORT_EMAIL_PERIODS`: a comma-separated list of `daily` and/or `weekly`. defaults to `daily`.
+    /// - `REPORT_DECIMAL_SEPARATOR`, `REPORT_DATE_FORMAT` and `REPORT_TIMEZONE`: how numbers and timestamps are formatted (see `ReportLocale`).
+    ///
+    /// Returns `None` if reports aren't configured (or misconfigured, which is logged).
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("SMTP_HOST").ok().filter(|host| !host.trim().is_empty())?;
+
+        let port = match std::env::var("SMTP_PORT") {
+            Ok(port) => match port.parse() {
+                Ok(port) => port,
+                Err(_) => {
+                    eprintln!("(EmailReporter::from_env) Invalid SMTP_PORT: {}", port);
+                    return None;
+                }
+            },
+            Err(_) => DEFAULT_SMTP_PORT,
+        };
+
+        let builder = if port == IMPLICIT_TLS_SMTP_PORT {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+        };
+
+        let mut builder = match builder {
+            Ok(builder) => builder.port(port),
+            Err(err) => {
+                eprintln!("(EmailReporter::from_env) Invalid SMTP_HOST {}: {}", host, err);
+                return None;
+            }
+        };
+
+        if let (Ok(username), Ok(password)) = (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = match std::env::var("REPORT_EMAIL_FROM").unwrap_or_default().parse() {
+            Ok(from) => from,
+            Err(err) => {
+                eprintln!("(EmailReporter::from_env) Invalid REPORT_EMAIL_FROM: {}", err);
+                return None;
+            }
+        };
+
+        let recipients: Vec<_> = std::env::var("REPORT_EMAIL_RECIPIENTS")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|address| !address.trim().is_empty())
+            .filter_map(|address| match address.trim().parse() {
+                Ok(recipient) => Some(recipient),
+                Err(err) => {
+                    eprintln!("(EmailReporter::from_env) Ignoring invalid recipient {}: {}", address, err);
+                    None
+                }
+            })
+            .collect();
+
+        if recipients.is_empty() {
+            eprintln!("(EmailReporter::from_env) REPORT_EMAIL_RECIPIENTS is not set. Email reports are disabled.");
+            return None;
+        }
+
+        let periods = std::env::var("REPORT_EMAIL_PERIODS")
+            .map(|periods| periods.split(',').filter_map(ReportPeriod::parse).collect())
+            .unwrap_or_else(|_| vec![ReportPeriod::Daily]);
+
+        Some(Self { transport: builder.build(), from, recipients, periods, locale: ReportLocale::from_env(), rounding: RoundingPolicy::from_env() })
+    }
+
+    /// Emails a report to all recipients, with the equity curve attached as CSV.
+    pub async fn send_report(&self, report: &PerformanceReport) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(format!("{:?} trading report: {} USDT over {} trades", report.period, self.rounding.format_amount(&self.locale, report.stats.total_pnl), report.stats.total_trades));
+
+        for recipient in &self.recipients {
+            builder = builder.to(recipient.clone());
+        }
+
+        let message = builder.multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::html(build_report_html(report, &self.locale, &self.rounding)))
+                .singlepart(Attachment::new("equity.csv".to_string()).body(build_equity_csv(&report.equity, &self.locale), ContentType::parse("text/csv")?))
+        )?;
+
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
+
+/// Builds the report of the trades closed between `from` and `to`.
+async fn build_report(
+    mongo_state: &MongoDBState,
+    period: ReportPeriod,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>
+) -> Result<PerformanceReport, mongodb::error::Error> {
+    let trades = mongo_state.fetch_closed_trades_between(from, to).await?;
+
+    // all values are reported in USDT, so that no conversion rate is required
+    let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+    let overview = mongo_state
+        .aggregate_stats_overview(doc! { "closeTimestamp": { "$gte": from.timestamp(), "$lt": to.timestamp() } }, &conversion, resolve_timezone(None), to)
+        .await?;
+
+    Ok(PerformanceReport {
+        period,
+        from,
+        to,
+        stats: overview.lifetime,
+        equity: calc_equity_curve(&trades),
+        trades,
+    })
+}
+
+/// Periodically sends the due performance reports via email (see `EmailReporter::from_env`).
+///
+/// When running multiple instances, only the leader sends reports.
+pub async fn start_report_mailer(app_state: Arc<AppState>) {
+    let Some(reporter) = EmailReporter::from_env() else {
+        return
+    };
+
+    let mut interval = tokio::time::interval(StdDuration::from_secs(REPORT_MAILER_INTERVAL_SECS));
+    let mut last_run = app_state.clock.now();
+
+    loop {
+        interval.tick().await;
+
+        let now = app_state.clock.now();
+
+        if !app_state.leadership.is_leader() {
+            last_run = now;
+            continue;
+        }
+
+        let mut succeeded = true;
+
+        for period in &reporter.periods {
+            let Some((from, to)) = calc_due_report_window(*period, last_run, now) else { continue };
+
+            let result = match build_report(&app_state.mongo_state, *period, from, to).await {
+                Ok(report) => reporter.send_report(&report).await,
+                Err(err) => Err(err.into()),
+            };
+
+            match result {
+                Ok(_) => println!("(start_report_mailer) Sent {:?} report to {} recipients", period, reporter.recipients.len()),
+                Err(err) => {
+                    eprintln!("(start_report_mailer) Failed to send {:?} report: {}", period, err);
+                    succeeded = false;
+                }
+            }
+        }
+
+        // only advance on success so that a failed report is retried on the next tick
+        if succeeded {
+            last_run = now;
+        }
+    }
+}
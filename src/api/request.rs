@@ -0,0 +1,30 @@
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{constants::{MAX_REQUEST_ID_LEN, REQUEST_ID_HEADER}, models::RequestId};
+
+/// Returns the request ID sent by the caller if it's valid (non-empty, printable ASCII and at most `MAX_REQUEST_ID_LEN` long),
+/// or generates a new one.
+pub fn resolve_request_id(incoming: Option<&str>) -> String {
+    match incoming.map(str::trim) {
+        Some(id) if !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.chars().all(|c| c.is_ascii_graphic()) => id.to_string(),
+        _ => ObjectId::new().to_hex(),
+    }
+}
+
+/// Assigns a request ID to every request, so that e.g. a misbehaving trade can be traced back to the exact alert that opened it.
+///
+/// The ID is available to handlers as an `Extension<RequestId>` and returned in the `X-Request-Id` response header.
+pub async fn propagate_request_id(mut request: Request, next: Next) -> Response {
+    let request_id = resolve_request_id(request.headers().get(REQUEST_ID_HEADER).and_then(|value| value.to_str().ok()));
+
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let mut response = next.run(request).await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
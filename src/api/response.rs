@@ -0,0 +1,66 @@
+use std::str::FromStr;
+
+use axum::Json;
+use hyper::StatusCode;
+
+use crate::models::{ApiResponse, RejectionReason, ResponseCode, ResponseVerbosity};
+
+impl From<RejectionReason> for ResponseCode {
+    fn from(reason: RejectionReason) -> Self {
+        match reason {
+            RejectionReason::InvalidPayload => ResponseCode::InvalidPayload,
+            RejectionReason::InvalidSecret => ResponseCode::InvalidSecret,
+            RejectionReason::InvalidTimestamp => ResponseCode::InvalidTimestamp,
+            RejectionReason::SymbolNotAllowed => ResponseCode::SymbolNotAllowed,
+            RejectionReason::MarketClosed => ResponseCode::MarketClosed,
+            RejectionReason::PositionConflict => ResponseCode::PositionConflict,
+            RejectionReason::LiveTradingDisabled => ResponseCode::LiveTradingDisabled,
+            RejectionReason::StrategyDisabled => ResponseCode::StrategyDisabled,
+            RejectionReason::AnomalyDetected => ResponseCode::AnomalyDetected,
+            RejectionReason::NoConversionRate => ResponseCode::NoConversionRate,
+            RejectionReason::Paused => ResponseCode::Paused,
+            RejectionReason::Cooldown => ResponseCode::Cooldown,
+            RejectionReason::ExchangeRejected => ResponseCode::ExchangeRejected,
+            RejectionReason::FilteredByScript => ResponseCode::FilteredByScript,
+            RejectionReason::FilteredByPlugin => ResponseCode::FilteredByPlugin,
+            RejectionReason::NoEdge => ResponseCode::NoEdge,
+            RejectionReason::RiskCapExceeded => ResponseCode::RiskCapExceeded,
+            RejectionReason::ExchangeMaintenance => ResponseCode::ExchangeMaintenance,
+        }
+    }
+}
+
+impl FromStr for ResponseVerbosity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "verbose" => Ok(ResponseVerbosity::Verbose),
+            "minimal" => Ok(ResponseVerbosity::Minimal),
+            _ => Err(format!("Unknown response verbosity: {}", s)),
+        }
+    }
+}
+
+impl ResponseVerbosity {
+    /// Reads the verbosity from the `RESPONSE_VERBOSITY` env variable (`verbose` or `minimal`). Defaults to `verbose`.
+    pub fn from_env() -> Self {
+        match std::env::var("RESPONSE_VERBOSITY") {
+            Ok(verbosity) => verbosity.parse().unwrap_or_else(|err| {
+                eprintln!("(ResponseVerbosity::from_env) {}. Defaulting to verbose.", err);
+                ResponseVerbosity::Verbose
+            }),
+            Err(_) => ResponseVerbosity::Verbose,
+        }
+    }
+
+    /// Strips the human-readable message from a response in minimal mode. Responses without a code keep their message,
+    /// since callers would have nothing else to go on.
+    pub fn apply<T>(self, (status_code, Json(mut response)): (StatusCode, Json<ApiResponse<T>>)) -> (StatusCode, Json<ApiResponse<T>>) {
+        if self == ResponseVerbosity::Minimal && response.code.is_some() {
+            response.message.clear();
+        }
+
+        (status_code, Json(response))
+    }
+}
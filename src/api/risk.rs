@@ -0,0 +1,550 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, str::FromStr, sync::{atomic::Ordering, Arc}};
+
+use axum::{Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use hyper::StatusCode;
+use mongodb::bson::{self, doc, from_document};
+
+use crate::{api::{calc_notional_value, calc_percentage_exits, calc_pnl, clamp_to_isolated_margin, fx::currency_conversion_stage, get_settlement_currency, is_liquidation_hit, split_pair, stats::resolve_timezone, to_coinbase_product_id}, constants::{DEFAULT_LEVERAGE, DEFAULT_NOTIONAL_VALUE, DEFAULT_PAPER_ACCOUNT_BALANCE, DEFAULT_VAR_WARNING_EQUITY_FRACTION, VAR_CONFIDENCE, VAR_LOOKBACK_DAYS, VAR_MIN_OBSERVATIONS}, models::{tradingview::TradingViewAlert, ActiveTrade, ApiResponse, AppState, ContractType, CurrencyConversion, DailyCandle, ExposureReport, MongoDBState, Notification, NotificationSeverity, ProductExposure, ReportingCurrency, RiskCapAction, StrategyParameters, StressTestRequest, StressTestResult, StressedPosition, TradeDirection}};
+
+impl MongoDBState {
+    /// Sums the realized PnL (in USDT) of the trades closed since `since`.
+    pub async fn sum_realized_pnl_since(&self, since: DateTime<Utc>) -> Result<f64, mongodb::error::Error> {
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+        let mut cursor = self.closed_trade_collection
+            .aggregate(vec![
+                doc! { "$match": { "closeTimestamp": { "$gte": since.timestamp() } } },
+                currency_conversion_stage(&conversion),
+                doc! { "$group": { "_id": null, "pnl": { "$sum": "$pnl" } } },
+            ])
+            .await?;
+
+        if cursor.advance().await? {
+            Ok(cursor.deserialize_current()?.get_f64("pnl").unwrap_or(0.0))
+        } else {
+            Ok(0.0)
+        }
+    }
+
+    /// Fetches the daily candles (UTC) of the products since `since` from their price ticks, oldest first.
+    pub async fn fetch_daily_candles(&self, products: &[String], since: DateTime<Utc>) -> Result<Vec<DailyCandle>, mongodb::error::Error> {
+        let mut cursor = self.price_tick_collection
+            .aggregate(vec![
+                doc! { "$match": { "product": { "$in": products }, "timestamp": { "$gte": bson::DateTime::from_millis(since.timestamp_millis()) } } },
+                doc! { "$sort": { "timestamp": 1 } },
+                doc! {
+                    "$group": {
+                        "_id": { "product": "$product", "day": { "$dateToString": { "format": "%Y-%m-%d", "date": "$timestamp" } } },
+                        "high": { "$max": "$price" },
+                        "low": { "$min": "$price" },
+                        "close": { "$last": "$price" },
+                    }
+                },
+                doc! { "$project": { "_id": 0, "product": "$_id.product", "day": "$_id.day", "high": 1, "low": 1, "close": 1 } },
+                doc! { "$sort": { "product": 1, "day": 1 } },
+            ])
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        let mut candles = Vec::new();
+
+        while cursor.advance().await? {
+            candles.push(from_document::<DailyCandle>(cursor.deserialize_current()?)?);
+        }
+
+        Ok(candles)
+    }
+}
+
+/// Calculates the start of the day `now` falls into in `timezone`, i.e. the last local midnight.
+///
+/// If midnight doesn't exist on that day (because a DST transition skips it), the day starts at the first valid local time after it.
+pub fn calc_day_start(now: DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+    let date = now.with_timezone(&timezone).date_naive();
+
+    (0..=1)
+        .find_map(|hour| date.and_hms_opt(hour, 0, 0)?.and_local_timezone(timezone).earliest())
+        .map(|day_start| day_start.with_timezone(&Utc))
+}
+
+/// Pauses the execution of alerts if the realized loss of the current day (in the `REPORT_TIMEZONE`) reached the `DAILY_LOSS_LIMIT_USDT` env variable
+/// (a positive amount of USDT). Called whenever a trade is closed.
+///
+/// The execution stays paused until an operator resumes it (`/resume` command).
+pub async fn enforce_daily_loss_limit(app_state: &AppState) {
+    let Some(limit) = std::env::var("DAILY_LOSS_LIMIT_USDT").ok().and_then(|limit| limit.parse::<f64>().ok()) else {
+        return
+    };
+
+    let Some(midnight) = calc_day_start(app_state.clock.now(), resolve_timezone(None)) else {
+        return
+    };
+
+    let pnl = match app_state.mongo_state.sum_realized_pnl_since(midnight).await {
+        Ok(pnl) => pnl,
+        Err(err) => {
+            eprintln!("(enforce_daily_loss_limit) Failed to sum today's PnL: {}", err);
+            return;
+        }
+    };
+
+    // only notify once, when the execution gets paused
+    if pnl <= -limit.abs() && !app_state.paused.swap(true, Ordering::SeqCst) {
+        app_state.notifier.notify(Notification::new(
+            NotificationSeverity::Critical,
+            "Daily loss limit hit",
+            format!("Today's realized PnL is {:.2} USDT (limit: -{:.2} USDT). The execution of alerts is paused until resumed.", pnl, limit.abs())
+        ));
+    }
+}
+
+/// Returns the starting balance (in USDT) of the paper account, set by the `PAPER_ACCOUNT_BALANCE_USDT` env variable
+/// (or `DEFAULT_PAPER_ACCOUNT_BALANCE`).
+pub fn paper_account_balance() -> f64 {
+    std::env::var("PAPER_ACCOUNT_BALANCE_USDT")
+        .ok()
+        .and_then(|balance| balance.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_PAPER_ACCOUNT_BALANCE)
+}
+
+impl StressTestRequest {
+    /// Returns the price change (in percent) of `currency` under the shocks of the request.
+    pub fn shock_of(&self, currency: &str) -> f64 {
+        self.shocks
+            .iter()
+            .find(|(shocked_currency, _)| shocked_currency.eq_ignore_ascii_case(currency))
+            .map_or(self.default_shock, |(_, shock)| *shock)
+    }
+
+    /// Checks that all shocks are finite and leave prices above zero.
+    pub fn validate(&self) -> Result<(), String> {
+        let shocks = self.shocks.iter().map(|(currency, shock)| (currency.as_str(), *shock));
+
+        match shocks.chain([("the default", self.default_shock)]).find(|(_, shock)| !shock.is_finite() || *shock <= -100.0) {
+            Some((currency, shock)) => Err(format!("Invalid shock of {}: {}. Prices can't fall by 100% or more.", currency, shock)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Applies a price change of `shock_percentage` to an open trade priced at `current_price`.
+///
+/// `usdt_rates` are the values of 1 unit of the trade's settlement currency in USDT before and after the shocks
+/// (which differ for inverse contracts, whose settlement currency is the shocked base currency).
+///
+/// Cross margin trades are treated as isolated ones, i.e. they're liquidated at once.
+pub fn stress_position(trade: &ActiveTrade, current_price: f64, shock_percentage: f64, (usdt_rate, shocked_usdt_rate): (f64, f64)) -> StressedPosition {
+    let shocked_price = current_price * (1.0 + shock_percentage / 100.0);
+    let leverage: f64 = trade.leverage.into();
+    let liquidated = is_liquidation_hit(trade, shocked_price);
+
+    let current_pnl = calc_pnl(trade.entry_price, current_price, trade.quantity, 0.0, 0.0, &trade.direction, &trade.contract_type);
+    let shocked_pnl = calc_pnl(trade.entry_price, shocked_price, trade.quantity, 0.0, 0.0, &trade.direction, &trade.contract_type);
+    let shocked_pnl = if liquidated {
+        clamp_to_isolated_margin(shocked_pnl, trade.quantity, trade.entry_price, leverage, &trade.contract_type)
+    } else {
+        shocked_pnl
+    };
+
+    let margin = calc_notional_value(trade.quantity, trade.entry_price, &trade.contract_type) / leverage;
+
+    StressedPosition {
+        id: trade.id.to_hex(),
+        alert_name: trade.alert_name.clone(),
+        pair: trade.pair.clone(),
+        direction: trade.direction.clone(),
+        current_price,
+        shocked_price,
+        current_pnl: current_pnl * usdt_rate,
+        shocked_pnl: shocked_pnl * shocked_usdt_rate,
+        margin: margin * shocked_usdt_rate,
+        liquidated,
+    }
+}
+
+/// Sums up the stressed positions into the equity and margin usage of the paper account.
+pub fn summarize_stress_test(positions: Vec<StressedPosition>, unpriced_pairs: Vec<String>, account_balance: f64, realized_pnl: f64) -> StressTestResult {
+    let current_unrealized_pnl = positions.iter().map(|position| position.current_pnl).sum::<f64>();
+    let shocked_unrealized_pnl = positions.iter().map(|position| position.shocked_pnl).sum::<f64>();
+    // liquidated positions release their (lost) margin
+    let margin = positions.iter().filter(|position| !position.liquidated).map(|position| position.margin).sum::<f64>();
+    let shocked_equity = account_balance + realized_pnl + shocked_unrealized_pnl;
+
+    StressTestResult {
+        account_balance,
+        realized_pnl,
+        current_unrealized_pnl,
+        shocked_unrealized_pnl,
+        current_equity: account_balance + realized_pnl + current_unrealized_pnl,
+        shocked_equity,
+        margin,
+        margin_usage_percentage: (shocked_equity > 0.0).then(|| margin / shocked_equity * 100.0),
+        positions,
+        unpriced_pairs,
+    }
+}
+
+impl AppState {
+    /// Applies the shocks of `request` to the active trades at the latest prices.
+    ///
+    /// Returns the stressed positions, alongside the pairs of the trades without a price or USDT rate.
+    pub fn stress_active_trades(&self, request: &StressTestRequest) -> (Vec<StressedPosition>, Vec<String>) {
+        let mut trades: Vec<ActiveTrade> = self.active_trades.lock().unwrap().values().cloned().collect();
+        trades.sort_by_key(|trade| trade.open_timestamp);
+
+        let prices = self.latest_prices.lock().unwrap().clone();
+        let mut positions = Vec::new();
+        let mut unpriced_pairs = Vec::new();
+
+        for trade in trades {
+            let price = to_coinbase_product_id(&trade.pair).and_then(|product_id| prices.get(&product_id).copied());
+            let base = split_pair(&trade.pair).map(|(base, _)| base);
+            let settlement_currency = get_settlement_currency(&trade.pair, &trade.contract_type);
+            let usdt_rate = settlement_currency.as_deref().and_then(|currency| self.usdt_value_of(currency));
+
+            let (Some(price), Some(base), Some(settlement_currency), Some(usdt_rate)) = (price, base, settlement_currency, usdt_rate) else {
+                if !unpriced_pairs.contains(&trade.pair) {
+                    unpriced_pairs.push(trade.pair.clone());
+                }
+                continue;
+            };
+
+            let shocked_usdt_rate = if settlement_currency == "USDT" {
+                usdt_rate
+            } else {
+                usdt_rate * (1.0 + request.shock_of(&settlement_currency) / 100.0)
+            };
+
+            positions.push(stress_position(&trade, price, request.shock_of(&base), (usdt_rate, shocked_usdt_rate)));
+        }
+
+        (positions, unpriced_pairs)
+    }
+}
+
+/// Applies hypothetical price shocks (e.g. `{"shocks": {"BTC": -10, "ETH": 5}}`) to the open trades and the paper account,
+/// returning the resulting PnL, margin usage and which trades would be liquidated.
+///
+/// Nothing is changed; the endpoint is a what-if tool for reviewing the risk of the open positions.
+pub async fn stress_test_positions(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Json(request): Json<StressTestRequest>,
+) -> (StatusCode, Json<ApiResponse<StressTestResult>>) {
+    if let Err(err) = request.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(stress_test_positions) {}", err),
+                data: None
+            })
+        )
+    }
+
+    let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+    let realized_pnl = match app_state.mongo_state.sum_closed_pnl(doc! {}, &conversion).await {
+        Ok(realized_pnl) => realized_pnl,
+        Err(err) => {
+            eprintln!("(stress_test_positions) Failed to sum closed PnL: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(stress_test_positions) Failed to sum closed PnL: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let (positions, unpriced_pairs) = app_state.stress_active_trades(&request);
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(stress_test_positions) Stress tested open trades successfully.".to_string(),
+            data: Some(summarize_stress_test(positions, unpriced_pairs, paper_account_balance(), realized_pnl))
+        })
+    )
+}
+
+/// Returns the fraction of the paper equity that the value-at-risk may reach before warning, set by the `VAR_WARNING_EQUITY_FRACTION` env variable
+/// (or `DEFAULT_VAR_WARNING_EQUITY_FRACTION`).
+pub fn var_warning_equity_fraction() -> f64 {
+    std::env::var("VAR_WARNING_EQUITY_FRACTION")
+        .ok()
+        .and_then(|fraction| fraction.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_VAR_WARNING_EQUITY_FRACTION)
+}
+
+/// Calculates the daily returns of each product from the closes of its daily candles (oldest first), keyed by the day of the later close.
+pub fn calc_daily_returns(candles: &[DailyCandle]) -> HashMap<String, BTreeMap<String, f64>> {
+    let mut returns: HashMap<String, BTreeMap<String, f64>> = HashMap::new();
+
+    for pair in candles.windows(2) {
+        let (previous, close) = (&pair[0], &pair[1]);
+
+        if previous.product == close.product && previous.close > 0.0 {
+            returns.entry(close.product.clone()).or_default().insert(close.day.clone(), close.close / previous.close - 1.0);
+        }
+    }
+
+    returns
+}
+
+/// Estimates the one-day value-at-risk of the exposures at `confidence` by historical simulation: the net exposure to each product is
+/// revalued with the daily returns of every past day, and the loss at the `1 - confidence` quantile of those days is returned.
+///
+/// Returns the value-at-risk (never negative) and the number of simulated days, or `None` if fewer than `VAR_MIN_OBSERVATIONS` days are available.
+pub fn calc_historical_var(exposures: &[ProductExposure], returns: &HashMap<String, BTreeMap<String, f64>>, confidence: f64) -> Option<(f64, usize)> {
+    let days: BTreeSet<&String> = exposures
+        .iter()
+        .filter_map(|exposure| returns.get(&exposure.product))
+        .flat_map(|product_returns| product_returns.keys())
+        .collect();
+
+    if days.len() < VAR_MIN_OBSERVATIONS {
+        return None;
+    }
+
+    let mut pnls: Vec<f64> = days
+        .iter()
+        .map(|day| {
+            exposures
+                .iter()
+                .map(|exposure| exposure.net * returns.get(&exposure.product).and_then(|product_returns| product_returns.get(*day)).copied().unwrap_or(0.0))
+                .sum::<f64>()
+        })
+        .collect();
+
+    pnls.sort_by(|a, b| a.total_cmp(b));
+
+    let index = (((1.0 - confidence) * pnls.len() as f64).floor() as usize).min(pnls.len() - 1);
+
+    Some(((-pnls[index]).max(0.0), pnls.len()))
+}
+
+impl AppState {
+    /// Calculates the exposure (in USDT) of the active trades to each product at the latest prices.
+    ///
+    /// Returns the exposures ordered by product, alongside the pairs of the trades without a price or USDT rate.
+    pub fn calc_product_exposures(&self) -> (Vec<ProductExposure>, Vec<String>) {
+        let trades: Vec<ActiveTrade> = self.active_trades.lock().unwrap().values().cloned().collect();
+        let prices = self.latest_prices.lock().unwrap().clone();
+
+        let mut exposures: BTreeMap<String, ProductExposure> = BTreeMap::new();
+        let mut unpriced_pairs = Vec::new();
+
+        for trade in trades {
+            let product = to_coinbase_product_id(&trade.pair);
+            let price = product.as_ref().and_then(|product| prices.get(product).copied());
+            let quote_usdt_value = split_pair(&trade.pair).and_then(|(_, quote)| self.usdt_value_of(&quote));
+
+            let (Some(product), Some(price), Some(quote_usdt_value)) = (product, price, quote_usdt_value) else {
+                if !unpriced_pairs.contains(&trade.pair) {
+                    unpriced_pairs.push(trade.pair.clone());
+                }
+                continue;
+            };
+
+            // the quantity of inverse contracts already is their notional value in the quote currency
+            let notional_value = match trade.contract_type {
+                ContractType::Linear => trade.quantity * price,
+                ContractType::Inverse => trade.quantity,
+            } * quote_usdt_value;
+
+            let exposure = exposures.entry(product.clone()).or_insert_with(|| ProductExposure { product, long: 0.0, short: 0.0, net: 0.0 });
+
+            match trade.direction {
+                TradeDirection::Long => exposure.long += notional_value,
+                TradeDirection::Short => exposure.short += notional_value,
+            }
+
+            exposure.net = exposure.long - exposure.short;
+        }
+
+        (exposures.into_values().collect(), unpriced_pairs)
+    }
+}
+
+/// Returns the exposure of the open trades to each product and their one-day value-at-risk, simulated from the daily closes
+/// of the last `VAR_LOOKBACK_DAYS` in the price ticks.
+///
+/// Warns (in the response) if the value-at-risk exceeds the `VAR_WARNING_EQUITY_FRACTION` of the paper equity.
+pub async fn get_exposure(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<ExposureReport>>) {
+    let (exposures, unpriced_pairs) = app_state.calc_product_exposures();
+    let products: Vec<String> = exposures.iter().map(|exposure| exposure.product.clone()).collect();
+    let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+    let since = app_state.clock.now() - Duration::days(VAR_LOOKBACK_DAYS);
+
+    let result = match app_state.mongo_state.sum_closed_pnl(doc! {}, &conversion).await {
+        Ok(realized_pnl) => app_state.mongo_state.fetch_daily_candles(&products, since).await.map(|candles| (realized_pnl, candles)),
+        Err(err) => Err(err),
+    };
+
+    let (realized_pnl, candles) = match result {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("(get_exposure) Failed to fetch PnL or daily candles: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_exposure) Failed to fetch PnL or daily candles: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let equity = paper_account_balance() + realized_pnl + app_state.calc_unrealized_pnl();
+    let var = calc_historical_var(&exposures, &calc_daily_returns(&candles), VAR_CONFIDENCE);
+    let value_at_risk = var.map(|(value_at_risk, _)| value_at_risk);
+    let value_at_risk_percentage = value_at_risk.filter(|_| equity > 0.0).map(|value_at_risk| value_at_risk / equity * 100.0);
+    let warning_fraction = var_warning_equity_fraction();
+
+    let warning = value_at_risk_percentage
+        .filter(|percentage| *percentage > warning_fraction * 100.0)
+        .map(|percentage| format!("The value-at-risk is {:.2}% of the equity, exceeding the limit of {:.2}%.", percentage, warning_fraction * 100.0));
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_exposure) Calculated exposure successfully.".to_string(),
+            data: Some(ExposureReport {
+                gross_exposure: exposures.iter().map(|exposure| exposure.long + exposure.short).sum(),
+                net_exposure: exposures.iter().map(|exposure| exposure.net).sum(),
+                exposures,
+                equity,
+                value_at_risk,
+                confidence: VAR_CONFIDENCE,
+                observations: var.map_or(0, |(_, observations)| observations),
+                value_at_risk_percentage,
+                warning,
+                unpriced_pairs,
+            })
+        })
+    )
+}
+
+impl FromStr for RiskCapAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "shrink" => Ok(RiskCapAction::Shrink),
+            "reject" => Ok(RiskCapAction::Reject),
+            _ => Err(format!("Unknown risk cap action: {}", s)),
+        }
+    }
+}
+
+impl RiskCapAction {
+    /// Reads the action from the `RISK_CAP_ACTION` env variable (`shrink` or `reject`). Defaults to `shrink`.
+    pub fn from_env() -> Self {
+        match std::env::var("RISK_CAP_ACTION") {
+            Ok(action) => action.parse().unwrap_or_else(|err| {
+                eprintln!("(RiskCapAction::from_env) {}. Defaulting to shrink.", err);
+                RiskCapAction::Shrink
+            }),
+            Err(_) => RiskCapAction::Shrink,
+        }
+    }
+}
+
+/// Returns the fraction of the paper equity that a trade may lose at its stop loss, set by the `MAX_RISK_PER_TRADE_FRACTION` env variable.
+/// `None` if trades aren't capped.
+pub fn max_risk_per_trade_fraction() -> Option<f64> {
+    std::env::var("MAX_RISK_PER_TRADE_FRACTION")
+        .ok()
+        .and_then(|fraction| fraction.parse::<f64>().ok())
+        .filter(|fraction| *fraction > 0.0)
+}
+
+/// Calculates the loss (in USDT) of a trade of `notional_value` if it's exited at its stop loss.
+///
+/// Without a stop loss (or with one beyond the liquidation price), the whole margin of the trade is at risk.
+pub fn calc_stop_loss_risk(entry_price: f64, stop_loss: Option<f64>, direction: &TradeDirection, notional_value: f64, leverage: f64) -> f64 {
+    let margin = notional_value / leverage;
+
+    let Some(stop_loss) = stop_loss.filter(|_| entry_price > 0.0) else {
+        return margin
+    };
+
+    let distance = match direction {
+        TradeDirection::Long => (entry_price - stop_loss) / entry_price,
+        TradeDirection::Short => (stop_loss - entry_price) / entry_price,
+    };
+
+    (notional_value * distance.max(0.0)).min(margin)
+}
+
+/// Shrinks the notional value of a trade so that its loss at the stop loss is `max_risk` (in USDT).
+/// `None` if the risk is already within the cap.
+pub fn cap_notional_value(notional_value: f64, risk: f64, max_risk: f64) -> Option<f64> {
+    (risk > max_risk).then(|| notional_value * max_risk / risk)
+}
+
+impl AppState {
+    /// Enforces the per-trade risk cap (`MAX_RISK_PER_TRADE_FRACTION` of the paper equity) on an alert about to be executed,
+    /// after its strategy, filter script and plugins sized it.
+    ///
+    /// A trade that would lose more at its stop loss is shrunk to the cap, or rejected with the reason if `RISK_CAP_ACTION` is `reject`.
+    /// The cap isn't enforced if the paper equity can't be calculated.
+    pub async fn enforce_risk_cap(&self, alert: &TradingViewAlert, parameters: &mut StrategyParameters) -> Result<(), String> {
+        let Some(max_risk_fraction) = max_risk_per_trade_fraction() else {
+            return Ok(())
+        };
+
+        let equity = match self.paper_equity().await {
+            Ok(equity) => equity,
+            Err(err) => {
+                eprintln!("(enforce_risk_cap) Failed to calculate the paper equity: {}", err);
+                return Ok(());
+            }
+        };
+
+        let direction: TradeDirection = alert.signal.into();
+        let notional_value = parameters.notional_value.unwrap_or(DEFAULT_NOTIONAL_VALUE);
+        let leverage: f64 = parameters.leverage.unwrap_or(DEFAULT_LEVERAGE).into();
+        // the stop loss of the alert takes precedence over the one of the strategy, as when the trade is built
+        let stop_loss = alert.stop_loss.or(calc_percentage_exits(alert.price, &direction, None, parameters.stop_loss_percentage).1);
+
+        let risk = calc_stop_loss_risk(alert.price, stop_loss, &direction, notional_value, leverage);
+        let max_risk = equity.max(0.0) * max_risk_fraction;
+
+        let Some(capped_notional_value) = cap_notional_value(notional_value, risk, max_risk) else {
+            return Ok(())
+        };
+
+        match RiskCapAction::from_env() {
+            RiskCapAction::Shrink if capped_notional_value > 0.0 => {
+                println!(
+                    "(enforce_risk_cap) Shrinking the trade of {} on {} from {:.2} to {:.2} USDT, since it risks {:.2} USDT (cap: {:.2} USDT).",
+                    alert.name, alert.pair, notional_value, capped_notional_value, risk, max_risk
+                );
+
+                parameters.notional_value = Some(capped_notional_value);
+                Ok(())
+            }
+            _ => Err(format!(
+                "The trade of {} on {} risks {:.2} USDT at its stop loss, exceeding the cap of {:.2} USDT ({:.2}% of the equity).",
+                alert.name, alert.pair, risk, max_risk, max_risk_fraction * 100.0
+            )),
+        }
+    }
+}
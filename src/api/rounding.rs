@@ -0,0 +1,147 @@
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{constants::{DEFAULT_DISPLAY_DECIMALS, MAX_ROUNDING_DECIMALS, QUANTITY_DECIMALS}, models::{ReportLocale, RoundingMode, RoundingPolicy}};
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "half_even" => Ok(RoundingMode::HalfEven),
+            "half_away_from_zero" | "half_up" => Ok(RoundingMode::HalfAwayFromZero),
+            _ => Err(format!("Unknown rounding mode: {}", s)),
+        }
+    }
+}
+
+/// Rounds `value` to `decimals` decimals (at most `MAX_ROUNDING_DECIMALS`), breaking ties by `mode`.
+///
+/// Values within floating point error of a tie (e.g. `1.005`, which is stored as `1.00499999...`) count as ties.
+pub fn round_decimals(value: f64, decimals: u32, mode: RoundingMode) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+
+    let factor = 10_f64.powi(decimals.min(MAX_ROUNDING_DECIMALS) as i32);
+    let scaled = value * factor;
+    let floor = scaled.floor();
+
+    let rounded = if ((scaled - floor) - 0.5).abs() < 1e-9 {
+        match mode {
+            RoundingMode::HalfEven if (floor as i64) % 2 == 0 => floor,
+            RoundingMode::HalfEven => floor + 1.0,
+            RoundingMode::HalfAwayFromZero if value < 0.0 => floor,
+            RoundingMode::HalfAwayFromZero => floor + 1.0,
+        }
+    } else {
+        scaled.round()
+    };
+
+    rounded / factor
+}
+
+/// Parses the price decimals of symbols, e.g. `BTCUSDT:2,ETHBTC:5`.
+pub fn parse_symbol_decimals(value: &str) -> Result<HashMap<String, u32>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (symbol, decimals) = entry.split_once(':').ok_or_else(|| format!("Expected <symbol>:<decimals>, got {:?}", entry))?;
+            let decimals = decimals
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|decimals| *decimals <= MAX_ROUNDING_DECIMALS)
+                .ok_or_else(|| format!("Invalid decimals of {}: {:?}", symbol.trim(), decimals))?;
+
+            Ok((symbol.trim().to_uppercase(), decimals))
+        })
+        .collect()
+}
+
+/// Reads a rounding mode from the env variable `name`, falling back to `default` if it's unset or invalid.
+fn rounding_mode_from_env(name: &str, default: RoundingMode) -> RoundingMode {
+    match std::env::var(name) {
+        Ok(value) => value.parse().unwrap_or_else(|err| {
+            eprintln!("(RoundingPolicy::from_env) Invalid {}: {}. Using {:?}.", name, err, default);
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+impl Default for RoundingPolicy {
+    fn default() -> Self {
+        Self {
+            accounting_mode: RoundingMode::HalfEven,
+            display_mode: RoundingMode::HalfAwayFromZero,
+            display_decimals: DEFAULT_DISPLAY_DECIMALS,
+            price_decimals: HashMap::new(),
+        }
+    }
+}
+
+impl RoundingPolicy {
+    /// Reads the policy from the `ACCOUNTING_ROUNDING_MODE` (defaults to half even), `DISPLAY_ROUNDING_MODE` (defaults to half away from zero),
+    /// `DISPLAY_DECIMALS` and `SYMBOL_PRICE_DECIMALS` env variables.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        let display_decimals = match std::env::var("DISPLAY_DECIMALS") {
+            Ok(value) => match value.trim().parse::<u32>() {
+                Ok(decimals) if decimals <= MAX_ROUNDING_DECIMALS => decimals,
+                _ => {
+                    eprintln!("(RoundingPolicy::from_env) Invalid DISPLAY_DECIMALS {:?}; using {}.", value, default.display_decimals);
+                    default.display_decimals
+                }
+            },
+            Err(_) => default.display_decimals,
+        };
+
+        let price_decimals = match std::env::var("SYMBOL_PRICE_DECIMALS") {
+            Ok(value) => parse_symbol_decimals(&value).unwrap_or_else(|err| {
+                eprintln!("(RoundingPolicy::from_env) {}. Displaying prices with full precision.", err);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Self {
+            accounting_mode: rounding_mode_from_env("ACCOUNTING_ROUNDING_MODE", default.accounting_mode),
+            display_mode: rounding_mode_from_env("DISPLAY_ROUNDING_MODE", default.display_mode),
+            display_decimals,
+            price_decimals,
+        }
+    }
+
+    /// Rounds an order quantity to `QUANTITY_DECIMALS` decimals, as it's booked.
+    pub fn round_quantity(&self, quantity: f64) -> f64 {
+        round_decimals(quantity, QUANTITY_DECIMALS, self.accounting_mode)
+    }
+
+    /// Rounds an amount (e.g. PnL or fees) for display.
+    pub fn display_amount(&self, value: f64) -> f64 {
+        round_decimals(value, self.display_decimals, self.display_mode)
+    }
+
+    /// Rounds a price of `pair` for display, if the pair has price decimals.
+    pub fn display_price(&self, pair: &str, price: f64) -> f64 {
+        match self.price_decimals.get(&pair.to_uppercase()) {
+            Some(decimals) => round_decimals(price, *decimals, self.display_mode),
+            None => price,
+        }
+    }
+
+    /// Formats an amount for display in a report.
+    pub fn format_amount(&self, locale: &ReportLocale, value: f64) -> String {
+        locale.format_fixed(self.display_amount(value), self.display_decimals as usize)
+    }
+
+    /// Formats a price of `pair` for display in a report.
+    pub fn format_price(&self, locale: &ReportLocale, pair: &str, price: f64) -> String {
+        match self.price_decimals.get(&pair.to_uppercase()) {
+            Some(decimals) => locale.format_fixed(self.display_price(pair, price), *decimals as usize),
+            None => locale.format_number(price),
+        }
+    }
+}
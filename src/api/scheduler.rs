@@ -0,0 +1,103 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+
+use crate::{constants::STRATEGY_SCHEDULER_INTERVAL_SECS, models::{AuditAction, AuditActor, MongoDBState, SharedClock, Strategy}};
+
+/// Parses a cron expression evaluated in UTC.
+///
+/// Standard 5-field expressions (minute, hour, day of month, month, weekday) are accepted alongside the
+/// 6/7-field format (with leading seconds and optional trailing year), in which case they trigger at second 0.
+pub fn parse_cron_expression(expression: &str) -> Result<Schedule, cron::error::Error> {
+    let expression = expression.trim();
+
+    if expression.split_whitespace().count() == 5 {
+        Schedule::from_str(&format!("0 {}", expression))
+    } else {
+        Schedule::from_str(expression)
+    }
+}
+
+/// Determines whether a strategy should be enabled (`Some(true)`) or disabled (`Some(false)`) based on the
+/// enable/disable schedules that triggered after `from` up until (and including) `to`.
+///
+/// If both triggered, the most recent one wins (disabling wins ties). Returns `None` if neither triggered.
+pub fn calc_scheduled_state(
+    enable_schedule: Option<&Schedule>,
+    disable_schedule: Option<&Schedule>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>
+) -> Option<bool> {
+    let last_trigger = |schedule: Option<&Schedule>| {
+        schedule.and_then(|schedule| schedule.after(&from).take_while(|time| *time <= to).last())
+    };
+
+    match (last_trigger(enable_schedule), last_trigger(disable_schedule)) {
+        (Some(enabled_at), Some(disabled_at)) => Some(enabled_at > disabled_at),
+        (Some(_), None) => Some(true),
+        (None, Some(_)) => Some(false),
+        (None, None) => None,
+    }
+}
+
+/// Applies the enable/disable schedules of a single strategy that triggered between `from` and `to`.
+async fn apply_strategy_schedule(mongo_state: &MongoDBState, strategy: &Strategy, from: DateTime<Utc>, to: DateTime<Utc>) {
+    // invalid expressions are rejected by the strategy API, so they're only skipped here
+    let enable_schedule = strategy.enable_cron.as_deref().and_then(|expression| parse_cron_expression(expression).ok());
+    let disable_schedule = strategy.disable_cron.as_deref().and_then(|expression| parse_cron_expression(expression).ok());
+
+    let Some(enabled) = calc_scheduled_state(enable_schedule.as_ref(), disable_schedule.as_ref(), from, to) else { return };
+
+    if enabled == strategy.enabled {
+        return;
+    }
+
+    match mongo_state.set_strategy_enabled(&strategy.name, enabled, to).await {
+        Ok(_) => {
+            println!("(apply_strategy_schedule) {} strategy {}", if enabled { "Enabled" } else { "Disabled" }, strategy.name);
+
+            let (action, expression) = if enabled {
+                (AuditAction::Enabled, &strategy.enable_cron)
+            } else {
+                (AuditAction::Disabled, &strategy.disable_cron)
+            };
+
+            mongo_state.record_audit(
+                AuditActor::Scheduler,
+                action,
+                &strategy.name,
+                expression.as_ref().map(|expression| format!("Triggered by cron expression `{}`", expression)),
+                None,
+                to
+            ).await;
+        }
+        Err(err) => eprintln!("(apply_strategy_schedule) Failed to update strategy {}: {}", strategy.name, err),
+    }
+}
+
+/// Periodically evaluates the enable/disable cron expressions of all strategies and toggles them accordingly.
+///
+/// The schedules are evaluated against `clock`, so that a simulated clock triggers them deterministically.
+pub async fn start_strategy_scheduler(mongo_state: Arc<MongoDBState>, clock: SharedClock) {
+    let mut interval = tokio::time::interval(Duration::from_secs(STRATEGY_SCHEDULER_INTERVAL_SECS));
+    let mut last_run = clock.now();
+
+    loop {
+        interval.tick().await;
+
+        let now = clock.now();
+
+        match mongo_state.fetch_scheduled_strategies().await {
+            Ok(strategies) => {
+                for strategy in &strategies {
+                    apply_strategy_schedule(&mongo_state, strategy, last_run, now).await;
+                }
+
+                // only advance on success so that triggers aren't missed while the database is unreachable
+                last_run = now;
+            }
+            Err(err) => eprintln!("(start_strategy_scheduler) Failed to fetch strategies: {}", err),
+        }
+    }
+}
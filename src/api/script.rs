@@ -0,0 +1,168 @@
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+
+use crate::{constants::{MAX_SCRIPT_ARRAY_SIZE, MAX_SCRIPT_CALL_LEVELS, MAX_SCRIPT_EXPR_DEPTH, MAX_SCRIPT_FUNCTION_EXPR_DEPTH, MAX_SCRIPT_LENGTH, MAX_SCRIPT_MAP_SIZE, MAX_SCRIPT_OPERATIONS, MAX_SCRIPT_STRING_SIZE}, models::{tradingview::TradingViewAlert, ActiveTrade, FilterDecision, FilterOverrides, StrategyParameters, TradeDirection, TradeSignal}};
+
+impl FilterOverrides {
+    /// Applies the overrides to the alert and the trading parameters of its strategy.
+    pub fn apply(self, alert: &mut TradingViewAlert, parameters: &mut StrategyParameters) {
+        alert.take_profit = self.take_profit.or(alert.take_profit);
+        alert.stop_loss = self.stop_loss.or(alert.stop_loss);
+        parameters.notional_value = self.notional_value.or(parameters.notional_value);
+    }
+}
+
+/// Builds the Rhai engine that filter scripts are compiled and run with, limited so that a faulty script can't stall an alert
+/// or exhaust the memory.
+fn filter_script_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+    engine.set_max_call_levels(MAX_SCRIPT_CALL_LEVELS);
+    engine.set_max_string_size(MAX_SCRIPT_STRING_SIZE);
+    engine.set_max_array_size(MAX_SCRIPT_ARRAY_SIZE);
+    engine.set_max_map_size(MAX_SCRIPT_MAP_SIZE);
+    engine.set_max_expr_depths(MAX_SCRIPT_EXPR_DEPTH, MAX_SCRIPT_FUNCTION_EXPR_DEPTH);
+
+    engine
+}
+
+/// Compiles a filter script, failing if it's too long or contains a syntax error.
+pub fn compile_filter_script(engine: &Engine, source: &str) -> Result<AST, String> {
+    if source.len() > MAX_SCRIPT_LENGTH {
+        return Err(format!("The script exceeds the maximum length of {} bytes.", MAX_SCRIPT_LENGTH))
+    }
+
+    engine.compile(source).map_err(|err| err.to_string())
+}
+
+/// Validates a filter script before it's saved to a strategy.
+pub fn validate_filter_script(source: &str) -> Result<(), String> {
+    compile_filter_script(&filter_script_engine(), source).map(|_| ())
+}
+
+/// Runs the filter script of a strategy against an alert and decides whether (and how) the alert is executed.
+///
+/// The script has access to the following variables:
+/// - `alert`: the alert (`name`, `signal`, `pair`, `price`, `take_profit`, `stop_loss`, `experiment` and `indicators`).
+/// - `price`: the latest market price of the pair, or `()` if none was received yet.
+/// - `positions`: the open trades (`alert_name`, `pair`, `direction`, `entry_price`, `quantity`, `take_profit` and `stop_loss`).
+///
+/// It evaluates to `true` (or `()`) to execute the alert, `false` or a reason (string) to reject it,
+/// or a map with any of `allow`, `reason`, `take_profit`, `stop_loss` and `notional_value` to modify it.
+pub fn run_filter_script(
+    source: &str,
+    alert: &TradingViewAlert,
+    market_price: Option<f64>,
+    positions: &[ActiveTrade]
+) -> Result<FilterDecision, String> {
+    let engine = filter_script_engine();
+    let ast = compile_filter_script(&engine, source)?;
+
+    let mut scope = Scope::new();
+    scope.push_constant("alert", alert_to_map(alert));
+    scope.push_constant("price", market_price.map_or(Dynamic::UNIT, Dynamic::from_float));
+    scope.push_constant("positions", positions.iter().map(|trade| Dynamic::from_map(trade_to_map(trade))).collect::<rhai::Array>());
+
+    let result = engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast).map_err(|err| err.to_string())?;
+
+    to_filter_decision(result)
+}
+
+/// Converts an alert into the `alert` variable of a filter script.
+fn alert_to_map(alert: &TradingViewAlert) -> Map {
+    let indicators: Map = alert.indicators
+        .iter()
+        .map(|(name, value)| (name.as_str().into(), Dynamic::from_float(*value)))
+        .collect();
+
+    let signal = match alert.signal {
+        TradeSignal::Buy => "buy",
+        TradeSignal::Sell => "sell",
+    };
+
+    let mut map = Map::new();
+    map.insert("name".into(), alert.name.clone().into());
+    map.insert("signal".into(), signal.into());
+    map.insert("pair".into(), alert.pair.clone().into());
+    map.insert("price".into(), Dynamic::from_float(alert.price));
+    map.insert("take_profit".into(), alert.take_profit.map_or(Dynamic::UNIT, Dynamic::from_float));
+    map.insert("stop_loss".into(), alert.stop_loss.map_or(Dynamic::UNIT, Dynamic::from_float));
+    map.insert("experiment".into(), alert.experiment.clone().map_or(Dynamic::UNIT, Dynamic::from));
+    map.insert("indicators".into(), Dynamic::from_map(indicators));
+
+    map
+}
+
+/// Converts an open trade into an entry of the `positions` variable of a filter script.
+fn trade_to_map(trade: &ActiveTrade) -> Map {
+    let direction = match trade.direction {
+        TradeDirection::Long => "long",
+        TradeDirection::Short => "short",
+    };
+
+    let mut map = Map::new();
+    map.insert("alert_name".into(), trade.alert_name.clone().into());
+    map.insert("pair".into(), trade.pair.clone().into());
+    map.insert("direction".into(), direction.into());
+    map.insert("entry_price".into(), Dynamic::from_float(trade.entry_price));
+    map.insert("quantity".into(), Dynamic::from_float(trade.quantity));
+    map.insert("take_profit".into(), trade.take_profit.map_or(Dynamic::UNIT, Dynamic::from_float));
+    map.insert("stop_loss".into(), trade.stop_loss.map_or(Dynamic::UNIT, Dynamic::from_float));
+
+    map
+}
+
+/// Interprets the value a filter script evaluated to.
+fn to_filter_decision(result: Dynamic) -> Result<FilterDecision, String> {
+    if result.is_unit() {
+        return Ok(FilterDecision::Allow(FilterOverrides::default()))
+    }
+
+    if let Ok(allow) = result.as_bool() {
+        return Ok(if allow {
+            FilterDecision::Allow(FilterOverrides::default())
+        } else {
+            FilterDecision::Deny("Rejected by the filter script.".to_string())
+        })
+    }
+
+    if result.is_string() {
+        return Ok(FilterDecision::Deny(result.into_string()?))
+    }
+
+    let Some(map) = result.clone().try_cast::<Map>() else {
+        return Err(format!("The script must evaluate to a bool, string or map, not {}.", result.type_name()))
+    };
+
+    let mut allow = true;
+    let mut reason = None;
+    let mut overrides = FilterOverrides::default();
+
+    for (key, value) in map {
+        match key.as_str() {
+            "allow" => allow = value.as_bool().map_err(|_| "`allow` must be a bool.".to_string())?,
+            "reason" => reason = Some(value.into_string().map_err(|_| "`reason` must be a string.".to_string())?),
+            "take_profit" => overrides.take_profit = Some(to_positive_number(&key, &value)?),
+            "stop_loss" => overrides.stop_loss = Some(to_positive_number(&key, &value)?),
+            "notional_value" => overrides.notional_value = Some(to_positive_number(&key, &value)?),
+            _ => return Err(format!("Unknown key `{}` in the result of the script.", key)),
+        }
+    }
+
+    if allow {
+        Ok(FilterDecision::Allow(overrides))
+    } else {
+        Ok(FilterDecision::Deny(reason.unwrap_or_else(|| "Rejected by the filter script.".to_string())))
+    }
+}
+
+/// Converts a number returned by a filter script (integer or float), which must be positive.
+fn to_positive_number(key: &str, value: &Dynamic) -> Result<f64, String> {
+    let number = value.as_float().or_else(|_| value.as_int().map(|int| int as f64))
+        .map_err(|_| format!("`{}` must be a number.", key))?;
+
+    if number.is_finite() && number > 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("`{}` must be positive.", key))
+    }
+}
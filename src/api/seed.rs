@@ -0,0 +1,260 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, to_bson};
+use serde_json::Value;
+
+use crate::{api::{build_closed_paper_trade_at, build_paper_trade, calc_trailing_stop, is_trigger_hit, script::run_filter_script, split_pair, timeseries::chart_time_filter, to_coinbase_product_id}, constants::SEED_IMPORT_KEY_PREFIX, models::{tradingview::TradingViewAlert, ActiveTrade, AppState, ClosedTrade, FilterDecision, MongoDBState, PriceTick, RejectionReason, SeedSummary, Strategy, TradeDirection}};
+
+/// CRUD operations for seeding the paper history of strategies in the database.
+impl MongoDBState {
+    /// Fetches the strategies with a seed date that weren't seeded yet.
+    pub async fn fetch_unseeded_strategies(&self) -> Result<Vec<Strategy>, mongodb::error::Error> {
+        let cursor = self.strategy_collection
+            .find(doc! { "seedFrom": { "$ne": null }, "seededTimestamp": null })
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+
+    /// Marks the paper history of a strategy as seeded, so that it isn't replayed again on the next startup.
+    pub async fn mark_strategy_seeded(&self, name: &str, seeded_timestamp: DateTime<Utc>) -> Result<(), mongodb::error::Error> {
+        self.strategy_collection
+            .update_one(doc! { "name": name }, doc! { "$set": { "seededTimestamp": seeded_timestamp.timestamp() } })
+            .await
+            .map(|_| ())
+    }
+
+    /// Fetches the alerts of a strategy sent since `since` that were stored because the strategy was disabled, oldest first.
+    ///
+    /// Payloads that no longer deserialize into an alert are skipped.
+    pub async fn fetch_seed_alerts(&self, alert_name: &str, since: DateTime<Utc>) -> Result<Vec<TradingViewAlert>, mongodb::error::Error> {
+        let cursor = self.rejected_alert_collection
+            .find(doc! {
+                "alertName": alert_name,
+                "reason": to_bson(&RejectionReason::StrategyDisabled)?,
+                "timestamp": { "$gte": since.timestamp() },
+            })
+            .await?;
+
+        let mut alerts: Vec<TradingViewAlert> = self.collect_documents(cursor)
+            .await?
+            .into_iter()
+            .filter_map(|rejected_alert| {
+                let mut payload = rejected_alert.payload;
+
+                // the secret is redacted before the payload is stored
+                payload.as_object_mut()?.insert("secret".to_string(), Value::String(String::new()));
+
+                serde_json::from_value::<TradingViewAlert>(payload).ok()
+            })
+            .filter(|alert| alert.timestamp >= since)
+            .collect();
+
+        alerts.sort_by_key(|alert| alert.timestamp);
+
+        Ok(alerts)
+    }
+
+    /// Fetches all price ticks of a product since `since`, oldest first.
+    pub async fn fetch_price_ticks_since(&self, product: &str, since: DateTime<Utc>) -> Result<Vec<PriceTick>, mongodb::error::Error> {
+        let mut filter = chart_time_filter(Some(since), None);
+        filter.insert("product", product.to_uppercase());
+
+        let cursor = self.price_tick_collection
+            .find(filter)
+            .sort(doc! { "timestamp": 1 })
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+}
+
+/// The import key of a seeded trade, so that replaying the same alerts again doesn't seed duplicate trades.
+pub fn seed_import_key(trade: &ClosedTrade) -> String {
+    format!("{}:{}:{}:{}", SEED_IMPORT_KEY_PREFIX, trade.alert_name, trade.pair, trade.open_timestamp.timestamp())
+}
+
+/// Runs the open trade through the price ticks within `(from, until]`, trailing its stop loss, and returns its closed trade once its
+/// liquidation, stop loss, take profit or an exit rule is hit (at the price of the tick).
+fn replay_price_ticks(
+    app_state: &AppState,
+    trade: &mut ActiveTrade,
+    ticks: &[(DateTime<Utc>, f64)],
+    (from, until): (DateTime<Utc>, DateTime<Utc>),
+) -> Option<ClosedTrade> {
+    let start = ticks.partition_point(|(timestamp, _)| *timestamp <= from);
+
+    for (timestamp, price) in ticks[start..].iter().take_while(|(timestamp, _)| *timestamp <= until) {
+        if let Some(stop_loss) = calc_trailing_stop(trade, *price) {
+            trade.stop_loss = Some(stop_loss);
+        }
+
+        if is_trigger_hit(trade, *price) || app_state.plugins.exit_rule_hit(trade, *price, *timestamp).is_some() {
+            return Some(build_closed_paper_trade_at(app_state, trade, *price, *timestamp));
+        }
+    }
+
+    None
+}
+
+/// Runs all open trades through the price ticks of their products within `(from, until]`, moving the ones that were exited to `closed_trades`.
+fn replay_open_trades(
+    app_state: &AppState,
+    open_trades: &mut HashMap<String, ActiveTrade>,
+    closed_trades: &mut Vec<ClosedTrade>,
+    ticks: &HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+    range: (DateTime<Utc>, DateTime<Utc>),
+) {
+    open_trades.retain(|pair, trade| {
+        let Some(product_ticks) = to_coinbase_product_id(pair).and_then(|product_id| ticks.get(&product_id)) else {
+            return true
+        };
+
+        match replay_price_ticks(app_state, trade, product_ticks, range) {
+            Some(closed_trade) => {
+                closed_trades.push(closed_trade);
+                false
+            }
+            None => true,
+        }
+    });
+}
+
+/// Replays the alerts of a strategy (oldest first) as paper trades, exiting them against the price ticks of their products
+/// (keyed by product ID, oldest first), and returns the trades closed by `now`.
+///
+/// Alerts are executed like live ones: through the filter script of the strategy and the alert filter plugins,
+/// flipping an open trade on an opposite signal and ignoring a signal in the direction of the open trade.
+/// Trades still open at `now` aren't seeded, since they were never actually tracked.
+pub fn replay_alerts(
+    app_state: &AppState,
+    strategy: &Strategy,
+    alerts: Vec<TradingViewAlert>,
+    ticks: &HashMap<String, Vec<(DateTime<Utc>, f64)>>,
+    now: DateTime<Utc>,
+) -> Vec<ClosedTrade> {
+    let mut open_trades: HashMap<String, ActiveTrade> = HashMap::new();
+    let mut closed_trades = Vec::new();
+    let mut replayed_until = alerts.first().map_or(now, |alert| alert.timestamp);
+
+    for mut alert in alerts {
+        replay_open_trades(app_state, &mut open_trades, &mut closed_trades, ticks, (replayed_until, alert.timestamp));
+        replayed_until = alert.timestamp;
+
+        let mut parameters = strategy.parameters.clone();
+
+        if let Some(source) = &strategy.filter_script {
+            let positions: Vec<ActiveTrade> = open_trades.values().cloned().collect();
+
+            match run_filter_script(source, &alert, Some(alert.price), &positions) {
+                Ok(FilterDecision::Allow(overrides)) => overrides.apply(&mut alert, &mut parameters),
+                Ok(FilterDecision::Deny(_)) | Err(_) => continue,
+            }
+        }
+
+        if app_state.plugins.filter_alert(&mut alert, &mut parameters).is_err() {
+            continue;
+        }
+
+        let Some(quote_usdt_value) = split_pair(&alert.pair).and_then(|(_, quote)| app_state.usdt_value_of(&quote)) else {
+            continue;
+        };
+
+        let direction: TradeDirection = alert.signal.into();
+
+        match open_trades.remove(&alert.pair) {
+            Some(trade) if trade.direction == direction => {
+                open_trades.insert(alert.pair.clone(), trade);
+                continue;
+            }
+            Some(trade) => closed_trades.push(build_closed_paper_trade_at(app_state, &trade, alert.price, alert.timestamp)),
+            None => {}
+        }
+
+        let open_timestamp = alert.timestamp;
+        let mut trade = build_paper_trade(alert, &parameters, quote_usdt_value, &app_state.rounding, false, "", open_timestamp);
+        trade.originating_request_id = None;
+
+        open_trades.insert(trade.pair.clone(), trade);
+    }
+
+    replay_open_trades(app_state, &mut open_trades, &mut closed_trades, ticks, (replayed_until, now));
+    closed_trades.sort_by_key(|trade| trade.close_timestamp);
+
+    closed_trades
+}
+
+/// Seeds the paper history of a strategy by replaying its alerts stored since its seed date against the recorded price ticks.
+///
+/// Seeded trades are inserted as closed paper trades with an import key, so that trades seeded before (e.g. by another replica) are skipped.
+pub async fn seed_strategy(app_state: &AppState, strategy: &Strategy) -> Result<SeedSummary, mongodb::error::Error> {
+    let Some(seed_from) = strategy.seed_from else {
+        return Ok(SeedSummary::default())
+    };
+
+    let mongo_state = &app_state.mongo_state;
+    let alerts = mongo_state.fetch_seed_alerts(&strategy.name, seed_from).await?;
+    let mut ticks = HashMap::new();
+
+    for product_id in alerts.iter().filter_map(|alert| to_coinbase_product_id(&alert.pair)) {
+        if ticks.contains_key(&product_id) {
+            continue;
+        }
+
+        let product_ticks = mongo_state.fetch_price_ticks_since(&product_id, seed_from)
+            .await?
+            .into_iter()
+            .map(|tick| (DateTime::from_timestamp_millis(tick.timestamp.timestamp_millis()).unwrap_or_default(), tick.price))
+            .collect::<Vec<_>>();
+
+        ticks.insert(product_id, product_ticks);
+    }
+
+    let replayed_alerts = alerts.len();
+    let mut trades = replay_alerts(app_state, strategy, alerts, &ticks, app_state.clock.now());
+
+    for trade in &mut trades {
+        trade.import_key = Some(seed_import_key(trade));
+    }
+
+    let keys: Vec<String> = trades.iter().filter_map(|trade| trade.import_key.clone()).collect();
+    let existing_keys = mongo_state.fetch_existing_import_keys(&keys).await?;
+    let total = trades.len();
+
+    trades.retain(|trade| trade.import_key.as_ref().is_none_or(|key| !existing_keys.contains(key)));
+
+    if !trades.is_empty() {
+        mongo_state.closed_trade_collection.insert_many(&trades).await?;
+    }
+
+    Ok(SeedSummary { replayed_alerts, trades: trades.len(), duplicates: total - trades.len() })
+}
+
+/// Seeds the paper history of all strategies with a seed date that weren't seeded yet. Run once at startup.
+pub async fn seed_strategies(app_state: Arc<AppState>) {
+    let strategies = match app_state.mongo_state.fetch_unseeded_strategies().await {
+        Ok(strategies) => strategies,
+        Err(err) => {
+            eprintln!("(seed_strategies) Failed to fetch strategies to seed: {}", err);
+            return;
+        }
+    };
+
+    for strategy in strategies {
+        match seed_strategy(&app_state, &strategy).await {
+            Ok(summary) => {
+                println!(
+                    "(seed_strategies) Seeded {} trades of {} from {} replayed alerts ({} already seeded).",
+                    summary.trades, strategy.name, summary.replayed_alerts, summary.duplicates
+                );
+
+                if let Err(err) = app_state.mongo_state.mark_strategy_seeded(&strategy.name, app_state.clock.now()).await {
+                    eprintln!("(seed_strategies) Failed to mark {} as seeded: {}", strategy.name, err);
+                }
+            }
+            Err(err) => eprintln!("(seed_strategies) Failed to seed {}: {}", strategy.name, err),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::HashSet, sync::{Arc, Mutex}, time::Duration as StdDuration};
+
+use axum::{Extension, Json};
+use chrono::{Duration, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::doc, results::{DeleteResult, UpdateResult}};
+
+use crate::{api::to_coinbase_product_id, configs::is_duplicate_key_error, constants::{ACCEPTED_SYMBOLS, INSTANCE_HEARTBEAT_TTL_SECS, SHARD_REBALANCE_INTERVAL_SECS, SYMBOL_CLAIM_TTL_SECS}, models::{ApiResponse, AppState, MongoDBState, ShardStatus, Sharding}};
+
+/// Operations on instance heartbeats and symbol claims in the database.
+///
+/// Heartbeats and claims are shared between replicas, so they're timed with the system clock rather than `AppState::clock`.
+impl MongoDBState {
+    /// Records a heartbeat of `instance_id`.
+    pub async fn send_instance_heartbeat(&self, instance_id: &str) -> Result<UpdateResult, mongodb::error::Error> {
+        self.instance_heartbeat_collection
+            .update_one(
+                doc! { "_id": instance_id },
+                doc! { "$set": { "lastHeartbeat": Utc::now().timestamp() } }
+            )
+            .upsert(true)
+            .await
+    }
+
+    /// Counts the instances that sent a heartbeat within `INSTANCE_HEARTBEAT_TTL_SECS`.
+    pub async fn count_live_instances(&self) -> Result<u64, mongodb::error::Error> {
+        let cutoff = (Utc::now() - Duration::seconds(INSTANCE_HEARTBEAT_TTL_SECS)).timestamp();
+
+        self.instance_heartbeat_collection.count_documents(doc! { "lastHeartbeat": { "$gte": cutoff } }).await
+    }
+
+    /// Renews the claim of `instance_id` on `pair`. Returns false if the claim is no longer held by the instance.
+    pub async fn renew_symbol_claim(&self, instance_id: &str, pair: &str) -> Result<bool, mongodb::error::Error> {
+        let expires_at = (Utc::now() + Duration::seconds(SYMBOL_CLAIM_TTL_SECS)).timestamp();
+
+        let result = self.symbol_claim_collection
+            .update_one(
+                doc! { "_id": pair, "holder": instance_id },
+                doc! { "$set": { "expiresAt": expires_at } }
+            )
+            .await?;
+
+        Ok(result.matched_count > 0)
+    }
+
+    /// Claims `pair` for `instance_id` if it's unclaimed or its claim expired. Returns whether the claim succeeded.
+    pub async fn try_claim_symbol(&self, instance_id: &str, pair: &str) -> Result<bool, mongodb::error::Error> {
+        let now = Utc::now();
+
+        // if the symbol is validly claimed by another instance, the upsert collides with the existing `_id` instead
+        let result = self.symbol_claim_collection
+            .update_one(
+                doc! { "_id": pair, "expiresAt": { "$lt": now.timestamp() } },
+                doc! { "$set": { "holder": instance_id, "expiresAt": (now + Duration::seconds(SYMBOL_CLAIM_TTL_SECS)).timestamp() } }
+            )
+            .upsert(true)
+            .await;
+
+        match result {
+            Ok(result) => Ok(result.matched_count > 0 || result.upserted_id.is_some()),
+            Err(err) if is_duplicate_key_error(&err) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Releases the claim of `instance_id` on `pair`, so that another instance can claim it right away.
+    pub async fn release_symbol_claim(&self, instance_id: &str, pair: &str) -> Result<DeleteResult, mongodb::error::Error> {
+        self.symbol_claim_collection.delete_one(doc! { "_id": pair, "holder": instance_id }).await
+    }
+}
+
+/// Calculates the maximum amount of symbols a single instance should claim, so that all symbols are spread evenly across the live instances.
+pub fn calc_fair_share(symbols: usize, instances: u64) -> usize {
+    symbols.div_ceil(instances.max(1) as usize)
+}
+
+impl Sharding {
+    /// Reads the sharding configuration from the `SHARD_MODE` env variable.
+    ///
+    /// Sharding is disabled unless `SHARD_MODE` is `true`.
+    pub fn from_env() -> Self {
+        Self {
+            enabled: std::env::var("SHARD_MODE").is_ok_and(|value| value.eq_ignore_ascii_case("true")),
+            claimed_pairs: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Whether this instance handles `pair` (i.e. subscribes to its price feed and checks its price triggers).
+    pub fn handles_pair(&self, pair: &str) -> bool {
+        !self.enabled || self.claimed_pairs.lock().unwrap().contains(&pair.to_uppercase())
+    }
+
+    /// Whether this instance handles the pair of the Coinbase product `product_id` (e.g. `SOL-USDT`).
+    pub fn handles_product(&self, product_id: &str) -> bool {
+        !self.enabled || self.claimed_pairs
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|pair| to_coinbase_product_id(pair).is_some_and(|pair_product_id| pair_product_id == product_id))
+    }
+}
+
+/// Renews the symbol claims of this instance and rebalances them towards its fair share of `ACCEPTED_SYMBOLS`.
+///
+/// Returns the claimed symbols afterwards.
+async fn rebalance_symbol_claims(app_state: &AppState, claimed: &HashSet<String>) -> Result<HashSet<String>, mongodb::error::Error> {
+    let mongo_state = &app_state.mongo_state;
+    let instance_id = &app_state.instance_id;
+
+    mongo_state.send_instance_heartbeat(instance_id).await?;
+
+    let fair_share = calc_fair_share(ACCEPTED_SYMBOLS.len(), mongo_state.count_live_instances().await?);
+
+    let mut renewed: Vec<String> = Vec::new();
+
+    for pair in claimed {
+        if mongo_state.renew_symbol_claim(instance_id, pair).await? {
+            renewed.push(pair.clone());
+        }
+    }
+
+    renewed.sort();
+
+    // release the symbols beyond the fair share, e.g. after another instance joined
+    while renewed.len() > fair_share {
+        if let Some(pair) = renewed.pop() {
+            mongo_state.release_symbol_claim(instance_id, &pair).await?;
+        }
+    }
+
+    let mut claimed: HashSet<String> = renewed.into_iter().collect();
+
+    // claim unclaimed (or expired) symbols up to the fair share, e.g. after another instance left
+    for pair in ACCEPTED_SYMBOLS {
+        if claimed.len() >= fair_share {
+            break;
+        }
+
+        if !claimed.contains(*pair) && mongo_state.try_claim_symbol(instance_id, pair).await? {
+            claimed.insert(pair.to_string());
+        }
+    }
+
+    Ok(claimed)
+}
+
+/// Periodically claims this instance's share of the symbols, subscribing the price feed to newly claimed symbols
+/// and unsubscribing it from the ones claimed by other instances.
+///
+/// Does nothing if sharding is disabled.
+pub async fn start_shard_coordinator(app_state: Arc<AppState>) {
+    if !app_state.sharding.enabled {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(StdDuration::from_secs(SHARD_REBALANCE_INTERVAL_SECS));
+
+    println!("(start_shard_coordinator) Running as instance {}", app_state.instance_id);
+
+    loop {
+        interval.tick().await;
+
+        let previous = app_state.sharding.claimed_pairs.lock().unwrap().clone();
+
+        let claimed = match rebalance_symbol_claims(&app_state, &previous).await {
+            Ok(claimed) => claimed,
+            Err(err) => {
+                // drop all claims if they can't be renewed, since other instances will take them over once they expire
+                eprintln!("(start_shard_coordinator) Failed to rebalance symbol claims: {}", err);
+                HashSet::new()
+            }
+        };
+
+        *app_state.sharding.claimed_pairs.lock().unwrap() = claimed.clone();
+
+        for pair in claimed.difference(&previous) {
+            println!("(start_shard_coordinator) Claimed {}", pair);
+            app_state.subscribe_pair(pair);
+        }
+
+        for pair in previous.difference(&claimed) {
+            println!("(start_shard_coordinator) Released {}", pair);
+            app_state.unsubscribe_pair(pair);
+        }
+    }
+}
+
+/// Returns the symbols claimed by this instance.
+pub async fn get_shard_status(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<ShardStatus>>) {
+    let mut claimed_pairs: Vec<String> = app_state.sharding.claimed_pairs.lock().unwrap().iter().cloned().collect();
+    claimed_pairs.sort();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_shard_status) Fetched shard status successfully.".to_string(),
+            data: Some(ShardStatus {
+                instance_id: app_state.instance_id.clone(),
+                enabled: app_state.sharding.enabled,
+                claimed_pairs,
+            })
+        })
+    )
+}
@@ -0,0 +1,183 @@
+use chrono::Duration;
+use mongodb::bson::{doc, Document};
+
+use crate::{api::{risk::paper_account_balance, to_coinbase_product_id}, constants::{ATR_PERIOD_DAYS, DEFAULT_EQUITY_PERCENTAGE, DEFAULT_KELLY_FRACTION, DEFAULT_LEVERAGE, DEFAULT_NOTIONAL_VALUE, DEFAULT_TARGET_VOLATILITY_PERCENTAGE, KELLY_LOOKBACK_TRADES, KELLY_MIN_TRADES, MAX_VOLATILITY_SIZE_MULTIPLIER}, models::{AppState, CurrencyConversion, DailyCandle, MongoDBState, ReportingCurrency, SizingMode, StrategyParameters}};
+
+impl MongoDBState {
+    /// Fetches the ROEs of the last `limit` closed trades of a strategy, newest first.
+    pub async fn fetch_recent_roes(&self, alert_name: &str, limit: i64) -> Result<Vec<f64>, mongodb::error::Error> {
+        let mut cursor = self.closed_trade_collection
+            .clone_with_type::<Document>()
+            .find(doc! { "alertName": alert_name })
+            .sort(doc! { "closeTimestamp": -1 })
+            .limit(limit)
+            .projection(doc! { "roe": 1 })
+            .await?;
+
+        let mut roes = Vec::new();
+
+        while cursor.advance().await? {
+            roes.push(cursor.deserialize_current()?.get_f64("roe").unwrap_or(0.0));
+        }
+
+        Ok(roes)
+    }
+}
+
+/// Calculates the Kelly criterion `W - (1 - W) / R` from the ROEs of a strategy's trades, where `W` is the win rate
+/// and `R` the average winning ROE over the average losing ROE.
+///
+/// Returns `None` if there are fewer than `KELLY_MIN_TRADES` trades. A strategy without losing trades gets its win rate (i.e. 1).
+pub fn calc_kelly_criterion(roes: &[f64]) -> Option<f64> {
+    if roes.len() < KELLY_MIN_TRADES {
+        return None;
+    }
+
+    let (wins, losses): (Vec<f64>, Vec<f64>) = roes.iter().partition(|roe| **roe > 0.0);
+    let win_rate = wins.len() as f64 / roes.len() as f64;
+
+    if losses.is_empty() {
+        return Some(win_rate);
+    }
+
+    if wins.is_empty() {
+        return Some(-1.0);
+    }
+
+    let average_win = wins.iter().sum::<f64>() / wins.len() as f64;
+    let average_loss = losses.iter().map(|roe| roe.abs()).sum::<f64>() / losses.len() as f64;
+
+    if average_loss == 0.0 {
+        return Some(win_rate);
+    }
+
+    Some(win_rate - (1.0 - win_rate) / (average_win / average_loss))
+}
+
+/// Calculates the notional value of a Kelly-sized trade: the Kelly criterion (scaled by `kelly_fraction`) of the equity is used
+/// as the margin of the trade, since the ROEs it's calculated from are returns on the margin.
+pub fn calc_kelly_notional_value(equity: f64, kelly_criterion: f64, kelly_fraction: f64, leverage: f64) -> f64 {
+    equity.max(0.0) * kelly_criterion * kelly_fraction * leverage
+}
+
+/// Calculates the notional value of an equity percentage sized trade.
+pub fn calc_equity_notional_value(equity: f64, equity_percentage: f64) -> f64 {
+    equity.max(0.0) * equity_percentage / 100.0
+}
+
+/// Calculates the average true range of the last `period` daily candles of a product (oldest first), in percent of the latest close.
+///
+/// Returns `None` if there are fewer than `period + 1` candles, since the first true range requires the previous close.
+pub fn calc_atr_percentage(candles: &[DailyCandle], period: usize) -> Option<f64> {
+    if period == 0 || candles.len() < period + 1 {
+        return None;
+    }
+
+    let true_ranges: Vec<f64> = candles
+        .windows(2)
+        .map(|pair| {
+            let (previous, candle) = (&pair[0], &pair[1]);
+            (candle.high - candle.low)
+                .max((candle.high - previous.close).abs())
+                .max((candle.low - previous.close).abs())
+        })
+        .collect();
+
+    let atr = true_ranges[true_ranges.len() - period..].iter().sum::<f64>() / period as f64;
+    let close = candles.last()?.close;
+
+    (close > 0.0).then(|| atr / close * 100.0)
+}
+
+/// Scales the notional value inversely to the volatility of the pair, relative to the targeted volatility,
+/// up to `MAX_VOLATILITY_SIZE_MULTIPLIER` times the notional value.
+pub fn scale_to_volatility_target(notional_value: f64, target_volatility_percentage: f64, atr_percentage: f64) -> f64 {
+    if atr_percentage <= 0.0 {
+        return notional_value * MAX_VOLATILITY_SIZE_MULTIPLIER;
+    }
+
+    notional_value * (target_volatility_percentage / atr_percentage).min(MAX_VOLATILITY_SIZE_MULTIPLIER)
+}
+
+impl AppState {
+    /// The paper equity in USDT: the paper account balance plus the realized PnL of all closed trades.
+    pub async fn paper_equity(&self) -> Result<f64, mongodb::error::Error> {
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+        let realized_pnl = self.mongo_state.sum_closed_pnl(doc! {}, &conversion).await?;
+
+        Ok(paper_account_balance() + realized_pnl)
+    }
+
+    /// Sizes the next trade of a strategy on `pair` according to its sizing mode, by setting the notional value of its parameters.
+    ///
+    /// Falls back to the fixed notional value if there isn't enough history (closed trades or daily candles) yet, or it (or the equity) can't be fetched.
+    /// Returns the reason if a Kelly-sized strategy has no edge, in which case no trade should be opened.
+    pub async fn size_position(&self, alert_name: &str, pair: &str, parameters: &mut StrategyParameters) -> Result<(), String> {
+        let notional_value = parameters.notional_value.unwrap_or(DEFAULT_NOTIONAL_VALUE);
+
+        match parameters.sizing_mode.unwrap_or_default() {
+            SizingMode::Fixed => {}
+            SizingMode::Kelly => {
+                let roes = match self.mongo_state.fetch_recent_roes(alert_name, KELLY_LOOKBACK_TRADES).await {
+                    Ok(roes) => roes,
+                    Err(err) => {
+                        eprintln!("(size_position) Failed to fetch the recent trades of {}: {}", alert_name, err);
+                        return Ok(());
+                    }
+                };
+
+                let Some(kelly_criterion) = calc_kelly_criterion(&roes) else {
+                    return Ok(());
+                };
+
+                if kelly_criterion <= 0.0 {
+                    return Err(format!("The last {} trades of {} have no edge (Kelly criterion: {:.3}).", roes.len(), alert_name, kelly_criterion));
+                }
+
+                let equity = match self.paper_equity().await {
+                    Ok(equity) => equity,
+                    Err(err) => {
+                        eprintln!("(size_position) Failed to calculate the paper equity: {}", err);
+                        return Ok(());
+                    }
+                };
+
+                let leverage: f64 = parameters.leverage.unwrap_or(DEFAULT_LEVERAGE).into();
+                let kelly_fraction = parameters.kelly_fraction.unwrap_or(DEFAULT_KELLY_FRACTION);
+
+                parameters.notional_value = Some(calc_kelly_notional_value(equity, kelly_criterion, kelly_fraction, leverage));
+            }
+            SizingMode::EquityPercentage => {
+                match self.paper_equity().await {
+                    Ok(equity) => {
+                        let percentage = parameters.equity_percentage.unwrap_or(DEFAULT_EQUITY_PERCENTAGE);
+                        parameters.notional_value = Some(calc_equity_notional_value(equity, percentage));
+                    }
+                    Err(err) => eprintln!("(size_position) Failed to calculate the paper equity: {}", err),
+                }
+            }
+            SizingMode::VolatilityTarget => {
+                let Some(product) = to_coinbase_product_id(pair) else {
+                    return Ok(());
+                };
+
+                let since = self.clock.now() - Duration::days(ATR_PERIOD_DAYS as i64 + 2);
+
+                let candles = match self.mongo_state.fetch_daily_candles(&[product], since).await {
+                    Ok(candles) => candles,
+                    Err(err) => {
+                        eprintln!("(size_position) Failed to fetch the daily candles of {}: {}", pair, err);
+                        return Ok(());
+                    }
+                };
+
+                if let Some(atr_percentage) = calc_atr_percentage(&candles, ATR_PERIOD_DAYS) {
+                    let target = parameters.target_volatility_percentage.unwrap_or(DEFAULT_TARGET_VOLATILITY_PERCENTAGE);
+                    parameters.notional_value = Some(scale_to_volatility_target(notional_value, target, atr_percentage));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration};
+use mongodb::{bson::doc, results::UpdateResult};
+
+use crate::{constants::{MAX_STATE_SNAPSHOT_AGE_SECS, STATE_SNAPSHOT_ID, STATE_SNAPSHOT_INTERVAL_SECS}, models::{AppState, MongoDBState, StateSnapshot}};
+
+/// CRUD operations for the in-memory state snapshot in the database.
+impl MongoDBState {
+    /// Stores a snapshot, replacing the previous one.
+    pub async fn save_state_snapshot(&self, snapshot: &StateSnapshot) -> Result<UpdateResult, mongodb::error::Error> {
+        self.state_snapshot_collection
+            .replace_one(doc! { "_id": &snapshot.id }, snapshot)
+            .upsert(true)
+            .await
+    }
+
+    /// Fetches the latest snapshot, if any.
+    pub async fn fetch_state_snapshot(&self) -> Result<Option<StateSnapshot>, mongodb::error::Error> {
+        self.state_snapshot_collection.find_one(doc! { "_id": STATE_SNAPSHOT_ID }).await
+    }
+}
+
+impl AppState {
+    /// Takes a snapshot of the auxiliary in-memory state.
+    pub fn to_snapshot(&self) -> StateSnapshot {
+        let latest_prices = self.latest_prices
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(product_id, price)| (product_id.clone(), *price))
+            .collect();
+
+        let alert_history = self.alert_history
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, timestamps)| (name.clone(), timestamps.iter().map(DateTime::timestamp).collect()))
+            .collect();
+
+        StateSnapshot {
+            id: STATE_SNAPSHOT_ID.to_string(),
+            timestamp: self.clock.now(),
+            latest_prices,
+            alert_history,
+        }
+    }
+
+    /// Restores a snapshot into memory. Values already received since boot take precedence over the snapshot's.
+    pub fn restore_snapshot(&self, snapshot: StateSnapshot) {
+        {
+            let mut prices = self.latest_prices.lock().unwrap();
+
+            for (product_id, price) in snapshot.latest_prices {
+                if prices.get(&product_id).is_none() {
+                    prices.insert(product_id, price);
+                }
+            }
+        }
+
+        {
+            let mut history = self.alert_history.lock().unwrap();
+
+            for (name, timestamps) in snapshot.alert_history {
+                if history.get(&name).is_none() {
+                    history.insert(name, timestamps.into_iter().filter_map(|timestamp| DateTime::from_timestamp(timestamp, 0)).collect());
+                }
+            }
+        }
+    }
+
+    /// Snapshots the in-memory state into the database.
+    pub async fn save_snapshot(&self) {
+        if let Err(err) = self.mongo_state.save_state_snapshot(&self.to_snapshot()).await {
+            eprintln!("(save_snapshot) Failed to save state snapshot: {}", err);
+        }
+    }
+
+    /// Restores the latest snapshot from the database on boot, unless it's older than `MAX_STATE_SNAPSHOT_AGE_SECS`.
+    pub async fn restore_latest_snapshot(&self) {
+        match self.mongo_state.fetch_state_snapshot().await {
+            Ok(Some(snapshot)) if self.clock.now() - snapshot.timestamp <= Duration::seconds(MAX_STATE_SNAPSHOT_AGE_SECS) => {
+                println!("(restore_latest_snapshot) Restoring state snapshot from {}", snapshot.timestamp);
+                self.restore_snapshot(snapshot);
+            }
+            Ok(Some(snapshot)) => println!("(restore_latest_snapshot) Skipping stale state snapshot from {}", snapshot.timestamp),
+            Ok(None) => {}
+            Err(err) => eprintln!("(restore_latest_snapshot) Failed to fetch state snapshot: {}", err),
+        }
+    }
+}
+
+/// Periodically snapshots the in-memory state into the database.
+pub async fn start_state_snapshotter(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(STATE_SNAPSHOT_INTERVAL_SECS));
+
+    // the first tick completes immediately, and there's nothing worth snapshotting right after boot
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        app_state.save_snapshot().await;
+    }
+}
+
+/// Resolves once the server receives Ctrl+C (or SIGTERM on Unix), after snapshotting the in-memory state.
+///
+/// Used for the server's graceful shutdown.
+pub async fn shutdown_signal(app_state: Arc<AppState>) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("(shutdown_signal) Failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("(shutdown_signal) Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    println!("(shutdown_signal) Shutting down. Saving state snapshot.");
+    app_state.save_snapshot().await;
+}
@@ -1,15 +1,64 @@
-use std::{collections::HashMap, sync::{Arc, Mutex}};
+use std::{collections::HashMap, sync::{atomic::AtomicBool, Arc, Mutex}};
 
 use mongodb::bson::oid::ObjectId;
+use tokio::sync::mpsc;
 
-use crate::models::{ActiveTrade, AppState, MongoDBState};
+use crate::{api::plugin::registered_plugins, constants::{MAX_CACHED_ALERT_HISTORIES, MAX_CACHED_PRICES}, models::{AppState, BinanceClient, CoinbaseClient, Exchange, FeatureFlags, FeedChannels, FeedQualityMonitor, Leadership, LiveCloseTracker, LruCache, MongoDBState, MqttPublisher, NetworkConfig, Notifier, PluginRegistry, PositionConflictGuard, PriceConsensus, PriceTickRecorder, ResponseVerbosity, RoundingPolicy, SharedClock, Sharding, SystemClock, TradeTickRecorder, TradingCalendar, WsCommand}};
 
 impl AppState {
     /// Initialize a new `AppState`.
-    pub fn new(mongo_state: Arc<MongoDBState>) -> Self {
+    /// 
+    /// `ws_commands` is the sending half of the channel consumed by the price listener (see `start_price_listener`).
+    pub fn new(mongo_state: Arc<MongoDBState>, ws_commands: mpsc::UnboundedSender<WsCommand>) -> Self {
+        let network = NetworkConfig::from_env();
+        let http_client = network.http_client();
+
         Self {
+            instance_id: std::env::var("INSTANCE_ID").unwrap_or_else(|_| ObjectId::new().to_hex()),
             mongo_state,
             active_trades: Arc::new(Mutex::new(HashMap::new())),
+            latest_prices: Arc::new(Mutex::new(LruCache::new(MAX_CACHED_PRICES))),
+            ws_commands,
+            price_alerts: Arc::new(Mutex::new(HashMap::new())),
+            notifier: Notifier::from_env(),
+            alert_history: Arc::new(Mutex::new(LruCache::new(MAX_CACHED_ALERT_HISTORIES))),
+            alert_locks: Arc::new(Mutex::new(HashMap::new())),
+            leadership: Leadership::from_env(),
+            command_secret: std::env::var("COMMAND_SECRET").ok().filter(|secret| !secret.is_empty()),
+            sharding: Sharding::from_env(),
+            paused: AtomicBool::new(false),
+            response_verbosity: ResponseVerbosity::from_env(),
+            price_ticks: PriceTickRecorder::from_env(),
+            trade_ticks: TradeTickRecorder::from_env(),
+            clock: Arc::new(SystemClock),
+            features: FeatureFlags::from_env(),
+            plugins: registered_plugins(),
+            mqtt: MqttPublisher::from_env(),
+            network,
+            feed_channels: FeedChannels::from_env(),
+            feed_quality: FeedQualityMonitor::from_env(),
+            price_consensus: PriceConsensus::from_env(),
+            trading_calendar: TradingCalendar::from_env(),
+            rounding: RoundingPolicy::from_env(),
+            conflict_guard: PositionConflictGuard::from_env(),
+            live_exchange: BinanceClient::from_env(http_client.clone()).map(|client| Arc::new(client) as Arc<dyn Exchange>),
+            live_closes: LiveCloseTracker::default(),
+            funding_exchange: Arc::new(BinanceClient::public(http_client.clone())),
+            price_exchange: Arc::new(CoinbaseClient::new(http_client)),
         }
     }
+
+    /// Replaces the system clock with `clock` (e.g. a `SimulatedClock` to advance time deterministically).
+    #[allow(dead_code)]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Replaces the plugins compiled into the bot with `plugins` (e.g. to test a plugin).
+    #[allow(dead_code)]
+    pub fn with_plugins(mut self, plugins: PluginRegistry) -> Self {
+        self.plugins = plugins;
+        self
+    }
 }
\ No newline at end of file
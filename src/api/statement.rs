@@ -0,0 +1,350 @@
+use std::{sync::Arc, time::Duration as StdDuration};
+
+use axum::{extract::{Path, Query}, http::header, response::{IntoResponse, Response}, Extension, Json};
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, to_bson, Document}, results::UpdateResult, Cursor};
+
+use crate::{api::{report::escape_html, risk::paper_account_balance, trade::{calc_page_skip, clamp_per_page}}, constants::{MAX_PER_PAGE, STATEMENT_GENERATOR_INTERVAL_SECS}, models::{AccountStatement, AccountStatementQuery, ApiResponse, AppState, ClosedTrade, CurrencyConversion, MongoDBState, ReportLocale, ReportingCurrency, RoundingPolicy, TradeKind}};
+
+/// CRUD operations for account statements in the database.
+impl MongoDBState {
+    /// Inserts an account statement into the database, or replaces it if it was already generated (e.g. by another replica).
+    pub async fn upsert_account_statement(&self, statement: &AccountStatement) -> Result<UpdateResult, mongodb::error::Error> {
+        self.account_statement_collection
+            .replace_one(doc! { "_id": &statement.id }, statement)
+            .upsert(true)
+            .await
+    }
+
+    /// Fetches an account statement by its ID (e.g. `paper-2025-01`).
+    pub async fn fetch_account_statement(&self, id: &str) -> Result<Option<AccountStatement>, mongodb::error::Error> {
+        self.account_statement_collection.find_one(doc! { "_id": id }).await
+    }
+
+    /// Fetches the account statements matching `filter` with pagination, newest first. Their HTML is left out.
+    pub async fn fetch_account_statements(&self, filter: Document, page: u32, per_page: u32) -> Result<Vec<AccountStatement>, mongodb::error::Error> {
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) is empty
+        let Some(skip) = calc_page_skip(page.max(1), per_page) else {
+            return Ok(Vec::new())
+        };
+
+        let cursor: Cursor<AccountStatement> = self
+            .account_statement_collection
+            .find(filter)
+            .projection(doc! { "html": 0 })
+            .sort(doc! { "from": -1 })
+            .skip(skip)
+            .limit(per_page as i64)
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+
+    /// Fetches the trades of `account` closed between `from` (inclusive) and `to` (exclusive), oldest first.
+    pub async fn fetch_account_trades_between(&self, account: &TradeKind, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<ClosedTrade>, mongodb::error::Error> {
+        let cursor: Cursor<ClosedTrade> = self
+            .closed_trade_collection
+            .find(doc! { "kind": to_bson(account)?, "closeTimestamp": { "$gte": from.timestamp(), "$lt": to.timestamp() } })
+            .sort(doc! { "closeTimestamp": 1 })
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+}
+
+/// The ID of the statement of `account` for `month` (e.g. `paper-2025-01`).
+pub fn account_statement_id(account: &TradeKind, month: &str) -> String {
+    match account {
+        TradeKind::Paper => format!("paper-{}", month),
+        TradeKind::Live => format!("live-{}", month),
+    }
+}
+
+/// Parses a month (e.g. `2025-01`) into its start (inclusive) and end (exclusive) in UTC.
+pub fn parse_statement_month(month: &str) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d").ok()?;
+    let end = start.checked_add_months(Months::new(1))?;
+
+    Some((start.and_hms_opt(0, 0, 0)?.and_utc(), end.and_hms_opt(0, 0, 0)?.and_utc()))
+}
+
+/// Returns the month (e.g. `2025-01`) whose statements became due between `from` (exclusive) and `to` (inclusive), if any.
+///
+/// Statements are due at the start of the next month (UTC), once the month they cover has ended.
+pub fn calc_due_statement_month(from: DateTime<Utc>, to: DateTime<Utc>) -> Option<String> {
+    let boundary = to.date_naive().with_day(1)?;
+    let boundary_timestamp = boundary.and_hms_opt(0, 0, 0)?.and_utc();
+
+    (boundary_timestamp > from && boundary_timestamp <= to).then(|| {
+        let month = boundary - Months::new(1);
+        format!("{:04}-{:02}", month.year(), month.month())
+    })
+}
+
+/// Summarizes the trades of `account` closed within `month` (between `from` and `to`) into a statement, starting at `opening_equity`.
+///
+/// All amounts are converted to USDT at the settlement rate of each trade. The HTML is rendered separately (see `build_statement_html`).
+pub fn build_account_statement(
+    account: TradeKind,
+    month: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    opening_equity: f64,
+    trades: &[ClosedTrade],
+    generated_timestamp: DateTime<Utc>
+) -> AccountStatement {
+    let in_usdt = |trade: &ClosedTrade, amount: f64| amount * trade.settlement_usdt_rate.unwrap_or(1.0);
+
+    let realized_pnl: f64 = trades.iter().map(|trade| in_usdt(trade, trade.pnl)).sum();
+
+    AccountStatement {
+        id: account_statement_id(&account, month),
+        account,
+        month: month.to_string(),
+        from,
+        to,
+        opening_equity,
+        trades: trades.len(),
+        realized_pnl,
+        execution_fees: trades.iter().map(|trade| in_usdt(trade, trade.execution_fees)).sum(),
+        liquidation_fees: trades.iter().map(|trade| in_usdt(trade, trade.liquidation_fee)).sum(),
+        funding_fees: trades.iter().map(|trade| in_usdt(trade, trade.funding_fees)).sum(),
+        closing_equity: opening_equity + realized_pnl,
+        generated_timestamp,
+        html: String::new(),
+    }
+}
+
+/// Renders a statement as a standalone HTML document: a summary of the month, followed by a table of the trades closed within it.
+///
+/// Numbers and timestamps are formatted according to `locale`.
+pub fn build_statement_html(statement: &AccountStatement, trades: &[ClosedTrade], locale: &ReportLocale, rounding: &RoundingPolicy) -> String {
+    let title = format!("{:?} account statement {}", statement.account, statement.month);
+
+    let mut html = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title}</title></head><body>\
+        <h2>{title}</h2>\
+        <p>{} - {} ({})</p>\
+        <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+        <tr><th>Opening equity (USDT)</th><td>{}</td></tr>\
+        <tr><th>Trades closed</th><td>{}</td></tr>\
+        <tr><th>Execution fees (USDT)</th><td>{}</td></tr>\
+        <tr><th>Liquidation fees (USDT)</th><td>{}</td></tr>\
+        <tr><th>Funding fees (USDT)</th><td>{}</td></tr>\
+        <tr><th>Realized PnL (USDT)</th><td>{}</td></tr>\
+        <tr><th>Closing equity (USDT)</th><td>{}</td></tr>\
+        </table>",
+        locale.format_timestamp_short(statement.from),
+        locale.format_timestamp_short(statement.to),
+        locale.timezone.name(),
+        rounding.format_amount(locale, statement.opening_equity),
+        statement.trades,
+        rounding.format_amount(locale, statement.execution_fees),
+        rounding.format_amount(locale, statement.liquidation_fees),
+        rounding.format_amount(locale, statement.funding_fees),
+        rounding.format_amount(locale, statement.realized_pnl),
+        rounding.format_amount(locale, statement.closing_equity),
+    );
+
+    if trades.is_empty() {
+        html.push_str("<p>No trades were closed within this month.</p></body></html>");
+        return html;
+    }
+
+    html.push_str(&format!(
+        "<h3>Closed trades</h3>\
+        <table border=\"1\" cellpadding=\"4\" cellspacing=\"0\">\
+        <tr><th>Closed ({})</th><th>Strategy</th><th>Pair</th><th>Direction</th><th>Quantity</th><th>Entry</th><th>Exit</th><th>Fees</th><th>Funding</th><th>PnL</th></tr>",
+        locale.timezone.name()
+    ));
+
+    for trade in trades {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{} {}</td></tr>",
+            locale.format_timestamp_short(trade.close_timestamp),
+            escape_html(&trade.alert_name),
+            escape_html(&trade.pair),
+            trade.direction,
+            locale.format_number(trade.quantity),
+            rounding.format_price(locale, &trade.pair, trade.entry_price),
+            rounding.format_price(locale, &trade.pair, trade.exit_price),
+            rounding.format_amount(locale, trade.execution_fees + trade.liquidation_fee),
+            rounding.format_amount(locale, trade.funding_fees),
+            rounding.format_amount(locale, trade.pnl),
+            escape_html(&trade.settlement_currency),
+        ));
+    }
+
+    html.push_str("</table></body></html>");
+
+    html
+}
+
+impl AppState {
+    /// Generates the statement of `account` for `month` (e.g. `2025-01`) and stores it, replacing any previously generated one.
+    ///
+    /// The opening equity of the paper account starts at the paper account balance. The live account's balance isn't known, so its equity
+    /// starts at zero (i.e. it's the cumulative realized PnL).
+    pub async fn generate_account_statement(&self, account: TradeKind, month: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<AccountStatement, mongodb::error::Error> {
+        let starting_balance = match account {
+            TradeKind::Paper => paper_account_balance(),
+            TradeKind::Live => 0.0,
+        };
+
+        // all values are reported in USDT, so that no conversion rate is required
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+        let realized_before = self.mongo_state
+            .sum_closed_pnl(doc! { "kind": to_bson(&account)?, "closeTimestamp": { "$lt": from.timestamp() } }, &conversion)
+            .await?;
+
+        let trades = self.mongo_state.fetch_account_trades_between(&account, from, to).await?;
+
+        let mut statement = build_account_statement(account, month, from, to, starting_balance + realized_before, &trades, self.clock.now());
+        statement.html = build_statement_html(&statement, &trades, &ReportLocale::from_env(), &self.rounding);
+
+        self.mongo_state.upsert_account_statement(&statement).await?;
+
+        Ok(statement)
+    }
+}
+
+/// Periodically generates the statements of the month that just ended (see `calc_due_statement_month`) and stores them,
+/// for the paper account, and for the live account if live trading is enabled.
+///
+/// When running multiple instances, only the leader generates statements.
+pub async fn start_statement_generator(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(STATEMENT_GENERATOR_INTERVAL_SECS));
+    let mut last_run = app_state.clock.now();
+
+    loop {
+        interval.tick().await;
+
+        let now = app_state.clock.now();
+
+        if !app_state.leadership.is_leader() {
+            last_run = now;
+            continue;
+        }
+
+        let Some((month, (from, to))) = calc_due_statement_month(last_run, now).and_then(|month| parse_statement_month(&month).map(|window| (month, window))) else {
+            last_run = now;
+            continue;
+        };
+
+        let mut accounts = vec![TradeKind::Paper];
+
+        if app_state.features.live_trading {
+            accounts.push(TradeKind::Live);
+        }
+
+        let mut succeeded = true;
+
+        for account in accounts {
+            match app_state.generate_account_statement(account.clone(), &month, from, to).await {
+                Ok(statement) => println!("(start_statement_generator) Generated statement {}", statement.id),
+                Err(err) => {
+                    eprintln!("(start_statement_generator) Failed to generate the {:?} statement of {}: {}", account, month, err);
+                    succeeded = false;
+                }
+            }
+        }
+
+        // only advance on success so that a failed statement is retried on the next tick
+        if succeeded {
+            last_run = now;
+        }
+    }
+}
+
+/// Returns the stored account statements (without their HTML), optionally filtered by account, newest first.
+pub async fn get_account_statements(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<AccountStatementQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<AccountStatement>>>) {
+    let mut filter = Document::new();
+
+    if let Some(account) = &query.account {
+        if let Ok(account) = to_bson(account) {
+            filter.insert("account", account);
+        }
+    }
+
+    match mongo_state.fetch_account_statements(filter, query.page.unwrap_or(1), query.per_page.unwrap_or(MAX_PER_PAGE as u32)).await {
+        Ok(statements) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_account_statements) Fetched account statements successfully.".to_string(),
+                data: Some(statements)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_account_statements) Failed to fetch account statements: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_account_statements) Failed to fetch account statements: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Builds the JSON response of a failure to download a statement.
+fn statement_error_response(status: StatusCode, status_text: &'static str, message: String) -> Response {
+    (
+        status,
+        Json(ApiResponse::<()> {
+            status: status_text,
+            code: None,
+            message,
+            data: None
+        })
+    ).into_response()
+}
+
+/// Downloads the statement of an account (`paper` or `live`) for a month (e.g. `2025-01`) as an HTML document.
+///
+/// Statements of ended months that weren't generated yet (e.g. before statements existed) are generated and stored first.
+pub async fn download_account_statement(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path((account, month)): Path<(TradeKind, String)>,
+) -> Response {
+    let Some((from, to)) = parse_statement_month(&month) else {
+        return statement_error_response(StatusCode::BAD_REQUEST, "400 Bad Request", format!("(download_account_statement) Invalid month: {}. Expected e.g. 2025-01.", month))
+    };
+
+    let statement = match app_state.mongo_state.fetch_account_statement(&account_statement_id(&account, &month)).await {
+        Ok(Some(statement)) => statement,
+        Ok(None) if to > app_state.clock.now() => {
+            return statement_error_response(StatusCode::BAD_REQUEST, "400 Bad Request", format!("(download_account_statement) The month {} hasn't ended yet.", month))
+        }
+        Ok(None) => match app_state.generate_account_statement(account, &month, from, to).await {
+            Ok(statement) => statement,
+            Err(err) => {
+                eprintln!("(download_account_statement) Failed to generate account statement: {}", err);
+                return statement_error_response(StatusCode::INTERNAL_SERVER_ERROR, "500 Internal Server Error", format!("(download_account_statement) Failed to generate account statement: {}", err))
+            }
+        },
+        Err(err) => {
+            eprintln!("(download_account_statement) Failed to fetch account statement: {}", err);
+            return statement_error_response(StatusCode::INTERNAL_SERVER_ERROR, "500 Internal Server Error", format!("(download_account_statement) Failed to fetch account statement: {}", err))
+        }
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"statement-{}.html\"", statement.id)),
+        ],
+        statement.html
+    ).into_response()
+}
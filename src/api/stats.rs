@@ -0,0 +1,771 @@
+use std::{collections::{BTreeMap, HashMap}, sync::Arc};
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use hyper::StatusCode;
+use mongodb::bson::{doc, from_document, Bson, Document};
+
+use crate::{api::{fx::currency_conversion_stage, stats_helpers::{calc_correlation, calc_max_drawdown}}, constants::{HEATMAP_WEEKDAYS, ROLLING_WINDOW_DAYS}, models::{ApiResponse, AppState, CompareQuery, CurrencyConversion, DailyReturnCorrelation, GroupedStats, HeatmapBucket, MonthlyStats, MongoDBState, PerformanceStats, ReportLocale, ReportingCurrency, RollingWindowStats, RoundingPolicy, StatsBreakdown, StatsComparison, StatsHeatmap, StatsOverview, StatsQuery, StatsTotals, StrategyComparison}};
+
+impl PerformanceStats {
+    /// Derives the ratio metrics (win rate, profit factor) from the raw sums returned by the `$group` stage.
+    pub fn finalize(mut self) -> Self {
+        if self.total_trades > 0 {
+            self.win_rate = self.wins as f64 / self.total_trades as f64 * 100.0;
+        }
+
+        if self.gross_loss < 0.0 {
+            self.profit_factor = Some(self.gross_profit / self.gross_loss.abs());
+        }
+
+        self
+    }
+
+    /// Rounds the monetary values and ratios for display, once all metrics are derived.
+    pub fn rounded(mut self, rounding: &RoundingPolicy) -> Self {
+        for value in [
+            &mut self.win_rate,
+            &mut self.total_pnl,
+            &mut self.average_pnl,
+            &mut self.average_roe,
+            &mut self.gross_profit,
+            &mut self.gross_loss,
+            &mut self.total_execution_fees,
+            &mut self.total_funding_fees,
+            &mut self.best_trade_pnl,
+            &mut self.worst_trade_pnl,
+        ] {
+            *value = rounding.display_amount(*value);
+        }
+
+        self.profit_factor = self.profit_factor.map(|profit_factor| rounding.display_amount(profit_factor));
+
+        self
+    }
+}
+
+impl StatsOverview {
+    /// Rounds all metrics for display.
+    pub fn rounded(mut self, rounding: &RoundingPolicy) -> Self {
+        self.lifetime = self.lifetime.rounded(rounding);
+
+        for window in &mut self.rolling {
+            window.stats = std::mem::take(&mut window.stats).rounded(rounding);
+        }
+
+        for month in &mut self.monthly {
+            month.stats = std::mem::take(&mut month.stats).rounded(rounding);
+            month.pnl_change = month.pnl_change.map(|pnl_change| rounding.display_amount(pnl_change));
+        }
+
+        self
+    }
+}
+
+impl StatsBreakdown {
+    /// Rounds all metrics for display.
+    pub fn rounded(mut self, rounding: &RoundingPolicy) -> Self {
+        for groups in [
+            &mut self.by_pair,
+            &mut self.by_direction,
+            &mut self.by_leverage,
+            &mut self.by_entry_hour,
+            &mut self.by_experiment,
+            &mut self.by_trend,
+            &mut self.by_volatility,
+        ] {
+            for group in groups.iter_mut() {
+                group.stats = std::mem::take(&mut group.stats).rounded(rounding);
+            }
+        }
+
+        self
+    }
+}
+
+impl StatsComparison {
+    /// Rounds all metrics for display. Correlations are left as they are.
+    pub fn rounded(mut self, rounding: &RoundingPolicy) -> Self {
+        for strategy in &mut self.strategies {
+            strategy.stats = std::mem::take(&mut strategy.stats).rounded(rounding);
+            strategy.expectancy = rounding.display_amount(strategy.expectancy);
+            strategy.max_drawdown = rounding.display_amount(strategy.max_drawdown);
+        }
+
+        self
+    }
+}
+
+/// Builds a `$group` stage that accumulates the raw `PerformanceStats` sums of closed trades, grouped by `group_id`.
+///
+/// Pass `Bson::Null` as `group_id` to aggregate all matched trades into a single group.
+pub fn performance_group_stage(group_id: impl Into<Bson>) -> Document {
+    doc! {
+        "$group": {
+            "_id": group_id.into(),
+            "totalTrades": { "$sum": 1 },
+            "wins": { "$sum": { "$cond": [{ "$gt": ["$pnl", 0] }, 1, 0] } },
+            "losses": { "$sum": { "$cond": [{ "$lt": ["$pnl", 0] }, 1, 0] } },
+            "totalPnl": { "$sum": "$pnl" },
+            "averagePnl": { "$avg": "$pnl" },
+            "averageRoe": { "$avg": "$roe" },
+            "grossProfit": { "$sum": { "$cond": [{ "$gt": ["$pnl", 0] }, "$pnl", 0] } },
+            "grossLoss": { "$sum": { "$cond": [{ "$lt": ["$pnl", 0] }, "$pnl", 0] } },
+            "totalExecutionFees": { "$sum": "$executionFees" },
+            "totalFundingFees": { "$sum": "$fundingFees" },
+            "bestTradePnl": { "$max": "$pnl" },
+            "worstTradePnl": { "$min": "$pnl" },
+        }
+    }
+}
+
+/// Builds an expression converting a timestamp field stored in seconds (e.g. `$openTimestamp`) into a BSON date,
+/// so that date operators such as `$hour` or `$dateToString` can be applied to it.
+pub fn timestamp_to_date(field: &str) -> Document {
+    doc! { "$toDate": { "$multiply": [field, 1000] } }
+}
+
+/// Builds the operand of a date operator (e.g. `$hour`) that evaluates a timestamp field stored in seconds in `timezone`,
+/// so that trades are bucketed by local wall-clock time rather than UTC.
+pub fn local_date_operand(field: &str, timezone: Tz) -> Document {
+    doc! { "date": timestamp_to_date(field), "timezone": timezone.name() }
+}
+
+/// Converts the query parameters of the stats endpoints into a `$match` filter for closed trades.
+pub fn stats_filter(query: &StatsQuery) -> Document {
+    let mut filter = Document::new();
+
+    if let Some(alert_name) = &query.alert_name {
+        filter.insert("alertName", alert_name);
+    }
+
+    if let Some(pair) = &query.pair {
+        filter.insert("pair", pair.to_uppercase());
+    }
+
+    if let Some(experiment) = &query.experiment {
+        filter.insert("experiment", experiment);
+    }
+
+    filter
+}
+
+/// Reads the first (and only) group of a `$facet` output field as `PerformanceStats`.
+///
+/// If no trades matched the facet, empty stats are returned.
+fn facet_stats(facets: &Document, key: &str) -> Result<PerformanceStats, mongodb::error::Error> {
+    match facets.get_array(key).ok().and_then(|groups| groups.first()).and_then(Bson::as_document) {
+        Some(group) => Ok(from_document::<PerformanceStats>(group.clone())?.finalize()),
+        None => Ok(PerformanceStats::default()),
+    }
+}
+
+/// Reads all groups of a `$facet` output field as `GroupedStats`, keyed by the stringified group `_id`.
+fn facet_groups(facets: &Document, key: &str) -> Result<Vec<GroupedStats>, mongodb::error::Error> {
+    let mut groups = Vec::new();
+
+    for group in facets.get_array(key).map(|groups| groups.as_slice()).unwrap_or_default() {
+        let Some(group) = group.as_document() else { continue };
+
+        let key = match group.get("_id") {
+            Some(Bson::String(key)) => key.clone(),
+            Some(Bson::Null) | None => "unknown".to_string(),
+            Some(other) => other.to_string(),
+        };
+
+        groups.push(GroupedStats {
+            key,
+            stats: from_document::<PerformanceStats>(group.clone())?.finalize(),
+        });
+    }
+
+    Ok(groups)
+}
+
+/// Reads a numeric BSON value (double or integer) as `f64`, defaulting to zero.
+fn bson_to_f64(value: Option<&Bson>) -> f64 {
+    match value {
+        Some(Bson::Double(value)) => *value,
+        Some(Bson::Int32(value)) => *value as f64,
+        Some(Bson::Int64(value)) => *value as f64,
+        _ => 0.0,
+    }
+}
+
+/// Builds the leading stages of every stats pipeline: the `$match` filter, followed by the conversion of
+/// monetary fields into the reporting currency.
+fn stats_match_stages(filter: Document, conversion: &CurrencyConversion) -> Vec<Document> {
+    let mut stages = vec![doc! { "$match": filter }];
+    stages.push(currency_conversion_stage(conversion));
+
+    stages
+}
+
+/// Runs a single `$facet` aggregation over the closed trades matching `filter` and returns the resulting document.
+async fn aggregate_facets(
+    mongo_state: &MongoDBState,
+    filter: Document,
+    conversion: &CurrencyConversion,
+    facets: Document
+) -> Result<Document, mongodb::error::Error> {
+    let mut pipeline = stats_match_stages(filter, conversion);
+    pipeline.push(doc! { "$facet": facets });
+
+    let mut cursor = mongo_state.closed_trade_collection
+        .aggregate(pipeline)
+        .selection_criteria(mongo_state.stats_read_preference.selection_criteria())
+        .await?;
+
+    // `$facet` always outputs exactly one document
+    if cursor.advance().await? {
+        Ok(cursor.deserialize_current()?)
+    } else {
+        Ok(Document::new())
+    }
+}
+
+/// Aggregation queries for closed trade statistics.
+impl MongoDBState {
+    /// Aggregates the lifetime, rolling window (ending at `now`) and monthly performance of the closed trades matching `filter`.
+    ///
+    /// All breakdowns are computed in a single `$facet` aggregation to avoid multiple round trips.
+    pub async fn aggregate_stats_overview(
+        &self,
+        filter: Document,
+        conversion: &CurrencyConversion,
+        timezone: Tz,
+        now: DateTime<Utc>
+    ) -> Result<StatsOverview, mongodb::error::Error> {
+        let mut facets = doc! {
+            "lifetime": [performance_group_stage(Bson::Null)],
+            "monthly": [
+                performance_group_stage(doc! {
+                    "$dateToString": { "format": "%Y-%m", "date": timestamp_to_date("$closeTimestamp"), "timezone": timezone.name() }
+                }),
+                { "$sort": { "_id": 1 } },
+            ],
+        };
+
+        for days in ROLLING_WINDOW_DAYS {
+            let window_start = (now - Duration::days(days)).timestamp();
+
+            facets.insert(format!("last{}Days", days), vec![
+                Bson::Document(doc! { "$match": { "closeTimestamp": { "$gte": window_start } } }),
+                Bson::Document(performance_group_stage(Bson::Null)),
+            ]);
+        }
+
+        let facets = aggregate_facets(self, filter, conversion, facets).await?;
+
+        let lifetime = facet_stats(&facets, "lifetime")?;
+
+        let mut rolling = Vec::new();
+
+        for days in ROLLING_WINDOW_DAYS {
+            rolling.push(RollingWindowStats {
+                days,
+                stats: facet_stats(&facets, &format!("last{}Days", days))?,
+            });
+        }
+
+        let mut monthly: Vec<MonthlyStats> = Vec::new();
+
+        for GroupedStats { key: month, stats } in facet_groups(&facets, "monthly")? {
+            // month-over-month change compared to the previous month on record
+            let pnl_change = monthly.last().map(|previous| stats.total_pnl - previous.stats.total_pnl);
+
+            monthly.push(MonthlyStats { month, stats, pnl_change });
+        }
+
+        Ok(StatsOverview { currency: conversion.currency, timezone, lifetime, rolling, monthly })
+    }
+
+    /// Aggregates the performance of the closed trades matching `filter`, grouped by pair, direction, leverage, entry hour (in `timezone`), experiment and market regime at entry.
+    pub async fn aggregate_stats_breakdown(
+        &self,
+        filter: Document,
+        conversion: &CurrencyConversion,
+        timezone: Tz
+    ) -> Result<StatsBreakdown, mongodb::error::Error> {
+        let facets = doc! {
+            "byPair": [performance_group_stage("$pair"), { "$sort": { "totalPnl": -1 } }],
+            "byDirection": [performance_group_stage("$direction"), { "$sort": { "_id": 1 } }],
+            "byLeverage": [performance_group_stage("$leverage"), { "$sort": { "_id": 1 } }],
+            "byEntryHour": [
+                performance_group_stage(doc! { "$hour": local_date_operand("$openTimestamp", timezone) }),
+                { "$sort": { "_id": 1 } },
+            ],
+            "byExperiment": [performance_group_stage("$experiment"), { "$sort": { "_id": 1 } }],
+            "byTrend": [performance_group_stage("$regime.trend"), { "$sort": { "_id": 1 } }],
+            "byVolatility": [performance_group_stage("$regime.volatility"), { "$sort": { "_id": 1 } }],
+        };
+
+        let facets = aggregate_facets(self, filter, conversion, facets).await?;
+
+        Ok(StatsBreakdown {
+            currency: conversion.currency,
+            timezone,
+            by_pair: facet_groups(&facets, "byPair")?,
+            by_direction: facet_groups(&facets, "byDirection")?,
+            by_leverage: facet_groups(&facets, "byLeverage")?,
+            by_entry_hour: facet_groups(&facets, "byEntryHour")?,
+            by_experiment: facet_groups(&facets, "byExperiment")?,
+            by_trend: facet_groups(&facets, "byTrend")?,
+            by_volatility: facet_groups(&facets, "byVolatility")?,
+        })
+    }
+
+    /// Aggregates the realized PnL and trade count of the closed trades matching `filter`, bucketed by weekday and hour of entry (in `timezone`).
+    pub async fn aggregate_stats_heatmap(
+        &self,
+        filter: Document,
+        conversion: &CurrencyConversion,
+        timezone: Tz
+    ) -> Result<StatsHeatmap, mongodb::error::Error> {
+        let mut pipeline = stats_match_stages(filter, conversion);
+        pipeline.extend([
+            doc! { "$addFields": { "openDate": timestamp_to_date("$openTimestamp") } },
+            doc! {
+                "$group": {
+                    "_id": {
+                        "weekday": { "$isoDayOfWeek": { "date": "$openDate", "timezone": timezone.name() } },
+                        "hour": { "$hour": { "date": "$openDate", "timezone": timezone.name() } },
+                    },
+                    "pnl": { "$sum": "$pnl" },
+                    "trades": { "$sum": 1 },
+                }
+            },
+            doc! { "$project": { "_id": 0, "weekday": "$_id.weekday", "hour": "$_id.hour", "pnl": 1, "trades": 1 } },
+        ]);
+
+        let mut cursor = self.closed_trade_collection
+            .aggregate(pipeline)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        let mut heatmap = StatsHeatmap {
+            currency: conversion.currency,
+            timezone,
+            weekdays: HEATMAP_WEEKDAYS.to_vec(),
+            pnl: vec![vec![0.0; 24]; HEATMAP_WEEKDAYS.len()],
+            trades: vec![vec![0; 24]; HEATMAP_WEEKDAYS.len()],
+        };
+
+        while cursor.advance().await? {
+            let bucket = from_document::<HeatmapBucket>(cursor.deserialize_current()?)?;
+
+            // ISO weekdays start from 1 (Monday)
+            let Some(weekday) = bucket.weekday.checked_sub(1) else { continue };
+
+            if weekday < HEATMAP_WEEKDAYS.len() && bucket.hour < 24 {
+                heatmap.pnl[weekday][bucket.hour] = bucket.pnl;
+                heatmap.trades[weekday][bucket.hour] = bucket.trades;
+            }
+        }
+
+        Ok(heatmap)
+    }
+
+    /// Aggregates side-by-side metrics of the strategies (alert names) in `names`,
+    /// including the pairwise correlation of their daily returns.
+    ///
+    /// If `experiment` is provided, only the trades opened under that experiment are compared.
+    pub async fn aggregate_stats_comparison(
+        &self,
+        names: &[String],
+        experiment: Option<&str>,
+        conversion: &CurrencyConversion,
+        timezone: Tz
+    ) -> Result<StatsComparison, mongodb::error::Error> {
+        let facets = doc! {
+            "stats": [performance_group_stage("$alertName")],
+            "daily": [{
+                "$group": {
+                    "_id": {
+                        "alertName": "$alertName",
+                        "day": { "$dateToString": { "format": "%Y-%m-%d", "date": timestamp_to_date("$closeTimestamp"), "timezone": timezone.name() } },
+                    },
+                    "pnl": { "$sum": "$pnl" },
+                }
+            }],
+            "sequences": [
+                { "$sort": { "closeTimestamp": 1 } },
+                { "$group": { "_id": "$alertName", "pnls": { "$push": "$pnl" } } },
+            ],
+        };
+
+        let mut filter = doc! { "alertName": { "$in": names } };
+
+        if let Some(experiment) = experiment {
+            filter.insert("experiment", experiment);
+        }
+
+        let facets = aggregate_facets(self, filter, conversion, facets).await?;
+
+        let mut stats_by_name: HashMap<String, PerformanceStats> = facet_groups(&facets, "stats")?
+            .into_iter()
+            .map(|group| (group.key, group.stats))
+            .collect();
+
+        // daily PnL per strategy, keyed by day so that the series of all strategies share the same (sorted) days
+        let mut daily_pnl: BTreeMap<String, HashMap<String, f64>> = BTreeMap::new();
+
+        for entry in facets.get_array("daily").map(|entries| entries.as_slice()).unwrap_or_default() {
+            let Some(entry) = entry.as_document() else { continue };
+            let Ok(id) = entry.get_document("_id") else { continue };
+            let (Ok(alert_name), Ok(day)) = (id.get_str("alertName"), id.get_str("day")) else { continue };
+
+            daily_pnl
+                .entry(day.to_string())
+                .or_default()
+                .insert(alert_name.to_string(), bson_to_f64(entry.get("pnl")));
+        }
+
+        let mut pnl_sequences: HashMap<String, Vec<f64>> = HashMap::new();
+
+        for sequence in facets.get_array("sequences").map(|sequences| sequences.as_slice()).unwrap_or_default() {
+            let Some(sequence) = sequence.as_document() else { continue };
+            let Ok(alert_name) = sequence.get_str("_id") else { continue };
+
+            let pnls = sequence
+                .get_array("pnls")
+                .map(|pnls| pnls.iter().map(|pnl| bson_to_f64(Some(pnl))).collect())
+                .unwrap_or_default();
+
+            pnl_sequences.insert(alert_name.to_string(), pnls);
+        }
+
+        let strategies = names
+            .iter()
+            .map(|name| {
+                let stats = stats_by_name.remove(name).unwrap_or_default();
+
+                StrategyComparison {
+                    alert_name: name.clone(),
+                    // equivalent to `win rate * average win - loss rate * average loss`
+                    expectancy: stats.average_pnl,
+                    max_drawdown: calc_max_drawdown(pnl_sequences.get(name).map(Vec::as_slice).unwrap_or_default()),
+                    stats,
+                }
+            })
+            .collect();
+
+        // days without closed trades count as zero PnL for that strategy
+        let daily_series = |name: &String| -> Vec<f64> {
+            daily_pnl.values().map(|pnls| pnls.get(name).copied().unwrap_or(0.0)).collect()
+        };
+
+        let mut correlations = Vec::new();
+
+        for (i, a) in names.iter().enumerate() {
+            for b in &names[i + 1..] {
+                correlations.push(DailyReturnCorrelation {
+                    a: a.clone(),
+                    b: b.clone(),
+                    correlation: calc_correlation(&daily_series(a), &daily_series(b)),
+                });
+            }
+        }
+
+        Ok(StatsComparison { currency: conversion.currency, timezone, strategies, correlations })
+    }
+
+    /// Counts the active trades matching `filter` server-side, without fetching them.
+    pub async fn count_active_trades(&self, filter: Document) -> Result<u64, mongodb::error::Error> {
+        let pipeline = vec![
+            doc! { "$match": filter },
+            doc! { "$count": "count" },
+        ];
+
+        let mut cursor = self.active_trade_collection
+            .aggregate(pipeline)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        // `$count` outputs no document at all if nothing matched
+        if cursor.advance().await? {
+            let count: Document = cursor.deserialize_current()?;
+            Ok(bson_to_f64(count.get("count")) as u64)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Sums the PnL of the closed trades matching `filter` server-side, converted into the reporting currency.
+    pub async fn sum_closed_pnl(&self, filter: Document, conversion: &CurrencyConversion) -> Result<f64, mongodb::error::Error> {
+        let mut pipeline = stats_match_stages(filter, conversion);
+        pipeline.push(doc! { "$group": { "_id": Bson::Null, "pnl": { "$sum": "$pnl" } } });
+
+        let mut cursor = self.closed_trade_collection
+            .aggregate(pipeline)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        if cursor.advance().await? {
+            let total: Document = cursor.deserialize_current()?;
+            Ok(bson_to_f64(total.get("pnl")))
+        } else {
+            Ok(0.0)
+        }
+    }
+}
+
+/// Resolves the timezone a stats request is bucketed in, defaulting to the timezone configured via `REPORT_TIMEZONE`.
+pub fn resolve_timezone(timezone: Option<Tz>) -> Tz {
+    timezone.unwrap_or_else(|| ReportLocale::from_env().timezone)
+}
+
+/// Resolves how the values of a stats request are converted, defaulting to the currency configured via `REPORTING_CURRENCY`.
+///
+/// Fails with `503 Service Unavailable` if the price feed hasn't received a rate for the requested currency yet,
+/// since trades closed without a stored rate couldn't be converted.
+fn resolve_conversion<T>(
+    app_state: &AppState,
+    currency: Option<ReportingCurrency>,
+    caller: &str
+) -> Result<CurrencyConversion, (StatusCode, Json<ApiResponse<T>>)> {
+    let currency = currency.unwrap_or_else(ReportingCurrency::from_env);
+
+    match app_state.current_fx_rates().rate(currency) {
+        Some(fallback_rate) => Ok(CurrencyConversion { currency, fallback_rate }),
+        None => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                status: "503 Service Unavailable",
+                code: None,
+                message: format!("({}) No conversion rate available for {:?} yet.", caller, currency),
+                data: None
+            })
+        )),
+    }
+}
+
+/// Returns the lifetime performance of closed trades, alongside rolling window (last 7/30/90 days) and month-over-month breakdowns.
+///
+/// Optionally filtered by `alert_name`, `pair` and `experiment` query parameters.
+pub async fn get_stats(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> (StatusCode, Json<ApiResponse<StatsOverview>>) {
+    let conversion = match resolve_conversion(&app_state, query.currency, "get_stats") {
+        Ok(conversion) => conversion,
+        Err(response) => return response,
+    };
+
+    match mongo_state.aggregate_stats_overview(stats_filter(&query), &conversion, resolve_timezone(query.timezone), app_state.clock.now()).await {
+        Ok(overview) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_stats) Fetched stats successfully.".to_string(),
+                data: Some(overview.rounded(&app_state.rounding))
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_stats) Failed to aggregate stats: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_stats) Failed to aggregate stats: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns the performance of closed trades grouped by pair, direction, leverage, local entry hour and market regime at entry (trend and volatility),
+/// to identify the instruments, sessions or market conditions a strategy performs poorly in.
+///
+/// Optionally filtered by `alert_name`, `pair` and `experiment` query parameters.
+pub async fn get_stats_breakdown(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> (StatusCode, Json<ApiResponse<StatsBreakdown>>) {
+    let conversion = match resolve_conversion(&app_state, query.currency, "get_stats_breakdown") {
+        Ok(conversion) => conversion,
+        Err(response) => return response,
+    };
+
+    match mongo_state.aggregate_stats_breakdown(stats_filter(&query), &conversion, resolve_timezone(query.timezone)).await {
+        Ok(breakdown) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_stats_breakdown) Fetched stats breakdown successfully.".to_string(),
+                data: Some(breakdown.rounded(&app_state.rounding))
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_stats_breakdown) Failed to aggregate stats breakdown: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_stats_breakdown) Failed to aggregate stats breakdown: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns a weekday × local hour matrix of the realized PnL and trade count of closed trades, bucketed by entry time.
+///
+/// Optionally filtered by `alert_name`, `pair` and `experiment` query parameters.
+pub async fn get_stats_heatmap(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> (StatusCode, Json<ApiResponse<StatsHeatmap>>) {
+    let conversion = match resolve_conversion(&app_state, query.currency, "get_stats_heatmap") {
+        Ok(conversion) => conversion,
+        Err(response) => return response,
+    };
+
+    match mongo_state.aggregate_stats_heatmap(stats_filter(&query), &conversion, resolve_timezone(query.timezone)).await {
+        Ok(heatmap) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_stats_heatmap) Fetched stats heatmap successfully.".to_string(),
+                data: Some(heatmap)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_stats_heatmap) Failed to aggregate stats heatmap: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_stats_heatmap) Failed to aggregate stats heatmap: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns side-by-side metrics (win rate, expectancy, drawdown) of multiple strategies, alongside the pairwise correlation
+/// of their daily returns, so that capital can be allocated between them.
+///
+/// The strategies are provided as a comma-separated `names` query parameter (e.g. `?names=a,b,c`), and can optionally
+/// be restricted to the trades of a single `experiment`.
+pub async fn get_stats_comparison(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<CompareQuery>,
+) -> (StatusCode, Json<ApiResponse<StatsComparison>>) {
+    let mut names: Vec<String> = Vec::new();
+
+    for name in query.names.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+
+    if names.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: "(get_stats_comparison) At least one alert name must be provided.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    let conversion = match resolve_conversion(&app_state, query.currency, "get_stats_comparison") {
+        Ok(conversion) => conversion,
+        Err(response) => return response,
+    };
+
+    match mongo_state.aggregate_stats_comparison(&names, query.experiment.as_deref(), &conversion, resolve_timezone(query.timezone)).await {
+        Ok(comparison) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_stats_comparison) Fetched stats comparison successfully.".to_string(),
+                data: Some(comparison.rounded(&app_state.rounding))
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_stats_comparison) Failed to aggregate stats comparison: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_stats_comparison) Failed to aggregate stats comparison: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns the number of active trades and the total PnL of closed trades, computed by the database,
+/// so that dashboards don't need to page through the collections just to show totals.
+///
+/// Optionally filtered by `alert_name`, `pair` and `experiment` query parameters.
+pub async fn get_stats_totals(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> (StatusCode, Json<ApiResponse<StatsTotals>>) {
+    let conversion = match resolve_conversion(&app_state, query.currency, "get_stats_totals") {
+        Ok(conversion) => conversion,
+        Err(response) => return response,
+    };
+
+    let filter = stats_filter(&query);
+
+    let totals = async {
+        Ok::<_, mongodb::error::Error>(StatsTotals {
+            currency: conversion.currency,
+            active_trades: mongo_state.count_active_trades(filter.clone()).await?,
+            closed_pnl: app_state.rounding.display_amount(mongo_state.sum_closed_pnl(filter, &conversion).await?),
+        })
+    };
+
+    match totals.await {
+        Ok(totals) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_stats_totals) Fetched totals successfully.".to_string(),
+                data: Some(totals)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_stats_totals) Failed to aggregate totals: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_stats_totals) Failed to aggregate totals: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,46 @@
+/// Calculates the maximum drawdown (in USDT value) of a sequence of realized PnLs, ordered by close time.
+///
+/// The drawdown is measured as the largest peak-to-trough decline of the cumulative PnL, starting from zero.
+/// Returned as a positive number (or zero if the cumulative PnL never declined).
+pub fn calc_max_drawdown(pnls: &[f64]) -> f64 {
+    let mut cumulative_pnl = 0.0;
+    let mut peak = 0.0_f64;
+    let mut max_drawdown = 0.0_f64;
+
+    for pnl in pnls {
+        cumulative_pnl += pnl;
+        peak = peak.max(cumulative_pnl);
+        max_drawdown = max_drawdown.max(peak - cumulative_pnl);
+    }
+
+    max_drawdown
+}
+
+/// Calculates the Pearson correlation coefficient between two equally long series (e.g. daily returns of two strategies).
+///
+/// Returns `None` if the series differ in length, contain less than two values, or if either series has no variance.
+pub fn calc_correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance_a = 0.0;
+    let mut variance_b = 0.0;
+
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return None;
+    }
+
+    Some(covariance / (variance_a.sqrt() * variance_b.sqrt()))
+}
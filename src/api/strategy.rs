@@ -0,0 +1,582 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, http::{header, HeaderMap}, Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, to_bson}, results::{DeleteResult, UpdateResult}, Cursor};
+
+use crate::{api::{outcome::validate_callback_url, scheduler::parse_cron_expression, script::validate_filter_script}, constants::{ALERT_SECRET_PLACEHOLDER, ALERT_WEBHOOK_PATH, DEFAULT_NOTIONAL_VALUE}, models::{AlertTemplate, ApiResponse, AppState, AuditAction, AuditActor, ExchangeProfile, MongoDBState, RequestId, Strategy, StrategyConfig, StrategyFromTemplate, StrategyParameters, StrategyTemplate, StrategyTemplateInfo, TradeLeverage}};
+
+/// CRUD operations for strategies in the database.
+impl MongoDBState {
+    /// Fetches all registered strategies, ordered by name.
+    pub async fn fetch_strategies(&self) -> Result<Vec<Strategy>, mongodb::error::Error> {
+        let mut cursor: Cursor<Strategy> = self.strategy_collection.find(doc! {}).sort(doc! { "name": 1 }).await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Fetches all strategies that have an enable or disable cron expression.
+    pub async fn fetch_scheduled_strategies(&self) -> Result<Vec<Strategy>, mongodb::error::Error> {
+        let mut cursor: Cursor<Strategy> = self
+            .strategy_collection
+            .find(doc! {
+                "$or": [
+                    { "enableCron": { "$type": "string" } },
+                    { "disableCron": { "$type": "string" } },
+                ]
+            })
+            .await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Fetches a strategy from the database based on the provided name (i.e. alert name).
+    pub async fn fetch_strategy(&self, name: &str) -> Result<Option<Strategy>, mongodb::error::Error> {
+        self.strategy_collection.find_one(doc! { "name": name }).await
+    }
+
+    /// Registers a strategy, or replaces the configuration of an existing one with the same name, updated at `now`.
+    pub async fn upsert_strategy(&self, name: &str, config: &StrategyConfig, now: DateTime<Utc>) -> Result<UpdateResult, mongodb::error::Error> {
+        self.strategy_collection
+            .update_one(
+                doc! { "name": name },
+                doc! {
+                    "$set": {
+                        "enabled": config.enabled,
+                        "experiment": &config.experiment,
+                        "enableCron": &config.enable_cron,
+                        "disableCron": &config.disable_cron,
+                        "callbackUrl": &config.callback_url,
+                        "filterScript": &config.filter_script,
+                        "seedFrom": config.seed_from.map(|seed_from| seed_from.timestamp()),
+                        "parameters": to_bson(&config.parameters).map_err(mongodb::error::Error::from)?,
+                        "updatedTimestamp": now.timestamp(),
+                    }
+                }
+            )
+            .upsert(true)
+            .await
+    }
+
+    /// Enables or disables a strategy, updated at `now`.
+    pub async fn set_strategy_enabled(&self, name: &str, enabled: bool, now: DateTime<Utc>) -> Result<UpdateResult, mongodb::error::Error> {
+        self.strategy_collection
+            .update_one(
+                doc! { "name": name },
+                doc! { "$set": { "enabled": enabled, "updatedTimestamp": now.timestamp() } }
+            )
+            .await
+    }
+
+    /// Deletes a strategy from the database based on the provided name.
+    pub async fn delete_strategy(&self, name: &str) -> Result<DeleteResult, mongodb::error::Error> {
+        self.strategy_collection.delete_one(doc! { "name": name }).await
+    }
+}
+
+/// Returns all registered strategies.
+pub async fn get_strategies(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+) -> (StatusCode, Json<ApiResponse<Vec<Strategy>>>) {
+    match mongo_state.fetch_strategies().await {
+        Ok(strategies) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_strategies) Fetched strategies successfully.".to_string(),
+                data: Some(strategies)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_strategies) Failed to fetch strategies: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_strategies) Failed to fetch strategies: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns a single strategy based on the provided name.
+pub async fn get_strategy(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(name): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Strategy>>) {
+    match mongo_state.fetch_strategy(&name).await {
+        Ok(Some(strategy)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_strategy) Fetched strategy successfully.".to_string(),
+                data: Some(strategy)
+            })
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(get_strategy) Strategy {} not found.", name),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_strategy) Failed to fetch strategy: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_strategy) Failed to fetch strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Registers a strategy or replaces its configuration (enabled state, experiment, enable/disable schedules, outcome callback, filter script, seed date and trading parameters).
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn put_strategy(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
+    Path(name): Path<String>,
+    Json(config): Json<StrategyConfig>,
+) -> (StatusCode, Json<ApiResponse<Strategy>>) {
+    // reject invalid cron expressions upfront, since the scheduler would otherwise silently skip them
+    for expression in [&config.enable_cron, &config.disable_cron].into_iter().flatten() {
+        if let Err(err) = parse_cron_expression(expression) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    status: "400 Bad Request",
+                    code: None,
+                    message: format!("(put_strategy) Invalid cron expression `{}`: {}", expression, err),
+                    data: None
+                })
+            )
+        }
+    }
+
+    if let Err(err) = validate_strategy_parameters(&config.parameters) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(put_strategy) Invalid parameters: {}", err),
+                data: None
+            })
+        )
+    }
+
+    if let Some(callback_url) = &config.callback_url {
+        if let Err(err) = validate_callback_url(callback_url) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    status: "400 Bad Request",
+                    code: None,
+                    message: format!("(put_strategy) Invalid callback URL `{}`. {}", callback_url, err),
+                    data: None
+                })
+            )
+        }
+    }
+
+    if let Err(err) = config.filter_script.as_deref().map_or(Ok(()), validate_filter_script) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(put_strategy) Invalid filter script: {}", err),
+                data: None
+            })
+        )
+    }
+
+    let now = app_state.clock.now();
+
+    if config.seed_from.is_some_and(|seed_from| seed_from >= now) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: "(put_strategy) The seed date must be in the past.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    let result = match mongo_state.upsert_strategy(&name, &config, now).await {
+        Ok(_) => mongo_state.fetch_strategy(&name).await,
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Ok(Some(strategy)) => {
+            mongo_state.record_audit(
+                AuditActor::Api,
+                AuditAction::Updated,
+                &name,
+                Some(format!(
+                    "enabled: {}, experiment: {:?}, enableCron: {:?}, disableCron: {:?}, callbackUrl: {:?}, filterScript: {}, seedFrom: {:?}, parameters: {:?}",
+                    strategy.enabled, strategy.experiment, strategy.enable_cron, strategy.disable_cron, strategy.callback_url, strategy.filter_script.is_some(), strategy.seed_from, strategy.parameters
+                )),
+                Some(&request_id.0),
+                now
+            ).await;
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: None,
+                    message: "(put_strategy) Saved strategy successfully.".to_string(),
+                    data: Some(strategy)
+                })
+            )
+        }
+        Ok(None) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse {
+                status: "500 Internal Server Error",
+                code: None,
+                message: format!("(put_strategy) Strategy {} not found after saving.", name),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(put_strategy) Failed to save strategy: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(put_strategy) Failed to save strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Deletes a strategy based on the provided name. Its alerts will be executed unconditionally afterwards.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn delete_strategy(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
+    Path(name): Path<String>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    match mongo_state.delete_strategy(&name).await {
+        Ok(result) if result.deleted_count == 0 => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(delete_strategy) Strategy {} not found.", name),
+                data: None
+            })
+        ),
+        Ok(_) => {
+            mongo_state.record_audit(AuditActor::Api, AuditAction::Deleted, &name, None, Some(&request_id.0), app_state.clock.now()).await;
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: None,
+                    message: "(delete_strategy) Deleted strategy successfully.".to_string(),
+                    data: None
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(delete_strategy) Failed to delete strategy: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(delete_strategy) Failed to delete strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+impl StrategyTemplate {
+    /// All built-in presets.
+    pub const ALL: [StrategyTemplate; 3] = [StrategyTemplate::Scalp, StrategyTemplate::Swing, StrategyTemplate::TrendFollow];
+
+    /// Returns the trading parameters bundled with the preset.
+    pub fn parameters(&self) -> StrategyParameters {
+        match self {
+            StrategyTemplate::Scalp => StrategyParameters {
+                notional_value: Some(DEFAULT_NOTIONAL_VALUE),
+                sizing_mode: None,
+                kelly_fraction: None,
+                target_volatility_percentage: None,
+                equity_percentage: None,
+                leverage: Some(TradeLeverage::Ten),
+                take_profit_percentage: Some(0.6),
+                stop_loss_percentage: Some(0.3),
+                trailing_stop_percentage: None,
+                cooldown_secs: Some(60),
+                exchange: None,
+                margin_mode: None,
+            },
+            StrategyTemplate::Swing => StrategyParameters {
+                notional_value: Some(DEFAULT_NOTIONAL_VALUE),
+                sizing_mode: None,
+                kelly_fraction: None,
+                target_volatility_percentage: None,
+                equity_percentage: None,
+                leverage: Some(TradeLeverage::Three),
+                take_profit_percentage: Some(8.0),
+                stop_loss_percentage: Some(4.0),
+                trailing_stop_percentage: None,
+                cooldown_secs: Some(4 * 60 * 60),
+                exchange: None,
+                margin_mode: None,
+            },
+            // no take profit, since the trailing stop exits the trade once the trend reverses
+            StrategyTemplate::TrendFollow => StrategyParameters {
+                notional_value: Some(DEFAULT_NOTIONAL_VALUE),
+                sizing_mode: None,
+                kelly_fraction: None,
+                target_volatility_percentage: None,
+                equity_percentage: None,
+                leverage: Some(TradeLeverage::Two),
+                take_profit_percentage: None,
+                stop_loss_percentage: Some(5.0),
+                trailing_stop_percentage: Some(3.0),
+                cooldown_secs: Some(60 * 60),
+                exchange: None,
+                margin_mode: None,
+            },
+        }
+    }
+}
+
+/// Checks that the trading parameters of a strategy are usable (i.e. positive sizes and distances).
+pub fn validate_strategy_parameters(parameters: &StrategyParameters) -> Result<(), String> {
+    if parameters.notional_value.is_some_and(|notional_value| notional_value <= 0.0) {
+        return Err("notionalValue must be positive".to_string());
+    }
+
+    if parameters.kelly_fraction.is_some_and(|fraction| fraction <= 0.0 || fraction > 1.0) {
+        return Err("kellyFraction must be between 0 and 1".to_string());
+    }
+
+    // leveraged trades may be worth more than the equity
+    if parameters.equity_percentage.is_some_and(|percentage| percentage <= 0.0) {
+        return Err("equityPercentage must be positive".to_string());
+    }
+
+    let percentages = [
+        ("takeProfitPercentage", parameters.take_profit_percentage),
+        ("targetVolatilityPercentage", parameters.target_volatility_percentage),
+        ("stopLossPercentage", parameters.stop_loss_percentage),
+        ("trailingStopPercentage", parameters.trailing_stop_percentage),
+    ];
+
+    for (field, percentage) in percentages {
+        // a stop 100% away from the entry price would never trigger (or be negative)
+        if percentage.is_some_and(|percentage| percentage <= 0.0 || percentage >= 100.0) {
+            return Err(format!("{} must be between 0 and 100", field));
+        }
+    }
+
+    if let Some(exchange) = &parameters.exchange {
+        if ExchangeProfile::for_exchange(exchange).is_none() {
+            return Err(format!("exchange must be one of: {}", ExchangeProfile::known_exchanges().collect::<Vec<_>>().join(", ")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the built-in strategy presets and their trading parameters.
+pub async fn get_strategy_templates() -> (StatusCode, Json<ApiResponse<Vec<StrategyTemplateInfo>>>) {
+    let templates = StrategyTemplate::ALL
+        .iter()
+        .map(|template| StrategyTemplateInfo { template: *template, parameters: template.parameters() })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_strategy_templates) Fetched strategy templates successfully.".to_string(),
+            data: Some(templates)
+        })
+    )
+}
+
+/// Registers a new strategy with the trading parameters of a built-in preset (scalp, swing or trend-follow).
+///
+/// Fails if a strategy with the same name is already registered, so that its configuration isn't silently replaced.
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn create_strategy_from_template(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
+    Json(body): Json<StrategyFromTemplate>,
+) -> (StatusCode, Json<ApiResponse<Strategy>>) {
+    match mongo_state.fetch_strategy(&body.name).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse {
+                    status: "409 Conflict",
+                    code: None,
+                    message: format!("(create_strategy_from_template) Strategy {} already exists.", body.name),
+                    data: None
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(create_strategy_from_template) Failed to fetch strategy: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(create_strategy_from_template) Failed to fetch strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+
+    let config = StrategyConfig {
+        enabled: body.enabled,
+        experiment: body.experiment,
+        enable_cron: None,
+        disable_cron: None,
+        callback_url: None,
+        filter_script: None,
+        seed_from: None,
+        parameters: body.template.parameters(),
+    };
+
+    put_strategy(Extension(mongo_state), Extension(app_state), Extension(request_id), Path(body.name), Json(config)).await
+}
+
+/// Renders the message of a TradingView strategy alert that deserializes into a `TradingViewAlert` for the strategy `name`.
+///
+/// The signal, pair, price and timestamps are TradingView placeholders. The take profit and stop loss are left out,
+/// since they're derived from the strategy's parameters.
+pub fn render_alert_template(name: &str, secret: &str) -> String {
+    // `serde_json` escapes the values, while the placeholders are inserted verbatim (`{{close}}` must be a bare number)
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+
+    [
+        "{".to_string(),
+        format!("  \"name\": {},", quote(name)),
+        "  \"signal\": \"{{strategy.order.action}}\",".to_string(),
+        "  \"pair\": \"{{ticker}}\",".to_string(),
+        "  \"price\": {{close}},".to_string(),
+        "  \"idempotency_key\": \"{{strategy.order.id}}-{{timenow}}\",".to_string(),
+        "  \"timestamp\": \"{{timenow}}\",".to_string(),
+        format!("  \"secret\": {}", quote(secret)),
+        "}".to_string(),
+    ].join("\n")
+}
+
+/// Resolves the base URL that TradingView reaches the bot at: the `PUBLIC_BASE_URL` env variable,
+/// or else the host that the request was sent to.
+fn public_base_url(headers: &HeaderMap) -> String {
+    if let Ok(base_url) = std::env::var("PUBLIC_BASE_URL") {
+        return base_url.trim_end_matches('/').to_string();
+    }
+
+    let host = headers.get(header::HOST).and_then(|host| host.to_str().ok()).unwrap_or("localhost");
+
+    format!("http://{}", host)
+}
+
+/// Returns the webhook URL and the exact alert message to configure on the TradingView alert of a registered strategy,
+/// so that its payload doesn't need to be written by hand.
+///
+/// The secret is always a placeholder, since the endpoint is public and the secret authorizes trades.
+pub async fn get_alert_template(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<ApiResponse<AlertTemplate>>) {
+    match mongo_state.fetch_strategy(&name).await {
+        Ok(Some(strategy)) => {
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: None,
+                    message: "(get_alert_template) Rendered alert template successfully.".to_string(),
+                    data: Some(AlertTemplate {
+                        webhook_url: format!("{}{}", public_base_url(&headers), ALERT_WEBHOOK_PATH),
+                        message: render_alert_template(&strategy.name, ALERT_SECRET_PLACEHOLDER),
+                    })
+                })
+            )
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(get_alert_template) Strategy {} not found.", name),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_alert_template) Failed to fetch strategy: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_alert_template) Failed to fetch strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
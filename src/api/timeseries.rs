@@ -0,0 +1,359 @@
+use std::{collections::BTreeMap, str::FromStr, sync::Arc, time::Duration as StdDuration};
+
+use axum::{extract::Query, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{self, doc, Document};
+
+use crate::{api::{calc_pnl, get_settlement_currency, to_coinbase_product_id}, constants::{EQUITY_SNAPSHOT_INTERVAL_SECS, MAX_BUFFERED_PRICE_TICKS, MAX_CHART_POINTS, PRICE_TICK_INTERVAL_SECS, TICK_PERSISTENCE_DOWNGRADE_SECS}, models::{ApiResponse, AppState, ChartPoint, ChartQuery, CurrencyConversion, EquitySnapshot, MongoDBState, PriceTick, PriceTickRecorder, ReportingCurrency, TickPersistence}};
+
+/// CRUD operations for price ticks and equity snapshots in the database.
+impl MongoDBState {
+    /// Inserts a batch of price ticks into the database.
+    pub async fn insert_price_ticks(&self, ticks: &[PriceTick]) -> Result<(), mongodb::error::Error> {
+        if ticks.is_empty() {
+            return Ok(());
+        }
+
+        self.price_tick_collection.insert_many(ticks).await.map(|_| ())
+    }
+
+    /// Inserts an equity snapshot into the database.
+    pub async fn insert_equity_snapshot(&self, snapshot: &EquitySnapshot) -> Result<(), mongodb::error::Error> {
+        self.equity_snapshot_collection.insert_one(snapshot).await.map(|_| ())
+    }
+
+    /// Fetches the price ticks of a product within the time range of `query`, oldest first (at most `MAX_CHART_POINTS`).
+    pub async fn fetch_price_ticks(&self, product: &str, query: &ChartQuery) -> Result<Vec<PriceTick>, mongodb::error::Error> {
+        let mut filter = chart_time_filter(query.from, query.to);
+        filter.insert("product", product.to_uppercase());
+
+        let cursor = self.price_tick_collection
+            .find(filter)
+            .sort(doc! { "timestamp": 1 })
+            .limit(MAX_CHART_POINTS)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+
+    /// Fetches the equity snapshots within the time range of `query`, oldest first (at most `MAX_CHART_POINTS`).
+    pub async fn fetch_equity_snapshots(&self, query: &ChartQuery) -> Result<Vec<EquitySnapshot>, mongodb::error::Error> {
+        let cursor = self.equity_snapshot_collection
+            .find(chart_time_filter(query.from, query.to))
+            .sort(doc! { "timestamp": 1 })
+            .limit(MAX_CHART_POINTS)
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+}
+
+/// Builds the filter on the (BSON date) `timestamp` field of a time-series collection.
+pub fn chart_time_filter(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Document {
+    let mut timestamp = Document::new();
+
+    if let Some(from) = from {
+        timestamp.insert("$gte", bson::DateTime::from_millis(from.timestamp_millis()));
+    }
+
+    if let Some(to) = to {
+        timestamp.insert("$lt", bson::DateTime::from_millis(to.timestamp_millis()));
+    }
+
+    if timestamp.is_empty() {
+        Document::new()
+    } else {
+        doc! { "timestamp": timestamp }
+    }
+}
+
+impl FromStr for TickPersistence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Ok(TickPersistence::None),
+            "sampled" => Ok(TickPersistence::Sampled),
+            "all" => Ok(TickPersistence::All),
+            _ => Err(format!("Unknown tick persistence: {}", s)),
+        }
+    }
+}
+
+impl PriceTickRecorder {
+    /// Reads the tick persistence from the `TICK_PERSISTENCE` env variable (`all`, `sampled` or `none`, defaults to `sampled`),
+    /// and the sample interval from `TICK_SAMPLE_INTERVAL_SECS` (defaults to `PRICE_TICK_INTERVAL_SECS`).
+    pub fn from_env() -> Self {
+        let persistence = match std::env::var("TICK_PERSISTENCE") {
+            Ok(persistence) => persistence.parse().unwrap_or_else(|err| {
+                eprintln!("(PriceTickRecorder::from_env) {}. Defaulting to sampled.", err);
+                TickPersistence::Sampled
+            }),
+            Err(_) => TickPersistence::Sampled,
+        };
+
+        let sample_interval_secs = std::env::var("TICK_SAMPLE_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(PRICE_TICK_INTERVAL_SECS);
+
+        Self { persistence, sample_interval_secs, ..Default::default() }
+    }
+
+    /// The tick persistence in effect at `now`, i.e. the configured one unless it's downgraded because of the load.
+    pub fn effective_persistence(&self, now: DateTime<Utc>) -> TickPersistence {
+        let downgraded = self.downgraded_until.lock().unwrap().is_some_and(|until| now < until);
+
+        if downgraded {
+            self.persistence.min(TickPersistence::Sampled)
+        } else {
+            self.persistence
+        }
+    }
+
+    /// Buffers a tick of `product_id` received from the price feed, if all ticks are persisted.
+    pub fn record(&self, product_id: &str, price: f64, now: DateTime<Utc>) {
+        if self.effective_persistence(now) != TickPersistence::All {
+            return;
+        }
+
+        self.buffer.lock().unwrap().push(PriceTick {
+            timestamp: bson::DateTime::from_millis(now.timestamp_millis()),
+            product: product_id.to_string(),
+            price,
+        });
+    }
+
+    /// Takes all buffered ticks. If there are more than `MAX_BUFFERED_PRICE_TICKS`, they're sampled instead,
+    /// and the persistence is downgraded to sampled ticks for `TICK_PERSISTENCE_DOWNGRADE_SECS`.
+    pub fn take(&self, now: DateTime<Utc>) -> Vec<PriceTick> {
+        let ticks = std::mem::take(&mut *self.buffer.lock().unwrap());
+
+        if ticks.len() <= MAX_BUFFERED_PRICE_TICKS {
+            return ticks;
+        }
+
+        eprintln!(
+            "(PriceTickRecorder::take) {} ticks were buffered since the last flush; only persisting sampled ticks for {} seconds.",
+            ticks.len(), TICK_PERSISTENCE_DOWNGRADE_SECS
+        );
+
+        *self.downgraded_until.lock().unwrap() = Some(now + Duration::seconds(TICK_PERSISTENCE_DOWNGRADE_SECS));
+
+        sample_price_ticks(ticks, self.sample_interval_secs)
+    }
+
+    /// The interval (in seconds) that backfilled prices are sampled with at `now`, or `None` if no ticks are persisted.
+    ///
+    /// When persisting all ticks, the last price within each second is kept.
+    pub fn backfill_interval_secs(&self, now: DateTime<Utc>) -> Option<u64> {
+        match self.effective_persistence(now) {
+            TickPersistence::None => None,
+            TickPersistence::Sampled => Some(self.sample_interval_secs),
+            TickPersistence::All => Some(1),
+        }
+    }
+}
+
+/// Samples price ticks (oldest first) by keeping the last tick of each product within each `interval_secs`.
+pub fn sample_price_ticks(ticks: Vec<PriceTick>, interval_secs: u64) -> Vec<PriceTick> {
+    let interval_millis = interval_secs.max(1) as i64 * 1000;
+    let mut samples: BTreeMap<(i64, String), PriceTick> = BTreeMap::new();
+
+    for tick in ticks {
+        samples.insert((tick.timestamp.timestamp_millis().div_euclid(interval_millis), tick.product.clone()), tick);
+    }
+
+    samples.into_values().collect()
+}
+
+/// Converts a BSON date into a chart point timestamp.
+fn to_chart_timestamp(timestamp: bson::DateTime) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(timestamp.timestamp_millis()).unwrap_or_default()
+}
+
+impl AppState {
+    /// Calculates the PnL (in USDT, excluding fees) that the active trades would realize if they were closed at the latest prices.
+    ///
+    /// Trades without a price, or settled in a currency without a USDT rate, are left out.
+    pub fn calc_unrealized_pnl(&self) -> f64 {
+        let trades: Vec<_> = self.active_trades.lock().unwrap().values().cloned().collect();
+        let prices = self.latest_prices.lock().unwrap().clone();
+
+        trades
+            .iter()
+            .filter_map(|trade| {
+                let price = to_coinbase_product_id(&trade.pair).and_then(|product_id| prices.get(&product_id).copied())?;
+                let settlement_currency = get_settlement_currency(&trade.pair, &trade.contract_type)?;
+                let usdt_rate = self.usdt_value_of(&settlement_currency)?;
+
+                let pnl = calc_pnl(trade.entry_price, price, trade.quantity, 0.0, 0.0, &trade.direction, &trade.contract_type);
+
+                Some(pnl * usdt_rate)
+            })
+            .sum()
+    }
+
+    /// Snapshots the current equity (realized and unrealized PnL) of the account into the database.
+    pub async fn save_equity_snapshot(&self) {
+        let conversion = CurrencyConversion { currency: ReportingCurrency::Usdt, fallback_rate: 1.0 };
+
+        let realized_pnl = match self.mongo_state.sum_closed_pnl(doc! {}, &conversion).await {
+            Ok(realized_pnl) => realized_pnl,
+            Err(err) => {
+                eprintln!("(save_equity_snapshot) Failed to sum closed PnL: {}", err);
+                return;
+            }
+        };
+
+        let unrealized_pnl = self.calc_unrealized_pnl();
+        let active_trades = self.active_trades.lock().unwrap().len() as u64;
+
+        let snapshot = EquitySnapshot {
+            timestamp: bson::DateTime::now(),
+            realized_pnl,
+            unrealized_pnl,
+            equity: realized_pnl + unrealized_pnl,
+            active_trades,
+        };
+
+        if let Err(err) = self.mongo_state.insert_equity_snapshot(&snapshot).await {
+            eprintln!("(save_equity_snapshot) Failed to save equity snapshot: {}", err);
+        }
+    }
+
+    /// Persists price ticks according to the tick persistence: either the latest price of each product received from the price feed,
+    /// or all ticks buffered since the last call.
+    pub async fn save_price_ticks(&self) {
+        let ticks: Vec<PriceTick> = match self.price_ticks.effective_persistence(self.clock.now()) {
+            TickPersistence::None => return,
+            TickPersistence::Sampled => {
+                let timestamp = bson::DateTime::now();
+
+                self.latest_prices
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|(product, price)| PriceTick { timestamp, product: product.clone(), price: *price })
+                    .collect()
+            }
+            TickPersistence::All => self.price_ticks.take(self.clock.now()),
+        };
+
+        if let Err(err) = self.mongo_state.insert_price_ticks(&ticks).await {
+            eprintln!("(save_price_ticks) Failed to save price ticks: {}", err);
+        }
+    }
+}
+
+/// Periodically persists price ticks (every `TICK_SAMPLE_INTERVAL_SECS`).
+///
+/// When running multiple instances, only the leader records ticks, so that they aren't duplicated.
+pub async fn start_price_tick_recorder(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(app_state.price_ticks.sample_interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        if app_state.leadership.is_leader() {
+            app_state.save_price_ticks().await;
+        }
+    }
+}
+
+/// Periodically snapshots the equity of the account.
+///
+/// When running multiple instances, only the leader takes snapshots, so that they aren't duplicated.
+pub async fn start_equity_snapshotter(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(StdDuration::from_secs(EQUITY_SNAPSHOT_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        if app_state.leadership.is_leader() {
+            app_state.save_equity_snapshot().await;
+        }
+    }
+}
+
+/// Returns the recorded prices of a product (`product` query parameter, e.g. SOL-USDT) for charting, oldest first.
+///
+/// Optionally limited to the `from` and `to` time range.
+pub async fn get_price_ticks(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<ChartQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ChartPoint>>>) {
+    let Some(product) = query.product.clone() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: "(get_price_ticks) The `product` query parameter is required.".to_string(),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.fetch_price_ticks(&product, &query).await {
+        Ok(ticks) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_price_ticks) Fetched price ticks successfully.".to_string(),
+                data: Some(ticks.into_iter().map(|tick| ChartPoint { timestamp: to_chart_timestamp(tick.timestamp), value: tick.price }).collect())
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_price_ticks) Failed to fetch price ticks: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_price_ticks) Failed to fetch price ticks: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns the snapshotted equity of the account (in USDT) for charting, oldest first.
+///
+/// Optionally limited to the `from` and `to` time range.
+pub async fn get_equity_history(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<ChartQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ChartPoint>>>) {
+    match mongo_state.fetch_equity_snapshots(&query).await {
+        Ok(snapshots) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_equity_history) Fetched equity snapshots successfully.".to_string(),
+                data: Some(snapshots.into_iter().map(|snapshot| ChartPoint { timestamp: to_chart_timestamp(snapshot.timestamp), value: snapshot.equity }).collect())
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_equity_history) Failed to fetch equity snapshots: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_equity_history) Failed to fetch equity snapshots: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
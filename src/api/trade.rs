@@ -1,21 +1,24 @@
-use std::{collections::HashMap, sync::{Arc, Mutex}};
+use std::{collections::HashMap, future::IntoFuture, sync::{atomic::Ordering, Arc, Mutex}};
 
-use axum::{Extension, Json};
-use chrono::Utc;
+use axum::{extract::{Path, Query}, Extension, Json};
+use chrono::{DateTime, Duration, Utc};
 use hyper::StatusCode;
 use mongodb::{bson::{doc, oid::ObjectId, to_bson, Document}, results::{DeleteResult, InsertOneResult, UpdateResult}, Cursor};
 use serde_json::Value;
 
-use crate::{api::{calc_final_execution_fees, calc_final_funding_fees, calc_liquidation_price, calc_pnl, calc_roe}, constants::{ACCEPTED_SYMBOLS, DEFAULT_LEVERAGE, DEFAULT_NOTIONAL_VALUE, MAX_PER_PAGE}, models::{tradingview::TradingViewAlert, ActiveTrade, ApiResponse, AppState, ClosedTrade, MongoDBState, TradeKind, TradeLeverage, TradeSignal}};
+use crate::{api::{alert::{alert_idempotency_key, alert_max_age_secs, check_alert_timestamp, complete_alert_claim, reject_alert, replay_alert_claim}, anomaly::detect_alert_anomalies, conflict::find_conflicting_trades, command::is_valid_command_secret, live::{close_active_trade, process_live_trade_alert}, outcome::send_alert_outcome, risk::enforce_daily_loss_limit, script::run_filter_script, calc_adjusted_exits, calc_final_execution_fees, calc_final_funding_fees, calc_funding_payments, calc_liquidation_fee, calc_liquidation_price, calc_notional_value, calc_order_quantity, calc_partial_liquidation, calc_percentage_exits, calc_pnl, calc_roe, calc_trailing_stop, clamp_to_isolated_margin, get_settlement_currency, is_liquidation_hit, matches_bulk_update, split_pair}, configs::{is_duplicate_key_error, retry_transient_write}, constants::{ACCEPTED_SYMBOLS, DEFAULT_LEVERAGE, DEFAULT_NOTIONAL_VALUE, MAX_CONCURRENT_ALERT_RETRIES, MAX_PER_PAGE, PAPER_TRADING_EXCHANGE}, models::{tradingview::TradingViewAlert, ActiveTrade, ApiResponse, AppState, AuditAction, AuditActor, BulkExitUpdate, ClosedTrade, ConflictPolicy, ExchangeProfile, ExitAdjustment, FilterDecision, ManualCloseRequest, MongoDBState, Notification, NotificationSeverity, RejectionReason, RequestId, ResponseCode, RoundingPolicy, StrategyParameters, TradeDirection, TradeEventKind, TradeKind, TradeListQuery}};
 
 /// A thread-safe map of active trades in memory.
 pub type ActiveTradesMap = Arc<Mutex<HashMap<ObjectId, ActiveTrade>>>;
 
 /// CRUD operations for active and closed trades in the database.
+#[allow(dead_code)]
 impl MongoDBState {
     /// Adds an active trade instance into the database. Called when a trade is executed.
-    pub async fn add_active_trade(&self, trade: ActiveTrade) -> Result<InsertOneResult, mongodb::error::Error> {
-        self.active_trade_collection.insert_one(trade).await
+    pub async fn add_active_trade(&self, mut trade: ActiveTrade) -> Result<InsertOneResult, mongodb::error::Error> {
+        trade.entry_order_id = trade.entry_order_id.map(|id| self.encrypt_field("entryOrderId", &id)).transpose()?;
+
+        retry_transient_write("add_active_trade", || self.chaos.disrupt_write("add_active_trade", self.active_trade_collection.insert_one(&trade).into_future())).await
     }
 
     /// Fetches all active trades with pagination and optional filtering
@@ -26,30 +29,27 @@ impl MongoDBState {
         page: u32,
         per_page: u32,
     ) -> Result<Vec<ActiveTrade>, mongodb::error::Error> {
-        let per_page = per_page.min(MAX_PER_PAGE as u32); // ensure per_page is within the limit `MAX_PER_PAGE`
-        let skip = (page - 1) * per_page;
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) has no trades
+        let Some(skip) = calc_page_skip(page, per_page) else {
+            return Ok(Vec::new())
+        };
 
-        let mut cursor: Cursor<ActiveTrade> = self
+        let cursor: Cursor<ActiveTrade> = self
             .active_trade_collection
             .find(filter.unwrap_or_default())
-            .skip(skip as u64)
+            .skip(skip)
             .limit(per_page as i64)
             .await?;
-        
-        let mut results = Vec::new();
-
-        while cursor.advance().await? {
-            let trade = cursor.deserialize_current()?;
 
-            results.push(trade);
-        }
-
-        Ok(results)
+        self.collect_documents(cursor).await?.into_iter().map(|trade| self.decrypt_active_trade(trade)).collect()
     }
 
     /// Fetches an active trade from the database based on the provided ID.
     pub async fn fetch_active_trade(&self, id: ObjectId) -> Result<Option<ActiveTrade>, mongodb::error::Error> {
-        self.active_trade_collection.find_one(doc! { "_id": id }).await
+        let trade = self.active_trade_collection.find_one(doc! { "_id": id }).await?;
+
+        trade.map(|trade| self.decrypt_active_trade(trade)).transpose()
     }
 
     /// Fetches an active trade from the database based on the provided alert name, pair and kind (APK).
@@ -60,24 +60,29 @@ impl MongoDBState {
         kind: &TradeKind,
     ) -> Result<Option<ActiveTrade>, mongodb::error::Error> {
         // convert TradeKind to Bson
-        let kind_bson = to_bson(&kind).map_err(|e| mongodb::error::Error::from(e))?;
+        let kind_bson = to_bson(&kind).map_err(mongodb::error::Error::from)?;
 
-        self.active_trade_collection.find_one(doc! { "alertName": alert_name, "pair": pair, "kind": kind_bson }).await
+        let trade = self.active_trade_collection.find_one(doc! { "alertName": alert_name, "pair": pair, "kind": kind_bson }).await?;
+
+        trade.map(|trade| self.decrypt_active_trade(trade)).transpose()
     }
 
     /// Updates an active trade in the database based on the provided ID.
     pub async fn update_active_trade(&self, id: ObjectId, update: Document) -> Result<UpdateResult, mongodb::error::Error> {
-        self.active_trade_collection.update_one(doc! { "_id": id }, update).await
+        retry_transient_write("update_active_trade", || self.chaos.disrupt_write("update_active_trade", self.active_trade_collection.update_one(doc! { "_id": id }, update.clone()).into_future())).await
     }
 
     /// Deletes an active trade from the database based on the provided ID.
     pub async fn delete_active_trade(&self, id: ObjectId) -> Result<DeleteResult, mongodb::error::Error> {
-        self.active_trade_collection.delete_one(doc! { "_id": id }).await
+        retry_transient_write("delete_active_trade", || self.chaos.disrupt_write("delete_active_trade", self.active_trade_collection.delete_one(doc! { "_id": id }).into_future())).await
     }
 
     /// Adds a closed trade instance into the database. Called when a trade is closed.
-    pub async fn add_closed_trade(&self, trade: ClosedTrade) -> Result<InsertOneResult, mongodb::error::Error> {
-        self.closed_trade_collection.insert_one(trade).await
+    pub async fn add_closed_trade(&self, mut trade: ClosedTrade) -> Result<InsertOneResult, mongodb::error::Error> {
+        trade.entry_order_id = trade.entry_order_id.map(|id| self.encrypt_field("entryOrderId", &id)).transpose()?;
+        trade.exit_order_id = trade.exit_order_id.map(|id| self.encrypt_field("exitOrderId", &id)).transpose()?;
+
+        retry_transient_write("add_closed_trade", || self.chaos.disrupt_write("add_closed_trade", self.closed_trade_collection.insert_one(&trade).into_future())).await
     }
 
     /// Fetches all closed trades with pagination and optional filtering
@@ -88,30 +93,27 @@ impl MongoDBState {
         page: u32,
         per_page: u32,
     ) -> Result<Vec<ClosedTrade>, mongodb::error::Error> {
-        let per_page = per_page.min(MAX_PER_PAGE as u32); // ensure per_page is within the limit `MAX_PER_PAGE`
-        let skip = (page - 1) * per_page;
+        let per_page = clamp_per_page(per_page); // ensure per_page is within the limit `MAX_PER_PAGE`
+        // a page that can't exist (e.g. because its offset overflows) has no trades
+        let Some(skip) = calc_page_skip(page, per_page) else {
+            return Ok(Vec::new())
+        };
 
-        let mut cursor: Cursor<ClosedTrade> = self
+        let cursor: Cursor<ClosedTrade> = self
             .closed_trade_collection
             .find(filter.unwrap_or_default())
-            .skip(skip as u64)
+            .skip(skip)
             .limit(per_page as i64)
             .await?;
-        
-        let mut results = Vec::new();
-
-        while cursor.advance().await? {
-            let trade = cursor.deserialize_current()?;
-
-            results.push(trade);
-        }
 
-        Ok(results)
+        self.collect_documents(cursor).await?.into_iter().map(|trade| self.decrypt_closed_trade(trade)).collect()
     }
 
     /// Fetches a closed trade from the database based on the provided ID.
     pub async fn fetch_closed_trade(&self, id: ObjectId) -> Result<Option<ClosedTrade>, mongodb::error::Error> {
-        self.closed_trade_collection.find_one(doc! { "_id": id }).await
+        let trade = self.closed_trade_collection.find_one(doc! { "_id": id }).await?;
+
+        trade.map(|trade| self.decrypt_closed_trade(trade)).transpose()
     }
 
     /// Updates a closed trade in the database based on the provided ID.
@@ -119,10 +121,216 @@ impl MongoDBState {
         self.closed_trade_collection.update_one(doc! { "_id": id }, update).await
     }
 
+    /// Fetches when the last trade (active or closed) of an alert name was opened.
+    pub async fn fetch_last_trade_open_timestamp(&self, alert_name: &str) -> Result<Option<DateTime<Utc>>, mongodb::error::Error> {
+        let filter = doc! { "alertName": alert_name };
+        let sort = doc! { "openTimestamp": -1 };
+
+        let active = self.active_trade_collection.find_one(filter.clone()).sort(sort.clone()).await?;
+        let closed = self.closed_trade_collection.find_one(filter).sort(sort).await?;
+
+        Ok(active.map(|trade| trade.open_timestamp).max(closed.map(|trade| trade.open_timestamp)))
+    }
+
     /// Deletes a closed trade from the database based on the provided ID.
     pub async fn delete_closed_trade(&self, id: ObjectId) -> Result<DeleteResult, mongodb::error::Error> {
         self.closed_trade_collection.delete_one(doc! { "_id": id }).await
     }
+
+    /// Decrypts the exchange order ID of a stored active trade.
+    pub fn decrypt_active_trade(&self, mut trade: ActiveTrade) -> Result<ActiveTrade, mongodb::error::Error> {
+        trade.entry_order_id = trade.entry_order_id.map(|id| self.decrypt_field("entryOrderId", &id)).transpose()?;
+
+        Ok(trade)
+    }
+
+    /// Decrypts the exchange order IDs of a stored closed trade.
+    pub fn decrypt_closed_trade(&self, mut trade: ClosedTrade) -> Result<ClosedTrade, mongodb::error::Error> {
+        trade.entry_order_id = trade.entry_order_id.map(|id| self.decrypt_field("entryOrderId", &id)).transpose()?;
+        trade.exit_order_id = trade.exit_order_id.map(|id| self.decrypt_field("exitOrderId", &id)).transpose()?;
+
+        Ok(trade)
+    }
+}
+
+/// Builds a new active paper trade from an alert, sized and leveraged according to the strategy's `parameters`
+/// (or `DEFAULT_NOTIONAL_VALUE` and `DEFAULT_LEVERAGE`).
+///
+/// The take profit and stop loss prices of the alert take precedence over the strategy's percentages.
+/// 
+/// `quote_usdt_value` is the value of 1 unit of the pair's quote currency in USDT.
+pub fn build_paper_trade(
+    alert: TradingViewAlert,
+    parameters: &StrategyParameters,
+    quote_usdt_value: f64,
+    rounding: &RoundingPolicy,
+    near_maintenance: bool,
+    request_id: &str,
+    open_timestamp: DateTime<Utc>
+) -> ActiveTrade {
+    let direction: TradeDirection = alert.signal.into();
+    let notional_value = parameters.notional_value.unwrap_or(DEFAULT_NOTIONAL_VALUE);
+    let leverage = parameters.leverage.unwrap_or(DEFAULT_LEVERAGE);
+    let (take_profit, stop_loss) = calc_percentage_exits(alert.price, &direction, parameters.take_profit_percentage, parameters.stop_loss_percentage);
+    // larger positions fall into higher maintenance margin tiers of the exchange
+    let exchange_profile = ExchangeProfile::resolve(parameters.exchange.as_deref());
+    let maintenance_margin_percentage = exchange_profile.maintenance_margin_percentage(notional_value);
+
+    ActiveTrade {
+        id: ObjectId::new(),
+        alert_name: alert.name,
+        pair: alert.pair,
+        kind: TradeKind::Paper,
+        open_timestamp,
+        quantity: calc_order_quantity(notional_value, alert.price, quote_usdt_value, &alert.contract_type, rounding),
+        entry_price: alert.price,
+        leverage,
+        contract_type: alert.contract_type,
+        liquidation_price: calc_liquidation_price(alert.price, leverage.into(), &direction, &alert.contract_type, maintenance_margin_percentage),
+        direction,
+        take_profit: alert.take_profit.or(take_profit),
+        stop_loss: alert.stop_loss.or(stop_loss),
+        near_maintenance,
+        experiment: alert.experiment,
+        originating_request_id: Some(request_id.to_string()),
+        trailing_stop_percentage: parameters.trailing_stop_percentage,
+        exchange: Some(exchange_profile.name),
+        margin_mode: parameters.margin_mode.unwrap_or_default(),
+        partial_liquidations: Vec::new(),
+        regime: None,
+        entry_fees: None,
+        entry_order_id: None,
+    }
+}
+
+/// The average notional value of a trade between opening it and exiting it at `exit_price`, which funding fees are estimated with.
+fn calc_average_notional_value(trade: &ActiveTrade, exit_price: f64) -> f64 {
+    (
+        calc_notional_value(trade.quantity, trade.entry_price, &trade.contract_type) +
+        calc_notional_value(trade.quantity, exit_price, &trade.contract_type)
+    ) / 2.0
+}
+
+/// Records the funding payments of a paper trade closed as `closed_trade` in the funding ledger (see `calc_funding_payments`).
+///
+/// Failing to record them doesn't fail closing the trade, so errors are only logged.
+pub async fn record_funding_payments(app_state: &AppState, trade: &ActiveTrade, closed_trade: &ClosedTrade) {
+    let payments = calc_funding_payments(
+        trade,
+        closed_trade.close_timestamp,
+        calc_average_notional_value(trade, closed_trade.exit_price),
+        &ExchangeProfile::resolve(trade.exchange.as_deref()).funding_schedule,
+        &app_state.trading_calendar.market_hours(&trade.pair),
+        &closed_trade.settlement_currency
+    );
+
+    if let Err(err) = app_state.mongo_state.add_funding_payments(&payments).await {
+        eprintln!("(record_funding_payments) Failed to record the funding payments of trade {}: {}", trade.id, err);
+    }
+}
+
+/// Builds the closed trade of a paper trade exited at `exit_price` now (according to the clock of `app_state`).
+/// 
+/// If `exit_price` crossed the liquidation price, the trade is liquidated instead: it's exited at the liquidation price,
+/// charged the liquidation fee of its exchange, and its loss is clamped to its isolated margin.
+/// 
+/// The PnL and ROE include the realized PnL of any partial liquidations of the trade.
+pub fn build_closed_paper_trade(app_state: &AppState, trade: &ActiveTrade, exit_price: f64) -> ClosedTrade {
+    build_closed_paper_trade_at(app_state, trade, exit_price, app_state.clock.now())
+}
+
+/// Builds the closed trade of a paper trade exited at `exit_price` at `close_timestamp` (e.g. when replaying past alerts).
+pub fn build_closed_paper_trade_at(app_state: &AppState, trade: &ActiveTrade, exit_price: f64, close_timestamp: DateTime<Utc>) -> ClosedTrade {
+    let exchange_profile = ExchangeProfile::resolve(trade.exchange.as_deref());
+
+    let liquidated = is_liquidation_hit(trade, exit_price);
+    let exit_price = if liquidated { trade.liquidation_price } else { exit_price };
+
+    let execution_fees = calc_final_execution_fees(
+        trade.quantity,
+        trade.entry_price,
+        &trade.contract_type,
+        exchange_profile.taker_fee_percentage
+    );
+
+    let funding_fees = calc_final_funding_fees(
+        trade.open_timestamp,
+        close_timestamp,
+        calc_average_notional_value(trade, exit_price),
+        &exchange_profile.funding_schedule,
+        &app_state.trading_calendar.market_hours(&trade.pair)
+    );
+
+    let pnl = calc_pnl(
+        trade.entry_price,
+        exit_price,
+        trade.quantity,
+        execution_fees,
+        funding_fees,
+        &trade.direction,
+        &trade.contract_type,
+    );
+
+    // partially liquidated quantities were already closed, but still count towards the margin and the result of the trade
+    let pnl = pnl + trade.partial_liquidations.iter().map(|step| step.pnl).sum::<f64>();
+    let original_quantity = trade.quantity + trade.partial_liquidations.iter().map(|step| step.quantity).sum::<f64>();
+
+    let (pnl, liquidation_fee) = if liquidated {
+        let liquidation_fee = calc_liquidation_fee(trade.quantity, exit_price, &trade.contract_type, exchange_profile.liquidation_fee_percentage);
+        let pnl = clamp_to_isolated_margin(pnl - liquidation_fee, original_quantity, trade.entry_price, trade.leverage.into(), &trade.contract_type);
+
+        (pnl, liquidation_fee)
+    } else {
+        (pnl, 0.0)
+    };
+    
+    let roe = calc_roe(
+        pnl,
+        trade.entry_price,
+        original_quantity,
+        trade.leverage.into(),
+        &trade.contract_type
+    );
+
+    let settlement_currency = get_settlement_currency(&trade.pair, &trade.contract_type)
+        .unwrap_or_else(|| "USDT".to_string());
+
+    ClosedTrade {
+        id: trade.id,
+        alert_name: trade.alert_name.clone(),
+        pair: trade.pair.clone(),
+        direction: trade.direction.clone(),
+        kind: trade.kind.clone(),
+        quantity: trade.quantity,
+        entry_price: trade.entry_price,
+        exit_price,
+        leverage: trade.leverage,
+        contract_type: trade.contract_type,
+        liquidation_price: trade.liquidation_price,
+        take_profit: trade.take_profit,
+        stop_loss: trade.stop_loss,
+        open_timestamp: trade.open_timestamp,
+        close_timestamp,
+        pnl,
+        roe,
+        // get the opening fee and add the closing fee
+        execution_fees,
+        // funding fee is simplified and estimated based on entry and exit prices
+        funding_fees,
+        fx_rates: app_state.current_fx_rates(),
+        settlement_usdt_rate: app_state.usdt_value_of(&settlement_currency),
+        settlement_currency,
+        near_maintenance: trade.near_maintenance,
+        experiment: trade.experiment.clone(),
+        originating_request_id: trade.originating_request_id.clone(),
+        import_key: None,
+        liquidated,
+        liquidation_fee,
+        partial_liquidations: trade.partial_liquidations.clone(),
+        regime: trade.regime.clone(),
+        entry_order_id: trade.entry_order_id.clone(),
+        exit_order_id: None,
+    }
 }
 
 /// Executes a paper trade based on the alert received from TradingView.
@@ -131,300 +339,1170 @@ impl MongoDBState {
 /// 
 /// Only one paper trade can exist for a given pair at a time, regardless of direction. If a new alert is received and is the opposite direction of the current trade,
 /// the current trade will be closed (a new one will NOT be opened). The next incoming alert will then determine the new trade's direction.
+///
+/// The outcome is returned as a machine-readable `code`, alongside a human-readable message unless `RESPONSE_VERBOSITY` is `minimal`.
 pub async fn execute_paper_trade(
     Extension(mongo_state): Extension<Arc<MongoDBState>>, 
     Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
+    payload: Json<Value>
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let verbosity = app_state.response_verbosity;
+
+    verbosity.apply(receive_trade_alert(mongo_state, app_state, request_id, payload, TradeKind::Paper).await)
+}
+
+/// Executes a live trade based on the alert received from TradingView, placing market orders on Binance (see `process_live_trade_alert`).
+///
+/// Requires the `live_trading` feature and the Binance API credentials (`BINANCE_API_KEY` and `BINANCE_API_SECRET`).
+pub async fn execute_live_trade(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>, 
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
     payload: Json<Value>
 ) -> (StatusCode, Json<ApiResponse<()>>) {
-    println!("Received payload: {:?}", payload);
+    let verbosity = app_state.response_verbosity;
+
+    verbosity.apply(receive_trade_alert(mongo_state, app_state, request_id, payload, TradeKind::Live).await)
+}
+
+/// Authenticates an alert and claims it, so that it's only executed once across replicas, before executing it as a trade of `kind`.
+async fn receive_trade_alert(
+    mongo_state: Arc<MongoDBState>,
+    app_state: Arc<AppState>,
+    request_id: RequestId,
+    payload: Json<Value>,
+    kind: TradeKind
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let handler = match kind {
+        TradeKind::Paper => "execute_paper_trade",
+        TradeKind::Live => "execute_live_trade",
+    };
+
+    println!("({}) [{}] Received payload: {:?}", handler, request_id.0, payload);
+
+    let payload = payload.0;
 
-    match serde_json::from_value::<TradingViewAlert>(payload.0) {
+    // when running multiple instances, only the leader executes alerts
+    if !app_state.can_execute().await {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                status: "503 Service Unavailable",
+                code: Some(ResponseCode::NotLeader),
+                message: format!("({}) This instance is not the leader.", handler),
+                data: None
+            })
+        )
+    }
+
+    match serde_json::from_value::<TradingViewAlert>(payload.clone()) {
         Ok(alert) => {
-            let expected_secret = std::env::var("TRADINGVIEW_SECRET").expect("(execute_paper_trade) TRADINGVIEW_SECRET must be set");
+            let expected_secret = std::env::var("TRADINGVIEW_SECRET").unwrap_or_else(|_| panic!("({}) TRADINGVIEW_SECRET must be set", handler));
 
             if alert.secret != expected_secret {
-                eprintln!("(execute_paper_trade) Invalid secret provided.");
-
-                return (
-                    StatusCode::UNAUTHORIZED,
-                    Json(ApiResponse {
-                        status: "401 Unauthorized",
-                        message: "(execute_paper_trade) Invalid secret provided.".to_string(),
-                        data: None
-                    })
-                )
+                return reject_alert(
+                    &app_state,
+                    &payload,
+                    &request_id.0,
+                    RejectionReason::InvalidSecret,
+                    (StatusCode::UNAUTHORIZED, "401 Unauthorized"),
+                    format!("({}) Invalid secret provided.", handler)
+                ).await
             }
 
-            // check if the symbol is accepted
-            if !ACCEPTED_SYMBOLS.contains(&alert.pair.to_uppercase().as_str()) {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse {
-                        status: "400 Bad Request",
-                        message: format!("(execute_paper_trade) Symbol {} not accepted", alert.pair),
-                        data: None,
-                    })
-                )
+            // block replayed captures of valid alerts
+            if let Err(reason) = check_alert_timestamp(alert.timestamp, app_state.clock.now(), alert_max_age_secs()) {
+                return reject_alert(
+                    &app_state,
+                    &payload,
+                    &request_id.0,
+                    RejectionReason::InvalidTimestamp,
+                    (StatusCode::UNAUTHORIZED, "401 Unauthorized"),
+                    format!("({}) Invalid timestamp: {}", handler, reason)
+                ).await
             }
 
-            // a check needs to be made to ensure that an active trade with the same pair, kind AND alert name doesn't already exist
-            // if it does exist:
-            // 1. if the direction is the same, do nothing (i.e. ignore the alert).
-            // 2. if the direction is the opposite, close the current trade and open a new one in this direction.
-            // if it doesn't exist, proceed to open a new trade.
-            if let Ok(Some(existing_trade)) = mongo_state.fetch_active_trade_by_apk(&alert.name, &alert.pair, &TradeKind::Paper).await {
-                println!("(execute_paper_trade) Existing trade found: {:?}", existing_trade);
+            // when running multiple replicas, only the replica claiming the alert executes it, while the others return its outcome
+            let idempotency_key = alert_idempotency_key(&alert, &payload);
 
-                if existing_trade.direction == alert.signal.into() {
-                    println!("(execute_paper_trade) Alert signal matches existing trade direction. Ignoring alert.");
+            match mongo_state.try_claim_alert(&idempotency_key, &app_state.instance_id, app_state.clock.now()).await {
+                Ok(None) => {}
+                Ok(Some(claim)) => {
+                    println!("({}) [{}] Alert {} was already claimed by {}.", handler, request_id.0, idempotency_key, claim.holder);
+                    return replay_alert_claim(claim)
+                }
+                Err(err) => {
+                    eprintln!("({}) [{}] Failed to claim alert: {}", handler, request_id.0, err);
 
                     return (
-                        StatusCode::OK,
+                        StatusCode::INTERNAL_SERVER_ERROR,
                         Json(ApiResponse {
-                            status: "200 OK",
-                            message: "(execute_paper_trade) Alert signal matches existing trade direction. Ignoring alert.".to_string(),
+                            status: "500 Internal Server Error",
+                            code: Some(ResponseCode::InternalError),
+                            message: format!("({}) Failed to claim alert: {}", handler, err),
                             data: None
                         })
                     )
-                } else {
-                    println!("(execute_paper_trade) Alert signal is opposite of existing trade direction. Closing existing trade and opening a new one.");
-                    
-                    let execution_fees = calc_final_execution_fees(
-                        existing_trade.quantity,
-                        existing_trade.entry_price
-                    );
-
-                    let funding_fees = calc_final_funding_fees(
-                        existing_trade.open_timestamp,
-                        Utc::now(),
-                        ((existing_trade.quantity * existing_trade.entry_price) + (existing_trade.quantity * alert.price)) / 2.0
-                    );
-
-                    let pnl = calc_pnl(
-                        existing_trade.entry_price,
-                        alert.price,
-                        existing_trade.quantity,
-                        execution_fees,
-                        funding_fees,
-                        &existing_trade.direction,
-                    );
-                    
-                    let roe = calc_roe(
-                        pnl,
-                        existing_trade.entry_price,
-                        existing_trade.quantity,
-                        existing_trade.leverage.into()
-                    );
-
-                    // close the existing trade and add it to the closed trades collection
-                    let closed_trade = ClosedTrade {
-                        id: existing_trade.id,
-                        alert_name: alert.name.clone(),
-                        pair: existing_trade.pair,
-                        direction: existing_trade.direction,
-                        kind: existing_trade.kind,
-                        quantity: existing_trade.quantity,
-                        entry_price: existing_trade.entry_price,
-                        exit_price: alert.price,
-                        leverage: existing_trade.leverage,
-                        liquidation_price: existing_trade.liquidation_price,
-                        open_timestamp: existing_trade.open_timestamp,
-                        close_timestamp: Utc::now(),
-                        pnl,
-                        roe,
-                        // get the opening fee and add the closing fee
-                        execution_fees,
-                        // funding fee is simplified and estimated based on entry and exit prices
-                        funding_fees,
-                    };
-
-                    // add the closed trade to the database. since this is a paper trade, no need to 
-                    // call any API to close the trade on the exchange.
-                    match mongo_state.add_closed_trade(closed_trade).await {
-                        Ok(_) => {
-                            // delete the existing trade from the active trades collection
-                            match mongo_state.delete_active_trade(existing_trade.id).await {
-                                Ok(_) => {
-                                    println!("(execute_paper_trade) Closed existing trade and added to closed trades collection. Now creating a new trade.");
-
-                                    // removes the trade from the ActiveTradesMap
-                                    {
-                                        let mut map = app_state.active_trades.lock().unwrap();
-                                        map.remove(&existing_trade.id);
-                                    }
+                }
+            }
 
-                                    // create a new trade based on the alert on the opposite direction
-                                    let new_active_trade = ActiveTrade {
-                                        id: ObjectId::new(),
-                                        alert_name: alert.name,
-                                        pair: alert.pair,
-                                        direction: alert.signal.into(),
-                                        kind: TradeKind::Paper,
-                                        open_timestamp: Utc::now(),
-                                        quantity: (DEFAULT_NOTIONAL_VALUE / alert.price * 100.0).round() / 100.0, // rounded to 2 dp
-                                        entry_price: alert.price,
-                                        leverage: DEFAULT_LEVERAGE,
-                                        liquidation_price: calc_liquidation_price(alert.price, DEFAULT_LEVERAGE.into(), &alert.signal.into()),
-                                        take_profit: alert.take_profit,
-                                        stop_loss: alert.stop_loss,
-                                    };
-
-                                    // add the new trade to the active trades collection
-                                    match mongo_state.add_active_trade(new_active_trade.clone()).await {
-                                        Ok(_) => {
-                                            println!("(execute_paper_trade) Opened new trade successfully.");
-
-                                            // insert the trade into the in-memory store
-                                            {
-                                                let mut map = app_state.active_trades.lock().unwrap();
-                                                map.insert(new_active_trade.id, new_active_trade);
-                                            }
-
-                                            return (
-                                                StatusCode::OK,
-                                                Json(ApiResponse {
-                                                    status: "200 OK",
-                                                    message: "(execute_paper_trade) Closed existing trade and added to closed trades collection. Also opened new trade successfully.".to_string(),
-                                                    data: None
-                                                })
-                                            )
-                                        }
-                                        Err(err) => {
-                                            eprintln!("(execute_paper_trade) Failed to open new trade: {}", err);
-
-                                            return (
-                                                StatusCode::INTERNAL_SERVER_ERROR,
-                                                Json(ApiResponse {
-                                                    status: "500 Internal Server Error",
-                                                    message: format!("(execute_paper_trade) Failed to open new trade: {}", err),
-                                                    data: None
-                                                })
-                                            )
+            let (alert_name, pair) = (alert.name.clone(), alert.pair.clone());
+            let response = match &kind {
+                TradeKind::Paper => process_paper_trade_alert(&mongo_state, &app_state, &payload, &request_id.0, alert).await,
+                TradeKind::Live => process_live_trade_alert(&mongo_state, &app_state, &payload, &request_id.0, alert).await,
+            };
+
+            complete_alert_claim(&mongo_state, &idempotency_key, &response).await;
+            send_alert_outcome(&app_state, &request_id.0, &alert_name, &pair, &kind, &response).await;
+
+            response
+        }
+        
+        Err(err) => {
+            reject_alert(
+                &app_state,
+                &payload,
+                &request_id.0,
+                RejectionReason::InvalidPayload,
+                (StatusCode::UNPROCESSABLE_ENTITY, "422 Unprocessable Entity"),
+                format!("({}) Failed to deserialize payload: {}", handler, err)
+            ).await
+        }
+    }
+}
+
+
+/// Whether alerts for `pair` are executed: it has to be one of the `ACCEPTED_SYMBOLS`, or a TradFi symbol (traded through its market calendar).
+pub fn is_accepted_symbol(app_state: &AppState, pair: &str) -> bool {
+    ACCEPTED_SYMBOLS.contains(&pair.to_uppercase().as_str()) || app_state.trading_calendar.is_tradfi(pair)
+}
+
+/// An alert rejected by `run_pre_trade_guards`: the reason, status and message to reject it with (see `reject_alert`).
+pub type PreTradeRejection = (RejectionReason, (StatusCode, &'static str), String);
+
+/// Runs the guards that every alert passes before a trade of `kind` is opened on `exchange`, once its exits and sizing are final:
+/// the per-trade risk cap, the anomaly guard, the conflicting-trade guard and, for live trades, the maintenance windows of the exchange.
+///
+/// Returns whether the trade is opened near a maintenance window of the exchange, or why the alert is rejected.
+pub async fn run_pre_trade_guards(
+    app_state: &AppState,
+    alert: &TradingViewAlert,
+    parameters: &mut StrategyParameters,
+    exchange: &str,
+    kind: &TradeKind
+) -> Result<bool, PreTradeRejection> {
+    let handler = match kind {
+        TradeKind::Paper => "execute_paper_trade",
+        TradeKind::Live => "execute_live_trade",
+    };
+
+    // the loss at the stop loss of the trade is capped to a fraction of the paper equity
+    if let Err(reason) = app_state.enforce_risk_cap(alert, parameters).await {
+        return Err((
+            RejectionReason::RiskCapExceeded,
+            (StatusCode::UNPROCESSABLE_ENTITY, "422 Unprocessable Entity"),
+            format!("({}) {}", handler, reason)
+        ))
+    }
+
+    // guard against fat-fingered prices, alert floods and strategies waking up after a long silence
+    let anomalies = detect_alert_anomalies(app_state, &app_state.mongo_state, alert).await;
+
+    for anomaly in &anomalies {
+        app_state.notifier.notify(Notification::new(
+            if anomaly.is_blocking() { NotificationSeverity::Warning } else { NotificationSeverity::Info },
+            if anomaly.is_blocking() { "Alert blocked" } else { "Alert flagged" },
+            format!("Anomaly detected on alert {} for {}: {:?}", alert.name, alert.pair, anomaly)
+        ));
+    }
+
+    if let Some(anomaly) = anomalies.iter().find(|anomaly| anomaly.is_blocking()) {
+        return Err((
+            RejectionReason::AnomalyDetected,
+            (StatusCode::UNPROCESSABLE_ENTITY, "422 Unprocessable Entity"),
+            format!("({}) Alert blocked due to an anomaly: {:?}", handler, anomaly)
+        ))
+    }
+
+    // opposing trades of other strategies on the same exchange account would cancel each other out in one-way mode
+    let conflicts: Vec<ActiveTrade> = {
+        let map = app_state.active_trades.lock().unwrap();
+        find_conflicting_trades(map.values(), &alert.name, &alert.pair, exchange, &alert.contract_type, &alert.signal.into())
+            .into_iter()
+            .cloned()
+            .collect()
+    };
+
+    if let Err(reason) = app_state.conflict_guard.check(exchange, &conflicts.iter().collect::<Vec<_>>()) {
+        return Err((
+            RejectionReason::PositionConflict,
+            (StatusCode::CONFLICT, "409 Conflict"),
+            format!("({}) {}", handler, reason)
+        ))
+    }
+
+    if !conflicts.is_empty() && app_state.conflict_guard.policy == ConflictPolicy::Net {
+        app_state.notifier.notify(Notification::new(
+            NotificationSeverity::Info,
+            "Opposing trades netted",
+            format!("The trade of {} on {} opposes {} trades of other strategies on {}; the account only holds the net position.", alert.name, alert.pair, conflicts.len(), exchange)
+        ));
+    }
+
+    let now = app_state.clock.now();
+
+    // no live orders are submitted while the exchange is under maintenance, since it would reject them
+    if matches!(kind, TradeKind::Live) {
+        match app_state.mongo_state.fetch_overlapping_maintenance_window(exchange, now, now).await {
+            Ok(Some(window)) => {
+                return Err((
+                    RejectionReason::ExchangeMaintenance,
+                    (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable"),
+                    format!("({}) {} is under maintenance until {}.", handler, exchange, window.end)
+                ))
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("({}) Failed to check maintenance windows: {}", handler, err),
+        }
+    }
+
+    // flag trades opened close to a maintenance window of the exchange, since orders around maintenance are unreliable
+    let near_maintenance = match app_state.mongo_state.is_near_maintenance(exchange, now).await {
+        Ok(near_maintenance) => near_maintenance,
+        Err(err) => {
+            eprintln!("({}) Failed to check maintenance windows: {}", handler, err);
+            false
+        }
+    };
+
+    if near_maintenance {
+        app_state.notifier.notify(Notification::new(
+            NotificationSeverity::Warning,
+            "Trade near exchange maintenance",
+            format!("{} is (or will be) under maintenance around this time. Flagging the trade of {} on {}.", exchange, alert.name, alert.pair)
+        ));
+    }
+
+    Ok(near_maintenance)
+}
+
+/// Executes an authenticated alert: validates it against the accepted symbols, its strategy and the anomaly guard,
+/// then opens (or flips) the paper trade of its strategy on the pair.
+async fn process_paper_trade_alert(
+    mongo_state: &MongoDBState,
+    app_state: &AppState,
+    payload: &Value,
+    request_id: &str,
+    mut alert: TradingViewAlert
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    if app_state.paused.load(Ordering::SeqCst) {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::Paused,
+            (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable"),
+            "(execute_paper_trade) The execution of alerts is paused.".to_string()
+        ).await
+    }
+
+    // check if the symbol is accepted (TradFi symbols are accepted through their market calendar)
+    if !is_accepted_symbol(app_state, &alert.pair) {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::SymbolNotAllowed,
+            (StatusCode::BAD_REQUEST, "400 Bad Request"),
+            format!("(execute_paper_trade) Symbol {} not accepted", alert.pair)
+        ).await
+    }
+
+    // TradFi symbols can't be traded while their market is closed
+    if !app_state.trading_calendar.is_open(&alert.pair, app_state.clock.now()) {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::MarketClosed,
+            (StatusCode::UNPROCESSABLE_ENTITY, "422 Unprocessable Entity"),
+            format!("(execute_paper_trade) The market of {} is closed.", alert.pair)
+        ).await
+    }
+
+    // alerts of registered strategies are only executed while the strategy is enabled
+    let (mut parameters, filter_script) = match mongo_state.fetch_strategy(&alert.name).await {
+        Ok(Some(strategy)) => {
+            if !strategy.enabled {
+                return reject_alert(
+                    app_state,
+                    payload,
+                    request_id,
+                    RejectionReason::StrategyDisabled,
+                    (StatusCode::FORBIDDEN, "403 Forbidden"),
+                    format!("(execute_paper_trade) Strategy {} is disabled.", strategy.name)
+                ).await
+            }
+
+            // the experiment of the alert takes precedence over the strategy's
+            if alert.experiment.is_none() {
+                alert.experiment = strategy.experiment;
+            }
+
+            (strategy.parameters, strategy.filter_script)
+        }
+        Ok(None) => (StrategyParameters::default(), None),
+        Err(err) => {
+            eprintln!("(execute_paper_trade) [{}] Failed to fetch strategy: {}", request_id, err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: Some(ResponseCode::InternalError),
+                    message: format!("(execute_paper_trade) Failed to fetch strategy: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    // strategies with a cooldown only open another trade once the cooldown since their last one has passed
+    if let Some(cooldown_secs) = parameters.cooldown_secs {
+        match mongo_state.fetch_last_trade_open_timestamp(&alert.name).await {
+            Ok(Some(last_open)) if app_state.clock.now() - last_open < Duration::seconds(cooldown_secs as i64) => {
+                return reject_alert(
+                    app_state,
+                    payload,
+                    request_id,
+                    RejectionReason::Cooldown,
+                    (StatusCode::TOO_MANY_REQUESTS, "429 Too Many Requests"),
+                    format!("(execute_paper_trade) Strategy {} is within its cooldown of {}s since its last trade.", alert.name, cooldown_secs)
+                ).await
+            }
+            Ok(_) => {}
+            Err(err) => eprintln!("(execute_paper_trade) [{}] Failed to fetch the last trade of {}: {}", request_id, alert.name, err),
+        }
+    }
+
+    // the notional value is sized by the sizing mode of the strategy, before the filter script may override it
+    if let Err(reason) = app_state.size_position(&alert.name, &alert.pair, &mut parameters).await {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::NoEdge,
+            (StatusCode::UNPROCESSABLE_ENTITY, "422 Unprocessable Entity"),
+            format!("(execute_paper_trade) {}", reason)
+        ).await
+    }
+
+    // the filter script of the strategy may reject the alert, or override its exits and sizing
+    if let Some(filter_script) = &filter_script {
+        let market_price = app_state.current_price(&alert.pair).await.ok();
+        let positions: Vec<ActiveTrade> = app_state.active_trades.lock().unwrap().values().cloned().collect();
+
+        match run_filter_script(filter_script, &alert, market_price, &positions) {
+            Ok(FilterDecision::Allow(overrides)) => overrides.apply(&mut alert, &mut parameters),
+            Ok(FilterDecision::Deny(reason)) => {
+                return reject_alert(
+                    app_state,
+                    payload,
+                    request_id,
+                    RejectionReason::FilteredByScript,
+                    (StatusCode::FORBIDDEN, "403 Forbidden"),
+                    format!("(execute_paper_trade) Alert rejected by the filter script of {}: {}", alert.name, reason)
+                ).await
+            }
+            Err(err) => {
+                app_state.notifier.notify(Notification::new(
+                    NotificationSeverity::Warning,
+                    "Filter script failed",
+                    format!("The filter script of {} failed on an alert for {}: {}", alert.name, alert.pair, err)
+                ));
+
+                return reject_alert(
+                    app_state,
+                    payload,
+                    request_id,
+                    RejectionReason::FilteredByScript,
+                    (StatusCode::UNPROCESSABLE_ENTITY, "422 Unprocessable Entity"),
+                    format!("(execute_paper_trade) The filter script of {} failed: {}", alert.name, err)
+                ).await
+            }
+        }
+    }
+
+    // the alert filter plugins compiled into the bot may reject the alert, or override its exits and sizing as well
+    if let Err((filter, reason)) = app_state.plugins.filter_alert(&mut alert, &mut parameters) {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::FilteredByPlugin,
+            (StatusCode::FORBIDDEN, "403 Forbidden"),
+            format!("(execute_paper_trade) Alert rejected by the {} plugin: {}", filter, reason)
+        ).await
+    }
+
+    // the exchange that the strategy intends to trade on
+    let exchange = parameters.exchange.clone().unwrap_or_else(|| PAPER_TRADING_EXCHANGE.to_string());
+
+    let near_maintenance = match run_pre_trade_guards(app_state, &alert, &mut parameters, &exchange, &TradeKind::Paper).await {
+        Ok(near_maintenance) => near_maintenance,
+        Err((reason, status, message)) => return reject_alert(app_state, payload, request_id, reason, status, message).await,
+    };
+
+    // the value of 1 unit of the pair's quote currency in USDT, used to size trades on pairs not quoted in USDT
+    let quote_usdt_value = match split_pair(&alert.pair).and_then(|(_, quote)| app_state.usdt_value_of(&quote)) {
+        Some(quote_usdt_value) => quote_usdt_value,
+        None => {
+            return reject_alert(
+                app_state,
+                payload,
+                request_id,
+                RejectionReason::NoConversionRate,
+                (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable"),
+                format!("(execute_paper_trade) No conversion rate available for the quote currency of {} yet.", alert.pair)
+            ).await
+        }
+    };
+
+    // the market regime of the pair at entry, so that stats can be broken down by regime
+    let regime = app_state.detect_market_regime(&alert.pair).await;
+
+    // the chaos mode may simulate the exchange rejecting the order
+    if mongo_state.chaos.rejects_order() {
+        return reject_alert(
+            app_state,
+            payload,
+            request_id,
+            RejectionReason::ExchangeRejected,
+            (StatusCode::BAD_GATEWAY, "502 Bad Gateway"),
+            format!("(execute_paper_trade) [chaos] {} rejected the order of {} on {}.", exchange, alert.name, alert.pair)
+        ).await
+    }
+
+    // concurrent alerts of the strategy on the pair wait for each other, so that they don't both open a trade.
+    // the unique index on the alert name, pair and kind of active trades covers alerts executed concurrently by other replicas,
+    // in which case the alert is executed again against the trade opened in the meantime.
+    let _alert_lock = app_state.lock_alert(&alert.name, &alert.pair).await;
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+
+        // a check needs to be made to ensure that an active trade with the same pair, kind AND alert name doesn't already exist
+        // if it does exist:
+        // 1. if the direction is the same, do nothing (i.e. ignore the alert).
+        // 2. if the direction is the opposite, close the current trade and open a new one in this direction.
+        // if it doesn't exist, proceed to open a new trade.
+        let response = if let Ok(Some(existing_trade)) = mongo_state.fetch_active_trade_by_apk(&alert.name, &alert.pair, &TradeKind::Paper).await {
+            println!("(execute_paper_trade) [{}] Existing trade found: {:?}", request_id, existing_trade);
+
+            if existing_trade.direction == alert.signal.into() {
+                println!("(execute_paper_trade) [{}] Alert signal matches existing trade direction. Ignoring alert.", request_id);
+
+                (
+                    StatusCode::OK,
+                    Json(ApiResponse {
+                        status: "200 OK",
+                        code: Some(ResponseCode::AlertIgnoredSameDirection),
+                        message: "(execute_paper_trade) Alert signal matches existing trade direction. Ignoring alert.".to_string(),
+                        data: None
+                    })
+                )
+            } else {
+                println!("(execute_paper_trade) [{}] Alert signal is opposite of existing trade direction. Closing existing trade and opening a new one.", request_id);
+            
+                // close the existing trade and add it to the closed trades collection
+                let closed_trade = build_closed_paper_trade(app_state, &existing_trade, alert.price);
+
+                // add the closed trade to the database. since this is a paper trade, no need to 
+                // call any API to close the trade on the exchange.
+                match mongo_state.add_closed_trade(closed_trade.clone()).await {
+                    Ok(_) => {
+                        // delete the existing trade from the active trades collection
+                        match mongo_state.delete_active_trade(existing_trade.id).await {
+                            Ok(_) => {
+                                println!("(execute_paper_trade) [{}] Closed existing trade and added to closed trades collection. Now creating a new trade.", request_id);
+
+                                // removes the trade from the ActiveTradesMap
+                                {
+                                    let mut map = app_state.active_trades.lock().unwrap();
+                                    map.remove(&existing_trade.id);
+                                }
+
+                                app_state.mqtt.publish_trade_event(TradeEventKind::Closed, &closed_trade.alert_name, &closed_trade);
+
+                                record_funding_payments(app_state, &existing_trade, &closed_trade).await;
+
+                                enforce_daily_loss_limit(app_state).await;
+
+                                // create a new trade based on the alert on the opposite direction
+                                let mut new_active_trade = build_paper_trade(alert.clone(), &parameters, quote_usdt_value, &app_state.rounding, near_maintenance, request_id, app_state.clock.now());
+                                new_active_trade.regime = regime.clone();
+
+                                // add the new trade to the active trades collection
+                                match mongo_state.add_active_trade(new_active_trade.clone()).await {
+                                    Ok(_) => {
+                                        println!("(execute_paper_trade) [{}] Opened new trade successfully.", request_id);
+
+                                        // insert the trade into the in-memory store, and make sure the price feed tracks its pair
+                                        app_state.subscribe_pair(&new_active_trade.pair);
+                                        app_state.mqtt.publish_trade_event(TradeEventKind::Opened, &new_active_trade.alert_name, &new_active_trade);
+                                        {
+                                            let mut map = app_state.active_trades.lock().unwrap();
+                                            map.insert(new_active_trade.id, new_active_trade);
                                         }
+
+                                        (
+                                            StatusCode::OK,
+                                            Json(ApiResponse {
+                                                status: "200 OK",
+                                                code: Some(ResponseCode::TradeFlipped),
+                                                message: "(execute_paper_trade) Closed existing trade and added to closed trades collection. Also opened new trade successfully.".to_string(),
+                                                data: None
+                                            })
+                                        )
+                                    }
+                                    Err(err) if is_duplicate_key_error(&err) && attempt < MAX_CONCURRENT_ALERT_RETRIES => {
+                                        println!("(execute_paper_trade) [{}] Another trade was opened concurrently. Executing the alert again.", request_id);
+                                        continue;
+                                    }
+                                    Err(err) => {
+                                        eprintln!("(execute_paper_trade) [{}] Failed to open new trade: {}", request_id, err);
+
+                                        (
+                                            StatusCode::INTERNAL_SERVER_ERROR,
+                                            Json(ApiResponse {
+                                                status: "500 Internal Server Error",
+                                                code: Some(ResponseCode::InternalError),
+                                                message: format!("(execute_paper_trade) Failed to open new trade: {}", err),
+                                                data: None
+                                            })
+                                        )
                                     }
-                                }
-                                Err(err) => {
-                                    eprintln!("(execute_paper_trade) Failed to delete existing trade: {}", err);
-
-                                    return (
-                                        StatusCode::INTERNAL_SERVER_ERROR,
-                                        Json(ApiResponse {
-                                            status: "500 Internal Server Error",
-                                            message: format!("(execute_paper_trade) Failed to delete existing trade: {}", err),
-                                            data: None
-                                        })
-                                    )
                                 }
                             }
-                        }
-                        Err(err) => {
-                            eprintln!("(execute_paper_trade) Failed to add closed trade: {}", err);
-
-                            return (
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                                Json(ApiResponse {
-                                    status: "500 Internal Server Error",
-                                    message: format!("(execute_paper_trade) Failed to add closed trade: {}", err),
-                                    data: None
-                                })
-                            )
-                        }
-                    }
-                }
-            // if no existing trade is found, proceed to open a new paper trade
-            } else {
-                println!("(execute_paper_trade) No existing trade found. Proceeding to open new trade.");
-
-                let active_trade = ActiveTrade {
-                    id: ObjectId::new(),
-                    alert_name: alert.name,
-                    pair: alert.pair,
-                    direction: alert.signal.into(),
-                    kind: TradeKind::Paper,
-                    open_timestamp: Utc::now(),
-                    quantity: (DEFAULT_NOTIONAL_VALUE / alert.price * 100.0).round() / 100.0, // rounded to 2 dp
-                    entry_price: alert.price,
-                    leverage: DEFAULT_LEVERAGE,
-                    liquidation_price: calc_liquidation_price(alert.price, DEFAULT_LEVERAGE.into(), &alert.signal.into()),
-                    take_profit: alert.take_profit,
-                    stop_loss: alert.stop_loss,
-                };
-
-                match mongo_state.add_active_trade(active_trade.clone()).await {
-                    Ok(_) => {
-                        println!("(execute_paper_trade) Opened new trade successfully.");
+                            Err(err) => {
+                                eprintln!("(execute_paper_trade) [{}] Failed to delete existing trade: {}", request_id, err);
 
-                        // insert the trade into the in-memory store
-                        {
-                            let mut map = app_state.active_trades.lock().unwrap();
-                            map.insert(active_trade.id, active_trade);
+                                (
+                                    StatusCode::INTERNAL_SERVER_ERROR,
+                                    Json(ApiResponse {
+                                        status: "500 Internal Server Error",
+                                        code: Some(ResponseCode::InternalError),
+                                        message: format!("(execute_paper_trade) Failed to delete existing trade: {}", err),
+                                        data: None
+                                    })
+                                )
+                            }
                         }
-
-                        return (
-                            StatusCode::OK,
-                            Json(ApiResponse {
-                                status: "200 OK",
-                                message: "(execute_paper_trade) Opened new trade successfully.".to_string(),
-                                data: None
-                            })
-                        )
                     }
                     Err(err) => {
-                        eprintln!("(execute_paper_trade) Failed to open new trade: {}", err);
+                        eprintln!("(execute_paper_trade) [{}] Failed to add closed trade: {}", request_id, err);
 
-                        return (
+                        (
                             StatusCode::INTERNAL_SERVER_ERROR,
                             Json(ApiResponse {
                                 status: "500 Internal Server Error",
-                                message: format!("(execute_paper_trade) Failed to open new trade: {}", err),
+                                code: Some(ResponseCode::InternalError),
+                                message: format!("(execute_paper_trade) Failed to add closed trade: {}", err),
                                 data: None
                             })
                         )
                     }
                 }
             }
-        }
-        
-        Err(err) => {
-            eprintln!("(execute_trade) Failed to deserialize payload: {}", err);
+        // if no existing trade is found, proceed to open a new paper trade
+        } else {
+            println!("(execute_paper_trade) [{}] No existing trade found. Proceeding to open new trade.", request_id);
 
-            (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponse {
-                    status: "422 Unprocessable Entity",
-                    message: format!("(execute_paper_trade) Failed to deserialize payload: {}", err),
-                    data: None
-                })
-            )
-        }
+            let mut active_trade = build_paper_trade(alert.clone(), &parameters, quote_usdt_value, &app_state.rounding, near_maintenance, request_id, app_state.clock.now());
+            active_trade.regime = regime.clone();
+
+            match mongo_state.add_active_trade(active_trade.clone()).await {
+                Ok(_) => {
+                    println!("(execute_paper_trade) [{}] Opened new trade successfully.", request_id);
+
+                    // insert the trade into the in-memory store, and make sure the price feed tracks its pair
+                    app_state.subscribe_pair(&active_trade.pair);
+                    app_state.mqtt.publish_trade_event(TradeEventKind::Opened, &active_trade.alert_name, &active_trade);
+                    {
+                        let mut map = app_state.active_trades.lock().unwrap();
+                        map.insert(active_trade.id, active_trade);
+                    }
+
+                    (
+                        StatusCode::OK,
+                        Json(ApiResponse {
+                            status: "200 OK",
+                            code: Some(ResponseCode::TradeOpened),
+                            message: "(execute_paper_trade) Opened new trade successfully.".to_string(),
+                            data: None
+                        })
+                    )
+                }
+                Err(err) if is_duplicate_key_error(&err) && attempt < MAX_CONCURRENT_ALERT_RETRIES => {
+                    println!("(execute_paper_trade) [{}] Another trade was opened concurrently. Executing the alert again.", request_id);
+                    continue;
+                }
+                Err(err) => {
+                    eprintln!("(execute_paper_trade) [{}] Failed to open new trade: {}", request_id, err);
+
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse {
+                            status: "500 Internal Server Error",
+                            code: Some(ResponseCode::InternalError),
+                            message: format!("(execute_paper_trade) Failed to open new trade: {}", err),
+                            data: None
+                        })
+                    )
+                }
+            }
+        };
+
+        break response;
     }
 }
 
-
-/// Closes an active paper trade if either:
+/// Closes an active paper trade at `exit_price`, e.g. if either:
 /// 1) The take profit price is hit.
 /// 2) The stop loss price is hit.
 /// 3) The liquidation price is hit.
+/// 4) An operator closes it manually.
 /// - Removes from in-memory
 /// - Moves to closed trades collection in DB
+/// 
+/// Returns the closed trade, or `None` if the trade isn't active (e.g. it was already closed).
 pub async fn close_paper_trade(
     app_state: &AppState, 
     trade_id: &ObjectId,
     exit_price: f64
-) {
+) -> Result<Option<ClosedTrade>, mongodb::error::Error> {
     // remove from in-memory so we don't close it twice
-    {
+    let trade = {
         let mut map = app_state.active_trades.lock().unwrap();
-        map.remove(trade_id);
-    }
+        map.remove(trade_id)
+    };
+
+    let Some(trade) = trade else {
+        return Ok(None)
+    };
+
+    let closed_trade = build_closed_paper_trade(app_state, &trade, exit_price);
+
+    let result = async {
+        app_state.mongo_state.add_closed_trade(closed_trade.clone()).await?;
+        app_state.mongo_state.delete_active_trade(trade.id).await
+    }.await;
+
+    if let Err(err) = result {
+        // put the trade back, so that closing it can be retried
+        app_state.active_trades.lock().unwrap().insert(trade.id, trade);
 
-    // 2. Insert into "closed trades" + remove from "active trades" in DB
-    //    Reuse your existing logic (calc fees, PnL, etc.)
-    //    or replicate your existing "closing" code.
-    //    For example:
-    //        let closed_trade = ClosedTrade { ... };
-    //        app_state.mongo_state.add_closed_trade(closed_trade).await?;
-    //        app_state.mongo_state.delete_active_trade(trade.id).await?;
+        return Err(err)
+    }
 
     println!("Trade {} closed at price {}", trade_id, exit_price);
-}
\ No newline at end of file
+
+    app_state.mqtt.publish_trade_event(TradeEventKind::Closed, &closed_trade.alert_name, &closed_trade);
+
+    record_funding_payments(app_state, &trade, &closed_trade).await;
+
+    enforce_daily_loss_limit(app_state).await;
+
+    Ok(Some(closed_trade))
+}
+impl AppState {
+    /// Moves the stop loss of a trailing trade along with `current_price` (see `calc_trailing_stop`), in memory and in the database.
+    ///
+    /// Returns the trade with its (possibly) updated stop loss.
+    pub async fn trail_stop_loss(&self, mut trade: ActiveTrade, current_price: f64) -> ActiveTrade {
+        let Some(stop_loss) = calc_trailing_stop(&trade, current_price) else {
+            return trade
+        };
+
+        trade.stop_loss = Some(stop_loss);
+
+        {
+            let mut map = self.active_trades.lock().unwrap();
+
+            // the trade may have been closed in the meantime
+            match map.get_mut(&trade.id) {
+                Some(active_trade) => active_trade.stop_loss = Some(stop_loss),
+                None => return trade,
+            }
+        }
+
+        if let Err(err) = self.mongo_state.update_active_trade(trade.id, doc! { "$set": { "stopLoss": stop_loss } }).await {
+            eprintln!("(trail_stop_loss) Failed to save the trailed stop loss of trade {}: {}", trade.id, err);
+        }
+
+        trade
+    }
+
+    /// Liquidates a cross margin trade in steps (see `calc_partial_liquidation`) for as long as `current_price` crosses its liquidation price,
+    /// in memory and in the database. Each step is recorded in the audit log.
+    ///
+    /// Returns the partially liquidated trade, or `None` if it has to be liquidated entirely (always the case unless the
+    /// `auto_liquidation` feature is enabled).
+    pub async fn partially_liquidate(&self, mut trade: ActiveTrade, current_price: f64) -> Option<ActiveTrade> {
+        if !self.features.auto_liquidation {
+            return None;
+        }
+
+        let exchange_profile = ExchangeProfile::resolve(trade.exchange.as_deref());
+        let quote_usdt_value = split_pair(&trade.pair)
+            .and_then(|(_, quote)| self.usdt_value_of(&quote))
+            .unwrap_or(1.0);
+
+        while is_liquidation_hit(&trade, current_price) {
+            let step = calc_partial_liquidation(&trade, &exchange_profile, quote_usdt_value, &self.rounding, self.clock.now())?;
+
+            trade.quantity -= step.quantity;
+            trade.liquidation_price = step.next_liquidation_price;
+            trade.partial_liquidations.push(step.clone());
+
+            {
+                let mut map = self.active_trades.lock().unwrap();
+
+                // the trade may have been closed in the meantime
+                match map.get_mut(&trade.id) {
+                    Some(active_trade) => *active_trade = trade.clone(),
+                    None => return None,
+                }
+            }
+
+            let update = doc! {
+                "$set": {
+                    "quantity": trade.quantity,
+                    "liquidationPrice": trade.liquidation_price,
+                    "partialLiquidations": to_bson(&trade.partial_liquidations).unwrap_or_default(),
+                }
+            };
+
+            if let Err(err) = self.mongo_state.update_active_trade(trade.id, update).await {
+                eprintln!("(partially_liquidate) Failed to save the partial liquidation of trade {}: {}", trade.id, err);
+            }
+
+            self.mongo_state.record_audit(
+                AuditActor::PriceListener,
+                AuditAction::PartiallyLiquidated,
+                &trade.id.to_hex(),
+                Some(format!(
+                    "Closed {} at {} (PnL: {:.2}), {} remaining with liquidation price {}",
+                    step.quantity, step.price, step.pnl, trade.quantity, trade.liquidation_price
+                )),
+                None,
+                self.clock.now()
+            ).await;
+
+            self.mqtt.publish_trade_event(TradeEventKind::PartiallyLiquidated, &trade.alert_name, &trade);
+        }
+
+        Some(trade)
+    }
+}
+
+/// Validates the adjustments of a bulk update: at least one exit has to be adjusted, to a positive price or percentage.
+pub fn validate_bulk_exit_update(update: &BulkExitUpdate) -> Result<(), String> {
+    if update.take_profit.is_none() && update.stop_loss.is_none() {
+        return Err("At least one of takeProfit and stopLoss must be provided.".to_string());
+    }
+
+    if update.pair.is_none() && update.alert_name.is_none() && !update.all_trades {
+        return Err("At least one of pair and alertName must be provided, or allTrades must be set to update all trades.".to_string());
+    }
+
+    for (name, adjustment) in [("takeProfit", update.take_profit), ("stopLoss", update.stop_loss)] {
+        let value = match adjustment {
+            Some(ExitAdjustment::Price(value)) | Some(ExitAdjustment::Percentage(value)) => value,
+            None => continue,
+        };
+
+        if !value.is_finite() || value <= 0.0 {
+            return Err(format!("The {} must be a positive number, got {}.", name, value));
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves the take profit and/or stop loss of all active trades matching the `pair` and `alertName` filters at once (e.g. before news events),
+/// either to a price or a percentage away from the entry price of each trade.
+///
+/// The trades are updated in the database first. If any of the updates fails, the trades updated so far are reverted, so that either all
+/// or none of the trades are updated. Only then are the trades updated in memory, all at once.
+///
+/// Requests are authenticated with the `COMMAND_SECRET` env variable.
+pub async fn bulk_update_active_trades(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Extension(request_id): Extension<RequestId>,
+    Json(update): Json<BulkExitUpdate>,
+) -> (StatusCode, Json<ApiResponse<Vec<ActiveTrade>>>) {
+    if !is_valid_command_secret(app_state.command_secret.as_deref(), &update.secret) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse {
+                status: "401 Unauthorized",
+                code: None,
+                message: "(bulk_update_active_trades) Invalid secret provided.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    // when running multiple instances, only the leader holds the trades it updates
+    if !app_state.can_execute().await {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                status: "503 Service Unavailable",
+                code: Some(ResponseCode::NotLeader),
+                message: "(bulk_update_active_trades) This instance is not the leader.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    if let Err(err) = validate_bulk_exit_update(&update) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(bulk_update_active_trades) {}", err),
+                data: None
+            })
+        )
+    }
+
+    // each matching trade with its new exits
+    let changes = {
+        let map = app_state.active_trades.lock().unwrap();
+        map.values()
+            .filter(|trade| matches_bulk_update(trade, &update))
+            .map(|trade| (trade.clone(), calc_adjusted_exits(trade, &update)))
+            .collect::<Vec<_>>()
+    };
+
+    let exits_update = |(take_profit, stop_loss): (Option<f64>, Option<f64>)| doc! { "$set": { "takeProfit": take_profit, "stopLoss": stop_loss } };
+
+    for (index, (trade, exits)) in changes.iter().enumerate() {
+        if let Err(err) = app_state.mongo_state.update_active_trade(trade.id, exits_update(*exits)).await {
+            eprintln!("(bulk_update_active_trades) Failed to update trade {}: {}. Reverting {} trades.", trade.id, err, index);
+
+            for (previous, _) in &changes[..index] {
+                if let Err(err) = app_state.mongo_state.update_active_trade(previous.id, exits_update((previous.take_profit, previous.stop_loss))).await {
+                    eprintln!("(bulk_update_active_trades) Failed to revert trade {}: {}", previous.id, err);
+                }
+            }
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(bulk_update_active_trades) Failed to update trade {}: {}", trade.id, err),
+                    data: None
+                })
+            )
+        }
+    }
+
+    let updated_trades: Vec<ActiveTrade> = {
+        let mut map = app_state.active_trades.lock().unwrap();
+
+        // trades closed in the meantime are skipped
+        changes
+            .iter()
+            .filter_map(|(trade, (take_profit, stop_loss))| {
+                let trade = map.get_mut(&trade.id)?;
+                trade.take_profit = *take_profit;
+                trade.stop_loss = *stop_loss;
+                Some(trade.clone())
+            })
+            .collect()
+    };
+
+    for trade in &updated_trades {
+        app_state.mongo_state.record_audit(
+            AuditActor::Api,
+            AuditAction::Updated,
+            &trade.id.to_hex(),
+            Some(format!("takeProfit: {:?}, stopLoss: {:?}", trade.take_profit, trade.stop_loss)),
+            Some(&request_id.0),
+            app_state.clock.now()
+        ).await;
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: format!("(bulk_update_active_trades) Updated {} trades successfully.", updated_trades.len()),
+            data: Some(updated_trades)
+        })
+    )
+}
+
+/// Builds the MongoDB filter of the trades (active or closed) matching `query`.
+pub fn trade_list_filter(query: &TradeListQuery) -> Document {
+    let mut filter = Document::new();
+
+    if let Some(pair) = &query.pair {
+        filter.insert("pair", pair.to_uppercase());
+    }
+
+    if let Some(direction) = &query.direction {
+        filter.insert("direction", to_bson(direction).unwrap_or_default());
+    }
+
+    if let Some(kind) = &query.kind {
+        filter.insert("kind", to_bson(kind).unwrap_or_default());
+    }
+
+    filter
+}
+
+/// Clamps the page size of a trade list to `1..=MAX_PER_PAGE`.
+pub fn clamp_per_page(per_page: u32) -> u32 {
+    per_page.clamp(1, MAX_PER_PAGE as u32)
+}
+
+/// Calculates how many trades to skip to reach the 1-based `page` of a trade list.
+///
+/// Returns `None` if the page is 0 or the amount overflows, i.e. the page can't exist.
+pub fn calc_page_skip(page: u32, per_page: u32) -> Option<u64> {
+    page.checked_sub(1)?.checked_mul(per_page).map(u64::from)
+}
+
+/// Builds the response to a trade list request whose page can't exist.
+fn invalid_page_response<T>(handler: &str, page: u32) -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse {
+            status: "400 Bad Request",
+            code: None,
+            message: format!("({}) Page {} is out of range.", handler, page),
+            data: None
+        })
+    )
+}
+
+/// Returns the active trades, optionally filtered by pair, direction and kind.
+pub async fn get_active_trades(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<TradeListQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ActiveTrade>>>) {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = clamp_per_page(query.per_page.unwrap_or(MAX_PER_PAGE as u32));
+
+    if calc_page_skip(page, per_page).is_none() {
+        return invalid_page_response("get_active_trades", page)
+    }
+
+    match mongo_state.fetch_active_trades(Some(trade_list_filter(&query)), page, per_page).await {
+        Ok(trades) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_active_trades) Fetched active trades successfully.".to_string(),
+                data: Some(trades)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_active_trades) Failed to fetch active trades: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_active_trades) Failed to fetch active trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Returns the closed trades, optionally filtered by pair, direction and kind.
+pub async fn get_closed_trades(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Query(query): Query<TradeListQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ClosedTrade>>>) {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = clamp_per_page(query.per_page.unwrap_or(MAX_PER_PAGE as u32));
+
+    if calc_page_skip(page, per_page).is_none() {
+        return invalid_page_response("get_closed_trades", page)
+    }
+
+    match mongo_state.fetch_closed_trades(Some(trade_list_filter(&query)), page, per_page).await {
+        Ok(trades) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_closed_trades) Fetched closed trades successfully.".to_string(),
+                data: Some(trades)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_closed_trades) Failed to fetch closed trades: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_closed_trades) Failed to fetch closed trades: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Closes an active trade manually (e.g. to exit a position without waiting for an alert), at the `exitPrice` of the body, or at the current
+/// market price if it's omitted. Its PnL and fees are calculated like those of any other closed trade. Requests are authenticated with
+/// the `COMMAND_SECRET` env variable.
+///
+/// Live trades are closed with a market order on their exchange, at the price it fills at, so they don't accept an `exitPrice`.
+pub async fn close_trade(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(body): Json<ManualCloseRequest>,
+) -> (StatusCode, Json<ApiResponse<ClosedTrade>>) {
+    if !is_valid_command_secret(app_state.command_secret.as_deref(), &body.secret) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse {
+                status: "401 Unauthorized",
+                code: None,
+                message: "(close_trade) Invalid secret provided.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    // when running multiple instances, only the leader holds the trades it closes
+    if !app_state.can_execute().await {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                status: "503 Service Unavailable",
+                code: Some(ResponseCode::NotLeader),
+                message: "(close_trade) This instance is not the leader.".to_string(),
+                data: None
+            })
+        )
+    }
+
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(close_trade) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    let trade = app_state.active_trades.lock().unwrap().get(&id).cloned();
+    let Some(trade) = trade else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(close_trade) Trade {} is not active.", id),
+                data: None
+            })
+        )
+    };
+
+    let exit_price = match body.exit_price {
+        Some(_) if matches!(trade.kind, TradeKind::Live) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    status: "400 Bad Request",
+                    code: None,
+                    message: "(close_trade) Live trades close at the price their order fills at; omit exitPrice.".to_string(),
+                    data: None
+                })
+            )
+        }
+        Some(exit_price) if exit_price.is_finite() && exit_price > 0.0 => exit_price,
+        Some(exit_price) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    status: "400 Bad Request",
+                    code: None,
+                    message: format!("(close_trade) Invalid exit price: {}", exit_price),
+                    data: None
+                })
+            )
+        }
+        None => match app_state.current_price(&trade.pair).await {
+            Ok(price) => price,
+            Err(err) => {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ApiResponse {
+                        status: "503 Service Unavailable",
+                        code: None,
+                        message: format!("(close_trade) {}", err),
+                        data: None
+                    })
+                )
+            }
+        },
+    };
+
+    match close_active_trade(&app_state, &id, exit_price).await {
+        Ok(Some(closed_trade)) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: format!("(close_trade) Closed trade {} at {}.", id, closed_trade.exit_price),
+                data: Some(closed_trade)
+            })
+        ),
+        // e.g. a trigger closed it in the meantime
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse {
+                status: "404 Not Found",
+                code: None,
+                message: format!("(close_trade) Trade {} is not active.", id),
+                data: None
+            })
+        ),
+        Err(err) => {
+            eprintln!("(close_trade) Failed to close trade {}: {}", id, err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(close_trade) Failed to close trade {}: {}", id, err),
+                    data: None
+                })
+            )
+        }
+    }
+}
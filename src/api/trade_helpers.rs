@@ -1,20 +1,97 @@
-use chrono::{DateTime, Duration, Timelike, Utc};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
 
-use crate::{constants::{EXECUTION_FEE_PERCENTAGE, FUNDING_FEE_8H_PERCENTAGE, FUNDING_FEE_HOURS, MAINTENANCE_MARGIN}, models::{ActiveTrade, TradeDirection}};
+use crate::{constants::{FUNDING_FEE_8H_PERCENTAGE, MAX_PARTIAL_LIQUIDATION_STEPS, PARTIAL_LIQUIDATION_STEP_PERCENTAGE, QUOTE_CURRENCIES}, models::{ActiveTrade, BulkExitUpdate, ContractType, ExchangeProfile, ExitAdjustment, FundingPayment, FundingRate, FundingSchedule, MarginMode, MarketHours, PartialLiquidation, RoundingPolicy, TradeDirection}};
 
-/// Calculate the Profit and Loss (PnL) for a trade.
+/// Splits a pair (e.g. `ETHBTC`, `SOL-USDT`) into its base and quote currencies, based on the known `QUOTE_CURRENCIES`.
+/// 
+/// Returns `None` if the pair doesn't end with a known quote currency.
+pub fn split_pair(pair: &str) -> Option<(String, String)> {
+    let normalized: String = pair
+        .chars()
+        .filter(|c| !matches!(c, '-' | '/' | '_'))
+        .collect::<String>()
+        .to_uppercase();
+
+    QUOTE_CURRENCIES
+        .iter()
+        .filter(|quote| normalized.len() > quote.len() && normalized.ends_with(*quote))
+        .max_by_key(|quote| quote.len())
+        .map(|quote| (normalized[..normalized.len() - quote.len()].to_string(), quote.to_string()))
+}
+
+/// Converts a pair (e.g. `SOLUSDT`) into its Coinbase product ID (e.g. `SOL-USDT`), as used by the price feed.
+pub fn to_coinbase_product_id(pair: &str) -> Option<String> {
+    split_pair(pair).map(|(base, quote)| format!("{}-{}", base, quote))
+}
+
+/// Returns the currency that the PnL and fees of a trade on `pair` are settled in.
+/// 
+/// Linear contracts settle in the quote currency, inverse contracts in the base currency.
+pub fn get_settlement_currency(pair: &str, contract_type: &ContractType) -> Option<String> {
+    let (base, quote) = split_pair(pair)?;
+
+    match contract_type {
+        ContractType::Linear => Some(quote),
+        ContractType::Inverse => Some(base),
+    }
+}
+
+/// Calculates the notional value of a position at `price` (in the settlement currency).
+/// 
+/// For linear contracts, this is `quantity * price` (in the quote currency).
+/// For inverse contracts, `quantity` already is the notional value in the quote currency, so this is `quantity / price` (in the base currency).
+pub fn calc_notional_value(quantity: f64, price: f64, contract_type: &ContractType) -> f64 {
+    match contract_type {
+        ContractType::Linear => quantity * price,
+        ContractType::Inverse => quantity / price,
+    }
+}
+
+/// Calculates the quantity to open a paper trade with, given the notional value of the trade (in USDT).
+/// 
+/// `quote_usdt_value` is the value of 1 unit of the pair's quote currency in USDT (1.0 for USDT-quoted pairs).
+/// 
+/// For linear contracts, this is the quantity of the base currency. For inverse contracts, this is the notional value in the quote currency.
+/// Rounded to `QUANTITY_DECIMALS` dp by the accounting rounding of `rounding`.
+pub fn calc_order_quantity(
+    notional_value: f64,
+    price: f64,
+    quote_usdt_value: f64,
+    contract_type: &ContractType,
+    rounding: &RoundingPolicy
+) -> f64 {
+    let quote_notional_value = notional_value / quote_usdt_value;
+
+    let quantity = match contract_type {
+        ContractType::Linear => quote_notional_value / price,
+        ContractType::Inverse => quote_notional_value,
+    };
+
+    rounding.round_quantity(quantity)
+}
+
+/// Calculate the Profit and Loss (PnL) for a trade (in the settlement currency).
+/// 
+/// For inverse contracts, the PnL is denominated in the base currency: `quantity * (1 / entry price - 1 / exit price)` for longs.
 pub fn calc_pnl(
     entry_price: f64,
     exit_price: f64,
     quantity: f64,
     execution_fees: f64,
     funding_fees: f64,
-    direction: &TradeDirection
+    direction: &TradeDirection,
+    contract_type: &ContractType
 ) -> f64 {
+    let long_pnl = match contract_type {
+        ContractType::Linear => (exit_price - entry_price) * quantity,
+        ContractType::Inverse => quantity * (1.0 / entry_price - 1.0 / exit_price),
+    };
+
     let raw_pnl = if *direction == TradeDirection::Long {
-        (exit_price - entry_price) * quantity
+        long_pnl
     } else {
-        (entry_price - exit_price) * quantity
+        -long_pnl
     };
 
     raw_pnl - execution_fees - funding_fees
@@ -25,10 +102,11 @@ pub fn calc_roe(
     pnl: f64,
     entry_price: f64,
     quantity: f64,
-    leverage: f64
+    leverage: f64,
+    contract_type: &ContractType
 ) -> f64 {
-    // calculate margin (equity used)
-    let notional_value = entry_price * quantity;
+    // calculate margin (equity used), denominated in the same currency as the PnL
+    let notional_value = calc_notional_value(quantity, entry_price, contract_type);
     let margin = notional_value / leverage;
 
     // return ROE as percentage
@@ -39,29 +117,124 @@ pub fn calc_roe(
 /// 
 /// Only used primarily in paper trading to simulate real liquidation prices.
 /// 
-/// Maintenance margin is also taken into account.
+/// Maintenance margin (in percentage of the notional value, see `ExchangeProfile::maintenance_margin_percentage`) is also taken into account,
+/// so that larger positions are liquidated earlier.
 pub fn calc_liquidation_price(
     entry_price: f64,
     leverage: f64,
-    direction: &TradeDirection
+    direction: &TradeDirection,
+    contract_type: &ContractType,
+    maintenance_margin_percentage: f64
 ) -> f64 {
-    if *direction == TradeDirection::Long {
+    let maintenance_margin = maintenance_margin_percentage / 100.0;
+
+    match (contract_type, direction) {
         // liq price = entry price * (1 - (1 / leverage) + (maintenance margin [in ratio format] / leverage))
-        entry_price * (1.0 - (1.0 / leverage) + ((MAINTENANCE_MARGIN / 100.0) / leverage)) 
-    } else {
+        (ContractType::Linear, TradeDirection::Long) => entry_price * (1.0 - (1.0 / leverage) + (maintenance_margin / leverage)),
         // liq price = entry price * (1 + (1 / leverage) - (maintenance margin [in ratio format] / leverage))
-        entry_price * (1.0 + (1.0 / leverage) - ((MAINTENANCE_MARGIN / 100.0) / leverage))
+        (ContractType::Linear, TradeDirection::Short) => entry_price * (1.0 + (1.0 / leverage) - (maintenance_margin / leverage)),
+        // the margin and PnL of inverse contracts are denominated in the base currency, so the liquidation price solves
+        // margin + pnl = maintenance margin, i.e. (1 / leverage) / entry price + (1 / entry price - 1 / liq price) = maintenance margin / liq price
+        // liq price = entry price * leverage * (1 + maintenance margin) / (leverage + 1)
+        (ContractType::Inverse, TradeDirection::Long) => entry_price * leverage * (1.0 + maintenance_margin) / (leverage + 1.0),
+        // liq price = entry price * leverage * (1 - maintenance margin) / (leverage - 1)
+        // a 1x short on an inverse contract is fully hedged and can never be liquidated.
+        (ContractType::Inverse, TradeDirection::Short) => {
+            if leverage <= 1.0 {
+                f64::INFINITY
+            } else {
+                entry_price * leverage * (1.0 - maintenance_margin) / (leverage - 1.0)
+            }
+        }
     }
 }
 
-/// Calculate the final execution fee for a trade, taking both opening and closing fees into account.
+/// Calculate the final execution fee for a trade (in the settlement currency), taking both opening and closing fees into account.
+/// 
+/// `fee_percentage` is the fee of the exchange for each side (e.g. the taker fee for market orders).
+/// 
+/// Used purely for paper trading only.
+pub fn calc_final_execution_fees(quantity: f64, entry_price: f64, contract_type: &ContractType, fee_percentage: f64) -> f64 {
+    2.0 * (fee_percentage / 100.0 * calc_notional_value(quantity, entry_price, contract_type))
+}
+
+/// Calculates the fee charged by the exchange (in the settlement currency) for liquidating a trade at `liquidation_price`.
 /// 
 /// Used purely for paper trading only.
-pub fn calc_final_execution_fees(quantity: f64, entry_price: f64) -> f64 {
-    2.0 * (EXECUTION_FEE_PERCENTAGE / 100.0 * quantity * entry_price)
+pub fn calc_liquidation_fee(quantity: f64, liquidation_price: f64, contract_type: &ContractType, fee_percentage: f64) -> f64 {
+    fee_percentage / 100.0 * calc_notional_value(quantity, liquidation_price, contract_type)
+}
+
+/// Clamps the loss of a liquidated trade (in the settlement currency) to its isolated margin, i.e. the notional value at entry divided by the leverage.
+/// 
+/// A liquidated position can never lose more than its margin, since any further loss is covered by the exchange's insurance fund.
+pub fn clamp_to_isolated_margin(pnl: f64, quantity: f64, entry_price: f64, leverage: f64, contract_type: &ContractType) -> f64 {
+    let margin = calc_notional_value(quantity, entry_price, contract_type) / leverage;
+
+    pnl.max(-margin)
+}
+
+/// Calculates the next step of a stepwise liquidation of `trade`, which closes `PARTIAL_LIQUIDATION_STEP_PERCENTAGE` of the position at its liquidation price.
+/// 
+/// Only cross margin trades that are still above the lowest maintenance margin tier of their exchange are liquidated in steps,
+/// up to `MAX_PARTIAL_LIQUIDATION_STEPS` times. The remaining position gets the liquidation price of the (possibly lower) tier it falls into.
+/// 
+/// `quote_usdt_value` is the value of 1 unit of the pair's quote currency in USDT, since the tiers are in USDT.
+/// 
+/// Returns `None` if the trade should be liquidated entirely instead.
+pub fn calc_partial_liquidation(
+    trade: &ActiveTrade,
+    exchange_profile: &ExchangeProfile,
+    quote_usdt_value: f64,
+    rounding: &RoundingPolicy,
+    timestamp: DateTime<Utc>
+) -> Option<PartialLiquidation> {
+    if trade.margin_mode != MarginMode::Cross || trade.partial_liquidations.len() >= MAX_PARTIAL_LIQUIDATION_STEPS {
+        return None;
+    }
+
+    // the notional value of the position at entry in USDT, which is what the tiers are based on
+    let notional_usdt_value = |quantity: f64| match trade.contract_type {
+        ContractType::Linear => quantity * trade.entry_price,
+        ContractType::Inverse => quantity,
+    } * quote_usdt_value;
+
+    let lowest_tier_max_notional_value = exchange_profile.maintenance_margin_tiers.first()?.max_notional_value?;
+
+    if notional_usdt_value(trade.quantity) <= lowest_tier_max_notional_value {
+        return None;
+    }
+
+    let quantity = rounding.round_quantity(trade.quantity * PARTIAL_LIQUIDATION_STEP_PERCENTAGE / 100.0);
+
+    if quantity <= 0.0 || quantity >= trade.quantity {
+        return None;
+    }
+
+    let price = trade.liquidation_price;
+    let execution_fees = calc_final_execution_fees(quantity, trade.entry_price, &trade.contract_type, exchange_profile.taker_fee_percentage);
+    let pnl = calc_pnl(trade.entry_price, price, quantity, execution_fees, 0.0, &trade.direction, &trade.contract_type);
+
+    let remaining_quantity = trade.quantity - quantity;
+    let maintenance_margin_percentage = exchange_profile.maintenance_margin_percentage(notional_usdt_value(remaining_quantity));
+
+    Some(PartialLiquidation {
+        timestamp,
+        quantity,
+        price,
+        pnl,
+        next_liquidation_price: calc_liquidation_price(
+            trade.entry_price,
+            trade.leverage.into(),
+            &trade.direction,
+            &trade.contract_type,
+            maintenance_margin_percentage
+        ),
+    })
 }
 
-/// Calculates the final funding fees for a trade, taking into account the funding fee percentage, the duration and the average notional value of the trade.
+/// Calculates the final funding fees for a trade, taking into account the funding fee percentage, the funding schedule of the exchange,
+/// the duration and the average notional value of the trade. No funding is charged at funding times at which the market of the pair is closed.
 /// 
 /// Used only in paper trading to simulate real funding fees.
 /// 
@@ -72,60 +245,157 @@ pub fn calc_final_funding_fees(
     close_timestamp: DateTime<Utc>,
     // the average margin/notional value of the position between opening and closing the trade.
     // calculated by (initial margin + final margin) / 2
-    average_notional_value: f64
+    average_notional_value: f64,
+    funding_schedule: &FundingSchedule,
+    market_hours: &MarketHours
 ) -> f64 {
     // edge case: no funding fees if the trade duration is zero or somehow negative
     if open_timestamp >= close_timestamp {
         return 0.0;
     }
 
-    // init final funding fees
-    let mut final_funding_fees = 0.0;
+    // each settlement charges its share of the 8 hour funding fee
+    let fee_per_funding = average_notional_value * (FUNDING_FEE_8H_PERCENTAGE / 100.0) * funding_schedule.interval_hours as f64 / 8.0;
+
+    fee_per_funding * funding_schedule.count_open_funding_times(open_timestamp, close_timestamp, market_hours) as f64
+}
 
-    // start from the first funding interval after `open_timestamp`
-    let mut current_funding_time = get_next_funding_time(open_timestamp);
+/// Splits the final funding fees of a paper trade closed at `close_timestamp` (see `calc_final_funding_fees`) into one funding payment
+/// per settlement, for the funding ledger. The amounts of the payments add up to the funding fees of the closed trade.
+pub fn calc_funding_payments(
+    trade: &ActiveTrade,
+    close_timestamp: DateTime<Utc>,
+    average_notional_value: f64,
+    funding_schedule: &FundingSchedule,
+    market_hours: &MarketHours,
+    settlement_currency: &str
+) -> Vec<FundingPayment> {
+    // each settlement charges its share of the 8 hour funding fee
+    let rate = (FUNDING_FEE_8H_PERCENTAGE / 100.0) * funding_schedule.interval_hours as f64 / 8.0;
+    let exchange = ExchangeProfile::resolve(trade.exchange.as_deref()).name;
 
-    while current_funding_time <= close_timestamp {
-        // add the funding fee for this interval
-        final_funding_fees += average_notional_value * (FUNDING_FEE_8H_PERCENTAGE / 100.0);
+    funding_schedule
+        .open_funding_times(trade.open_timestamp, close_timestamp, market_hours)
+        .into_iter()
+        .map(|funding_time| FundingPayment {
+            id: ObjectId::new(),
+            trade_id: trade.id,
+            alert_name: trade.alert_name.clone(),
+            pair: trade.pair.clone(),
+            exchange: exchange.to_lowercase(),
+            kind: trade.kind.clone(),
+            direction: trade.direction.clone(),
+            funding_time,
+            rate,
+            notional_value: average_notional_value,
+            amount: average_notional_value * rate,
+            settlement_currency: settlement_currency.to_string(),
+        })
+        .collect()
+}
 
-        // move on to the next funding interval
-        current_funding_time += Duration::hours(8);
+/// Calculates the funding accrued by an open trade (in the settlement currency) from the settled funding rates of its pair.
+/// 
+/// Only settlements after the trade was opened are taken into account. The notional value is based on the mark price
+/// at each settlement (falling back to the entry price). Positive values are funding paid, negative values are funding received.
+pub fn calc_accrued_funding(trade: &ActiveTrade, funding_rates: &[FundingRate]) -> f64 {
+    let funding: f64 = funding_rates
+        .iter()
+        .filter(|funding_rate| funding_rate.funding_time > trade.open_timestamp)
+        .map(|funding_rate| {
+            let price = funding_rate.mark_price.unwrap_or(trade.entry_price);
+
+            calc_notional_value(trade.quantity, price, &trade.contract_type) * funding_rate.rate
+        })
+        .sum();
+
+    // positive funding rates are paid by longs and received by shorts
+    if trade.direction == TradeDirection::Long {
+        funding
+    } else {
+        -funding
     }
+}
+
+/// Calculates the take profit and stop loss prices that are `take_profit_percentage` and `stop_loss_percentage` away from the entry price.
+pub fn calc_percentage_exits(
+    entry_price: f64,
+    direction: &TradeDirection,
+    take_profit_percentage: Option<f64>,
+    stop_loss_percentage: Option<f64>
+) -> (Option<f64>, Option<f64>) {
+    // longs profit from rising prices, shorts from falling prices
+    let sign = if *direction == TradeDirection::Long { 1.0 } else { -1.0 };
 
-    final_funding_fees
+    (
+        take_profit_percentage.map(|percentage| entry_price * (1.0 + sign * percentage / 100.0)),
+        stop_loss_percentage.map(|percentage| entry_price * (1.0 - sign * percentage / 100.0)),
+    )
 }
 
-/// Get the next funding time after a given timestamp.
-pub fn get_next_funding_time(timestamp: DateTime<Utc>) -> DateTime<Utc> {
-    let date = timestamp.date_naive();
-    let hour = timestamp.hour();
+/// Whether `trade` matches the filters of a bulk update (the pair case-insensitively). A bulk update without filters matches all trades.
+pub fn matches_bulk_update(trade: &ActiveTrade, update: &BulkExitUpdate) -> bool {
+    update.pair.as_ref().is_none_or(|pair| trade.pair.eq_ignore_ascii_case(pair))
+        && update.alert_name.as_ref().is_none_or(|alert_name| trade.alert_name == *alert_name)
+}
 
-    // find the next funding hour today
-    for &funding_hour in &FUNDING_FEE_HOURS {
-        if hour < funding_hour.into() {
-            if let Some(next_time) = date.and_hms_opt(funding_hour.into(), 0, 0) {
-                return DateTime::from_naive_utc_and_offset(next_time, Utc);
-            }
+/// Calculates the take profit and stop loss of `trade` after the adjustments of a bulk update. Exits without an adjustment are kept.
+pub fn calc_adjusted_exits(trade: &ActiveTrade, update: &BulkExitUpdate) -> (Option<f64>, Option<f64>) {
+    let percentage = |adjustment: Option<ExitAdjustment>| match adjustment {
+        Some(ExitAdjustment::Percentage(percentage)) => Some(percentage),
+        _ => None,
+    };
+
+    let (take_profit, stop_loss) = calc_percentage_exits(trade.entry_price, &trade.direction, percentage(update.take_profit), percentage(update.stop_loss));
+
+    let adjust = |adjustment: Option<ExitAdjustment>, from_percentage: Option<f64>, current: Option<f64>| match adjustment {
+        Some(ExitAdjustment::Price(price)) => Some(price),
+        Some(ExitAdjustment::Percentage(_)) => from_percentage,
+        None => current,
+    };
+
+    (
+        adjust(update.take_profit, take_profit, trade.take_profit),
+        adjust(update.stop_loss, stop_loss, trade.stop_loss),
+    )
+}
+
+/// Calculates the stop loss of a trailing trade at `current_price`.
+///
+/// Returns `None` if the trade doesn't trail its stop loss, or if the trailed stop loss wouldn't be tighter than the current one
+/// (i.e. stops only ever move in the trade's favor).
+pub fn calc_trailing_stop(trade: &ActiveTrade, current_price: f64) -> Option<f64> {
+    let percentage = trade.trailing_stop_percentage?;
+
+    match trade.direction {
+        TradeDirection::Long => {
+            let stop = current_price * (1.0 - percentage / 100.0);
+            trade.stop_loss.is_none_or(|stop_loss| stop > stop_loss).then_some(stop)
+        }
+        TradeDirection::Short => {
+            let stop = current_price * (1.0 + percentage / 100.0);
+            trade.stop_loss.is_none_or(|stop_loss| stop < stop_loss).then_some(stop)
         }
     }
+}
 
-    // if no funding hour is found today, return the first funding hour of the next day
-    if let Some(next_day_time) = (date + Duration::days(1)).and_hms_opt(FUNDING_FEE_HOURS[0].into(), 0, 0) {
-        return DateTime::from_naive_utc_and_offset(next_day_time, Utc);
+/// Checks if the trade's liquidation price is reached by `current_price`.
+pub fn is_liquidation_hit(trade: &ActiveTrade, current_price: f64) -> bool {
+    match trade.direction {
+        TradeDirection::Long => current_price <= trade.liquidation_price,
+        TradeDirection::Short => current_price >= trade.liquidation_price,
     }
-
-    // this point should never be reached if funding hours are correctly configured
-    panic!("(get_next_funding_time) No valid funding times configured");
 }
 
 /// Checks if the trade’s liquidation, stop loss or take profit is triggered by `current_price`.
 pub fn is_trigger_hit(trade: &ActiveTrade, current_price: f64) -> bool {
+    is_liquidation_hit(trade, current_price) || is_exit_trigger_hit(trade, current_price)
+}
+
+/// Checks if the trade’s stop loss or take profit is triggered by `current_price`.
+pub fn is_exit_trigger_hit(trade: &ActiveTrade, current_price: f64) -> bool {
     match trade.direction {
         TradeDirection::Long => {
-            if current_price <= trade.liquidation_price {
-                return true;
-            }
             if let Some(sl) = trade.stop_loss {
                 if current_price <= sl {
                     return true;
@@ -138,9 +408,6 @@ pub fn is_trigger_hit(trade: &ActiveTrade, current_price: f64) -> bool {
             }
         }
         TradeDirection::Short => {
-            if current_price >= trade.liquidation_price {
-                return true;
-            }
             if let Some(sl) = trade.stop_loss {
                 if current_price >= sl {
                     return true;
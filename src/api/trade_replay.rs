@@ -0,0 +1,199 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{self, doc, from_document, oid::ObjectId};
+
+use crate::{api::to_coinbase_product_id, constants::{MAX_REPLAY_CANDLES, MIN_REPLAY_PADDING_CANDLES, REPLAY_CANDLE_INTERVALS_SECS, REPLAY_PADDING_FRACTION}, models::{ApiResponse, Candle, ClosedTrade, MongoDBState, ReplayEvent, ReplayEventKind, TradeDirection, TradeReplay}};
+
+impl MongoDBState {
+    /// Aggregates the price ticks of a product within `[from, to]` into candles of `interval_secs`, oldest first.
+    pub async fn fetch_candles(&self, product: &str, (from, to): (DateTime<Utc>, DateTime<Utc>), interval_secs: i64) -> Result<Vec<Candle>, mongodb::error::Error> {
+        let interval_millis = interval_secs * 1000;
+        let millis = doc! { "$toLong": "$timestamp" };
+
+        let mut cursor = self.price_tick_collection
+            .aggregate(vec![
+                doc! {
+                    "$match": {
+                        "product": product.to_uppercase(),
+                        "timestamp": { "$gte": bson::DateTime::from_millis(from.timestamp_millis()), "$lte": bson::DateTime::from_millis(to.timestamp_millis()) },
+                    }
+                },
+                doc! { "$sort": { "timestamp": 1 } },
+                doc! {
+                    "$group": {
+                        "_id": { "$subtract": [millis.clone(), { "$mod": [millis, interval_millis] }] },
+                        "open": { "$first": "$price" },
+                        "high": { "$max": "$price" },
+                        "low": { "$min": "$price" },
+                        "close": { "$last": "$price" },
+                    }
+                },
+                doc! { "$sort": { "_id": 1 } },
+                doc! { "$project": { "_id": 0, "timestamp": { "$toLong": { "$divide": ["$_id", 1000] } }, "open": 1, "high": 1, "low": 1, "close": 1 } },
+            ])
+            .selection_criteria(self.stats_read_preference.selection_criteria())
+            .await?;
+
+        let mut candles = Vec::new();
+
+        while cursor.advance().await? {
+            candles.push(from_document::<Candle>(cursor.deserialize_current()?)?);
+        }
+
+        Ok(candles)
+    }
+}
+
+/// Picks the candle length of a trade's replay and the time range it covers: the shortest candle that fits the trade
+/// (padded by `REPLAY_PADDING_FRACTION` of its duration, but at least `MIN_REPLAY_PADDING_CANDLES` candles) into `MAX_REPLAY_CANDLES`.
+pub fn calc_replay_range(open_timestamp: DateTime<Utc>, close_timestamp: DateTime<Utc>) -> (i64, DateTime<Utc>, DateTime<Utc>) {
+    let duration_secs = (close_timestamp - open_timestamp).num_seconds().max(0);
+    let padding_secs = |interval_secs: i64| ((duration_secs as f64 * REPLAY_PADDING_FRACTION) as i64).max(interval_secs * MIN_REPLAY_PADDING_CANDLES);
+
+    let interval_secs = REPLAY_CANDLE_INTERVALS_SECS
+        .into_iter()
+        .find(|interval_secs| (duration_secs + 2 * padding_secs(*interval_secs)) / interval_secs <= MAX_REPLAY_CANDLES)
+        .unwrap_or(REPLAY_CANDLE_INTERVALS_SECS[REPLAY_CANDLE_INTERVALS_SECS.len() - 1]);
+
+    let padding = chrono::Duration::seconds(padding_secs(interval_secs));
+
+    (interval_secs, open_timestamp - padding, close_timestamp + padding)
+}
+
+/// Infers what closed a trade from its exit price: its liquidation, take profit or stop loss, or anything else (e.g. an opposite alert).
+pub fn classify_exit(trade: &ClosedTrade) -> ReplayEventKind {
+    if trade.liquidated {
+        return ReplayEventKind::Liquidation;
+    }
+
+    let (take_profit_hit, stop_loss_hit) = match trade.direction {
+        TradeDirection::Long => (
+            trade.take_profit.is_some_and(|take_profit| trade.exit_price >= take_profit),
+            trade.stop_loss.is_some_and(|stop_loss| trade.exit_price <= stop_loss),
+        ),
+        TradeDirection::Short => (
+            trade.take_profit.is_some_and(|take_profit| trade.exit_price <= take_profit),
+            trade.stop_loss.is_some_and(|stop_loss| trade.exit_price >= stop_loss),
+        ),
+    };
+
+    if take_profit_hit {
+        ReplayEventKind::TakeProfit
+    } else if stop_loss_hit {
+        ReplayEventKind::StopLoss
+    } else {
+        ReplayEventKind::Exit
+    }
+}
+
+/// Builds the markers of a trade's replay: its entry, partial liquidations and exit, oldest first.
+pub fn build_replay_events(trade: &ClosedTrade) -> Vec<ReplayEvent> {
+    let entry = ReplayEvent { kind: ReplayEventKind::Entry, timestamp: trade.open_timestamp, price: trade.entry_price };
+    let exit = ReplayEvent { kind: classify_exit(trade), timestamp: trade.close_timestamp, price: trade.exit_price };
+
+    let partial_liquidations = trade.partial_liquidations
+        .iter()
+        .map(|step| ReplayEvent { kind: ReplayEventKind::PartialLiquidation, timestamp: step.timestamp, price: step.price });
+
+    std::iter::once(entry).chain(partial_liquidations).chain(std::iter::once(exit)).collect()
+}
+
+/// Returns everything needed to render a closed trade on a chart in one payload: the candles of its pair around it,
+/// its entry, partial liquidations and exit (and what triggered it), and its TP, SL and liquidation levels.
+///
+/// Candles are only available for the periods that price ticks were recorded in.
+pub async fn get_trade_replay(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<TradeReplay>>) {
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(get_trade_replay) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    let trade = match mongo_state.fetch_closed_trade(id).await {
+        Ok(Some(trade)) => trade,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse {
+                    status: "404 Not Found",
+                    code: None,
+                    message: format!("(get_trade_replay) Closed trade {} not found.", id),
+                    data: None
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(get_trade_replay) Failed to fetch trade {}: {}", id, err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_trade_replay) Failed to fetch trade {}: {}", id, err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let Some(product) = to_coinbase_product_id(&trade.pair) else {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse {
+                status: "422 Unprocessable Entity",
+                code: None,
+                message: format!("(get_trade_replay) Unsupported pair of trade {}: {}", id, trade.pair),
+                data: None
+            })
+        )
+    };
+
+    let (interval_secs, from, to) = calc_replay_range(trade.open_timestamp, trade.close_timestamp);
+
+    match mongo_state.fetch_candles(&product, (from, to), interval_secs).await {
+        Ok(candles) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: format!("(get_trade_replay) Fetched replay with {} candles successfully.", candles.len()),
+                data: Some(TradeReplay {
+                    product,
+                    interval_secs,
+                    candles,
+                    events: build_replay_events(&trade),
+                    take_profit: trade.take_profit,
+                    stop_loss: trade.stop_loss,
+                    liquidation_price: trade.liquidation_price,
+                    trade,
+                })
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_trade_replay) Failed to fetch candles of trade {}: {}", id, err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_trade_replay) Failed to fetch candles of trade {}: {}", id, err),
+                    data: None
+                })
+            )
+        }
+    }
+}
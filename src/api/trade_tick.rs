@@ -0,0 +1,135 @@
+use std::{sync::{Arc, Mutex}, time::Duration as StdDuration};
+
+use axum::{extract::Path, Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{constants::{MAX_BUFFERED_TRADE_TICKS, MAX_TRADE_TICKS, TRADE_TICK_FLUSH_INTERVAL_SECS}, models::{ApiResponse, AppState, LruCache, MongoDBState, TradeTick, TradeTickRecorder}};
+
+/// CRUD operations for trade ticks in the database.
+impl MongoDBState {
+    /// Inserts a batch of trade ticks into the database.
+    pub async fn insert_trade_ticks(&self, ticks: &[TradeTick]) -> Result<(), mongodb::error::Error> {
+        if ticks.is_empty() {
+            return Ok(());
+        }
+
+        self.trade_tick_collection.insert_many(ticks).await.map(|_| ())
+    }
+
+    /// Fetches the ticks captured while a trade was open, oldest first (at most `MAX_TRADE_TICKS`).
+    pub async fn fetch_trade_ticks(&self, trade_id: ObjectId) -> Result<Vec<TradeTick>, mongodb::error::Error> {
+        let cursor = self.trade_tick_collection
+            .find(doc! { "tradeId": trade_id })
+            .sort(doc! { "timestamp": 1 })
+            .limit(MAX_TRADE_TICKS)
+            .await?;
+
+        self.collect_documents(cursor).await
+    }
+}
+
+impl TradeTickRecorder {
+    /// Reads whether ticks are captured from the `TRADE_TICK_CAPTURE` env variable (`true` or `false`). Defaults to `false`.
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("TRADE_TICK_CAPTURE").is_ok_and(|enabled| enabled.trim().eq_ignore_ascii_case("true"));
+
+        Self { enabled, buffer: Mutex::new(LruCache::new(MAX_BUFFERED_TRADE_TICKS)) }
+    }
+
+    /// Records the price observed for each of `trade_ids` at `timestamp`, replacing any earlier price within the same second.
+    pub fn record(&self, trade_ids: &[ObjectId], price: f64, timestamp: DateTime<Utc>) {
+        if !self.enabled || trade_ids.is_empty() {
+            return;
+        }
+
+        let mut buffer = self.buffer.lock().unwrap();
+
+        for trade_id in trade_ids {
+            buffer.insert((*trade_id, timestamp.timestamp()), price);
+        }
+    }
+
+    /// Takes all buffered ticks, oldest first.
+    pub fn take(&self) -> Vec<TradeTick> {
+        let buffer = self.buffer.lock().unwrap().take();
+
+        let mut ticks: Vec<TradeTick> = buffer
+            .into_iter()
+            .map(|((trade_id, second), price)| TradeTick {
+                trade_id,
+                timestamp: DateTime::from_timestamp(second, 0).unwrap_or_default(),
+                price,
+            })
+            .collect();
+
+        ticks.sort_by_key(|tick| tick.timestamp);
+
+        ticks
+    }
+}
+
+/// Periodically writes the ticks captured while trades are open into the database. Does nothing if capturing ticks is disabled.
+pub async fn start_trade_tick_flusher(app_state: Arc<AppState>) {
+    if !app_state.trade_ticks.enabled {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(StdDuration::from_secs(TRADE_TICK_FLUSH_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        let ticks = app_state.trade_ticks.take();
+
+        if let Err(err) = app_state.mongo_state.insert_trade_ticks(&ticks).await {
+            eprintln!("(start_trade_tick_flusher) Failed to save {} trade ticks: {}", ticks.len(), err);
+        }
+    }
+}
+
+/// Returns the price path (1 second bars) observed while a trade was open, oldest first, e.g. to post-mortem what triggered its close.
+///
+/// Only available if ticks were captured (`TRADE_TICK_CAPTURE` env variable) while the trade was open.
+pub async fn get_trade_ticks(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Path(id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Vec<TradeTick>>>) {
+    let Ok(id) = ObjectId::parse_str(&id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse {
+                status: "400 Bad Request",
+                code: None,
+                message: format!("(get_trade_ticks) Invalid ID: {}", id),
+                data: None
+            })
+        )
+    };
+
+    match mongo_state.fetch_trade_ticks(id).await {
+        Ok(ticks) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: format!("(get_trade_ticks) Fetched {} ticks successfully.", ticks.len()),
+                data: Some(ticks)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_trade_ticks) Failed to fetch ticks of trade {}: {}", id, err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_trade_ticks) Failed to fetch ticks of trade {}: {}", id, err),
+                    data: None
+                })
+            )
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use chrono::DateTime;
+use hyper::StatusCode;
+
+use crate::{api::migration::migrations, models::{ApiResponse, AppState, ExchangeProfile, VersionInfo}};
+
+/// Builds the version info of this binary, with the feature flags of `app_state`.
+pub fn version_info(app_state: &AppState) -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: option_env!("GIT_COMMIT"),
+        build_timestamp: option_env!("BUILD_TIMESTAMP")
+            .and_then(|timestamp| timestamp.parse().ok())
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0)),
+        schema_version: migrations().iter().map(|migration| migration.version).max().unwrap_or(0),
+        exchanges: ExchangeProfile::known_exchanges().collect(),
+        features: app_state.features,
+    }
+}
+
+/// Returns the version, build info, schema version and enabled features of the running binary.
+pub async fn get_version(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<VersionInfo>>) {
+    (
+        StatusCode::OK,
+        Json(ApiResponse {
+            status: "200 OK",
+            code: None,
+            message: "(get_version) Version retrieved.".to_string(),
+            data: Some(version_info(&app_state))
+        })
+    )
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+use mongodb::{bson::doc, Cursor};
+
+use crate::{constants::ACCEPTED_SYMBOLS, models::{ApiResponse, AppState, MongoDBState, WatchlistEntry, WatchlistUpdate}};
+
+/// CRUD operations for the watchlist in the database.
+impl MongoDBState {
+    /// Fetches all pairs on the watchlist, ordered by pair.
+    pub async fn fetch_watchlist(&self) -> Result<Vec<WatchlistEntry>, mongodb::error::Error> {
+        let mut cursor: Cursor<WatchlistEntry> = self.watchlist_collection.find(doc! {}).sort(doc! { "pair": 1 }).await?;
+
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            results.push(cursor.deserialize_current()?);
+        }
+
+        Ok(results)
+    }
+
+    /// Replaces the watchlist with `pairs` at `now`. Pairs that were already on the watchlist keep their original `added_timestamp`.
+    pub async fn replace_watchlist(&self, pairs: &[String], now: DateTime<Utc>) -> Result<(), mongodb::error::Error> {
+        self.watchlist_collection.delete_many(doc! { "pair": { "$nin": pairs } }).await?;
+
+        for pair in pairs {
+            self.watchlist_collection
+                .update_one(
+                    doc! { "pair": pair },
+                    doc! { "$setOnInsert": { "pair": pair, "addedTimestamp": now.timestamp() } }
+                )
+                .upsert(true)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns all pairs on the watchlist.
+pub async fn get_watchlist(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+) -> (StatusCode, Json<ApiResponse<Vec<WatchlistEntry>>>) {
+    match mongo_state.fetch_watchlist().await {
+        Ok(watchlist) => (
+            StatusCode::OK,
+            Json(ApiResponse {
+                status: "200 OK",
+                code: None,
+                message: "(get_watchlist) Fetched watchlist successfully.".to_string(),
+                data: Some(watchlist)
+            })
+        ),
+        Err(err) => {
+            eprintln!("(get_watchlist) Failed to fetch watchlist: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(get_watchlist) Failed to fetch watchlist: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
+
+/// Replaces the watchlist, subscribing the price feed to added pairs and unsubscribing it from removed pairs
+/// (unless a trade is still open on them).
+///
+/// Requests are authenticated with the `X-Command-Secret` header.
+pub async fn put_watchlist(
+    Extension(mongo_state): Extension<Arc<MongoDBState>>,
+    Extension(app_state): Extension<Arc<AppState>>,
+    Json(update): Json<WatchlistUpdate>,
+) -> (StatusCode, Json<ApiResponse<Vec<WatchlistEntry>>>) {
+    let mut pairs: Vec<String> = Vec::new();
+
+    for pair in update.pairs.iter().map(|pair| pair.trim().to_uppercase()) {
+        // check if the symbol is accepted
+        if !ACCEPTED_SYMBOLS.contains(&pair.as_str()) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse {
+                    status: "400 Bad Request",
+                    code: None,
+                    message: format!("(put_watchlist) Symbol {} not accepted", pair),
+                    data: None
+                })
+            )
+        }
+
+        if !pairs.contains(&pair) {
+            pairs.push(pair);
+        }
+    }
+
+    let previous = match mongo_state.fetch_watchlist().await {
+        Ok(previous) => previous,
+        Err(err) => {
+            eprintln!("(put_watchlist) Failed to fetch watchlist: {}", err);
+
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(put_watchlist) Failed to fetch watchlist: {}", err),
+                    data: None
+                })
+            )
+        }
+    };
+
+    let result = match mongo_state.replace_watchlist(&pairs, app_state.clock.now()).await {
+        Ok(_) => mongo_state.fetch_watchlist().await,
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Ok(watchlist) => {
+            for entry in previous.iter().filter(|entry| !pairs.contains(&entry.pair)) {
+                app_state.unsubscribe_pair(&entry.pair);
+            }
+
+            for pair in &pairs {
+                app_state.subscribe_pair(pair);
+            }
+
+            (
+                StatusCode::OK,
+                Json(ApiResponse {
+                    status: "200 OK",
+                    code: None,
+                    message: "(put_watchlist) Updated watchlist successfully.".to_string(),
+                    data: Some(watchlist)
+                })
+            )
+        }
+        Err(err) => {
+            eprintln!("(put_watchlist) Failed to update watchlist: {}", err);
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse {
+                    status: "500 Internal Server Error",
+                    code: None,
+                    message: format!("(put_watchlist) Failed to update watchlist: {}", err),
+                    data: None
+                })
+            )
+        }
+    }
+}
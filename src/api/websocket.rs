@@ -1,113 +1,550 @@
-use std::sync::Arc;
+use std::{collections::{HashMap, HashSet}, str::FromStr, sync::{Arc, Mutex}, time::{Duration, Instant}};
 
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use mongodb::bson::oid::ObjectId;
+use tokio_tungstenite::tungstenite::protocol::Message;
 use futures_util::{StreamExt, SinkExt};
-use tokio::sync::mpsc::{self, Receiver};
-use serde_json::{from_str, json, Value};
+use tokio::sync::mpsc;
+use serde_json::{from_str, json};
 
-use crate::constants::ACCEPTED_SYMBOLS;
-use crate::models::{ActiveTrade, AppState, CoinbaseTickerUpdate, WsCommand};
+use crate::constants::{FEED_IDLE_TIMEOUT_SECS, FEED_MAX_RECONNECT_DELAY_SECS, FEED_PING_INTERVAL_SECS, FEED_RECONNECT_DELAY_SECS, FX_PRODUCT_IDS};
+use crate::models::{ActiveTrade, AppState, CoinbaseTickerFields, FeedChannel, FeedChannels, FeedConnectionEnd, FeedKeepalive, LruCache, NetworkConfig, Notification, NotificationSeverity, PriceFeedTick, PriceVenue, TickIssue, TradeKind, WsCommand};
 
-use crate::api::{close_paper_trade, is_trigger_hit};
+use crate::api::{close_paper_trade, network::connect_websocket, is_exit_trigger_hit, is_liquidation_hit, live::close_live_trade, to_coinbase_product_id};
 
-/// Connects to Coinbase WebSocket and subscribes to one or multiple tickers.
-/// Sends each incoming `ticker` event to the provided MPSC sender.
-pub async fn connect_and_subscribe_to_coinbase(tx: mpsc::Sender<CoinbaseTickerUpdate>) {
+/// A thread-safe map of the latest price of each product (e.g. `BTC-USD`) received from the price feed.
+pub type LatestPricesMap = Arc<Mutex<LruCache<String, f64>>>;
+
+impl AppState {
+    /// Subscribes the price feed to the ticker of `pair` (e.g. when it's added to the watchlist or a trade is opened on it).
+    /// 
+    /// When sharding, only the pairs claimed by this instance are subscribed to. TradFi symbols aren't listed on Coinbase, so they're never subscribed to.
+    pub fn subscribe_pair(&self, pair: &str) {
+        if !self.sharding.handles_pair(pair) || self.trading_calendar.is_tradfi(pair) {
+            return;
+        }
+
+        if let Some(product_id) = to_coinbase_product_id(pair) {
+            if self.ws_commands.send(WsCommand::Subscribe(product_id)).is_err() {
+                eprintln!("(subscribe_pair) Price feed stopped; cannot subscribe to {}.", pair);
+            }
+        }
+    }
+
+    /// Returns the latest price of `pair` received from the price feed, or its ticker on the price exchange if the feed has none yet
+    /// (e.g. right after a restart).
+    pub async fn current_price(&self, pair: &str) -> Result<f64, String> {
+        let latest_price = to_coinbase_product_id(pair)
+            .and_then(|product_id| self.latest_prices.lock().unwrap().get(&product_id).copied());
+
+        match latest_price {
+            Some(price) => Ok(price),
+            None => self.price_exchange.get_ticker(pair).await.map_err(|err| format!("No price available for {} yet: {}", pair, err)),
+        }
+    }
+
+    /// Unsubscribes the price feed from the ticker of `pair`, unless a trade is still open on it.
+    pub fn unsubscribe_pair(&self, pair: &str) {
+        let has_open_trade = {
+            let map = self.active_trades.lock().unwrap();
+            map.values().any(|trade| trade.pair.eq_ignore_ascii_case(pair))
+        };
+
+        if has_open_trade {
+            return;
+        }
+
+        if let Some(product_id) = to_coinbase_product_id(pair) {
+            if self.ws_commands.send(WsCommand::Unsubscribe(product_id)).is_err() {
+                eprintln!("(unsubscribe_pair) Price feed stopped; cannot unsubscribe from {}.", pair);
+            }
+        }
+    }
+}
+
+impl FeedKeepalive {
+    /// Starts tracking a connection established at `now`.
+    pub fn new(idle_timeout: Duration, now: Instant) -> Self {
+        Self { last_received: now, idle_timeout }
+    }
+
+    /// Records that a frame was received at `now`.
+    pub fn record_frame(&mut self, now: Instant) {
+        self.last_received = now;
+    }
+
+    /// Whether no frame was received for longer than the idle timeout by `now`.
+    pub fn is_stale(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_received) > self.idle_timeout
+    }
+}
+
+impl FromStr for FeedChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ticker" => Ok(FeedChannel::Ticker),
+            "ticker_batch" => Ok(FeedChannel::TickerBatch),
+            "matches" => Ok(FeedChannel::Matches),
+            _ => Err(format!("Unknown feed channel: {}", s)),
+        }
+    }
+}
+
+impl FeedChannel {
+    /// The name of the channel in Coinbase's subscription messages.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeedChannel::Ticker => "ticker",
+            FeedChannel::TickerBatch => "ticker_batch",
+            FeedChannel::Matches => "matches",
+        }
+    }
+}
+
+/// Parses the feed channel of pairs, e.g. `BTCUSDT:matches,DOGEUSDT:ticker_batch`, into the channel of each Coinbase product.
+pub fn parse_pair_channels(value: &str) -> Result<HashMap<String, FeedChannel>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (pair, channel) = entry.split_once(':').ok_or_else(|| format!("Expected <pair>:<channel>, got {:?}", entry))?;
+            let product_id = to_coinbase_product_id(pair.trim()).ok_or_else(|| format!("Unsupported pair: {}", pair.trim()))?;
+
+            Ok((product_id, channel.parse::<FeedChannel>()?))
+        })
+        .collect()
+}
+
+impl FeedChannels {
+    /// Reads the default channel from the `FEED_CHANNEL` env variable (defaults to `ticker`), and the channel of individual pairs
+    /// from the `FEED_PAIR_CHANNELS` env variable.
+    pub fn from_env() -> Self {
+        let default = match std::env::var("FEED_CHANNEL") {
+            Ok(value) => value.parse().unwrap_or_else(|err| {
+                eprintln!("(FeedChannels::from_env) Invalid FEED_CHANNEL: {}. Using the ticker channel.", err);
+                FeedChannel::Ticker
+            }),
+            Err(_) => FeedChannel::Ticker,
+        };
+
+        let products = match std::env::var("FEED_PAIR_CHANNELS") {
+            Ok(value) => parse_pair_channels(&value).unwrap_or_else(|err| {
+                eprintln!("(FeedChannels::from_env) {}. Using {} for every pair.", err, default.name());
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+
+        Self { default, products }
+    }
+
+    /// The channel that `product_id` (e.g. `BTC-USD`) is subscribed to.
+    pub fn channel_of(&self, product_id: &str) -> FeedChannel {
+        self.products.get(product_id).copied().unwrap_or(self.default)
+    }
+
+    /// Builds a Coinbase `subscribe` or `unsubscribe` message for `product_ids`, each on its own channel.
+    ///
+    /// Channels are listed in a fixed order, so that the same products always produce the same message.
+    pub fn subscription_message(&self, message_type: &str, product_ids: &[&str]) -> Message {
+        let channels: Vec<_> = [FeedChannel::Ticker, FeedChannel::TickerBatch, FeedChannel::Matches]
+            .into_iter()
+            .filter_map(|channel| {
+                let channel_product_ids: Vec<&str> = product_ids
+                    .iter()
+                    .copied()
+                    .filter(|product_id| self.channel_of(product_id) == channel)
+                    .collect();
+
+                (!channel_product_ids.is_empty()).then(|| json!({ "name": channel.name(), "product_ids": channel_product_ids }))
+            })
+            .collect();
+
+        let message = json!({
+            "type": message_type,
+            "channels": channels
+        });
+
+        Message::Text(message.to_string().into())
+    }
+}
+
+/// Parses a Coinbase WebSocket message into a tick of the price feed. Returns `None` for messages other than `ticker` messages
+/// (of the `ticker` and `ticker_batch` channels) and `match` messages (of the `matches` channel).
+///
+/// This runs for every message of every subscribed product, so other messages are skipped before being parsed,
+/// and only the required fields of ticks are parsed (without copying them, except for the product ID).
+pub fn parse_ticker_message(text: &str) -> Option<PriceFeedTick> {
+    // Coinbase sends compact JSON, so ticks always contain one of these. `last_match` is the last trade before subscribing
+    if !text.contains(r#""type":"ticker""#) && !text.contains(r#""type":"match""#) && !text.contains(r#""type":"last_match""#) {
+        return None;
+    }
+
+    let fields = from_str::<CoinbaseTickerFields>(text).ok()?;
+
+    if !matches!(fields.update_type.as_ref(), "ticker" | "match" | "last_match") {
+        return None;
+    }
+
+    Some(PriceFeedTick {
+        product_id: fields.product_id.to_uppercase(),
+        price: fields.price.and_then(|price| price.parse::<f64>().ok()).unwrap_or(0.0),
+        sequence: fields.sequence,
+        trade_id: fields.trade_id,
+    })
+}
+
+/// Derives the products to subscribe to when connecting from the subscription changes queued before: the pairs of the trades,
+/// watchlist entries and price alerts preloaded at boot. The products required for currency conversion are always included first.
+pub fn initial_product_ids(pending: impl IntoIterator<Item = WsCommand>) -> Vec<String> {
+    let mut product_ids: Vec<String> = FX_PRODUCT_IDS.iter().map(|product_id| product_id.to_string()).collect();
+
+    for command in pending {
+        match command {
+            WsCommand::Subscribe(product_id) if !product_ids.contains(&product_id) => product_ids.push(product_id),
+            WsCommand::Unsubscribe(product_id) if !FX_PRODUCT_IDS.contains(&product_id.as_str()) => product_ids.retain(|id| *id != product_id),
+            _ => {}
+        }
+    }
+
+    product_ids
+}
+
+/// Applies a subscription change to the `subscribed` products, returning the message type and product to send to Coinbase,
+/// or `None` if it's already (un)subscribed. The `pinned` products are never unsubscribed from.
+fn apply_ws_command(subscribed: &mut HashSet<String>, pinned: &HashSet<String>, command: WsCommand) -> Option<(&'static str, String)> {
+    match command {
+        WsCommand::Subscribe(product_id) if subscribed.insert(product_id.clone()) => Some(("subscribe", product_id)),
+        WsCommand::Unsubscribe(product_id) if !pinned.contains(&product_id) && subscribed.remove(&product_id) => Some(("unsubscribe", product_id)),
+        _ => None,
+    }
+}
+
+/// How long to wait before reconnecting the price feed after `failures` failed attempts in a row.
+pub fn calc_feed_reconnect_delay(failures: u32) -> Duration {
+    let delay_secs = FEED_RECONNECT_DELAY_SECS.saturating_mul(2_u64.saturating_pow(failures.saturating_sub(1)));
+
+    Duration::from_secs(delay_secs.min(FEED_MAX_RECONNECT_DELAY_SECS))
+}
+
+/// Connects to Coinbase WebSocket and subscribes to one or multiple tickers, each on its channel in `channels`.
+/// Sends each incoming tick to the provided MPSC sender.
+/// 
+/// The initial tickers are those of the `WsCommand`s queued in `commands` before connecting (see `initial_product_ids`).
+/// Further tickers are subscribed to and unsubscribed from based on the `WsCommand`s received from `commands` afterwards.
+/// The tickers required for currency conversion are never unsubscribed from.
+///
+/// The connection goes through the proxy of `network`, and offers compression if enabled.
+///
+/// Whenever the connection fails or is lost, it's reconnected after a backoff (see `calc_feed_reconnect_delay`), resubscribing
+/// to all the tickers. Only returns once the receiver of `tx` is dropped.
+pub async fn connect_and_subscribe_to_coinbase(tx: mpsc::Sender<PriceFeedTick>, mut commands: mpsc::UnboundedReceiver<WsCommand>, channels: FeedChannels, network: NetworkConfig) {
+    // subscribe to exactly the products queued before connecting (e.g. of the trades opened before a restart),
+    // alongside the products required for currency conversion
+    let mut pending = Vec::new();
+
+    while let Ok(command) = commands.try_recv() {
+        pending.push(command);
+    }
+
+    let pinned: HashSet<String> = FX_PRODUCT_IDS.iter().map(|product_id| product_id.to_string()).collect();
+    let mut subscribed: HashSet<String> = initial_product_ids(pending).into_iter().collect();
+    let mut failures = 0;
+
+    loop {
+        match run_coinbase_connection(&tx, &mut commands, &channels, &network, &mut subscribed, &pinned).await {
+            FeedConnectionEnd::Stopped => break,
+            FeedConnectionEnd::Lost { reason, established } => {
+                // a connection that worked for a while starts backing off anew
+                failures = if established { 1 } else { failures + 1 };
+                let delay = calc_feed_reconnect_delay(failures);
+
+                eprintln!("(connect_and_subscribe_to_coinbase) {}. Reconnecting in {}s (attempt {}).", reason, delay.as_secs(), failures);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    println!("(connect_and_subscribe_to_coinbase) Exiting read loop.");
+}
+
+/// Runs a single connection to Coinbase WebSocket until it ends, subscribing to all the `subscribed` products first
+/// (after applying the subscription changes queued while disconnected).
+///
+/// Pings of Coinbase are answered, and Coinbase is pinged every `FEED_PING_INTERVAL_SECS`. If no frame is received for
+/// `FEED_IDLE_TIMEOUT_SECS`, the connection is considered half-open and dropped.
+async fn run_coinbase_connection(
+    tx: &mpsc::Sender<PriceFeedTick>,
+    commands: &mut mpsc::UnboundedReceiver<WsCommand>,
+    channels: &FeedChannels,
+    network: &NetworkConfig,
+    subscribed: &mut HashSet<String>,
+    pinned: &HashSet<String>
+) -> FeedConnectionEnd {
     let coinbase_ws_url = "wss://ws-feed.exchange.coinbase.com";
-    let (ws_stream, _) = connect_async(coinbase_ws_url)
-        .await
-        .expect("(connect_and_subscribe_to_coinbase) Failed to connect to Coinbase WebSocket");
+    let ws_stream = match connect_websocket(coinbase_ws_url, network).await {
+        Ok(ws_stream) => ws_stream,
+        Err(err) => return FeedConnectionEnd::Lost { reason: format!("Failed to connect to Coinbase WebSocket: {}", err), established: false },
+    };
 
     println!("(connect_and_subscribe_to_coinbase) Connected to Coinbase: {}", coinbase_ws_url);
 
     let (mut write, mut read) = ws_stream.split();
 
-    // subscribe to "ticker" for BTC-USD, ETH-USD (more will be added)
-    let subscription_message = json!({
-        "type": "subscribe",
-        "product_ids": ["BTC-USD", "ETH-USD"],
-        "channels": ["ticker"]
-    });
+    while let Ok(command) = commands.try_recv() {
+        apply_ws_command(subscribed, pinned, command);
+    }
+
+    let product_ids: Vec<&str> = subscribed.iter().map(String::as_str).collect();
+
+    if let Err(e) = write.send(channels.subscription_message("subscribe", &product_ids)).await {
+        return FeedConnectionEnd::Lost { reason: format!("Failed to send subscription message: {}", e), established: false };
+    }
+
+    println!("(connect_and_subscribe_to_coinbase) Subscribed to: {:?}", product_ids);
+
+    // pings keep frames (pongs) coming even when no ticker moves, so a connection without any frame for a while is half-open
+    let mut keepalive = FeedKeepalive::new(Duration::from_secs(FEED_IDLE_TIMEOUT_SECS), Instant::now());
+    let ping_period = Duration::from_secs(FEED_PING_INTERVAL_SECS);
+    let mut ping_interval = tokio::time::interval_at(tokio::time::Instant::now() + ping_period, ping_period);
+    let mut established = false;
+
+    let lost = |reason: String, established: bool| FeedConnectionEnd::Lost { reason, established };
+
+    loop {
+        let msg_result = tokio::select! {
+            msg_result = read.next() => match msg_result {
+                Some(msg_result) => msg_result,
+                None => return lost("The connection was closed".to_string(), established),
+            },
+            _ = ping_interval.tick() => {
+                if keepalive.is_stale(Instant::now()) {
+                    return lost(format!("No frame received for {}s; the connection is half-open", FEED_IDLE_TIMEOUT_SECS), established);
+                }
 
-    write
-        .send(Message::Text(subscription_message.to_string().into()))
-        .await
-        .expect("(connect_and_subscribe_to_coinbase) Failed to send subscription message");
+                if let Err(e) = write.send(Message::Ping(Vec::new().into())).await {
+                    return lost(format!("Failed to send ping: {}", e), established);
+                }
 
-    println!("(connect_and_subscribe_to_coinbase) Subscribed to: [\"BTC-USD\", \"ETH-USD\"]");
+                continue;
+            }
+            Some(command) = commands.recv() => {
+                let Some((message_type, product_id)) = apply_ws_command(subscribed, pinned, command) else {
+                    continue;
+                };
+
+                // the change is already applied, so it's sent with the rest when reconnecting
+                if let Err(e) = write.send(channels.subscription_message(message_type, &[&product_id])).await {
+                    return lost(format!("Failed to {} {}: {}", message_type, product_id, e), established);
+                }
+
+                println!("(connect_and_subscribe_to_coinbase) Sent {} for {}", message_type, product_id);
+                continue;
+            }
+        };
+
+        if msg_result.is_ok() {
+            keepalive.record_frame(Instant::now());
+            established = true;
+        }
 
-    // continuously read messages
-    while let Some(msg_result) = read.next().await {
         match msg_result {
             Ok(Message::Text(text)) => {
-                // attempt to parse as `CoinbaseTickerUpdate`
-                if let Ok(ticker_update) = from_str::<CoinbaseTickerUpdate>(&text) {
-                    // we only want `type == "ticker"`
-                    if ticker_update.update_type == "ticker" {
-                        // send the typed struct to the receiver
-                        if tx.send(ticker_update).await.is_err() {
-                            eprintln!("(connect_and_subscribe_to_coinbase) Receiver dropped; stopping connection.");
-                            break;
-                        }
-                    } else {
-                        // e.g. "subscriptions" or something else
-                        println!("(connect_and_subscribe_to_coinbase) Non-ticker message: {text}");
+                // we only want ticks (`type == "ticker"` or `type == "match"`)
+                if let Some(mut tick) = parse_ticker_message(&text) {
+                    // batched tickers skip trades by design, which mustn't be backfilled
+                    if channels.channel_of(&tick.product_id) == FeedChannel::TickerBatch {
+                        tick.trade_id = None;
                     }
+
+                    // send the typed struct to the receiver
+                    if tx.send(tick).await.is_err() {
+                        eprintln!("(connect_and_subscribe_to_coinbase) Receiver dropped; stopping connection.");
+                        return FeedConnectionEnd::Stopped;
+                    }
+                } else {
+                    // e.g. "subscriptions" or something else
+                    println!("(connect_and_subscribe_to_coinbase) Non-ticker message: {text}");
                 }
             }
-            Ok(_) => { /* ignore non-text/binary pings, etc. */ }
-            Err(e) => {
-                eprintln!("(connect_and_subscribe_to_coinbase) WebSocket error: {}", e);
-                break;
+            Ok(Message::Ping(payload)) => {
+                if let Err(e) = write.send(Message::Pong(payload)).await {
+                    return lost(format!("Failed to answer ping: {}", e), established);
+                }
             }
+            Ok(Message::Close(frame)) => return lost(format!("Coinbase closed the connection: {:?}", frame), established),
+            // pongs (and binary frames) only count towards the keepalive
+            Ok(_) => {}
+            Err(e) => return lost(format!("WebSocket error: {}", e), established),
         }
     }
-
-    println!("(connect_and_subscribe_to_coinbase) Exiting read loop.");
 }
 
 /// Spawns:
 /// 1) A task that connects to Coinbase WebSocket and sends price updates into an mpsc channel.
 /// 2) A task that receives those price updates, checks active trades in memory, and closes them if triggered.
-pub async fn start_price_listener(app_state: Arc<AppState>) {
+/// 
+/// `ws_commands` is the receiving half of `AppState::ws_commands`.
+pub async fn start_price_listener(app_state: Arc<AppState>, ws_commands: mpsc::UnboundedReceiver<WsCommand>) {
     // 1. Channel for typed ticker updates
-    let (tx, mut rx) = mpsc::channel::<CoinbaseTickerUpdate>(100);
+    let (tx, mut rx) = mpsc::channel::<PriceFeedTick>(100);
 
     // 2. Spawn the WebSocket subscription task
     let tx_clone = tx.clone();
+    let app_state_for_ws = app_state.clone();
     tokio::spawn(async move {
-        connect_and_subscribe_to_coinbase(tx_clone).await;
+        connect_and_subscribe_to_coinbase(tx_clone, ws_commands, app_state_for_ws.feed_channels.clone(), app_state_for_ws.network.clone()).await;
+
+        // the feed reconnects by itself, so it only stops once its ticks are no longer processed.
+        // stops and price triggers can no longer fire without the feed
+        app_state_for_ws.notifier.notify(Notification::new(
+            NotificationSeverity::Critical,
+            "Price feed down",
+            "The Coinbase WebSocket feed stopped. Active trades are no longer monitored."
+        ));
     });
 
     // 3. Spawn a consumer task
     let app_state_for_rx = app_state.clone();
+    let backfill_client = app_state.network.http_client();
     tokio::spawn(async move {
         while let Some(ticker_update) = rx.recv().await {
-            // Print the entire struct for debugging
-            println!("(start_price_listener) Received Coinbase update: {:?}", ticker_update);
-
-            // Example: parse out the product_id and price
-            let product_id = ticker_update.product_id.to_uppercase(); // "BTC-USD"
-            let price_str = ticker_update.price.unwrap_or_else(|| "0.0".into());
-            let price = price_str.parse::<f64>().unwrap_or(0.0);
-
-            // Now find trades matching this product_id
-            let trades_to_check: Vec<ActiveTrade> = {
-                let map = app_state_for_rx.active_trades.lock().unwrap();
-                map.values()
-                    .filter(|trade| trade.pair.eq_ignore_ascii_case(&product_id))
-                    .cloned()
-                    .collect()
-            };
-
-            // For each trade, check if triggers are hit
-            for trade in trades_to_check {
-                if is_trigger_hit(&trade, price) {
-                    println!("(start_price_listener) Trigger hit for trade: {:?}", trade);
-                    
-                    close_paper_trade(&app_state_for_rx, &trade.id, price).await;
+            process_price_tick(&app_state_for_rx, ticker_update, &backfill_client).await;
+        }
+    });
+}
+
+/// Processes a single tick of the price feed: discards bad ticks, records the price, and closes the active trades on the product
+/// whose triggers (TP/SL, liquidation or an exit rule) are hit.
+pub async fn process_price_tick(app_state: &Arc<AppState>, ticker_update: PriceFeedTick, backfill_client: &reqwest::Client) {
+    // the chaos mode may simulate a gap in the price feed by dropping its ticks
+    if app_state.mongo_state.chaos.is_feed_gap(app_state.clock.now()) {
+        return;
+    }
+
+    // the product ID (e.g. "BTC-USD") and price were already parsed by the WebSocket task
+    let PriceFeedTick { product_id, price, sequence, trade_id } = ticker_update;
+
+    // discard obviously bad ticks (invalid prices, late or duplicate ticks and spikes) before any triggers are evaluated
+    if let Err(issue) = app_state.feed_quality.assess(&product_id, sequence, price, app_state.clock.now()) {
+        eprintln!("(process_price_tick) Discarded tick of {} at {} (sequence: {:?}): {:?}", product_id, price, sequence, issue);
+        return;
+    }
+
+    // a single venue's flash crash print mustn't stop out every trade, so ticks deviating from the other venues are discarded too
+    if let Err(median) = app_state.price_consensus.submit(&product_id, PriceVenue::Coinbase, price, app_state.clock.now()) {
+        app_state.feed_quality.record_consensus_outlier(&product_id);
+        eprintln!("(process_price_tick) Discarded tick of {} at {}: {:?} (consensus: {})", product_id, price, TickIssue::ConsensusOutlier, median);
+        return;
+    }
+
+    // keep track of the latest price of each product (e.g. for currency conversion)
+    app_state.latest_prices.lock().unwrap().insert(product_id.clone(), price);
+    app_state.mqtt.publish_ticker(&product_id, price, app_state.clock.now());
+    app_state.price_ticks.record(&product_id, price, app_state.clock.now());
+
+    // when running multiple instances, only the leader (or, when sharding, the instance claiming the pair) processes price triggers
+    let processes_triggers = if app_state.sharding.enabled {
+        app_state.sharding.handles_product(&product_id)
+    } else {
+        app_state.leadership.is_leader()
+    };
+
+    if !processes_triggers {
+        return;
+    }
+
+    // trades dropped by the feed (or a reconnect) are backfilled from the REST API in the background
+    if let Some(gap) = app_state.feed_quality.record_trade_id(&product_id, trade_id) {
+        let app_state_for_backfill = app_state.clone();
+        let backfill_client = backfill_client.clone();
+
+        tokio::spawn(async move {
+            app_state_for_backfill.backfill_trade_gap(&backfill_client, &gap).await;
+        });
+    }
+
+    // notify any price alerts on this product
+    app_state.check_price_alerts(&product_id, price).await;
+
+    // Now find trades matching this product_id
+    // triggers of trades whose market is closed aren't evaluated until it reopens
+    let trades_to_check: Vec<ActiveTrade> = {
+        let map = app_state.active_trades.lock().unwrap();
+        map.values()
+            .filter(|trade| to_coinbase_product_id(&trade.pair).is_some_and(|trade_product_id| trade_product_id == product_id))
+            .filter(|trade| app_state.trading_calendar.is_open(&trade.pair, app_state.clock.now()))
+            .cloned()
+            .collect()
+    };
+
+    // capture the price path of each trade before any of them may be closed by this tick
+    let trade_ids: Vec<ObjectId> = trades_to_check.iter().map(|trade| trade.id).collect();
+    app_state.trade_ticks.record(&trade_ids, price, app_state.clock.now());
+
+    // liquidations are checked against the index price of the venues (like exchanges use their mark price), TP/SL against the last price
+    let index_price = app_state.price_consensus.index_price(&product_id, app_state.clock.now()).unwrap_or(price);
+
+    // For each trade, check if triggers are hit
+    for trade in trades_to_check {
+        let trade = app_state.trail_stop_loss(trade, price).await;
+
+        // live trades are exited with a market order on Binance, which liquidates them by itself
+        if matches!(trade.kind, TradeKind::Live) {
+            let exit_rule = app_state.plugins.exit_rule_hit(&trade, price, app_state.clock.now());
+
+            // the close order is placed in the background, so that the feed isn't held up by the exchange, and every tick doesn't retry it
+            if (is_exit_trigger_hit(&trade, price) || exit_rule.is_some()) && app_state.live_closes.try_start(trade.id, app_state.clock.now()) {
+                println!("(process_price_tick) Trigger hit for live trade (exit rule: {:?}): {:?}", exit_rule, trade);
+
+                let app_state = app_state.clone();
+
+                tokio::spawn(async move {
+                    let result = close_live_trade(&app_state, &trade.id).await;
+
+                    if let Err(err) = &result {
+                        eprintln!("(process_price_tick) Failed to close live trade {}: {}", trade.id, err);
+                    }
+
+                    app_state.live_closes.finish(trade.id, result.is_ok(), app_state.clock.now());
+                });
+            }
+
+            continue;
+        }
+
+        // large cross margin trades are liquidated in steps first, which may move their liquidation price out of reach
+        let trade = if is_liquidation_hit(&trade, index_price) {
+            app_state.partially_liquidate(trade.clone(), index_price).await.unwrap_or(trade)
+        } else {
+            trade
+        };
+
+        // exit rule plugins may close the trade before any of its triggers are hit
+        let exit_rule = app_state.plugins.exit_rule_hit(&trade, price, app_state.clock.now());
+        let liquidated = is_liquidation_hit(&trade, index_price);
+
+        if liquidated || is_exit_trigger_hit(&trade, price) || exit_rule.is_some() {
+            println!("(process_price_tick) Trigger hit for trade (exit rule: {:?}): {:?}", exit_rule, trade);
+
+            // a liquidated trade is exited at its liquidation price, which the index price crossed
+            let exit_price = if liquidated { index_price } else { price };
+
+            match close_paper_trade(app_state, &trade.id, exit_price).await {
+                Ok(Some(closed_trade)) if closed_trade.liquidated => {
+                    app_state.notifier.notify(Notification::new(
+                        NotificationSeverity::Critical,
+                        "Trade liquidated",
+                        format!(
+                            "{:?} trade {} of {} on {} was liquidated at {}. PnL: {:.2} {}.",
+                            trade.direction, trade.id, trade.alert_name, trade.pair, closed_trade.exit_price, closed_trade.pnl, closed_trade.settlement_currency
+                        )
+                    ));
                 }
+                Ok(_) => {}
+                Err(err) => eprintln!("(process_price_tick) Failed to close trade {}: {}", trade.id, err),
             }
         }
-    });
+    }
 }
\ No newline at end of file
@@ -1,24 +1,248 @@
-use std::sync::Arc;
-use mongodb::{bson::doc, options::ClientOptions, Client};
+use std::{future::Future, str::FromStr, sync::{atomic::Ordering, Arc}, time::Duration};
+use mongodb::{bson::doc, error::{ErrorKind, WriteFailure, RETRYABLE_WRITE_ERROR, TRANSIENT_TRANSACTION_ERROR}, event::{cmap::CmapEvent, EventHandler}, options::{ClientOptions, ReadPreference, SelectionCriteria}, Client, Cursor};
+use serde::de::DeserializeOwned;
 
-use crate::models::{ActiveTrade, ClosedTrade, MongoDBState};
+use crate::{constants::{MONGO_WRITE_MAX_RETRIES, MONGO_WRITE_RETRY_BACKOFF_MS}, models::{AccountStatement, ActiveTrade, AlertClaim, AppliedMigration, AuditLogEntry, ChaosMode, ClosedTrade, DeserializationMode, EquitySnapshot, FieldCipher, FundingPayment, FundingRate, InstanceHeartbeat, LeaderLease, Leaderboard, MaintenanceWindow, MongoDBState, MongoPoolConfig, MongoPoolMetrics, MongoPoolStats, PriceAlert, PriceTick, QueuedCommand, RejectedAlert, StateSnapshot, StatsReadPreference, Strategy, SymbolClaim, TradePnlSnapshot, TradeTick, WatchlistEntry}};
 
 impl MongoDBState {
     /// Initializes a new MongoDBState instance with the provided client and required collections.
     pub fn new(client: Arc<Client>) -> Self {
         let active_trade_collection = client.database("main").collection::<ActiveTrade>("ActiveTrades");
         let closed_trade_collection = client.database("main").collection::<ClosedTrade>("ClosedTrades");
+        let funding_rate_collection = client.database("main").collection::<FundingRate>("FundingRates");
+        let funding_payment_collection = client.database("main").collection::<FundingPayment>("FundingPayments");
+        let maintenance_window_collection = client.database("main").collection::<MaintenanceWindow>("MaintenanceWindows");
+        let strategy_collection = client.database("main").collection::<Strategy>("Strategies");
+        let audit_log_collection = client.database("main").collection::<AuditLogEntry>("AuditLog");
+        let watchlist_collection = client.database("main").collection::<WatchlistEntry>("Watchlist");
+        let price_alert_collection = client.database("main").collection::<PriceAlert>("PriceAlerts");
+        let rejected_alert_collection = client.database("main").collection::<RejectedAlert>("RejectedAlerts");
+        let alert_claim_collection = client.database("main").collection::<AlertClaim>("AlertClaims");
+        let command_collection = client.database("main").collection::<QueuedCommand>("Commands");
+        let state_snapshot_collection = client.database("main").collection::<StateSnapshot>("StateSnapshots");
+        let leader_lease_collection = client.database("main").collection::<LeaderLease>("Leases");
+        let instance_heartbeat_collection = client.database("main").collection::<InstanceHeartbeat>("InstanceHeartbeats");
+        let symbol_claim_collection = client.database("main").collection::<SymbolClaim>("SymbolClaims");
+        let migration_collection = client.database("main").collection::<AppliedMigration>("Migrations");
+        let price_tick_collection = client.database("main").collection::<PriceTick>("PriceTicks");
+        let equity_snapshot_collection = client.database("main").collection::<EquitySnapshot>("EquitySnapshots");
+        let trade_tick_collection = client.database("main").collection::<TradeTick>("TradeTicks");
+        let trade_pnl_snapshot_collection = client.database("main").collection::<TradePnlSnapshot>("TradePnlSnapshots");
+        let leaderboard_collection = client.database("main").collection::<Leaderboard>("Leaderboards");
+        let account_statement_collection = client.database("main").collection::<AccountStatement>("AccountStatements");
 
         Self {
             active_trade_collection,
-            closed_trade_collection
+            closed_trade_collection,
+            funding_rate_collection,
+            funding_payment_collection,
+            maintenance_window_collection,
+            strategy_collection,
+            audit_log_collection,
+            watchlist_collection,
+            price_alert_collection,
+            rejected_alert_collection,
+            alert_claim_collection,
+            command_collection,
+            state_snapshot_collection,
+            leader_lease_collection,
+            instance_heartbeat_collection,
+            symbol_claim_collection,
+            migration_collection,
+            price_tick_collection,
+            equity_snapshot_collection,
+            trade_tick_collection,
+            trade_pnl_snapshot_collection,
+            leaderboard_collection,
+            account_statement_collection,
+            deserialization_mode: DeserializationMode::from_env(),
+            field_cipher: FieldCipher::from_env(),
+            stats_read_preference: StatsReadPreference::from_env(),
+            chaos: ChaosMode::from_env(),
         }
     }
 }
 
-/// Initializes a MongoDB client, returning `Arc<Client>` for sharing across threads.
-pub async fn init_mongo(uri: &str) -> mongodb::error::Result<Arc<Client>> {
-    let client_options = ClientOptions::parse(uri).await?;
+impl MongoDBState {
+    /// Collects the documents of a cursor. Malformed documents fail the fetch in strict mode, or are logged and skipped in lenient mode.
+    pub async fn collect_documents<T: DeserializeOwned + Send + Sync>(&self, mut cursor: Cursor<T>) -> Result<Vec<T>, mongodb::error::Error> {
+        let mut results = Vec::new();
+
+        while cursor.advance().await? {
+            match cursor.deserialize_current() {
+                Ok(document) => results.push(document),
+                Err(err) if self.deserialization_mode == DeserializationMode::Lenient => {
+                    let id = cursor.current().get("_id").ok().flatten().map(|id| format!("{:?}", id)).unwrap_or_default();
+                    let kind = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+                    eprintln!("(collect_documents) Skipping malformed {} document {}: {}", kind, id, err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+impl FromStr for DeserializationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "strict" => Ok(DeserializationMode::Strict),
+            "lenient" => Ok(DeserializationMode::Lenient),
+            _ => Err(format!("Unknown deserialization mode: {}", s)),
+        }
+    }
+}
+
+impl DeserializationMode {
+    /// Reads the mode from the `DESERIALIZATION_MODE` env variable (`strict` or `lenient`). Defaults to `lenient`.
+    pub fn from_env() -> Self {
+        match std::env::var("DESERIALIZATION_MODE") {
+            Ok(mode) => mode.parse().unwrap_or_else(|err| {
+                eprintln!("(DeserializationMode::from_env) {}. Defaulting to lenient.", err);
+                DeserializationMode::Lenient
+            }),
+            Err(_) => DeserializationMode::Lenient,
+        }
+    }
+}
+
+impl FromStr for StatsReadPreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', '-'], "").as_str() {
+            "primary" => Ok(StatsReadPreference::Primary),
+            "primarypreferred" => Ok(StatsReadPreference::PrimaryPreferred),
+            "secondary" => Ok(StatsReadPreference::Secondary),
+            "secondarypreferred" => Ok(StatsReadPreference::SecondaryPreferred),
+            "nearest" => Ok(StatsReadPreference::Nearest),
+            _ => Err(format!("Unknown read preference: {}", s)),
+        }
+    }
+}
+
+impl StatsReadPreference {
+    /// Reads the read preference from the `STATS_READ_PREFERENCE` env variable (e.g. `secondaryPreferred`). Defaults to `secondaryPreferred`.
+    pub fn from_env() -> Self {
+        match std::env::var("STATS_READ_PREFERENCE") {
+            Ok(preference) => preference.parse().unwrap_or_else(|err| {
+                eprintln!("(StatsReadPreference::from_env) {}. Defaulting to secondaryPreferred.", err);
+                StatsReadPreference::SecondaryPreferred
+            }),
+            Err(_) => StatsReadPreference::SecondaryPreferred,
+        }
+    }
+
+    /// Converts the read preference into the driver's selection criteria.
+    pub fn selection_criteria(&self) -> SelectionCriteria {
+        let read_preference = match self {
+            StatsReadPreference::Primary => ReadPreference::Primary,
+            StatsReadPreference::PrimaryPreferred => ReadPreference::PrimaryPreferred { options: None },
+            StatsReadPreference::Secondary => ReadPreference::Secondary { options: None },
+            StatsReadPreference::SecondaryPreferred => ReadPreference::SecondaryPreferred { options: None },
+            StatsReadPreference::Nearest => ReadPreference::Nearest { options: None },
+        };
+
+        SelectionCriteria::ReadPreference(read_preference)
+    }
+}
+
+impl MongoPoolConfig {
+    /// Reads the pool tuning from the variables returned by `lookup`. Invalid values are logged and ignored.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let parse = |name: &str| -> Option<u64> {
+            let value = lookup(name)?;
+
+            match value.trim().parse() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    eprintln!("(MongoPoolConfig::from_lookup) Ignoring invalid {}: {}", name, value);
+                    None
+                }
+            }
+        };
+        let parse_u32 = |name: &str| parse(name).and_then(|value| u32::try_from(value).ok());
+        let parse_millis = |name: &str| parse(name).map(Duration::from_millis);
+
+        Self {
+            max_pool_size: parse_u32("MONGODB_MAX_POOL_SIZE"),
+            min_pool_size: parse_u32("MONGODB_MIN_POOL_SIZE"),
+            max_connecting: parse_u32("MONGODB_MAX_CONNECTING"),
+            connect_timeout: parse_millis("MONGODB_CONNECT_TIMEOUT_MS"),
+            server_selection_timeout: parse_millis("MONGODB_SERVER_SELECTION_TIMEOUT_MS"),
+            max_idle_time: parse_millis("MONGODB_MAX_IDLE_TIME_MS"),
+            retry_writes: lookup("MONGODB_RETRY_WRITES").and_then(|value| match value.trim().to_lowercase().as_str() {
+                "true" | "1" => Some(true),
+                "false" | "0" => Some(false),
+                _ => {
+                    eprintln!("(MongoPoolConfig::from_lookup) Ignoring invalid MONGODB_RETRY_WRITES: {}", value);
+                    None
+                }
+            }),
+        }
+    }
+
+    /// Reads the pool tuning from the `MONGODB_*` env variables.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Overrides the options parsed from the connection string with the configured values.
+    pub fn apply(&self, client_options: &mut ClientOptions) {
+        client_options.max_pool_size = self.max_pool_size.or(client_options.max_pool_size);
+        client_options.min_pool_size = self.min_pool_size.or(client_options.min_pool_size);
+        client_options.max_connecting = self.max_connecting.or(client_options.max_connecting);
+        client_options.connect_timeout = self.connect_timeout.or(client_options.connect_timeout);
+        client_options.server_selection_timeout = self.server_selection_timeout.or(client_options.server_selection_timeout);
+        client_options.max_idle_time = self.max_idle_time.or(client_options.max_idle_time);
+        client_options.retry_writes = self.retry_writes.or(client_options.retry_writes);
+    }
+}
+
+impl MongoPoolMetrics {
+    /// Updates the counters from a connection pool event emitted by the driver.
+    pub fn record(&self, event: &CmapEvent) {
+        let counter = match event {
+            CmapEvent::ConnectionCreated(_) => &self.connections_created,
+            CmapEvent::ConnectionClosed(_) => &self.connections_closed,
+            CmapEvent::ConnectionCheckedOut(_) => &self.checkouts,
+            CmapEvent::ConnectionCheckedIn(_) => &self.checkins,
+            CmapEvent::ConnectionCheckoutFailed(_) => &self.checkout_failures,
+            CmapEvent::PoolCleared(_) => &self.pool_clears,
+            _ => return,
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the counters.
+    pub fn stats(&self) -> MongoPoolStats {
+        let connections_created = self.connections_created.load(Ordering::Relaxed);
+        let checkouts = self.checkouts.load(Ordering::Relaxed);
+
+        MongoPoolStats {
+            open_connections: connections_created.saturating_sub(self.connections_closed.load(Ordering::Relaxed)),
+            checked_out_connections: checkouts.saturating_sub(self.checkins.load(Ordering::Relaxed)),
+            connections_created,
+            checkout_failures: self.checkout_failures.load(Ordering::Relaxed),
+            pool_clears: self.pool_clears.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Initializes a MongoDB client, returning `Arc<Client>` for sharing across threads, alongside the counters of its connection pool.
+///
+/// The pool is tuned with the `MONGODB_*` env variables (see `MongoPoolConfig`).
+pub async fn init_mongo(uri: &str) -> mongodb::error::Result<(Arc<Client>, Arc<MongoPoolMetrics>)> {
+    let mut client_options = ClientOptions::parse(uri).await?;
+    MongoPoolConfig::from_env().apply(&mut client_options);
+
+    let pool_metrics = Arc::new(MongoPoolMetrics::default());
+    let pool_metrics_for_events = pool_metrics.clone();
+    client_options.cmap_event_handler = Some(EventHandler::callback(move |event| pool_metrics_for_events.record(&event)));
 
     // creates a new client (wrapped in Arc for thread-safe sharing)
     let client = Client::with_options(client_options)?;
@@ -27,5 +251,58 @@ pub async fn init_mongo(uri: &str) -> mongodb::error::Result<Arc<Client>> {
     client.database("admin").run_command(doc! { "ping": 1 }).await?;
 
     println!("MongoDB connected successfully!");
-    Ok(Arc::new(client))
-}
\ No newline at end of file
+    Ok((Arc::new(client), pool_metrics))
+}
+
+/// The MongoDB error code of a duplicate key error.
+const DUPLICATE_KEY_ERROR_CODE: i32 = 11000;
+
+/// Checks whether a MongoDB error was caused by a duplicate key (i.e. a unique index or `_id` collision).
+pub fn is_duplicate_key_error(err: &mongodb::error::Error) -> bool {
+    match err.kind.as_ref() {
+        ErrorKind::Command(command_error) => command_error.code == DUPLICATE_KEY_ERROR_CODE,
+        ErrorKind::Write(WriteFailure::WriteError(write_error)) => write_error.code == DUPLICATE_KEY_ERROR_CODE,
+        _ => false,
+    }
+}
+
+/// The MongoDB error code of creating a collection that already exists.
+const NAMESPACE_EXISTS_ERROR_CODE: i32 = 48;
+
+/// Checks whether a MongoDB error was caused by creating a collection that already exists.
+pub fn is_namespace_exists_error(err: &mongodb::error::Error) -> bool {
+    matches!(err.kind.as_ref(), ErrorKind::Command(command_error) if command_error.code == NAMESPACE_EXISTS_ERROR_CODE)
+}
+
+/// Checks whether a MongoDB error is transient, i.e. the same write may succeed if retried (e.g. a dropped connection,
+/// a primary election or a cleared connection pool).
+pub fn is_transient_error(err: &mongodb::error::Error) -> bool {
+    err.contains_label(RETRYABLE_WRITE_ERROR)
+        || err.contains_label(TRANSIENT_TRANSACTION_ERROR)
+        || matches!(err.kind.as_ref(), ErrorKind::Io(_) | ErrorKind::ConnectionPoolCleared { .. } | ErrorKind::ServerSelection { .. })
+}
+
+/// Runs `write`, retrying it up to `MONGO_WRITE_MAX_RETRIES` times with exponential backoff while it fails with a transient error.
+///
+/// Complements the driver's own `retryWrites`, which only retries once and not while no server can be selected
+/// (e.g. while the pool is saturated by a burst of webhooks).
+pub async fn retry_transient_write<T, F, Fut>(operation: &str, mut write: F) -> Result<T, mongodb::error::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, mongodb::error::Error>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        match write().await {
+            Err(err) if attempt < MONGO_WRITE_MAX_RETRIES && is_transient_error(&err) => {
+                let backoff = Duration::from_millis(MONGO_WRITE_RETRY_BACKOFF_MS * 2u64.pow(attempt));
+                eprintln!("({}) Transient write error, retrying in {:?}: {}", operation, backoff, err);
+
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -1,3 +1,7 @@
 pub mod db;
+pub mod env;
+pub mod tls;
 
-pub use db::*;
\ No newline at end of file
+pub use db::*;
+pub use env::*;
+pub use tls::*;
\ No newline at end of file
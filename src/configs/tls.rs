@@ -0,0 +1,49 @@
+use axum_server::tls_rustls::RustlsConfig;
+
+/// Resolves the paths of the TLS certificate chain and private key (both PEM-encoded).
+///
+/// Returns `Ok(None)` if neither path is set, in which case the server is served over plain HTTP (e.g. behind a reverse proxy).
+pub fn resolve_tls_paths(cert_path: Option<String>, key_path: Option<String>) -> Result<Option<(String, String)>, String> {
+    let cert_path = cert_path.filter(|path| !path.trim().is_empty());
+    let key_path = key_path.filter(|path| !path.trim().is_empty());
+
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => Ok(Some((cert_path, key_path))),
+        (None, None) => Ok(None),
+        (Some(_), None) => Err("TLS_CERT_PATH is set, but TLS_KEY_PATH isn't".to_string()),
+        (None, Some(_)) => Err("TLS_KEY_PATH is set, but TLS_CERT_PATH isn't".to_string()),
+    }
+}
+
+/// Loads the TLS configuration from the certificate and private key at `TLS_CERT_PATH` and `TLS_KEY_PATH`.
+///
+/// Returns the configuration alongside both paths (to reload from later), or `None` if TLS isn't configured.
+pub async fn init_tls() -> Option<(RustlsConfig, String, String)> {
+    let (cert_path, key_path) = resolve_tls_paths(std::env::var("TLS_CERT_PATH").ok(), std::env::var("TLS_KEY_PATH").ok())
+        .expect("Invalid TLS configuration")?;
+
+    let config = RustlsConfig::from_pem_file(&cert_path, &key_path)
+        .await
+        .expect("Failed to load the TLS certificate and private key");
+
+    Some((config, cert_path, key_path))
+}
+
+/// Reloads the TLS certificate and private key whenever the process receives `SIGHUP` (e.g. after a certificate renewal),
+/// without dropping open connections. The previous certificate is kept if the new one fails to load.
+#[cfg(unix)]
+pub async fn reload_tls_on_sighup(config: RustlsConfig, cert_path: String, key_path: String) {
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("(reload_tls_on_sighup) Failed to listen for SIGHUP");
+
+    while hangup.recv().await.is_some() {
+        match config.reload_from_pem_file(&cert_path, &key_path).await {
+            Ok(_) => println!("(reload_tls_on_sighup) Reloaded the TLS certificate from {}", cert_path),
+            Err(err) => eprintln!("(reload_tls_on_sighup) Failed to reload the TLS certificate, keeping the previous one: {}", err),
+        }
+    }
+}
+
+/// `SIGHUP` doesn't exist on non-unix platforms, so the certificate is only loaded on startup.
+#[cfg(not(unix))]
+pub async fn reload_tls_on_sighup(_config: RustlsConfig, _cert_path: String, _key_path: String) {}
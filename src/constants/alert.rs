@@ -0,0 +1,19 @@
+/// How long (in seconds) an alert may stay claimed without an outcome before another replica may take the claim over,
+/// e.g. because the replica executing it crashed before completing the claim.
+pub const ALERT_CLAIM_STALE_SECS: i64 = 300;
+
+/// How long (in seconds) alert claims are kept before MongoDB expires them. Must be well above the maximum age of an alert,
+/// so that duplicate deliveries still find the claim.
+pub const ALERT_CLAIM_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// The default maximum age (in seconds) of an alert's `timestamp`. Older alerts are rejected as replays.
+/// 
+/// Can be overridden with the `ALERT_MAX_AGE_SECS` env variable.
+pub const DEFAULT_ALERT_MAX_AGE_SECS: i64 = 60;
+
+/// How many times an alert is re-executed when another trade of its strategy on the same pair was opened concurrently
+/// (e.g. by another replica), before giving up.
+pub const MAX_CONCURRENT_ALERT_RETRIES: u32 = 3;
+
+/// How far (in seconds) an alert's `timestamp` may be in the future, to tolerate clock skew between TradingView and the server.
+pub const MAX_ALERT_CLOCK_SKEW_SECS: i64 = 5;
@@ -0,0 +1,11 @@
+/// Alerts whose price deviates from the latest market price by more than this percentage are blocked (e.g. a fat-fingered Pine template).
+pub const MAX_ALERT_PRICE_DEVIATION_PERCENTAGE: f64 = 5.0;
+
+/// The window (in seconds) over which the alert frequency of a strategy is measured.
+pub const ALERT_FREQUENCY_WINDOW_SECS: i64 = 60;
+
+/// The maximum amount of alerts a single strategy may send within `ALERT_FREQUENCY_WINDOW_SECS` before further alerts are blocked.
+pub const MAX_ALERTS_PER_WINDOW: usize = 10;
+
+/// Alerts of strategies without any trade activity for this many days are flagged (but still executed).
+pub const STRATEGY_SILENCE_DAYS: i64 = 90;
@@ -0,0 +1,8 @@
+/// The maximum number of products whose latest price is kept in memory.
+pub const MAX_CACHED_PRICES: usize = 1_000;
+
+/// The maximum number of strategies whose recent alert timestamps are kept in memory.
+pub const MAX_CACHED_ALERT_HISTORIES: usize = 1_000;
+
+/// The maximum number of trade ticks buffered until they're written into the database. The oldest are dropped first.
+pub const MAX_BUFFERED_TRADE_TICKS: usize = 100_000;
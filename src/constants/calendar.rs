@@ -0,0 +1,8 @@
+/// The hour (UTC) that the forex market opens at on Sundays and closes at on Fridays.
+pub const FOREX_WEEKLY_OPEN_HOUR: u32 = 22;
+
+/// The local time (hour, minute) in New York that the US equities market opens at on weekdays.
+pub const US_EQUITIES_OPEN_TIME: (u32, u32) = (9, 30);
+
+/// The local time (hour, minute) in New York that the US equities market closes at on weekdays.
+pub const US_EQUITIES_CLOSE_TIME: (u32, u32) = (16, 0);
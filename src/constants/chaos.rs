@@ -0,0 +1,2 @@
+/// How long (in seconds) a gap in the price feed injected by the chaos mode lasts, unless `CHAOS_FEED_GAP_SECS` is set.
+pub const DEFAULT_CHAOS_FEED_GAP_SECS: u64 = 30;
@@ -0,0 +1,11 @@
+/// The base URL of the Telegram Bot API.
+pub const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// How long (in seconds) a single `getUpdates` long poll waits for new messages.
+pub const TELEGRAM_POLL_TIMEOUT_SECS: u64 = 30;
+
+/// How often (in seconds) the command queue is checked for pending commands.
+pub const COMMAND_POLL_INTERVAL_SECS: u64 = 1;
+
+/// The header carrying the command secret of requests to routes without a request body (e.g. `DELETE` routes).
+pub const COMMAND_SECRET_HEADER: &str = "x-command-secret";
@@ -0,0 +1,9 @@
+/// The base URL of Binance's spot WebSocket streams, used as a secondary price feed.
+pub const BINANCE_SPOT_WS_URL: &str = "wss://stream.binance.com:9443";
+
+/// The deviation (in percent of the median price of the other venues) above which a venue's tick is rejected,
+/// unless `CONSENSUS_DEVIATION_PERCENTAGE` is set.
+pub const DEFAULT_CONSENSUS_DEVIATION_PERCENTAGE: f64 = 2.0;
+
+/// How long (in seconds) the price of a venue counts towards the consensus after it was received.
+pub const CONSENSUS_MAX_PRICE_AGE_SECS: i64 = 30;
@@ -0,0 +1,11 @@
+/// The trailing window (in days) that the correlation of strategies to BTC is calculated over, unless specified.
+pub const DEFAULT_CORRELATION_WINDOW_DAYS: usize = 30;
+
+/// The maximum trailing window (in days) of the correlation of strategies to BTC.
+pub const MAX_CORRELATION_WINDOW_DAYS: usize = 180;
+
+/// How many days of rolling correlations are returned by `GET /stats/correlation`.
+pub const CORRELATION_LOOKBACK_DAYS: usize = 90;
+
+/// The minimum number of days within a window (since the first trade of the strategy) for its correlation to be calculated.
+pub const CORRELATION_MIN_DAYS: usize = 10;
@@ -0,0 +1,8 @@
+/// How many times a write failing with a transient error (e.g. a dropped connection or a primary election) is retried.
+pub const MONGO_WRITE_MAX_RETRIES: u32 = 3;
+
+/// The delay (in milliseconds) before the first retry of a transient write error, doubled on every further retry.
+pub const MONGO_WRITE_RETRY_BACKOFF_MS: u64 = 100;
+
+/// The collection that the duplicate active trades of a strategy, pair and kind are moved to before their unique index is created.
+pub const DUPLICATE_ACTIVE_TRADES_COLLECTION: &str = "DuplicateActiveTrades";
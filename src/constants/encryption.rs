@@ -0,0 +1,4 @@
+/// The prefix of encrypted field values, followed by the base64-encoded nonce and ciphertext.
+/// 
+/// Values without the prefix are read as plaintext, so that documents stored before encryption was enabled stay readable.
+pub const ENCRYPTED_FIELD_PREFIX: &str = "enc:v1:";
@@ -0,0 +1,33 @@
+/// The maker and taker fees (in percentage format) of each known exchange at its lowest fee tier, as `(exchange, maker fee, taker fee)`.
+/// 
+/// Paper trades are filled as market orders, so they pay the taker fee when opening and closing.
+pub const EXCHANGE_TRADING_FEES: [(&str, f64, f64); 6] = [
+    ("binance", 0.02, 0.05),
+    ("bybit", 0.02, 0.055),
+    ("okx", 0.02, 0.05),
+    ("bitget", 0.02, 0.06),
+    ("hyperliquid", 0.015, 0.045),
+    ("dydx", 0.01, 0.05),
+];
+
+/// The fee (in percentage of the notional value at the liquidation price) that each known exchange charges when liquidating a position,
+/// paid into its insurance fund.
+pub const EXCHANGE_LIQUIDATION_FEES: [(&str, f64); 6] = [
+    ("binance", 1.25),
+    ("bybit", 1.0),
+    ("okx", 1.0),
+    ("bitget", 1.0),
+    ("hyperliquid", 0.0),
+    ("dydx", 1.5),
+];
+
+/// The maintenance margin tiers of each known exchange (modelled after its BTC perpetual), as `(maximum notional value in USDT,
+/// maintenance margin in percentage)` in ascending order. The last tier applies to any larger notional value.
+pub const EXCHANGE_MAINTENANCE_MARGIN_TIERS: [(&str, &[(f64, f64)]); 6] = [
+    ("binance", &[(50_000.0, 0.4), (600_000.0, 0.5), (3_000_000.0, 0.65), (12_000_000.0, 1.0), (f64::MAX, 2.5)]),
+    ("bybit", &[(2_000_000.0, 0.5), (10_000_000.0, 1.0), (f64::MAX, 2.0)]),
+    ("okx", &[(500_000.0, 0.4), (5_000_000.0, 0.6), (f64::MAX, 1.5)]),
+    ("bitget", &[(150_000.0, 0.4), (1_000_000.0, 0.5), (f64::MAX, 1.5)]),
+    ("hyperliquid", &[(f64::MAX, 1.25)]),
+    ("dydx", &[(f64::MAX, 3.0)]),
+];
@@ -0,0 +1,36 @@
+/// The price change (in percent of the last accepted price) above which a tick is discarded as a spike, unless `FEED_SPIKE_PERCENTAGE` is set.
+pub const DEFAULT_FEED_SPIKE_PERCENTAGE: f64 = 10.0;
+
+/// The number of spikes in a row after which the new price level is accepted, since the market really moved.
+pub const FEED_SPIKE_CONFIRMATION_TICKS: u32 = 3;
+
+/// How long (in seconds) no tick may be received for a product before it counts as a gap in the price feed.
+pub const FEED_GAP_SECS: i64 = 60;
+
+/// How often (in seconds) the price feed pings Coinbase, so that an idle connection still receives frames (pongs).
+pub const FEED_PING_INTERVAL_SECS: u64 = 5;
+
+/// How long (in seconds) the price feed may receive no frame at all (not even a pong) before the connection is considered
+/// half-open and dropped.
+pub const FEED_IDLE_TIMEOUT_SECS: u64 = 15;
+
+/// How long (in seconds) the price feed waits before reconnecting to Coinbase after losing the connection. The wait doubles with
+/// each failed attempt in a row, up to `FEED_MAX_RECONNECT_DELAY_SECS`.
+pub const FEED_RECONNECT_DELAY_SECS: u64 = 1;
+
+/// The longest wait (in seconds) before reconnecting the price feed.
+pub const FEED_MAX_RECONNECT_DELAY_SECS: u64 = 60;
+
+/// The base URL of Coinbase's Exchange REST API, used to backfill the trades missed by the price feed and to read the price of pairs.
+pub const COINBASE_EXCHANGE_API_URL: &str = "https://api.exchange.coinbase.com";
+
+/// The minimum number of trades skipped by the trade IDs of two ticks to backfill them.
+///
+/// The ticker channel batches cascading matches into a single tick, so small jumps are expected.
+pub const FEED_BACKFILL_MIN_MISSING_TRADES: u64 = 50;
+
+/// The number of trades fetched per request when backfilling (the maximum allowed by Coinbase).
+pub const FEED_BACKFILL_PAGE_SIZE: u64 = 1000;
+
+/// The maximum number of requests per backfill. Older trades of longer gaps aren't backfilled.
+pub const FEED_BACKFILL_MAX_PAGES: usize = 10;
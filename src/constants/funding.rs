@@ -0,0 +1,36 @@
+/// The base URL of Binance's USDⓈ-M futures REST API, used to poll funding rates and to place the orders of live trades.
+pub const BINANCE_FUTURES_API_URL: &str = "https://fapi.binance.com";
+
+/// How long (in milliseconds) a signed request to Binance stays valid after its timestamp.
+pub const BINANCE_RECV_WINDOW_MS: u64 = 5000;
+
+/// The exchange that live trades are placed on.
+pub const LIVE_TRADING_EXCHANGE: &str = "binance";
+
+/// How long (in seconds) the price feed waits before retrying to close a live trade after the exchange rejected the close.
+/// The wait doubles with each failure in a row, up to `LIVE_CLOSE_MAX_RETRY_DELAY_SECS`.
+pub const LIVE_CLOSE_RETRY_DELAY_SECS: i64 = 2;
+
+/// The longest wait (in seconds) before retrying to close a live trade.
+pub const LIVE_CLOSE_MAX_RETRY_DELAY_SECS: i64 = 300;
+
+/// How often (in seconds) the funding rates of all accepted symbols are polled.
+/// 
+/// Funding settles every 8 hours at most exchanges, so hourly polling keeps the history up to date without hitting rate limits.
+pub const FUNDING_RATE_POLL_INTERVAL_SECS: u64 = 3600;
+
+/// The maximum amount of funding rates fetched per pair per poll (the maximum allowed by Binance).
+pub const FUNDING_RATE_FETCH_LIMIT: u32 = 1000;
+
+/// How often (in hours) funding settles on exchanges without a known interval, unless `FUNDING_INTERVAL_HOURS_<EXCHANGE>` is set.
+pub const DEFAULT_FUNDING_INTERVAL_HOURS: u32 = 8;
+
+/// How often (in hours) funding settles on each known exchange. Settlements are aligned to midnight UTC.
+pub const EXCHANGE_FUNDING_INTERVAL_HOURS: [(&str, u32); 6] = [
+    ("binance", 8),
+    ("bybit", 8),
+    ("okx", 8),
+    ("bitget", 8),
+    ("hyperliquid", 1),
+    ("dydx", 1),
+];
@@ -0,0 +1,16 @@
+use crate::models::ReportingCurrency;
+
+/// The currency that stats and reports are denominated in if neither the `REPORTING_CURRENCY` env variable nor the request specifies one.
+pub const DEFAULT_REPORTING_CURRENCY: ReportingCurrency = ReportingCurrency::Usdt;
+
+/// The Coinbase product providing the USDT to USD conversion rate.
+pub const USDT_USD_PRODUCT_ID: &str = "USDT-USD";
+
+/// The Coinbase product providing the USDT to EUR conversion rate.
+pub const USDT_EUR_PRODUCT_ID: &str = "USDT-EUR";
+
+/// The Coinbase product providing the BTC to USD price, used to derive the USDT to BTC conversion rate.
+pub const BTC_USD_PRODUCT_ID: &str = "BTC-USD";
+
+/// The products that the price feed always subscribes to in order to keep conversion rates up to date.
+pub const FX_PRODUCT_IDS: &[&str] = &[USDT_USD_PRODUCT_ID, USDT_EUR_PRODUCT_ID, BTC_USD_PRODUCT_ID];
@@ -0,0 +1,8 @@
+/// The ID of the lease document that instances compete for.
+pub const LEADER_LEASE_ID: &str = "leader";
+
+/// How long (in seconds) a leader lease is valid without being renewed. Another instance takes over once it expires.
+pub const LEADER_LEASE_TTL_SECS: i64 = 15;
+
+/// How often (in seconds) instances renew (or try to acquire) the leader lease. Must be well below `LEADER_LEASE_TTL_SECS`.
+pub const LEADER_LEASE_RENEW_INTERVAL_SECS: u64 = 5;
@@ -0,0 +1,8 @@
+/// How often (in seconds) the leaderboards of all windows are recomputed.
+pub const LEADERBOARD_REFRESH_INTERVAL_SECS: u64 = 900;
+
+/// Strategies with fewer closed trades than this within a window aren't ranked, since their metrics aren't meaningful yet.
+pub const LEADERBOARD_MIN_TRADES: usize = 5;
+
+/// The amount of entries returned by `GET /leaderboard`, unless a `limit` is provided.
+pub const DEFAULT_LEADERBOARD_LIMIT: usize = 10;
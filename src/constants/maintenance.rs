@@ -0,0 +1,11 @@
+/// The exchange that paper trades simulate. Maintenance windows of this exchange flag newly opened paper trades.
+pub const PAPER_TRADING_EXCHANGE: &str = "binance";
+
+/// The base URL of Binance's spot REST API, used to poll the exchange's system status.
+pub const BINANCE_SPOT_API_URL: &str = "https://api.binance.com";
+
+/// How often (in seconds) the exchange's system status is polled for unannounced maintenance.
+pub const MAINTENANCE_STATUS_POLL_INTERVAL_SECS: u64 = 300;
+
+/// Trades opened within this many minutes before or after a maintenance window are flagged as opened near maintenance.
+pub const MAINTENANCE_PROXIMITY_MINUTES: i64 = 60;
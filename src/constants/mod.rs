@@ -1,5 +1,71 @@
+pub mod alert;
+pub mod anomaly;
+pub mod cache;
+pub mod calendar;
+pub mod chaos;
+pub mod command;
+pub mod consensus;
+pub mod correlation;
+pub mod db;
+pub mod encryption;
+pub mod exchange;
+pub mod feed_quality;
+pub mod funding;
+pub mod fx;
+pub mod leader;
+pub mod leaderboard;
+pub mod maintenance;
+pub mod mqtt;
+pub mod network;
+pub mod notification;
 pub mod pagination;
+pub mod regime;
+pub mod report;
+pub mod request;
+pub mod risk;
+pub mod rounding;
+pub mod script;
+pub mod seed;
+pub mod shard;
+pub mod sizing;
+pub mod snapshot;
+pub mod stats;
+pub mod strategy;
+pub mod timeseries;
 pub mod trade;
 
+pub use alert::*;
+pub use anomaly::*;
+pub use cache::*;
+pub use calendar::*;
+pub use chaos::*;
+pub use command::*;
+pub use consensus::*;
+pub use correlation::*;
+pub use db::*;
+pub use encryption::*;
+pub use exchange::*;
+pub use feed_quality::*;
+pub use funding::*;
+pub use fx::*;
+pub use leader::*;
+pub use leaderboard::*;
+pub use maintenance::*;
+pub use mqtt::*;
+pub use network::*;
+pub use notification::*;
 pub use pagination::*;
+pub use regime::*;
+pub use report::*;
+pub use request::*;
+pub use risk::*;
+pub use rounding::*;
+pub use script::*;
+pub use seed::*;
+pub use shard::*;
+pub use sizing::*;
+pub use snapshot::*;
+pub use stats::*;
+pub use strategy::*;
+pub use timeseries::*;
 pub use trade::*;
\ No newline at end of file
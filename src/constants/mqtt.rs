@@ -0,0 +1,14 @@
+/// The port of the MQTT broker, unless overridden by the `MQTT_PORT` env variable.
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// The prefix of all MQTT topics, unless overridden by the `MQTT_TOPIC_PREFIX` env variable.
+pub const DEFAULT_MQTT_TOPIC_PREFIX: &str = "tv-trading-bot";
+
+/// How often (in seconds) the MQTT connection is kept alive when idle.
+pub const MQTT_KEEP_ALIVE_SECS: u64 = 30;
+
+/// The amount of messages buffered while the MQTT broker is unreachable. further messages are dropped.
+pub const MQTT_CHANNEL_CAPACITY: usize = 256;
+
+/// How long (in seconds) to wait before reconnecting to the MQTT broker after the connection failed.
+pub const MQTT_RECONNECT_DELAY_SECS: u64 = 5;
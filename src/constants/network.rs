@@ -0,0 +1,13 @@
+/// The port of SOCKS5 proxies whose URL doesn't specify one.
+pub const DEFAULT_SOCKS_PROXY_PORT: u16 = 1080;
+
+/// The maximum size (in bytes) of the response head of an HTTP proxy to a `CONNECT` request.
+pub const MAX_PROXY_RESPONSE_HEAD_BYTES: usize = 8 * 1024;
+
+/// The maximum size (in bytes) of a compressed WebSocket message, both before and after it's inflated.
+pub const MAX_INFLATED_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// The WebSocket extension offered when connecting with compression enabled (RFC 7692).
+///
+/// Only the server compresses its messages; the messages sent by the bot are small and left uncompressed, which the extension allows.
+pub const PERMESSAGE_DEFLATE_OFFER: &str = "permessage-deflate";
@@ -0,0 +1,10 @@
+use crate::models::NotificationSeverity;
+
+/// The endpoint of the Pushover API that sends messages.
+pub const PUSHOVER_MESSAGES_API_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// The default minimum severity of the notifications delivered to webhooks.
+pub const DEFAULT_WEBHOOK_MIN_SEVERITY: NotificationSeverity = NotificationSeverity::Info;
+
+/// The default minimum severity of the notifications delivered to push sinks (Pushover, ntfy), so that phones only buzz for critical events.
+pub const DEFAULT_PUSH_MIN_SEVERITY: NotificationSeverity = NotificationSeverity::Critical;
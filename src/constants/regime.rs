@@ -0,0 +1,14 @@
+/// How many days of daily candles the market regime of a pair is derived from when a trade is opened.
+pub const REGIME_LOOKBACK_DAYS: i64 = 60;
+
+/// The period (in days) of the EMA of the daily closes that the trend is derived from.
+pub const REGIME_EMA_PERIOD_DAYS: usize = 20;
+
+/// The number of days over which the slope of the EMA is measured.
+pub const REGIME_SLOPE_DAYS: usize = 5;
+
+/// The maximum change (in percent) of the EMA over `REGIME_SLOPE_DAYS` days for the trend to be considered flat.
+pub const REGIME_FLAT_SLOPE_PERCENTAGE: f64 = 1.0;
+
+/// The percentiles of the ATR below which a pair is quiet, and above which it's volatile.
+pub const REGIME_VOLATILITY_PERCENTILES: (f64, f64) = (100.0 / 3.0, 200.0 / 3.0);
@@ -0,0 +1,11 @@
+/// How often (in seconds) the report mailer checks whether a report is due.
+pub const REPORT_MAILER_INTERVAL_SECS: u64 = 60;
+
+/// The default SMTP port (submission with STARTTLS).
+pub const DEFAULT_SMTP_PORT: u16 = 587;
+
+/// The SMTP port using implicit TLS instead of STARTTLS.
+pub const IMPLICIT_TLS_SMTP_PORT: u16 = 465;
+
+/// How often (in seconds) the statement generator checks whether the statements of the previous month are due.
+pub const STATEMENT_GENERATOR_INTERVAL_SECS: u64 = 3600;
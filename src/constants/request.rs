@@ -0,0 +1,5 @@
+/// The header carrying the request ID. If the caller sends one, it's reused instead of generating a new one.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The maximum length of a request ID sent by the caller. Longer IDs are replaced with a generated one.
+pub const MAX_REQUEST_ID_LEN: usize = 128;
@@ -0,0 +1,15 @@
+/// The starting balance (in USDT) of the paper account, unless set by the `PAPER_ACCOUNT_BALANCE_USDT` env variable.
+pub const DEFAULT_PAPER_ACCOUNT_BALANCE: f64 = 10_000.0;
+
+/// The confidence level of the value-at-risk estimate of the open trades.
+pub const VAR_CONFIDENCE: f64 = 0.95;
+
+/// How many days of daily closes (from the price ticks) the value-at-risk is simulated over.
+pub const VAR_LOOKBACK_DAYS: i64 = 90;
+
+/// The minimum number of daily returns required to estimate the value-at-risk.
+pub const VAR_MIN_OBSERVATIONS: usize = 20;
+
+/// The fraction of the paper equity that the value-at-risk may reach before `GET /risk/exposure` warns about it,
+/// unless set by the `VAR_WARNING_EQUITY_FRACTION` env variable.
+pub const DEFAULT_VAR_WARNING_EQUITY_FRACTION: f64 = 0.1;
@@ -0,0 +1,8 @@
+/// The decimals that order quantities are rounded to.
+pub const QUANTITY_DECIMALS: u32 = 2;
+
+/// The default decimals of displayed amounts (e.g. PnL and fees).
+pub const DEFAULT_DISPLAY_DECIMALS: u32 = 2;
+
+/// The most decimals that values can be rounded to.
+pub const MAX_ROUNDING_DECIMALS: u32 = 12;
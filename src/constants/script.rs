@@ -0,0 +1,23 @@
+/// The maximum amount of operations a strategy's filter script may execute per alert, so that a runaway loop can't stall the alert.
+pub const MAX_SCRIPT_OPERATIONS: u64 = 100_000;
+
+/// The maximum depth of nested function calls within a filter script.
+pub const MAX_SCRIPT_CALL_LEVELS: usize = 16;
+
+/// The maximum length (in bytes) of a string built by a filter script, so that e.g. repeatedly doubling a string can't exhaust the memory.
+pub const MAX_SCRIPT_STRING_SIZE: usize = 10_000;
+
+/// The maximum amount of elements of an array built by a filter script. Must be above the amount of open trades passed as `positions`.
+pub const MAX_SCRIPT_ARRAY_SIZE: usize = 10_000;
+
+/// The maximum amount of entries of an object map built by a filter script.
+pub const MAX_SCRIPT_MAP_SIZE: usize = 1_000;
+
+/// The maximum nesting depth of expressions at the top level of a filter script.
+pub const MAX_SCRIPT_EXPR_DEPTH: usize = 64;
+
+/// The maximum nesting depth of expressions within the functions of a filter script.
+pub const MAX_SCRIPT_FUNCTION_EXPR_DEPTH: usize = 32;
+
+/// The maximum length (in bytes) of a filter script.
+pub const MAX_SCRIPT_LENGTH: usize = 10_000;
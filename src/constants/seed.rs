@@ -0,0 +1,2 @@
+/// The prefix of the import key of seeded trades, followed by the alert name, pair and open timestamp of the trade.
+pub const SEED_IMPORT_KEY_PREFIX: &str = "seed";
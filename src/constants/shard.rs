@@ -0,0 +1,9 @@
+/// How long (in seconds) a symbol claim is valid without being renewed. Another instance may claim the symbol once it expires.
+pub const SYMBOL_CLAIM_TTL_SECS: i64 = 30;
+
+/// How long (in seconds) an instance counts as live after its last heartbeat.
+pub const INSTANCE_HEARTBEAT_TTL_SECS: i64 = 30;
+
+/// How often (in seconds) instances send a heartbeat, renew their symbol claims and rebalance symbols.
+/// Must be well below `SYMBOL_CLAIM_TTL_SECS` and `INSTANCE_HEARTBEAT_TTL_SECS`.
+pub const SHARD_REBALANCE_INTERVAL_SECS: u64 = 10;
@@ -0,0 +1,22 @@
+/// The fraction of the full Kelly criterion that Kelly-sized trades are sized with, unless set by the strategy.
+/// Full Kelly sizing is notoriously volatile, so half of it is used by default.
+pub const DEFAULT_KELLY_FRACTION: f64 = 0.5;
+
+/// How many of the latest closed trades of a strategy its win rate and payoff are calculated from for Kelly sizing.
+pub const KELLY_LOOKBACK_TRADES: i64 = 50;
+
+/// The minimum number of closed trades of a strategy required for Kelly sizing. Until then, trades are sized at the fixed notional value.
+pub const KELLY_MIN_TRADES: usize = 20;
+
+/// The daily volatility (ATR in percent of the price) at which volatility-targeted trades are sized at the fixed notional value,
+/// unless set by the strategy.
+pub const DEFAULT_TARGET_VOLATILITY_PERCENTAGE: f64 = 2.0;
+
+/// The number of daily candles the average true range (ATR) is calculated over.
+pub const ATR_PERIOD_DAYS: usize = 14;
+
+/// The maximum multiple of the fixed notional value that volatility targeting sizes trades up to in calm markets.
+pub const MAX_VOLATILITY_SIZE_MULTIPLIER: f64 = 3.0;
+
+/// The notional value of equity percentage sized trades in percent of the paper equity, unless set by the strategy.
+pub const DEFAULT_EQUITY_PERCENTAGE: f64 = 10.0;
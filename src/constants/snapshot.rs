@@ -0,0 +1,8 @@
+/// How often (in seconds) the in-memory state is snapshotted into the database.
+pub const STATE_SNAPSHOT_INTERVAL_SECS: u64 = 30;
+
+/// Snapshots older than this (in seconds) are not restored on boot, since their prices would be stale.
+pub const MAX_STATE_SNAPSHOT_AGE_SECS: i64 = 900;
+
+/// The ID of the single snapshot document, which is replaced on every snapshot.
+pub const STATE_SNAPSHOT_ID: &str = "latest";
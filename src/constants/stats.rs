@@ -0,0 +1,5 @@
+/// The rolling windows (in days) that `GET /stats` breaks the performance of closed trades down into.
+pub const ROLLING_WINDOW_DAYS: [i64; 3] = [7, 30, 90];
+
+/// The labels of the weekday rows returned by `GET /stats/heatmap`, in ISO order (starting from Monday).
+pub const HEATMAP_WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
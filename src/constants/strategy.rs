@@ -0,0 +1,11 @@
+/// How often (in seconds) the strategy scheduler evaluates the enable/disable cron expressions of all strategies.
+pub const STRATEGY_SCHEDULER_INTERVAL_SECS: u64 = 60;
+
+/// How long (in seconds) to wait for a strategy's outcome callback to respond before giving up.
+pub const OUTCOME_CALLBACK_TIMEOUT_SECS: u64 = 10;
+
+/// The path of the webhook that TradingView alerts are sent to.
+pub const ALERT_WEBHOOK_PATH: &str = "/trade/execute_paper_trade";
+
+/// The value of the secret in rendered alert templates, unless the actual secret is requested.
+pub const ALERT_SECRET_PLACEHOLDER: &str = "<TRADINGVIEW_SECRET>";
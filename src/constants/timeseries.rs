@@ -0,0 +1,20 @@
+/// How often (in seconds) the latest price of each product is recorded as a price tick, unless `TICK_SAMPLE_INTERVAL_SECS` is set.
+pub const PRICE_TICK_INTERVAL_SECS: u64 = 10;
+
+/// The maximum number of ticks buffered between two flushes when persisting all ticks, above which the persistence is downgraded to sampled ticks.
+pub const MAX_BUFFERED_PRICE_TICKS: usize = 10_000;
+
+/// How long (in seconds) the persistence of all ticks stays downgraded to sampled ticks under load.
+pub const TICK_PERSISTENCE_DOWNGRADE_SECS: i64 = 300;
+
+/// How long (in seconds) price ticks are kept before the database expires them (7 days).
+pub const PRICE_TICK_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// How often (in seconds) the equity of the account is snapshotted.
+pub const EQUITY_SNAPSHOT_INTERVAL_SECS: u64 = 300;
+
+/// How long (in seconds) equity snapshots are kept before the database expires them (2 years).
+pub const EQUITY_SNAPSHOT_TTL_SECS: u64 = 2 * 365 * 24 * 60 * 60;
+
+/// The maximum number of points returned by a single chart query.
+pub const MAX_CHART_POINTS: i64 = 10_000;
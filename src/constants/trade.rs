@@ -8,30 +8,44 @@ pub const ACCEPTED_SYMBOLS: &[&str] = &[
     "ETHUSDT",
     "BNBUSDT",
     "SOLUSDT",
+    "ETHBTC",
+    "BTCUSD",
 ];
 
-/// Fee for opening and closing a trade (in percentage format). Used in paper trades only to simulate real trading fees.
-pub const EXECUTION_FEE_PERCENTAGE: f64 = 0.05;
+/// The quote currencies that pairs can be split by (e.g. `ETHBTC` into `ETH` and `BTC`).
+/// 
+/// When multiple quote currencies match a pair, the longest one is used (e.g. `USDT` over `USD` for `BTCUSDT`).
+pub const QUOTE_CURRENCIES: &[&str] = &[
+    "USDT",
+    "USDC",
+    "USD",
+    "EUR",
+    "BTC",
+    "ETH",
+];
 
 /// Funding fee for holding a trade over 8 hours (in percentage format). Used in paper trades only to simulate real funding fees.
 /// 
 /// Negative funding fees are paid by the shorters to longers. Positive funding fees are paid by longers to shorters.
 /// 
-/// If a trade is held for more than 8 hours, the funding fee will start accumulating based on this percentage.
+/// On exchanges settling funding more often than every 8 hours, each settlement charges a proportional share of this percentage
+/// (e.g. half of it every 4 hours), so that the fee per hour stays the same.
 pub const FUNDING_FEE_8H_PERCENTAGE: f64 = 0.01;
 
-/// The hours (in UTC) at which the funding fee will start accumulating. Used in paper trades only to simulate real funding fees.
-/// 
-/// If a trade is opened, say, 07:59 UTC, the funding fee will start accumulating at 08:00 UTC.
-pub const FUNDING_FEE_HOURS: [u8; 3] = [0, 8, 16];
-
 /// The margin required (in percentage) of the notional value to keep the trade open and prevent liquidation. 
-/// Used in paper trades only to simulate real margin requirements.
+/// Used in paper trades only to simulate real margin requirements, if the exchange profile has no maintenance margin tiers.
 pub const MAINTENANCE_MARGIN: f64 = 1.0;
 
+/// The percentage of the remaining position that each step of a stepwise liquidation closes (cross margin trades only).
+pub const PARTIAL_LIQUIDATION_STEP_PERCENTAGE: f64 = 50.0;
+
+/// The maximum number of partial liquidation steps of a trade, after which it's liquidated entirely.
+pub const MAX_PARTIAL_LIQUIDATION_STEPS: usize = 3;
+
 /// The default total value of a trade upon entry (in USDT). Used in paper trades only to simulate real trades.
 /// 
 /// Therefore, the quantity of the base currency will be calculated based on this value and the entry price.
+/// For pairs not quoted in USDT, this value is converted into the quote currency first.
 pub const DEFAULT_NOTIONAL_VALUE: f64 = 1000.0;
 
 /// The default leverage used for a trade. Used in paper trades only to simulate real trades.
@@ -40,9 +54,37 @@ pub const DEFAULT_LEVERAGE: TradeLeverage = TradeLeverage::Three;
 /// The default take profit percentage to set for a trade. Used in paper trades only to simulate real trades.
 ///
 /// This is only used if the alert does not provide a take profit price.
+#[allow(dead_code)]
 pub const DEFAULT_TAKE_PROFIT_PERCENTAGE: f64 = 5.0;
 
 /// The default stop loss percentage to set for a trade. Used in paper trades only to simulate real trades.
 /// 
 /// This is only used if the alert does not provide a stop loss price.
-pub const DEFAULT_STOP_LOSS_PERCENTAGE: f64 = 2.0;
\ No newline at end of file
+#[allow(dead_code)]
+pub const DEFAULT_STOP_LOSS_PERCENTAGE: f64 = 2.0;
+/// The alert name of trades imported from an exchange's trade history, unless another one is specified.
+pub const IMPORTED_ALERT_NAME: &str = "imported";
+
+/// How often (in seconds) the ticks captured while trades are open are written into the database.
+pub const TRADE_TICK_FLUSH_INTERVAL_SECS: u64 = 5;
+
+/// The size (in bytes) of the capped collection of trade ticks. The oldest ticks are overwritten once it's full.
+pub const TRADE_TICKS_CAPPED_SIZE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// The maximum number of ticks returned for a single trade.
+pub const MAX_TRADE_TICKS: i64 = 100_000;
+
+/// How often (in seconds) the unrealized PnL of each active trade is snapshotted.
+pub const TRADE_PNL_SNAPSHOT_INTERVAL_SECS: u64 = 60;
+
+/// The candle lengths (in seconds) that `GET /trade/{id}/replay` picks from, shortest first.
+pub const REPLAY_CANDLE_INTERVALS_SECS: [i64; 6] = [60, 300, 900, 3600, 14_400, 86_400];
+
+/// The maximum number of candles returned for a trade replay. Longer trades are replayed with longer candles.
+pub const MAX_REPLAY_CANDLES: i64 = 500;
+
+/// The fraction of a trade's duration that the replay shows before it was opened and after it was closed.
+pub const REPLAY_PADDING_FRACTION: f64 = 0.1;
+
+/// The minimum number of candles that the replay shows before a trade was opened and after it was closed.
+pub const MIN_REPLAY_PADDING_CANDLES: i64 = 10;
@@ -0,0 +1,289 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ring::hmac;
+
+use crate::{api::split_pair, constants::{BINANCE_FUTURES_API_URL, BINANCE_RECV_WINDOW_MS, FUNDING_RATE_FETCH_LIMIT, LIVE_TRADING_EXCHANGE}, models::{BinanceApiError, BinanceClient, BinanceExchangeInfo, BinanceFundingRate, BinanceOrderResponse, BinancePositionRisk, BinanceSymbolFilter, BinanceSymbolInfo, BinanceTicker, BinanceUserTrade, Exchange, FundingRate, OrderFill, OrderSide, SymbolFilters}};
+
+/// Signs `query` with `secret` (HMAC-SHA256), returning the signature as lowercase hex, as required by Binance's signed endpoints.
+pub fn sign_query(query: &str, secret: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+
+    hmac::sign(&key, query.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Parses the fill of an order from its response and its trades, summing up the commissions charged in `settlement_currency`.
+///
+/// A market order may only be partially filled (e.g. it expired after exhausting the order book), in which case the fill is its
+/// executed quantity. Returns an error if nothing was filled.
+pub fn parse_order_fill(order: &BinanceOrderResponse, trades: &[BinanceUserTrade], settlement_currency: &str) -> Result<OrderFill, String> {
+    let quantity = order.executed_qty.parse::<f64>().map_err(|err| format!("Invalid executed quantity {}: {}", order.executed_qty, err))?;
+
+    if quantity <= 0.0 {
+        return Err(format!("Order {} was not filled (status: {})", order.order_id, order.status));
+    }
+
+    let price = order.avg_price.parse::<f64>().map_err(|err| format!("Invalid average price {}: {}", order.avg_price, err))?;
+
+    let fees = trades
+        .iter()
+        .filter(|trade| trade.commission_asset.eq_ignore_ascii_case(settlement_currency))
+        .filter_map(|trade| trade.commission.parse::<f64>().ok())
+        .sum();
+
+    Ok(OrderFill { order_id: order.order_id, price, quantity, fees })
+}
+
+/// Parses the order size rules of a symbol from its exchange info. Market orders follow `MARKET_LOT_SIZE`, on top of `LOT_SIZE`,
+/// so the coarser step size and larger minimum quantity of both are used.
+pub fn parse_symbol_filters(symbol: &BinanceSymbolInfo) -> SymbolFilters {
+    let mut filters = SymbolFilters::default();
+
+    for filter in &symbol.filters {
+        match filter {
+            BinanceSymbolFilter::LotSize { step_size, min_qty } | BinanceSymbolFilter::MarketLotSize { step_size, min_qty } => {
+                if let Ok(step) = step_size.parse::<f64>() {
+                    if step > filters.step_size {
+                        filters.step_size = step;
+                        // e.g. "0.00100000" has a precision of 3
+                        filters.precision = step_size.trim_end_matches('0').split_once('.').map_or(0, |(_, decimals)| decimals.len());
+                    }
+                }
+
+                filters.min_quantity = filters.min_quantity.max(min_qty.parse().unwrap_or(0.0));
+            }
+            BinanceSymbolFilter::MinNotional { notional } => filters.min_notional = notional.parse().unwrap_or(0.0),
+            BinanceSymbolFilter::Other => {}
+        }
+    }
+
+    filters
+}
+
+/// Rounds `quantity` down to the step size of `filters`, formatted as Binance expects it.
+///
+/// Returns an error if the rounded quantity is zero or below the minimum quantity, or if an order that isn't reduce-only would be
+/// worth less than the minimum notional value at `price`.
+pub fn round_order_quantity(quantity: f64, price: f64, filters: &SymbolFilters, reduce_only: bool) -> Result<String, String> {
+    let rounded = if filters.step_size > 0.0 {
+        // the epsilon keeps quantities that are already a multiple of the step size from being rounded down a step
+        ((quantity / filters.step_size) + 1e-9).floor() * filters.step_size
+    } else {
+        quantity
+    };
+
+    if rounded <= 0.0 || rounded < filters.min_quantity {
+        return Err(format!("The quantity {} is below the minimum order quantity of {} (step size {}).", quantity, filters.min_quantity.max(filters.step_size), filters.step_size));
+    }
+
+    if !reduce_only && rounded * price < filters.min_notional {
+        return Err(format!("The order of {} is worth {:.2} USDT, below the minimum notional value of {} USDT.", rounded, rounded * price, filters.min_notional));
+    }
+
+    Ok(if filters.step_size > 0.0 { format!("{:.*}", filters.precision, rounded) } else { rounded.to_string() })
+}
+
+impl BinanceClient {
+    /// Builds a client from the `BINANCE_API_KEY` and `BINANCE_API_SECRET` env variables, and optionally `BINANCE_FUTURES_API_URL`
+    /// (e.g. the testnet).
+    ///
+    /// Requests are sent with `client`. Returns `None` if the credentials aren't set.
+    pub fn from_env(client: reqwest::Client) -> Option<Self> {
+        let api_key = std::env::var("BINANCE_API_KEY").ok().filter(|key| !key.trim().is_empty())?;
+        let api_secret = std::env::var("BINANCE_API_SECRET").ok().filter(|secret| !secret.trim().is_empty())?;
+
+        Some(Self {
+            client,
+            base_url: std::env::var("BINANCE_FUTURES_API_URL").unwrap_or_else(|_| BINANCE_FUTURES_API_URL.to_string()),
+            api_key,
+            api_secret,
+            symbol_filters: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Returns the order size rules of `symbol`, fetching the exchange info the first time.
+    async fn fetch_symbol_filters(&self, symbol: &str) -> Result<SymbolFilters, String> {
+        if let Some(filters) = self.symbol_filters.lock().unwrap().get(symbol) {
+            return Ok(filters.clone())
+        }
+
+        let body = self.send_public("/fapi/v1/exchangeInfo", &[]).await?;
+        let exchange_info: BinanceExchangeInfo = serde_json::from_str(&body).map_err(|err| format!("Failed to parse the exchange info: {}", err))?;
+
+        let mut symbol_filters = self.symbol_filters.lock().unwrap();
+
+        for info in &exchange_info.symbols {
+            symbol_filters.insert(info.symbol.clone(), parse_symbol_filters(info));
+        }
+
+        symbol_filters.get(symbol).cloned().ok_or_else(|| format!("{} is not listed on Binance.", symbol))
+    }
+
+    /// Builds a client of the public market data endpoints (e.g. funding rates), which don't need API credentials.
+    pub fn public(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            base_url: BINANCE_FUTURES_API_URL.to_string(),
+            api_key: String::new(),
+            api_secret: String::new(),
+            symbol_filters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Builds the signed query string of `params`, with the current timestamp and receive window appended.
+    fn signed_query(&self, params: &[(&str, String)]) -> String {
+        let mut query = params
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>();
+
+        query.push(format!("recvWindow={}", BINANCE_RECV_WINDOW_MS));
+        // Binance rejects requests outside of the receive window of its own time, so simulated clocks must not be used here
+        query.push(format!("timestamp={}", Utc::now().timestamp_millis()));
+
+        let query = query.join("&");
+        let signature = sign_query(&query, &self.api_secret);
+
+        format!("{}&signature={}", query, signature)
+    }
+
+    /// Sends a signed request, returning the body of a successful response, or the error reported by Binance.
+    async fn send_signed(&self, method: reqwest::Method, path: &str, params: &[(&str, String)]) -> Result<String, String> {
+        let response = self.client
+            .request(method, format!("{}{}?{}", self.base_url, path, self.signed_query(params)))
+            .header("X-MBX-APIKEY", &self.api_key)
+            .send()
+            .await
+            .map_err(|err| format!("Failed to reach Binance: {}", err))?;
+
+        read_response(response).await
+    }
+
+    /// Sends a request to a public market data endpoint, which doesn't need to be signed.
+    async fn send_public(&self, path: &str, params: &[(&str, String)]) -> Result<String, String> {
+        let response = self.client
+            .get(format!("{}{}", self.base_url, path))
+            .query(params)
+            .send()
+            .await
+            .map_err(|err| format!("Failed to reach Binance: {}", err))?;
+
+        read_response(response).await
+    }
+}
+
+/// Returns the body of a successful response, or the error reported by Binance.
+async fn read_response(response: reqwest::Response) -> Result<String, String> {
+    let status = response.status();
+    let body = response.text().await.map_err(|err| format!("Failed to read the response of Binance: {}", err))?;
+
+    if !status.is_success() {
+        return Err(match serde_json::from_str::<BinanceApiError>(&body) {
+            Ok(error) => format!("Binance rejected the request ({}): {}", error.code, error.msg),
+            Err(_) => format!("Binance rejected the request ({}): {}", status, body),
+        });
+    }
+
+    Ok(body)
+}
+
+#[async_trait]
+impl Exchange for BinanceClient {
+    fn name(&self) -> &'static str {
+        LIVE_TRADING_EXCHANGE
+    }
+
+    /// Places a market order of `quantity` on `pair` (e.g. BTCUSDT) and waits for its fill, including the fees charged for it.
+    /// The quantity is rounded down to the step size of the symbol first (see `round_order_quantity`).
+    ///
+    /// Orders closing a trade are reduce-only, so that they can never open a position in the other direction.
+    async fn place_order(&self, pair: &str, side: OrderSide, quantity: f64, reduce_only: bool) -> Result<OrderFill, String> {
+        let symbol = pair.to_uppercase();
+        let settlement_currency = split_pair(&symbol).map(|(_, quote)| quote).unwrap_or_else(|| "USDT".to_string());
+
+        let filters = self.fetch_symbol_filters(&symbol).await?;
+        // the minimum notional value only applies to orders that aren't reduce-only
+        let price = if reduce_only || filters.min_notional <= 0.0 { 0.0 } else { self.get_ticker(&symbol).await? };
+        let quantity = round_order_quantity(quantity, price, &filters, reduce_only)?;
+
+        let body = self.send_signed(reqwest::Method::POST, "/fapi/v1/order", &[
+            ("symbol", symbol.clone()),
+            ("side", side.as_str().to_string()),
+            ("type", "MARKET".to_string()),
+            ("quantity", quantity),
+            ("reduceOnly", reduce_only.to_string()),
+            ("newOrderRespType", "RESULT".to_string()),
+        ]).await?;
+
+        let order: BinanceOrderResponse = serde_json::from_str(&body).map_err(|err| format!("Failed to parse the order: {}", err))?;
+
+        // the fees are only reported by the trades of the order
+        let trades = match self.send_signed(reqwest::Method::GET, "/fapi/v1/userTrades", &[("symbol", symbol), ("orderId", order.order_id.to_string())]).await {
+            Ok(body) => serde_json::from_str::<Vec<BinanceUserTrade>>(&body).unwrap_or_default(),
+            Err(err) => {
+                eprintln!("(place_order) Failed to fetch the fees of order {}: {}", order.order_id, err);
+                Vec::new()
+            }
+        };
+
+        parse_order_fill(&order, &trades, &settlement_currency)
+    }
+
+    async fn set_leverage(&self, pair: &str, leverage: f64) -> Result<(), String> {
+        self.send_signed(reqwest::Method::POST, "/fapi/v1/leverage", &[
+            ("symbol", pair.to_uppercase()),
+            ("leverage", (leverage as u32).to_string()),
+        ]).await?;
+
+        Ok(())
+    }
+
+    /// In one-way mode, the position of a symbol is the net of all the live trades on it.
+    async fn get_position(&self, pair: &str) -> Result<f64, String> {
+        let body = self.send_signed(reqwest::Method::GET, "/fapi/v2/positionRisk", &[("symbol", pair.to_uppercase())]).await?;
+        let positions: Vec<BinancePositionRisk> = serde_json::from_str(&body).map_err(|err| format!("Failed to parse the positions: {}", err))?;
+
+        positions
+            .iter()
+            .map(|position| position.position_amt.parse::<f64>().map_err(|err| format!("Invalid position amount {}: {}", position.position_amt, err)))
+            .sum()
+    }
+
+    async fn get_ticker(&self, pair: &str) -> Result<f64, String> {
+        let body = self.send_public("/fapi/v1/ticker/price", &[("symbol", pair.to_uppercase())]).await?;
+        let ticker: BinanceTicker = serde_json::from_str(&body).map_err(|err| format!("Failed to parse the ticker: {}", err))?;
+
+        ticker.price.parse().map_err(|err| format!("Invalid price {}: {}", ticker.price, err))
+    }
+
+    /// Returns up to `FUNDING_RATE_FETCH_LIMIT` settled funding rates. Only the USDⓈ-M perpetuals have any.
+    async fn get_funding_rates(&self, pair: &str, since: Option<DateTime<Utc>>) -> Result<Vec<FundingRate>, String> {
+        let mut params = vec![
+            ("symbol", pair.to_uppercase()),
+            ("limit", FUNDING_RATE_FETCH_LIMIT.to_string()),
+        ];
+
+        if let Some(since) = since {
+            params.push(("startTime", (since.timestamp_millis() + 1).to_string()));
+        }
+
+        let body = self.send_public("/fapi/v1/fundingRate", &params).await?;
+        let binance_rates: Vec<BinanceFundingRate> = serde_json::from_str(&body).map_err(|err| format!("Failed to parse the funding rates: {}", err))?;
+
+        binance_rates
+            .into_iter()
+            .filter_map(|binance_rate| DateTime::from_timestamp_millis(binance_rate.funding_time).map(|funding_time| (funding_time, binance_rate)))
+            .map(|(funding_time, binance_rate)| Ok(FundingRate {
+                rate: binance_rate.funding_rate.parse().map_err(|err| format!("Invalid funding rate {}: {}", binance_rate.funding_rate, err))?,
+                mark_price: binance_rate.mark_price.and_then(|price| price.parse().ok()),
+                pair: binance_rate.symbol,
+                funding_time,
+            }))
+            .collect()
+    }
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::{api::to_coinbase_product_id, constants::COINBASE_EXCHANGE_API_URL, models::{CoinbaseClient, CoinbaseTicker, Exchange, FundingRate, OrderFill, OrderSide}};
+
+impl CoinbaseClient {
+    /// Builds a client of Coinbase's public API, sending requests with `client`.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            base_url: COINBASE_EXCHANGE_API_URL.to_string(),
+        }
+    }
+}
+
+impl Default for CoinbaseClient {
+    fn default() -> Self {
+        Self::new(reqwest::Client::new())
+    }
+}
+
+#[async_trait]
+impl Exchange for CoinbaseClient {
+    fn name(&self) -> &'static str {
+        "coinbase"
+    }
+
+    async fn place_order(&self, pair: &str, _side: OrderSide, _quantity: f64, _reduce_only: bool) -> Result<OrderFill, String> {
+        Err(format!("Coinbase is only used for prices; can't place an order on {}.", pair))
+    }
+
+    async fn get_ticker(&self, pair: &str) -> Result<f64, String> {
+        let product_id = to_coinbase_product_id(pair).ok_or_else(|| format!("Invalid pair {}", pair))?;
+
+        let ticker = self.client
+            .get(format!("{}/products/{}/ticker", self.base_url, product_id))
+            // Coinbase rejects requests without a user agent
+            .header(reqwest::header::USER_AGENT, env!("CARGO_PKG_NAME"))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| format!("Failed to fetch the ticker of {}: {}", product_id, err))?
+            .json::<CoinbaseTicker>()
+            .await
+            .map_err(|err| format!("Failed to parse the ticker of {}: {}", product_id, err))?;
+
+        ticker.price.parse().map_err(|err| format!("Invalid price {}: {}", ticker.price, err))
+    }
+
+    /// Coinbase only lists spot markets, which have no funding.
+    async fn get_funding_rates(&self, _pair: &str, _since: Option<DateTime<Utc>>) -> Result<Vec<FundingRate>, String> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,30 @@
+pub mod binance;
+pub mod coinbase;
+
+use crate::models::{OrderSide, TradeDirection};
+
+impl OrderSide {
+    /// The side of the order opening a trade in `direction`.
+    pub fn opening(direction: &TradeDirection) -> Self {
+        match direction {
+            TradeDirection::Long => Self::Buy,
+            TradeDirection::Short => Self::Sell,
+        }
+    }
+
+    /// The side of the order closing a trade in `direction`.
+    pub fn closing(direction: &TradeDirection) -> Self {
+        match direction {
+            TradeDirection::Long => Self::Sell,
+            TradeDirection::Short => Self::Buy,
+        }
+    }
+
+    /// The name of the side in the APIs of exchanges.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Buy => "BUY",
+            Self::Sell => "SELL",
+        }
+    }
+}
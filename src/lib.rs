@@ -0,0 +1,9 @@
+pub mod models;
+pub mod api;
+pub mod routes;
+pub mod configs;
+pub mod constants;
+pub mod plugins;
+pub mod exchanges;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson;
+use serde::{Deserialize, Serialize};
+
+use super::ResponseCode;
+
+/// A claim of a replica on an alert, ensuring that an alert delivered to multiple replicas (or retried) is only executed once.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertClaim {
+    /// the idempotency key of the alert.
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// the ID of the instance that claimed (and executed) the alert.
+    pub holder: String,
+    /// the timestamp of when the alert was claimed.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub claimed_timestamp: DateTime<Utc>,
+    /// the time of when the alert was claimed, which the TTL index of the collection expires claims by.
+    /// TTL indexes require a BSON date rather than a timestamp in seconds.
+    #[serde(default)]
+    pub claimed_at: Option<bson::DateTime>,
+    /// the outcome of executing the alert. `None` while the alert is still being executed.
+    pub outcome: Option<AlertOutcome>,
+}
+
+/// The response returned by the replica that executed an alert, replayed to duplicate deliveries of the alert.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertOutcome {
+    /// the HTTP status code of the response.
+    pub status_code: u16,
+    /// the machine-readable code of the response.
+    #[serde(default)]
+    pub code: Option<ResponseCode>,
+    /// the message of the response.
+    pub message: String,
+}
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// An anomaly detected on an incoming alert.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum AlertAnomaly {
+    /// the alert's price deviates too far from the latest market price. blocks the alert.
+    #[serde(rename_all = "camelCase")]
+    PriceDeviation { alert_price: f64, market_price: f64, deviation_percentage: f64 },
+    /// the strategy sent too many alerts within a short window. blocks the alert.
+    #[serde(rename_all = "camelCase")]
+    FrequencySpike { alerts: usize, window_secs: i64 },
+    /// the strategy has had no trade activity for a long time. the alert is only flagged.
+    #[serde(rename_all = "camelCase")]
+    SilentStrategy {
+        #[serde(with = "chrono::serde::ts_seconds")]
+        last_activity: DateTime<Utc>,
+    },
+}
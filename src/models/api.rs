@@ -1,12 +1,88 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// `ApiResponse` is a generic struct that represents the response that the server sends back to the client.
 #[derive(Serialize)]
 pub struct ApiResponse<T> {
     /// includes both the status code and the status message.
     pub status: &'static str,
-    /// includes the message that the server wants to send back to the client.
+    /// a stable, machine-readable outcome that callers can branch on (e.g. `TRADE_OPENED`), if the endpoint provides one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ResponseCode>,
+    /// includes the message that the server wants to send back to the client. omitted if empty (see `ResponseVerbosity`).
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub message: String,
     /// includes any optional data that the server wants to send back to the client.
     pub data: Option<T>
-}
\ No newline at end of file
+}
+
+/// The machine-readable outcomes of executing an alert, returned in the `code` field of the response.
+///
+/// Unlike the message, these are stable and safe to branch on.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ResponseCode {
+    /// a new trade was opened.
+    TradeOpened,
+    /// the existing trade in the opposite direction was closed, and a new trade was opened.
+    TradeFlipped,
+    /// a trade in the same direction is already open, so the alert was ignored.
+    AlertIgnoredSameDirection,
+    /// the alert is already being executed by another replica.
+    AlertInProgress,
+    /// this instance isn't the leader, so it doesn't execute alerts.
+    NotLeader,
+    /// the payload couldn't be deserialized into an alert.
+    InvalidPayload,
+    /// the secret of the alert didn't match.
+    InvalidSecret,
+    /// the timestamp of the alert was too old or in the future.
+    InvalidTimestamp,
+    /// the pair of the alert isn't accepted.
+    SymbolNotAllowed,
+    /// the market of the pair is closed.
+    MarketClosed,
+    /// the trade would oppose the trade of another strategy on the same exchange account.
+    PositionConflict,
+    /// live trading is disabled or not configured.
+    LiveTradingDisabled,
+    /// the strategy of the alert is disabled.
+    StrategyDisabled,
+    /// the alert was blocked by the anomaly guard.
+    AnomalyDetected,
+    /// no conversion rate was available to size the trade.
+    NoConversionRate,
+    /// the execution of alerts is paused.
+    Paused,
+    /// the strategy of the alert opened a trade within its cooldown.
+    Cooldown,
+    /// the order of the alert was rejected by the exchange. retrying may succeed.
+    ExchangeRejected,
+    /// the alert was rejected by the filter script of its strategy.
+    FilteredByScript,
+    /// the alert was rejected by an alert filter plugin.
+    FilteredByPlugin,
+    /// the recent trades of the Kelly-sized strategy give it no edge, so no trade was opened.
+    NoEdge,
+    /// the loss at the stop loss of the trade would exceed the per-trade risk cap, so no trade was opened.
+    RiskCapExceeded,
+    /// the exchange is under maintenance, so no order was placed.
+    ExchangeMaintenance,
+    /// an order was filled on the exchange but the trade couldn't be recorded, so the position was flattened again. the alert
+    /// must not be retried, since it would place another order.
+    OrderNotRecorded,
+    /// the alert couldn't be executed due to an internal error (e.g. a database failure). retrying may succeed.
+    InternalError
+}
+
+/// How much human-readable detail the alert webhook includes in its responses (`RESPONSE_VERBOSITY` env variable).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ResponseVerbosity {
+    /// includes the human-readable message alongside the code.
+    Verbose,
+    /// only includes the code, e.g. to not expose internal details when the webhook is reachable from the internet.
+    Minimal
+}
+
+/// The ID of the request being handled, used to correlate logs, stored documents and the response of a single request.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// A record of a change made to the bot's configuration, either via the API or automatically.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    /// the unique database ID of the entry.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// the timestamp of when the change was made.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// who made the change.
+    pub actor: AuditActor,
+    /// what was changed.
+    pub action: AuditAction,
+    /// the name or ID of the changed entity (e.g. the strategy name).
+    pub target: String,
+    /// optional human-readable details of the change.
+    pub details: Option<String>,
+    /// the ID of the API request that made the change, if it was made via the API.
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+/// Used to determine who made a change recorded in the audit log.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditActor {
+    /// a request to the bot's API.
+    Api,
+    /// the strategy scheduler task.
+    Scheduler,
+    /// a command sent by an operator (e.g. via the Telegram bot).
+    Command,
+    /// the price listener (e.g. when liquidating a trade).
+    PriceListener
+}
+
+/// The kinds of changes recorded in the audit log.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    /// the entity was created or its configuration replaced.
+    Updated,
+    Deleted,
+    Enabled,
+    Disabled,
+    /// part of the trade was liquidated.
+    PartiallyLiquidated
+}
+
+/// Query parameters accepted by `GET /audit`.
+#[derive(Deserialize, Debug)]
+pub struct AuditLogQuery {
+    /// only include entries of this target (e.g. a strategy name).
+    pub target: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
@@ -0,0 +1,30 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+/// A map holding at most `capacity` entries, which evicts the least recently written entry when a new one doesn't fit.
+///
+/// Reads don't count as a use, so that e.g. prices stop being cached once the price feed stops sending them.
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    /// the maximum number of entries.
+    pub capacity: usize,
+    /// the value of each key, and when it was last written.
+    pub entries: HashMap<K, (V, u64)>,
+    /// the keys ordered by when they were last written, least recently first.
+    pub order: BTreeMap<u64, K>,
+    /// incremented on every write.
+    pub writes: u64,
+    /// the number of entries evicted because the cache was full.
+    pub evictions: u64,
+}
+
+/// The size and evictions of an in-memory cache, as returned by `GET /admin/caches`.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheReport {
+    pub name: &'static str,
+    pub len: usize,
+    pub capacity: usize,
+    pub evictions: u64,
+}
@@ -0,0 +1,34 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// The trading hours that the market of a pair follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketCalendar {
+    /// open 24/7, like crypto markets.
+    #[default]
+    Continuous,
+    /// open from Sunday 22:00 UTC until Friday 22:00 UTC, like FX CFDs.
+    Forex,
+    /// open on weekdays from 09:30 until 16:00 New York time, like US indices.
+    UsEquities,
+}
+
+/// The trading hours of a pair, i.e. its market calendar and the holidays its market is closed on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketHours {
+    pub calendar: MarketCalendar,
+    /// the days (in the market's timezone) that a non-continuous market is closed on.
+    pub holidays: HashSet<NaiveDate>,
+}
+
+/// The market calendars of the traditional-market (TradFi) symbols. All other symbols trade 24/7.
+#[derive(Debug, Clone, Default)]
+pub struct TradingCalendar {
+    /// the calendar of each TradFi symbol (e.g. `EURUSD`) (`TRADFI_SYMBOLS` env variable).
+    pub symbols: HashMap<String, MarketCalendar>,
+    /// the days that all TradFi markets are closed on (`MARKET_HOLIDAYS` env variable).
+    pub holidays: HashSet<NaiveDate>,
+}
@@ -0,0 +1,24 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+
+/// Randomly injects failures to verify the bot's recovery paths before trusting it with live capital (`CHAOS_*` env variables).
+/// Never enable it in production.
+#[derive(Debug)]
+pub struct ChaosMode {
+    /// whether failures are injected at all (`CHAOS_MODE`).
+    pub enabled: bool,
+    /// the probability (0 to 1) that a trade write to the database fails with a transient error (`CHAOS_DB_WRITE_FAILURE_RATE`).
+    pub db_write_failure_rate: f64,
+    /// the probability (0 to 1) that the exchange rejects an order opened by an alert (`CHAOS_ORDER_REJECTION_RATE`).
+    pub order_rejection_rate: f64,
+    /// the probability (0 to 1) that a tick of the price feed starts a gap (`CHAOS_FEED_GAP_RATE`).
+    pub feed_gap_rate: f64,
+    /// how long (in seconds) a gap in the price feed lasts (`CHAOS_FEED_GAP_SECS`).
+    pub feed_gap_secs: u64,
+    /// the end of the current gap in the price feed, if any.
+    pub feed_gap_until: Mutex<Option<DateTime<Utc>>>,
+    /// decides whether each failure is injected.
+    pub rng: SystemRandom,
+}
@@ -0,0 +1,26 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+/// The source of the current time used by trade execution, funding accrual and the schedulers.
+///
+/// Production uses the `SystemClock`, while backtests and tests use a `SimulatedClock` to advance time deterministically.
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A thread-safe, shared clock.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real clock of the system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+/// A clock that only moves when it's set or advanced.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct SimulatedClock {
+    /// the current time of the clock.
+    pub now: Mutex<DateTime<Utc>>,
+}
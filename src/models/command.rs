@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// A command sent by an operator (e.g. via a chat bot), queued in the database until it's processed by the trade engine.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedCommand {
+    /// the unique database ID of the command.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// where the command was sent from.
+    pub source: CommandSource,
+    /// who sent the command (e.g. the Telegram chat ID).
+    pub sender: String,
+    /// the raw text of the command (e.g. `/close 6790f0c5e3a1b2c3d4e5f601`).
+    pub text: String,
+    /// the processing status of the command.
+    pub status: CommandStatus,
+    /// the reply to the command once it's processed.
+    pub result: Option<String>,
+    /// the timestamp of when the command was queued.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_timestamp: DateTime<Utc>,
+    /// the timestamp of when the command was processed.
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub processed_timestamp: Option<DateTime<Utc>>,
+}
+
+/// Used to determine where a command was sent from.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandSource {
+    /// a message to the Telegram bot. the reply is sent back to the chat.
+    Telegram,
+    /// a request to `POST /commands` (e.g. from a Discord bot). the reply can be fetched via `GET /commands/:id`.
+    Api
+}
+
+/// The processing status of a queued command.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CommandStatus {
+    Pending,
+    Processing,
+    Completed,
+    Failed
+}
+
+/// A command parsed from its text.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BotCommand {
+    /// `/close <trade id>`: closes an active paper trade at the latest price.
+    Close { trade_id: ObjectId },
+    /// `/pause [strategy]`: pauses the execution of all alerts, or disables a single strategy.
+    Pause { strategy: Option<String> },
+    /// `/resume [strategy]`: resumes the execution of all alerts, or enables a single strategy.
+    Resume { strategy: Option<String> },
+    /// `/status`: summarizes the open trades and the state of the bot.
+    Status,
+    /// `/pnl [today|week|month|all]`: summarizes the realized PnL of the trades closed within the period.
+    Pnl { period: PnlPeriod },
+}
+
+/// The periods accepted by the `/pnl` command.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PnlPeriod {
+    /// since the start of the current UTC day.
+    Today,
+    /// within the last 7 days.
+    Week,
+    /// within the last 30 days.
+    Month,
+    All
+}
+
+/// The request body of `POST /commands`.
+#[derive(Deserialize, Debug)]
+pub struct NewCommand {
+    /// the raw text of the command (e.g. `/status`).
+    pub text: String,
+    /// who sent the command (e.g. a Discord user), for the audit trail.
+    pub sender: String,
+    /// the secret key to authenticate the command (`COMMAND_SECRET` env variable).
+    pub secret: String,
+}
+
+/// An update returned by Telegram's `getUpdates` method.
+#[derive(Deserialize, Debug)]
+pub struct TelegramUpdate {
+    pub update_id: i64,
+    pub message: Option<TelegramMessage>,
+}
+
+/// A message within a `TelegramUpdate`.
+#[derive(Deserialize, Debug)]
+pub struct TelegramMessage {
+    pub chat: TelegramChat,
+    pub text: Option<String>,
+}
+
+/// The chat a `TelegramMessage` was sent in.
+#[derive(Deserialize, Debug)]
+pub struct TelegramChat {
+    pub id: i64,
+}
+
+/// The response envelope of the Telegram Bot API.
+#[derive(Deserialize, Debug)]
+pub struct TelegramResponse<T> {
+    pub ok: bool,
+    pub result: Option<T>,
+    pub description: Option<String>,
+}
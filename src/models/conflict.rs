@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens when a strategy would open a trade opposing the trade of another strategy on the same exchange account and pair,
+/// which would cancel each other out on exchanges in one-way mode.
+#[derive(Deserialize, Serialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// opposing trades are opened without a check, as long as each strategy's trades are only simulated.
+    #[default]
+    Allow,
+    /// the alert opening the opposing trade is rejected.
+    Block,
+    /// the opposing trade is opened and booked per strategy, while the account only holds the net position (see `GET /positions/net`).
+    Net,
+    /// the opposing trade is only opened on exchanges whose account runs in hedge mode, which holds both directions separately.
+    RequireHedgeMode,
+}
+
+/// Detects strategies that would hold opposing positions on the same exchange account and pair.
+#[derive(Debug, Default, Clone)]
+pub struct PositionConflictGuard {
+    /// the policy applied to conflicting trades (`POSITION_CONFLICT_POLICY` env variable).
+    pub policy: ConflictPolicy,
+    /// the exchanges whose account runs in hedge mode (`HEDGE_MODE_EXCHANGES` env variable), in lowercase.
+    pub hedge_mode_exchanges: Vec<String>,
+}
@@ -0,0 +1,51 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The venues that prices are received from. Coinbase is the primary price feed that triggers are evaluated against,
+/// the others only contribute to the consensus price (`SECONDARY_PRICE_FEEDS` env variable).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriceVenue {
+    Coinbase,
+    Binance,
+}
+
+/// The last accepted price of a product on a venue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VenuePrice {
+    pub price: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Compares the prices of each product across venues, so that a tick deviating too far from the other venues is rejected.
+#[derive(Debug, Default)]
+pub struct PriceConsensus {
+    /// the venues connected besides Coinbase. the consensus is disabled without any.
+    pub secondary_venues: Vec<PriceVenue>,
+    /// the deviation (in percent of the median price of the other venues) above which a tick is rejected (`CONSENSUS_DEVIATION_PERCENTAGE` env variable).
+    pub deviation_percentage: f64,
+    /// the weight of each venue in the index price (`INDEX_PRICE_WEIGHTS` env variable). venues are weighted equally if empty.
+    pub index_weights: HashMap<PriceVenue, f64>,
+    /// the last accepted price of each product (e.g. `BTC-USDT`) on each venue.
+    pub prices: Mutex<HashMap<String, HashMap<PriceVenue, VenuePrice>>>,
+}
+
+/// A message of Binance's combined trade streams (`<symbol>@trade`).
+#[derive(Debug, Deserialize)]
+pub struct BinanceTradeStreamMessage {
+    pub stream: String,
+    pub data: BinanceTradeEvent,
+}
+
+/// A trade on Binance.
+#[derive(Debug, Deserialize)]
+pub struct BinanceTradeEvent {
+    /// the symbol (e.g. `BTCUSDT`).
+    #[serde(rename = "s")]
+    pub symbol: String,
+    /// the price (e.g. `"96289.34"`).
+    #[serde(rename = "p")]
+    pub price: String,
+}
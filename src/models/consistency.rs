@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+
+/// A difference between an active trade in memory and in the database.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeDiscrepancy {
+    /// the ID of the trade.
+    pub trade_id: ObjectId,
+    /// how the trade differs.
+    pub kind: DiscrepancyKind,
+    /// the fields that differ, if the trade exists in both.
+    pub fields: Vec<String>,
+}
+
+/// The kinds of differences between the in-memory and stored active trades.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum DiscrepancyKind {
+    /// the trade is stored, but not tracked in memory (i.e. its TP/SL/liquidation won't fire).
+    MissingInMemory,
+    /// the trade is tracked in memory, but not stored (i.e. it won't survive a restart).
+    MissingInDatabase,
+    /// the trade exists in both, but some of its fields differ.
+    Mismatched
+}
+
+/// The response data of the consistency endpoints.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    /// the timestamp of when the check was made.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub checked_timestamp: DateTime<Utc>,
+    /// the amount of active trades in memory.
+    pub in_memory: usize,
+    /// the amount of active trades in the database.
+    pub in_database: usize,
+    /// the differences found.
+    pub discrepancies: Vec<TradeDiscrepancy>,
+    /// whether the in-memory state was repaired to match the database.
+    pub repaired: bool,
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// Query parameters accepted by `GET /stats/correlation`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrelationQuery {
+    /// only include this strategy.
+    pub alert_name: Option<String>,
+    /// the trailing window (in days) of each correlation. defaults to `DEFAULT_CORRELATION_WINDOW_DAYS`.
+    pub window: Option<usize>,
+}
+
+/// The realized PnL of a strategy per UTC day, as aggregated from its closed trades.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyDailyPnl {
+    #[serde(rename = "_id")]
+    pub alert_name: String,
+    /// the PnL (in USDT) of each day with closed trades, oldest first.
+    pub days: Vec<DailyPnl>,
+}
+
+/// The realized PnL (in USDT) of a day.
+#[derive(Deserialize, Debug)]
+pub struct DailyPnl {
+    /// the day in `YYYY-MM-DD` format.
+    pub day: String,
+    pub pnl: f64,
+}
+
+/// The correlation of a strategy's daily PnL to the daily returns of BTC over the trailing window ending on `day`.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrelationPoint {
+    pub day: String,
+    /// the Pearson correlation (-1 to 1). `None` if there were too few days since the first trade, or either series didn't vary.
+    pub correlation: Option<f64>,
+}
+
+/// The rolling correlation of a strategy's daily PnL to BTC.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyCorrelation {
+    pub alert_name: String,
+    /// the correlation over the latest window.
+    pub correlation: Option<f64>,
+    /// the correlation over the window ending on each day, oldest first.
+    pub rolling: Vec<CorrelationPoint>,
+}
+
+/// The response data of `GET /stats/correlation`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrelationReport {
+    /// the product whose daily returns the strategies are correlated to.
+    pub benchmark: String,
+    pub window_days: usize,
+    pub strategies: Vec<StrategyCorrelation>,
+}
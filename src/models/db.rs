@@ -1,9 +1,125 @@
+use std::{sync::atomic::AtomicU64, time::Duration};
+
 use mongodb::Collection;
+use serde::Serialize;
 
-use super::{ActiveTrade, ClosedTrade};
+use super::{AccountStatement, ActiveTrade, AlertClaim, ChaosMode, AppliedMigration, AuditLogEntry, ClosedTrade, EquitySnapshot, FieldCipher, FundingPayment, FundingRate, InstanceHeartbeat, LeaderLease, Leaderboard, MaintenanceWindow, PriceAlert, PriceTick, QueuedCommand, RejectedAlert, StateSnapshot, Strategy, SymbolClaim, TradePnlSnapshot, TradeTick, WatchlistEntry};
 
 /// A struct that manages MongoDB collections and provide shared access across the app.
 pub struct MongoDBState {
     pub active_trade_collection: Collection<ActiveTrade>,
     pub closed_trade_collection: Collection<ClosedTrade>,
-}
\ No newline at end of file
+    pub funding_rate_collection: Collection<FundingRate>,
+    pub funding_payment_collection: Collection<FundingPayment>,
+    pub maintenance_window_collection: Collection<MaintenanceWindow>,
+    pub strategy_collection: Collection<Strategy>,
+    pub audit_log_collection: Collection<AuditLogEntry>,
+    pub watchlist_collection: Collection<WatchlistEntry>,
+    pub price_alert_collection: Collection<PriceAlert>,
+    pub rejected_alert_collection: Collection<RejectedAlert>,
+    pub alert_claim_collection: Collection<AlertClaim>,
+    pub command_collection: Collection<QueuedCommand>,
+    pub state_snapshot_collection: Collection<StateSnapshot>,
+    pub leader_lease_collection: Collection<LeaderLease>,
+    pub instance_heartbeat_collection: Collection<InstanceHeartbeat>,
+    pub symbol_claim_collection: Collection<SymbolClaim>,
+    pub migration_collection: Collection<AppliedMigration>,
+    pub price_tick_collection: Collection<PriceTick>,
+    pub equity_snapshot_collection: Collection<EquitySnapshot>,
+    pub trade_tick_collection: Collection<TradeTick>,
+    pub trade_pnl_snapshot_collection: Collection<TradePnlSnapshot>,
+    pub leaderboard_collection: Collection<Leaderboard>,
+    pub account_statement_collection: Collection<AccountStatement>,
+    /// How documents that fail to deserialize are handled when fetching multiple documents.
+    pub deserialization_mode: DeserializationMode,
+    /// Encrypts sensitive fields at rest. `None` if field encryption isn't configured.
+    pub field_cipher: Option<FieldCipher>,
+    /// Which members of the replica set serve the heavy read-only queries (stats, reports, exports and charts).
+    pub stats_read_preference: StatsReadPreference,
+    /// Randomly injects failures to test the recovery paths. Disabled unless `CHAOS_MODE` is `true`.
+    pub chaos: ChaosMode,
+}
+
+/// How documents that fail to deserialize (e.g. old documents missing a required field) are handled when fetching multiple documents
+/// (`DESERIALIZATION_MODE` env variable).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DeserializationMode {
+    /// fails the whole fetch.
+    Strict,
+    /// logs and skips the malformed document, returning the rest. malformed documents can be repaired with a migration.
+    Lenient
+}
+
+/// Which members of the replica set serve the heavy read-only queries, so that they don't compete with trade execution on the primary
+/// (`STATS_READ_PREFERENCE` env variable). Trade reads and writes always use the primary.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StatsReadPreference {
+    /// only the primary.
+    Primary,
+    /// the primary, or a secondary if the primary is unavailable.
+    PrimaryPreferred,
+    /// only secondaries.
+    Secondary,
+    /// a secondary, or the primary if no secondary is available (e.g. on a standalone server).
+    SecondaryPreferred,
+    /// the member with the lowest latency.
+    Nearest
+}
+
+/// Tuning of the MongoDB connection pool, read from `MONGODB_*` env variables. Unset values keep the driver (or connection string) defaults.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MongoPoolConfig {
+    /// the maximum number of connections per server (`MONGODB_MAX_POOL_SIZE`).
+    pub max_pool_size: Option<u32>,
+    /// the number of connections kept open even when idle (`MONGODB_MIN_POOL_SIZE`).
+    pub min_pool_size: Option<u32>,
+    /// the maximum number of connections being established at once (`MONGODB_MAX_CONNECTING`).
+    pub max_connecting: Option<u32>,
+    /// how long establishing a connection may take (`MONGODB_CONNECT_TIMEOUT_MS`).
+    pub connect_timeout: Option<Duration>,
+    /// how long an operation waits for a suitable server before failing (`MONGODB_SERVER_SELECTION_TIMEOUT_MS`).
+    pub server_selection_timeout: Option<Duration>,
+    /// how long a connection may stay idle before it's closed (`MONGODB_MAX_IDLE_TIME_MS`).
+    pub max_idle_time: Option<Duration>,
+    /// whether the driver retries a failed write once (`MONGODB_RETRY_WRITES`).
+    pub retry_writes: Option<bool>,
+}
+
+/// Counters of the MongoDB connection pool, updated from the driver's connection pool events.
+#[derive(Debug, Default)]
+pub struct MongoPoolMetrics {
+    pub connections_created: AtomicU64,
+    pub connections_closed: AtomicU64,
+    pub checkouts: AtomicU64,
+    pub checkins: AtomicU64,
+    pub checkout_failures: AtomicU64,
+    pub pool_clears: AtomicU64,
+}
+
+/// A snapshot of the MongoDB connection pool counters.
+#[derive(Serialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MongoPoolStats {
+    /// the number of currently open connections.
+    pub open_connections: u64,
+    /// the number of connections currently checked out by operations.
+    pub checked_out_connections: u64,
+    /// the total number of connections created since startup.
+    pub connections_created: u64,
+    /// the total number of operations that failed to check out a connection (e.g. timed out waiting for one).
+    pub checkout_failures: u64,
+    /// the total number of times the pool was cleared (e.g. after a network error).
+    pub pool_clears: u64,
+}
+
+/// The response data of `GET /admin/mongo`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MongoHealth {
+    /// whether the database responded to a ping.
+    pub reachable: bool,
+    /// the round trip time of the ping in milliseconds, if it succeeded.
+    pub ping_ms: Option<u64>,
+    /// the counters of the connection pool.
+    pub pool: MongoPoolStats,
+}
@@ -0,0 +1,8 @@
+use ring::{aead::LessSafeKey, rand::SystemRandom};
+
+/// Encrypts sensitive fields (e.g. account identifiers) with AES-256-GCM before they're stored in the database.
+pub struct FieldCipher {
+    pub key: LessSafeKey,
+    /// generates a random nonce for each encrypted value.
+    pub rng: SystemRandom,
+}
@@ -0,0 +1,20 @@
+/// A variable of the env config schema, validated at startup (see `env_schema`).
+pub struct EnvVarSpec {
+    /// the name of the variable.
+    pub name: &'static str,
+    /// whether the server refuses to start without it.
+    pub required: bool,
+    /// what the variable configures, included in error messages.
+    pub description: &'static str,
+    /// checks a value of the variable, returning why it's invalid otherwise.
+    pub validate: fn(&str) -> Result<(), String>,
+}
+
+/// A missing or invalid variable of the env config.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigError {
+    /// the name of the variable.
+    pub variable: String,
+    /// what's wrong with it, and how to fix it.
+    pub message: String,
+}
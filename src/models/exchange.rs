@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{ActiveTrade, FundingRate, FundingSchedule, OrderFill, OrderSide};
+
+/// A venue that trades are priced, and (for live trades) executed on, through `AppState`: prices missing from the price feed are read
+/// from its `price_exchange` (see `AppState::current_price`), funding rates are polled from its `funding_exchange`, and live trades
+/// are placed on its `live_exchange`. Supporting another exchange only takes implementing this interface (see the `exchanges` module).
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    /// the name of the exchange (e.g. binance), matching its `ExchangeProfile` if it has one.
+    fn name(&self) -> &'static str;
+
+    /// Places a market order of `quantity` on `pair` and returns its fill. Reduce-only orders can only shrink a position.
+    async fn place_order(&self, pair: &str, side: OrderSide, quantity: f64, reduce_only: bool) -> Result<OrderFill, String>;
+
+    /// Closes the position of `trade` with a reduce-only market order of its quantity.
+    async fn close_position(&self, trade: &ActiveTrade) -> Result<OrderFill, String> {
+        self.place_order(&trade.pair, OrderSide::closing(&trade.direction), trade.quantity, true).await
+    }
+
+    /// Sets the leverage of `pair` on the account, for the orders placed on it afterwards. Ignored by exchanges without
+    /// per-pair leverage.
+    async fn set_leverage(&self, _pair: &str, _leverage: f64) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returns the size of the open position of the account on `pair` (positive if long, negative if short, zero if flat).
+    async fn get_position(&self, _pair: &str) -> Result<f64, String> {
+        Err(format!("{} doesn't report positions.", self.name()))
+    }
+
+    /// Returns the last traded price of `pair`.
+    async fn get_ticker(&self, pair: &str) -> Result<f64, String>;
+
+    /// Returns the funding rates of `pair` settled after `since` (or the latest ones if `None`), oldest first. Empty if the exchange
+    /// doesn't list the pair as a perpetual.
+    async fn get_funding_rates(&self, pair: &str, since: Option<DateTime<Utc>>) -> Result<Vec<FundingRate>, String>;
+}
+
+/// Reads the prices of Coinbase's spot markets through its REST API. Coinbase is only used for prices (like the price feed),
+/// so placing orders on it isn't supported.
+#[derive(Debug, Clone)]
+pub struct CoinbaseClient {
+    pub client: reqwest::Client,
+    /// the base URL of the API (`COINBASE_EXCHANGE_API_URL`).
+    pub base_url: String,
+}
+
+/// The response of Coinbase's `GET /products/{product_id}/ticker` endpoint.
+#[derive(Debug, Deserialize)]
+pub struct CoinbaseTicker {
+    pub price: String,
+}
+
+/// The fees, funding schedule and margin requirements of an exchange, used to simulate paper trades as if they were placed on it.
+/// 
+/// Each strategy selects the exchange it intends to trade on (see `StrategyParameters::exchange`).
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeProfile {
+    /// the name of the exchange (e.g. binance).
+    pub name: String,
+    /// when funding settles on the exchange.
+    pub funding_schedule: FundingSchedule,
+    /// the fee (in percentage format) for orders adding liquidity.
+    pub maker_fee_percentage: f64,
+    /// the fee (in percentage format) for orders taking liquidity.
+    pub taker_fee_percentage: f64,
+    /// the fee (in percentage of the notional value at the liquidation price) for liquidated positions.
+    pub liquidation_fee_percentage: f64,
+    /// the maintenance margin required depending on the notional value of a position, in ascending order.
+    pub maintenance_margin_tiers: Vec<MaintenanceMarginTier>,
+}
+
+/// The maintenance margin required for positions up to a notional value.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceMarginTier {
+    /// the maximum notional value (in USDT) of positions in this tier. `None` for the last tier.
+    pub max_notional_value: Option<f64>,
+    /// the margin required (in percentage) of the notional value to keep the position open.
+    pub maintenance_margin_percentage: f64,
+}
+
+/// An exchange profile returned by `GET /exchanges`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeProfileInfo {
+    #[serde(flatten)]
+    pub profile: ExchangeProfile,
+    /// the hours (in UTC) at which funding settles.
+    pub funding_hours: Vec<u32>,
+}
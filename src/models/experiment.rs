@@ -0,0 +1,13 @@
+use serde::Serialize;
+
+/// The response data of `DELETE /experiments/{name}`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentReset {
+    /// the name of the experiment that was reset.
+    pub experiment: String,
+    /// the amount of active trades of the experiment that were discarded.
+    pub deleted_active_trades: u64,
+    /// the amount of closed trades of the experiment that were deleted.
+    pub deleted_closed_trades: u64,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use super::{DateFormat, DecimalSeparator};
+
+/// The formats closed trades can be exported in.
+#[derive(Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// one row per trade, with a header row.
+    #[default]
+    Csv,
+    /// one JSON document per line.
+    Ndjson
+}
+
+/// Query parameters accepted by `GET /trade/closed/export` and `GET /reports/equity`.
+#[derive(Deserialize, Debug, Default)]
+pub struct ClosedTradeExportQuery {
+    /// the format of the export. defaults to CSV. ignored by the equity report, which is always CSV.
+    pub format: Option<ExportFormat>,
+    /// only include trades triggered by this alert name (i.e. a single strategy).
+    pub alert_name: Option<String>,
+    /// only include trades closed at or after this time (RFC 3339).
+    pub from: Option<DateTime<Utc>>,
+    /// only include trades closed before this time (RFC 3339).
+    pub to: Option<DateTime<Utc>>,
+    /// the decimal separator of CSV numbers (`point` or `comma`). defaults to `REPORT_DECIMAL_SEPARATOR`.
+    pub decimal_separator: Option<DecimalSeparator>,
+    /// the format of CSV timestamps (`rfc3339`, `iso`, `european` or `us`). defaults to `REPORT_DATE_FORMAT`.
+    pub date_format: Option<DateFormat>,
+    /// the timezone CSV timestamps are displayed in (e.g. `Europe/Berlin`). defaults to `REPORT_TIMEZONE`.
+    pub timezone: Option<Tz>,
+}
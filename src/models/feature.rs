@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+/// Gates risky subsystems, which are all off unless enabled in the config (`FEATURE_*` env variables).
+#[derive(Serialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlags {
+    /// whether trades may be placed on exchanges with real money (`FEATURE_LIVE_TRADING`). Live trades are placed on Binance and
+    /// require the `BINANCE_API_KEY` and `BINANCE_API_SECRET` credentials, otherwise the server refuses to start.
+    pub live_trading: bool,
+    /// whether large cross margin trades are liquidated in steps rather than at once (`FEATURE_AUTO_LIQUIDATION`).
+    pub auto_liquidation: bool,
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why a tick of the price feed was discarded before any triggers were evaluated against it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickIssue {
+    /// the tick had no price, or a price that isn't a positive number.
+    InvalidPrice,
+    /// the sequence number of the tick wasn't greater than the last one of its product (a late or duplicate tick).
+    OutOfOrder,
+    /// the price moved more than the spike threshold away from the last accepted price.
+    Spike,
+    /// the price deviated too far from the prices of the other venues (see `PriceConsensus`).
+    ConsensusOutlier,
+}
+
+/// The data quality of the price feed of a product since the bot started.
+#[derive(Debug, Default, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductFeedQuality {
+    /// the number of ticks received.
+    pub ticks: u64,
+    /// the number of ticks discarded for any reason.
+    pub discarded: u64,
+    /// the number of ticks discarded for an invalid price.
+    pub invalid_prices: u64,
+    /// the number of ticks discarded for an out-of-order or duplicate sequence number.
+    pub out_of_order: u64,
+    /// the number of ticks discarded as price spikes.
+    pub spikes: u64,
+    /// the number of ticks discarded for deviating from the other venues.
+    pub consensus_outliers: u64,
+    /// the number of times no tick was received for longer than `FEED_GAP_SECS`.
+    pub gaps: u64,
+    /// the longest time (in seconds) between two ticks.
+    pub max_gap_secs: i64,
+    pub last_sequence: Option<u64>,
+    /// the last price that wasn't discarded.
+    pub last_price: Option<f64>,
+    pub last_tick_timestamp: Option<DateTime<Utc>>,
+    /// the ID of the last trade of the product reported by a tick.
+    pub last_trade_id: Option<u64>,
+    /// the number of trades skipped by the trade IDs of the ticks (either batched into a single tick or dropped by the feed).
+    pub missed_trades: u64,
+    /// the number of missed trades fetched from the REST API afterwards.
+    pub backfilled_trades: u64,
+    /// the number of spikes discarded in a row, after which the new price level is accepted.
+    #[serde(skip)]
+    pub consecutive_spikes: u32,
+}
+
+/// Tracks the data quality of the price feed of each product and discards obviously bad ticks.
+#[derive(Debug, Default)]
+pub struct FeedQualityMonitor {
+    /// the price change (in percent of the last accepted price) above which a tick is considered a spike (`FEED_SPIKE_PERCENTAGE` env variable).
+    pub spike_percentage: f64,
+    /// the data quality of each product (e.g. `BTC-USD`).
+    pub products: Mutex<HashMap<String, ProductFeedQuality>>,
+}
+
+/// The data quality of the price feed of a product, as returned by `GET /admin/feed_quality`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedQualityReport {
+    pub product_id: String,
+    /// the share of ticks (0 to 100) that were neither discarded nor preceded by a gap.
+    pub score: f64,
+    #[serde(flatten)]
+    pub quality: ProductFeedQuality,
+}
+
+/// A range of trades of a product that the price feed didn't deliver, detected from a jump in the trade IDs of its ticks.
+///
+/// The sequence numbers of the ticker channel aren't contiguous (they're shared with the other channels of the product), but its trade IDs are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeGap {
+    pub product_id: String,
+    /// the ID of the last trade received before the gap.
+    pub after_trade_id: u64,
+    /// the ID of the first trade received after the gap.
+    pub before_trade_id: u64,
+}
+
+/// A trade returned by Coinbase's `GET /products/{product_id}/trades`.
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct CoinbaseTrade {
+    pub trade_id: u64,
+    pub price: String,  // "96289.34"
+    pub size: Option<String>,
+    pub side: Option<String>,
+    pub time: DateTime<Utc>,
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::{TradeDirection, TradeKind};
+
+/// A settled funding rate of a perpetual contract, polled from the exchange and stored for historical lookups.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    /// the pair that the funding rate applies to (e.g. BTCUSDT).
+    pub pair: String,
+    /// the timestamp of the funding settlement.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub funding_time: DateTime<Utc>,
+    /// the funding rate (in ratio format, e.g. 0.0001 = 0.01%).
+    /// 
+    /// positive rates are paid by longs to shorts. negative rates are paid by shorts to longs.
+    pub rate: f64,
+    /// the mark price of the pair at the time of the funding settlement, if provided by the exchange.
+    pub mark_price: Option<f64>,
+}
+
+/// When funding settles on an exchange: every `interval_hours` hours, aligned to midnight UTC
+/// (e.g. at 00:00, 08:00 and 16:00 UTC for an 8 hour interval).
+///
+/// Funding times are computed in UTC, so they're unaffected by daylight saving time.
+#[derive(Debug, Serialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingSchedule {
+    /// the hours between two funding settlements. always a divisor of 24 (e.g. 1, 4 or 8).
+    pub interval_hours: u32,
+}
+
+/// A single entry returned by Binance's `GET /fapi/v1/fundingRate` endpoint.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceFundingRate {
+    pub symbol: String,
+    /// the funding settlement timestamp (in milliseconds).
+    pub funding_time: i64,
+    pub funding_rate: String,
+    /// empty for some older settlements.
+    pub mark_price: Option<String>,
+}
+
+/// Query parameters accepted by `GET /funding/{pair}`.
+#[derive(Deserialize, Debug)]
+pub struct FundingQuery {
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// The funding accrued by an open trade since it was opened.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenTradeFunding {
+    /// the unique database ID of the trade.
+    pub trade_id: ObjectId,
+    /// the alert name that triggered the trade.
+    pub alert_name: String,
+    /// the direction of the trade (long or short).
+    pub direction: TradeDirection,
+    /// the amount of funding settlements since the trade was opened.
+    pub settlements: usize,
+    /// the cumulative funding of the trade (in the settlement currency).
+    /// 
+    /// positive values are funding paid, negative values are funding received.
+    pub cumulative_funding: f64,
+}
+
+/// The response data of `GET /funding/{pair}`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingHistory {
+    /// the pair that the funding rates apply to.
+    pub pair: String,
+    /// the stored funding rates of the pair, newest first.
+    pub rates: Vec<FundingRate>,
+    /// the funding accrued by each open trade on the pair.
+    pub open_trades: Vec<OpenTradeFunding>,
+}
+
+
+/// A single funding payment of a trade, recorded in the funding ledger when the trade is closed, so that the `fundingFees`
+/// of the closed trade can be audited settlement by settlement (the amounts of its payments add up to them).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingPayment {
+    /// the unique database ID of the payment.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// the unique database ID of the trade that the payment belongs to.
+    pub trade_id: ObjectId,
+    /// the alert name that triggered the trade.
+    pub alert_name: String,
+    /// the pair of the trade (e.g. BTCUSDT).
+    pub pair: String,
+    /// the exchange account that the payment was settled on.
+    pub exchange: String,
+    /// whether the payment was simulated (paper trades) or real (live trades).
+    pub kind: TradeKind,
+    /// the direction of the trade (long or short).
+    pub direction: TradeDirection,
+    /// the timestamp of the funding settlement.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub funding_time: DateTime<Utc>,
+    /// the funding rate of the settlement (in ratio format, e.g. 0.0001 = 0.01%).
+    pub rate: f64,
+    /// the notional value of the trade that the rate was applied to.
+    pub notional_value: f64,
+    /// the amount of the payment (in the settlement currency).
+    ///
+    /// positive values are funding paid, negative values are funding received.
+    pub amount: f64,
+    /// the currency that the payment was settled in (e.g. USDT, or BTC for inverse contracts).
+    pub settlement_currency: String,
+}
+
+/// Query parameters accepted by `GET /funding/ledger`.
+#[derive(Deserialize, Debug, Default)]
+pub struct FundingLedgerQuery {
+    /// only include the payments of this trade.
+    pub trade_id: Option<String>,
+    /// only include the payments settled on this exchange account (e.g. binance).
+    pub exchange: Option<String>,
+    /// only include the payments of this pair.
+    pub pair: Option<String>,
+    /// only include payments settled at or after this time (RFC 3339).
+    pub from: Option<DateTime<Utc>>,
+    /// only include payments settled before this time (RFC 3339).
+    pub to: Option<DateTime<Utc>>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// The response data of `GET /funding/ledger`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingLedger {
+    /// the matching payments, newest first.
+    pub payments: Vec<FundingPayment>,
+    /// the sum of the amounts of `payments` (mixing settlement currencies if the payments do).
+    pub total_amount: f64,
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// The currencies that stats and reports can be denominated in.
+///
+/// All trade values are recorded in USDT, and converted into the reporting currency using the `FxRates` stored on each closed trade.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ReportingCurrency {
+    Usdt,
+    Usd,
+    Eur,
+    Btc
+}
+
+/// The value of 1 USDT in each supported reporting currency, snapshotted from the price feed when a trade is closed.
+///
+/// A rate is `None` if the price feed hadn't received the required ticker(s) yet.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct FxRates {
+    /// the value of 1 USDT in USD.
+    pub usd: Option<f64>,
+    /// the value of 1 USDT in EUR.
+    pub eur: Option<f64>,
+    /// the value of 1 USDT in BTC.
+    pub btc: Option<f64>,
+}
+
+/// Describes how the monetary values of closed trades are converted within a stats aggregation.
+#[derive(Debug, Clone, Copy)]
+pub struct CurrencyConversion {
+    /// the currency to denominate the values in.
+    pub currency: ReportingCurrency,
+    /// the current rate of 1 USDT in `currency`, used for trades closed without a stored rate.
+    pub fallback_rate: f64,
+}
@@ -0,0 +1,9 @@
+use std::sync::Arc;
+
+use super::AppState;
+
+/// The gRPC control-plane service (see `proto/control.proto`), exposing the management operations to typed clients.
+pub struct ControlPlaneService {
+    /// the state of the bot that the operations act on.
+    pub app_state: Arc<AppState>,
+}
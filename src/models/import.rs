@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// The trade history CSV exports that can be imported.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeHistoryFormat {
+    /// Binance USDⓈ-M futures "Trade History" export (one row per fill). Requires the `Date(UTC)`, `Symbol`, `Side`, `Price`,
+    /// `Quantity`, `Fee` and `Realized Profit` columns.
+    Binance,
+    /// Bybit derivatives "Closed P&L" export (one row per closed position). Requires the `Contracts`, `Closing Direction`, `Qty`,
+    /// `Entry Price`, `Exit Price`, `Closed P&L` and `Trade Time(UTC+0)` columns.
+    Bybit
+}
+
+/// Query parameters accepted by `POST /trade/import`.
+#[derive(Deserialize, Debug)]
+pub struct ImportQuery {
+    /// the exchange the CSV was exported from.
+    pub exchange: TradeHistoryFormat,
+    /// the alert name to file the imported trades under. defaults to `IMPORTED_ALERT_NAME`.
+    pub alert_name: Option<String>,
+}
+
+/// The response data of `POST /trade/import`.
+#[derive(Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    /// the amount of trades imported.
+    pub imported: usize,
+    /// the amount of trades skipped because they were already imported.
+    pub duplicates: usize,
+    /// the amount of rows that don't close a position (e.g. opening fills), which are skipped.
+    pub skipped: usize,
+    /// the rows that couldn't be imported, with the reason.
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicBool, AtomicI64};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The lease held by the leader instance when running multiple instances for redundancy.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderLease {
+    /// the ID of the lease (always `LEADER_LEASE_ID`).
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// the ID of the instance holding the lease.
+    pub holder: String,
+    /// the timestamp after which the lease can be taken over by another instance.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub expires_at: DateTime<Utc>,
+    /// incremented whenever the lease changes hands. a leader only executes while the stored token still matches its own,
+    /// so that a leader that lost its lease (e.g. after a long pause) can't execute alongside the new one.
+    pub fencing_token: i64,
+}
+
+/// The leadership state of this instance.
+#[derive(Debug)]
+pub struct Leadership {
+    /// whether leader election is enabled (`HA_MODE` env variable). if disabled, this instance always leads.
+    pub enabled: bool,
+    /// whether this instance currently holds the leader lease.
+    pub is_leader: AtomicBool,
+    /// the fencing token of the lease held by this instance.
+    pub fencing_token: AtomicI64,
+}
+
+/// The response data of `GET /leader`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderStatus {
+    pub instance_id: String,
+    pub enabled: bool,
+    pub is_leader: bool,
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The windows that strategies are ranked over.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum LeaderboardWindow {
+    #[serde(rename = "7d")]
+    Week,
+    #[default]
+    #[serde(rename = "30d")]
+    Month,
+    #[serde(rename = "90d")]
+    Quarter,
+    #[serde(rename = "all")]
+    AllTime
+}
+
+/// The metrics that strategies can be ranked by.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardMetric {
+    /// the sum of the ROE of all trades.
+    #[default]
+    Roe,
+    /// the total PnL relative to the maximum drawdown.
+    RiskAdjustedReturn,
+    /// the share of trading days closed with a profit.
+    Consistency
+}
+
+/// The performance of a strategy within a leaderboard window.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    /// the position of the strategy when ranked by the requested metric, starting from 1.
+    #[serde(default)]
+    pub rank: usize,
+    /// the alert name of the strategy.
+    pub alert_name: String,
+    /// the amount of trades closed within the window.
+    pub trades: usize,
+    /// the total PnL (in USDT).
+    pub total_pnl: f64,
+    /// the sum of the ROE (in percentage format) of all trades.
+    pub total_roe: f64,
+    /// the largest peak-to-trough decline of the cumulative PnL (in USDT).
+    pub max_drawdown: f64,
+    /// the total PnL divided by the maximum drawdown. `None` if the strategy never drew down.
+    pub return_over_drawdown: Option<f64>,
+    /// the percentage of trading days (in the reporting timezone) closed with a profit.
+    pub profitable_days_percentage: f64,
+}
+
+/// The precomputed leaderboard of a window, stored by the leaderboard aggregator.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaderboard {
+    #[serde(rename = "_id")]
+    pub window: LeaderboardWindow,
+    /// the timestamp of when the leaderboard was computed.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub computed_timestamp: DateTime<Utc>,
+    /// the ranked strategies.
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// The closed trades of a strategy within a window, oldest first, as returned by the leaderboard aggregation.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyTradeSequence {
+    #[serde(rename = "_id")]
+    pub alert_name: String,
+    /// the sum of the ROE of all trades.
+    pub total_roe: f64,
+    pub trades: Vec<ClosedPnl>,
+}
+
+/// The realized PnL of a closed trade.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedPnl {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub close_timestamp: DateTime<Utc>,
+    pub pnl: f64,
+}
+
+/// Query parameters accepted by `GET /leaderboard`.
+#[derive(Deserialize, Debug, Default)]
+pub struct LeaderboardQuery {
+    /// the window to rank the strategies over (`7d`, `30d`, `90d` or `all`). defaults to `30d`.
+    pub window: Option<LeaderboardWindow>,
+    /// the metric to rank the strategies by. defaults to `roe`.
+    pub sort_by: Option<LeaderboardMetric>,
+    /// the maximum amount of strategies returned. defaults to `DEFAULT_LEADERBOARD_LIMIT`.
+    pub limit: Option<usize>,
+}
+
+/// The response data of `GET /leaderboard`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedLeaderboard {
+    pub window: LeaderboardWindow,
+    pub sort_by: LeaderboardMetric,
+    /// the timestamp of when the underlying leaderboard was computed.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub computed_timestamp: DateTime<Utc>,
+    pub entries: Vec<LeaderboardEntry>,
+}
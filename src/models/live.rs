@@ -0,0 +1,156 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+
+/// Places signed orders on Binance's USDⓈ-M futures REST API for live trades (`BINANCE_API_KEY` and `BINANCE_API_SECRET` env variables).
+#[derive(Clone)]
+pub struct BinanceClient {
+    pub client: reqwest::Client,
+    /// the base URL of the API (`BINANCE_FUTURES_API_URL` env variable, e.g. the testnet), defaulting to the production API.
+    pub base_url: String,
+    pub api_key: String,
+    /// the secret that requests are signed with (HMAC-SHA256). never logged.
+    pub api_secret: String,
+    /// the order size rules of each symbol, fetched once from the exchange info.
+    pub symbol_filters: Arc<Mutex<HashMap<String, SymbolFilters>>>,
+}
+
+/// The rules that the quantity of a market order on a symbol has to follow (the `MARKET_LOT_SIZE`, `LOT_SIZE` and `MIN_NOTIONAL` filters).
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SymbolFilters {
+    /// the increment that quantities are rounded down to (e.g. 0.001 BTC).
+    pub step_size: f64,
+    /// the number of decimals of `step_size`, which quantities are formatted with.
+    pub precision: usize,
+    /// the smallest quantity that can be ordered.
+    pub min_quantity: f64,
+    /// the smallest notional value (in USDT) of an order that isn't reduce-only.
+    pub min_notional: f64,
+}
+
+/// The side of an order.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// The fill of a market order, as reported by the exchange.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OrderFill {
+    /// the ID of the order on the exchange.
+    pub order_id: i64,
+    /// the average price that the order was filled at.
+    pub price: f64,
+    /// the filled quantity of the base currency.
+    pub quantity: f64,
+    /// the fees charged for the order (in the settlement currency, e.g. USDT).
+    pub fees: f64,
+}
+
+/// The response of Binance's `POST /fapi/v1/order` endpoint (with `newOrderRespType=RESULT`).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceOrderResponse {
+    pub order_id: i64,
+    pub status: String,
+    pub avg_price: String,
+    pub executed_qty: String,
+}
+
+/// The response of Binance's `GET /fapi/v1/exchangeInfo` endpoint.
+#[derive(Debug, Deserialize)]
+pub struct BinanceExchangeInfo {
+    pub symbols: Vec<BinanceSymbolInfo>,
+}
+
+/// A symbol of Binance's exchange info, with its filters.
+#[derive(Debug, Deserialize)]
+pub struct BinanceSymbolInfo {
+    pub symbol: String,
+    pub filters: Vec<BinanceSymbolFilter>,
+}
+
+/// A filter of a symbol of Binance's exchange info. Only the filters that orders are sized by are parsed.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType")]
+pub enum BinanceSymbolFilter {
+    #[serde(rename = "LOT_SIZE")]
+    LotSize {
+        #[serde(rename = "stepSize")]
+        step_size: String,
+        #[serde(rename = "minQty")]
+        min_qty: String,
+    },
+    #[serde(rename = "MARKET_LOT_SIZE")]
+    MarketLotSize {
+        #[serde(rename = "stepSize")]
+        step_size: String,
+        #[serde(rename = "minQty")]
+        min_qty: String,
+    },
+    #[serde(rename = "MIN_NOTIONAL")]
+    MinNotional {
+        notional: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// A single entry returned by Binance's `GET /fapi/v1/userTrades` endpoint.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceUserTrade {
+    pub commission: String,
+    pub commission_asset: String,
+}
+
+/// The response of Binance's `GET /fapi/v1/ticker/price` endpoint.
+#[derive(Debug, Deserialize)]
+pub struct BinanceTicker {
+    pub price: String,
+}
+
+/// A single entry returned by Binance's `GET /fapi/v2/positionRisk` endpoint.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinancePositionRisk {
+    pub position_amt: String,
+}
+
+/// The body of an error returned by Binance (e.g. `{"code": -2019, "msg": "Margin is insufficient."}`).
+#[derive(Debug, Deserialize)]
+pub struct BinanceApiError {
+    pub code: i64,
+    pub msg: String,
+}
+
+/// The live trades that the price feed is closing in the background, so that each is only closed by one task at a time,
+/// and closes rejected by the exchange are retried after a backoff rather than on every tick.
+#[derive(Debug, Default)]
+pub struct LiveCloseTracker {
+    pub attempts: Mutex<HashMap<ObjectId, LiveCloseAttempt>>,
+}
+
+/// The state of closing a live trade by its exit triggers.
+#[derive(Debug, Clone)]
+pub struct LiveCloseAttempt {
+    /// whether a close order of the trade is being placed.
+    pub in_flight: bool,
+    /// how many closes in a row failed.
+    pub failures: u32,
+    /// when the close can be retried after the last failure.
+    pub retry_at: DateTime<Utc>,
+}
+
+/// Why a live trade couldn't be opened.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OpenLiveTradeError {
+    /// the exchange rejected the order, so nothing was opened.
+    Rejected(String),
+    /// the order was filled but the trade couldn't be recorded, so the position was flattened again. since an order was placed,
+    /// the alert must not be retried.
+    NotRecorded(String),
+}
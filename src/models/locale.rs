@@ -0,0 +1,50 @@
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+/// The character separating the integer and fractional parts of numbers in reports.
+#[derive(Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DecimalSeparator {
+    /// `1234.56`. CSV fields are separated by commas.
+    #[default]
+    Point,
+    /// `1234,56`. CSV fields are separated by semicolons, as spreadsheets using a decimal comma expect.
+    Comma
+}
+
+/// How timestamps are formatted in reports.
+#[derive(Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    /// `2024-12-31T18:30:00+01:00`.
+    #[default]
+    Rfc3339,
+    /// `2024-12-31 18:30:00`.
+    Iso,
+    /// `31.12.2024 18:30:00`.
+    European,
+    /// `12/31/2024 06:30:00 PM`.
+    Us
+}
+
+/// How numbers and timestamps are formatted in the generated CSV and HTML reports.
+///
+/// Defaults to the `REPORT_DECIMAL_SEPARATOR`, `REPORT_DATE_FORMAT` and `REPORT_TIMEZONE` env variables, each of which can be
+/// overridden per export.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ReportLocale {
+    pub decimal_separator: DecimalSeparator,
+    pub date_format: DateFormat,
+    /// the timezone timestamps are displayed in.
+    pub timezone: Tz,
+}
+
+impl Default for ReportLocale {
+    fn default() -> Self {
+        Self {
+            decimal_separator: DecimalSeparator::default(),
+            date_format: DateFormat::default(),
+            timezone: Tz::UTC,
+        }
+    }
+}
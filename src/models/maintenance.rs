@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// A period during which an exchange is (or will be) under maintenance and rejects orders.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceWindow {
+    /// the unique database ID of the maintenance window.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// the exchange that is under maintenance (e.g. binance).
+    pub exchange: String,
+    /// the timestamp of when the maintenance starts.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub start: DateTime<Utc>,
+    /// the timestamp of when the maintenance ends.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub end: DateTime<Utc>,
+    /// an optional description of the maintenance.
+    pub reason: Option<String>,
+    /// how the maintenance window was registered.
+    pub source: MaintenanceSource,
+}
+
+/// Used to determine how a maintenance window was registered.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum MaintenanceSource {
+    /// added manually via `POST /maintenance`.
+    Manual,
+    /// detected by polling the exchange's system status endpoint.
+    StatusEndpoint
+}
+
+/// The request body of `POST /maintenance`.
+#[derive(Deserialize, Debug)]
+pub struct NewMaintenanceWindow {
+    /// the exchange that will be under maintenance (e.g. binance).
+    pub exchange: String,
+    /// when the maintenance starts (RFC 3339, e.g. `2025-01-01T06:00:00Z`).
+    pub start: DateTime<Utc>,
+    /// when the maintenance ends (RFC 3339).
+    pub end: DateTime<Utc>,
+    /// an optional description of the maintenance.
+    pub reason: Option<String>,
+}
+
+/// The response of Binance's `GET /sapi/v1/system/status` endpoint.
+#[derive(Deserialize, Debug)]
+pub struct BinanceSystemStatus {
+    /// 0 = normal, 1 = system maintenance.
+    pub status: u8,
+    pub msg: String,
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use super::MongoDBState;
+
+/// A record of a migration that was applied to the database.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    /// the version of the migration.
+    #[serde(rename = "_id")]
+    pub version: u32,
+    /// the name of the migration.
+    pub name: String,
+    /// the timestamp of when the migration was applied.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub applied_timestamp: DateTime<Utc>,
+}
+
+/// A change to the stored documents (e.g. backfilling or renaming a field), applied once at startup.
+///
+/// Migrations must be idempotent, since replicas starting at the same time may both apply a pending migration.
+pub struct Migration {
+    /// the version of the migration. migrations are applied in ascending order, and a version must never be reused.
+    pub version: u32,
+    /// a short description of the migration.
+    pub name: &'static str,
+    pub run: for<'a> fn(&'a MongoDBState) -> BoxFuture<'a, Result<(), mongodb::error::Error>>,
+}
@@ -1,12 +1,122 @@
 pub mod tradingview;
+pub mod experiment;
+pub mod export;
+pub mod strategy;
+pub mod audit;
+pub mod watchlist;
+pub mod notification;
+pub mod outcome;
+pub mod pnl_snapshot;
+pub mod price_alert;
+pub mod readiness;
+pub mod anomaly;
+pub mod rejected_alert;
+pub mod alert_claim;
+pub mod chaos;
+pub mod clock;
+pub mod command;
+pub mod consistency;
+pub mod encryption;
+pub mod env;
+pub mod exchange;
+pub mod feature;
+pub mod report;
+pub mod snapshot;
+pub mod leader;
+pub mod locale;
+pub mod shard;
+pub mod fx;
+pub mod funding;
+pub mod import;
+pub mod maintenance;
+pub mod migration;
 pub mod trade;
+pub mod trade_tick;
 pub mod api;
 pub mod db;
 pub mod websocket;
 pub mod state;
+pub mod stats;
+pub mod timeseries;
+pub mod version;
+pub mod script;
+pub mod plugin;
+pub mod grpc;
+pub mod mqtt;
+pub mod leaderboard;
+pub mod seed;
+pub mod risk;
+pub mod regime;
+pub mod correlation;
+pub mod trade_replay;
+pub mod feed_quality;
+pub mod consensus;
+pub mod cache;
+pub mod calendar;
+pub mod rounding;
+pub mod position;
+pub mod conflict;
+pub mod statement;
+pub mod live;
+pub mod network;
 
 pub use trade::*;
+pub use trade_tick::*;
+pub use experiment::*;
+pub use export::*;
+pub use strategy::*;
+pub use audit::*;
+pub use watchlist::*;
+pub use notification::*;
+pub use outcome::*;
+pub use pnl_snapshot::*;
+pub use price_alert::*;
+pub use readiness::*;
+pub use anomaly::*;
+pub use rejected_alert::*;
+pub use alert_claim::*;
+pub use chaos::*;
+pub use clock::*;
+pub use command::*;
+pub use consistency::*;
+pub use encryption::*;
+pub use env::*;
+pub use exchange::*;
+pub use feature::*;
+pub use report::*;
+pub use snapshot::*;
+pub use leader::*;
+pub use locale::*;
+pub use shard::*;
+pub use fx::*;
+pub use funding::*;
+pub use import::*;
+pub use maintenance::*;
+pub use migration::*;
 pub use api::*;
 pub use db::*;
 pub use websocket::*;
-pub use state::*;
\ No newline at end of file
+pub use state::*;
+pub use stats::*;
+pub use timeseries::*;
+pub use version::*;
+pub use script::*;
+pub use plugin::*;
+pub use grpc::*;
+pub use mqtt::*;
+pub use leaderboard::*;
+pub use seed::*;
+pub use risk::*;
+pub use regime::*;
+pub use correlation::*;
+pub use trade_replay::*;
+pub use feed_quality::*;
+pub use consensus::*;
+pub use cache::*;
+pub use calendar::*;
+pub use rounding::*;
+pub use position::*;
+pub use conflict::*;
+pub use statement::*;
+pub use live::*;
+pub use network::*;
\ No newline at end of file
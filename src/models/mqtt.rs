@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// The lifecycle events of a trade that are published to MQTT.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeEventKind {
+    Opened,
+    Closed,
+    /// part of the trade was liquidated.
+    PartiallyLiquidated
+}
+
+/// The payload of a trade lifecycle event, published to `<prefix>/trades/<alert name>/<event>`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeEvent<'a, T: Serialize> {
+    pub event: TradeEventKind,
+    /// the active or closed trade, as returned by the API.
+    pub trade: &'a T,
+}
+
+/// The payload of a ticker, published (retained) to `<prefix>/tickers/<product ID>`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TickerEvent<'a> {
+    /// the product ID of the ticker (e.g. `SOL-USD`).
+    pub product_id: &'a str,
+    pub price: f64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The connection and topics of the MQTT sink, configured via the `MQTT_*` env variables.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    /// the username and password to authenticate with, if any.
+    pub credentials: Option<(String, String)>,
+    /// the prefix of all topics (e.g. `tv-trading-bot`).
+    pub topic_prefix: String,
+    /// the products whose tickers are published (e.g. `SOL-USD`).
+    pub tickers: HashSet<String>,
+}
+
+/// Publishes trade lifecycle events and tickers to an MQTT broker, so that dashboards can subscribe to them without polling.
+#[derive(Debug, Clone, Default)]
+pub struct MqttPublisher {
+    /// the client connected to the broker. `None` if the MQTT sink is disabled.
+    pub client: Option<rumqttc::AsyncClient>,
+    /// the prefix of all topics.
+    pub topic_prefix: String,
+    /// the products whose tickers are published.
+    pub tickers: HashSet<String>,
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// An event that is delivered to the operator through the configured notification sinks.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    /// how urgent the event is.
+    pub severity: NotificationSeverity,
+    /// a short summary of the event (e.g. `Price alert triggered`).
+    pub title: String,
+    /// the details of the event.
+    pub message: String,
+    /// the timestamp of when the event occurred.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Used to determine how urgent a notification is.
+#[derive(Serialize, Debug, PartialEq, PartialOrd, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationSeverity {
+    Info,
+    Warning,
+    Critical
+}
+
+/// A destination that notifications are delivered to.
+#[derive(Debug, Clone)]
+pub enum NotificationSink {
+    /// POSTs a JSON payload to a URL. The payload includes both a `content` (Discord) and `text` (Slack) field.
+    Webhook { url: String },
+    /// sends a push notification via Pushover to a user (or group) of an application.
+    Pushover { app_token: String, user_key: String },
+    /// publishes a push notification to an ntfy topic (e.g. `https://ntfy.sh/my-topic`), optionally authenticated with an access token.
+    Ntfy { topic_url: String, access_token: Option<String> },
+}
+
+/// A configured sink alongside the minimum severity of the notifications delivered to it.
+#[derive(Debug, Clone)]
+pub struct NotificationTarget {
+    pub sink: NotificationSink,
+    /// notifications less severe than this aren't delivered to the sink.
+    pub min_severity: NotificationSeverity,
+}
+
+/// Dispatches notifications to all configured sinks.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    pub client: reqwest::Client,
+    pub targets: Vec<NotificationTarget>,
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{ActiveTrade, ResponseCode};
+
+/// The outcome of processing an alert, sent to the callback URL of its strategy (e.g. for an external alert journal).
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertOutcomeEvent {
+    /// the ID of the request that delivered the alert.
+    pub request_id: String,
+    /// the alert name of the strategy.
+    pub alert_name: String,
+    /// the pair of the alert.
+    pub pair: String,
+    /// what the bot did with the alert.
+    pub outcome: AlertOutcomeKind,
+    /// the machine-readable code of the response.
+    pub code: Option<ResponseCode>,
+    /// the HTTP status code of the response.
+    pub status_code: u16,
+    /// the human-readable message of the response.
+    pub message: String,
+    /// a snapshot of the strategy's open trade on the pair after processing the alert, if any.
+    pub trade: Option<ActiveTrade>,
+    /// the timestamp of when the alert was processed.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A coarse summary of what the bot did with an alert.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertOutcomeKind {
+    /// a new trade was opened.
+    Opened,
+    /// the existing trade was closed and a new one opened in the opposite direction.
+    Flipped,
+    /// a trade in the same direction was already open.
+    Ignored,
+    /// the alert was rejected (e.g. the strategy is disabled or an anomaly was detected).
+    Rejected,
+    /// the alert couldn't be executed due to an internal error.
+    Failed
+}
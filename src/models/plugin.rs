@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use super::{tradingview::TradingViewAlert, ActiveTrade, FilterDecision, StrategyParameters};
+
+/// A plugin inspecting each alert before it's executed, e.g. to reject alerts or to apply custom sizing.
+///
+/// Alert filters run in the order they were registered in, after the filter script of the strategy.
+pub trait AlertFilter: Send + Sync {
+    /// the name of the filter, used in logs and rejection messages.
+    fn name(&self) -> &'static str;
+
+    /// Decides whether (and how) `alert` is executed, given the trading parameters of its strategy.
+    fn filter(&self, alert: &TradingViewAlert, parameters: &StrategyParameters) -> FilterDecision;
+}
+
+/// A plugin closing active trades on conditions other than their take profit, stop loss or liquidation price.
+pub trait ExitRule: Send + Sync {
+    /// the name of the rule, used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Whether `trade` should be closed at `price`.
+    fn should_exit(&self, trade: &ActiveTrade, price: f64, now: DateTime<Utc>) -> bool;
+}
+
+/// The alert filters and exit rules registered with the bot (see `registered_plugins`).
+#[derive(Default, Clone)]
+pub struct PluginRegistry {
+    /// the alert filters, in the order they run in.
+    pub alert_filters: Vec<Arc<dyn AlertFilter>>,
+    /// the exit rules, in the order they are checked in.
+    pub exit_rules: Vec<Arc<dyn ExitRule>>,
+}
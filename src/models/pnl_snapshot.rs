@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// The unrealized PnL of an active trade at a point in time, used to show how a position evolved before it closed.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TradePnlSnapshot {
+    /// the ID of the trade.
+    pub trade_id: ObjectId,
+    /// the timestamp of the snapshot.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// the latest price of the trade's pair at `timestamp`.
+    pub price: f64,
+    /// the PnL (in the settlement currency, excluding fees) that the trade would realize if it were closed at `price`.
+    pub unrealized_pnl: f64,
+    /// the ROE (in percent) corresponding to `unrealized_pnl`.
+    pub roe: f64,
+}
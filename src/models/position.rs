@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use super::{ContractType, TradeDirection};
+
+/// The active trades of all strategies on the same pair, contract type and exchange netted into a single position, which is what
+/// the exchange account actually holds when the strategies trade live. Returned by `GET /positions/net`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetPosition {
+    /// the exchange the trades are (simulated to be) placed on.
+    pub exchange: String,
+    pub pair: String,
+    pub contract_type: ContractType,
+    /// the direction of the net position. `None` if the long and short trades cancel each other out.
+    pub direction: Option<TradeDirection>,
+    /// the size of the net position (the long minus the short quantity, in absolute terms).
+    pub quantity: f64,
+    /// the sum of the quantities of the long trades.
+    pub long_quantity: f64,
+    /// the sum of the quantities of the short trades.
+    pub short_quantity: f64,
+    /// the average entry price of the net position, as the exchange would book it when the trades were executed in the order they were opened.
+    /// `None` if the position is flat.
+    pub average_entry_price: Option<f64>,
+    /// the margin posted by all netted trades (in the settlement currency), which backs the net position like cross margin.
+    pub margin: f64,
+    /// the notional value of the net position at its average entry price divided by its margin.
+    pub effective_leverage: Option<f64>,
+    /// the price at which the net position would be liquidated. `None` if it's flat or can't be liquidated.
+    pub liquidation_price: Option<f64>,
+    /// the latest price of the pair.
+    pub mark_price: Option<f64>,
+    /// the unrealized PnL of the net position at the mark price (in the settlement currency).
+    pub unrealized_pnl: Option<f64>,
+    /// how far (in percent of the mark price) the price has to move against the net position to liquidate it.
+    pub liquidation_distance_percentage: Option<f64>,
+    /// the number of netted trades.
+    pub trades: usize,
+    /// the alert names of the strategies with trades in the position, sorted.
+    pub strategies: Vec<String>,
+}
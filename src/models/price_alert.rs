@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// A user-defined alert on the price of a pair, independent of any trade. Price alerts trigger once.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceAlert {
+    /// the unique database ID of the price alert.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// the pair to watch (e.g. SOLUSDT).
+    pub pair: String,
+    /// when the alert triggers.
+    pub condition: PriceAlertCondition,
+    /// an optional note included in the notification.
+    pub note: Option<String>,
+    /// the timestamp of when the alert was created.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_timestamp: DateTime<Utc>,
+    /// the timestamp of when the alert triggered. `None` while the alert is active.
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub triggered_timestamp: Option<DateTime<Utc>>,
+    /// the price that triggered the alert.
+    pub triggered_price: Option<f64>,
+}
+
+/// The conditions that a price alert can trigger on.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PriceAlertCondition {
+    /// triggers once the price is at or above `price`.
+    Above { price: f64 },
+    /// triggers once the price is at or below `price`.
+    Below { price: f64 },
+    /// triggers once the price has moved `percent` (in either direction) from `reference_price`.
+    ///
+    /// if no reference price is provided upon creation, the latest price of the pair is used.
+    #[serde(rename_all = "camelCase")]
+    PercentMove { percent: f64, reference_price: Option<f64> },
+}
+
+/// The request body of `POST /price_alerts`.
+#[derive(Deserialize, Debug)]
+pub struct NewPriceAlert {
+    /// the pair to watch (e.g. SOLUSDT).
+    pub pair: String,
+    /// when the alert triggers (e.g. `{ "type": "above", "price": 250 }` or `{ "type": "percentMove", "percent": 5 }`).
+    pub condition: PriceAlertCondition,
+    /// an optional note included in the notification.
+    pub note: Option<String>,
+}
+
+/// Query parameters accepted by `GET /price_alerts`.
+#[derive(Deserialize, Debug)]
+pub struct PriceAlertQuery {
+    /// if true, only alerts that haven't triggered yet are returned.
+    pub active: Option<bool>,
+}
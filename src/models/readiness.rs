@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// The outcome of a startup check.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// the check succeeded.
+    Passed,
+    /// the check found a problem that doesn't prevent the server from working, but should be looked into.
+    Warning,
+    /// the check failed.
+    Failed,
+    /// the check doesn't apply to the current configuration.
+    Skipped
+}
+
+/// A single check of the startup self-check.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessCheck {
+    /// what was checked (e.g. `secret TRADINGVIEW_SECRET`).
+    pub name: String,
+    /// the outcome of the check.
+    pub status: CheckStatus,
+    /// whether the server refuses to start if this check fails.
+    pub critical: bool,
+    /// a human-readable explanation of the outcome.
+    pub message: String,
+}
+
+/// The result of validating the configuration and the database, run at startup and returned by `GET /admin/readiness`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    /// when the checks were run.
+    pub timestamp: DateTime<Utc>,
+    /// whether none of the critical checks failed.
+    pub ready: bool,
+    /// all checks, in the order they were run.
+    pub checks: Vec<ReadinessCheck>,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// The market regime of a pair when a trade was opened, derived from its daily candles.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketRegime {
+    /// the direction of the trend, from the slope of the EMA of the daily closes.
+    pub trend: TrendRegime,
+    /// the change (in percent) of the EMA of the daily closes over the last `REGIME_SLOPE_DAYS` days.
+    pub ema_slope_percentage: f64,
+    /// how volatile the pair was, from the percentile of its ATR.
+    pub volatility: VolatilityRegime,
+    /// the percentile (0 to 100) of the latest ATR among the ATRs of the last `REGIME_LOOKBACK_DAYS` days.
+    pub atr_percentile: f64,
+}
+
+/// The direction of the trend of a pair.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendRegime {
+    Up,
+    Down,
+    /// the EMA moved less than `REGIME_FLAT_SLOPE_PERCENTAGE` either way.
+    Flat
+}
+
+/// How volatile a pair is compared to its recent history.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VolatilityRegime {
+    /// the ATR is in the lowest third of its recent history.
+    Quiet,
+    Normal,
+    /// the ATR is in the highest third of its recent history.
+    Volatile
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// An incoming alert that was rejected (i.e. didn't execute a trade), stored to debug alerts that seemingly "did nothing".
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedAlert {
+    /// the unique database ID of the rejected alert.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// the timestamp of when the alert was rejected.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// the machine-readable reason of the rejection.
+    pub reason: RejectionReason,
+    /// the human-readable details of the rejection.
+    pub message: String,
+    /// the alert name, if the payload contained one.
+    pub alert_name: Option<String>,
+    /// the pair, if the payload contained one.
+    pub pair: Option<String>,
+    /// the received payload, with the secret redacted.
+    pub payload: Value,
+    /// the ID of the request that delivered the alert.
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+/// The machine-readable reasons an alert can be rejected for.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RejectionReason {
+    /// the payload couldn't be deserialized into an alert (e.g. a missing or mistyped field).
+    InvalidPayload,
+    /// the secret of the alert didn't match `TRADINGVIEW_SECRET`.
+    InvalidSecret,
+    /// the timestamp of the alert was too old (e.g. a replayed webhook) or in the future.
+    InvalidTimestamp,
+    /// the pair of the alert isn't one of the `ACCEPTED_SYMBOLS` (nor a TradFi symbol).
+    SymbolNotAllowed,
+    /// the market of the pair (a TradFi symbol) is closed.
+    MarketClosed,
+    /// the trade would oppose the trade of another strategy on the same exchange account and pair (`POSITION_CONFLICT_POLICY`).
+    PositionConflict,
+    /// live trading is disabled (`FEATURE_LIVE_TRADING`) or the Binance API credentials aren't set.
+    LiveTradingDisabled,
+    /// the strategy of the alert is disabled.
+    StrategyDisabled,
+    /// the alert was blocked by the anomaly guard.
+    AnomalyDetected,
+    /// no conversion rate was available to size the trade.
+    NoConversionRate,
+    /// the execution of alerts was paused by an operator (`/pause` command).
+    Paused,
+    /// the strategy of the alert opened a trade within its cooldown.
+    Cooldown,
+    /// the order of the alert was rejected by the exchange.
+    ExchangeRejected,
+    /// the alert was rejected by the filter script of its strategy (or the script failed).
+    FilteredByScript,
+    /// the alert was rejected by an alert filter plugin.
+    FilteredByPlugin,
+    /// the strategy is sized by the Kelly criterion, which gives its recent trades no edge.
+    NoEdge,
+    /// the loss at the stop loss of the trade would exceed the per-trade risk cap.
+    RiskCapExceeded,
+    /// the exchange of a live trade is under maintenance, so no order is placed on it.
+    ExchangeMaintenance
+}
+
+/// Query parameters accepted by `GET /alerts/rejected`.
+#[derive(Deserialize, Debug)]
+pub struct RejectedAlertQuery {
+    /// only include alerts rejected for this reason (e.g. `INVALID_SECRET`).
+    pub reason: Option<RejectionReason>,
+    /// only include alerts with this alert name.
+    pub alert_name: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
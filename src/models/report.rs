@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use lettre::{message::Mailbox, AsyncSmtpTransport, Tokio1Executor};
+use serde::Serialize;
+
+use super::{ClosedTrade, PerformanceStats, ReportLocale, RoundingPolicy};
+
+/// The periods a performance report can be sent for.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportPeriod {
+    /// the previous UTC day, sent at midnight (UTC).
+    Daily,
+    /// the previous 7 days, sent on Mondays at midnight (UTC).
+    Weekly
+}
+
+/// A point on the equity curve of a report, i.e. the cumulative PnL (in USDT) after a trade was closed.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EquityPoint {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    pub equity: f64,
+}
+
+/// The performance of the trades closed within a report period.
+#[derive(Debug)]
+pub struct PerformanceReport {
+    pub period: ReportPeriod,
+    /// the start of the period (inclusive).
+    pub from: DateTime<Utc>,
+    /// the end of the period (exclusive).
+    pub to: DateTime<Utc>,
+    /// the metrics of the trades closed within the period (in USDT).
+    pub stats: PerformanceStats,
+    /// the trades closed within the period, oldest first.
+    pub trades: Vec<ClosedTrade>,
+    /// the cumulative PnL after each trade.
+    pub equity: Vec<EquityPoint>,
+}
+
+/// Sends performance reports via SMTP to the configured recipients.
+#[derive(Debug, Clone)]
+pub struct EmailReporter {
+    pub transport: AsyncSmtpTransport<Tokio1Executor>,
+    pub from: Mailbox,
+    pub recipients: Vec<Mailbox>,
+    /// the periods to send reports for.
+    pub periods: Vec<ReportPeriod>,
+    /// how numbers and timestamps are formatted in the reports.
+    pub locale: ReportLocale,
+    /// how amounts and prices are rounded in the reports.
+    pub rounding: RoundingPolicy,
+}
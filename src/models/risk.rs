@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::TradeDirection;
+
+/// What happens to a trade whose loss at its stop loss exceeds the per-trade risk cap (`RISK_CAP_ACTION` env variable).
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum RiskCapAction {
+    /// the notional value of the trade is reduced until its loss at the stop loss is within the cap.
+    #[default]
+    Shrink,
+    /// the alert is rejected.
+    Reject
+}
+
+/// The request body of `POST /risk/stress`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StressTestRequest {
+    /// the price change (in percent) of each base currency (e.g. `{"BTC": -10, "ETH": 5}`).
+    #[serde(default)]
+    pub shocks: HashMap<String, f64>,
+    /// the price change (in percent) of the base currencies without a shock of their own. defaults to 0.
+    #[serde(default)]
+    pub default_shock: f64,
+}
+
+/// An open trade under a hypothetical price shock. All values are denominated in USDT.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StressedPosition {
+    /// the database ID of the trade.
+    pub id: String,
+    pub alert_name: String,
+    pub pair: String,
+    pub direction: TradeDirection,
+    /// the latest price of the pair.
+    pub current_price: f64,
+    /// the price of the pair after the shock.
+    pub shocked_price: f64,
+    /// the unrealized PnL (excluding fees) at the latest price.
+    pub current_pnl: f64,
+    /// the unrealized PnL (excluding fees) after the shock, clamped to the margin of the trade if it would be liquidated.
+    pub shocked_pnl: f64,
+    /// the margin of the trade, i.e. its notional value at entry divided by its leverage.
+    pub margin: f64,
+    /// whether the shocked price crosses the liquidation price of the trade.
+    pub liquidated: bool,
+}
+
+/// The response data of `POST /risk/stress`. All values are denominated in USDT.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StressTestResult {
+    /// the starting balance of the paper account (see `PAPER_ACCOUNT_BALANCE_USDT`).
+    pub account_balance: f64,
+    /// the total PnL of all closed trades.
+    pub realized_pnl: f64,
+    /// the unrealized PnL of the open trades at the latest prices.
+    pub current_unrealized_pnl: f64,
+    /// the unrealized PnL of the open trades after the shocks.
+    pub shocked_unrealized_pnl: f64,
+    /// the equity of the paper account at the latest prices (balance, realized and unrealized PnL).
+    pub current_equity: f64,
+    /// the equity of the paper account after the shocks.
+    pub shocked_equity: f64,
+    /// the total margin of the open trades that wouldn't be liquidated.
+    pub margin: f64,
+    /// the margin in percent of the shocked equity. `None` if the shocked equity isn't positive.
+    pub margin_usage_percentage: Option<f64>,
+    /// the open trades under the shocks.
+    pub positions: Vec<StressedPosition>,
+    /// the pairs of open trades that were left out, since no price (or USDT rate) is available for them yet.
+    pub unpriced_pairs: Vec<String>,
+}
+
+/// The exposure of the open trades to a product. All values are denominated in USDT.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductExposure {
+    /// the product ID of the price feed (e.g. BTC-USDT).
+    pub product: String,
+    /// the notional value of the long trades at the latest price.
+    pub long: f64,
+    /// the notional value of the short trades at the latest price.
+    pub short: f64,
+    /// the long minus the short notional value.
+    pub net: f64,
+}
+
+/// The daily candle of a product (UTC), built from its price ticks of the day.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct DailyCandle {
+    pub product: String,
+    /// the day in `YYYY-MM-DD` format.
+    pub day: String,
+    /// the highest price tick of the day.
+    pub high: f64,
+    /// the lowest price tick of the day.
+    pub low: f64,
+    /// the last price tick of the day.
+    pub close: f64,
+}
+
+/// The response data of `GET /risk/exposure`. All values are denominated in USDT.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureReport {
+    /// the exposure to each product with open trades.
+    pub exposures: Vec<ProductExposure>,
+    /// the sum of the long and short notional values.
+    pub gross_exposure: f64,
+    /// the sum of the net notional values.
+    pub net_exposure: f64,
+    /// the equity of the paper account (balance, realized and unrealized PnL).
+    pub equity: f64,
+    /// the loss over one day that the open trades are not expected to exceed at `confidence`, simulated from the daily returns
+    /// of their products. `None` if there aren't enough daily closes yet.
+    pub value_at_risk: Option<f64>,
+    /// the confidence level of the value-at-risk (e.g. 0.95).
+    pub confidence: f64,
+    /// the number of daily returns the value-at-risk was simulated from.
+    pub observations: usize,
+    /// the value-at-risk in percent of the equity. `None` if there's no value-at-risk or the equity isn't positive.
+    pub value_at_risk_percentage: Option<f64>,
+    /// set if the value-at-risk exceeds the `VAR_WARNING_EQUITY_FRACTION` of the equity.
+    pub warning: Option<String>,
+    /// the pairs of open trades that were left out, since no price (or USDT rate) is available for them yet.
+    pub unpriced_pairs: Vec<String>,
+}
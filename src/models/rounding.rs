@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// How values exactly halfway between two rounded values are rounded.
+#[derive(Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// to the nearest even digit (banker's rounding), e.g. `0.125` to `0.12`. doesn't bias sums of many rounded values.
+    #[default]
+    HalfEven,
+    /// away from zero, e.g. `0.125` to `0.13` and `-0.125` to `-0.13`.
+    HalfAwayFromZero,
+}
+
+/// How values are rounded. Values are stored and calculated with full precision; accounting values (e.g. order quantities)
+/// are rounded when they're booked, display values only when they're shown in API responses and reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundingPolicy {
+    /// how accounting values are rounded (`ACCOUNTING_ROUNDING_MODE` env variable).
+    pub accounting_mode: RoundingMode,
+    /// how displayed values are rounded (`DISPLAY_ROUNDING_MODE` env variable).
+    pub display_mode: RoundingMode,
+    /// the decimals of displayed amounts, e.g. PnL and fees (`DISPLAY_DECIMALS` env variable).
+    pub display_decimals: u32,
+    /// the decimals of the displayed prices of each symbol, e.g. `ETHBTC` with 5 (`SYMBOL_PRICE_DECIMALS` env variable).
+    /// prices of other symbols are displayed with full precision.
+    pub price_decimals: HashMap<String, u32>,
+}
@@ -0,0 +1,19 @@
+/// The outcome of a strategy's filter script for an alert.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterDecision {
+    /// execute the alert, with the exits or sizing overridden by the script (if any).
+    Allow(FilterOverrides),
+    /// reject the alert for the given reason.
+    Deny(String),
+}
+
+/// The values of an alert that a filter script overrides. Unset values are left as is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterOverrides {
+    /// the take profit price of the trade.
+    pub take_profit: Option<f64>,
+    /// the stop loss price of the trade.
+    pub stop_loss: Option<f64>,
+    /// the notional value (in USDT) of the trade.
+    pub notional_value: Option<f64>,
+}
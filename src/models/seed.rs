@@ -0,0 +1,10 @@
+/// The outcome of seeding the paper history of a strategy.
+#[derive(Debug, Default, PartialEq)]
+pub struct SeedSummary {
+    /// the amount of stored alerts that were replayed.
+    pub replayed_alerts: usize,
+    /// the amount of closed trades seeded.
+    pub trades: usize,
+    /// the amount of trades skipped because they were already seeded (e.g. by another replica).
+    pub duplicates: usize,
+}
@@ -0,0 +1,48 @@
+use std::{collections::HashSet, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A claim of an instance on a symbol. Only the holder subscribes to the symbol's price feed and checks its price triggers.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolClaim {
+    /// the claimed symbol (e.g. SOLUSDT).
+    #[serde(rename = "_id")]
+    pub pair: String,
+    /// the ID of the instance holding the claim.
+    pub holder: String,
+    /// the timestamp after which the claim can be taken over by another instance.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub expires_at: DateTime<Utc>,
+}
+
+/// The latest heartbeat of an instance, used to determine how many instances share the symbols.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceHeartbeat {
+    /// the ID of the instance.
+    #[serde(rename = "_id")]
+    pub instance_id: String,
+    /// the timestamp of the instance's latest heartbeat.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+/// The sharding state of this instance.
+#[derive(Debug)]
+pub struct Sharding {
+    /// whether sharding is enabled (`SHARD_MODE` env variable). if disabled, this instance handles all symbols.
+    pub enabled: bool,
+    /// the symbols currently claimed by this instance.
+    pub claimed_pairs: Mutex<HashSet<String>>,
+}
+
+/// The response data of `GET /shard`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardStatus {
+    pub instance_id: String,
+    pub enabled: bool,
+    pub claimed_pairs: Vec<String>,
+}
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A snapshot of the auxiliary in-memory state that isn't stored in any other collection, restored on boot to speed up restarts.
+///
+/// Active trades and price alerts are not included, since they're loaded from their own collections.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSnapshot {
+    /// the ID of the snapshot (always `STATE_SNAPSHOT_ID`).
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// the timestamp of when the snapshot was taken.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// the latest price of each product received from the price feed.
+    pub latest_prices: HashMap<String, f64>,
+    /// the recent alert timestamps (in seconds) of each strategy, used to detect frequency spikes.
+    pub alert_history: HashMap<String, Vec<i64>>,
+}
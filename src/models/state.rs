@@ -1,13 +1,74 @@
-use std::sync::Arc;
+use std::sync::{atomic::AtomicBool, Arc};
 
-use crate::api::ActiveTradesMap;
+use tokio::sync::mpsc;
 
-use super::MongoDBState;
+use crate::api::{alert::AlertLocksMap, anomaly::AlertHistoryMap, price_alert::PriceAlertsMap, ActiveTradesMap, LatestPricesMap};
+
+use super::{Exchange, FeatureFlags, FeedChannels, FeedQualityMonitor, Leadership, LiveCloseTracker, MongoDBState, MqttPublisher, NetworkConfig, Notifier, PluginRegistry, PositionConflictGuard, PriceConsensus, PriceTickRecorder, ResponseVerbosity, RoundingPolicy, Sharding, SharedClock, TradeTickRecorder, TradingCalendar, WsCommand};
 
 /// A global application state struct which can be shared across handlers, WebSockets, etc.
 pub struct AppState {
+    /// The unique ID of this instance (`INSTANCE_ID` env variable, or a random ID), used to coordinate multiple instances.
+    pub instance_id: String,
     /// The MongoDB data-access object.
     pub mongo_state: Arc<MongoDBState>,
     /// All active trades in memory (for real-time checks).
     pub active_trades: ActiveTradesMap,
+    /// The latest price of each product received from the price feed.
+    pub latest_prices: LatestPricesMap,
+    /// Sends subscription changes to the price feed's WebSocket connection.
+    pub ws_commands: mpsc::UnboundedSender<WsCommand>,
+    /// All price alerts that haven't triggered yet in memory.
+    pub price_alerts: PriceAlertsMap,
+    /// Delivers notifications to the configured sinks.
+    pub notifier: Notifier,
+    /// The recent alert timestamps of each strategy in memory.
+    pub alert_history: AlertHistoryMap,
+    /// The locks serializing concurrent alerts of the same strategy on the same pair.
+    pub alert_locks: AlertLocksMap,
+    /// Whether this instance is the leader when running multiple instances.
+    pub leadership: Leadership,
+    /// The secret that commands and manual changes are authenticated with (`COMMAND_SECRET` env variable).
+    /// Nothing is authorized if it's unset.
+    pub command_secret: Option<String>,
+    /// The symbols handled by this instance when sharding symbols across multiple instances.
+    pub sharding: Sharding,
+    /// Whether the execution of alerts was paused by an operator (`/pause` command).
+    pub paused: AtomicBool,
+    /// How much human-readable detail the alert webhook includes in its responses.
+    pub response_verbosity: ResponseVerbosity,
+    /// Persists the prices of the price feed as price ticks.
+    pub price_ticks: PriceTickRecorder,
+    /// Captures the ticks observed while trades are open.
+    pub trade_ticks: TradeTickRecorder,
+    /// The source of the current time (the system clock, or a simulated clock in backtests and tests).
+    pub clock: SharedClock,
+    /// The risky subsystems that are enabled in the config.
+    pub features: FeatureFlags,
+    /// The alert filters and exit rules compiled into the bot.
+    pub plugins: PluginRegistry,
+    /// Publishes trade lifecycle events and tickers to MQTT, if configured.
+    pub mqtt: MqttPublisher,
+    /// The proxy and compression settings of the connections to the price feeds and exchanges.
+    pub network: NetworkConfig,
+    /// The Coinbase WebSocket channel that the price feed of each product is subscribed to.
+    pub feed_channels: FeedChannels,
+    /// Tracks the data quality of the price feed and discards obviously bad ticks.
+    pub feed_quality: FeedQualityMonitor,
+    /// Compares the prices of the price feed with those of the secondary venues.
+    pub price_consensus: PriceConsensus,
+    /// The market calendars of the TradFi symbols, which don't trade 24/7.
+    pub trading_calendar: TradingCalendar,
+    /// How accounting and displayed values are rounded.
+    pub rounding: RoundingPolicy,
+    /// Detects strategies that would hold opposing positions on the same exchange account and pair.
+    pub conflict_guard: PositionConflictGuard,
+    /// The exchange that live trades are placed on (Binance), if its API credentials are set.
+    pub live_exchange: Option<Arc<dyn Exchange>>,
+    /// The live trades being closed in the background after their exit triggers were hit.
+    pub live_closes: LiveCloseTracker,
+    /// The exchange that prices are read from when the price feed has none yet (Coinbase, like the price feed).
+    pub price_exchange: Arc<dyn Exchange>,
+    /// The exchange that the funding rates of perpetuals are polled from (Binance's public market data).
+    pub funding_exchange: Arc<dyn Exchange>,
 }
\ No newline at end of file
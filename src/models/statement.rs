@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::TradeKind;
+
+/// A monthly statement of an account (paper or live), like a broker statement: the equity at the start and the end of the month,
+/// alongside the trades, fees and funding in between. Generated once the month ended and stored, so that it can be downloaded later.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatement {
+    /// the unique database ID of the statement, made of its account and month (e.g. `paper-2025-01`).
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// the account that the statement covers.
+    pub account: TradeKind,
+    /// the month that the statement covers (e.g. `2025-01`).
+    pub month: String,
+    /// the start of the month (inclusive, UTC).
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub from: DateTime<Utc>,
+    /// the end of the month (exclusive, UTC).
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub to: DateTime<Utc>,
+    /// the equity (in USDT) at the start of the month: the starting balance plus the realized PnL of all trades closed before.
+    pub opening_equity: f64,
+    /// the amount of trades closed within the month.
+    pub trades: usize,
+    /// the realized PnL (in USDT) of the trades closed within the month, net of fees and funding.
+    pub realized_pnl: f64,
+    /// the execution fees (in USDT) of the trades closed within the month.
+    pub execution_fees: f64,
+    /// the liquidation fees (in USDT) of the trades liquidated within the month.
+    pub liquidation_fees: f64,
+    /// the funding fees (in USDT) of the trades closed within the month. positive values are funding paid.
+    pub funding_fees: f64,
+    /// the equity (in USDT) at the end of the month.
+    pub closing_equity: f64,
+    /// when the statement was generated.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub generated_timestamp: DateTime<Utc>,
+    /// the statement rendered as an HTML document. left out when listing statements.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub html: String,
+}
+
+/// Query parameters accepted by `GET /reports/statements`.
+#[derive(Deserialize, Debug)]
+pub struct AccountStatementQuery {
+    /// only include the statements of this account (`paper` or `live`).
+    pub account: Option<TradeKind>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
\ No newline at end of file
@@ -0,0 +1,228 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use super::ReportingCurrency;
+
+/// Query parameters accepted by the stats endpoints.
+#[derive(Deserialize, Debug, Default)]
+pub struct StatsQuery {
+    /// only include trades triggered by this alert name (i.e. a single strategy).
+    pub alert_name: Option<String>,
+    /// only include trades executed on this pair (e.g. SOLUSDT).
+    pub pair: Option<String>,
+    /// only include trades opened under this experiment.
+    pub experiment: Option<String>,
+    /// the currency to denominate the values in. defaults to the `REPORTING_CURRENCY` env variable.
+    pub currency: Option<ReportingCurrency>,
+    /// the IANA timezone that days, months and hours are bucketed in (e.g. `Europe/Berlin`). defaults to the `REPORT_TIMEZONE` env variable.
+    pub timezone: Option<Tz>,
+}
+
+/// Query parameters accepted by `GET /stats/compare`.
+#[derive(Deserialize, Debug)]
+pub struct CompareQuery {
+    /// a comma-separated list of alert names (strategies) to compare (e.g. `a,b,c`).
+    pub names: String,
+    /// only compare the trades opened under this experiment (e.g. to compare the variants of an A/B test).
+    pub experiment: Option<String>,
+    /// the currency to denominate the values in. defaults to the `REPORTING_CURRENCY` env variable.
+    pub currency: Option<ReportingCurrency>,
+    /// the IANA timezone that days, months and hours are bucketed in (e.g. `Europe/Berlin`). defaults to the `REPORT_TIMEZONE` env variable.
+    pub timezone: Option<Tz>,
+}
+
+/// Aggregated performance metrics of a set of closed trades.
+///
+/// The raw sums are produced by a MongoDB `$group` stage, while the ratios (win rate, profit factor) are derived afterwards.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PerformanceStats {
+    /// the total amount of closed trades.
+    pub total_trades: u64,
+    /// the amount of trades closed with a positive PnL.
+    pub wins: u64,
+    /// the amount of trades closed with a negative PnL.
+    pub losses: u64,
+    /// the percentage of trades closed with a positive PnL.
+    pub win_rate: f64,
+    /// the sum of the PnL of all trades (in the reporting currency).
+    pub total_pnl: f64,
+    /// the average PnL per trade (in the reporting currency).
+    pub average_pnl: f64,
+    /// the average ROE per trade (in percentage format).
+    pub average_roe: f64,
+    /// the sum of the PnL of all winning trades (in the reporting currency).
+    pub gross_profit: f64,
+    /// the sum of the PnL of all losing trades (in the reporting currency). always zero or negative.
+    pub gross_loss: f64,
+    /// gross profit divided by the absolute gross loss. `None` if there are no losing trades.
+    pub profit_factor: Option<f64>,
+    /// the sum of all execution fees paid (in the reporting currency).
+    pub total_execution_fees: f64,
+    /// the sum of all funding fees paid (in the reporting currency).
+    pub total_funding_fees: f64,
+    /// the PnL of the best trade (in the reporting currency).
+    pub best_trade_pnl: f64,
+    /// the PnL of the worst trade (in the reporting currency).
+    pub worst_trade_pnl: f64,
+}
+
+/// Performance metrics of the trades closed within the last `days` days.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingWindowStats {
+    /// the length of the window in days (e.g. 7, 30, 90).
+    pub days: i64,
+    /// the metrics of the trades closed within the window.
+    pub stats: PerformanceStats,
+}
+
+/// Performance metrics of the trades closed within a single calendar month (UTC).
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyStats {
+    /// the month in `YYYY-MM` format.
+    pub month: String,
+    /// the metrics of the trades closed within the month.
+    pub stats: PerformanceStats,
+    /// the difference in total PnL compared to the previous month (in the reporting currency).
+    ///
+    /// `None` for the first month on record.
+    pub pnl_change: Option<f64>,
+}
+
+/// Performance metrics of the closed trades sharing the same value for a grouping dimension (e.g. the same pair).
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupedStats {
+    /// the value of the grouping dimension (e.g. `SOLUSDT` when grouped by pair, `14` when grouped by entry hour).
+    pub key: String,
+    /// the metrics of the trades in this group.
+    pub stats: PerformanceStats,
+}
+
+/// The response data of `GET /stats/breakdown`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsBreakdown {
+    /// the currency that all monetary values are denominated in.
+    pub currency: ReportingCurrency,
+    /// the timezone that days, months and hours are bucketed in.
+    pub timezone: Tz,
+    /// the metrics grouped by the pair traded.
+    pub by_pair: Vec<GroupedStats>,
+    /// the metrics grouped by the direction of the trade (long or short).
+    pub by_direction: Vec<GroupedStats>,
+    /// the metrics grouped by the leverage used.
+    pub by_leverage: Vec<GroupedStats>,
+    /// the metrics grouped by the local hour of day the trade was opened at.
+    pub by_entry_hour: Vec<GroupedStats>,
+    /// the metrics grouped by the experiment the trade was opened under. trades without an experiment are grouped under `unknown`.
+    pub by_experiment: Vec<GroupedStats>,
+    /// the metrics grouped by the trend of the pair when the trade was opened. trades opened without enough price history are grouped under `unknown`.
+    pub by_trend: Vec<GroupedStats>,
+    /// the metrics grouped by the volatility of the pair when the trade was opened. trades opened without enough price history are grouped under `unknown`.
+    pub by_volatility: Vec<GroupedStats>,
+}
+
+/// The response data of `GET /stats/heatmap`.
+///
+/// Both matrices are indexed as `[weekday][hour]`, where weekday `0` is Monday and hour is the local hour of entry.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsHeatmap {
+    /// the currency that all monetary values are denominated in.
+    pub currency: ReportingCurrency,
+    /// the timezone that days, months and hours are bucketed in.
+    pub timezone: Tz,
+    /// the labels of the weekday rows, starting from Monday.
+    pub weekdays: Vec<&'static str>,
+    /// the realized PnL (in the reporting currency) of the trades opened within each weekday/hour bucket.
+    pub pnl: Vec<Vec<f64>>,
+    /// the amount of trades opened within each weekday/hour bucket.
+    pub trades: Vec<Vec<u64>>,
+}
+
+/// A single weekday/hour bucket returned by the heatmap aggregation.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapBucket {
+    /// the local ISO weekday of entry (1 = Monday, 7 = Sunday).
+    pub weekday: usize,
+    /// the local hour of entry (0-23).
+    pub hour: usize,
+    /// the realized PnL of the trades in this bucket.
+    pub pnl: f64,
+    /// the amount of trades in this bucket.
+    pub trades: u64,
+}
+
+/// The metrics of a single strategy (alert name) returned by `GET /stats/compare`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyComparison {
+    /// the alert name of the strategy.
+    pub alert_name: String,
+    /// the lifetime metrics of the strategy's closed trades.
+    pub stats: PerformanceStats,
+    /// the expected PnL per trade (in the reporting currency), i.e. `win rate * average win - loss rate * average loss`.
+    pub expectancy: f64,
+    /// the largest peak-to-trough decline of the strategy's cumulative PnL (in the reporting currency).
+    pub max_drawdown: f64,
+}
+
+/// The correlation of the daily returns between two strategies.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyReturnCorrelation {
+    /// the alert name of the first strategy.
+    pub a: String,
+    /// the alert name of the second strategy.
+    pub b: String,
+    /// the Pearson correlation of both strategies' daily PnL (local calendar days). days without closed trades count as zero PnL.
+    ///
+    /// `None` if there is not enough data to correlate.
+    pub correlation: Option<f64>,
+}
+
+/// The response data of `GET /stats/compare`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsComparison {
+    /// the currency that all monetary values are denominated in.
+    pub currency: ReportingCurrency,
+    /// the timezone that days, months and hours are bucketed in.
+    pub timezone: Tz,
+    /// the metrics of each requested strategy, in the requested order.
+    pub strategies: Vec<StrategyComparison>,
+    /// the pairwise correlations of the strategies' daily returns.
+    pub correlations: Vec<DailyReturnCorrelation>,
+}
+
+/// The response data of `GET /stats`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsOverview {
+    /// the currency that all monetary values are denominated in.
+    pub currency: ReportingCurrency,
+    /// the timezone that days, months and hours are bucketed in.
+    pub timezone: Tz,
+    /// the metrics of all closed trades.
+    pub lifetime: PerformanceStats,
+    /// the metrics of the trades closed within each of the `ROLLING_WINDOW_DAYS` windows.
+    pub rolling: Vec<RollingWindowStats>,
+    /// the metrics of each local calendar month, oldest first.
+    pub monthly: Vec<MonthlyStats>,
+}
+
+/// The response data of `GET /stats/totals`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsTotals {
+    /// the currency that `closed_pnl` is denominated in.
+    pub currency: ReportingCurrency,
+    /// the number of currently active trades.
+    pub active_trades: u64,
+    /// the total PnL of all closed trades.
+    pub closed_pnl: f64,
+}
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::{MarginMode, TradeLeverage};
+
+/// A strategy registered with the bot, identified by the alert name its TradingView alerts are sent with.
+///
+/// Alerts of strategies that aren't registered are always executed.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Strategy {
+    /// the unique database ID of the strategy.
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    /// the alert name of the strategy.
+    pub name: String,
+    /// whether alerts of the strategy are currently executed.
+    pub enabled: bool,
+    /// the experiment that the strategy's trades are grouped under, unless the alert specifies one.
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// a cron expression (UTC) of when the strategy is automatically enabled (e.g. `0 30 13 * * Mon-Fri` for the US session open).
+    #[serde(default)]
+    pub enable_cron: Option<String>,
+    /// a cron expression (UTC) of when the strategy is automatically disabled.
+    #[serde(default)]
+    pub disable_cron: Option<String>,
+    /// a URL that receives the outcome of each alert of the strategy (e.g. an external alert journal).
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// a Rhai script deciding whether (and how) each alert of the strategy is executed (see `run_filter_script`).
+    #[serde(default)]
+    pub filter_script: Option<String>,
+    /// the date to seed the paper history of the strategy from, by replaying its stored alerts at startup (see `seed_strategy`).
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub seed_from: Option<DateTime<Utc>>,
+    /// the timestamp of when the paper history of the strategy was seeded. `None` if it wasn't seeded (yet).
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub seeded_timestamp: Option<DateTime<Utc>>,
+    /// the trading parameters of the strategy's trades (sizing, leverage, exits and cooldown).
+    #[serde(default)]
+    pub parameters: StrategyParameters,
+    /// the timestamp of when the strategy was last updated.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub updated_timestamp: DateTime<Utc>,
+}
+
+/// The request body of `PUT /strategies/{name}`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyConfig {
+    /// whether alerts of the strategy are executed. defaults to true.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// the experiment to group the strategy's trades under.
+    pub experiment: Option<String>,
+    /// a cron expression (UTC) of when to automatically enable the strategy.
+    ///
+    /// accepts the standard 5 fields (minute to weekday), or 6/7 fields including seconds (and year).
+    pub enable_cron: Option<String>,
+    /// a cron expression (UTC) of when to automatically disable the strategy.
+    pub disable_cron: Option<String>,
+    /// an HTTP(S) URL to send the outcome of each alert to (see `AlertOutcomeEvent`). Internal addresses (e.g. loopback, private or
+    /// link-local) are rejected.
+    pub callback_url: Option<String>,
+    /// a Rhai script that filters (or modifies) each alert of the strategy, given the alert, the market price and the open positions.
+    pub filter_script: Option<String>,
+    /// a past date (RFC 3339) to seed the strategy's paper history from. at the next startup, the alerts of the strategy received since then
+    /// (while it was disabled) are replayed against the recorded prices, so that its stats don't start from zero.
+    pub seed_from: Option<DateTime<Utc>>,
+    /// the trading parameters of the strategy's trades. unset parameters fall back to the bot's defaults.
+    #[serde(default)]
+    pub parameters: StrategyParameters,
+}
+
+/// The trading parameters of a strategy. Unset parameters fall back to the bot's defaults (or the alert's own values).
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyParameters {
+    /// the notional value (in USDT) of each trade. defaults to `DEFAULT_NOTIONAL_VALUE`.
+    #[serde(default)]
+    pub notional_value: Option<f64>,
+    /// how the notional value of each trade is sized. defaults to a fixed `notional_value`.
+    #[serde(default)]
+    pub sizing_mode: Option<SizingMode>,
+    /// the fraction of the full Kelly criterion to size trades with (between 0 and 1, kelly sizing only). defaults to `DEFAULT_KELLY_FRACTION`.
+    #[serde(default)]
+    pub kelly_fraction: Option<f64>,
+    /// the daily volatility (ATR in percent of the price) at which trades are sized at `notional_value` (volatility target sizing only).
+    /// defaults to `DEFAULT_TARGET_VOLATILITY_PERCENTAGE`.
+    #[serde(default)]
+    pub target_volatility_percentage: Option<f64>,
+    /// the notional value of each trade in percent of the paper equity (equity percentage sizing only). defaults to `DEFAULT_EQUITY_PERCENTAGE`.
+    #[serde(default)]
+    pub equity_percentage: Option<f64>,
+    /// the leverage of each trade. defaults to `DEFAULT_LEVERAGE`.
+    #[serde(default)]
+    pub leverage: Option<TradeLeverage>,
+    /// the take profit distance (in percent of the entry price), used if the alert doesn't set a take profit price.
+    #[serde(default)]
+    pub take_profit_percentage: Option<f64>,
+    /// the stop loss distance (in percent of the entry price), used if the alert doesn't set a stop loss price.
+    #[serde(default)]
+    pub stop_loss_percentage: Option<f64>,
+    /// if set, the stop loss trails the best price since the trade was opened at this distance (in percent).
+    #[serde(default)]
+    pub trailing_stop_percentage: Option<f64>,
+    /// the minimum time (in seconds) between the openings of two trades of the strategy. alerts within the cooldown are rejected.
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+    /// the exchange whose fees, funding schedule and margin requirements are simulated (see `GET /exchanges`). defaults to `PAPER_TRADING_EXCHANGE`.
+    #[serde(default)]
+    pub exchange: Option<String>,
+    /// how the margin of each trade backs its losses. defaults to isolated.
+    #[serde(default)]
+    pub margin_mode: Option<MarginMode>,
+}
+
+/// How the notional value of a strategy's trades is sized.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SizingMode {
+    /// every trade has the same notional value.
+    #[default]
+    Fixed,
+    /// trades are sized at a fraction of the paper equity, given by the Kelly criterion of the strategy's recent win rate and payoff.
+    Kelly,
+    /// trades are sized inversely to the volatility (ATR) of the pair, so that each trade carries a similar risk.
+    VolatilityTarget,
+    /// trades are sized at a percentage of the paper equity, so that profits compound and drawdowns reduce the size of later trades.
+    EquityPercentage
+}
+
+/// The built-in strategy presets, constructible via `POST /strategies/from_template`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyTemplate {
+    /// short-lived trades with tight exits and high leverage.
+    Scalp,
+    /// trades held for days, with wide exits and moderate leverage.
+    Swing,
+    /// trades that ride a trend with a trailing stop and no fixed take profit.
+    TrendFollow
+}
+
+/// The request body of `POST /strategies/from_template`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyFromTemplate {
+    /// the alert name of the strategy to register.
+    pub name: String,
+    /// the preset to construct the strategy from.
+    pub template: StrategyTemplate,
+    /// whether alerts of the strategy are executed. defaults to true.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// the experiment to group the strategy's trades under.
+    pub experiment: Option<String>,
+}
+
+/// A preset returned by `GET /strategies/templates`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyTemplateInfo {
+    /// the preset.
+    pub template: StrategyTemplate,
+    /// the trading parameters bundled with the preset.
+    pub parameters: StrategyParameters,
+}
+
+/// Strategies are enabled unless explicitly disabled.
+fn default_enabled() -> bool {
+    true
+}
+
+/// The response data of `GET /strategies/{name}/alert_template`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertTemplate {
+    /// the URL to set as the webhook URL of the TradingView alert.
+    pub webhook_url: String,
+    /// the message to set on the TradingView alert. contains TradingView placeholders (e.g. `{{close}}`), so it's only valid JSON once TradingView fills them in.
+    pub message: String,
+}
@@ -0,0 +1,85 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use mongodb::bson;
+use serde::{Deserialize, Serialize};
+
+/// The price of a product at a point in time, stored in a time-series collection bucketed by product.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceTick {
+    /// the time of the tick. time-series collections require a BSON date rather than a timestamp in seconds.
+    pub timestamp: bson::DateTime,
+    /// the product ID of the price feed (e.g. SOL-USDT). used as the metadata field of the collection.
+    pub product: String,
+    /// the latest price of the product at `timestamp`.
+    pub price: f64,
+}
+
+/// The equity of the account at a point in time, stored in a time-series collection.
+///
+/// All values are denominated in USDT.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EquitySnapshot {
+    /// the time of the snapshot. time-series collections require a BSON date rather than a timestamp in seconds.
+    pub timestamp: bson::DateTime,
+    /// the total PnL of all closed trades.
+    pub realized_pnl: f64,
+    /// the PnL that the active trades would realize if they were closed at the latest prices (excluding fees).
+    pub unrealized_pnl: f64,
+    /// the sum of the realized and unrealized PnL.
+    pub equity: f64,
+    /// the number of active trades.
+    pub active_trades: u64,
+}
+
+/// Query parameters accepted by `GET /reports/ticks` and `GET /reports/equity/history`.
+#[derive(Deserialize, Debug, Default)]
+pub struct ChartQuery {
+    /// the product ID to return the ticks of (e.g. SOL-USDT). required by `GET /reports/ticks`.
+    pub product: Option<String>,
+    /// only include points at or after this time (RFC 3339).
+    pub from: Option<DateTime<Utc>>,
+    /// only include points before this time (RFC 3339).
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A single point of a chart.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartPoint {
+    /// the time of the point.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// the value at `timestamp` (a price, or the equity).
+    pub value: f64,
+}
+
+/// Which prices of the price feed are persisted as price ticks (`TICK_PERSISTENCE` env variable).
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum TickPersistence {
+    /// no price ticks are persisted.
+    None,
+    /// the latest price of each product is persisted every `TICK_SAMPLE_INTERVAL_SECS`.
+    #[default]
+    Sampled,
+    /// every tick received from the price feed is persisted.
+    All,
+}
+
+/// Persists the prices of the price feed as price ticks, according to the configured tick persistence.
+///
+/// When persisting all ticks, more ticks than `MAX_BUFFERED_PRICE_TICKS` between two flushes downgrade it to sampled ticks
+/// for `TICK_PERSISTENCE_DOWNGRADE_SECS`.
+#[derive(Debug, Default)]
+pub struct PriceTickRecorder {
+    /// the configured tick persistence.
+    pub persistence: TickPersistence,
+    /// how often (in seconds) the latest prices are sampled, and buffered ticks are written into the database.
+    pub sample_interval_secs: u64,
+    /// until when all ticks are downgraded to sampled ticks because of the load.
+    pub downgraded_until: Mutex<Option<DateTime<Utc>>>,
+    /// the ticks waiting to be written when persisting all ticks.
+    pub buffer: Mutex<Vec<PriceTick>>,
+}
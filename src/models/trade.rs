@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
+use super::{FxRates, MarketRegime};
+
 /// A trade instance that is generated upon executing a trade.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +25,8 @@ pub struct ActiveTrade {
     /// quantity of the base currency of the pair being traded.
     /// 
     /// (e.g. if SOL-USDT, then this would be the quantity of SOL)
+    /// 
+    /// for inverse contracts, this is the notional value in the quote currency instead (e.g. if BTCUSD, the amount of USD).
     pub quantity: f64,
     /// the price of the base currency to the quote currency of the pair at the time of the trade.
     /// 
@@ -31,19 +35,56 @@ pub struct ActiveTrade {
     /// the leverage used for the trade.
     /// 
     /// if spot trading, this will be set to 1x.
+    #[serde(default)]
     pub leverage: TradeLeverage,
+    /// whether the trade is a linear (quote-margined) or inverse (coin-margined) contract.
+    #[serde(default)]
+    pub contract_type: ContractType,
     /// the liquidation price of the trade.
+    #[serde(default)]
     pub liquidation_price: f64,
     /// if a take profit (TP) price is set, it will be stored here.
     pub take_profit: Option<f64>,
     /// if a stop loss (SL) price is set, it will be stored here.
     pub stop_loss: Option<f64>,
+    /// whether the trade was opened close to (or during) a maintenance window of the exchange.
+    #[serde(default)]
+    pub near_maintenance: bool,
+    /// the experiment that the trade was opened under, if any.
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// the ID of the request (i.e. the alert) that opened the trade, if it was opened by one.
+    #[serde(default)]
+    pub originating_request_id: Option<String>,
+    /// if set, the stop loss trails the best price since the trade was opened at this distance (in percent).
+    #[serde(default)]
+    pub trailing_stop_percentage: Option<f64>,
+    /// the exchange that the trade is simulated on. `None` for trades opened before exchange profiles existed (i.e. `PAPER_TRADING_EXCHANGE`).
+    #[serde(default)]
+    pub exchange: Option<String>,
+    /// how the margin of the trade backs its losses.
+    #[serde(default)]
+    pub margin_mode: MarginMode,
+    /// the steps in which the trade was partially liquidated so far, oldest first. `quantity` is what remains after them.
+    #[serde(default)]
+    pub partial_liquidations: Vec<PartialLiquidation>,
+    /// the market regime of the pair when the trade was opened. `None` if there wasn't enough price history.
+    #[serde(default)]
+    pub regime: Option<MarketRegime>,
+    /// the fees (in the settlement currency) actually charged by the exchange for opening a live trade. `None` for paper trades,
+    /// whose fees are simulated when they're closed.
+    #[serde(default)]
+    pub entry_fees: Option<f64>,
+    /// the ID of the order that opened a live trade on its exchange. `None` for paper trades. Encrypted in the database if field
+    /// encryption is enabled.
+    #[serde(default)]
+    pub entry_order_id: Option<String>,
 }
 
 /// An instance of a trade that has been successfully closed.
 /// 
 /// This will include all the relevant details of the trade, including the profit/loss, fees, etc.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ClosedTrade {
     /// the unique database ID of the trade.
@@ -60,6 +101,8 @@ pub struct ClosedTrade {
     /// quantity of the base currency of the pair that was traded.
     /// 
     /// (e.g. if SOL-USDT, then this would be the quantity of SOL)
+    /// 
+    /// for inverse contracts, this is the notional value in the quote currency instead (e.g. if BTCUSD, the amount of USD).
     pub quantity: f64,
     /// the price of the base currency to the quote currency of the pair when the trade was opened/executed.
     /// 
@@ -70,33 +113,100 @@ pub struct ClosedTrade {
     /// the leverage used for the trade.
     /// 
     /// if spot trading, this will be set to 1x.
+    #[serde(default)]
     pub leverage: TradeLeverage,
+    /// whether the trade was a linear (quote-margined) or inverse (coin-margined) contract.
+    #[serde(default)]
+    pub contract_type: ContractType,
     /// the liquidation price of the trade.
+    #[serde(default)]
     pub liquidation_price: f64,
+    /// the take profit (TP) price of the trade when it was closed, if any. `None` for trades closed before it was recorded.
+    #[serde(default)]
+    pub take_profit: Option<f64>,
+    /// the stop loss (SL) price of the trade when it was closed (i.e. after trailing), if any. `None` for trades closed before it was recorded.
+    #[serde(default)]
+    pub stop_loss: Option<f64>,
     /// the timestamp of when the trade was opened.
     #[serde(with = "chrono::serde::ts_seconds")]
     pub open_timestamp: DateTime<Utc>,
     /// the timestamp of when the trade was closed.
     #[serde(with = "chrono::serde::ts_seconds")]
     pub close_timestamp: DateTime<Utc>,
-    /// the profit or loss of the trade (in USDT value).
+    /// the profit or loss of the trade (in the settlement currency).
     /// 
     /// this will already take the base profit/loss and all fees into account.
     pub pnl: f64,
     /// the return on equity (ROE) of the trade (in percentage format).
     /// 
     /// this takes leverage into account.
+    #[serde(default)]
     pub roe: f64,
-    /// the fees paid for closing and opening the trade (in USDT value). used primarily in paper trades only, unless the exchange
+    /// the fees paid for closing and opening the trade (in the settlement currency). used primarily in paper trades only, unless the exchange
     /// that the trade was executed in provides this value (for live trades).
+    #[serde(default)]
     pub execution_fees: f64,
-    /// the funding fees paid for holding the trade over several hours or days (in USDT value). used primarily in paper trades only, unless the exchange
+    /// the funding fees paid for holding the trade over several hours or days (in the settlement currency). used primarily in paper trades only, unless the exchange
     /// the trade was executed in provides this value (for live trades).
     /// 
     /// at the start of trades, all `funding_fees` will start at 0 and accumulate after 1, 4 or 8 hours depending on the exchange.
     /// 
     /// for spot trades, this will be kept at 0.
+    #[serde(default)]
     pub funding_fees: f64,
+    /// the value of 1 USDT in each reporting currency at the time of closing the trade.
+    /// 
+    /// used to denominate stats and reports in the configured reporting currency.
+    #[serde(default)]
+    pub fx_rates: FxRates,
+    /// the currency that the PnL and fees are denominated in.
+    /// 
+    /// for linear contracts, this is the quote currency (e.g. BTC for ETH-BTC). for inverse contracts, this is the base currency.
+    #[serde(default = "default_settlement_currency")]
+    pub settlement_currency: String,
+    /// the value of 1 unit of the settlement currency in USDT at the time of closing the trade.
+    /// 
+    /// used to aggregate trades settled in different currencies. `None` if the price feed had no rate available.
+    #[serde(default)]
+    pub settlement_usdt_rate: Option<f64>,
+    /// whether the trade was opened close to (or during) a maintenance window of the exchange.
+    #[serde(default)]
+    pub near_maintenance: bool,
+    /// the experiment that the trade was opened under, if any.
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// the ID of the request (i.e. the alert) that opened the trade, if it was opened by one.
+    #[serde(default)]
+    pub originating_request_id: Option<String>,
+    /// a hash of the exchange export row the trade was imported from, used to skip rows that were already imported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_key: Option<String>,
+    /// whether the trade was liquidated (in which case `exit_price` is the liquidation price).
+    #[serde(default)]
+    pub liquidated: bool,
+    /// the fee charged by the exchange for liquidating the trade (in the settlement currency), included in `pnl`.
+    #[serde(default)]
+    pub liquidation_fee: f64,
+    /// the steps in which the trade was partially liquidated before it was closed. their PnL is included in `pnl`,
+    /// while `quantity` is the quantity that remained open until the trade was closed.
+    #[serde(default)]
+    pub partial_liquidations: Vec<PartialLiquidation>,
+    /// the market regime of the pair when the trade was opened. `None` if there wasn't enough price history.
+    #[serde(default)]
+    pub regime: Option<MarketRegime>,
+    /// the ID of the order that opened a live trade on its exchange. `None` for paper trades. Encrypted in the database if field
+    /// encryption is enabled.
+    #[serde(default)]
+    pub entry_order_id: Option<String>,
+    /// the ID of the order that closed a live trade on its exchange. `None` for paper trades, and for live trades that the exchange closed
+    /// by itself (e.g. liquidated). Encrypted in the database if field encryption is enabled.
+    #[serde(default)]
+    pub exit_order_id: Option<String>,
+}
+
+/// The settlement currency of closed trades stored before non-USDT settlements were supported.
+fn default_settlement_currency() -> String {
+    "USDT".to_string()
 }
 
 impl From<TradeSignal> for TradeDirection {
@@ -138,7 +248,104 @@ pub enum TradeDirection {
     Short
 }
 
+/// Used to determine how a trade is margined and settled.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractType {
+    /// quote-margined contracts (e.g. SOL-USDT, ETH-BTC). the quantity is denominated in the base currency,
+    /// while the PnL and fees are settled in the quote currency.
+    #[default]
+    Linear,
+    /// coin-margined contracts (e.g. BTCUSD perpetuals). the quantity is the notional value in the quote currency,
+    /// while the PnL and fees are settled in the base currency.
+    Inverse
+}
+
+/// Used to determine how the margin of a trade backs its losses.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum MarginMode {
+    /// the trade is liquidated at once when its margin is used up.
+    #[default]
+    Isolated,
+    /// large trades are liquidated in steps (if the `auto_liquidation` feature is enabled), each reducing the position towards
+    /// a lower maintenance margin tier of the exchange.
+    Cross
+}
+
+/// A step of a stepwise liquidation, which closed part of a cross margin trade.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialLiquidation {
+    /// the timestamp of the liquidation step.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// the quantity that was closed.
+    pub quantity: f64,
+    /// the price that the quantity was closed at (the liquidation price at that time).
+    pub price: f64,
+    /// the realized PnL of the closed quantity, including its execution fees (in the settlement currency).
+    pub pnl: f64,
+    /// the liquidation price of the remaining position after the step.
+    pub next_liquidation_price: f64,
+}
+
+/// A new take profit or stop loss of the trades updated by `POST /trade/active/bulk_update`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExitAdjustment {
+    /// the exit is moved to this price, e.g. `{ "price": 95000 }`.
+    Price(f64),
+    /// the exit is moved this percentage away from the entry price of each trade (in its direction of profit for take profits, loss for stop losses),
+    /// e.g. `{ "percentage": 2.5 }`.
+    Percentage(f64),
+}
+
+/// The request body of `POST /trade/active/bulk_update`. At least one exit has to be adjusted.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkExitUpdate {
+    /// the secret key to authenticate the request (`COMMAND_SECRET` env variable).
+    pub secret: String,
+    /// only update the trades on this pair.
+    pub pair: Option<String>,
+    /// only update the trades opened by this alert name.
+    pub alert_name: Option<String>,
+    /// the new take profit of the trades. left as is if omitted.
+    pub take_profit: Option<ExitAdjustment>,
+    /// the new stop loss of the trades. left as is if omitted.
+    pub stop_loss: Option<ExitAdjustment>,
+    /// update all active trades (including live ones) if neither `pair` nor `alertName` is given.
+    /// required so that a request without filters can't update every trade by accident.
+    #[serde(default)]
+    pub all_trades: bool,
+}
+
+/// The request body of `POST /trade/close/{id}`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualCloseRequest {
+    /// the secret key to authenticate the request (`COMMAND_SECRET` env variable).
+    pub secret: String,
+    /// the price to close a paper trade at. defaults to the current market price. rejected for live trades, which close at their fill price.
+    pub exit_price: Option<f64>,
+}
+
+/// Query parameters accepted by `GET /trade/active` and `GET /trade/closed`.
+#[derive(Deserialize, Debug, Default)]
+pub struct TradeListQuery {
+    /// only include the trades on this pair.
+    pub pair: Option<String>,
+    /// only include the trades in this direction (long or short).
+    pub direction: Option<TradeDirection>,
+    /// only include the trades of this kind (paper or live).
+    pub kind: Option<TradeKind>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
 /// Used to determine the status of a trade.
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum TradeStatus {
@@ -147,9 +354,10 @@ pub enum TradeStatus {
 }
 
 /// Used to determine the leverage of a trade.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
 pub enum TradeLeverage {
     #[serde(rename = "1x")]
+    #[default]
     One,
     #[serde(rename = "2x")]
     Two,
@@ -161,10 +369,10 @@ pub enum TradeLeverage {
     Ten
 }
 
-impl Into<f64> for TradeLeverage {
+impl From<TradeLeverage> for f64 {
     /// Converts a `TradeLeverage` enum into a `f64` value.
-    fn into(self) -> f64 {
-        match self {
+    fn from(leverage: TradeLeverage) -> Self {
+        match leverage {
             TradeLeverage::One => 1.0,
             TradeLeverage::Two => 2.0,
             TradeLeverage::Three => 3.0,
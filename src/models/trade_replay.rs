@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::ClosedTrade;
+
+/// A candle of a product's price ticks.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Candle {
+    /// the start of the candle.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// What happened to a trade at a point of its replay.
+#[derive(Serialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayEventKind {
+    /// the trade was opened.
+    Entry,
+    /// part of the trade was liquidated.
+    PartialLiquidation,
+    /// the trade was closed at (or beyond) its take profit.
+    TakeProfit,
+    /// the trade was closed at (or beyond) its stop loss.
+    StopLoss,
+    /// the trade was liquidated.
+    Liquidation,
+    /// the trade was closed otherwise (e.g. by an opposite alert, an exit rule or manually).
+    Exit
+}
+
+/// A marker of a trade's replay.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayEvent {
+    pub kind: ReplayEventKind,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    pub price: f64,
+}
+
+/// The response data of `GET /trade/{id}/replay`: everything needed to render a closed trade on a chart.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeReplay {
+    pub trade: ClosedTrade,
+    /// the product ID the candles are of (e.g. `SOL-USDT`).
+    pub product: String,
+    /// the length of each candle in seconds.
+    pub interval_secs: i64,
+    /// the candles from before the trade was opened until after it was closed, oldest first.
+    pub candles: Vec<Candle>,
+    /// the entry, partial liquidations and exit of the trade, oldest first.
+    pub events: Vec<ReplayEvent>,
+    /// the take profit price of the trade when it was closed, if any.
+    pub take_profit: Option<f64>,
+    /// the stop loss price of the trade when it was closed, if any.
+    pub stop_loss: Option<f64>,
+    pub liquidation_price: f64,
+}
@@ -0,0 +1,29 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use super::LruCache;
+
+/// The price of a trade's pair observed while the trade was open, stored as a 1 second bar (the last price within the second).
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeTick {
+    /// the ID of the trade that was open when the tick was observed.
+    pub trade_id: ObjectId,
+    /// the start of the second that the tick was observed in.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+    /// the last price observed within the second.
+    pub price: f64,
+}
+
+/// Buffers the ticks observed while trades are open until they're written into the database (`TRADE_TICK_CAPTURE` env variable).
+#[derive(Debug, Default)]
+pub struct TradeTickRecorder {
+    /// whether ticks are captured at all.
+    pub enabled: bool,
+    /// the last price of each trade within each second (in seconds since the epoch), waiting to be written (at most `MAX_BUFFERED_TRADE_TICKS`).
+    pub buffer: Mutex<LruCache<(ObjectId, i64), f64>>,
+}
@@ -1,10 +1,13 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-use super::TradeSignal;
+use super::{ContractType, TradeSignal};
 
 /// `TradingViewAlert` is a struct that represents the payload data that TradingView sends to the server 
 /// upon receiving an alert.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct TradingViewAlert {
     /// the alert name
     pub name: String,
@@ -19,6 +22,22 @@ pub struct TradingViewAlert {
     pub take_profit: Option<f64>,
     /// the stop loss price to set for the trade
     pub stop_loss: Option<f64>,
+    /// whether to open a linear (quote-margined) or inverse (coin-margined) contract. defaults to linear.
+    #[serde(default)]
+    pub contract_type: ContractType,
+    /// the experiment to group the trade under (e.g. when A/B testing parameter changes of a strategy).
+    #[serde(default)]
+    pub experiment: Option<String>,
+    /// indicator values at the time of the alert (e.g. `{"rsi": {{plot_0}}}`), available to the filter script of the strategy.
+    #[serde(default)]
+    pub indicators: HashMap<String, f64>,
+    /// a unique key of the alert (e.g. `{{strategy.order.id}}-{{timenow}}`), ensuring it's only executed once across replicas and retries.
+    /// 
+    /// if not set, a key is derived from the alert's contents instead.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// the time the alert was sent at (e.g. `{{timenow}}`), in RFC 3339 format. alerts outside of the accepted window are rejected as replays.
+    pub timestamp: DateTime<Utc>,
     /// the secret key to authenticate the trade execution request
     pub secret: String,
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::FeatureFlags;
+
+/// The response data of `GET /version`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    /// the version of the crate.
+    pub version: &'static str,
+    /// the git commit the binary was built from, if it was built from a git checkout.
+    pub git_commit: Option<&'static str>,
+    /// when the binary was built.
+    pub build_timestamp: Option<DateTime<Utc>>,
+    /// the version of the latest database migration, i.e. the schema this binary expects.
+    pub schema_version: u32,
+    /// the exchanges with a built-in profile.
+    pub exchanges: Vec<&'static str>,
+    /// the enabled feature flags.
+    pub features: FeatureFlags,
+}
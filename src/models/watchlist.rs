@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A pair that the price feed tracks regardless of whether a trade is open on it (e.g. to pre-warm its price data).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistEntry {
+    /// the pair being tracked (e.g. SOLUSDT).
+    pub pair: String,
+    /// the timestamp of when the pair was added to the watchlist.
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub added_timestamp: DateTime<Utc>,
+}
+
+/// The request body of `PUT /watchlist`.
+#[derive(Deserialize, Debug)]
+pub struct WatchlistUpdate {
+    /// the complete set of pairs to track. pairs not included are removed from the watchlist.
+    pub pairs: Vec<String>,
+}
@@ -1,35 +1,81 @@
+use std::{borrow::Cow, collections::HashMap, time::{Duration, Instant}};
+
 use serde::Deserialize;
 
 /// The commands that are sent to the writer task.
 /// 
-/// Used to subscribe and unsubscribe from the WebSocket to fetch/unfetch tickers.
+/// Used to subscribe and unsubscribe from the WebSocket to fetch/unfetch tickers. Each command holds a Coinbase product ID (e.g. `SOL-USDT`).
 #[derive(Debug)]
 pub enum WsCommand {
     Subscribe(String),
     Unsubscribe(String),
 }
 
-/// Represents a ticker update from Coinbase WebSocket.
-#[derive(Debug, Deserialize, Clone)]
-pub struct CoinbaseTickerUpdate {
-    #[serde(rename = "type")]
-    pub update_type: String, // typically "ticker"
+/// How a connection of the price feed ended.
+#[derive(Debug, PartialEq)]
+pub enum FeedConnectionEnd {
+    /// the receiver of the ticks was dropped, so the price feed stops.
+    Stopped,
+    /// the connection failed or was lost, so it's reconnected. `established` if it was subscribed and had received frames.
+    Lost { reason: String, established: bool },
+}
+
+/// The Coinbase WebSocket channel that the price feed of a product is subscribed to.
+#[derive(Deserialize, Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedChannel {
+    /// a tick whenever the product trades (cascading matches are batched).
+    #[default]
+    Ticker,
+    /// at most one tick every 5 seconds, for less bandwidth. trades in between are skipped by design, so they aren't backfilled.
+    TickerBatch,
+    /// a tick for every single trade, for strategies with tight triggers.
+    Matches,
+}
 
+/// The channel that each product is subscribed to (`FEED_CHANNEL` and `FEED_PAIR_CHANNELS` env variables).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedChannels {
+    /// the channel of the products without their own channel.
+    pub default: FeedChannel,
+    /// the channel of each product (e.g. `BTC-USD`) that doesn't use the default one.
+    pub products: HashMap<String, FeedChannel>,
+}
+
+/// The fields of a Coinbase WebSocket `ticker` (or `match`) message that the price feed needs (e.g. `"product_id": "BTC-USD"`, `"price": "96289.34"`).
+///
+/// The strings are borrowed from the message rather than copied, and all other fields are skipped without being allocated.
+#[derive(Debug, Deserialize)]
+pub struct CoinbaseTickerFields<'a> {
+    #[serde(rename = "type", borrow)]
+    pub update_type: Cow<'a, str>,
+    #[serde(borrow)]
+    pub product_id: Cow<'a, str>,
+    #[serde(borrow)]
+    pub price: Option<Cow<'a, str>>,
     pub sequence: Option<u64>,
-    pub product_id: String,  // "BTC-USD"
-    
-    pub price: Option<String>,       // "96289.34"
-    pub open_24h: Option<String>,    // "95393.55"
-    pub volume_24h: Option<String>,  // "11941.80121342"
-    pub low_24h: Option<String>,
-    pub high_24h: Option<String>,
-    pub volume_30d: Option<String>,
-    pub best_bid: Option<String>,
-    pub best_bid_size: Option<String>,
-    pub best_ask: Option<String>,
-    pub best_ask_size: Option<String>,
-    pub side: Option<String>,        // "buy" or "sell" in coinbase feed
-    pub time: Option<String>,        // "2024-12-27T10:50:33.372945Z"
     pub trade_id: Option<u64>,
-    pub last_size: Option<String>,
-}
\ No newline at end of file
+}
+
+/// A tick of the Coinbase price feed, as forwarded from the WebSocket connection to the consumer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceFeedTick {
+    /// the uppercase product ID (e.g. `BTC-USD`).
+    pub product_id: String,
+    /// the price, or 0 if the message had none (or an invalid one), in which case the tick is discarded.
+    pub price: f64,
+    /// the sequence number of the message, used to detect out-of-order messages.
+    pub sequence: Option<u64>,
+    /// the ID of the last trade of the message, used to detect dropped trades.
+    pub trade_id: Option<u64>,
+}
+
+/// Tracks when a WebSocket connection last received a frame, so that half-open connections (silently dropped by the network)
+/// are detected without waiting for the TCP stack to time out.
+#[derive(Debug, Clone, Copy)]
+pub struct FeedKeepalive {
+    /// when the last frame (of any kind) was received.
+    pub last_received: Instant,
+    /// how long no frame may be received before the connection is considered dead.
+    pub idle_timeout: Duration,
+}
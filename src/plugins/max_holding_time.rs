@@ -0,0 +1,34 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::{ActiveTrade, ExitRule};
+
+/// The maximum time (in hours) a trade is held for, unless overridden by the `PLUGIN_MAX_HOLDING_HOURS` env variable.
+const DEFAULT_MAX_HOLDING_HOURS: i64 = 72;
+
+/// Closes trades that have been open for longer than a maximum holding time, regardless of their PnL.
+pub struct MaxHoldingTime {
+    /// the maximum time a trade is held for.
+    pub max_holding_time: Duration,
+}
+
+impl MaxHoldingTime {
+    /// Reads the maximum holding time from the `PLUGIN_MAX_HOLDING_HOURS` env variable. Defaults to `DEFAULT_MAX_HOLDING_HOURS`.
+    pub fn from_env() -> Self {
+        let hours = std::env::var("PLUGIN_MAX_HOLDING_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_MAX_HOLDING_HOURS);
+
+        Self { max_holding_time: Duration::hours(hours) }
+    }
+}
+
+impl ExitRule for MaxHoldingTime {
+    fn name(&self) -> &'static str {
+        "max_holding_time"
+    }
+
+    fn should_exit(&self, trade: &ActiveTrade, _price: f64, now: DateTime<Utc>) -> bool {
+        now - trade.open_timestamp >= self.max_holding_time
+    }
+}
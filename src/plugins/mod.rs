@@ -0,0 +1,2 @@
+#[cfg(feature = "plugin-max-holding-time")]
+pub mod max_holding_time;
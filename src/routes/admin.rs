@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::{get, post}, Extension, Router};
+
+use crate::{api::{cache::get_caches, command::require_command_secret, consistency::{get_consistency, repair_consistency}, feed_quality::get_feed_quality, health::get_mongo_health, readiness::get_readiness}, models::{MongoDBState, MongoPoolMetrics}};
+
+pub fn admin_routes(mongo_state: Arc<MongoDBState>, mongo_pool_metrics: Arc<MongoPoolMetrics>) -> Router {
+    Router::new()
+        .route("/consistency/repair", post(repair_consistency))
+        .route_layer(middleware::from_fn(require_command_secret))
+        .route("/caches", get(get_caches))
+        .route("/consistency", get(get_consistency))
+        .route("/feed_quality", get(get_feed_quality))
+        .route("/mongo", get(get_mongo_health))
+        .route("/readiness", get(get_readiness))
+        .layer(Extension(mongo_state))
+        .layer(Extension(mongo_pool_metrics))
+}
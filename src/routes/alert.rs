@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::alert::get_rejected_alerts, models::MongoDBState};
+
+pub fn alert_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/rejected", get(get_rejected_alerts))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::audit::get_audit_log, models::MongoDBState};
+
+pub fn audit_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", get(get_audit_log))
+        .layer(Extension(mongo_state))
+}
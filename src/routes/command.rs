@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use axum::{routing::{get, post}, Extension, Router};
+
+use crate::{api::command::{get_command, post_command}, models::MongoDBState};
+
+pub fn command_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", post(post_command))
+        .route("/:id", get(get_command))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+
+use crate::api::exchange::get_exchange_profiles;
+
+pub fn exchange_routes() -> Router {
+    Router::new()
+        .route("/", get(get_exchange_profiles))
+}
@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::delete, Extension, Router};
+
+use crate::{api::{command::require_command_secret, experiment::reset_experiment}, models::MongoDBState};
+
+pub fn experiment_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/:name", delete(reset_experiment))
+        .route_layer(middleware::from_fn(require_command_secret))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::funding::{get_funding_history, get_funding_ledger}, models::MongoDBState};
+
+pub fn funding_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/ledger", get(get_funding_ledger))
+        .route("/:pair", get(get_funding_history))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::leader::get_leader_status, models::MongoDBState};
+
+pub fn leader_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", get(get_leader_status))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::leaderboard::get_leaderboard, models::MongoDBState};
+
+pub fn leaderboard_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", get(get_leaderboard))
+        .layer(Extension(mongo_state))
+}
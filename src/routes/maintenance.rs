@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::{delete, get, post}, Extension, Router};
+
+use crate::{api::{command::require_command_secret, maintenance::{add_maintenance_window, delete_maintenance_window, get_maintenance_windows}}, models::MongoDBState};
+
+pub fn maintenance_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", post(add_maintenance_window))
+        .route("/:id", delete(delete_maintenance_window))
+        .route_layer(middleware::from_fn(require_command_secret))
+        .route("/", get(get_maintenance_windows))
+        .layer(Extension(mongo_state))
+}
@@ -1,3 +1,39 @@
+pub mod admin;
+pub mod alert;
+pub mod audit;
+pub mod command;
+pub mod exchange;
+pub mod experiment;
+pub mod funding;
+pub mod leader;
+pub mod leaderboard;
+pub mod maintenance;
+pub mod position;
+pub mod price_alert;
+pub mod report;
+pub mod risk;
+pub mod shard;
+pub mod stats;
+pub mod strategy;
 pub mod trade;
+pub mod watchlist;
 
-pub use trade::trade_routes;
\ No newline at end of file
+pub use admin::admin_routes;
+pub use alert::alert_routes;
+pub use audit::audit_routes;
+pub use command::command_routes;
+pub use exchange::exchange_routes;
+pub use experiment::experiment_routes;
+pub use funding::funding_routes;
+pub use leader::leader_routes;
+pub use leaderboard::leaderboard_routes;
+pub use maintenance::maintenance_routes;
+pub use position::position_routes;
+pub use price_alert::price_alert_routes;
+pub use report::report_routes;
+pub use risk::risk_routes;
+pub use shard::shard_routes;
+pub use stats::stats_routes;
+pub use strategy::strategy_routes;
+pub use trade::trade_routes;
+pub use watchlist::watchlist_routes;
\ No newline at end of file
@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+
+use crate::api::position::get_net_positions;
+
+pub fn position_routes() -> Router {
+    Router::new()
+        .route("/net", get(get_net_positions))
+}
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::{delete, get, post}, Extension, Router};
+
+use crate::{api::{command::require_command_secret, price_alert::{add_price_alert, delete_price_alert, get_price_alerts}}, models::MongoDBState};
+
+pub fn price_alert_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", post(add_price_alert))
+        .route("/:id", delete(delete_price_alert))
+        .route_layer(middleware::from_fn(require_command_secret))
+        .route("/", get(get_price_alerts))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::{export::get_equity_report, statement::{download_account_statement, get_account_statements}, timeseries::{get_equity_history, get_price_ticks}}, models::MongoDBState};
+
+pub fn report_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/equity", get(get_equity_report))
+        .route("/equity/history", get(get_equity_history))
+        .route("/ticks", get(get_price_ticks))
+        .route("/statements", get(get_account_statements))
+        .route("/statements/:account/:month", get(download_account_statement))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use axum::{routing::{get, post}, Extension, Router};
+
+use crate::{api::risk::{get_exposure, stress_test_positions}, models::MongoDBState};
+
+pub fn risk_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/exposure", get(get_exposure))
+        .route("/stress", post(stress_test_positions))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::shard::get_shard_status, models::MongoDBState};
+
+pub fn shard_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", get(get_shard_status))
+        .layer(Extension(mongo_state))
+}
@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use axum::{routing::get, Extension, Router};
+
+use crate::{api::{correlation::get_stats_correlation, stats::{get_stats, get_stats_breakdown, get_stats_comparison, get_stats_heatmap, get_stats_totals}}, models::MongoDBState};
+
+pub fn stats_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", get(get_stats))
+        .route("/breakdown", get(get_stats_breakdown))
+        .route("/heatmap", get(get_stats_heatmap))
+        .route("/compare", get(get_stats_comparison))
+        .route("/correlation", get(get_stats_correlation))
+        .route("/totals", get(get_stats_totals))
+        .layer(Extension(mongo_state))
+}
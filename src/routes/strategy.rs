@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::{get, post, put}, Extension, Router};
+
+use crate::{api::{command::require_command_secret, strategy::{create_strategy_from_template, delete_strategy, get_alert_template, get_strategies, get_strategy, get_strategy_templates, put_strategy}}, models::MongoDBState};
+
+pub fn strategy_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/from_template", post(create_strategy_from_template))
+        .route("/:name", put(put_strategy).delete(delete_strategy))
+        .route_layer(middleware::from_fn(require_command_secret))
+        .route("/", get(get_strategies))
+        .route("/templates", get(get_strategy_templates))
+        .route("/:name", get(get_strategy))
+        .route("/:name/alert_template", get(get_alert_template))
+        .layer(Extension(mongo_state))
+}
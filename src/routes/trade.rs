@@ -1,11 +1,22 @@
 use std::sync::Arc;
 
-use axum::{routing::post, Extension, Router};
+use axum::{middleware, routing::{get, post}, Extension, Router};
 
-use crate::{api::trade::execute_paper_trade, models::MongoDBState};
+use crate::{api::{command::require_command_secret, export::export_closed_trades, import::import_trades, pnl_snapshot::get_trade_pnl_history, trade::{bulk_update_active_trades, close_trade, execute_live_trade, execute_paper_trade, get_active_trades, get_closed_trades}, trade_replay::get_trade_replay, trade_tick::get_trade_ticks}, models::MongoDBState};
 
 pub fn trade_routes(mongo_state: Arc<MongoDBState>) -> Router {
     Router::new()
+        .route("/import", post(import_trades))
+        .route_layer(middleware::from_fn(require_command_secret))
         .route("/execute_paper_trade", post(execute_paper_trade))
+        .route("/execute_live_trade", post(execute_live_trade))
+        .route("/active", get(get_active_trades))
+        .route("/active/bulk_update", post(bulk_update_active_trades))
+        .route("/closed", get(get_closed_trades))
+        .route("/closed/export", get(export_closed_trades))
+        .route("/close/:id", post(close_trade))
+        .route("/:id/ticks", get(get_trade_ticks))
+        .route("/:id/pnl_history", get(get_trade_pnl_history))
+        .route("/:id/replay", get(get_trade_replay))
         .layer(Extension(mongo_state))
 }
\ No newline at end of file
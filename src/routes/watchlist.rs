@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::{get, put}, Extension, Router};
+
+use crate::{api::{command::require_command_secret, watchlist::{get_watchlist, put_watchlist}}, models::MongoDBState};
+
+pub fn watchlist_routes(mongo_state: Arc<MongoDBState>) -> Router {
+    Router::new()
+        .route("/", put(put_watchlist))
+        .route_layer(middleware::from_fn(require_command_secret))
+        .route("/", get(get_watchlist))
+        .layer(Extension(mongo_state))
+}
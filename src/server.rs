@@ -1,20 +1,13 @@
-mod models;
-mod api;
-mod routes;
-mod configs;
-mod constants;
-mod tests;
-
 use std::{net::SocketAddr, sync::Arc};
-use api::{start_price_listener, websocket};
+use tokio::sync::mpsc;
+use tv_trading_bot::api::{command::{start_command_processor, start_telegram_listener}, consensus::start_binance_price_feed, funding::start_funding_rate_poller, grpc::start_grpc_server, leader::start_leader_election, leaderboard::start_leaderboard_aggregator, maintenance::start_maintenance_status_poller, migration::run_migrations, pnl_snapshot::start_trade_pnl_snapshotter, readiness::run_startup_checks, report::start_report_mailer, request::propagate_request_id, scheduler::start_strategy_scheduler, seed::seed_strategies, shard::start_shard_coordinator, snapshot::{shutdown_signal, start_state_snapshotter}, start_price_listener, statement::start_statement_generator, timeseries::{start_equity_snapshotter, start_price_tick_recorder}, trade_tick::start_trade_tick_flusher, version::get_version};
 use axum::{
-    routing::get, Extension, Router
+    middleware, routing::get, Extension, Router
 };
 use dotenvy::dotenv;
-use configs::init_mongo;
-use models::{AppState, MongoDBState};
-use routes::trade_routes;
-use tokio::sync::mpsc;
+use tv_trading_bot::configs::{init_mongo, load_env, init_tls, reload_tls_on_sighup};
+use tv_trading_bot::models::{AppState, MongoDBState};
+use tv_trading_bot::routes::{admin_routes, alert_routes, audit_routes, command_routes, exchange_routes, experiment_routes, funding_routes, leader_routes, leaderboard_routes, maintenance_routes, position_routes, stats_routes, price_alert_routes, report_routes, risk_routes, shard_routes, strategy_routes, trade_routes, watchlist_routes};
 
 /// Checks to see if the server is running
 async fn run_axum() -> &'static str {
@@ -24,35 +17,212 @@ async fn run_axum() -> &'static str {
 #[tokio::main]
 async fn main() {
     dotenv().ok();
+    // resolve `_FILE` variables (e.g. Docker secrets) and validate the configuration before anything reads it
+    load_env();
 
-    let mongo_uri = std::env::var("MONGODB_URI").expect("MONGO_URI must be set");
-    let mongo_client = init_mongo(&mongo_uri).await.expect("Failed to initialize MongoDB client");
+    let mongo_uri = std::env::var("MONGODB_URI").expect("MONGODB_URI must be set");
+    let (mongo_client, mongo_pool_metrics) = init_mongo(&mongo_uri).await.expect("Failed to initialize MongoDB client");
     // initialize a mongo state (with the required collections) with the initialized client
     // wrap in an Arc again because the struct itself isn't wrapped in an Arc even if the cloned client is
     let mongo_state = Arc::new(MongoDBState::new(mongo_client.clone()));
 
+    // bring the stored documents up to date with the current schema before any of them are loaded
+    run_migrations(&mongo_state).await.expect("Failed to apply database migrations");
+
+    // validate the configuration and the database, and refuse to start if a critical check fails
+    let readiness = run_startup_checks(&mongo_state).await;
+    readiness.print();
+
+    if !readiness.ready {
+        panic!("A critical startup check failed, refusing to start");
+    }
+
+    // channel to change the price feed's subscriptions at runtime
+    let (ws_command_tx, ws_command_rx) = mpsc::unbounded_channel();
+
     // initialize and build an app state
-    let app_state = Arc::new(AppState::new(mongo_state.clone()));
+    let app_state = Arc::new(AppState::new(mongo_state.clone(), ws_command_tx));
+
+    // restore the auxiliary in-memory state (e.g. latest prices) from before the last shutdown
+    app_state.restore_latest_snapshot().await;
 
     // preload any existing trades from the database into in-memory
     if let Ok(existing_trades) = mongo_state.fetch_active_trades(None, 1, 1000).await {
         let mut map = app_state.active_trades.lock().unwrap();
         for t in existing_trades {
-            map.insert(t.id.clone(), t);
+            app_state.subscribe_pair(&t.pair);
+            map.insert(t.id, t);
         }
     }
 
+    // subscribe to the pairs on the watchlist
+    match mongo_state.fetch_watchlist().await {
+        Ok(watchlist) => {
+            for entry in watchlist {
+                app_state.subscribe_pair(&entry.pair);
+            }
+        }
+        Err(err) => eprintln!("Failed to fetch watchlist: {}", err),
+    }
+
+    // preload the price alerts that haven't triggered yet into in-memory
+    match mongo_state.fetch_price_alerts(true).await {
+        Ok(price_alerts) => {
+            let mut map = app_state.price_alerts.lock().unwrap();
+            for alert in price_alerts {
+                app_state.subscribe_pair(&alert.pair);
+                map.insert(alert.id, alert);
+            }
+        }
+        Err(err) => eprintln!("Failed to fetch price alerts: {}", err),
+    }
+
     let app_state_for_ws = app_state.clone();
     tokio::spawn(async move {
-        start_price_listener(app_state_for_ws).await;
+        start_price_listener(app_state_for_ws, ws_command_rx).await;
+    });
+
+    let app_state_for_binance = app_state.clone();
+    tokio::spawn(async move {
+        start_binance_price_feed(app_state_for_binance).await;
+    });
+
+    let app_state_for_leader_election = app_state.clone();
+    tokio::spawn(async move {
+        start_leader_election(app_state_for_leader_election).await;
+    });
+
+    let app_state_for_shard_coordinator = app_state.clone();
+    tokio::spawn(async move {
+        start_shard_coordinator(app_state_for_shard_coordinator).await;
+    });
+
+    let app_state_for_commands = app_state.clone();
+    tokio::spawn(async move {
+        start_command_processor(app_state_for_commands).await;
+    });
+
+    let app_state_for_telegram = app_state.clone();
+    tokio::spawn(async move {
+        start_telegram_listener(app_state_for_telegram).await;
+    });
+
+    let app_state_for_reports = app_state.clone();
+    tokio::spawn(async move {
+        start_report_mailer(app_state_for_reports).await;
+    });
+
+    let app_state_for_statements = app_state.clone();
+    tokio::spawn(async move {
+        start_statement_generator(app_state_for_statements).await;
+    });
+
+    let app_state_for_snapshots = app_state.clone();
+    tokio::spawn(async move {
+        start_state_snapshotter(app_state_for_snapshots).await;
+    });
+
+    let app_state_for_ticks = app_state.clone();
+    tokio::spawn(async move {
+        start_price_tick_recorder(app_state_for_ticks).await;
+    });
+
+    let app_state_for_equity = app_state.clone();
+    tokio::spawn(async move {
+        start_equity_snapshotter(app_state_for_equity).await;
+    });
+
+    let app_state_for_trade_ticks = app_state.clone();
+    tokio::spawn(async move {
+        start_trade_tick_flusher(app_state_for_trade_ticks).await;
+    });
+
+    let app_state_for_pnl_snapshots = app_state.clone();
+    tokio::spawn(async move {
+        start_trade_pnl_snapshotter(app_state_for_pnl_snapshots).await;
+    });
+
+    let app_state_for_leaderboard = app_state.clone();
+    tokio::spawn(async move {
+        start_leaderboard_aggregator(app_state_for_leaderboard).await;
+    });
+
+    let mongo_state_for_funding = mongo_state.clone();
+    let funding_exchange = app_state.funding_exchange.clone();
+    tokio::spawn(async move {
+        start_funding_rate_poller(mongo_state_for_funding, funding_exchange).await;
     });
 
+    let mongo_state_for_maintenance = mongo_state.clone();
+    let maintenance_client = app_state.network.http_client();
+    let clock_for_maintenance = app_state.clock.clone();
+    tokio::spawn(async move {
+        start_maintenance_status_poller(mongo_state_for_maintenance, maintenance_client, clock_for_maintenance).await;
+    });
+
+    let mongo_state_for_scheduler = mongo_state.clone();
+    let clock_for_scheduler = app_state.clock.clone();
+    tokio::spawn(async move {
+        start_strategy_scheduler(mongo_state_for_scheduler, clock_for_scheduler).await;
+    });
+
+    let app_state_for_seeding = app_state.clone();
+    tokio::spawn(async move {
+        seed_strategies(app_state_for_seeding).await;
+    });
+
+    let app_state_for_grpc = app_state.clone();
+    tokio::spawn(async move {
+        start_grpc_server(app_state_for_grpc).await;
+    });
+
+    let app_state_for_shutdown = app_state.clone();
+
     let app = Router::new()
         .route("/", get(run_axum))
+        .route("/version", get(get_version))
         // add trade routes
         .nest("/trade", trade_routes(mongo_state.clone()))
+        // add stats routes
+        .nest("/stats", stats_routes(mongo_state.clone()))
+        // add funding routes
+        .nest("/funding", funding_routes(mongo_state.clone()))
+        // add exchange profile routes
+        .nest("/exchanges", exchange_routes())
+        // add maintenance routes
+        .nest("/maintenance", maintenance_routes(mongo_state.clone()))
+        // add experiment routes
+        .nest("/experiments", experiment_routes(mongo_state.clone()))
+        // add strategy routes
+        .nest("/strategies", strategy_routes(mongo_state.clone()))
+        // add audit log routes
+        .nest("/audit", audit_routes(mongo_state.clone()))
+        // add watchlist routes
+        .nest("/watchlist", watchlist_routes(mongo_state.clone()))
+        // add price alert routes
+        .nest("/price_alerts", price_alert_routes(mongo_state.clone()))
+        // add alert routes
+        .nest("/alerts", alert_routes(mongo_state.clone()))
+        // add leader routes
+        .nest("/leader", leader_routes(mongo_state.clone()))
+        // add leaderboard routes
+        .nest("/leaderboard", leaderboard_routes(mongo_state.clone()))
+        // add shard routes
+        .nest("/shard", shard_routes(mongo_state.clone()))
+        // add command routes
+        .nest("/commands", command_routes(mongo_state.clone()))
+        // add report routes
+        .nest("/reports", report_routes(mongo_state.clone()))
+        // add risk routes
+        .nest("/risk", risk_routes(mongo_state.clone()))
+        // add position routes
+        .nest("/positions", position_routes())
+        // add admin routes
+        .nest("/admin", admin_routes(mongo_state.clone(), mongo_pool_metrics))
         .layer(Extension(app_state))
-        .layer(Extension(mongo_state));
+        .layer(Extension(mongo_state))
+        // assign every request an ID, returned in the `X-Request-Id` header
+        .layer(middleware::from_fn(propagate_request_id));
 
     let port = std::env::var("PORT")
         .unwrap_or_else(|_| "3000".to_string())
@@ -62,8 +232,33 @@ async fn main() {
     // bind to 0.0.0.0:<PORT or 3000>
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
 
+    // serve over HTTPS if a certificate is configured, so that the webhook can be exposed without a reverse proxy
+    if let Some((tls_config, cert_path, key_path)) = init_tls().await {
+        tokio::spawn(reload_tls_on_sighup(tls_config.clone(), cert_path, key_path));
+
+        let handle = axum_server::Handle::new();
+        let handle_for_shutdown = handle.clone();
+        tokio::spawn(async move {
+            shutdown_signal(app_state_for_shutdown).await;
+            handle_for_shutdown.graceful_shutdown(None);
+        });
+
+        println!("Server running on: https://{}", addr);
+
+        axum_server::bind_rustls(addr, tls_config)
+            .handle(handle)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+
+        return;
+    }
+
     println!("Server running on: http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(app_state_for_shutdown))
+        .await
+        .unwrap();
 }
\ No newline at end of file
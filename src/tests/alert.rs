@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+use crate::{api::alert::{alert_idempotency_key, check_alert_timestamp, is_stale_alert_claim}, constants::ALERT_CLAIM_STALE_SECS, models::{tradingview::TradingViewAlert, AlertClaim, AlertOutcome}, tests::app_state};
+
+fn payload(secret: &str, idempotency_key: Option<&str>) -> serde_json::Value {
+    json!({
+        "name": "breakout",
+        "signal": "buy",
+        "pair": "SOLUSDT",
+        "price": 150.0,
+        "take_profit": null,
+        "stop_loss": null,
+        "timestamp": "2025-01-01T12:00:00Z",
+        "secret": secret,
+        "idempotency_key": idempotency_key,
+    })
+}
+
+fn alert(payload: &serde_json::Value) -> TradingViewAlert {
+    serde_json::from_value(payload.clone()).unwrap()
+}
+
+#[test]
+pub fn redelivered_alerts_share_key() {
+    let first = payload("secret", None);
+    let retry = payload("another secret", None);
+
+    // the secret is ignored, so that a key never depends on it
+    assert_eq!(alert_idempotency_key(&alert(&first), &first), alert_idempotency_key(&alert(&retry), &retry));
+    // the SHA-256 hash is stable across builds, unlike the std hasher
+    assert_eq!(alert_idempotency_key(&alert(&first), &first).len(), "breakout:".len() + 64);
+}
+
+#[test]
+pub fn alerts_with_different_timestamps_or_contents_have_different_keys() {
+    let first = payload("secret", None);
+    let mut later = payload("secret", None);
+    later["timestamp"] = json!("2025-01-01T12:01:00Z");
+    let mut other = payload("secret", None);
+    other["price"] = json!(151.0);
+
+    assert_ne!(alert_idempotency_key(&alert(&first), &first), alert_idempotency_key(&alert(&later), &later));
+    assert_ne!(alert_idempotency_key(&alert(&first), &first), alert_idempotency_key(&alert(&other), &other));
+}
+
+#[test]
+pub fn explicit_idempotency_key_takes_precedence() {
+    let first = payload("secret", Some("order-1"));
+    let mut retry = payload("secret", Some("order-1"));
+    retry["price"] = json!(151.0);
+
+    assert_eq!(alert_idempotency_key(&alert(&first), &first), "breakout:order-1");
+    assert_eq!(alert_idempotency_key(&alert(&first), &first), alert_idempotency_key(&alert(&retry), &retry));
+}
+
+#[test]
+pub fn alerts_outside_timestamp_window_are_rejected() {
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+    assert!(check_alert_timestamp(now - chrono::Duration::seconds(30), now, 60).is_ok());
+    // a replayed capture
+    assert!(check_alert_timestamp(now - chrono::Duration::seconds(61), now, 60).is_err());
+    // small clock skew is tolerated, but not alerts from the future
+    assert!(check_alert_timestamp(now + chrono::Duration::seconds(3), now, 60).is_ok());
+    assert!(check_alert_timestamp(now + chrono::Duration::minutes(5), now, 60).is_err());
+}
+
+#[test]
+pub fn only_unfinished_claims_become_stale() {
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let claim = AlertClaim {
+        id: "breakout:order-1".to_string(),
+        holder: "instance-1".to_string(),
+        claimed_timestamp: now - chrono::Duration::seconds(ALERT_CLAIM_STALE_SECS),
+        claimed_at: None,
+        outcome: None,
+    };
+
+    assert!(is_stale_alert_claim(&claim, now));
+    assert!(!is_stale_alert_claim(&claim, now - chrono::Duration::seconds(1)));
+
+    // completed claims are replayed until they expire, no matter how old they are
+    let completed = AlertClaim { outcome: Some(AlertOutcome { status_code: 200, code: None, message: "opened".to_string() }), ..claim };
+    assert!(!is_stale_alert_claim(&completed, now + chrono::Duration::days(1)));
+}
+
+#[tokio::test]
+pub async fn concurrent_alerts_of_a_strategy_on_a_pair_are_serialized() {
+    let app_state = app_state().await;
+
+    let guard = app_state.lock_alert("breakout", "SOLUSDT").await;
+
+    // other strategies and pairs aren't blocked
+    assert!(tokio::time::timeout(Duration::from_millis(50), app_state.lock_alert("breakout", "BTCUSDT")).await.is_ok());
+    assert!(tokio::time::timeout(Duration::from_millis(50), app_state.lock_alert("reversal", "SOLUSDT")).await.is_ok());
+
+    // the same strategy on the same pair waits until the lock is released
+    assert!(tokio::time::timeout(Duration::from_millis(50), app_state.lock_alert("breakout", "SOLUSDT")).await.is_err());
+
+    drop(guard);
+
+    assert!(tokio::time::timeout(Duration::from_millis(50), app_state.lock_alert("breakout", "SOLUSDT")).await.is_ok());
+
+    // locks that are no longer held are dropped
+    let _guard = app_state.lock_alert("breakout", "ETHUSDT").await;
+    assert_eq!(app_state.alert_locks.lock().unwrap().len(), 1);
+}
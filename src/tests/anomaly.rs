@@ -0,0 +1,25 @@
+use std::collections::VecDeque;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::api::anomaly::{calc_price_deviation_percentage, record_alert_timestamp};
+
+#[test]
+pub fn price_deviation_from_market() {
+    assert!((calc_price_deviation_percentage(105.0, 100.0) - 5.0).abs() < 1e-9);
+    assert!((calc_price_deviation_percentage(90.0, 100.0) - 10.0).abs() < 1e-9);
+    assert_eq!(calc_price_deviation_percentage(100.0, 100.0), 0.0);
+}
+
+#[test]
+pub fn alert_frequency_within_window() {
+    let start = Utc.with_ymd_and_hms(2025, 1, 6, 12, 0, 0).unwrap();
+    let window = Duration::seconds(60);
+    let mut history = VecDeque::new();
+
+    assert_eq!(record_alert_timestamp(&mut history, start, window), 1);
+    assert_eq!(record_alert_timestamp(&mut history, start + Duration::seconds(30), window), 2);
+    // the first alert falls out of the window
+    assert_eq!(record_alert_timestamp(&mut history, start + Duration::seconds(60), window), 2);
+    assert_eq!(record_alert_timestamp(&mut history, start + Duration::seconds(200), window), 1);
+}
@@ -0,0 +1,60 @@
+use std::sync::Mutex;
+
+use chrono::DateTime;
+use mongodb::bson::oid::ObjectId;
+
+use crate::models::{CacheReport, LruCache, TradeTickRecorder};
+
+#[test]
+pub fn least_recently_written_entries_are_evicted() {
+    let mut cache = LruCache::new(2);
+
+    assert_eq!(cache.insert("BTC-USD", 100.0), None);
+    cache.insert("ETH-USD", 10.0);
+    // reads don't count as a use
+    assert_eq!(cache.get("BTC-USD"), Some(&100.0));
+    cache.insert("SOL-USD", 1.0);
+
+    assert_eq!(cache.get("BTC-USD"), None);
+    assert_eq!(cache.len(), 2);
+
+    // writes do
+    assert_eq!(cache.insert("ETH-USD", 11.0), Some(10.0));
+    cache.insert("BNB-USD", 5.0);
+
+    assert_eq!(cache.get("SOL-USD"), None);
+    assert_eq!(cache.get("ETH-USD"), Some(&11.0));
+    assert_eq!(cache.report("prices"), CacheReport { name: "prices", len: 2, capacity: 2, evictions: 2 });
+}
+
+#[test]
+pub fn cached_values_are_modified_in_place() {
+    let mut cache: LruCache<String, Vec<i64>> = LruCache::new(2);
+
+    cache.get_or_insert_with("breakout".to_string(), Vec::new).push(1);
+    cache.get_or_insert_with("reversal".to_string(), Vec::new).push(2);
+    cache.get_or_insert_with("breakout".to_string(), Vec::new).push(3);
+    cache.get_or_insert_with("scalp".to_string(), Vec::new).push(4);
+
+    assert_eq!(cache.get("breakout"), Some(&vec![1, 3]));
+    assert_eq!(cache.get("reversal"), None);
+
+    // least recently written first
+    assert_eq!(cache.take(), vec![("breakout".to_string(), vec![1, 3]), ("scalp".to_string(), vec![4])]);
+    assert!(cache.is_empty());
+    assert_eq!(cache.evictions, 1);
+}
+
+#[test]
+pub fn trade_tick_buffer_drops_the_oldest_ticks() {
+    let recorder = TradeTickRecorder { enabled: true, buffer: Mutex::new(LruCache::new(2)) };
+    let trade_id = ObjectId::new();
+
+    for second in 0..3 {
+        recorder.record(&[trade_id], 100.0 + second as f64, DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap());
+    }
+
+    let ticks = recorder.take();
+    assert_eq!(ticks.iter().map(|tick| tick.price).collect::<Vec<_>>(), vec![101.0, 102.0]);
+    assert_eq!(recorder.buffer.lock().unwrap().evictions, 1);
+}
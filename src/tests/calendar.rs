@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+use crate::{api::{calc_final_funding_fees, calendar::{parse_market_calendars, parse_market_holidays}}, models::{FundingSchedule, MarketCalendar, MarketHours, TradingCalendar}};
+
+fn at(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, min, 0).unwrap()
+}
+
+fn hours(calendar: MarketCalendar) -> MarketHours {
+    MarketHours { calendar, holidays: HashSet::new() }
+}
+
+#[test]
+pub fn forex_markets_close_over_the_weekend() {
+    let forex = hours(MarketCalendar::Forex);
+
+    // 2025-01-03 is a Friday
+    assert!(forex.is_open(at(2025, 1, 3, 21, 59)));
+    assert!(!forex.is_open(at(2025, 1, 3, 22, 0)));
+    assert!(!forex.is_open(at(2025, 1, 4, 12, 0)));
+    assert!(!forex.is_open(at(2025, 1, 5, 21, 59)));
+    assert!(forex.is_open(at(2025, 1, 5, 22, 0)));
+    assert!(forex.is_open(at(2025, 1, 6, 3, 0)));
+
+    // continuous markets never close
+    assert!(hours(MarketCalendar::Continuous).is_open(at(2025, 1, 4, 12, 0)));
+}
+
+#[test]
+pub fn us_equities_follow_new_york_time() {
+    let us_equities = hours(MarketCalendar::UsEquities);
+
+    // 09:30 New York time is 14:30 UTC in winter and 13:30 UTC in summer
+    assert!(!us_equities.is_open(at(2025, 1, 6, 14, 29)));
+    assert!(us_equities.is_open(at(2025, 1, 6, 14, 30)));
+    assert!(us_equities.is_open(at(2025, 7, 7, 13, 30)));
+    assert!(!us_equities.is_open(at(2025, 1, 6, 21, 0)));
+    assert!(!us_equities.is_open(at(2025, 1, 4, 15, 0)));
+
+    let mut holidays = HashSet::new();
+    holidays.insert(NaiveDate::from_ymd_opt(2025, 12, 25).unwrap());
+
+    let us_equities = MarketHours { calendar: MarketCalendar::UsEquities, holidays };
+    assert!(!us_equities.is_open(at(2025, 12, 25, 15, 0)));
+    assert!(us_equities.is_open(at(2025, 12, 26, 15, 0)));
+}
+
+#[test]
+pub fn only_tradfi_symbols_follow_a_calendar() {
+    let calendar = TradingCalendar {
+        symbols: parse_market_calendars("eurusd:forex, SPX500USD:us_equities").unwrap(),
+        holidays: parse_market_holidays("2025-12-25").unwrap(),
+    };
+
+    assert!(calendar.is_tradfi("EURUSD"));
+    assert!(!calendar.is_tradfi("BTCUSDT"));
+    assert!(!calendar.is_open("SPX500USD", at(2025, 12, 25, 15, 0)));
+    assert!(!calendar.is_open("EURUSD", at(2025, 12, 25, 15, 0)));
+
+    // holidays don't close crypto markets
+    assert!(calendar.is_open("BTCUSDT", at(2025, 12, 25, 15, 0)));
+    assert!(calendar.market_hours("BTCUSDT").holidays.is_empty());
+
+    assert!(parse_market_calendars("EURUSD").is_err());
+    assert!(parse_market_calendars("EURUSD:lunar").is_err());
+    assert!(parse_market_holidays("25/12/2025").is_err());
+}
+
+#[test]
+pub fn no_funding_is_charged_while_the_market_is_closed() {
+    let schedule = FundingSchedule { interval_hours: 8 };
+
+    // from Friday 00:00 to Monday 00:00 UTC: 9 funding times, of which only Friday's 08:00 and 16:00
+    // and Monday's 00:00 are while the forex market is open
+    let open = at(2025, 1, 3, 0, 0);
+    let close = at(2025, 1, 6, 0, 0);
+
+    assert_eq!(schedule.count_funding_times(open, close), 9);
+    assert_eq!(schedule.count_open_funding_times(open, close, &hours(MarketCalendar::Forex)), 3);
+
+    let continuous = calc_final_funding_fees(open, close, 1000.0, &schedule, &hours(MarketCalendar::Continuous));
+    let forex = calc_final_funding_fees(open, close, 1000.0, &schedule, &hours(MarketCalendar::Forex));
+
+    assert!((forex - continuous * 3.0 / 9.0).abs() < 1e-9);
+}
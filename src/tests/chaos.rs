@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+
+use crate::{configs::{is_transient_error, retry_transient_write}, constants::DEFAULT_CHAOS_FEED_GAP_SECS, models::ChaosMode};
+
+fn chaos_mode(vars: &[(&str, &str)]) -> ChaosMode {
+    let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+
+    ChaosMode::from_lookup(|name| vars.get(name).map(|value| value.to_string()))
+}
+
+#[test]
+pub fn chaos_mode_clamps_and_ignores_invalid_rates() {
+    let chaos = chaos_mode(&[
+        ("CHAOS_MODE", "true"),
+        ("CHAOS_DB_WRITE_FAILURE_RATE", "0.25"),
+        ("CHAOS_ORDER_REJECTION_RATE", "3"),
+        ("CHAOS_FEED_GAP_RATE", "often"),
+    ]);
+
+    assert!(chaos.enabled);
+    assert_eq!(chaos.db_write_failure_rate, 0.25);
+    assert_eq!(chaos.order_rejection_rate, 1.0);
+    assert_eq!(chaos.feed_gap_rate, 0.0);
+    assert_eq!(chaos.feed_gap_secs, DEFAULT_CHAOS_FEED_GAP_SECS);
+}
+
+#[test]
+pub fn chaos_mode_never_injects_failures_when_disabled() {
+    let chaos = chaos_mode(&[
+        ("CHAOS_ORDER_REJECTION_RATE", "1"),
+        ("CHAOS_FEED_GAP_RATE", "1"),
+    ]);
+
+    assert!(!chaos.enabled);
+    assert!(!chaos.rejects_order());
+    assert!(!chaos.is_feed_gap(Utc::now()));
+}
+
+#[test]
+pub fn chaos_mode_injects_failures_at_certain_rates() {
+    let chaos = chaos_mode(&[("CHAOS_MODE", "true"), ("CHAOS_ORDER_REJECTION_RATE", "1")]);
+
+    assert!(chaos.roll(1.0));
+    assert!(!chaos.roll(0.0));
+    assert!(chaos.rejects_order());
+    assert!(!chaos.is_feed_gap(Utc::now()));
+}
+
+#[test]
+pub fn feed_gap_lasts_for_the_configured_duration() {
+    let mut chaos = chaos_mode(&[("CHAOS_MODE", "true"), ("CHAOS_FEED_GAP_RATE", "1"), ("CHAOS_FEED_GAP_SECS", "10")]);
+    let now = Utc::now();
+
+    assert!(chaos.is_feed_gap(now));
+
+    // ticks within the gap are dropped even once no new gap would start
+    chaos.feed_gap_rate = 0.0;
+    assert!(chaos.is_feed_gap(now + Duration::seconds(9)));
+    assert!(!chaos.is_feed_gap(now + Duration::seconds(10)));
+}
+
+#[tokio::test]
+pub async fn injected_write_failures_are_transient_and_retried() {
+    let chaos = chaos_mode(&[("CHAOS_MODE", "true"), ("CHAOS_DB_WRITE_FAILURE_RATE", "1")]);
+
+    let err = chaos.disrupt_write("test", async { Ok(()) }).await.unwrap_err();
+    assert!(is_transient_error(&err));
+
+    // every attempt fails, so the error surfaces once the retries are exhausted
+    let mut attempts = 0;
+    let result = retry_transient_write("test", || {
+        attempts += 1;
+        chaos.disrupt_write("test", async { Ok(()) })
+    }).await;
+
+    assert!(result.is_err());
+    assert!(attempts > 1);
+}
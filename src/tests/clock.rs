@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+
+use crate::{api::{build_closed_paper_trade, build_paper_trade, calc_final_funding_fees, calc_notional_value}, models::{tradingview::TradingViewAlert, Clock, ExchangeProfile, MarketHours, SimulatedClock, StrategyParameters}, tests::app_state};
+
+#[test]
+pub fn simulated_clock_only_moves_when_set_or_advanced() {
+    let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let clock = SimulatedClock::new(start);
+
+    assert_eq!(clock.now(), start);
+    assert_eq!(clock.advance(Duration::hours(8)), start + Duration::hours(8));
+    assert_eq!(clock.now(), start + Duration::hours(8));
+
+    clock.set(start);
+    assert_eq!(clock.now(), start);
+}
+
+#[tokio::test]
+pub async fn paper_trades_are_timed_by_the_clock() {
+    let clock = Arc::new(SimulatedClock::new(Utc.with_ymd_and_hms(2025, 1, 1, 7, 0, 0).unwrap()));
+    let app_state = app_state().await.with_clock(clock.clone());
+
+    let alert: TradingViewAlert = serde_json::from_value(json!({
+        "name": "breakout",
+        "signal": "buy",
+        "pair": "BTCUSDT",
+        "price": 100000.0,
+        "take_profit": null,
+        "stop_loss": null,
+        "timestamp": "2025-01-01T07:00:00Z",
+        "secret": "secret",
+    })).unwrap();
+
+    let trade = build_paper_trade(alert, &StrategyParameters::default(), 1.0, &app_state.rounding, false, "request", app_state.clock.now());
+    assert_eq!(trade.open_timestamp, clock.now());
+
+    // held across the 08:00 and 16:00 funding times
+    let close_timestamp = clock.advance(Duration::minutes(10 * 60 + 30));
+    let closed_trade = build_closed_paper_trade(&app_state, &trade, 101000.0);
+
+    let average_notional_value = (calc_notional_value(trade.quantity, 100000.0, &trade.contract_type) + calc_notional_value(trade.quantity, 101000.0, &trade.contract_type)) / 2.0;
+
+    assert_eq!(closed_trade.close_timestamp, close_timestamp);
+    assert_eq!(closed_trade.funding_fees, calc_final_funding_fees(trade.open_timestamp, close_timestamp, average_notional_value, &ExchangeProfile::resolve(None).funding_schedule, &MarketHours::default()));
+    assert!(closed_trade.funding_fees > 0.0);
+}
+
+#[tokio::test]
+pub async fn state_snapshots_are_timed_by_the_clock() {
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 7, 0, 0).unwrap();
+    let app_state = app_state().await.with_clock(Arc::new(SimulatedClock::new(now)));
+
+    assert_eq!(app_state.to_snapshot().timestamp, now);
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use axum::{body::Body, http::{HeaderMap, HeaderValue, Request, StatusCode}, Extension};
+use mongodb::bson::{doc, oid::ObjectId};
+use tower::ServiceExt;
+
+use crate::{api::{command::{has_command_secret, is_valid_command_secret, parse_bot_command}, experiment::experiment_paper_trade_filter}, constants::COMMAND_SECRET_HEADER, models::{BotCommand, PnlPeriod}, routes::strategy_routes, tests::app_state};
+
+#[test]
+pub fn parse_commands() {
+    let trade_id = ObjectId::new();
+
+    assert_eq!(parse_bot_command(&format!("/close {}", trade_id)), Ok(BotCommand::Close { trade_id }));
+    assert_eq!(parse_bot_command("/pause"), Ok(BotCommand::Pause { strategy: None }));
+    assert_eq!(parse_bot_command("/resume  breakout "), Ok(BotCommand::Resume { strategy: Some("breakout".to_string()) }));
+    assert_eq!(parse_bot_command("/pnl"), Ok(BotCommand::Pnl { period: PnlPeriod::Today }));
+    assert_eq!(parse_bot_command("/pnl Week"), Ok(BotCommand::Pnl { period: PnlPeriod::Week }));
+    // Telegram appends the bot's username in group chats
+    assert_eq!(parse_bot_command("/status@tv_trading_bot"), Ok(BotCommand::Status));
+}
+
+#[test]
+pub fn invalid_commands_return_usage() {
+    assert!(parse_bot_command("/close").is_err());
+    assert!(parse_bot_command("/close not-an-id").is_err());
+    assert!(parse_bot_command("/pnl yesterday").is_err());
+    assert!(parse_bot_command("/buy SOLUSDT").is_err());
+}
+
+#[test]
+pub fn mutating_requests_need_the_command_secret() {
+    // nothing is authorized unless a secret is configured
+    assert!(!is_valid_command_secret(None, ""));
+    assert!(!is_valid_command_secret(Some(""), ""));
+    assert!(is_valid_command_secret(Some("secret"), "secret"));
+
+    let mut headers = HeaderMap::new();
+    assert!(!has_command_secret(&headers, Some("secret")));
+
+    headers.insert(COMMAND_SECRET_HEADER, HeaderValue::from_static("wrong"));
+    assert!(!has_command_secret(&headers, Some("secret")));
+
+    headers.insert(COMMAND_SECRET_HEADER, HeaderValue::from_static("secret"));
+    assert!(has_command_secret(&headers, Some("secret")));
+}
+
+#[test]
+pub fn experiment_resets_only_delete_paper_trades() {
+    assert_eq!(experiment_paper_trade_filter("v2"), doc! { "experiment": "v2", "kind": "paper" });
+}
+
+#[tokio::test]
+pub async fn mutating_routes_are_guarded_while_reads_are_not() {
+    let mut app_state = app_state().await;
+    app_state.command_secret = Some("secret".to_string());
+    let app_state = Arc::new(app_state);
+    let router = strategy_routes(app_state.mongo_state.clone()).layer(Extension(app_state));
+
+    let request = |method: &str, uri: &str, secret: Option<&str>| {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(secret) = secret {
+            builder = builder.header(COMMAND_SECRET_HEADER, secret);
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let response = router.clone().oneshot(request("DELETE", "/breakout", None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = router.clone().oneshot(request("PUT", "/breakout", Some("wrong"))).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = router.oneshot(request("GET", "/templates", None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,51 @@
+use chrono::{TimeZone, Utc};
+
+use crate::{api::conflict::find_conflicting_trades, models::{ActiveTrade, ConflictPolicy, ContractType, PositionConflictGuard, TradeDirection}, tests::active_trade};
+
+fn trade(alert_name: &str, pair: &str, direction: TradeDirection, exchange: Option<&str>) -> ActiveTrade {
+    ActiveTrade {
+        alert_name: alert_name.to_string(),
+        pair: pair.to_string(),
+        direction,
+        open_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        exchange: exchange.map(str::to_string),
+        ..active_trade()
+    }
+}
+
+#[test]
+pub fn only_opposing_trades_of_other_strategies_on_the_same_account_conflict() {
+    let trades = [
+        trade("a", "BTCUSDT", TradeDirection::Short, Some("binance")),
+        // the strategy's own trade is closed before the new one is opened
+        trade("b", "BTCUSDT", TradeDirection::Short, Some("binance")),
+        trade("c", "BTCUSDT", TradeDirection::Long, Some("binance")),
+        trade("d", "ETHUSDT", TradeDirection::Short, Some("binance")),
+        trade("e", "BTCUSDT", TradeDirection::Short, Some("bybit")),
+    ];
+
+    let conflicts = find_conflicting_trades(&trades, "b", "btcusdt", "Binance", &ContractType::Linear, &TradeDirection::Long);
+
+    assert_eq!(conflicts.iter().map(|trade| trade.alert_name.as_str()).collect::<Vec<_>>(), vec!["a"]);
+    assert!(find_conflicting_trades(&trades, "b", "BTCUSDT", "binance", &ContractType::Inverse, &TradeDirection::Long).is_empty());
+}
+
+#[test]
+pub fn the_policy_decides_whether_conflicting_trades_are_opened() {
+    let conflicting = trade("a", "BTCUSDT", TradeDirection::Short, None);
+    let conflicts = [&conflicting];
+
+    let guard = |policy| PositionConflictGuard { policy, hedge_mode_exchanges: vec!["binance".to_string()] };
+
+    assert!(guard(ConflictPolicy::Allow).check("bybit", &conflicts).is_ok());
+    assert!(guard(ConflictPolicy::Net).check("bybit", &conflicts).is_ok());
+    assert!(guard(ConflictPolicy::Block).check("binance", &conflicts).is_err());
+    assert!(guard(ConflictPolicy::RequireHedgeMode).check("Binance", &conflicts).is_ok());
+    assert!(guard(ConflictPolicy::RequireHedgeMode).check("bybit", &conflicts).is_err());
+
+    // trades without conflicts are always opened
+    assert!(guard(ConflictPolicy::Block).check("binance", &[]).is_ok());
+
+    assert_eq!("require_hedge_mode".parse::<ConflictPolicy>(), Ok(ConflictPolicy::RequireHedgeMode));
+    assert!("hedge_everything".parse::<ConflictPolicy>().is_err());
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+
+use crate::{api::consensus::{calc_index_price, calc_median, parse_index_weights}, constants::CONSENSUS_MAX_PRICE_AGE_SECS, models::{PriceConsensus, PriceVenue}};
+
+#[test]
+pub fn median_of_prices() {
+    assert_eq!(calc_median(&mut []), None);
+    assert_eq!(calc_median(&mut [3.0, 1.0, 2.0]), Some(2.0));
+    assert_eq!(calc_median(&mut [4.0, 1.0, 2.0, 3.0]), Some(2.5));
+}
+
+#[test]
+pub fn ticks_deviating_from_the_other_venues_are_rejected() {
+    let consensus = PriceConsensus { secondary_venues: vec![PriceVenue::Binance], deviation_percentage: 2.0, ..Default::default() };
+    let now = Utc::now();
+
+    // no other venue has a price yet
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Binance, 100.0, now), Ok(()));
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Coinbase, 101.0, now), Ok(()));
+
+    // a flash crash print on Coinbase
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Coinbase, 90.0, now), Err(100.0));
+    // the rejected tick doesn't count towards the consensus
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Binance, 102.5, now), Ok(()));
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Binance, 110.0, now), Err(101.0));
+
+    // other products and stale prices aren't compared
+    assert_eq!(consensus.submit("ETH-USDT", PriceVenue::Coinbase, 90.0, now), Ok(()));
+    let later = now + Duration::seconds(CONSENSUS_MAX_PRICE_AGE_SECS + 1);
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Coinbase, 90.0, later), Ok(()));
+}
+
+#[test]
+pub fn consensus_is_disabled_without_secondary_venues() {
+    let consensus = PriceConsensus { deviation_percentage: 2.0, ..Default::default() };
+    let now = Utc::now();
+
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Coinbase, 100.0, now), Ok(()));
+    assert_eq!(consensus.submit("BTC-USDT", PriceVenue::Coinbase, 50.0, now), Ok(()));
+    assert!(consensus.prices.lock().unwrap().is_empty());
+    assert_eq!("Binance".parse::<PriceVenue>(), Ok(PriceVenue::Binance));
+    assert!("kraken".parse::<PriceVenue>().is_err());
+}
+
+#[test]
+pub fn index_price_weights_the_venues() {
+    let prices = [(PriceVenue::Coinbase, 100.0), (PriceVenue::Binance, 104.0)];
+
+    assert_eq!(calc_index_price(&prices, &HashMap::new()), Some(102.0));
+
+    let weights = parse_index_weights("coinbase:3, binance:1").unwrap();
+    assert_eq!(calc_index_price(&prices, &weights), Some(101.0));
+    // the weights are normalized over the venues with a price
+    assert_eq!(calc_index_price(&prices[1..], &weights), Some(104.0));
+    assert_eq!(calc_index_price(&[], &weights), None);
+
+    // venues without a weight don't count
+    let weights = parse_index_weights("binance:1").unwrap();
+    assert_eq!(calc_index_price(&prices, &weights), Some(104.0));
+    assert_eq!(calc_index_price(&prices[..1], &weights), None);
+
+    assert!(parse_index_weights("coinbase").is_err());
+    assert!(parse_index_weights("coinbase:-1").is_err());
+    assert!(parse_index_weights("kraken:1").is_err());
+}
+
+#[test]
+pub fn index_price_only_includes_recent_prices() {
+    let consensus = PriceConsensus { secondary_venues: vec![PriceVenue::Binance], deviation_percentage: 2.0, ..Default::default() };
+    let now = Utc::now();
+
+    assert_eq!(consensus.index_price("BTC-USDT", now), None);
+
+    consensus.submit("BTC-USDT", PriceVenue::Binance, 100.0, now).unwrap();
+    consensus.submit("BTC-USDT", PriceVenue::Coinbase, 101.0, now + Duration::seconds(CONSENSUS_MAX_PRICE_AGE_SECS)).unwrap();
+
+    assert_eq!(consensus.index_price("BTC-USDT", now + Duration::seconds(CONSENSUS_MAX_PRICE_AGE_SECS)), Some(100.5));
+    assert_eq!(consensus.index_price("BTC-USDT", now + Duration::seconds(CONSENSUS_MAX_PRICE_AGE_SECS + 1)), Some(101.0));
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{api::consistency::diff_active_trades, models::{ActiveTrade, DiscrepancyKind}, tests::active_trade};
+
+fn sample_trade() -> ActiveTrade {
+    ActiveTrade {
+        open_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 7, 0, 0).unwrap(),
+        quantity: 2.0,
+        take_profit: Some(110.0),
+        stop_loss: Some(95.0),
+        ..active_trade()
+    }
+}
+
+#[test]
+pub fn diff_reports_missing_and_mismatched_trades() {
+    let consistent = sample_trade();
+    let only_stored = sample_trade();
+    let only_in_memory = sample_trade();
+    let mismatched = sample_trade();
+
+    // a stop loss moved in memory that was never persisted
+    let mut moved_stop = mismatched.clone();
+    moved_stop.stop_loss = Some(99.0);
+
+    // sub-second precision is lost in the database, so it isn't a discrepancy
+    let mut precise = consistent.clone();
+    precise.open_timestamp += chrono::Duration::milliseconds(250);
+
+    let in_memory: HashMap<ObjectId, ActiveTrade> = [precise, only_in_memory.clone(), moved_stop]
+        .into_iter()
+        .map(|trade| (trade.id, trade))
+        .collect();
+
+    let in_database = [consistent, only_stored.clone(), mismatched.clone()];
+
+    let discrepancies = diff_active_trades(&in_memory, &in_database);
+
+    assert_eq!(discrepancies.len(), 3);
+
+    let kind_of = |id: ObjectId| discrepancies.iter().find(|discrepancy| discrepancy.trade_id == id).map(|discrepancy| discrepancy.kind);
+
+    assert_eq!(kind_of(only_stored.id), Some(DiscrepancyKind::MissingInMemory));
+    assert_eq!(kind_of(only_in_memory.id), Some(DiscrepancyKind::MissingInDatabase));
+    assert_eq!(kind_of(mismatched.id), Some(DiscrepancyKind::Mismatched));
+
+    let mismatch = discrepancies.iter().find(|discrepancy| discrepancy.trade_id == mismatched.id).unwrap();
+    assert_eq!(mismatch.fields, vec!["stopLoss".to_string()]);
+}
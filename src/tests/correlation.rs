@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use crate::{api::correlation::{calc_correlation, calc_rolling_correlation}, constants::CORRELATION_MIN_DAYS};
+
+fn day(index: usize) -> String {
+    format!("2025-01-{:02}", index + 1)
+}
+
+#[test]
+pub fn correlation_of_series() {
+    let returns: Vec<f64> = (0..CORRELATION_MIN_DAYS).map(|i| if i % 2 == 0 { 0.01 } else { -0.02 } * (i as f64 + 1.0)).collect();
+    let levered: Vec<f64> = returns.iter().map(|r| r * 5000.0).collect();
+    let inverse: Vec<f64> = returns.iter().map(|r| -r * 200.0 + 3.0).collect();
+
+    assert!((calc_correlation(&levered, &returns).unwrap() - 1.0).abs() < 1e-9);
+    assert!((calc_correlation(&inverse, &returns).unwrap() + 1.0).abs() < 1e-9);
+    // a flat series doesn't vary
+    assert_eq!(calc_correlation(&[1.0; CORRELATION_MIN_DAYS], &returns), None);
+    // too few days
+    assert_eq!(calc_correlation(&levered[1..], &returns[1..]), None);
+}
+
+#[test]
+pub fn rolling_correlation_from_first_trade() {
+    let returns: BTreeMap<String, f64> = (0..30).map(|i| (day(i), if i % 3 == 0 { 0.02 } else { -0.01 } * (1.0 + i as f64 / 10.0))).collect();
+
+    // the strategy only started trading on the 11th day, and then tracks BTC
+    let daily_pnl: BTreeMap<String, f64> = returns.iter().skip(10).map(|(day, r)| (day.clone(), r * 1000.0)).collect();
+
+    let rolling = calc_rolling_correlation(&daily_pnl, &returns, 15);
+
+    assert_eq!(rolling.len(), 16);
+    assert_eq!(rolling[0].day, day(14));
+    // only 5 days since the first trade
+    assert_eq!(rolling[0].correlation, None);
+    assert!((rolling[15].correlation.unwrap() - 1.0).abs() < 1e-9);
+    assert_eq!(rolling[15].day, day(29));
+}
+
+#[test]
+pub fn rolling_correlation_counts_days_without_trades() {
+    let returns: BTreeMap<String, f64> = (0..20).map(|i| (day(i), if i % 2 == 0 { 0.01 } else { -0.01 })).collect();
+    // trades are only closed on the up days, so the PnL follows BTC while flat on the other days
+    let daily_pnl: BTreeMap<String, f64> = (0..20).step_by(2).map(|i| (day(i), 10.0)).collect();
+
+    let correlation = calc_rolling_correlation(&daily_pnl, &returns, 20)[0].correlation.unwrap();
+
+    assert!((correlation - 1.0).abs() < 1e-9);
+}
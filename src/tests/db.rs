@@ -0,0 +1,50 @@
+use std::{collections::HashMap, sync::atomic::Ordering, time::Duration};
+
+use crate::models::{MongoPoolConfig, MongoPoolMetrics, MongoPoolStats, StatsReadPreference};
+
+#[test]
+pub fn pool_config_ignores_unset_and_invalid_values() {
+    let vars = HashMap::from([
+        ("MONGODB_MAX_POOL_SIZE", "50"),
+        ("MONGODB_MIN_POOL_SIZE", "not a number"),
+        ("MONGODB_SERVER_SELECTION_TIMEOUT_MS", "2500"),
+        ("MONGODB_RETRY_WRITES", "false"),
+    ]);
+
+    let config = MongoPoolConfig::from_lookup(|name| vars.get(name).map(|value| value.to_string()));
+
+    assert_eq!(config, MongoPoolConfig {
+        max_pool_size: Some(50),
+        server_selection_timeout: Some(Duration::from_millis(2500)),
+        retry_writes: Some(false),
+        ..Default::default()
+    });
+    assert_eq!(MongoPoolConfig::from_lookup(|_| None), MongoPoolConfig::default());
+}
+
+#[test]
+pub fn pool_stats_derive_current_connections() {
+    let metrics = MongoPoolMetrics::default();
+    metrics.connections_created.store(10, Ordering::Relaxed);
+    metrics.connections_closed.store(4, Ordering::Relaxed);
+    metrics.checkouts.store(100, Ordering::Relaxed);
+    metrics.checkins.store(97, Ordering::Relaxed);
+    metrics.checkout_failures.store(2, Ordering::Relaxed);
+
+    assert_eq!(metrics.stats(), MongoPoolStats {
+        open_connections: 6,
+        checked_out_connections: 3,
+        connections_created: 10,
+        checkout_failures: 2,
+        pool_clears: 0,
+    });
+}
+
+#[test]
+pub fn stats_read_preference_accepts_driver_spellings() {
+    assert_eq!("secondaryPreferred".parse(), Ok(StatsReadPreference::SecondaryPreferred));
+    assert_eq!("secondary_preferred".parse(), Ok(StatsReadPreference::SecondaryPreferred));
+    assert_eq!(" PRIMARY ".parse(), Ok(StatsReadPreference::Primary));
+    assert_eq!("nearest".parse(), Ok(StatsReadPreference::Nearest));
+    assert!("tertiary".parse::<StatsReadPreference>().is_err());
+}
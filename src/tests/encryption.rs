@@ -0,0 +1,33 @@
+use crate::models::FieldCipher;
+
+const KEY: [u8; 32] = [7; 32];
+
+#[test]
+pub fn encrypted_fields_round_trip() {
+    let cipher = FieldCipher::new(&KEY).unwrap();
+
+    let encrypted = cipher.encrypt("sender", "123456789").unwrap();
+
+    assert!(encrypted.starts_with("enc:v1:"));
+    assert!(!encrypted.contains("123456789"));
+    assert_eq!(cipher.decrypt("sender", &encrypted).unwrap(), "123456789");
+
+    // a random nonce is used for each value
+    assert_ne!(cipher.encrypt("sender", "123456789").unwrap(), encrypted);
+
+    // values stored before encryption was enabled are read as is
+    assert_eq!(cipher.decrypt("sender", "123456789").unwrap(), "123456789");
+}
+
+#[test]
+pub fn encrypted_fields_are_authenticated() {
+    let cipher = FieldCipher::new(&KEY).unwrap();
+    let encrypted = cipher.encrypt("sender", "123456789").unwrap();
+
+    // moved into another field
+    assert!(cipher.decrypt("accountId", &encrypted).is_err());
+    // decrypted with another key
+    assert!(FieldCipher::new(&[8; 32]).unwrap().decrypt("sender", &encrypted).is_err());
+    // invalid key length
+    assert!(FieldCipher::new(&[7; 16]).is_err());
+}
@@ -0,0 +1,60 @@
+use std::{collections::HashMap, io};
+
+use crate::configs::{env_schema, resolve_file_vars, validate_env};
+
+fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+pub fn missing_required_variables_are_reported_with_a_hint() {
+    let errors = validate_env(&env_schema(), lookup(&[("MONGODB_URI", "mongodb://localhost:27017")]));
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].variable, "TRADINGVIEW_SECRET");
+    assert!(errors[0].message.contains("TRADINGVIEW_SECRET_FILE"));
+}
+
+#[test]
+pub fn invalid_variables_are_all_reported() {
+    let errors = validate_env(&env_schema(), lookup(&[
+        ("MONGODB_URI", "mongodb://localhost:27017"),
+        ("TRADINGVIEW_SECRET", "secret"),
+        ("PORT", "eighty"),
+        ("HA_MODE", "yes"),
+        ("CHAOS_ORDER_REJECTION_RATE", "1.5"),
+        ("RESPONSE_VERBOSITY", "loud"),
+        ("FIELD_ENCRYPTION_KEY", "c2hvcnQ="),
+        ("SMTP_PORT", "587"),
+        ("STATS_READ_PREFERENCE", "secondaryPreferred"),
+    ]));
+
+    let variables: Vec<&str> = errors.iter().map(|error| error.variable.as_str()).collect();
+    assert_eq!(variables, ["PORT", "FIELD_ENCRYPTION_KEY", "RESPONSE_VERBOSITY", "HA_MODE", "CHAOS_ORDER_REJECTION_RATE"]);
+
+    let valid = validate_env(&env_schema(), lookup(&[("MONGODB_URI", "mongodb://localhost:27017"), ("TRADINGVIEW_SECRET", "secret"), ("PORT", "8080")]));
+    assert!(valid.is_empty());
+}
+
+#[test]
+pub fn file_variables_are_resolved() {
+    let vars = [
+        ("TRADINGVIEW_SECRET_FILE", "/run/secrets/tradingview"),
+        ("MONGODB_URI", "mongodb://localhost:27017"),
+        ("MONGODB_URI_FILE", "/run/secrets/mongodb"),
+    ].map(|(name, value)| (name.to_string(), value.to_string()));
+
+    let read_file = |path: &str| match path {
+        "/run/secrets/tradingview" => Ok("secret\n".to_string()),
+        _ => Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+    };
+
+    // variables that are set directly take precedence over their file, which isn't read at all
+    assert_eq!(resolve_file_vars(vars.clone(), read_file).unwrap(), vec![("TRADINGVIEW_SECRET".to_string(), "secret".to_string())]);
+
+    let errors = resolve_file_vars(vars.into_iter().filter(|(name, _)| name != "MONGODB_URI"), read_file).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].variable, "MONGODB_URI_FILE");
+}
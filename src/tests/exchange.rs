@@ -0,0 +1,249 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{api::{build_closed_paper_trade, calc_final_execution_fees, calc_liquidation_fee, calc_liquidation_price, calc_partial_liquidation, clamp_to_isolated_margin, exchange::parse_maintenance_margin_tiers, strategy::validate_strategy_parameters}, constants::{MAX_PARTIAL_LIQUIDATION_STEPS, PAPER_TRADING_EXCHANGE}, models::{ActiveTrade, CoinbaseClient, ContractType, Exchange, ExchangeProfile, FundingRate, FundingSchedule, MaintenanceMarginTier, MarginMode, OrderFill, OrderSide, RoundingPolicy, StrategyParameters, TradeDirection, TradeKind, TradeLeverage}, tests::{active_trade, app_state}};
+
+#[test]
+pub fn known_exchanges_have_complete_profiles() {
+    for exchange in ExchangeProfile::known_exchanges() {
+        let profile = ExchangeProfile::from_lookup(exchange, |_| None).unwrap();
+
+        assert_eq!(profile.name, exchange);
+        assert!(profile.maker_fee_percentage <= profile.taker_fee_percentage);
+
+        // the tiers ascend and the last one covers any notional value
+        let (last, tiers) = profile.maintenance_margin_tiers.split_last().unwrap();
+        assert_eq!(last.max_notional_value, None);
+        assert!(tiers.iter().all(|tier| tier.max_notional_value.is_some()));
+        assert!(profile.maintenance_margin_tiers.windows(2).all(|pair| pair[0].maintenance_margin_percentage <= pair[1].maintenance_margin_percentage));
+    }
+
+    assert_eq!(ExchangeProfile::resolve(None).name, PAPER_TRADING_EXCHANGE);
+}
+
+#[test]
+pub fn exchange_profile_is_configurable() {
+    let vars = HashMap::from([
+        ("TAKER_FEE_PERCENTAGE_BYBIT", "0.04"),
+        ("MAKER_FEE_PERCENTAGE_BYBIT", "-1"),
+        ("FUNDING_INTERVAL_HOURS_BYBIT", "4"),
+    ]);
+
+    let profile = ExchangeProfile::from_lookup("Bybit", |name| vars.get(name).map(|value| value.to_string())).unwrap();
+
+    assert_eq!(profile.name, "bybit");
+    assert_eq!(profile.taker_fee_percentage, 0.04);
+    // negative fees are ignored
+    assert_eq!(profile.maker_fee_percentage, 0.02);
+    assert_eq!(profile.funding_schedule, FundingSchedule { interval_hours: 4 });
+    assert_eq!(profile.funding_schedule.funding_hours(), vec![0, 4, 8, 12, 16, 20]);
+    assert_eq!(profile.maintenance_margin_tiers[0], MaintenanceMarginTier { max_notional_value: Some(2_000_000.0), maintenance_margin_percentage: 0.5 });
+}
+
+#[test]
+pub fn unknown_exchanges_are_rejected() {
+    assert!(ExchangeProfile::from_lookup("mtgox", |_| None).is_none());
+    assert_eq!(ExchangeProfile::resolve(Some("mtgox")).name, PAPER_TRADING_EXCHANGE);
+
+    assert!(validate_strategy_parameters(&StrategyParameters { exchange: Some("mtgox".to_string()), ..Default::default() }).is_err());
+    assert!(validate_strategy_parameters(&StrategyParameters { exchange: Some("hyperliquid".to_string()), ..Default::default() }).is_ok());
+}
+
+#[test]
+pub fn maintenance_margin_depends_on_the_notional_value() {
+    let vars = HashMap::from([("MAINTENANCE_MARGIN_TIERS_BINANCE", "50000:0.5, 600000:1, *:2.5")]);
+    let profile = ExchangeProfile::from_lookup("binance", |name| vars.get(name).map(|value| value.to_string())).unwrap();
+
+    assert_eq!(profile.maintenance_margin_percentage(1_000.0), 0.5);
+    assert_eq!(profile.maintenance_margin_percentage(50_000.0), 0.5);
+    assert_eq!(profile.maintenance_margin_percentage(50_001.0), 1.0);
+    assert_eq!(profile.maintenance_margin_percentage(10_000_000.0), 2.5);
+
+    // larger positions are liquidated closer to the entry price
+    let liquidation_price = |notional_value: f64| calc_liquidation_price(
+        100.0,
+        10.0,
+        &TradeDirection::Long,
+        &ContractType::Linear,
+        profile.maintenance_margin_percentage(notional_value)
+    );
+
+    assert!((liquidation_price(1_000.0) - 90.05).abs() < 1e-9);
+    assert!((liquidation_price(10_000_000.0) - 90.25).abs() < 1e-9);
+}
+
+#[test]
+pub fn invalid_maintenance_margin_tiers_are_rejected() {
+    assert_eq!(parse_maintenance_margin_tiers("*:3").unwrap(), vec![MaintenanceMarginTier { max_notional_value: None, maintenance_margin_percentage: 3.0 }]);
+
+    // missing the last tier
+    assert!(parse_maintenance_margin_tiers("50000:0.4").is_err());
+    // not ascending
+    assert!(parse_maintenance_margin_tiers("50000:0.4,10000:0.5,*:1").is_err());
+    assert!(parse_maintenance_margin_tiers("*:1,50000:2").is_err());
+    assert!(parse_maintenance_margin_tiers("50000:150,*:1").is_err());
+    assert!(parse_maintenance_margin_tiers("50000").is_err());
+}
+
+#[test]
+pub fn liquidation_losses_are_clamped_to_the_margin() {
+    // 1% of the notional value of 100 contracts at 90
+    assert!((calc_liquidation_fee(100.0, 90.0, &ContractType::Linear, 1.0) - 90.0).abs() < 1e-9);
+
+    // margin of 100 contracts at 100 with 10x leverage = 1000
+    assert_eq!(clamp_to_isolated_margin(-1200.0, 100.0, 100.0, 10.0, &ContractType::Linear), -1000.0);
+    assert_eq!(clamp_to_isolated_margin(-800.0, 100.0, 100.0, 10.0, &ContractType::Linear), -800.0);
+}
+
+#[tokio::test]
+pub async fn paper_trades_crossing_the_liquidation_price_are_liquidated() {
+    let app_state = app_state().await;
+
+    let trade = ActiveTrade {
+        alert_name: "breakout".to_string(),
+        quantity: 100.0,
+        leverage: TradeLeverage::Ten,
+        liquidation_price: 90.5,
+        exchange: Some("binance".to_string()),
+        ..active_trade()
+    };
+
+    let closed_trade = build_closed_paper_trade(&app_state, &trade, 95.0);
+    assert!(!closed_trade.liquidated);
+    assert_eq!(closed_trade.liquidation_fee, 0.0);
+
+    // the price gapped through the liquidation price
+    let liquidated_trade = build_closed_paper_trade(&app_state, &trade, 50.0);
+    assert!(liquidated_trade.liquidated);
+    assert_eq!(liquidated_trade.exit_price, 90.5);
+    assert!(liquidated_trade.liquidation_fee > 0.0);
+    // the loss at 90.5 plus fees exceeds the margin of 1000
+    assert_eq!(liquidated_trade.pnl, -1000.0);
+}
+
+#[tokio::test]
+pub async fn large_cross_margin_trades_are_liquidated_in_steps() {
+    let app_state = app_state().await;
+    let binance = ExchangeProfile::for_exchange("binance").unwrap();
+
+    // 1,000,000 USDT of notional value falls into the 0.65% tier
+    let mut trade = ActiveTrade {
+        alert_name: "breakout".to_string(),
+        quantity: 10.0,
+        entry_price: 100_000.0,
+        leverage: TradeLeverage::Ten,
+        liquidation_price: calc_liquidation_price(100_000.0, 10.0, &TradeDirection::Long, &ContractType::Linear, 0.65),
+        exchange: Some("binance".to_string()),
+        ..active_trade()
+    };
+
+    // isolated margin trades are always liquidated entirely
+    assert!(calc_partial_liquidation(&trade, &binance, 1.0, &RoundingPolicy::default(), Utc::now()).is_none());
+
+    trade.margin_mode = MarginMode::Cross;
+
+    let step = calc_partial_liquidation(&trade, &binance, 1.0, &RoundingPolicy::default(), Utc::now()).unwrap();
+    assert_eq!(step.quantity, 5.0);
+    assert_eq!(step.price, trade.liquidation_price);
+    assert!(step.pnl < 0.0);
+    // the remaining 500,000 USDT fall into the 0.5% tier, so they're liquidated later
+    assert!((step.next_liquidation_price - 90_050.0).abs() < 1e-6);
+    assert!(step.next_liquidation_price < trade.liquidation_price);
+
+    for _ in 0..MAX_PARTIAL_LIQUIDATION_STEPS {
+        let step = calc_partial_liquidation(&trade, &binance, 1.0, &RoundingPolicy::default(), Utc::now()).unwrap();
+        trade.quantity -= step.quantity;
+        trade.liquidation_price = step.next_liquidation_price;
+        trade.partial_liquidations.push(step);
+    }
+
+    // the maximum number of steps was reached
+    assert!(calc_partial_liquidation(&trade, &binance, 1.0, &RoundingPolicy::default(), Utc::now()).is_none());
+
+    let realized_pnl: f64 = trade.partial_liquidations.iter().map(|step| step.pnl).sum();
+    let closed_trade = build_closed_paper_trade(&app_state, &trade, trade.entry_price);
+    assert_eq!(closed_trade.partial_liquidations.len(), MAX_PARTIAL_LIQUIDATION_STEPS);
+    // the realized PnL of the steps counts towards the closed trade, which only pays the fees of the remaining quantity on top
+    let execution_fees = calc_final_execution_fees(trade.quantity, trade.entry_price, &ContractType::Linear, binance.taker_fee_percentage);
+    assert!((closed_trade.pnl - (realized_pnl - execution_fees)).abs() < 1e-6);
+
+    // positions in the lowest tier are liquidated entirely
+    trade.partial_liquidations.clear();
+    trade.quantity = 0.5;
+    assert!(calc_partial_liquidation(&trade, &binance, 1.0, &RoundingPolicy::default(), Utc::now()).is_none());
+}
+
+/// An exchange that fills every order at a fixed price, recording the orders placed on it.
+struct MockExchange {
+    price: f64,
+    orders: Mutex<Vec<(String, OrderSide, f64, bool)>>,
+}
+
+#[async_trait]
+impl Exchange for MockExchange {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn place_order(&self, pair: &str, side: OrderSide, quantity: f64, reduce_only: bool) -> Result<OrderFill, String> {
+        self.orders.lock().unwrap().push((pair.to_string(), side, quantity, reduce_only));
+
+        Ok(OrderFill { order_id: 1, price: self.price, quantity, fees: 0.0 })
+    }
+
+    async fn get_ticker(&self, _pair: &str) -> Result<f64, String> {
+        Ok(self.price)
+    }
+
+    async fn get_funding_rates(&self, pair: &str, since: Option<DateTime<Utc>>) -> Result<Vec<FundingRate>, String> {
+        let funding_time = since.unwrap_or_else(Utc::now) + Duration::hours(8);
+
+        Ok(vec![FundingRate { pair: pair.to_string(), funding_time, rate: 0.0001, mark_price: Some(self.price) }])
+    }
+}
+
+#[tokio::test]
+pub async fn exchanges_share_one_interface() {
+    let mut app_state = app_state().await;
+
+    let exchange = Arc::new(MockExchange { price: 101.0, orders: Mutex::new(Vec::new()) });
+    let trade = ActiveTrade {
+        alert_name: "breakout".to_string(),
+        pair: "SOLUSDT".to_string(),
+        direction: TradeDirection::Short,
+        kind: TradeKind::Live,
+        quantity: 3.0,
+        leverage: TradeLeverage::Ten,
+        liquidation_price: 109.5,
+        exchange: Some("binance".to_string()),
+        ..active_trade()
+    };
+
+    // positions are closed with a reduce-only order on the opposite side, and leverage is optional
+    let fill = exchange.close_position(&trade).await.unwrap();
+    assert_eq!(fill.price, 101.0);
+    assert_eq!(exchange.orders.lock().unwrap()[0], ("SOLUSDT".to_string(), OrderSide::Buy, 3.0, true));
+    assert!(exchange.set_leverage("SOLUSDT", 10.0).await.is_ok());
+
+    // Coinbase is only used for prices
+    let coinbase = CoinbaseClient::default();
+    assert_eq!(coinbase.name(), "coinbase");
+    assert!(coinbase.place_order("BTCUSDT", OrderSide::Buy, 1.0, false).await.is_err());
+    assert!(coinbase.close_position(&trade).await.is_err());
+    assert!(coinbase.get_funding_rates("BTCUSDT", None).await.unwrap().is_empty());
+
+    // funding rates are polled from the funding exchange
+    app_state.funding_exchange = exchange.clone();
+    let funding_rates = app_state.funding_exchange.get_funding_rates("SOLUSDT", None).await.unwrap();
+    assert_eq!(funding_rates.len(), 1);
+    assert_eq!(funding_rates[0].rate, 0.0001);
+
+    // without a price from the feed, the ticker of the price exchange is used
+    app_state.price_exchange = exchange.clone();
+    assert_eq!(app_state.current_price("SOLUSDT").await.unwrap(), 101.0);
+
+    app_state.latest_prices.lock().unwrap().insert("SOL-USDT".to_string(), 99.0);
+    assert_eq!(app_state.current_price("SOLUSDT").await.unwrap(), 99.0);
+}
@@ -0,0 +1,46 @@
+use chrono::{TimeZone, Utc};
+use mongodb::bson::doc;
+
+use crate::{api::{export::{closed_trade_csv_row, closed_trade_export_filter, to_csv_row}, import::parse_trade_history}, models::{ClosedTradeExportQuery, ReportLocale, TradeHistoryFormat}};
+
+#[test]
+pub fn export_filter_includes_only_given_bounds() {
+    assert_eq!(closed_trade_export_filter(&ClosedTradeExportQuery::default()), doc! {});
+
+    let from = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+
+    let query = ClosedTradeExportQuery {
+        alert_name: Some("BTC 1h".to_string()),
+        from: Some(from),
+        to: Some(to),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        closed_trade_export_filter(&query),
+        doc! { "alertName": "BTC 1h", "closeTimestamp": { "$gte": from.timestamp(), "$lt": to.timestamp() } }
+    );
+}
+
+#[test]
+pub fn csv_rows_quote_fields_where_required() {
+    let row = to_csv_row(["plain".to_string(), "with, comma".to_string(), "with \"quotes\"".to_string()], b',');
+
+    assert_eq!(row, "plain,\"with, comma\",\"with \"\"quotes\"\"\"\n");
+}
+
+#[test]
+pub fn closed_trades_render_with_stored_enum_names() {
+    let csv = "\
+Contracts,Closing Direction,Qty,Entry Price,Exit Price,Closed P&L,Trade Time(UTC+0)
+SOLUSDT,Close Short,10,110,100,99,2024-03-02 12:00:00
+";
+
+    let (trades, _) = parse_trade_history(TradeHistoryFormat::Bybit, csv, "imported").unwrap();
+    let row = closed_trade_csv_row(&trades[0], &ReportLocale::default());
+    let fields: Vec<&str> = row.trim_end().split(',').collect();
+
+    assert_eq!(fields[1..5], ["imported", "SOLUSDT", "short", "live"]);
+    assert_eq!(fields[12], "99");
+}
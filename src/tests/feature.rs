@@ -0,0 +1,32 @@
+use crate::{api::{migration::migrations, version::version_info}, models::FeatureFlags, tests::app_state};
+
+#[test]
+pub fn risky_features_are_disabled_by_default() {
+    assert_eq!(FeatureFlags::from_lookup(|_| None), FeatureFlags::default());
+    assert!(!FeatureFlags::default().live_trading);
+    assert!(!FeatureFlags::default().auto_liquidation);
+
+    let features = FeatureFlags::from_lookup(|name| match name {
+        "FEATURE_AUTO_LIQUIDATION" => Some(" TRUE ".to_string()),
+        "FEATURE_LIVE_TRADING" => Some("yes".to_string()),
+        _ => None,
+    });
+
+    assert!(features.auto_liquidation);
+    // only `true` enables a feature
+    assert!(!features.live_trading);
+}
+
+#[tokio::test]
+pub async fn version_info_reports_the_schema_version_and_features() {
+    let mut app_state = app_state().await;
+    app_state.features.auto_liquidation = true;
+
+    let info = version_info(&app_state);
+
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.schema_version, migrations().last().unwrap().version);
+    assert!(info.exchanges.contains(&"binance"));
+    assert!(info.features.auto_liquidation);
+    assert!(info.build_timestamp.is_some());
+}
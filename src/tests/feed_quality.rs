@@ -0,0 +1,119 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{api::feed_quality::{assess_tick, calc_feed_quality_score, detect_trade_gap, downsample_trades}, constants::{FEED_BACKFILL_MIN_MISSING_TRADES, FEED_GAP_SECS, FEED_SPIKE_CONFIRMATION_TICKS}, models::{CoinbaseTrade, FeedQualityMonitor, ProductFeedQuality, TickIssue, TradeGap}};
+
+#[test]
+pub fn bad_ticks_are_discarded() {
+    let mut quality = ProductFeedQuality::default();
+    let now = Utc::now();
+
+    assert_eq!(assess_tick(&mut quality, Some(10), 100.0, now, 10.0), Ok(()));
+    assert_eq!(assess_tick(&mut quality, None, 0.0, now, 10.0), Err(TickIssue::InvalidPrice));
+    assert_eq!(assess_tick(&mut quality, None, f64::NAN, now, 10.0), Err(TickIssue::InvalidPrice));
+    // late and duplicate ticks
+    assert_eq!(assess_tick(&mut quality, Some(9), 100.0, now, 10.0), Err(TickIssue::OutOfOrder));
+    assert_eq!(assess_tick(&mut quality, Some(10), 100.0, now, 10.0), Err(TickIssue::OutOfOrder));
+    // sequence numbers of the ticker channel aren't contiguous
+    assert_eq!(assess_tick(&mut quality, Some(15), 105.0, now, 10.0), Ok(()));
+    assert_eq!(assess_tick(&mut quality, Some(16), 150.0, now, 10.0), Err(TickIssue::Spike));
+    assert_eq!(assess_tick(&mut quality, None, 104.0, now, 10.0), Ok(()));
+
+    assert_eq!(quality.ticks, 8);
+    assert_eq!(quality.discarded, 5);
+    assert_eq!((quality.invalid_prices, quality.out_of_order, quality.spikes), (2, 2, 1));
+    assert_eq!(quality.last_sequence, Some(16));
+    assert_eq!(quality.last_price, Some(104.0));
+}
+
+#[test]
+pub fn repeated_spikes_are_accepted_as_a_new_price_level() {
+    let mut quality = ProductFeedQuality::default();
+    let now = Utc::now();
+
+    assert!(assess_tick(&mut quality, None, 100.0, now, 10.0).is_ok());
+
+    for _ in 1..FEED_SPIKE_CONFIRMATION_TICKS {
+        assert_eq!(assess_tick(&mut quality, None, 80.0, now, 10.0), Err(TickIssue::Spike));
+    }
+
+    // the market really moved
+    assert!(assess_tick(&mut quality, None, 80.0, now, 10.0).is_ok());
+    assert_eq!(quality.last_price, Some(80.0));
+    assert!(assess_tick(&mut quality, None, 81.0, now, 10.0).is_ok());
+}
+
+#[test]
+pub fn gaps_lower_the_feed_quality_score() {
+    let mut quality = ProductFeedQuality::default();
+    let now = Utc::now();
+
+    assert_eq!(calc_feed_quality_score(&quality), 100.0);
+
+    assert!(assess_tick(&mut quality, None, 100.0, now, 10.0).is_ok());
+    assert!(assess_tick(&mut quality, None, 100.0, now + Duration::seconds(FEED_GAP_SECS + 30), 10.0).is_ok());
+    assert!(assess_tick(&mut quality, None, 100.0, now + Duration::seconds(FEED_GAP_SECS + 31), 10.0).is_ok());
+    assert_eq!(assess_tick(&mut quality, None, -1.0, now + Duration::seconds(FEED_GAP_SECS + 32), 10.0), Err(TickIssue::InvalidPrice));
+
+    assert_eq!(quality.gaps, 1);
+    assert_eq!(quality.max_gap_secs, FEED_GAP_SECS + 30);
+    // 1 gap and 1 discarded tick out of 4 ticks
+    assert_eq!(calc_feed_quality_score(&quality), 50.0);
+}
+
+#[test]
+pub fn feed_quality_is_tracked_per_product() {
+    let monitor = FeedQualityMonitor { spike_percentage: 5.0, ..Default::default() };
+    let now = Utc::now();
+
+    assert!(monitor.assess("ETH-USD", Some(1), 3000.0, now).is_ok());
+    assert!(monitor.assess("BTC-USD", Some(1), 100_000.0, now).is_ok());
+    // the sequence numbers of each product are independent
+    assert!(monitor.assess("ETH-USD", Some(2), 3010.0, now).is_ok());
+    assert_eq!(monitor.assess("BTC-USD", Some(2), 110_000.0, now), Err(TickIssue::Spike));
+
+    let reports = monitor.reports();
+    assert_eq!(reports.iter().map(|report| report.product_id.as_str()).collect::<Vec<_>>(), vec!["BTC-USD", "ETH-USD"]);
+    assert_eq!(reports[0].score, 50.0);
+    assert_eq!(reports[1].score, 100.0);
+}
+
+#[test]
+pub fn skipped_trade_ids_are_backfilled() {
+    let mut quality = ProductFeedQuality::default();
+
+    assert_eq!(detect_trade_gap(&mut quality, "BTC-USD", None), None);
+    assert_eq!(detect_trade_gap(&mut quality, "BTC-USD", Some(100)), None);
+    // cascading matches are batched into a single tick
+    assert_eq!(detect_trade_gap(&mut quality, "BTC-USD", Some(103)), None);
+    assert_eq!(quality.missed_trades, 2);
+
+    let before_trade_id = 103 + FEED_BACKFILL_MIN_MISSING_TRADES + 1;
+    assert_eq!(
+        detect_trade_gap(&mut quality, "BTC-USD", Some(before_trade_id)),
+        Some(TradeGap { product_id: "BTC-USD".to_string(), after_trade_id: 103, before_trade_id })
+    );
+    assert_eq!(quality.missed_trades, 2 + FEED_BACKFILL_MIN_MISSING_TRADES);
+
+    // older trade IDs don't move the last one back
+    assert_eq!(detect_trade_gap(&mut quality, "BTC-USD", Some(50)), None);
+    assert_eq!(quality.last_trade_id, Some(before_trade_id));
+}
+
+#[test]
+pub fn backfilled_trades_are_downsampled_into_price_ticks() {
+    let trade = |trade_id: u64, price: &str, secs: i64| CoinbaseTrade {
+        trade_id,
+        price: price.to_string(),
+        size: None,
+        side: None,
+        time: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+    };
+
+    let trades = [trade(1, "100", 0), trade(2, "101", 4), trade(3, "invalid", 8), trade(4, "102", 12), trade(5, "0", 15)];
+    let ticks = downsample_trades(&trades, "BTC-USD", 10);
+
+    // the last valid price within each 10 seconds
+    assert_eq!(ticks.iter().map(|tick| tick.price).collect::<Vec<_>>(), vec![101.0, 102.0]);
+    assert_eq!(ticks[0].timestamp.timestamp_millis(), 1_700_000_004_000);
+    assert!(ticks.iter().all(|tick| tick.product == "BTC-USD"));
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{api::{calc_final_funding_fees, calc_funding_payments, funding::funding_ledger_filter}, models::{ActiveTrade, FundingLedgerQuery, FundingSchedule, MarketHours, TradeDirection}, tests::active_trade};
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, second).unwrap()
+}
+
+fn schedule(interval_hours: u32) -> FundingSchedule {
+    FundingSchedule::new(interval_hours).unwrap()
+}
+
+#[test]
+pub fn next_funding_time_is_strictly_after_timestamp() {
+    let eight_hours = schedule(8);
+
+    assert_eq!(eight_hours.next_funding_time(at(2025, 1, 1, 7, 59, 59)), at(2025, 1, 1, 8, 0, 0));
+    // a trade opened exactly at a funding time only pays the following one
+    assert_eq!(eight_hours.next_funding_time(at(2025, 1, 1, 8, 0, 0)), at(2025, 1, 1, 16, 0, 0));
+    assert_eq!(eight_hours.next_funding_time(at(2025, 1, 1, 0, 0, 0)), at(2025, 1, 1, 8, 0, 0));
+}
+
+#[test]
+pub fn next_funding_time_rolls_over_day_month_and_year_boundaries() {
+    let eight_hours = schedule(8);
+
+    assert_eq!(eight_hours.next_funding_time(at(2025, 1, 1, 16, 0, 1)), at(2025, 1, 2, 0, 0, 0));
+    assert_eq!(eight_hours.next_funding_time(at(2025, 1, 31, 23, 59, 59)), at(2025, 2, 1, 0, 0, 0));
+    assert_eq!(eight_hours.next_funding_time(at(2024, 2, 28, 20, 0, 0)), at(2024, 2, 29, 0, 0, 0));
+    assert_eq!(eight_hours.next_funding_time(at(2024, 12, 31, 23, 30, 0)), at(2025, 1, 1, 0, 0, 0));
+}
+
+#[test]
+pub fn funding_times_follow_the_interval() {
+    assert_eq!(schedule(1).next_funding_time(at(2025, 1, 1, 13, 30, 0)), at(2025, 1, 1, 14, 0, 0));
+    assert_eq!(schedule(4).next_funding_time(at(2025, 1, 1, 13, 30, 0)), at(2025, 1, 1, 16, 0, 0));
+    assert_eq!(schedule(4).next_funding_time(at(2025, 1, 1, 22, 0, 0)), at(2025, 1, 2, 0, 0, 0));
+
+    let from = at(2025, 1, 1, 0, 0, 0);
+    let to = at(2025, 1, 2, 0, 0, 0);
+
+    assert_eq!(schedule(1).count_funding_times(from, to), 24);
+    assert_eq!(schedule(4).count_funding_times(from, to), 6);
+    assert_eq!(schedule(8).count_funding_times(from, to), 3);
+    assert_eq!(schedule(8).count_funding_times(to, from), 0);
+}
+
+#[test]
+pub fn funding_times_ignore_daylight_saving_time() {
+    // Europe and the US switch clocks around these dates, which must not shift UTC funding times
+    let eight_hours = schedule(8);
+
+    assert_eq!(eight_hours.next_funding_time(at(2025, 3, 30, 1, 30, 0)), at(2025, 3, 30, 8, 0, 0));
+    assert_eq!(eight_hours.count_funding_times(at(2025, 3, 30, 0, 0, 0), at(2025, 3, 31, 0, 0, 0)), 3);
+    assert_eq!(eight_hours.count_funding_times(at(2025, 11, 2, 0, 0, 0), at(2025, 11, 3, 0, 0, 0)), 3);
+}
+
+#[test]
+pub fn funding_intervals_must_divide_a_day() {
+    assert!(FundingSchedule::new(0).is_err());
+    assert!(FundingSchedule::new(5).is_err());
+    assert!(FundingSchedule::new(48).is_err());
+    assert!(FundingSchedule::new(24).is_ok());
+}
+
+#[test]
+pub fn funding_schedule_is_configurable_per_exchange() {
+    let vars = HashMap::from([("FUNDING_INTERVAL_HOURS_BYBIT", "4"), ("FUNDING_INTERVAL_HOURS_OKX", "7")]);
+    let lookup = |name: &str| vars.get(name).map(|value| value.to_string());
+
+    assert_eq!(FundingSchedule::from_lookup("bybit", lookup), schedule(4));
+    // invalid intervals fall back to the known interval of the exchange
+    assert_eq!(FundingSchedule::from_lookup("okx", lookup), schedule(8));
+    assert_eq!(FundingSchedule::from_lookup("hyperliquid", lookup), schedule(1));
+    assert_eq!(FundingSchedule::from_lookup("unknown", lookup), schedule(8));
+}
+
+#[test]
+pub fn funding_fees_per_hour_are_independent_of_the_interval() {
+    let open = at(2025, 1, 1, 0, 0, 0);
+    let close = at(2025, 1, 2, 0, 0, 0);
+
+    // 3 settlements of 0.01% of 1000
+    let eight_hours = calc_final_funding_fees(open, close, 1000.0, &schedule(8), &MarketHours::default());
+    assert!((eight_hours - 0.3).abs() < 1e-9);
+
+    assert!((calc_final_funding_fees(open, close, 1000.0, &schedule(4), &MarketHours::default()) - eight_hours).abs() < 1e-9);
+    assert!((calc_final_funding_fees(open, close, 1000.0, &schedule(1), &MarketHours::default()) - eight_hours).abs() < 1e-9);
+
+    // only the settlements while the trade was open are charged
+    assert!((calc_final_funding_fees(at(2025, 1, 1, 7, 0, 0), at(2025, 1, 1, 9, 0, 0), 1000.0, &schedule(1), &MarketHours::default()) - 0.025).abs() < 1e-9);
+    assert_eq!(calc_final_funding_fees(close, open, 1000.0, &schedule(8), &MarketHours::default()), 0.0);
+}
+
+#[test]
+pub fn funding_payments_add_up_to_the_final_funding_fees() {
+    let trade = ActiveTrade {
+        alert_name: "a".to_string(),
+        direction: TradeDirection::Short,
+        open_timestamp: at(2025, 1, 1, 7, 0, 0),
+        exchange: Some("Binance".to_string()),
+        ..active_trade()
+    };
+    let close_timestamp = at(2025, 1, 2, 8, 0, 0);
+
+    let payments = calc_funding_payments(&trade, close_timestamp, 1_000.0, &schedule(8), &MarketHours::default(), "USDT");
+    let funding_fees = calc_final_funding_fees(trade.open_timestamp, close_timestamp, 1_000.0, &schedule(8), &MarketHours::default());
+
+    // 08:00 and 16:00 on the first day, 00:00 and 08:00 on the second day
+    assert_eq!(payments.iter().map(|payment| payment.funding_time).collect::<Vec<_>>(), vec![
+        at(2025, 1, 1, 8, 0, 0),
+        at(2025, 1, 1, 16, 0, 0),
+        at(2025, 1, 2, 0, 0, 0),
+        at(2025, 1, 2, 8, 0, 0),
+    ]);
+    assert!((payments.iter().map(|payment| payment.amount).sum::<f64>() - funding_fees).abs() < 1e-9);
+    assert!(payments.iter().all(|payment| payment.trade_id == trade.id && payment.exchange == "binance"));
+}
+
+#[test]
+pub fn funding_ledger_queries_filter_by_trade_account_and_time() {
+    let trade_id = ObjectId::new();
+
+    let filter = funding_ledger_filter(&FundingLedgerQuery {
+        trade_id: Some(trade_id.to_hex()),
+        exchange: Some("Bybit".to_string()),
+        from: Some(at(2025, 1, 1, 0, 0, 0)),
+        ..Default::default()
+    }).unwrap();
+
+    assert_eq!(filter, doc! {
+        "tradeId": trade_id,
+        "exchange": "bybit",
+        "fundingTime": { "$gte": at(2025, 1, 1, 0, 0, 0).timestamp() },
+    });
+
+    assert_eq!(funding_ledger_filter(&FundingLedgerQuery::default()).unwrap(), doc! {});
+    assert!(funding_ledger_filter(&FundingLedgerQuery { trade_id: Some("not-an-id".to_string()), ..Default::default() }).is_err());
+}
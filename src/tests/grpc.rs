@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use mongodb::bson::oid::ObjectId;
+use tonic::{Code, Request};
+
+use crate::{api::grpc::{check_grpc_secret, proto::{control_plane_server::ControlPlane, CloseTradeRequest, ListActiveTradesRequest}}, models::{ActiveTrade, ControlPlaneService, TradeDirection, TradeLeverage}, tests::{active_trade, app_state}};
+
+fn trade(alert_name: &str, open_hour: u32) -> ActiveTrade {
+    ActiveTrade {
+        alert_name: alert_name.to_string(),
+        pair: "SOLUSDT".to_string(),
+        direction: TradeDirection::Short,
+        open_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, open_hour, 0, 0).unwrap(),
+        entry_price: 150.0,
+        leverage: TradeLeverage::Two,
+        liquidation_price: 225.0,
+        take_profit: Some(140.0),
+        ..active_trade()
+    }
+}
+
+fn authorized(authorization: &str) -> Request<()> {
+    let mut request = Request::new(());
+    request.metadata_mut().insert("authorization", authorization.parse().unwrap());
+
+    request
+}
+
+#[test]
+pub fn grpc_requests_require_the_secret() {
+    assert!(check_grpc_secret(authorized("Bearer secret"), "secret").is_ok());
+    assert_eq!(check_grpc_secret(authorized("Bearer wrong"), "secret").unwrap_err().code(), Code::Unauthenticated);
+    assert_eq!(check_grpc_secret(Request::new(()), "secret").unwrap_err().code(), Code::Unauthenticated);
+    // an unset secret rejects everything
+    assert_eq!(check_grpc_secret(authorized("Bearer "), "").unwrap_err().code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+pub async fn grpc_lists_and_closes_active_trades() {
+    let app_state = Arc::new(app_state().await);
+
+    let (later, earlier, other) = (trade("breakout", 12), trade("breakout", 8), trade("reversal", 10));
+
+    for trade in [&later, &earlier, &other] {
+        app_state.active_trades.lock().unwrap().insert(trade.id, trade.clone());
+    }
+
+    let service = ControlPlaneService { app_state };
+
+    let trades = service.list_active_trades(Request::new(ListActiveTradesRequest { alert_name: Some("breakout".to_string()) }))
+        .await
+        .unwrap()
+        .into_inner()
+        .trades;
+
+    // oldest first
+    assert_eq!(trades.iter().map(|trade| trade.id.clone()).collect::<Vec<_>>(), vec![earlier.id.to_hex(), later.id.to_hex()]);
+    assert_eq!((trades[0].direction.as_str(), trades[0].leverage, trades[0].take_profit), ("short", 2.0, Some(140.0)));
+
+    let close = |id: String| service.close_trade(Request::new(CloseTradeRequest { id }));
+
+    assert_eq!(close("not an id".to_string()).await.unwrap_err().code(), Code::InvalidArgument);
+    assert_eq!(close(ObjectId::new().to_hex()).await.unwrap_err().code(), Code::NotFound);
+    // no price was received for the pair yet
+    assert_eq!(close(later.id.to_hex()).await.unwrap_err().code(), Code::Unavailable);
+}
@@ -0,0 +1,56 @@
+use crate::{api::import::parse_trade_history, models::{TradeDirection, TradeHistoryFormat}};
+
+#[test]
+pub fn binance_fills_are_paired_into_trades() {
+    // newest first, like the export
+    let csv = "\
+Date(UTC),Symbol,Side,Price,Quantity,Amount,Fee,Realized Profit
+2024-01-02 10:00:00,BTCUSDT,SELL,45000,0.1,4500,1.8 USDT,500
+2024-01-01 09:00:00,BTCUSDT,BUY,40000,0.1,4000,1.6 USDT,0
+2024-01-01 08:00:00,ETHUSDT,BUY,2000,1,2000,0.8,-100
+not a date,ETHUSDT,BUY,2000,1,2000,0.8,10
+";
+
+    let (trades, summary) = parse_trade_history(TradeHistoryFormat::Binance, csv, "imported").unwrap();
+
+    assert_eq!(trades.len(), 2);
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(summary.errors.len(), 1);
+
+    // a short closed by buying 100 USDT above its entry
+    let eth = &trades[0];
+    assert_eq!(eth.direction, TradeDirection::Short);
+    assert_eq!(eth.entry_price, 1900.0);
+    assert!((eth.pnl + 100.8).abs() < 1e-9);
+
+    // a long closed by selling, opened by the earlier buy (including its fee)
+    let btc = &trades[1];
+    assert_eq!(btc.direction, TradeDirection::Long);
+    assert!((btc.entry_price - 40_000.0).abs() < 1e-6);
+    assert_eq!(btc.open_timestamp.to_rfc3339(), "2024-01-01T09:00:00+00:00");
+    assert!((btc.execution_fees - 3.4).abs() < 1e-9);
+    assert!((btc.pnl - 496.6).abs() < 1e-9);
+    assert_eq!(btc.settlement_usdt_rate, Some(1.0));
+}
+
+#[test]
+pub fn bybit_rows_are_imported_with_stable_keys() {
+    let csv = "\
+Contracts,Closing Direction,Qty,Entry Price,Exit Price,Closed P&L,Trade Time(UTC+0)
+SOLUSDT,Close Long,10,100,110,98.5,2024-03-01 12:00:00
+SOLUSDT,Close Short,10,110,100,99,2024-03-02 12:00:00
+";
+
+    let (trades, summary) = parse_trade_history(TradeHistoryFormat::Bybit, csv, "imported").unwrap();
+
+    assert_eq!(trades.len(), 2);
+    assert!(summary.errors.is_empty());
+    assert_eq!(trades[0].direction, TradeDirection::Long);
+    assert_eq!(trades[1].direction, TradeDirection::Short);
+    assert_eq!(trades[0].pnl, 98.5);
+
+    // re-importing the same rows yields the same keys, so they're skipped as duplicates
+    let (reimported, _) = parse_trade_history(TradeHistoryFormat::Bybit, csv, "imported").unwrap();
+    assert_eq!(reimported[0].import_key, trades[0].import_key);
+    assert_ne!(trades[0].import_key, trades[1].import_key);
+}
@@ -0,0 +1,80 @@
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::{api::leaderboard::{calc_leaderboard_entry, rank_leaderboard}, models::{ClosedPnl, LeaderboardEntry, LeaderboardMetric, StrategyTradeSequence}};
+
+fn entry(alert_name: &str, total_pnl: f64, total_roe: f64, max_drawdown: f64, profitable_days_percentage: f64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        rank: 0,
+        alert_name: alert_name.to_string(),
+        trades: 10,
+        total_pnl,
+        total_roe,
+        max_drawdown,
+        return_over_drawdown: (max_drawdown > 0.0).then(|| total_pnl / max_drawdown),
+        profitable_days_percentage,
+    }
+}
+
+#[test]
+pub fn leaderboard_entry_from_trades() {
+    let close = |day: u32, hour: u32, pnl: f64| ClosedPnl { close_timestamp: Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap(), pnl };
+
+    let sequence = StrategyTradeSequence {
+        alert_name: "trend".to_string(),
+        total_roe: 42.0,
+        // day 1: +20, day 2: -30 + 40 = +10, day 3: -5
+        trades: vec![close(1, 10, 20.0), close(2, 10, -30.0), close(2, 12, 40.0), close(3, 10, -5.0)],
+    };
+
+    let entry = calc_leaderboard_entry(&sequence, Tz::UTC);
+
+    assert_eq!(entry.trades, 4);
+    assert_eq!(entry.total_pnl, 25.0);
+    assert_eq!(entry.total_roe, 42.0);
+    assert_eq!(entry.max_drawdown, 30.0);
+    assert_eq!(entry.return_over_drawdown, Some(25.0 / 30.0));
+    assert!((entry.profitable_days_percentage - 200.0 / 3.0).abs() < 1e-9);
+
+    // 20:00 UTC is already the next day in Tokyo (UTC+9): -10, +40 in UTC but +20, +10 in Tokyo
+    let sequence = StrategyTradeSequence {
+        alert_name: "trend".to_string(),
+        total_roe: 0.0,
+        trades: vec![close(1, 10, 20.0), close(1, 20, -30.0), close(2, 10, 40.0)],
+    };
+
+    assert_eq!(calc_leaderboard_entry(&sequence, Tz::UTC).profitable_days_percentage, 50.0);
+    assert_eq!(calc_leaderboard_entry(&sequence, Tz::Asia__Tokyo).profitable_days_percentage, 100.0);
+}
+
+#[test]
+pub fn leaderboard_ranked_by_metric() {
+    let entries = vec![
+        entry("steady", 50.0, 20.0, 5.0, 90.0),
+        entry("volatile", 200.0, 80.0, 100.0, 40.0),
+        entry("flawless", 10.0, 5.0, 0.0, 100.0),
+        entry("losing", -20.0, -10.0, 30.0, 20.0),
+    ];
+
+    let names = |entries: Vec<LeaderboardEntry>| entries.into_iter().map(|entry| (entry.rank, entry.alert_name)).collect::<Vec<_>>();
+
+    assert_eq!(names(rank_leaderboard(entries.clone(), LeaderboardMetric::Roe, 10)), vec![
+        (1, "volatile".to_string()),
+        (2, "steady".to_string()),
+        (3, "flawless".to_string()),
+        (4, "losing".to_string()),
+    ]);
+
+    // a profitable strategy that never drew down ranks first
+    assert_eq!(names(rank_leaderboard(entries.clone(), LeaderboardMetric::RiskAdjustedReturn, 10)), vec![
+        (1, "flawless".to_string()),
+        (2, "steady".to_string()),
+        (3, "volatile".to_string()),
+        (4, "losing".to_string()),
+    ]);
+
+    assert_eq!(names(rank_leaderboard(entries, LeaderboardMetric::Consistency, 2)), vec![
+        (1, "flawless".to_string()),
+        (2, "steady".to_string()),
+    ]);
+}
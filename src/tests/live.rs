@@ -0,0 +1,186 @@
+use chrono::{Duration, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::{api::{calc_pnl, live::{apply_entry_fill, build_closed_live_trade, calc_live_close_retry_delay, is_live_tradable, split_partially_closed_live_trade}, trade::is_accepted_symbol}, exchanges::binance::{parse_order_fill, parse_symbol_filters, round_order_quantity, sign_query}, models::{ActiveTrade, BinanceOrderResponse, BinanceSymbolInfo, BinanceUserTrade, ContractType, LiveCloseTracker, OrderFill, OrderSide, SymbolFilters, TradeDirection, TradeKind, TradeLeverage}, tests::{active_trade, app_state}};
+
+fn order(status: &str) -> BinanceOrderResponse {
+    BinanceOrderResponse {
+        order_id: 42,
+        status: status.to_string(),
+        avg_price: "60000.50".to_string(),
+        executed_qty: "0.002".to_string(),
+    }
+}
+
+fn user_trade(commission: &str, commission_asset: &str) -> BinanceUserTrade {
+    BinanceUserTrade {
+        commission: commission.to_string(),
+        commission_asset: commission_asset.to_string(),
+    }
+}
+
+#[test]
+pub fn queries_are_signed_like_binance() {
+    // the example of Binance's API documentation
+    let query = "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559";
+    let secret = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
+
+    assert_eq!(sign_query(query, secret), "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71");
+}
+
+#[test]
+pub fn only_usdt_pairs_are_live_tradable() {
+    assert!(is_live_tradable("BTCUSDT"));
+    assert!(is_live_tradable("sol-usdt"));
+    assert!(!is_live_tradable("ETHBTC"));
+    assert!(!is_live_tradable("BTCUSD"));
+}
+
+#[tokio::test]
+pub async fn live_alerts_share_the_accepted_symbols() {
+    let app_state = app_state().await;
+
+    // a USDT pair can only be traded live if it's accepted for paper trades as well
+    assert!(is_accepted_symbol(&app_state, "btcusdt") && is_live_tradable("btcusdt"));
+    assert!(is_live_tradable("PEPEUSDT"));
+    assert!(!is_accepted_symbol(&app_state, "PEPEUSDT"));
+}
+
+#[test]
+pub fn order_sides_follow_the_direction() {
+    assert_eq!(OrderSide::opening(&TradeDirection::Long), OrderSide::Buy);
+    assert_eq!(OrderSide::closing(&TradeDirection::Long), OrderSide::Sell);
+    assert_eq!(OrderSide::opening(&TradeDirection::Short), OrderSide::Sell);
+    assert_eq!(OrderSide::closing(&TradeDirection::Short).as_str(), "BUY");
+}
+
+#[test]
+pub fn order_fills_sum_up_their_fees() {
+    // commissions paid in another asset (e.g. BNB) aren't in the settlement currency
+    let trades = [user_trade("0.024", "USDT"), user_trade("0.036", "USDT"), user_trade("0.0001", "BNB")];
+
+    let fill = parse_order_fill(&order("FILLED"), &trades, "USDT").unwrap();
+    assert_eq!(fill.order_id, 42);
+    assert_eq!(fill.price, 60000.5);
+    assert_eq!(fill.quantity, 0.002);
+    assert!((fill.fees - 0.06).abs() < 1e-9);
+
+    // without trades, the fees are unknown rather than failing the fill
+    assert_eq!(parse_order_fill(&order("FILLED"), &[], "USDT").unwrap().fees, 0.0);
+
+    // an order that expired after being partially filled is filled by its executed quantity
+    assert_eq!(parse_order_fill(&order("EXPIRED"), &trades, "USDT").unwrap().quantity, 0.002);
+    assert!(parse_order_fill(&BinanceOrderResponse { executed_qty: "0.000".to_string(), ..order("EXPIRED") }, &trades, "USDT").is_err());
+    assert!(parse_order_fill(&BinanceOrderResponse { avg_price: "n/a".to_string(), ..order("FILLED") }, &trades, "USDT").is_err());
+}
+
+#[test]
+pub fn order_quantities_follow_the_symbol_filters() {
+    let symbol: BinanceSymbolInfo = serde_json::from_value(serde_json::json!({
+        "symbol": "BTCUSDT",
+        "filters": [
+            { "filterType": "PRICE_FILTER", "tickSize": "0.10" },
+            { "filterType": "LOT_SIZE", "stepSize": "0.001", "minQty": "0.001", "maxQty": "1000" },
+            { "filterType": "MARKET_LOT_SIZE", "stepSize": "0.001", "minQty": "0.002", "maxQty": "120" },
+            { "filterType": "MIN_NOTIONAL", "notional": "100" },
+        ],
+    })).unwrap();
+
+    let filters = parse_symbol_filters(&symbol);
+    assert_eq!(filters, SymbolFilters { step_size: 0.001, precision: 3, min_quantity: 0.002, min_notional: 100.0 });
+
+    assert_eq!(round_order_quantity(0.0036, 60000.0, &filters, false), Ok("0.003".to_string()));
+    assert_eq!(round_order_quantity(0.003, 60000.0, &filters, false), Ok("0.003".to_string()));
+
+    // 100 USDT of BTC is less than a step
+    assert!(round_order_quantity(100.0 / 96289.34 * 0.5, 96289.34, &filters, false).is_err());
+    // below the minimum notional value, unless the order only reduces a position
+    assert!(round_order_quantity(0.0015, 60000.0, &filters, false).is_err());
+    assert!(round_order_quantity(0.0025, 30000.0, &filters, false).is_err());
+    assert_eq!(round_order_quantity(0.0025, 30000.0, &filters, true), Ok("0.002".to_string()));
+}
+
+#[test]
+pub fn live_trades_are_opened_at_their_fill() {
+    let mut trade = ActiveTrade {
+        kind: TradeKind::Live,
+        quantity: 2.0,
+        leverage: TradeLeverage::Ten,
+        exchange: Some("binance".to_string()),
+        ..active_trade()
+    };
+
+    // a partial fill is recorded with the filled quantity, and reports the requested one
+    assert_eq!(apply_entry_fill(&mut trade, &OrderFill { order_id: 7, price: 101.0, quantity: 1.5, fees: 0.06 }), Some(2.0));
+    assert_eq!(trade.quantity, 1.5);
+    assert_eq!(trade.entry_price, 101.0);
+    assert_eq!(trade.entry_fees, Some(0.06));
+    assert_eq!(trade.entry_order_id.as_deref(), Some("7"));
+    assert!(trade.liquidation_price < 101.0 && trade.liquidation_price > 90.0);
+
+    let mut trade = ActiveTrade { quantity: 2.0, ..trade };
+    assert_eq!(apply_entry_fill(&mut trade, &OrderFill { order_id: 8, price: 101.0, quantity: 2.0, fees: 0.08 }), None);
+}
+
+#[tokio::test]
+pub async fn live_trades_are_closed_at_their_fill() {
+    let app_state = app_state().await;
+
+    let trade = ActiveTrade {
+        alert_name: "breakout".to_string(),
+        kind: TradeKind::Live,
+        open_timestamp: Utc::now() - Duration::minutes(5),
+        leverage: TradeLeverage::Ten,
+        liquidation_price: 90.5,
+        exchange: Some("binance".to_string()),
+        entry_fees: Some(0.05),
+        ..active_trade()
+    };
+
+    // even a fill beyond the liquidation price isn't liquidated locally
+    let fill = OrderFill { order_id: 7, price: 89.0, quantity: 1.0, fees: 0.04 };
+    let closed_trade = build_closed_live_trade(&app_state, &trade, &fill);
+
+    assert!(matches!(closed_trade.kind, TradeKind::Live));
+    assert!(!closed_trade.liquidated);
+    assert_eq!(closed_trade.exit_price, 89.0);
+    assert_eq!(closed_trade.liquidation_price, 90.5);
+    assert!((closed_trade.execution_fees - 0.09).abs() < 1e-9);
+    assert_eq!(closed_trade.pnl, calc_pnl(100.0, 89.0, 1.0, closed_trade.execution_fees, closed_trade.funding_fees, &TradeDirection::Long, &ContractType::Linear));
+    assert_eq!(closed_trade.exit_order_id.as_deref(), Some("7"));
+
+    // a partially filled close only closes its quantity, splitting the entry fees
+    let fill = OrderFill { order_id: 8, price: 110.0, quantity: 0.4, fees: 0.02 };
+    let (closed_part, remaining_part) = split_partially_closed_live_trade(&app_state, &trade, &fill);
+
+    assert_ne!(closed_part.id, trade.id);
+    assert_eq!(closed_part.quantity, 0.4);
+    assert!((closed_part.execution_fees - 0.04).abs() < 1e-9);
+    assert_eq!(remaining_part.id, trade.id);
+    assert!((remaining_part.quantity - 0.6).abs() < 1e-9);
+    assert!((remaining_part.entry_fees.unwrap() - 0.03).abs() < 1e-9);
+}
+
+#[test]
+pub fn live_closes_are_retried_with_a_backoff() {
+    let tracker = LiveCloseTracker::default();
+    let (trade_id, now) = (ObjectId::new(), Utc::now());
+
+    // only one close is in flight at a time
+    assert!(tracker.try_start(trade_id, now));
+    assert!(!tracker.try_start(trade_id, now));
+
+    tracker.finish(trade_id, false, now);
+    assert!(!tracker.try_start(trade_id, now + Duration::seconds(1)));
+    assert!(tracker.try_start(trade_id, now + calc_live_close_retry_delay(1)));
+
+    // the wait doubles with each failure in a row, up to a maximum
+    tracker.finish(trade_id, false, now);
+    assert!(!tracker.try_start(trade_id, now + calc_live_close_retry_delay(1)));
+    assert!(tracker.try_start(trade_id, now + calc_live_close_retry_delay(2)));
+    assert_eq!(calc_live_close_retry_delay(2), calc_live_close_retry_delay(1) * 2);
+    assert_eq!(calc_live_close_retry_delay(u32::MAX), calc_live_close_retry_delay(64));
+
+    tracker.finish(trade_id, true, now);
+    assert!(tracker.attempts.lock().unwrap().is_empty());
+}
@@ -0,0 +1,170 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, time::{Duration, Instant}};
+
+use axum::{Extension, Json};
+use chrono::Utc;
+use dotenvy::dotenv;
+use futures_util::{stream, StreamExt};
+use mongodb::{options::ClientOptions, Client};
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use crate::{api::{to_coinbase_product_id, trade::execute_paper_trade, websocket::process_price_tick}, models::{AppState, MongoDBState, PriceFeedTick, RequestId}};
+
+/// The experiment that the synthetic trades are opened under, so that they can be deleted afterwards.
+const LOAD_TEST_EXPERIMENT: &str = "load-test";
+
+/// The pairs that the synthetic alerts and ticks are spread across, with a base price.
+const LOAD_TEST_PAIRS: &[(&str, f64)] = &[("BTCUSDT", 60_000.0), ("ETHUSDT", 3_000.0), ("SOLUSDT", 150.0), ("BNBUSDT", 600.0)];
+
+fn env_count(name: &str, default: usize) -> usize {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+/// Returns the `percentile` (0-100) of `sorted` durations.
+fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+
+    sorted[((sorted.len() - 1) as f64 * percentile / 100.0).round() as usize]
+}
+
+fn print_latencies(label: &str, mut latencies: Vec<Duration>, elapsed: Duration) {
+    latencies.sort();
+
+    println!(
+        "{}: {} in {:.2?} ({:.0}/s), p50 {:.2?}, p95 {:.2?}, p99 {:.2?}, max {:.2?}",
+        label,
+        latencies.len(),
+        elapsed,
+        latencies.len() as f64 / elapsed.as_secs_f64(),
+        percentile(&latencies, 50.0),
+        percentile(&latencies, 95.0),
+        percentile(&latencies, 99.0),
+        latencies.last().copied().unwrap_or_default(),
+    );
+}
+
+/// Repeatedly measures how long acquiring the shared locks takes while `done` isn't set, as a proxy of their contention.
+async fn probe_lock_contention(app_state: Arc<AppState>, done: Arc<AtomicBool>) -> (Vec<Duration>, Vec<Duration>) {
+    let mut active_trades_waits = Vec::new();
+    let mut latest_prices_waits = Vec::new();
+
+    while !done.load(Ordering::Relaxed) {
+        let started = Instant::now();
+        drop(app_state.active_trades.lock().unwrap());
+        active_trades_waits.push(started.elapsed());
+
+        let started = Instant::now();
+        drop(app_state.latest_prices.lock().unwrap());
+        latest_prices_waits.push(started.elapsed());
+
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    (active_trades_waits, latest_prices_waits)
+}
+
+/// Fires synthetic alerts and price ticks at an in-process instance, and reports the throughput, the latency and the contention
+/// of the shared locks. Requires `MONGODB_URI` (preferably a throwaway database) and `TRADINGVIEW_SECRET`.
+///
+/// Run with `cargo test --release --features load-test load -- --nocapture`. The load is sized by `LOAD_TEST_ALERTS` (default 2000),
+/// `LOAD_TEST_TICKS` (default 10000) and `LOAD_TEST_CONCURRENCY` (default 32).
+#[tokio::test(flavor = "multi_thread")]
+pub async fn load() {
+    dotenv().ok();
+
+    let alerts = env_count("LOAD_TEST_ALERTS", 2000);
+    let ticks = env_count("LOAD_TEST_TICKS", 10_000);
+    let concurrency = env_count("LOAD_TEST_CONCURRENCY", 32).max(1);
+
+    let mongodb_uri = std::env::var("MONGODB_URI").expect("(load) MONGODB_URI not set");
+    let secret = std::env::var("TRADINGVIEW_SECRET").expect("(load) TRADINGVIEW_SECRET not set");
+
+    let client = Client::with_options(ClientOptions::parse(mongodb_uri).await.unwrap()).unwrap();
+    let mongo_state = Arc::new(MongoDBState::new(Arc::new(client)));
+    let (ws_commands, _ws_commands_rx) = mpsc::unbounded_channel();
+    let app_state = Arc::new(AppState::new(mongo_state.clone(), ws_commands));
+
+    let done = Arc::new(AtomicBool::new(false));
+    let probe = tokio::spawn(probe_lock_contention(app_state.clone(), done.clone()));
+
+    // alerts: each synthetic strategy alternates between buying and selling its pair, so that trades are both opened and flipped
+    let started = Instant::now();
+    let results: Vec<(Duration, u16)> = stream::iter(0..alerts)
+        .map(|i| {
+            let (mongo_state, app_state, secret) = (mongo_state.clone(), app_state.clone(), secret.clone());
+            let (pair, price) = LOAD_TEST_PAIRS[i % LOAD_TEST_PAIRS.len()];
+
+            async move {
+                let payload = json!({
+                    "name": format!("load-test-{}", i % (concurrency * 2)),
+                    "signal": if (i / LOAD_TEST_PAIRS.len()).is_multiple_of(2) { "buy" } else { "sell" },
+                    "pair": pair,
+                    "price": price,
+                    "experiment": LOAD_TEST_EXPERIMENT,
+                    "idempotency_key": format!("load-test-{}-{}", Utc::now().timestamp_millis(), i),
+                    "timestamp": Utc::now().to_rfc3339(),
+                    "secret": secret,
+                });
+
+                let sent = Instant::now();
+                let (status, _) = execute_paper_trade(Extension(mongo_state), Extension(app_state), Extension(RequestId(format!("load-test-{}", i))), Json(payload)).await;
+
+                (sent.elapsed(), status.as_u16())
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    let alerts_elapsed = started.elapsed();
+
+    // ticks: prices wander around the base price of each pair, which may hit the exits of the opened trades
+    let backfill_client = reqwest::Client::new();
+    let started = Instant::now();
+    let tick_latencies: Vec<Duration> = stream::iter(0..ticks)
+        .map(|i| {
+            let (app_state, backfill_client) = (app_state.clone(), backfill_client.clone());
+            let (pair, price) = LOAD_TEST_PAIRS[i % LOAD_TEST_PAIRS.len()];
+
+            async move {
+                let tick = PriceFeedTick {
+                    product_id: to_coinbase_product_id(pair).unwrap_or_else(|| pair.to_string()),
+                    price: price * (1.0 + ((i % 200) as f64 - 100.0) / 10_000.0),
+                    sequence: None,
+                    trade_id: None,
+                };
+
+                let sent = Instant::now();
+                process_price_tick(&app_state, tick, &backfill_client).await;
+
+                sent.elapsed()
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+    let ticks_elapsed = started.elapsed();
+
+    done.store(true, Ordering::Relaxed);
+    let (active_trades_waits, latest_prices_waits) = probe.await.unwrap();
+
+    println!("--- load test ({} alerts, {} ticks, concurrency {}) ---", alerts, ticks, concurrency);
+    print_latencies("alerts", results.iter().map(|(latency, _)| *latency).collect(), alerts_elapsed);
+
+    let mut statuses: Vec<u16> = results.iter().map(|(_, status)| *status).collect();
+    statuses.sort();
+    statuses.dedup();
+
+    for status in statuses {
+        println!("  status {}: {}", status, results.iter().filter(|(_, other)| *other == status).count());
+    }
+
+    print_latencies("ticks", tick_latencies, ticks_elapsed);
+    print_latencies("active_trades lock waits", active_trades_waits, alerts_elapsed + ticks_elapsed);
+    print_latencies("latest_prices lock waits", latest_prices_waits, alerts_elapsed + ticks_elapsed);
+
+    // discard the synthetic trades
+    mongo_state.delete_experiment_active_trades(LOAD_TEST_EXPERIMENT).await.unwrap();
+    mongo_state.delete_experiment_closed_trades(LOAD_TEST_EXPERIMENT).await.unwrap();
+}
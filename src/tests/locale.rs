@@ -0,0 +1,51 @@
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::{api::export::equity_csv_row, models::{ClosedTradeExportQuery, DateFormat, DecimalSeparator, ReportLocale}};
+
+#[test]
+pub fn default_locale_keeps_the_utc_rfc3339_format() {
+    let locale = ReportLocale::from_lookup(|_| None);
+    let timestamp = Utc.with_ymd_and_hms(2024, 12, 31, 17, 30, 0).unwrap();
+
+    assert_eq!(locale, ReportLocale::default());
+    assert_eq!(equity_csv_row(timestamp, 1234.5, &locale), "2024-12-31T17:30:00+00:00,1234.50000000\n");
+}
+
+#[test]
+pub fn locale_formats_numbers_and_timestamps() {
+    let locale = ReportLocale::from_lookup(|name| match name {
+        "REPORT_DECIMAL_SEPARATOR" => Some("comma".to_string()),
+        "REPORT_DATE_FORMAT" => Some("European".to_string()),
+        "REPORT_TIMEZONE" => Some("Europe/Berlin".to_string()),
+        _ => None,
+    });
+    let timestamp = Utc.with_ymd_and_hms(2024, 12, 31, 17, 30, 0).unwrap();
+
+    assert_eq!(locale.format_fixed(-12.345, 2), "-12,35");
+    assert_eq!(locale.format_timestamp(timestamp), "31.12.2024 18:30:00");
+    assert_eq!(locale.format_timestamp_short(timestamp), "31.12.2024 18:30");
+    // fields are separated by semicolons, since the comma is the decimal separator
+    assert_eq!(equity_csv_row(timestamp, 1234.5, &locale), "31.12.2024 18:30:00;1234,50000000\n");
+
+    let us = ReportLocale { date_format: DateFormat::Us, timezone: Tz::America__New_York, ..ReportLocale::default() };
+    assert_eq!(us.format_timestamp(timestamp), "12/31/2024 12:30:00 PM");
+}
+
+#[test]
+pub fn export_queries_override_the_configured_locale() {
+    let locale = ReportLocale::from_lookup(|name| (name == "REPORT_TIMEZONE").then(|| "Not/AZone".to_string()));
+    // invalid values fall back to the default
+    assert_eq!(locale.timezone, Tz::UTC);
+
+    let query = ClosedTradeExportQuery {
+        decimal_separator: Some(DecimalSeparator::Comma),
+        timezone: Some(Tz::Asia__Tokyo),
+        ..Default::default()
+    };
+
+    let locale = locale.with_overrides(&query);
+    assert_eq!(locale.decimal_separator, DecimalSeparator::Comma);
+    assert_eq!(locale.date_format, DateFormat::Rfc3339);
+    assert_eq!(locale.timezone, Tz::Asia__Tokyo);
+}
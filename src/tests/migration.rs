@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+
+use mongodb::bson::doc;
+
+use crate::api::migration::{is_live_trade_group, migrations, pending_migrations};
+
+#[test]
+pub fn migration_versions_are_unique_and_ascending() {
+    let versions: Vec<u32> = migrations().iter().map(|migration| migration.version).collect();
+
+    assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+pub fn only_unapplied_migrations_are_pending() {
+    let pending: Vec<u32> = pending_migrations(migrations(), &HashSet::from([1]))
+        .iter()
+        .map(|migration| migration.version)
+        .collect();
+
+    assert!(!pending.contains(&1));
+    assert_eq!(pending.len(), migrations().len() - 1);
+    assert!(pending_migrations(migrations(), &migrations().iter().map(|migration| migration.version).collect()).is_empty());
+}
+
+#[test]
+pub fn only_paper_duplicates_are_archived() {
+    let group = |kind: &str| doc! { "_id": { "alertName": "breakout", "pair": "BTCUSDT", "kind": kind }, "ids": [1, 2] };
+
+    assert!(is_live_trade_group(&group("live")));
+    assert!(!is_live_trade_group(&group("paper")));
+}
@@ -1,3 +1,104 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use mongodb::{bson::oid::ObjectId, options::ClientOptions, Client};
+use tokio::sync::mpsc;
+
+use crate::models::{ActiveTrade, AppState, ContractType, MarginMode, MongoDBState, TradeDirection, TradeKind, TradeLeverage};
+
+pub mod alert;
+pub mod anomaly;
+pub mod cache;
+pub mod calendar;
+pub mod chaos;
+pub mod clock;
+pub mod command;
+pub mod conflict;
+pub mod consensus;
+pub mod consistency;
+pub mod correlation;
+pub mod db;
+pub mod encryption;
+pub mod env;
+pub mod exchange;
+pub mod export;
+pub mod feature;
+pub mod feed_quality;
+pub mod funding;
+pub mod grpc;
+pub mod import;
+pub mod leaderboard;
+pub mod live;
+#[cfg(feature = "load-test")]
+pub mod load;
+pub mod locale;
+pub mod migration;
+pub mod mqtt;
+pub mod network;
+pub mod notifier;
+pub mod outcome;
+pub mod plugin;
+pub mod pnl_snapshot;
+pub mod position;
+pub mod price_alert;
+pub mod readiness;
+pub mod regime;
+pub mod report;
+pub mod request;
+pub mod response;
+pub mod risk;
+pub mod rounding;
+pub mod scheduler;
+pub mod script;
+pub mod seed;
+pub mod shard;
+pub mod sizing;
+pub mod statement;
+pub mod stats;
+pub mod strategy;
+pub mod timeseries;
+pub mod tls;
 pub mod trade;
+pub mod trade_helpers;
+pub mod trade_replay;
+pub mod trade_tick;
+pub mod websocket;
+
+/// Builds an app state whose MongoDB client connects lazily, so no database is required as long as the test doesn't query it.
+pub async fn app_state() -> AppState {
+    let client = Client::with_options(ClientOptions::parse("mongodb://localhost:27017").await.unwrap()).unwrap();
+    let (ws_commands, _) = mpsc::unbounded_channel();
+
+    AppState::new(Arc::new(MongoDBState::new(Arc::new(client))), ws_commands)
+}
 
-pub use trade::*;
\ No newline at end of file
+/// Builds an active paper trade: a 1x long of 1 BTCUSDT at 100, opened now, without exits.
+///
+/// Tests override the fields they depend on with struct update syntax (`ActiveTrade { quantity: 2.0, ..active_trade() }`).
+pub fn active_trade() -> ActiveTrade {
+    ActiveTrade {
+        id: ObjectId::new(),
+        alert_name: "Sample Alert".to_string(),
+        pair: "BTCUSDT".to_string(),
+        direction: TradeDirection::Long,
+        kind: TradeKind::Paper,
+        open_timestamp: Utc::now(),
+        quantity: 1.0,
+        entry_price: 100.0,
+        leverage: TradeLeverage::One,
+        contract_type: ContractType::Linear,
+        liquidation_price: 0.0,
+        take_profit: None,
+        stop_loss: None,
+        near_maintenance: false,
+        experiment: None,
+        originating_request_id: None,
+        trailing_stop_percentage: None,
+        exchange: None,
+        margin_mode: MarginMode::Isolated,
+        partial_liquidations: Vec::new(),
+        regime: None,
+        entry_fees: None,
+        entry_order_id: None,
+    }
+}
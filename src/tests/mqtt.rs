@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use chrono::{TimeZone, Utc};
+
+use crate::models::{MqttConfig, MqttPublisher, TickerEvent, TradeEvent, TradeEventKind};
+
+#[test]
+pub fn mqtt_sink_is_configured_via_env() {
+    assert_eq!(MqttConfig::from_lookup(|_| None), None);
+
+    let config = MqttConfig::from_lookup(|name| match name {
+        "MQTT_HOST" => Some("broker.local".to_string()),
+        "MQTT_USERNAME" => Some("bot".to_string()),
+        "MQTT_PASSWORD" => Some("password".to_string()),
+        "MQTT_TICKERS" => Some("sol-usd, BTC-USD,".to_string()),
+        _ => None,
+    }).unwrap();
+
+    assert_eq!(config, MqttConfig {
+        host: "broker.local".to_string(),
+        port: 1883,
+        credentials: Some(("bot".to_string(), "password".to_string())),
+        topic_prefix: "tv-trading-bot".to_string(),
+        tickers: HashSet::from(["SOL-USD".to_string(), "BTC-USD".to_string()]),
+    });
+
+    // a username without a password isn't used
+    let config = MqttConfig::from_lookup(|name| match name {
+        "MQTT_HOST" => Some("broker.local".to_string()),
+        "MQTT_PORT" => Some("8883".to_string()),
+        "MQTT_USERNAME" => Some("bot".to_string()),
+        "MQTT_TOPIC_PREFIX" => Some("home/trading".to_string()),
+        _ => None,
+    }).unwrap();
+
+    assert_eq!((config.port, config.credentials, config.topic_prefix.as_str()), (8883, None, "home/trading"));
+}
+
+#[test]
+pub fn mqtt_topics_and_payloads() {
+    let publisher = MqttPublisher { topic_prefix: "home/trading".to_string(), ..MqttPublisher::default() };
+
+    assert_eq!(publisher.trade_event_topic(TradeEventKind::PartiallyLiquidated, "breakout"), "home/trading/trades/breakout/partially_liquidated");
+    assert_eq!(publisher.ticker_topic("SOL-USD"), "home/trading/tickers/SOL-USD");
+
+    let trade = serde_json::json!({ "pair": "SOLUSDT" });
+    assert_eq!(
+        serde_json::to_value(TradeEvent { event: TradeEventKind::Opened, trade: &trade }).unwrap(),
+        serde_json::json!({ "event": "opened", "trade": { "pair": "SOLUSDT" } })
+    );
+
+    let timestamp = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    assert_eq!(
+        serde_json::to_value(TickerEvent { product_id: "SOL-USD", price: 150.5, timestamp }).unwrap(),
+        serde_json::json!({ "productId": "SOL-USD", "price": 150.5, "timestamp": 1735732800 })
+    );
+
+    // a disabled publisher silently drops events
+    publisher.publish_trade_event(TradeEventKind::Closed, "breakout", &trade);
+    publisher.publish_ticker("SOL-USD", 150.5, timestamp);
+}
@@ -0,0 +1,29 @@
+use crate::models::{NotificationSeverity, NotificationSink, NotificationTarget};
+
+#[test]
+pub fn targets_filter_by_minimum_severity() {
+    let push = NotificationTarget {
+        sink: NotificationSink::Ntfy { topic_url: "https://ntfy.sh/test".to_string(), access_token: None },
+        min_severity: NotificationSeverity::Critical,
+    };
+
+    assert!(push.accepts(NotificationSeverity::Critical));
+    assert!(!push.accepts(NotificationSeverity::Warning));
+    assert!(!push.accepts(NotificationSeverity::Info));
+
+    let webhook = NotificationTarget {
+        sink: NotificationSink::Webhook { url: "https://example.com/webhook".to_string() },
+        min_severity: NotificationSeverity::Warning,
+    };
+
+    assert!(webhook.accepts(NotificationSeverity::Critical));
+    assert!(webhook.accepts(NotificationSeverity::Warning));
+    assert!(!webhook.accepts(NotificationSeverity::Info));
+}
+
+#[test]
+pub fn parse_severity() {
+    assert_eq!(" Critical".parse::<NotificationSeverity>(), Ok(NotificationSeverity::Critical));
+    assert_eq!("warning".parse::<NotificationSeverity>(), Ok(NotificationSeverity::Warning));
+    assert!("urgent".parse::<NotificationSeverity>().is_err());
+}
@@ -0,0 +1,35 @@
+use crate::{api::outcome::validate_callback_url, models::{AlertOutcomeKind, ResponseCode}};
+
+#[test]
+pub fn outcomes_summarize_response_codes() {
+    assert_eq!(AlertOutcomeKind::from_code(Some(ResponseCode::TradeOpened)), AlertOutcomeKind::Opened);
+    assert_eq!(AlertOutcomeKind::from_code(Some(ResponseCode::TradeFlipped)), AlertOutcomeKind::Flipped);
+    assert_eq!(AlertOutcomeKind::from_code(Some(ResponseCode::AlertIgnoredSameDirection)), AlertOutcomeKind::Ignored);
+    assert_eq!(AlertOutcomeKind::from_code(Some(ResponseCode::StrategyDisabled)), AlertOutcomeKind::Rejected);
+    assert_eq!(AlertOutcomeKind::from_code(Some(ResponseCode::InternalError)), AlertOutcomeKind::Failed);
+    assert_eq!(AlertOutcomeKind::from_code(None), AlertOutcomeKind::Failed);
+}
+
+#[test]
+pub fn callback_urls_must_not_target_internal_addresses() {
+    assert!(validate_callback_url("https://example.com/outcomes").is_ok());
+    assert!(validate_callback_url("http://93.184.216.34:8080/outcomes").is_ok());
+
+    for url in [
+        "ftp://example.com/outcomes",
+        "http://localhost:3000/outcomes",
+        "http://api.localhost/outcomes",
+        "http://127.0.0.1/outcomes",
+        "http://10.0.0.5/outcomes",
+        "http://192.168.1.1/outcomes",
+        "http://169.254.169.254/latest/meta-data",
+        "http://100.64.0.1/outcomes",
+        "http://0.0.0.0/outcomes",
+        "http://[::1]/outcomes",
+        "http://[fd00::1]/outcomes",
+        "http://[fe80::1]/outcomes",
+        "http://[::ffff:127.0.0.1]/outcomes",
+    ] {
+        assert!(validate_callback_url(url).is_err(), "{} should be rejected", url);
+    }
+}
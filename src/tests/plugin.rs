@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::json;
+
+use crate::{models::{tradingview::TradingViewAlert, ActiveTrade, AlertFilter, ExitRule, FilterDecision, FilterOverrides, PluginRegistry, StrategyParameters, TradeDirection}, tests::active_trade};
+
+/// Rejects alerts on pairs other than `SOLUSDT`.
+struct SolOnly;
+
+impl AlertFilter for SolOnly {
+    fn name(&self) -> &'static str {
+        "sol_only"
+    }
+
+    fn filter(&self, alert: &TradingViewAlert, _parameters: &StrategyParameters) -> FilterDecision {
+        if alert.pair == "SOLUSDT" {
+            FilterDecision::Allow(FilterOverrides::default())
+        } else {
+            FilterDecision::Deny(format!("{} is not traded", alert.pair))
+        }
+    }
+}
+
+/// Doubles the notional value of the strategy.
+struct DoubleSize;
+
+impl AlertFilter for DoubleSize {
+    fn name(&self) -> &'static str {
+        "double_size"
+    }
+
+    fn filter(&self, _alert: &TradingViewAlert, parameters: &StrategyParameters) -> FilterDecision {
+        FilterDecision::Allow(FilterOverrides {
+            notional_value: Some(parameters.notional_value.unwrap_or(100.0) * 2.0),
+            ..FilterOverrides::default()
+        })
+    }
+}
+
+/// Closes trades once the price moved 1% in their favor.
+struct OnePercentProfit;
+
+impl ExitRule for OnePercentProfit {
+    fn name(&self) -> &'static str {
+        "one_percent_profit"
+    }
+
+    fn should_exit(&self, trade: &ActiveTrade, price: f64, _now: DateTime<Utc>) -> bool {
+        match trade.direction {
+            TradeDirection::Long => price >= trade.entry_price * 1.01,
+            TradeDirection::Short => price <= trade.entry_price * 0.99,
+        }
+    }
+}
+
+fn alert(pair: &str) -> TradingViewAlert {
+    serde_json::from_value(json!({
+        "name": "breakout",
+        "signal": "buy",
+        "pair": pair,
+        "price": 150.0,
+        "take_profit": null,
+        "stop_loss": null,
+        "timestamp": "2025-01-01T12:00:00Z",
+        "secret": "secret",
+    })).unwrap()
+}
+
+#[test]
+pub fn alert_filters_run_in_order() {
+    let plugins = PluginRegistry::default()
+        .with_alert_filter(Arc::new(SolOnly))
+        .with_alert_filter(Arc::new(DoubleSize))
+        .with_alert_filter(Arc::new(DoubleSize));
+
+    let mut sol = alert("SOLUSDT");
+    let mut parameters = StrategyParameters { notional_value: Some(50.0), ..StrategyParameters::default() };
+
+    // each filter sees the sizing of the ones before it
+    assert_eq!(plugins.filter_alert(&mut sol, &mut parameters), Ok(()));
+    assert_eq!(parameters.notional_value, Some(200.0));
+
+    let mut eth = alert("ETHUSDT");
+    let mut parameters = StrategyParameters::default();
+
+    assert_eq!(plugins.filter_alert(&mut eth, &mut parameters), Err(("sol_only", "ETHUSDT is not traded".to_string())));
+    assert_eq!(parameters.notional_value, None);
+}
+
+#[test]
+pub fn exit_rules_close_trades() {
+    let plugins = PluginRegistry::default().with_exit_rule(Arc::new(OnePercentProfit));
+    let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+    let trade = ActiveTrade {
+        pair: "SOLUSDT".to_string(),
+        open_timestamp: now - Duration::hours(1),
+        ..active_trade()
+    };
+
+    assert_eq!(plugins.exit_rule_hit(&trade, 100.5, now), None);
+    assert_eq!(plugins.exit_rule_hit(&trade, 101.0, now), Some("one_percent_profit"));
+    // no exit rules are registered by default
+    assert_eq!(PluginRegistry::default().exit_rule_hit(&trade, 101.0, now), None);
+}
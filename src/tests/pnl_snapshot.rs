@@ -0,0 +1,24 @@
+use chrono::Utc;
+
+use crate::{api::pnl_snapshot::build_trade_pnl_snapshot, models::{ActiveTrade, TradeDirection, TradeLeverage}, tests::active_trade};
+
+#[test]
+pub fn pnl_snapshot_excludes_fees() {
+    let trade = ActiveTrade {
+        pair: "SOLUSDT".to_string(),
+        direction: TradeDirection::Short,
+        quantity: 10.0,
+        leverage: TradeLeverage::Ten,
+        liquidation_price: 109.5,
+        ..active_trade()
+    };
+
+    let now = Utc::now();
+    let snapshot = build_trade_pnl_snapshot(&trade, 95.0, now);
+
+    assert_eq!(snapshot.trade_id, trade.id);
+    assert_eq!(snapshot.timestamp, now);
+    // a short gains 5 USDT per SOL, on a margin of 100 USDT
+    assert_eq!(snapshot.unrealized_pnl, 50.0);
+    assert!((snapshot.roe - 50.0).abs() < 1e-9);
+}
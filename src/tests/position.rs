@@ -0,0 +1,92 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::{api::{calc_liquidation_price, position::{calc_net_position, mark_net_position}}, models::{ActiveTrade, ContractType, ExchangeProfile, TradeDirection, TradeLeverage}, tests::active_trade};
+
+fn trade(alert_name: &str, direction: TradeDirection, quantity: f64, entry_price: f64, leverage: TradeLeverage, contract_type: ContractType, opened_after_mins: i64) -> ActiveTrade {
+    ActiveTrade {
+        alert_name: alert_name.to_string(),
+        direction,
+        open_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::minutes(opened_after_mins),
+        quantity,
+        entry_price,
+        leverage,
+        contract_type,
+        ..active_trade()
+    }
+}
+
+#[test]
+pub fn trades_are_netted_in_the_order_they_were_opened() {
+    let profile = ExchangeProfile::resolve(None);
+
+    // the short (opened last) reduces the position without changing its entry price
+    let trades = vec![
+        trade("b", TradeDirection::Short, 0.5, 130.0, TradeLeverage::Five, ContractType::Linear, 2),
+        trade("a", TradeDirection::Long, 1.0, 100.0, TradeLeverage::Ten, ContractType::Linear, 0),
+        trade("b", TradeDirection::Long, 1.0, 120.0, TradeLeverage::Ten, ContractType::Linear, 1),
+    ];
+
+    let position = calc_net_position(&trades, &profile, 1.0).unwrap();
+
+    assert_eq!(position.direction, Some(TradeDirection::Long));
+    assert_eq!(position.quantity, 1.5);
+    assert_eq!((position.long_quantity, position.short_quantity), (2.0, 0.5));
+    assert_eq!(position.average_entry_price, Some(110.0));
+    assert_eq!(position.strategies, vec!["a".to_string(), "b".to_string()]);
+    assert_eq!(position.trades, 3);
+
+    // the margin of all trades backs the net position
+    assert!((position.margin - 35.0).abs() < 1e-9);
+
+    let leverage = position.effective_leverage.unwrap();
+    assert!((leverage - 165.0 / 35.0).abs() < 1e-9);
+    assert_eq!(position.liquidation_price, Some(calc_liquidation_price(110.0, leverage, &TradeDirection::Long, &ContractType::Linear, profile.maintenance_margin_percentage(165.0))));
+
+    let mut position = position;
+    mark_net_position(&mut position, 120.0);
+
+    assert_eq!(position.unrealized_pnl, Some(15.0));
+    assert!(position.liquidation_distance_percentage.unwrap() > 0.0);
+}
+
+#[test]
+pub fn opposite_trades_flip_or_flatten_the_position() {
+    let profile = ExchangeProfile::resolve(None);
+
+    let flipped = calc_net_position(&[
+        trade("a", TradeDirection::Long, 1.0, 100.0, TradeLeverage::One, ContractType::Linear, 0),
+        trade("b", TradeDirection::Short, 3.0, 90.0, TradeLeverage::One, ContractType::Linear, 1),
+    ], &profile, 1.0).unwrap();
+
+    assert_eq!(flipped.direction, Some(TradeDirection::Short));
+    assert_eq!(flipped.quantity, 2.0);
+    assert_eq!(flipped.average_entry_price, Some(90.0));
+
+    let mut flat = calc_net_position(&[
+        trade("a", TradeDirection::Long, 1.0, 100.0, TradeLeverage::Two, ContractType::Linear, 0),
+        trade("b", TradeDirection::Short, 1.0, 105.0, TradeLeverage::Two, ContractType::Linear, 1),
+    ], &profile, 1.0).unwrap();
+
+    mark_net_position(&mut flat, 110.0);
+
+    assert_eq!(flat.direction, None);
+    assert_eq!(flat.quantity, 0.0);
+    assert_eq!(flat.average_entry_price, None);
+    assert_eq!(flat.liquidation_price, None);
+    assert_eq!(flat.unrealized_pnl, None);
+    assert_eq!(flat.mark_price, Some(110.0));
+
+    assert!(calc_net_position(&[], &profile, 1.0).is_none());
+}
+
+#[test]
+pub fn inverse_entry_prices_are_averaged_harmonically() {
+    let position = calc_net_position(&[
+        trade("a", TradeDirection::Long, 1000.0, 50_000.0, TradeLeverage::One, ContractType::Inverse, 0),
+        trade("b", TradeDirection::Long, 1000.0, 100_000.0, TradeLeverage::One, ContractType::Inverse, 1),
+    ], &ExchangeProfile::resolve(None), 1.0).unwrap();
+
+    // 2000 USD of contracts bought for 0.02 + 0.01 BTC
+    assert!((position.average_entry_price.unwrap() - 2000.0 / 0.03).abs() < 1e-6);
+    assert!((position.margin - 0.03).abs() < 1e-12);
+}
@@ -0,0 +1,20 @@
+use crate::{api::price_alert::is_price_alert_hit, models::PriceAlertCondition};
+
+#[test]
+pub fn price_level_alerts() {
+    assert!(is_price_alert_hit(&PriceAlertCondition::Above { price: 200.0 }, 200.0));
+    assert!(!is_price_alert_hit(&PriceAlertCondition::Above { price: 200.0 }, 199.9));
+    assert!(is_price_alert_hit(&PriceAlertCondition::Below { price: 100.0 }, 99.0));
+    assert!(!is_price_alert_hit(&PriceAlertCondition::Below { price: 100.0 }, 101.0));
+}
+
+#[test]
+pub fn percent_move_alerts_trigger_in_both_directions() {
+    let condition = PriceAlertCondition::PercentMove { percent: 5.0, reference_price: Some(100.0) };
+
+    assert!(is_price_alert_hit(&condition, 105.0));
+    assert!(is_price_alert_hit(&condition, 95.0));
+    assert!(!is_price_alert_hit(&condition, 104.0));
+    // without a reference price, the move cannot be measured
+    assert!(!is_price_alert_hit(&PriceAlertCondition::PercentMove { percent: 5.0, reference_price: None }, 1000.0));
+}
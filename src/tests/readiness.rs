@@ -0,0 +1,76 @@
+use crate::{api::readiness::{check_chaos_mode, check_exchange_credentials, check_indexes, check_secrets, check_symbols}, models::{CheckStatus, ReadinessReport}};
+
+#[test]
+pub fn missing_critical_secrets_fail_the_report() {
+    let checks = check_secrets(|name| (name == "COMMAND_SECRET").then(|| "secret".to_string()));
+
+    let tradingview = checks.iter().find(|check| check.name == "secret TRADINGVIEW_SECRET").unwrap();
+    assert_eq!(tradingview.status, CheckStatus::Failed);
+    assert!(tradingview.critical);
+
+    assert!(!ReadinessReport::new(checks).ready);
+
+    // empty secrets count as missing, but only critical ones fail the report
+    let checks = check_secrets(|name| Some(if name == "COMMAND_SECRET" { String::new() } else { "secret".to_string() }));
+    let command = checks.iter().find(|check| check.name == "secret COMMAND_SECRET").unwrap();
+    assert_eq!(command.status, CheckStatus::Warning);
+
+    assert!(ReadinessReport::new(checks).ready);
+}
+
+#[test]
+pub fn missing_indexes_are_reported() {
+    let existing = || Ok(vec!["_id_".to_string(), "importKey_1".to_string()]);
+
+    assert_eq!(check_indexes("closed trades", existing(), &["importKey_1"]).status, CheckStatus::Passed);
+
+    let check = check_indexes("active trades", existing(), &["alertName_1_pair_1_kind_1"]);
+    assert_eq!(check.status, CheckStatus::Failed);
+    assert!(check.message.contains("alertName_1_pair_1_kind_1"));
+}
+
+#[test]
+pub fn unresolvable_symbols_are_flagged() {
+    assert_eq!(check_symbols(&["BTCUSDT", "ETHBTC"]).status, CheckStatus::Passed);
+
+    let check = check_symbols(&["BTCUSDT", "FOOBAR"]);
+    assert_eq!(check.status, CheckStatus::Warning);
+    assert!(check.message.contains("FOOBAR"));
+}
+
+#[test]
+pub fn chaos_mode_and_live_trading_are_exclusive() {
+    let lookup = |chaos: &'static str, live: &'static str| move |name: &str| match name {
+        "CHAOS_MODE" => Some(chaos.to_string()),
+        "FEATURE_LIVE_TRADING" => Some(live.to_string()),
+        _ => None,
+    };
+
+    let check = check_chaos_mode(lookup("true", "true"));
+    assert_eq!(check.status, CheckStatus::Failed);
+    assert!(!ReadinessReport::new(vec![check]).ready);
+
+    assert_eq!(check_chaos_mode(lookup("true", "false")).status, CheckStatus::Warning);
+    assert_eq!(check_chaos_mode(lookup("false", "true")).status, CheckStatus::Passed);
+}
+
+#[test]
+pub fn live_trading_requires_exchange_credentials() {
+    let disabled = check_exchange_credentials(|_| None);
+    assert_eq!(disabled.status, CheckStatus::Skipped);
+
+    let missing_secret = check_exchange_credentials(|name| match name {
+        "FEATURE_LIVE_TRADING" => Some("true".to_string()),
+        "BINANCE_API_KEY" => Some("key".to_string()),
+        _ => None,
+    });
+    assert_eq!(missing_secret.status, CheckStatus::Failed);
+    assert!(missing_secret.message.contains("BINANCE_API_SECRET"));
+
+    let configured = check_exchange_credentials(|name| match name {
+        "FEATURE_LIVE_TRADING" => Some("true".to_string()),
+        "BINANCE_API_KEY" | "BINANCE_API_SECRET" => Some("value".to_string()),
+        _ => None,
+    });
+    assert_eq!(configured.status, CheckStatus::Passed);
+}
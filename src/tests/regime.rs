@@ -0,0 +1,62 @@
+use crate::{api::regime::{calc_atr_percentile, calc_ema, calc_ema_slope_percentage, classify_market_regime}, models::{DailyCandle, TrendRegime, VolatilityRegime}};
+
+/// Daily candles closing at each price, ranging `range` around the close.
+fn candles(closes: &[f64], range: impl Fn(usize) -> f64) -> Vec<DailyCandle> {
+    closes
+        .iter()
+        .enumerate()
+        .map(|(day, close)| DailyCandle {
+            product: "BTC-USD".to_string(),
+            day: format!("day-{:03}", day),
+            high: close + range(day) / 2.0,
+            low: close - range(day) / 2.0,
+            close: *close,
+        })
+        .collect()
+}
+
+#[test]
+pub fn ema_of_values() {
+    let ema = calc_ema(&[1.0, 2.0, 3.0, 4.0, 5.0], 3);
+
+    // seeded with the average of the first 3 values, then smoothed by 2 / (3 + 1)
+    assert_eq!(ema, vec![2.0, 3.0, 4.0]);
+    assert!(calc_ema(&[1.0, 2.0], 3).is_empty());
+}
+
+#[test]
+pub fn ema_slope_of_trends() {
+    let rising: Vec<f64> = (0..40).map(|day| 100.0 * 1.01_f64.powi(day)).collect();
+    let falling: Vec<f64> = rising.iter().rev().copied().collect();
+
+    assert!(calc_ema_slope_percentage(&candles(&rising, |_| 1.0)).unwrap() > 4.0);
+    assert!(calc_ema_slope_percentage(&candles(&falling, |_| 1.0)).unwrap() < -4.0);
+    assert!(calc_ema_slope_percentage(&candles(&[100.0; 40], |_| 1.0)).unwrap().abs() < 1e-9);
+    // not enough days for the EMA and its slope
+    assert_eq!(calc_ema_slope_percentage(&candles(&[100.0; 20], |_| 1.0)), None);
+}
+
+#[test]
+pub fn atr_percentile_of_latest_day() {
+    // the range widens every day, so the latest ATR is the highest
+    let widening = candles(&[100.0; 40], |day| 1.0 + day as f64 * 0.1);
+    let narrowing = candles(&[100.0; 40], |day| 5.0 - day as f64 * 0.1);
+
+    assert!(calc_atr_percentile(&widening).unwrap() > 90.0);
+    assert_eq!(calc_atr_percentile(&narrowing), Some(0.0));
+    assert_eq!(calc_atr_percentile(&candles(&[100.0; 10], |_| 1.0)), None);
+}
+
+#[test]
+pub fn market_regime_classification() {
+    let rising: Vec<f64> = (0..40).map(|day| 100.0 * 1.01_f64.powi(day)).collect();
+    let regime = classify_market_regime(&candles(&rising, |day| 5.0 - day as f64 * 0.1)).unwrap();
+
+    assert_eq!(regime.trend, TrendRegime::Up);
+    assert_eq!(regime.volatility, VolatilityRegime::Quiet);
+
+    let regime = classify_market_regime(&candles(&[100.0; 40], |day| 1.0 + day as f64 * 0.1)).unwrap();
+
+    assert_eq!(regime.trend, TrendRegime::Flat);
+    assert_eq!(regime.volatility, VolatilityRegime::Volatile);
+}
@@ -0,0 +1,36 @@
+use chrono::{TimeZone, Utc};
+
+use crate::{api::report::{calc_due_report_window, escape_html}, models::ReportPeriod};
+
+#[test]
+pub fn daily_report_is_due_after_midnight() {
+    let before_midnight = Utc.with_ymd_and_hms(2025, 1, 7, 23, 59, 30).unwrap();
+    let after_midnight = Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 30).unwrap();
+
+    assert_eq!(
+        calc_due_report_window(ReportPeriod::Daily, before_midnight, after_midnight),
+        Some((Utc.with_ymd_and_hms(2025, 1, 7, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 0).unwrap()))
+    );
+
+    // not due again within the same day
+    assert_eq!(calc_due_report_window(ReportPeriod::Daily, after_midnight, after_midnight + chrono::Duration::minutes(1)), None);
+}
+
+#[test]
+pub fn weekly_report_is_due_on_monday() {
+    // 2025-01-06 is a Monday
+    let sunday = Utc.with_ymd_and_hms(2025, 1, 5, 23, 59, 30).unwrap();
+    let monday = Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 30).unwrap();
+    let tuesday = Utc.with_ymd_and_hms(2025, 1, 7, 0, 0, 30).unwrap();
+
+    assert_eq!(
+        calc_due_report_window(ReportPeriod::Weekly, sunday, monday),
+        Some((Utc.with_ymd_and_hms(2024, 12, 30, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap()))
+    );
+    assert_eq!(calc_due_report_window(ReportPeriod::Weekly, monday, tuesday), None);
+}
+
+#[test]
+pub fn html_is_escaped() {
+    assert_eq!(escape_html("<b>\"a&b\"</b>"), "&lt;b&gt;&quot;a&amp;b&quot;&lt;/b&gt;");
+}
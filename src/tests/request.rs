@@ -0,0 +1,17 @@
+use crate::api::request::resolve_request_id;
+
+#[test]
+pub fn request_ids_are_reused_or_generated() {
+    assert_eq!(resolve_request_id(Some("tv-alert-42")), "tv-alert-42");
+
+    // missing, empty, too long or unprintable IDs are replaced with a generated one
+    for incoming in [None, Some(""), Some("contains spaces"), Some(&*"a".repeat(129))] {
+        let request_id = resolve_request_id(incoming);
+
+        assert_eq!(request_id.len(), 24);
+        assert_ne!(Some(request_id.as_str()), incoming);
+    }
+
+    // each generated ID is unique
+    assert_ne!(resolve_request_id(None), resolve_request_id(None));
+}
@@ -0,0 +1,48 @@
+use axum::Json;
+use chrono::Utc;
+use hyper::StatusCode;
+
+use crate::{api::alert::replay_alert_claim, models::{AlertClaim, AlertOutcome, ApiResponse, RejectionReason, ResponseCode, ResponseVerbosity}};
+
+#[test]
+pub fn minimal_verbosity_only_keeps_the_code() {
+    let response = || (
+        StatusCode::OK,
+        Json(ApiResponse::<()> {
+            status: "200 OK",
+            code: Some(ResponseCode::TradeOpened),
+            message: "(execute_paper_trade) Opened new trade successfully.".to_string(),
+            data: None
+        })
+    );
+
+    let (_, Json(verbose)) = ResponseVerbosity::Verbose.apply(response());
+    assert!(!verbose.message.is_empty());
+
+    let (status_code, Json(minimal)) = ResponseVerbosity::Minimal.apply(response());
+    assert_eq!(status_code, StatusCode::OK);
+    assert_eq!(minimal.code, Some(ResponseCode::TradeOpened));
+    assert_eq!(serde_json::to_value(&minimal).unwrap(), serde_json::json!({ "status": "200 OK", "code": "TRADE_OPENED", "data": null }));
+}
+
+#[test]
+pub fn rejections_and_replays_keep_their_code() {
+    assert_eq!(ResponseCode::from(RejectionReason::InvalidSecret), ResponseCode::InvalidSecret);
+    assert_eq!("minimal".parse::<ResponseVerbosity>(), Ok(ResponseVerbosity::Minimal));
+
+    let claim = AlertClaim {
+        id: "key".to_string(),
+        holder: "instance".to_string(),
+        claimed_timestamp: Utc::now(),
+        claimed_at: None,
+        outcome: Some(AlertOutcome { status_code: 200, code: Some(ResponseCode::TradeFlipped), message: "flipped".to_string() }),
+    };
+
+    let (status_code, Json(response)) = replay_alert_claim(claim.clone());
+    assert_eq!(status_code, StatusCode::OK);
+    assert_eq!(response.code, Some(ResponseCode::TradeFlipped));
+
+    let (status_code, Json(response)) = replay_alert_claim(AlertClaim { outcome: None, ..claim });
+    assert_eq!(status_code, StatusCode::CONFLICT);
+    assert_eq!(response.code, Some(ResponseCode::AlertInProgress));
+}
@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{api::risk::{calc_daily_returns, calc_historical_var, calc_stop_loss_risk, cap_notional_value, stress_position, summarize_stress_test}, models::{ActiveTrade, ContractType, DailyCandle, ProductExposure, RiskCapAction, StressTestRequest, TradeDirection, TradeLeverage}, tests::active_trade};
+
+fn trade(pair: &str, direction: TradeDirection, (quantity, entry_price): (f64, f64), leverage: TradeLeverage, liquidation_price: f64, contract_type: ContractType) -> ActiveTrade {
+    ActiveTrade {
+        pair: pair.to_string(),
+        direction,
+        quantity,
+        entry_price,
+        leverage,
+        contract_type,
+        liquidation_price,
+        ..active_trade()
+    }
+}
+
+#[test]
+pub fn stressed_positions_liquidate_past_their_liquidation_price() {
+    let long = trade("BTCUSDT", TradeDirection::Long, (1.0, 100.0), TradeLeverage::Ten, 90.5, ContractType::Linear);
+
+    let position = stress_position(&long, 100.0, -5.0, (1.0, 1.0));
+    assert_eq!((position.shocked_price, position.shocked_pnl, position.margin, position.liquidated), (95.0, -5.0, 10.0, false));
+
+    // the loss of a liquidated trade is clamped to its margin
+    let position = stress_position(&long, 100.0, -20.0, (1.0, 1.0));
+    assert!(position.liquidated);
+    assert_eq!(position.shocked_pnl, -10.0);
+
+    let short = trade("ETHUSDT", TradeDirection::Short, (2.0, 50.0), TradeLeverage::Five, 59.5, ContractType::Linear);
+
+    let position = stress_position(&short, 50.0, 5.0, (1.0, 1.0));
+    assert_eq!((position.current_pnl, position.shocked_pnl, position.margin, position.liquidated), (0.0, -5.0, 20.0, false));
+}
+
+#[test]
+pub fn stressed_inverse_positions_use_the_shocked_settlement_rate() {
+    let long = trade("BTCUSD", TradeDirection::Long, (1000.0, 100000.0), TradeLeverage::Two, 67333.0, ContractType::Inverse);
+
+    // the PnL and margin are settled in BTC, which is worth 10% less after the shock
+    let position = stress_position(&long, 100000.0, -10.0, (100000.0, 90000.0));
+
+    assert!((position.shocked_pnl - -100.0).abs() < 1e-9);
+    assert!((position.margin - 450.0).abs() < 1e-9);
+    assert!(!position.liquidated);
+}
+
+#[test]
+pub fn stress_test_summary() {
+    let long = trade("BTCUSDT", TradeDirection::Long, (1.0, 100.0), TradeLeverage::Ten, 90.5, ContractType::Linear);
+    let short = trade("ETHUSDT", TradeDirection::Short, (2.0, 50.0), TradeLeverage::Five, 59.5, ContractType::Linear);
+
+    let positions = vec![stress_position(&long, 102.0, -20.0, (1.0, 1.0)), stress_position(&short, 50.0, 5.0, (1.0, 1.0))];
+    let result = summarize_stress_test(positions, vec!["SOLUSDT".to_string()], 1000.0, 50.0);
+
+    assert_eq!(result.current_unrealized_pnl, 2.0);
+    assert_eq!(result.shocked_unrealized_pnl, -15.0);
+    assert_eq!(result.current_equity, 1052.0);
+    assert_eq!(result.shocked_equity, 1035.0);
+    // the liquidated long no longer uses margin
+    assert_eq!(result.margin, 20.0);
+    assert_eq!(result.margin_usage_percentage, Some(20.0 / 1035.0 * 100.0));
+    assert_eq!(result.unpriced_pairs, vec!["SOLUSDT".to_string()]);
+}
+
+#[test]
+pub fn stress_test_shocks() {
+    let request = StressTestRequest { shocks: HashMap::from([("btc".to_string(), -10.0)]), default_shock: -2.0 };
+
+    assert_eq!(request.shock_of("BTC"), -10.0);
+    assert_eq!(request.shock_of("ETH"), -2.0);
+    assert!(request.validate().is_ok());
+
+    let request = StressTestRequest { shocks: HashMap::from([("BTC".to_string(), -100.0)]), default_shock: 0.0 };
+    assert!(request.validate().is_err());
+
+    let request = StressTestRequest { shocks: HashMap::new(), default_shock: f64::NAN };
+    assert!(request.validate().is_err());
+}
+
+#[test]
+pub fn daily_returns_from_closes() {
+    let close = |product: &str, day: &str, close: f64| DailyCandle { product: product.to_string(), day: day.to_string(), high: close, low: close, close };
+
+    let returns = calc_daily_returns(&[
+        close("BTC-USDT", "2025-01-01", 100.0),
+        close("BTC-USDT", "2025-01-02", 110.0),
+        close("BTC-USDT", "2025-01-03", 99.0),
+        close("ETH-USDT", "2025-01-02", 50.0),
+        close("ETH-USDT", "2025-01-03", 55.0),
+    ]);
+
+    let btc = &returns["BTC-USDT"];
+    assert_eq!(btc.len(), 2);
+    assert!((btc["2025-01-02"] - 0.1).abs() < 1e-9);
+    assert!((btc["2025-01-03"] - -0.1).abs() < 1e-9);
+    // the first close of a product has no previous close to return from
+    assert_eq!(returns["ETH-USDT"].keys().collect::<Vec<_>>(), vec!["2025-01-03"]);
+}
+
+#[test]
+pub fn historical_value_at_risk() {
+    let exposure = |product: &str, net: f64| ProductExposure { product: product.to_string(), long: net.max(0.0), short: (-net).max(0.0), net };
+
+    // 100 days with returns of -5%, -4.9%, ..., +4.9%
+    let btc: BTreeMap<String, f64> = (0..100).map(|day| (format!("day-{:03}", day), (day as f64 - 50.0) / 1000.0)).collect();
+    let returns = HashMap::from([("BTC-USDT".to_string(), btc.clone())]);
+
+    // the 5% quantile of the days of a 1000 USDT long (beyond the 5 worst ones) is -4.5%
+    let (value_at_risk, observations) = calc_historical_var(&[exposure("BTC-USDT", 1000.0)], &returns, 0.95).unwrap();
+    assert_eq!(observations, 100);
+    assert!((value_at_risk - 45.0).abs() < 1e-9);
+
+    // a short loses on the up days instead
+    let (value_at_risk, _) = calc_historical_var(&[exposure("BTC-USDT", -1000.0)], &returns, 0.95).unwrap();
+    assert!((value_at_risk - 44.0).abs() < 1e-9);
+
+    // a perfectly hedged portfolio never loses
+    let returns = HashMap::from([("BTC-USDT".to_string(), btc.clone()), ("BTC-USD".to_string(), btc)]);
+    let (value_at_risk, _) = calc_historical_var(&[exposure("BTC-USDT", 1000.0), exposure("BTC-USD", -1000.0)], &returns, 0.95).unwrap();
+    assert_eq!(value_at_risk, 0.0);
+
+    // too few days of history
+    let returns = HashMap::from([("BTC-USDT".to_string(), BTreeMap::from([("2025-01-02".to_string(), -0.1)]))]);
+    assert_eq!(calc_historical_var(&[exposure("BTC-USDT", 1000.0)], &returns, 0.95), None);
+}
+
+#[test]
+pub fn stop_loss_risk_of_trades() {
+    // a long stopped out 2% below the entry loses 2% of its notional value
+    assert!((calc_stop_loss_risk(100.0, Some(98.0), &TradeDirection::Long, 5000.0, 5.0) - 100.0).abs() < 1e-9);
+    assert!((calc_stop_loss_risk(100.0, Some(103.0), &TradeDirection::Short, 5000.0, 5.0) - 150.0).abs() < 1e-9);
+    // without a stop loss, or with one beyond the liquidation price, the whole margin is at risk
+    assert!((calc_stop_loss_risk(100.0, None, &TradeDirection::Long, 5000.0, 5.0) - 1000.0).abs() < 1e-9);
+    assert!((calc_stop_loss_risk(100.0, Some(50.0), &TradeDirection::Long, 5000.0, 5.0) - 1000.0).abs() < 1e-9);
+    // a stop loss on the profitable side risks nothing
+    assert_eq!(calc_stop_loss_risk(100.0, Some(101.0), &TradeDirection::Long, 5000.0, 5.0), 0.0);
+}
+
+#[test]
+pub fn notional_value_capped_to_max_risk() {
+    // risking 150 USDT with a cap of 100 USDT shrinks the trade by a third
+    assert!((cap_notional_value(6000.0, 150.0, 100.0).unwrap() - 4000.0).abs() < 1e-9);
+    assert_eq!(cap_notional_value(6000.0, 80.0, 100.0), None);
+    assert_eq!(cap_notional_value(6000.0, 150.0, 0.0), Some(0.0));
+}
+
+#[test]
+pub fn risk_cap_action_parsing() {
+    assert_eq!("Reject".parse::<RiskCapAction>(), Ok(RiskCapAction::Reject));
+    assert_eq!(" shrink ".parse::<RiskCapAction>(), Ok(RiskCapAction::Shrink));
+    assert!("halve".parse::<RiskCapAction>().is_err());
+}
@@ -0,0 +1,42 @@
+use crate::{api::rounding::{parse_symbol_decimals, round_decimals}, models::{ReportLocale, RoundingMode, RoundingPolicy}};
+
+#[test]
+pub fn ties_are_broken_by_the_rounding_mode() {
+    assert_eq!(round_decimals(0.125, 2, RoundingMode::HalfEven), 0.12);
+    assert_eq!(round_decimals(0.135, 2, RoundingMode::HalfEven), 0.14);
+    assert_eq!(round_decimals(-0.125, 2, RoundingMode::HalfEven), -0.12);
+    assert_eq!(round_decimals(0.125, 2, RoundingMode::HalfAwayFromZero), 0.13);
+    assert_eq!(round_decimals(-0.125, 2, RoundingMode::HalfAwayFromZero), -0.13);
+
+    // 1.005 is stored as 1.00499999..., but still counts as a tie
+    assert_eq!(round_decimals(1.005, 2, RoundingMode::HalfAwayFromZero), 1.01);
+    assert_eq!(round_decimals(1.005, 2, RoundingMode::HalfEven), 1.0);
+
+    // values that aren't ties are rounded to the nearest value in both modes
+    assert_eq!(round_decimals(2.3449, 2, RoundingMode::HalfEven), 2.34);
+    assert_eq!(round_decimals(2.3451, 2, RoundingMode::HalfAwayFromZero), 2.35);
+    assert!(round_decimals(f64::NAN, 2, RoundingMode::HalfEven).is_nan());
+}
+
+#[test]
+pub fn accounting_and_display_values_are_rounded_separately() {
+    let mut policy = RoundingPolicy { price_decimals: parse_symbol_decimals("ethbtc:5").unwrap(), ..Default::default() };
+
+    // quantities are booked with banker's rounding, while displayed amounts round half away from zero
+    assert_eq!(policy.round_quantity(0.125), 0.12);
+    assert_eq!(policy.display_amount(0.125), 0.13);
+
+    assert_eq!(policy.display_price("ETHBTC", 0.0345678), 0.03457);
+    assert_eq!(policy.display_price("BTCUSDT", 96289.345), 96289.345);
+
+    let locale = ReportLocale::default();
+    assert_eq!(policy.format_amount(&locale, 12.5), "12.50");
+    assert_eq!(policy.format_price(&locale, "ETHBTC", 0.0345), "0.03450");
+    assert_eq!(policy.format_price(&locale, "BTCUSDT", 96289.5), "96289.5");
+
+    policy.display_decimals = 0;
+    assert_eq!(policy.format_amount(&locale, 12.5), "13");
+
+    assert!(parse_symbol_decimals("ETHBTC").is_err());
+    assert!(parse_symbol_decimals("ETHBTC:-1").is_err());
+}
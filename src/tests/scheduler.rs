@@ -0,0 +1,29 @@
+use chrono::{TimeZone, Utc};
+
+use crate::api::scheduler::{calc_scheduled_state, parse_cron_expression};
+
+#[test]
+pub fn parse_five_and_six_field_cron_expressions() {
+    assert!(parse_cron_expression("30 13 * * Mon-Fri").is_ok());
+    assert!(parse_cron_expression("0 30 13 * * Mon-Fri").is_ok());
+    assert!(parse_cron_expression("not a cron").is_err());
+}
+
+#[test]
+pub fn scheduled_state_follows_latest_trigger() {
+    // enable at 13:30 UTC, disable at 20:00 UTC on weekdays
+    let enable = parse_cron_expression("30 13 * * Mon-Fri").unwrap();
+    let disable = parse_cron_expression("0 20 * * Mon-Fri").unwrap();
+
+    // Monday 2025-01-06
+    let at = |hour, minute| Utc.with_ymd_and_hms(2025, 1, 6, hour, minute, 0).unwrap();
+
+    // nothing triggers within the minute before the session opens
+    assert_eq!(calc_scheduled_state(Some(&enable), Some(&disable), at(13, 28), at(13, 29)), None);
+    // the trigger time itself is inclusive
+    assert_eq!(calc_scheduled_state(Some(&enable), Some(&disable), at(13, 29), at(13, 30)), Some(true));
+    assert_eq!(calc_scheduled_state(Some(&enable), Some(&disable), at(19, 59), at(20, 0)), Some(false));
+    // if both triggered since the last evaluation, the latest one wins
+    assert_eq!(calc_scheduled_state(Some(&enable), Some(&disable), at(13, 0), at(21, 0)), Some(false));
+    assert_eq!(calc_scheduled_state(Some(&enable), None, at(13, 0), at(21, 0)), Some(true));
+}
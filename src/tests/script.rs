@@ -0,0 +1,72 @@
+use serde_json::json;
+
+use crate::{api::script::{run_filter_script, validate_filter_script}, models::{tradingview::TradingViewAlert, FilterDecision, FilterOverrides}};
+
+fn alert() -> TradingViewAlert {
+    serde_json::from_value(json!({
+        "name": "breakout",
+        "signal": "buy",
+        "pair": "SOLUSDT",
+        "price": 150.0,
+        "take_profit": 165.0,
+        "stop_loss": null,
+        "indicators": { "rsi": 72.5 },
+        "timestamp": "2025-01-01T12:00:00Z",
+        "secret": "secret",
+    })).unwrap()
+}
+
+#[test]
+pub fn filter_scripts_allow_or_deny_alerts() {
+    assert_eq!(run_filter_script("true", &alert(), None, &[]), Ok(FilterDecision::Allow(FilterOverrides::default())));
+    assert_eq!(run_filter_script("let x = 1;", &alert(), None, &[]), Ok(FilterDecision::Allow(FilterOverrides::default())));
+    assert_eq!(
+        run_filter_script(r#"if alert.indicators.rsi > 70 && alert.signal == "buy" { "overbought" } else { true }"#, &alert(), None, &[]),
+        Ok(FilterDecision::Deny("overbought".to_string()))
+    );
+    // no open positions on the pair, and no market price yet
+    assert_eq!(
+        run_filter_script(r#"positions.filter(|p| p.pair == alert.pair).len() == 0 && price == ()"#, &alert(), None, &[]),
+        Ok(FilterDecision::Allow(FilterOverrides::default()))
+    );
+}
+
+#[test]
+pub fn filter_scripts_override_exits_and_sizing() {
+    let decision = run_filter_script("#{ stop_loss: price * 0.95, notional_value: 250 }", &alert(), Some(140.0), &[]);
+
+    assert_eq!(decision, Ok(FilterDecision::Allow(FilterOverrides {
+        take_profit: None,
+        stop_loss: Some(140.0 * 0.95),
+        notional_value: Some(250.0),
+    })));
+    assert_eq!(
+        run_filter_script(r#"#{ allow: false, reason: "news" }"#, &alert(), None, &[]),
+        Ok(FilterDecision::Deny("news".to_string()))
+    );
+}
+
+#[test]
+pub fn invalid_filter_scripts_fail() {
+    assert!(validate_filter_script("if {").is_err());
+    assert!(validate_filter_script(&"1;".repeat(10_000)).is_err());
+    // unknown keys, non-positive values and unexpected result types
+    assert!(run_filter_script("#{ stoploss: 1.0 }", &alert(), None, &[]).is_err());
+    assert!(run_filter_script("#{ notional_value: -5 }", &alert(), None, &[]).is_err());
+    assert!(run_filter_script("42", &alert(), None, &[]).is_err());
+    // runaway loops are aborted
+    assert!(run_filter_script("loop {}", &alert(), None, &[]).is_err());
+}
+
+#[test]
+pub fn filter_scripts_cant_exhaust_the_memory() {
+    // without a size limit, 64 doublings would need exabytes long before the operations limit is reached
+    let doubled_string = run_filter_script(r#"let s = "x"; for i in 0..64 { s += s; } true"#, &alert(), None, &[]);
+    assert!(doubled_string.as_ref().is_err_and(|err| err.contains("string")), "{:?}", doubled_string);
+
+    let doubled_array = run_filter_script("let a = [1]; for i in 0..64 { a += a; } true", &alert(), None, &[]);
+    assert!(doubled_array.as_ref().is_err_and(|err| err.contains("array")), "{:?}", doubled_array);
+
+    // deeply nested expressions are rejected when compiling
+    assert!(validate_filter_script(&format!("{}1{}", "(".repeat(100), ")".repeat(100))).is_err());
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde_json::json;
+
+use crate::{api::seed::{replay_alerts, seed_import_key}, models::{tradingview::TradingViewAlert, Strategy, StrategyParameters, TradeDirection}, tests::app_state};
+
+fn strategy(filter_script: Option<&str>) -> Strategy {
+    Strategy {
+        id: ObjectId::new(),
+        name: "breakout".to_string(),
+        enabled: true,
+        experiment: None,
+        enable_cron: None,
+        disable_cron: None,
+        callback_url: None,
+        filter_script: filter_script.map(str::to_string),
+        seed_from: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+        seeded_timestamp: None,
+        parameters: StrategyParameters::default(),
+        updated_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+    }
+}
+
+fn alert(signal: &str, price: f64, exits: Option<(f64, f64)>, timestamp: DateTime<Utc>) -> TradingViewAlert {
+    serde_json::from_value(json!({
+        "name": "breakout",
+        "signal": signal,
+        "pair": "BTCUSDT",
+        "price": price,
+        "take_profit": exits.map(|(take_profit, _)| take_profit),
+        "stop_loss": exits.map(|(_, stop_loss)| stop_loss),
+        "timestamp": timestamp,
+        "secret": "",
+    })).unwrap()
+}
+
+#[tokio::test]
+pub async fn seeding_replays_alerts_against_price_ticks() {
+    let app_state = app_state().await;
+    let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let at = |minutes: i64| start + Duration::minutes(minutes);
+
+    let alerts = vec![
+        // exited by its take profit at the second tick
+        alert("buy", 100.0, Some((110.0, 90.0)), at(0)),
+        alert("sell", 120.0, None, at(10)),
+        // in the direction of the open short, so ignored
+        alert("sell", 118.0, None, at(20)),
+        // flips the short into a long, which is still open at the end and isn't seeded
+        alert("buy", 115.0, None, at(30)),
+    ];
+
+    let ticks = HashMap::from([("BTC-USDT".to_string(), vec![(at(1), 105.0), (at(2), 111.0), (at(3), 80.0), (at(40), 200.0)])]);
+
+    let trades = replay_alerts(&app_state, &strategy(None), alerts, &ticks, at(60));
+
+    assert_eq!(trades.len(), 2);
+
+    assert_eq!(trades[0].direction, TradeDirection::Long);
+    assert_eq!((trades[0].entry_price, trades[0].exit_price), (100.0, 111.0));
+    assert_eq!((trades[0].open_timestamp, trades[0].close_timestamp), (at(0), at(2)));
+    assert!(trades[0].pnl > 0.0);
+
+    assert_eq!(trades[1].direction, TradeDirection::Short);
+    assert_eq!((trades[1].entry_price, trades[1].exit_price), (120.0, 115.0));
+    assert_eq!((trades[1].open_timestamp, trades[1].close_timestamp), (at(10), at(30)));
+    assert_eq!(trades[1].originating_request_id, None);
+
+    assert_eq!(seed_import_key(&trades[0]), format!("seed:breakout:BTCUSDT:{}", at(0).timestamp()));
+    assert_ne!(seed_import_key(&trades[0]), seed_import_key(&trades[1]));
+}
+
+#[tokio::test]
+pub async fn seeding_applies_the_filter_script() {
+    let app_state = app_state().await;
+    let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+    let alerts = vec![
+        alert("buy", 100.0, None, start),
+        alert("sell", 120.0, None, start + Duration::minutes(10)),
+    ];
+
+    // the script only lets buys through, so the long is never closed
+    let trades = replay_alerts(&app_state, &strategy(Some(r#"alert.signal == "buy""#)), alerts.clone(), &HashMap::new(), start + Duration::hours(1));
+    assert!(trades.is_empty());
+
+    let trades = replay_alerts(&app_state, &strategy(None), alerts, &HashMap::new(), start + Duration::hours(1));
+    assert_eq!(trades.len(), 1);
+}
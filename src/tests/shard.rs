@@ -0,0 +1,12 @@
+use crate::api::shard::calc_fair_share;
+
+#[test]
+pub fn symbols_are_spread_evenly_across_instances() {
+    assert_eq!(calc_fair_share(6, 1), 6);
+    assert_eq!(calc_fair_share(6, 2), 3);
+    // the remainder is rounded up so that every symbol is claimed
+    assert_eq!(calc_fair_share(6, 4), 2);
+    assert_eq!(calc_fair_share(6, 10), 1);
+    // no live instances (e.g. before the first heartbeat) counts as one
+    assert_eq!(calc_fair_share(6, 0), 6);
+}
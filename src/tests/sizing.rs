@@ -0,0 +1,71 @@
+use crate::{api::{sizing::{calc_atr_percentage, calc_equity_notional_value, calc_kelly_criterion, calc_kelly_notional_value, scale_to_volatility_target}, strategy::validate_strategy_parameters}, constants::{KELLY_MIN_TRADES, MAX_VOLATILITY_SIZE_MULTIPLIER}, models::{DailyCandle, StrategyParameters}};
+
+fn candle(day: usize, high: f64, low: f64, close: f64) -> DailyCandle {
+    DailyCandle { product: "BTC-USD".to_string(), day: format!("2024-01-{:02}", day), high, low, close }
+}
+
+#[test]
+pub fn kelly_criterion_from_win_rate_and_payoff() {
+    // 60% win rate with winners twice the size of losers: 0.6 - 0.4 / 2 = 0.4
+    let roes: Vec<f64> = (0..KELLY_MIN_TRADES).map(|i| if i % 5 < 3 { 20.0 } else { -10.0 }).collect();
+    assert!((calc_kelly_criterion(&roes).unwrap() - 0.4).abs() < 1e-9);
+
+    // 40% win rate with equal payoffs has no edge
+    let roes: Vec<f64> = (0..KELLY_MIN_TRADES).map(|i| if i % 5 < 2 { 10.0 } else { -10.0 }).collect();
+    assert!(calc_kelly_criterion(&roes).unwrap() < 0.0);
+
+    // no losing trades
+    assert_eq!(calc_kelly_criterion(&[5.0; KELLY_MIN_TRADES]), Some(1.0));
+
+    // not enough trades yet
+    assert_eq!(calc_kelly_criterion(&[5.0; KELLY_MIN_TRADES - 1]), None);
+}
+
+#[test]
+pub fn kelly_notional_value_from_equity() {
+    // half Kelly of 0.4 on 10,000 USDT is a margin of 2,000 USDT, i.e. 10,000 USDT notional at 5x
+    assert!((calc_kelly_notional_value(10_000.0, 0.4, 0.5, 5.0) - 10_000.0).abs() < 1e-9);
+    assert_eq!(calc_kelly_notional_value(-500.0, 0.4, 0.5, 5.0), 0.0);
+}
+
+#[test]
+pub fn equity_notional_value_compounds() {
+    assert!((calc_equity_notional_value(10_000.0, 10.0) - 1000.0).abs() < 1e-9);
+    // profits grow and drawdowns shrink the size of later trades
+    assert!((calc_equity_notional_value(12_000.0, 10.0) - 1200.0).abs() < 1e-9);
+    assert!((calc_equity_notional_value(8_000.0, 10.0) - 800.0).abs() < 1e-9);
+    assert_eq!(calc_equity_notional_value(-100.0, 10.0), 0.0);
+}
+
+#[test]
+pub fn atr_percentage_of_daily_candles() {
+    let candles = vec![
+        candle(1, 105.0, 95.0, 100.0),
+        // the gap from the previous close exceeds the range of the day
+        candle(2, 112.0, 108.0, 110.0),
+        candle(3, 114.0, 106.0, 100.0),
+    ];
+
+    // true ranges of 12 and 8 average to 10, i.e. 10% of the last close
+    assert!((calc_atr_percentage(&candles, 2).unwrap() - 10.0).abs() < 1e-9);
+    // only the last true range
+    assert!((calc_atr_percentage(&candles, 1).unwrap() - 8.0).abs() < 1e-9);
+    assert_eq!(calc_atr_percentage(&candles, 3), None);
+}
+
+#[test]
+pub fn volatility_target_scales_inversely_to_atr() {
+    assert!((scale_to_volatility_target(1000.0, 2.0, 4.0) - 500.0).abs() < 1e-9);
+    assert!((scale_to_volatility_target(1000.0, 2.0, 1.0) - 2000.0).abs() < 1e-9);
+    assert_eq!(scale_to_volatility_target(1000.0, 2.0, 0.1), 1000.0 * MAX_VOLATILITY_SIZE_MULTIPLIER);
+}
+
+#[test]
+pub fn sizing_parameters_are_validated() {
+    assert!(validate_strategy_parameters(&StrategyParameters { kelly_fraction: Some(0.25), ..Default::default() }).is_ok());
+    assert!(validate_strategy_parameters(&StrategyParameters { kelly_fraction: Some(0.0), ..Default::default() }).is_err());
+    assert!(validate_strategy_parameters(&StrategyParameters { kelly_fraction: Some(1.5), ..Default::default() }).is_err());
+    assert!(validate_strategy_parameters(&StrategyParameters { target_volatility_percentage: Some(-1.0), ..Default::default() }).is_err());
+    assert!(validate_strategy_parameters(&StrategyParameters { equity_percentage: Some(250.0), ..Default::default() }).is_ok());
+    assert!(validate_strategy_parameters(&StrategyParameters { equity_percentage: Some(0.0), ..Default::default() }).is_err());
+}
@@ -0,0 +1,68 @@
+use chrono::{TimeZone, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use crate::{api::statement::{build_account_statement, build_statement_html, calc_due_statement_month, parse_statement_month}, models::{ClosedTrade, ReportLocale, RoundingPolicy, TradeKind}};
+
+fn closed_trade(alert_name: &str, pnl: f64, execution_fees: f64, funding_fees: f64, settlement_currency: &str, settlement_usdt_rate: f64) -> ClosedTrade {
+    mongodb::bson::from_document(doc! {
+        "_id": ObjectId::new(),
+        "alertName": alert_name,
+        "pair": "BTCUSDT",
+        "direction": "long",
+        "kind": "paper",
+        "quantity": 1.0,
+        "entryPrice": 100.0,
+        "exitPrice": 110.0,
+        "openTimestamp": 1_736_000_000_i64,
+        "closeTimestamp": 1_736_003_600_i64,
+        "pnl": pnl,
+        "executionFees": execution_fees,
+        "fundingFees": funding_fees,
+        "settlementCurrency": settlement_currency,
+        "settlementUsdtRate": settlement_usdt_rate,
+    }).unwrap()
+}
+
+#[test]
+pub fn statement_months_span_the_whole_month() {
+    assert_eq!(parse_statement_month("2024-02"), Some((
+        Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+    )));
+    assert_eq!(parse_statement_month("2024-12").unwrap().1, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+    assert_eq!(parse_statement_month("2024-13"), None);
+    assert_eq!(parse_statement_month("january"), None);
+}
+
+#[test]
+pub fn statements_are_due_once_the_month_ended() {
+    let before = Utc.with_ymd_and_hms(2024, 12, 31, 23, 0, 0).unwrap();
+    let after = Utc.with_ymd_and_hms(2025, 1, 1, 0, 30, 0).unwrap();
+
+    assert_eq!(calc_due_statement_month(before, after), Some("2024-12".to_string()));
+    assert_eq!(calc_due_statement_month(after, after + chrono::Duration::hours(1)), None);
+}
+
+#[test]
+pub fn statements_sum_up_the_month_in_usdt() {
+    let (from, to) = parse_statement_month("2025-01").unwrap();
+    let trades = [
+        closed_trade("<script>", 10.0, 1.0, 0.5, "USDT", 1.0),
+        closed_trade("inverse", -0.001, 0.0001, 0.0, "BTC", 50_000.0),
+    ];
+
+    let statement = build_account_statement(TradeKind::Paper, "2025-01", from, to, 1_000.0, &trades, to);
+
+    assert_eq!(statement.id, "paper-2025-01");
+    assert_eq!(statement.trades, 2);
+    assert!((statement.realized_pnl - -40.0).abs() < 1e-9);
+    assert!((statement.execution_fees - 6.0).abs() < 1e-9);
+    assert!((statement.funding_fees - 0.5).abs() < 1e-9);
+    assert!((statement.closing_equity - 960.0).abs() < 1e-9);
+
+    let html = build_statement_html(&statement, &trades, &ReportLocale::from_lookup(|_| None), &RoundingPolicy::default());
+
+    assert!(html.contains("960.00"));
+    assert!(html.contains("&lt;script&gt;"));
+    assert!(!html.contains("<script>"));
+}
@@ -0,0 +1,63 @@
+use chrono::{TimeZone, Utc};
+use chrono_tz::Tz;
+use mongodb::bson::doc;
+
+use crate::api::{risk::calc_day_start, stats::{local_date_operand, timestamp_to_date}, stats_helpers::{calc_correlation, calc_max_drawdown}};
+
+#[test]
+pub fn max_drawdown_from_peak() {
+    // cumulative PnL: 10, 30, 5, 15, -5 -> peak 30, trough -5
+    assert_eq!(calc_max_drawdown(&[10.0, 20.0, -25.0, 10.0, -20.0]), 35.0);
+    // only winning trades never draw down
+    assert_eq!(calc_max_drawdown(&[1.0, 2.0, 3.0]), 0.0);
+    // losses from the very first trade count against the starting point
+    assert_eq!(calc_max_drawdown(&[-5.0, 2.0]), 5.0);
+}
+
+#[test]
+pub fn correlation_of_daily_returns() {
+    let a = [1.0, 2.0, 3.0, 4.0];
+
+    assert!((calc_correlation(&a, &[2.0, 4.0, 6.0, 8.0]).unwrap() - 1.0).abs() < 1e-9);
+    assert!((calc_correlation(&a, &[-1.0, -2.0, -3.0, -4.0]).unwrap() + 1.0).abs() < 1e-9);
+    // no variance or mismatched lengths cannot be correlated
+    assert_eq!(calc_correlation(&a, &[1.0, 1.0, 1.0, 1.0]), None);
+    assert_eq!(calc_correlation(&a, &[1.0, 2.0]), None);
+}
+
+#[test]
+pub fn date_operators_are_evaluated_in_the_reporting_timezone() {
+    let timezone: Tz = "Asia/Tokyo".parse().unwrap();
+
+    assert_eq!(
+        local_date_operand("$openTimestamp", timezone),
+        doc! { "date": timestamp_to_date("$openTimestamp"), "timezone": "Asia/Tokyo" }
+    );
+}
+
+#[test]
+pub fn days_start_at_local_midnight() {
+    let berlin: Tz = "Europe/Berlin".parse().unwrap();
+
+    // 23:30 UTC is already the next day in Berlin (UTC+1 in winter)
+    let now = Utc.with_ymd_and_hms(2024, 1, 15, 23, 30, 0).unwrap();
+    assert_eq!(calc_day_start(now, berlin), Some(Utc.with_ymd_and_hms(2024, 1, 15, 23, 0, 0).unwrap()));
+    assert_eq!(calc_day_start(now, Tz::UTC), Some(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap()));
+
+    // in summer, Berlin is UTC+2
+    let now = Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap();
+    assert_eq!(calc_day_start(now, berlin), Some(Utc.with_ymd_and_hms(2024, 6, 30, 22, 0, 0).unwrap()));
+
+    // on the day DST ends, the day started before the clocks were set back
+    let now = Utc.with_ymd_and_hms(2024, 10, 27, 12, 0, 0).unwrap();
+    assert_eq!(calc_day_start(now, berlin), Some(Utc.with_ymd_and_hms(2024, 10, 26, 22, 0, 0).unwrap()));
+}
+
+#[test]
+pub fn days_start_after_a_skipped_midnight() {
+    // Chile skips from 00:00 to 01:00 when DST starts, e.g. on 2024-09-08
+    let santiago: Tz = "America/Santiago".parse().unwrap();
+    let now = Utc.with_ymd_and_hms(2024, 9, 8, 18, 0, 0).unwrap();
+
+    assert_eq!(calc_day_start(now, santiago), Some(Utc.with_ymd_and_hms(2024, 9, 8, 4, 0, 0).unwrap()));
+}
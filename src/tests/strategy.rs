@@ -0,0 +1,46 @@
+use crate::{api::strategy::{render_alert_template, validate_strategy_parameters}, models::{tradingview::TradingViewAlert, StrategyParameters, StrategyTemplate, TradeSignal}};
+
+#[test]
+pub fn templates_bundle_valid_parameters() {
+    for template in StrategyTemplate::ALL {
+        let parameters = template.parameters();
+
+        assert_eq!(validate_strategy_parameters(&parameters), Ok(()));
+        assert!(parameters.cooldown_secs.is_some());
+    }
+
+    // the trend-follow preset exits via its trailing stop instead of a fixed take profit
+    let trend_follow = StrategyTemplate::TrendFollow.parameters();
+    assert!(trend_follow.take_profit_percentage.is_none());
+    assert!(trend_follow.trailing_stop_percentage.is_some());
+}
+
+#[test]
+pub fn unusable_parameters_are_rejected() {
+    assert_eq!(validate_strategy_parameters(&StrategyParameters::default()), Ok(()));
+    assert!(validate_strategy_parameters(&StrategyParameters { notional_value: Some(0.0), ..Default::default() }).is_err());
+    assert!(validate_strategy_parameters(&StrategyParameters { stop_loss_percentage: Some(-1.0), ..Default::default() }).is_err());
+    assert!(validate_strategy_parameters(&StrategyParameters { trailing_stop_percentage: Some(100.0), ..Default::default() }).is_err());
+}
+
+#[test]
+pub fn alert_template_deserializes_once_filled_in() {
+    let template = render_alert_template("BTC \"breakout\"", "s3cret");
+
+    // what TradingView sends after substituting the placeholders
+    let message = template
+        .replace("{{strategy.order.action}}", "sell")
+        .replace("{{ticker}}", "BTCUSDT")
+        .replace("{{close}}", "64000.5")
+        .replace("{{strategy.order.id}}", "Short")
+        .replace("{{timenow}}", "2024-05-01T12:00:00Z");
+
+    let alert: TradingViewAlert = serde_json::from_str(&message).unwrap();
+
+    assert_eq!(alert.name, "BTC \"breakout\"");
+    assert_eq!(alert.signal, TradeSignal::Sell);
+    assert_eq!(alert.pair, "BTCUSDT");
+    assert_eq!(alert.price, 64000.5);
+    assert_eq!(alert.idempotency_key.as_deref(), Some("Short-2024-05-01T12:00:00Z"));
+    assert_eq!(alert.secret, "s3cret");
+}
@@ -0,0 +1,85 @@
+use chrono::{Duration, TimeZone, Utc};
+use mongodb::bson::{self, doc};
+
+use crate::{api::timeseries::{chart_time_filter, sample_price_ticks}, constants::{MAX_BUFFERED_PRICE_TICKS, TICK_PERSISTENCE_DOWNGRADE_SECS}, models::{PriceTick, PriceTickRecorder, TickPersistence}};
+
+#[test]
+pub fn chart_filter_compares_bson_dates() {
+    assert_eq!(chart_time_filter(None, None), doc! {});
+
+    let from = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap();
+
+    // time-series collections store the time field as a BSON date, not as a timestamp in seconds
+    assert_eq!(
+        chart_time_filter(Some(from), Some(to)),
+        doc! { "timestamp": { "$gte": bson::DateTime::from_millis(from.timestamp_millis()), "$lt": bson::DateTime::from_millis(to.timestamp_millis()) } }
+    );
+    assert_eq!(
+        chart_time_filter(None, Some(to)),
+        doc! { "timestamp": { "$lt": bson::DateTime::from_millis(to.timestamp_millis()) } }
+    );
+}
+
+#[test]
+pub fn tick_persistence_is_configurable() {
+    assert_eq!("ALL".parse::<TickPersistence>(), Ok(TickPersistence::All));
+    assert_eq!(" sampled ".parse::<TickPersistence>(), Ok(TickPersistence::Sampled));
+    assert_eq!("none".parse::<TickPersistence>(), Ok(TickPersistence::None));
+    assert!("every".parse::<TickPersistence>().is_err());
+
+    let now = Utc::now();
+
+    // only buffered when all ticks are persisted
+    let recorder = PriceTickRecorder { persistence: TickPersistence::Sampled, sample_interval_secs: 10, ..Default::default() };
+    recorder.record("BTC-USD", 100.0, now);
+    assert!(recorder.take(now).is_empty());
+    assert_eq!(recorder.backfill_interval_secs(now), Some(10));
+
+    let recorder = PriceTickRecorder { persistence: TickPersistence::None, sample_interval_secs: 10, ..Default::default() };
+    assert_eq!(recorder.backfill_interval_secs(now), None);
+}
+
+#[test]
+pub fn persisting_all_ticks_is_downgraded_under_load() {
+    let now = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    let recorder = PriceTickRecorder { persistence: TickPersistence::All, sample_interval_secs: 10, ..Default::default() };
+
+    recorder.record("BTC-USD", 100.0, now);
+    recorder.record("BTC-USD", 101.0, now);
+    assert_eq!(recorder.take(now).len(), 2);
+    assert_eq!(recorder.effective_persistence(now), TickPersistence::All);
+
+    for i in 0..=MAX_BUFFERED_PRICE_TICKS {
+        recorder.record("BTC-USD", 100.0 + i as f64, now);
+    }
+
+    // the buffered ticks (all within the same 10 seconds) are sampled into a single tick
+    let ticks = recorder.take(now);
+    assert_eq!(ticks.len(), 1);
+    assert_eq!(ticks[0].price, 100.0 + MAX_BUFFERED_PRICE_TICKS as f64);
+
+    assert_eq!(recorder.effective_persistence(now), TickPersistence::Sampled);
+    assert_eq!(recorder.backfill_interval_secs(now), Some(10));
+    recorder.record("BTC-USD", 100.0, now);
+    assert!(recorder.take(now).is_empty());
+
+    // until the load is gone
+    let later = now + Duration::seconds(TICK_PERSISTENCE_DOWNGRADE_SECS);
+    assert_eq!(recorder.effective_persistence(later), TickPersistence::All);
+    assert_eq!(recorder.backfill_interval_secs(later), Some(1));
+}
+
+#[test]
+pub fn price_ticks_are_sampled_per_product() {
+    let tick = |product: &str, secs: i64, price: f64| PriceTick {
+        timestamp: bson::DateTime::from_millis((1_700_000_000 + secs) * 1000),
+        product: product.to_string(),
+        price,
+    };
+
+    let ticks = vec![tick("BTC-USD", 0, 100.0), tick("ETH-USD", 1, 10.0), tick("BTC-USD", 5, 101.0), tick("BTC-USD", 10, 102.0)];
+    let sampled: Vec<(String, f64)> = sample_price_ticks(ticks, 10).into_iter().map(|tick| (tick.product, tick.price)).collect();
+
+    assert_eq!(sampled, vec![("BTC-USD".to_string(), 101.0), ("ETH-USD".to_string(), 10.0), ("BTC-USD".to_string(), 102.0)]);
+}
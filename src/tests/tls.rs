@@ -0,0 +1,16 @@
+use crate::configs::resolve_tls_paths;
+
+#[test]
+pub fn tls_requires_both_paths() {
+    assert_eq!(resolve_tls_paths(None, None), Ok(None));
+    // empty values count as unset
+    assert_eq!(resolve_tls_paths(Some("".to_string()), Some(" ".to_string())), Ok(None));
+
+    assert_eq!(
+        resolve_tls_paths(Some("cert.pem".to_string()), Some("key.pem".to_string())),
+        Ok(Some(("cert.pem".to_string(), "key.pem".to_string())))
+    );
+
+    assert!(resolve_tls_paths(Some("cert.pem".to_string()), None).is_err());
+    assert!(resolve_tls_paths(None, Some("key.pem".to_string())).is_err());
+}
@@ -1,8 +1,11 @@
-use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
 use dotenvy::dotenv;
-use mongodb::{bson::oid::ObjectId, options::ClientOptions, Client};
+use hyper::StatusCode;
+use mongodb::{bson::{doc, oid::ObjectId}, options::ClientOptions, Client};
 
-use crate::models::{ActiveTrade, MongoDBState, TradeDirection, TradeKind, TradeLeverage};
+use crate::{api::trade::{calc_page_skip, clamp_per_page, close_trade, trade_list_filter}, constants::MAX_PER_PAGE, models::{ActiveTrade, ClosedTrade, ContractType, ManualCloseRequest, MongoDBState, TradeKind, TradeLeverage, TradeListQuery}, tests::{active_trade, app_state}};
 
 #[tokio::test]
 pub async fn add_active_trade() {
@@ -11,30 +14,125 @@ pub async fn add_active_trade() {
     let mongodb_uri = std::env::var("MONGODB_URI").expect("(add_active_trade) MONGODB_URI not set");
     let client_options = ClientOptions::parse(mongodb_uri).await.unwrap();
     let client = Client::with_options(client_options).unwrap();
-    let db = client.database("main");
 
-    let state = MongoDBState {
-        active_trade_collection: db.collection("ActiveTrades"),
-        closed_trade_collection: db.collection("ClosedTrades"),
-    };
+    let state = MongoDBState::new(Arc::new(client));
 
     let sample_trade = ActiveTrade {
-        id: ObjectId::new(),
-        alert_name: "Sample Alert".to_string(),
         pair: "SOLUSDT".to_string(),
-        direction: TradeDirection::Long,
         kind: TradeKind::Live,
-        open_timestamp: Utc::now(),
         quantity: 100.0,
         entry_price: 231.4,
-        leverage: TradeLeverage::One,
+        liquidation_price: 10.0,
         take_profit: Some(240.0),
         stop_loss: Some(225.0),
-        liquidation_price: 10.0,
+        ..active_trade()
     };
 
     match state.add_active_trade(sample_trade).await {
         Ok(result) => println!("(add_active_trade) Inserted document ID: {:?}", result.inserted_id),
         Err(e) => eprintln!("(add_active_trade) Error: {:?}", e)
     }
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn old_trade_documents_deserialize_with_defaults() {
+    // a closed trade stored before leverage, fees and the fields added since were recorded
+    let document = doc! {
+        "_id": ObjectId::new(),
+        "alertName": "Sample Alert",
+        "pair": "BTCUSDT",
+        "direction": "long",
+        "kind": "paper",
+        "quantity": 1.0,
+        "entryPrice": 100.0,
+        "exitPrice": 110.0,
+        "openTimestamp": 1_700_000_000_i64,
+        "closeTimestamp": 1_700_003_600_i64,
+        "pnl": 10.0,
+    };
+
+    let trade: ClosedTrade = mongodb::bson::from_document(document).unwrap();
+
+    assert!(matches!(trade.leverage, TradeLeverage::One));
+    assert_eq!(trade.execution_fees, 0.0);
+    assert_eq!(trade.contract_type, ContractType::Linear);
+    assert_eq!(trade.settlement_currency, "USDT");
+    assert!(trade.import_key.is_none());
+}
+
+#[test]
+pub fn trade_lists_are_filtered_by_pair_direction_and_kind() {
+    assert_eq!(trade_list_filter(&TradeListQuery::default()), doc! {});
+
+    let query: TradeListQuery = serde_json::from_value(serde_json::json!({ "pair": "btcusdt", "direction": "short", "kind": "live", "page": 2 })).unwrap();
+    assert_eq!(query.page, Some(2));
+    assert_eq!(trade_list_filter(&query), doc! { "pair": "BTCUSDT", "direction": "short", "kind": "live" });
+}
+
+#[test]
+pub fn trade_list_pages_are_bounded() {
+    assert_eq!(clamp_per_page(0), 1);
+    assert_eq!(clamp_per_page(u32::MAX), MAX_PER_PAGE as u32);
+
+    assert_eq!(calc_page_skip(1, 50), Some(0));
+    assert_eq!(calc_page_skip(3, 50), Some(100));
+    // pages start at 1, and pages whose offset overflows can't exist
+    assert_eq!(calc_page_skip(0, 50), None);
+    assert_eq!(calc_page_skip(u32::MAX, MAX_PER_PAGE as u32), None);
+}
+
+#[tokio::test]
+pub async fn manual_close_requests_are_validated() {
+    let mut app_state = app_state().await;
+    app_state.command_secret = Some("close-secret".to_string());
+    let app_state = Arc::new(app_state);
+
+    let trade = ActiveTrade {
+        pair: "SOLUSDT".to_string(),
+        quantity: 100.0,
+        entry_price: 231.4,
+        liquidation_price: 10.0,
+        ..active_trade()
+    };
+    let trade_id = trade.id;
+    app_state.active_trades.lock().unwrap().insert(trade.id, trade.clone());
+
+    let mut live_trade = trade;
+    live_trade.id = ObjectId::new();
+    live_trade.kind = TradeKind::Live;
+    let live_trade_id = live_trade.id;
+    app_state.active_trades.lock().unwrap().insert(live_trade.id, live_trade);
+
+    let close = |id: String, exit_price: Option<f64>| close_trade(
+        Extension(app_state.clone()),
+        Path(id),
+        Json(ManualCloseRequest { secret: "close-secret".to_string(), exit_price })
+    );
+
+    let (status, _) = close_trade(
+        Extension(app_state.clone()),
+        Path(trade_id.to_hex()),
+        Json(ManualCloseRequest { secret: "wrong-secret".to_string(), exit_price: Some(230.0) })
+    ).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = close("not-an-id".to_string(), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, response) = close(ObjectId::new().to_hex(), Some(230.0)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(response.message.contains("is not active"));
+
+    for exit_price in [0.0, -1.0, f64::NAN] {
+        let (status, _) = close(trade_id.to_hex(), Some(exit_price)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    // live trades close at their fill price
+    let (status, _) = close(live_trade_id.to_hex(), Some(230.0)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // rejected requests leave the trades open
+    assert!(app_state.active_trades.lock().unwrap().contains_key(&trade_id));
+    assert!(app_state.active_trades.lock().unwrap().contains_key(&live_trade_id));
+}
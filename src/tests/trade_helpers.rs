@@ -0,0 +1,182 @@
+use chrono::{TimeZone, Utc};
+
+use crate::{api::{calc_accrued_funding, calc_adjusted_exits, calc_liquidation_price, calc_order_quantity, calc_percentage_exits, calc_pnl, calc_roe, calc_trailing_stop, get_settlement_currency, is_exit_trigger_hit, is_liquidation_hit, is_trigger_hit, matches_bulk_update, split_pair, to_coinbase_product_id, trade::validate_bulk_exit_update}, models::{ActiveTrade, BulkExitUpdate, ContractType, ExitAdjustment, FundingRate, RoundingPolicy, TradeDirection, TradeLeverage}, tests::active_trade};
+
+#[test]
+pub fn split_pair_by_quote_currency() {
+    assert_eq!(split_pair("BTCUSDT"), Some(("BTC".to_string(), "USDT".to_string())));
+    assert_eq!(split_pair("eth-btc"), Some(("ETH".to_string(), "BTC".to_string())));
+    assert_eq!(split_pair("BTCUSD"), Some(("BTC".to_string(), "USD".to_string())));
+    assert_eq!(split_pair("USDT"), None);
+
+    assert_eq!(to_coinbase_product_id("SOLUSDT"), Some("SOL-USDT".to_string()));
+    assert_eq!(to_coinbase_product_id("btc-usd"), Some("BTC-USD".to_string()));
+
+    assert_eq!(get_settlement_currency("ETHBTC", &ContractType::Linear), Some("BTC".to_string()));
+    assert_eq!(get_settlement_currency("BTCUSD", &ContractType::Inverse), Some("BTC".to_string()));
+}
+
+#[test]
+pub fn inverse_pnl_is_denominated_in_base_currency() {
+    // 10,000 USD of contracts, long from 50,000 to 100,000 -> 10,000 * (1/50,000 - 1/100,000) = 0.1 BTC
+    let pnl = calc_pnl(50_000.0, 100_000.0, 10_000.0, 0.0, 0.0, &TradeDirection::Long, &ContractType::Inverse);
+    assert!((pnl - 0.1).abs() < 1e-12);
+
+    let short_pnl = calc_pnl(50_000.0, 100_000.0, 10_000.0, 0.0, 0.0, &TradeDirection::Short, &ContractType::Inverse);
+    assert!((short_pnl + 0.1).abs() < 1e-12);
+
+    // margin at 2x = 10,000 / 50,000 / 2 = 0.1 BTC -> 100% ROE
+    let roe = calc_roe(pnl, 50_000.0, 10_000.0, 2.0, &ContractType::Inverse);
+    assert!((roe - 100.0).abs() < 1e-9);
+}
+
+#[test]
+pub fn inverse_liquidation_price() {
+    // a 1x inverse long loses its entire margin at half the entry price (ignoring maintenance margin)
+    let long_liquidation = calc_liquidation_price(50_000.0, 1.0, &TradeDirection::Long, &ContractType::Inverse, 1.0);
+    assert!(long_liquidation > 25_000.0 && long_liquidation < 26_000.0);
+
+    // a 1x inverse short is fully hedged
+    assert_eq!(calc_liquidation_price(50_000.0, 1.0, &TradeDirection::Short, &ContractType::Inverse, 1.0), f64::INFINITY);
+
+    let short_liquidation = calc_liquidation_price(50_000.0, 2.0, &TradeDirection::Short, &ContractType::Inverse, 1.0);
+    assert!(short_liquidation > 50_000.0 && short_liquidation < 100_000.0);
+}
+
+#[test]
+pub fn order_quantity_in_quote_currency() {
+    // 1000 USDT into ETH-BTC, with 1 BTC = 100,000 USDT and 1 ETH = 0.05 BTC -> 0.01 BTC / 0.05 = 0.2 ETH
+    assert_eq!(calc_order_quantity(1000.0, 0.05, 100_000.0, &ContractType::Linear, &RoundingPolicy::default()), 0.2);
+    // inverse contracts are sized in the quote currency directly
+    assert_eq!(calc_order_quantity(1000.0, 50_000.0, 1.0, &ContractType::Inverse, &RoundingPolicy::default()), 1000.0);
+}
+
+#[test]
+pub fn accrued_funding_only_counts_settlements_after_open() {
+    let open_timestamp = Utc.with_ymd_and_hms(2025, 1, 1, 7, 0, 0).unwrap();
+
+    let trade = ActiveTrade {
+        direction: TradeDirection::Short,
+        open_timestamp,
+        quantity: 2.0,
+        liquidation_price: 200.0,
+        ..active_trade()
+    };
+
+    let funding_rate = |hour: u32, rate: f64, mark_price: Option<f64>| FundingRate {
+        pair: "BTCUSDT".to_string(),
+        funding_time: Utc.with_ymd_and_hms(2025, 1, 1, hour, 0, 0).unwrap(),
+        rate,
+        mark_price,
+    };
+
+    let funding_rates = [
+        // settled before the trade was opened
+        funding_rate(0, 0.01, Some(100.0)),
+        funding_rate(8, 0.01, Some(110.0)),
+        funding_rate(16, -0.005, None),
+    ];
+
+    // shorts receive positive funding: -(2 * 110 * 0.01) - (2 * 100 * -0.005) = -2.2 + 1.0
+    let funding = calc_accrued_funding(&trade, &funding_rates);
+    assert!((funding + 1.2).abs() < 1e-9);
+}
+
+#[test]
+pub fn liquidation_triggers_before_stop_loss() {
+    let trade = ActiveTrade {
+        leverage: TradeLeverage::Ten,
+        liquidation_price: 90.5,
+        take_profit: Some(110.0),
+        stop_loss: Some(95.0),
+        ..active_trade()
+    };
+
+    // stop loss hit, but not liquidated
+    assert!(is_trigger_hit(&trade, 94.0));
+    assert!(!is_liquidation_hit(&trade, 94.0));
+
+    assert!(is_liquidation_hit(&trade, 90.0));
+    assert!(!is_liquidation_hit(&trade, 110.0));
+
+    // the liquidation is checked separately (against the index price)
+    assert!(is_exit_trigger_hit(&trade, 94.0));
+    assert!(is_exit_trigger_hit(&trade, 110.0));
+    assert!(!is_exit_trigger_hit(&trade, 100.0));
+}
+
+#[test]
+pub fn percentage_exits_follow_the_direction() {
+    assert_eq!(calc_percentage_exits(200.0, &TradeDirection::Long, Some(25.0), Some(50.0)), (Some(250.0), Some(100.0)));
+    assert_eq!(calc_percentage_exits(200.0, &TradeDirection::Short, Some(25.0), Some(50.0)), (Some(150.0), Some(300.0)));
+    assert_eq!(calc_percentage_exits(200.0, &TradeDirection::Long, None, Some(50.0)), (None, Some(100.0)));
+}
+
+#[test]
+pub fn trailing_stop_only_tightens() {
+    let mut trade = ActiveTrade {
+        leverage: TradeLeverage::Two,
+        liquidation_price: 50.5,
+        stop_loss: Some(95.0),
+        trailing_stop_percentage: Some(5.0),
+        ..active_trade()
+    };
+
+    // the price rose, so the stop follows it
+    assert_eq!(calc_trailing_stop(&trade, 110.0), Some(104.5));
+    // the price fell, so the stop stays
+    assert_eq!(calc_trailing_stop(&trade, 99.0), None);
+
+    trade.direction = TradeDirection::Short;
+    trade.stop_loss = Some(105.0);
+    assert_eq!(calc_trailing_stop(&trade, 90.0), Some(94.5));
+    assert_eq!(calc_trailing_stop(&trade, 101.0), None);
+
+    trade.trailing_stop_percentage = None;
+    assert_eq!(calc_trailing_stop(&trade, 90.0), None);
+}
+
+#[test]
+pub fn bulk_updates_adjust_the_exits_of_matching_trades() {
+    let trade = ActiveTrade {
+        direction: TradeDirection::Short,
+        entry_price: 200.0,
+        liquidation_price: 400.0,
+        take_profit: Some(150.0),
+        stop_loss: Some(250.0),
+        ..active_trade()
+    };
+
+    let update = BulkExitUpdate {
+        pair: Some("btcusdt".to_string()),
+        stop_loss: Some(ExitAdjustment::Percentage(5.0)),
+        ..BulkExitUpdate::default()
+    };
+
+    // the stop loss of a short moves above the entry price, the take profit is kept
+    assert!(matches_bulk_update(&trade, &update));
+    assert_eq!(calc_adjusted_exits(&trade, &update), (Some(150.0), Some(210.0)));
+
+    let update = BulkExitUpdate {
+        alert_name: Some("Sample Alert".to_string()),
+        take_profit: Some(ExitAdjustment::Price(180.0)),
+        stop_loss: Some(ExitAdjustment::Price(205.0)),
+        ..BulkExitUpdate::default()
+    };
+
+    assert!(matches_bulk_update(&trade, &update));
+    assert_eq!(calc_adjusted_exits(&trade, &update), (Some(180.0), Some(205.0)));
+
+    let update = BulkExitUpdate { alert_name: Some("Other Alert".to_string()), ..update };
+    assert!(!matches_bulk_update(&trade, &update));
+    assert!(validate_bulk_exit_update(&update).is_ok());
+
+    // at least one exit has to be adjusted, to a positive value
+    assert!(validate_bulk_exit_update(&BulkExitUpdate { take_profit: None, stop_loss: None, ..update }).is_err());
+    assert!(validate_bulk_exit_update(&BulkExitUpdate { take_profit: Some(ExitAdjustment::Percentage(-1.0)), all_trades: true, ..BulkExitUpdate::default() }).is_err());
+
+    // updating every trade has to be requested explicitly
+    let unfiltered = BulkExitUpdate { stop_loss: Some(ExitAdjustment::Percentage(5.0)), ..BulkExitUpdate::default() };
+    assert!(validate_bulk_exit_update(&unfiltered).is_err());
+    assert!(validate_bulk_exit_update(&BulkExitUpdate { all_trades: true, ..unfiltered }).is_ok());
+}
@@ -0,0 +1,64 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::{api::{build_closed_paper_trade_at, trade_replay::{build_replay_events, calc_replay_range, classify_exit}}, constants::MAX_REPLAY_CANDLES, models::{ActiveTrade, PartialLiquidation, ReplayEventKind, TradeDirection, TradeLeverage}, tests::{active_trade, app_state}};
+
+fn trade(direction: TradeDirection, take_profit: Option<f64>, stop_loss: Option<f64>) -> ActiveTrade {
+    ActiveTrade {
+        alert_name: "breakout".to_string(),
+        direction: direction.clone(),
+        open_timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap(),
+        leverage: TradeLeverage::Five,
+        liquidation_price: if direction == TradeDirection::Long { 80.5 } else { 119.5 },
+        take_profit,
+        stop_loss,
+        ..active_trade()
+    }
+}
+
+#[test]
+pub fn replay_range_fits_the_trade() {
+    let open = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+
+    // an hour long trade is replayed with 1 minute candles, padded by 10 candles
+    let (interval, from, to) = calc_replay_range(open, open + Duration::hours(1));
+    assert_eq!(interval, 60);
+    assert_eq!(from, open - Duration::minutes(10));
+    assert_eq!(to, open + Duration::hours(1) + Duration::minutes(10));
+
+    // a 10 day trade needs longer candles, padded by a day
+    let (interval, from, to) = calc_replay_range(open, open + Duration::days(10));
+    assert_eq!(interval, 3600);
+    assert_eq!(from, open - Duration::days(1));
+    assert!((to - from).num_seconds() / interval <= MAX_REPLAY_CANDLES);
+}
+
+#[tokio::test]
+pub async fn replay_events_of_closed_trades() {
+    let app_state = app_state().await;
+    let close = Utc.with_ymd_and_hms(2025, 1, 1, 14, 0, 0).unwrap();
+
+    let long = trade(TradeDirection::Long, Some(110.0), Some(95.0));
+    assert_eq!(classify_exit(&build_closed_paper_trade_at(&app_state, &long, 110.5, close)), ReplayEventKind::TakeProfit);
+    assert_eq!(classify_exit(&build_closed_paper_trade_at(&app_state, &long, 94.0, close)), ReplayEventKind::StopLoss);
+    assert_eq!(classify_exit(&build_closed_paper_trade_at(&app_state, &long, 102.0, close)), ReplayEventKind::Exit);
+    assert_eq!(classify_exit(&build_closed_paper_trade_at(&app_state, &long, 70.0, close)), ReplayEventKind::Liquidation);
+
+    let short = trade(TradeDirection::Short, Some(90.0), Some(105.0));
+    assert_eq!(classify_exit(&build_closed_paper_trade_at(&app_state, &short, 89.0, close)), ReplayEventKind::TakeProfit);
+    assert_eq!(classify_exit(&build_closed_paper_trade_at(&app_state, &short, 106.0, close)), ReplayEventKind::StopLoss);
+
+    let mut partially_liquidated = trade(TradeDirection::Long, None, None);
+    partially_liquidated.partial_liquidations.push(PartialLiquidation {
+        timestamp: close - Duration::hours(1),
+        quantity: 0.5,
+        price: 80.5,
+        pnl: -10.0,
+        next_liquidation_price: 75.0,
+    });
+
+    let closed_trade = build_closed_paper_trade_at(&app_state, &partially_liquidated, 85.0, close);
+    let kinds: Vec<ReplayEventKind> = build_replay_events(&closed_trade).iter().map(|event| event.kind).collect();
+
+    assert_eq!(kinds, vec![ReplayEventKind::Entry, ReplayEventKind::PartialLiquidation, ReplayEventKind::Exit]);
+    assert_eq!(closed_trade.take_profit, None);
+}
@@ -0,0 +1,36 @@
+use chrono::{TimeZone, Utc};
+use mongodb::bson::oid::ObjectId;
+
+use crate::models::{TradeTick, TradeTickRecorder};
+
+#[test]
+pub fn ticks_are_bucketed_into_one_second_bars() {
+    let recorder = TradeTickRecorder { enabled: true, ..Default::default() };
+    let (a, b) = (ObjectId::new(), ObjectId::new());
+    let second = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+    recorder.record(&[a, b], 100.0, second);
+    // the last price within a second wins
+    recorder.record(&[a], 101.0, second + chrono::Duration::milliseconds(500));
+    recorder.record(&[a], 102.0, second + chrono::Duration::seconds(1));
+
+    let mut ticks = recorder.take();
+    ticks.sort_by_key(|tick| (tick.timestamp, tick.trade_id == b));
+
+    assert_eq!(ticks, vec![
+        TradeTick { trade_id: a, timestamp: second, price: 101.0 },
+        TradeTick { trade_id: b, timestamp: second, price: 100.0 },
+        TradeTick { trade_id: a, timestamp: second + chrono::Duration::seconds(1), price: 102.0 },
+    ]);
+
+    // taking the ticks empties the buffer
+    assert!(recorder.take().is_empty());
+}
+
+#[test]
+pub fn nothing_is_recorded_when_disabled() {
+    let recorder = TradeTickRecorder::default();
+    recorder.record(&[ObjectId::new()], 100.0, Utc::now());
+
+    assert!(recorder.take().is_empty());
+}
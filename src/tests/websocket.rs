@@ -0,0 +1,129 @@
+use std::{collections::HashMap, time::{Duration, Instant}};
+
+use crate::{api::{calc_feed_reconnect_delay, initial_product_ids, parse_pair_channels, parse_ticker_message}, constants::{FEED_IDLE_TIMEOUT_SECS, FEED_MAX_RECONNECT_DELAY_SECS, FEED_PING_INTERVAL_SECS, FEED_RECONNECT_DELAY_SECS, FX_PRODUCT_IDS}, models::{FeedChannel, FeedChannels, FeedKeepalive, PriceFeedTick, WsCommand}};
+
+#[test]
+pub fn ticker_messages_are_parsed_into_ticks() {
+    let message = r#"{"type":"ticker","sequence":37475248783,"product_id":"btc-usd","price":"96289.34","open_24h":"95393.55","volume_24h":"11941.80121342","best_bid":"96289.33","side":"buy","time":"2024-12-27T10:50:33.372945Z","trade_id":745388001,"last_size":"0.00012"}"#;
+
+    assert_eq!(parse_ticker_message(message), Some(PriceFeedTick {
+        product_id: "BTC-USD".to_string(),
+        price: 96289.34,
+        sequence: Some(37475248783),
+        trade_id: Some(745388001),
+    }));
+
+    // ticks without a price are discarded later on
+    let message = r#"{"type":"ticker","product_id":"ETH-USD"}"#;
+    assert_eq!(parse_ticker_message(message).map(|tick| tick.price), Some(0.0));
+
+    // escaped strings can't be borrowed, but are still parsed
+    let message = r#"{"type":"ticker","product_id":"SOL\u002dUSD","price":"190.5"}"#;
+    assert_eq!(parse_ticker_message(message).map(|tick| tick.product_id), Some("SOL-USD".to_string()));
+
+    // trades of the matches channel are ticks as well
+    let message = r#"{"type":"match","trade_id":745388002,"sequence":37475248790,"maker_order_id":"ac928c66","side":"sell","size":"0.01","price":"96290.01","product_id":"BTC-USD","time":"2024-12-27T10:50:33.5Z"}"#;
+    assert_eq!(parse_ticker_message(message), Some(PriceFeedTick {
+        product_id: "BTC-USD".to_string(),
+        price: 96290.01,
+        sequence: Some(37475248790),
+        trade_id: Some(745388002),
+    }));
+
+    let message = r#"{"type":"last_match","trade_id":745388000,"product_id":"BTC-USD","price":"96288.5"}"#;
+    assert_eq!(parse_ticker_message(message).map(|tick| tick.price), Some(96288.5));
+}
+
+#[test]
+pub fn other_messages_are_skipped() {
+    assert_eq!(parse_ticker_message(r#"{"type":"subscriptions","channels":[{"name":"ticker","product_ids":["BTC-USD"]}]}"#), None);
+    assert_eq!(parse_ticker_message(r#"{"type":"heartbeat","product_id":"BTC-USD","sequence":1}"#), None);
+    assert_eq!(parse_ticker_message(r#"{"type":"ticker","product_id":"#), None);
+    assert_eq!(parse_ticker_message("not json"), None);
+}
+
+#[test]
+pub fn initial_subscription_includes_queued_products() {
+    let pending = vec![
+        WsCommand::Subscribe("ETH-USD".to_string()),
+        WsCommand::Subscribe("SOL-USD".to_string()),
+        WsCommand::Subscribe("ETH-USD".to_string()),
+        WsCommand::Unsubscribe("SOL-USD".to_string()),
+        // products required for currency conversion are never unsubscribed from
+        WsCommand::Unsubscribe("BTC-USD".to_string()),
+    ];
+
+    let product_ids = initial_product_ids(pending);
+
+    for product_id in FX_PRODUCT_IDS {
+        assert!(product_ids.contains(&product_id.to_string()));
+    }
+
+    assert!(product_ids.contains(&"ETH-USD".to_string()));
+    assert!(!product_ids.contains(&"SOL-USD".to_string()));
+    assert_eq!(product_ids.iter().filter(|product_id| *product_id == "ETH-USD").count(), 1);
+
+    // without open trades, only the products required for currency conversion are subscribed to
+    assert_eq!(initial_product_ids(Vec::new()).len(), FX_PRODUCT_IDS.len());
+}
+
+#[test]
+pub fn connections_without_frames_are_stale() {
+    let connected = Instant::now();
+    let mut keepalive = FeedKeepalive::new(Duration::from_secs(FEED_IDLE_TIMEOUT_SECS), connected);
+
+    // a few missed pongs are tolerated
+    const { assert!(FEED_IDLE_TIMEOUT_SECS > FEED_PING_INTERVAL_SECS * 2) };
+    assert!(!keepalive.is_stale(connected + Duration::from_secs(FEED_IDLE_TIMEOUT_SECS)));
+    assert!(keepalive.is_stale(connected + Duration::from_secs(FEED_IDLE_TIMEOUT_SECS + 1)));
+
+    // any frame (e.g. a pong) keeps the connection alive
+    keepalive.record_frame(connected + Duration::from_secs(10));
+    assert!(!keepalive.is_stale(connected + Duration::from_secs(FEED_IDLE_TIMEOUT_SECS + 1)));
+
+    // clocks never go backwards, but an earlier instant isn't stale either
+    assert!(!keepalive.is_stale(connected));
+}
+
+#[test]
+pub fn lost_connections_are_reconnected_with_a_backoff() {
+    assert_eq!(calc_feed_reconnect_delay(1), Duration::from_secs(FEED_RECONNECT_DELAY_SECS));
+    assert_eq!(calc_feed_reconnect_delay(3), Duration::from_secs(FEED_RECONNECT_DELAY_SECS * 4));
+    assert_eq!(calc_feed_reconnect_delay(u32::MAX), Duration::from_secs(FEED_MAX_RECONNECT_DELAY_SECS));
+}
+
+#[test]
+pub fn products_are_subscribed_to_their_channel() {
+    assert_eq!(parse_pair_channels("BTCUSDT:matches, dogeusd:ticker_batch"), Ok(HashMap::from([
+        ("BTC-USDT".to_string(), FeedChannel::Matches),
+        ("DOGE-USD".to_string(), FeedChannel::TickerBatch),
+    ])));
+    assert!(parse_pair_channels("BTCUSDT:trades").is_err());
+    assert!(parse_pair_channels("BTCUSDT").is_err());
+
+    let channels = FeedChannels {
+        default: FeedChannel::TickerBatch,
+        products: parse_pair_channels("BTCUSD:matches,ETHUSD:ticker").unwrap(),
+    };
+
+    assert_eq!(channels.channel_of("BTC-USD"), FeedChannel::Matches);
+    assert_eq!(channels.channel_of("SOL-USD"), FeedChannel::TickerBatch);
+
+    let message = channels.subscription_message("subscribe", &["SOL-USD", "BTC-USD", "ETH-USD", "DOGE-USD"]);
+    let message: serde_json::Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+
+    assert_eq!(message, serde_json::json!({
+        "type": "subscribe",
+        "channels": [
+            { "name": "ticker", "product_ids": ["ETH-USD"] },
+            { "name": "ticker_batch", "product_ids": ["SOL-USD", "DOGE-USD"] },
+            { "name": "matches", "product_ids": ["BTC-USD"] },
+        ]
+    }));
+
+    // unsubscribing only lists the channel of the product
+    let message = channels.subscription_message("unsubscribe", &["BTC-USD"]);
+    let message: serde_json::Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+
+    assert_eq!(message["channels"], serde_json::json!([{ "name": "matches", "product_ids": ["BTC-USD"] }]));
+}